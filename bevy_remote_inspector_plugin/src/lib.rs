@@ -0,0 +1,857 @@
+//! Companion plugin for `bevy_remote_inspector`. Added next to `RemotePlugin`, it registers
+//! the `inspector/*` methods the inspector uses for pausing, frame stepping, renaming and
+//! more. The inspector works without it, those features are just disabled then.
+//!
+//! ```no_run
+//! use bevy::prelude::*;
+//! use bevy::remote::{http::RemoteHttpPlugin, RemotePlugin};
+//! use bevy_remote_inspector_plugin::{AppMetadata, InspectorPlugin};
+//!
+//! App::new()
+//!     .add_plugins(DefaultPlugins)
+//!     .add_plugins((RemotePlugin::default(), RemoteHttpPlugin::default()))
+//!     .add_plugins(InspectorPlugin)
+//!     .insert_resource(AppMetadata::new(env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")))
+//!     .run();
+//! ```
+//!
+//! With the `msgpack` feature, adding `MsgpackHttpPlugin` too lets the inspector send and
+//! receive MessagePack instead of JSON, which is cheaper to encode and decode for big
+//! worlds.
+
+use bevy::{
+    core::FrameCount,
+    diagnostic::DiagnosticsStore,
+    ecs::{component::ComponentInfo, system::SystemId},
+    prelude::*,
+    reflect::TypeRegistry,
+    remote::{error_codes, BrpError, BrpResult, RemoteMethodSystemId, RemoteMethods},
+    time::TimeSystem,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+
+mod methods;
+
+pub use methods::*;
+#[cfg(all(feature = "msgpack", not(target_arch = "wasm32")))]
+pub use msgpack::{MsgpackHttpPlugin, DEFAULT_MSGPACK_PORT};
+
+/// Registers the inspector methods. Has to be added after `RemotePlugin`, without it there
+/// is nothing to register them with.
+pub struct InspectorPlugin;
+
+impl Plugin for InspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<InspectorPanels>()
+            .init_resource::<InspectorEncodings>()
+            .init_resource::<AppMetadata>()
+            .init_resource::<Stepping>()
+            .add_systems(First, advance_step.after(TimeSystem))
+            .add_systems(Last, finish_step);
+        #[cfg(feature = "render")]
+        render_stats::build(app);
+    }
+
+    /// Methods are registered once every plugin is built, `RemotePlugin` creates the
+    /// method table while building.
+    fn finish(&self, app: &mut App) {
+        let world = app.world_mut();
+        if !world.contains_resource::<RemoteMethods>() {
+            warn!("InspectorPlugin needs RemotePlugin, no inspector methods were registered");
+            return;
+        }
+        register(world, INSPECTOR_PING_METHOD, ping);
+        register(world, INSPECTOR_PAUSE_METHOD, pause);
+        register(world, INSPECTOR_RESUME_METHOD, resume);
+        register(world, INSPECTOR_STEP_METHOD, step);
+        register(world, INSPECTOR_FRAME_METHOD, frame);
+        register(world, INSPECTOR_PANELS_METHOD, panels);
+        register(world, INSPECTOR_RENAME_METHOD, rename);
+        register(world, INSPECTOR_CHANGE_TICKS_METHOD, change_ticks);
+        register(world, INSPECTOR_SEARCH_METHOD, search);
+        register(world, INSPECTOR_DIAGNOSTICS_METHOD, diagnostics);
+        register(world, INSPECTOR_METADATA_METHOD, metadata);
+        register(world, INSPECTOR_ENCODINGS_METHOD, encodings);
+        #[cfg(feature = "render")]
+        register(
+            world,
+            INSPECTOR_QUERY_VISIBLE_METHOD,
+            visible::query_visible,
+        );
+        #[cfg(feature = "render")]
+        register(
+            world,
+            INSPECTOR_RENDER_STATS_METHOD,
+            render_stats::render_stats,
+        );
+    }
+}
+
+fn register<M>(
+    world: &mut World,
+    method: &str,
+    handler: impl IntoSystem<In<Option<Value>>, BrpResult, M> + 'static,
+) {
+    let id: SystemId<In<Option<Value>>, BrpResult> = world.register_system(handler);
+    world
+        .resource_mut::<RemoteMethods>()
+        .insert(method, RemoteMethodSystemId::Instant(id));
+}
+
+/// Announces the game on the local network every couple of seconds, so inspectors on
+/// other machines list it without knowing its address. Opt-in, since anyone on the
+/// network learns where the remote protocol listens. Needs `RemoteHttpPlugin`.
+#[cfg(not(target_arch = "wasm32"))]
+pub struct DiscoveryPlugin;
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Plugin for DiscoveryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(Startup, discovery::announce);
+    }
+}
+
+/// A panel shown by the inspector, filled with whatever `method` returns. The game
+/// registers `method` itself, e.g. with `RemotePlugin::with_method`.
+#[derive(Serialize, Clone, Debug)]
+pub struct PanelDescriptor {
+    pub name: String,
+    /// JSON schema of the result, its `title` and `description` fields label the values.
+    pub schema: Value,
+    pub method: String,
+}
+
+/// Custom panels offered to the inspector.
+#[derive(Resource, Default)]
+pub struct InspectorPanels(pub Vec<PanelDescriptor>);
+
+/// A body encoding besides JSON the game accepts, see [`INSPECTOR_ENCODINGS_METHOD`].
+#[derive(Serialize, Clone, Debug)]
+pub struct EncodingEndpoint {
+    /// E.g. `msgpack`.
+    pub encoding: String,
+    pub port: u16,
+}
+
+/// Encodings offered to the inspector, filled in by the plugins serving them.
+#[derive(Resource, Default)]
+pub struct InspectorEncodings(pub Vec<EncodingEndpoint>);
+
+/// The Bevy release this plugin is built against, and so the game using it.
+pub const BEVY_VERSION: &str = "0.15";
+
+/// What the game says about itself. The plugin can't see the game's package, so it's
+/// filled in by the game, usually from `env!("CARGO_PKG_NAME")` and
+/// `env!("CARGO_PKG_VERSION")`.
+#[derive(Resource, Serialize, Clone, Debug, Default)]
+pub struct AppMetadata {
+    pub name: String,
+    pub version: String,
+    /// Cargo features or other flags the build was made with.
+    pub features: Vec<String>,
+}
+
+impl AppMetadata {
+    pub fn new(name: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+            features: Vec::new(),
+        }
+    }
+
+    pub fn with_features(mut self, features: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.features = features.into_iter().map(Into::into).collect();
+        self
+    }
+}
+
+/// Result of [`INSPECTOR_METADATA_METHOD`].
+#[derive(Serialize)]
+struct Metadata {
+    #[serde(flatten)]
+    app: AppMetadata,
+    bevy_version: &'static str,
+    /// `debug` or `release`, going by debug assertions.
+    profile: &'static str,
+    /// Change tick of the world, advancing with every system run.
+    tick: u32,
+    frame: Option<u32>,
+    /// Features of this plugin enabled in the build.
+    plugin_features: Vec<&'static str>,
+}
+
+fn metadata(In(_): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let metadata = Metadata {
+        app: world.resource::<AppMetadata>().clone(),
+        bevy_version: BEVY_VERSION,
+        profile: if cfg!(debug_assertions) {
+            "debug"
+        } else {
+            "release"
+        },
+        tick: world.change_tick().get(),
+        frame: world.get_resource::<FrameCount>().map(|frame| frame.0),
+        plugin_features: [
+            cfg!(feature = "render").then_some("render"),
+            cfg!(feature = "msgpack").then_some("msgpack"),
+        ]
+        .into_iter()
+        .flatten()
+        .collect(),
+    };
+    serde_json::to_value(metadata).map_err(BrpError::internal)
+}
+
+/// Progress of a frame step, which lets virtual time run for exactly one update.
+#[derive(Resource, Default, PartialEq, Eq)]
+enum Stepping {
+    #[default]
+    Idle,
+    /// Time was resumed, the next frame is the stepped one.
+    Requested,
+    /// Time advanced for the stepped frame, pause again at its end.
+    Advanced,
+}
+
+fn advance_step(mut stepping: ResMut<Stepping>) {
+    if *stepping == Stepping::Requested {
+        *stepping = Stepping::Advanced;
+    }
+}
+
+fn finish_step(mut stepping: ResMut<Stepping>, mut time: ResMut<Time<Virtual>>) {
+    if *stepping == Stepping::Advanced {
+        time.pause();
+        *stepping = Stepping::Idle;
+    }
+}
+
+fn parse<T: DeserializeOwned>(params: Option<Value>) -> Result<T, BrpError> {
+    let params = params.ok_or_else(|| BrpError {
+        code: error_codes::INVALID_PARAMS,
+        message: "Params not provided".to_owned(),
+        data: None,
+    })?;
+    serde_json::from_value(params).map_err(|e| BrpError {
+        code: error_codes::INVALID_PARAMS,
+        message: e.to_string(),
+        data: None,
+    })
+}
+
+fn ping(In(_): In<Option<Value>>) -> BrpResult {
+    Ok(Value::Null)
+}
+
+fn pause(In(_): In<Option<Value>>, mut time: ResMut<Time<Virtual>>) -> BrpResult {
+    time.pause();
+    Ok(Value::Null)
+}
+
+fn resume(
+    In(_): In<Option<Value>>,
+    mut time: ResMut<Time<Virtual>>,
+    mut stepping: ResMut<Stepping>,
+) -> BrpResult {
+    *stepping = Stepping::Idle;
+    time.unpause();
+    Ok(Value::Null)
+}
+
+fn step(
+    In(_): In<Option<Value>>,
+    mut time: ResMut<Time<Virtual>>,
+    mut stepping: ResMut<Stepping>,
+) -> BrpResult {
+    if !time.is_paused() {
+        return Err(BrpError {
+            code: error_codes::INVALID_REQUEST,
+            message: "Only a paused game can be stepped".to_owned(),
+            data: None,
+        });
+    }
+    *stepping = Stepping::Requested;
+    time.unpause();
+    Ok(Value::Null)
+}
+
+fn frame(In(_): In<Option<Value>>, frame_count: Option<Res<FrameCount>>) -> BrpResult {
+    let frame_count = frame_count.ok_or_else(|| BrpError {
+        code: error_codes::INTERNAL_ERROR,
+        message: "Frames aren't counted, add FrameCountPlugin".to_owned(),
+        data: None,
+    })?;
+    Ok(json!(frame_count.0))
+}
+
+fn panels(In(_): In<Option<Value>>, panels: Res<InspectorPanels>) -> BrpResult {
+    Ok(serde_json::to_value(&panels.0).unwrap_or_default())
+}
+
+fn encodings(In(_): In<Option<Value>>, encodings: Res<InspectorEncodings>) -> BrpResult {
+    Ok(serde_json::to_value(&encodings.0).unwrap_or_default())
+}
+
+#[derive(Deserialize)]
+struct RenameParams {
+    entity: Entity,
+    name: String,
+}
+
+fn rename(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let RenameParams { entity, name } = parse(params)?;
+    let Ok(mut entity_mut) = world.get_entity_mut(entity) else {
+        return Err(BrpError::entity_not_found(entity));
+    };
+    entity_mut.insert(Name::new(name));
+    Ok(Value::Null)
+}
+
+#[derive(Deserialize)]
+struct EntityParams {
+    entity: Entity,
+}
+
+#[derive(Serialize)]
+struct ComponentChangeTicks {
+    added: u32,
+    changed: u32,
+}
+
+/// Registered type path of a component like in `bevy/get`, the type name for unregistered
+/// ones.
+fn type_path(registry: &TypeRegistry, info: &ComponentInfo) -> String {
+    info.type_id()
+        .and_then(|type_id| registry.get(type_id))
+        .map_or_else(
+            || info.name().to_owned(),
+            |registration| registration.type_info().type_path().to_owned(),
+        )
+}
+
+/// Components are keyed by their registered type path like in `bevy/get`, unregistered
+/// ones by their type name.
+fn change_ticks(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let EntityParams { entity } = parse(params)?;
+    let Ok(entity_ref) = world.get_entity(entity) else {
+        return Err(BrpError::entity_not_found(entity));
+    };
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let components: serde_json::Map<String, Value> = entity_ref
+        .archetype()
+        .components()
+        .filter_map(|id| {
+            let ticks = entity_ref.get_change_ticks_by_id(id)?;
+            let path = type_path(&registry, world.components().get_info(id)?);
+            let ticks = ComponentChangeTicks {
+                added: ticks.added.get(),
+                changed: ticks.changed.get(),
+            };
+            Some((path, serde_json::to_value(ticks).ok()?))
+        })
+        .collect();
+    Ok(json!({
+        "tick": world.read_change_tick().get(),
+        "components": components,
+    }))
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+    #[serde(default)]
+    text: String,
+    #[serde(default)]
+    component: String,
+    #[serde(default)]
+    case_sensitive: bool,
+}
+
+/// `Transform` for `bevy_transform::components::transform::Transform`.
+fn short_name(type_path: &str) -> &str {
+    let without_generics = type_path.split('<').next().unwrap_or(type_path);
+    without_generics
+        .rsplit("::")
+        .next()
+        .unwrap_or(without_generics)
+}
+
+/// Matches like the inspector's search bar does on fetched entities, so the results don't
+/// depend on where the search ran. Type paths and short names both count.
+fn search(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+    let SearchParams {
+        text,
+        component,
+        case_sensitive,
+    } = parse(params)?;
+    let normalize = |text: &str| {
+        if case_sensitive {
+            text.to_owned()
+        } else {
+            text.to_lowercase()
+        }
+    };
+    let text = normalize(&text);
+    let component = normalize(&component);
+    let contains = |candidate: &str, part: &str| normalize(candidate).contains(part);
+    let world = &*world;
+    let registry = world.resource::<AppTypeRegistry>().read();
+    let mut matches = Vec::new();
+    for entity_ref in world.iter_entities() {
+        let paths: Vec<String> = entity_ref
+            .archetype()
+            .components()
+            .filter_map(|id| world.components().get_info(id))
+            .map(|info| type_path(&registry, info))
+            .collect();
+        let has = component.is_empty()
+            || paths
+                .iter()
+                .any(|path| contains(path, &component) || contains(short_name(path), &component));
+        if !has {
+            continue;
+        }
+        let entity = entity_ref.id();
+        let ids = [entity.to_string(), entity.index().to_string()];
+        let found = text.is_empty()
+            || entity_ref
+                .get::<Name>()
+                .map(Name::as_str)
+                .into_iter()
+                .chain(ids.iter().map(String::as_str))
+                .chain(paths.iter().map(String::as_str))
+                .chain(paths.iter().map(|path| short_name(path)))
+                .any(|candidate| contains(candidate, &text));
+        if found {
+            matches.push(entity);
+        }
+    }
+    serde_json::to_value(matches).map_err(BrpError::internal)
+}
+
+/// One measurement of the `DiagnosticsStore`.
+#[derive(Serialize)]
+pub(crate) struct Measurement {
+    path: String,
+    suffix: String,
+    value: Option<f64>,
+    smoothed: Option<f64>,
+    average: Option<f64>,
+}
+
+/// The enabled diagnostics whose path passes `filter`.
+pub(crate) fn measurements(
+    store: Option<&DiagnosticsStore>,
+    filter: impl Fn(&str) -> bool,
+) -> Vec<Measurement> {
+    store
+        .iter()
+        .flat_map(|store| store.iter())
+        .filter(|diagnostic| diagnostic.is_enabled && filter(diagnostic.path().as_str()))
+        .map(|diagnostic| Measurement {
+            path: diagnostic.path().to_string(),
+            suffix: diagnostic.suffix.to_string(),
+            value: diagnostic.value(),
+            smoothed: diagnostic.smoothed(),
+            average: diagnostic.average(),
+        })
+        .collect()
+}
+
+fn diagnostics(In(_): In<Option<Value>>, store: Option<Res<DiagnosticsStore>>) -> BrpResult {
+    let measurements = measurements(store.as_deref(), |_| true);
+    Ok(serde_json::to_value(measurements).unwrap_or_default())
+}
+
+#[cfg(feature = "render")]
+mod visible {
+    use bevy::{
+        prelude::*,
+        remote::{
+            builtin_methods::{process_remote_query_request, BrpQueryRow},
+            BrpError, BrpResult,
+        },
+    };
+    use serde::Deserialize;
+    use serde_json::Value;
+
+    use crate::parse;
+
+    #[derive(Deserialize)]
+    struct QueryVisibleParams {
+        query: Value,
+        #[serde(default)]
+        margin: f32,
+    }
+
+    /// Runs the query and drops the rows of entities outside the view. Entities without a
+    /// position, like the ones only grouping others, are always kept.
+    pub fn query_visible(In(params): In<Option<Value>>, world: &mut World) -> BrpResult {
+        let QueryVisibleParams { query, margin } = parse(params)?;
+        let rows = process_remote_query_request(In(Some(query)), world)?;
+        let rows: Vec<BrpQueryRow> = serde_json::from_value(rows).map_err(BrpError::internal)?;
+        let mut cameras = world.query::<(&Camera, &GlobalTransform)>();
+        let mut transforms = world.query::<&GlobalTransform>();
+        let world = &*world;
+        let Some((camera, camera_transform)) = cameras
+            .iter(world)
+            .filter(|(camera, _)| camera.is_active)
+            .max_by_key(|(camera, _)| camera.order)
+        else {
+            return serde_json::to_value(rows).map_err(BrpError::internal);
+        };
+        let rows: Vec<BrpQueryRow> = rows
+            .into_iter()
+            .filter(|row| match transforms.get(world, row.entity) {
+                Ok(transform) => in_view(camera, camera_transform, transform.translation(), margin),
+                Err(_) => true,
+            })
+            .collect();
+        serde_json::to_value(rows).map_err(BrpError::internal)
+    }
+
+    /// Whether `position` is on screen, or within `margin` of its edges measured in the
+    /// camera's image plane.
+    fn in_view(camera: &Camera, transform: &GlobalTransform, position: Vec3, margin: f32) -> bool {
+        let right = *transform.right() * margin;
+        let up = *transform.up() * margin;
+        [Vec3::ZERO, right, -right, up, -up].iter().any(|offset| {
+            camera
+                .world_to_ndc(transform, position + *offset)
+                .is_some_and(|ndc| {
+                    ndc.x.abs() <= 1.0 && ndc.y.abs() <= 1.0 && (0.0..=1.0).contains(&ndc.z)
+                })
+        })
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod discovery {
+    use std::net::{Ipv4Addr, SocketAddr, UdpSocket};
+    use std::time::Duration;
+
+    use bevy::{prelude::*, remote::http::HostPort};
+    use serde_json::json;
+
+    use crate::{AppMetadata, DISCOVERY_GROUP, DISCOVERY_PORT, DISCOVERY_SERVICE};
+
+    const INTERVAL: Duration = Duration::from_secs(2);
+
+    /// Sends the beacon from a thread of its own for as long as the game runs.
+    pub fn announce(port: Option<Res<HostPort>>, metadata: Res<AppMetadata>) {
+        let Some(port) = port else {
+            warn!("DiscoveryPlugin needs RemoteHttpPlugin, the game is not announced");
+            return;
+        };
+        let beacon = json!({
+            "service": DISCOVERY_SERVICE,
+            "name": metadata.name,
+            "version": metadata.version,
+            "port": port.0,
+        })
+        .to_string();
+        let socket = match UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)) {
+            Ok(socket) => socket,
+            Err(e) => {
+                warn!("Could not open the discovery socket: {e}");
+                return;
+            }
+        };
+        let target = SocketAddr::from((DISCOVERY_GROUP, DISCOVERY_PORT));
+        std::thread::spawn(move || loop {
+            if let Err(e) = socket.send_to(beacon.as_bytes(), target) {
+                warn!("Could not announce the game: {e}");
+                return;
+            }
+            std::thread::sleep(INTERVAL);
+        });
+    }
+}
+
+/// A second HTTP endpoint for the remote methods taking and answering MessagePack bodies.
+/// `RemoteHttpPlugin` only reads JSON, so it listens on a port of its own and hands the
+/// calls to `RemotePlugin` the same way.
+#[cfg(all(feature = "msgpack", not(target_arch = "wasm32")))]
+mod msgpack {
+    use std::io::{self, BufRead, BufReader, Read, Write};
+    use std::net::{IpAddr, Ipv4Addr, TcpListener, TcpStream};
+
+    use async_channel::Sender;
+    use bevy::{
+        prelude::*,
+        remote::{error_codes, BrpError, BrpMessage, BrpRequest, BrpSender},
+    };
+    use serde_json::{json, Value};
+
+    use crate::{EncodingEndpoint, InspectorEncodings, MSGPACK_CONTENT_TYPE};
+
+    /// Port listened on unless told otherwise, next to the remote protocol's 15702 and the
+    /// discovery beacons' 15703.
+    pub const DEFAULT_MSGPACK_PORT: u16 = 15704;
+
+    /// Largest request body read, so a broken client can't make the game allocate
+    /// without bounds.
+    const MAX_BODY: usize = 64 * 1024 * 1024;
+
+    /// Serves the remote methods with MessagePack bodies, announced to the inspector
+    /// through [`crate::INSPECTOR_ENCODINGS_METHOD`]. Needs `RemotePlugin` and
+    /// `InspectorPlugin`.
+    ///
+    /// Requests aren't checked for signatures, don't expose it where the JSON endpoint is
+    /// gated behind a shared secret. `+watch` methods answer once here, the inspector
+    /// streams them from the JSON endpoint.
+    pub struct MsgpackHttpPlugin {
+        pub address: IpAddr,
+        pub port: u16,
+    }
+
+    impl Default for MsgpackHttpPlugin {
+        fn default() -> Self {
+            Self {
+                address: IpAddr::V4(Ipv4Addr::LOCALHOST),
+                port: DEFAULT_MSGPACK_PORT,
+            }
+        }
+    }
+
+    impl MsgpackHttpPlugin {
+        pub fn with_address(mut self, address: impl Into<IpAddr>) -> Self {
+            self.address = address.into();
+            self
+        }
+
+        pub fn with_port(mut self, port: u16) -> Self {
+            self.port = port;
+            self
+        }
+    }
+
+    impl Plugin for MsgpackHttpPlugin {
+        fn build(&self, app: &mut App) {
+            let (address, port) = (self.address, self.port);
+            app.init_resource::<InspectorEncodings>().add_systems(
+                Startup,
+                move |sender: Option<Res<BrpSender>>, encodings: ResMut<InspectorEncodings>| {
+                    start(address, port, sender, encodings);
+                },
+            );
+        }
+    }
+
+    /// Listens from a thread of its own, the encoding is only offered once that works.
+    fn start(
+        address: IpAddr,
+        port: u16,
+        sender: Option<Res<BrpSender>>,
+        mut encodings: ResMut<InspectorEncodings>,
+    ) {
+        let Some(sender) = sender else {
+            warn!("MsgpackHttpPlugin needs RemotePlugin, MessagePack is not served");
+            return;
+        };
+        let listener = match TcpListener::bind((address, port)) {
+            Ok(listener) => listener,
+            Err(e) => {
+                warn!("Could not serve MessagePack on {address}:{port}: {e}");
+                return;
+            }
+        };
+        encodings.0.push(EncodingEndpoint {
+            encoding: "msgpack".to_owned(),
+            port,
+        });
+        let sender: Sender<BrpMessage> = (**sender).clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let sender = sender.clone();
+                std::thread::spawn(move || {
+                    if let Err(e) = serve(stream, &sender) {
+                        debug!("MessagePack request failed: {e}");
+                    }
+                });
+            }
+        });
+    }
+
+    /// Answers one HTTP request, then closes the connection.
+    fn serve(mut stream: TcpStream, sender: &Sender<BrpMessage>) -> io::Result<()> {
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let post = line.starts_with("POST ");
+        let mut length = 0;
+        let mut msgpack_body = false;
+        let mut msgpack_reply = false;
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                return Ok(());
+            }
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            let Some((name, value)) = header.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match name.trim().to_ascii_lowercase().as_str() {
+                "content-length" => length = value.parse().unwrap_or(0),
+                "content-type" => msgpack_body = value.starts_with(MSGPACK_CONTENT_TYPE),
+                "accept" => msgpack_reply = value.contains(MSGPACK_CONTENT_TYPE),
+                _ => {}
+            }
+        }
+        if !post {
+            return respond(
+                &mut stream,
+                "405 Method Not Allowed",
+                "text/plain",
+                b"POST only",
+            );
+        }
+        if length > MAX_BODY {
+            return respond(&mut stream, "413 Payload Too Large", "text/plain", b"");
+        }
+        let mut body = vec![0; length];
+        reader.read_exact(&mut body)?;
+        let reply = answer(&body, msgpack_body, sender);
+        if msgpack_reply {
+            let bytes = rmp_serde::to_vec_named(&reply).map_err(io::Error::other)?;
+            respond(&mut stream, "200 OK", MSGPACK_CONTENT_TYPE, &bytes)
+        } else {
+            let bytes = serde_json::to_vec(&reply)?;
+            respond(&mut stream, "200 OK", "application/json", &bytes)
+        }
+    }
+
+    fn respond(
+        stream: &mut TcpStream,
+        status: &str,
+        content_type: &str,
+        body: &[u8],
+    ) -> io::Result<()> {
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\n\
+             Content-Length: {}\r\nConnection: close\r\n\r\n",
+            body.len()
+        )?;
+        stream.write_all(body)?;
+        stream.flush()
+    }
+
+    /// Answers a single call or a batch like the JSON endpoint does. JSON bodies are read
+    /// too, for clients that fall back to them.
+    fn answer(body: &[u8], msgpack: bool, sender: &Sender<BrpMessage>) -> Value {
+        let request = if msgpack {
+            rmp_serde::from_slice::<Value>(body).map_err(|e| e.to_string())
+        } else {
+            serde_json::from_slice::<Value>(body).map_err(|e| e.to_string())
+        };
+        match request {
+            Ok(Value::Array(calls)) => calls.into_iter().map(|c| call(c, sender)).collect(),
+            Ok(single) => call(single, sender),
+            Err(message) => failure(
+                Value::Null,
+                BrpError {
+                    code: error_codes::PARSE_ERROR,
+                    message,
+                    data: None,
+                },
+            ),
+        }
+    }
+
+    /// Runs the method through `RemotePlugin` and waits for its first result.
+    fn call(call: Value, sender: &Sender<BrpMessage>) -> Value {
+        let id = call.get("id").cloned().unwrap_or(Value::Null);
+        let request: BrpRequest = match serde_json::from_value(call) {
+            Ok(request) => request,
+            Err(e) => {
+                let error = BrpError {
+                    code: error_codes::INVALID_REQUEST,
+                    message: e.to_string(),
+                    data: None,
+                };
+                return failure(id, error);
+            }
+        };
+        let (result_sender, result_receiver) = async_channel::bounded(1);
+        let message = BrpMessage {
+            method: request.method,
+            params: request.params,
+            sender: result_sender,
+        };
+        let result = sender
+            .send_blocking(message)
+            .ok()
+            .and_then(|()| result_receiver.recv_blocking().ok())
+            .unwrap_or_else(|| Err(BrpError::internal("The game stopped answering requests")));
+        match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => failure(id, error),
+        }
+    }
+
+    fn failure(id: Value, error: BrpError) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "error": error })
+    }
+}
+
+#[cfg(feature = "render")]
+mod render_stats {
+    use std::sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    };
+
+    use bevy::{
+        diagnostic::DiagnosticsStore,
+        ecs::entity::Entities,
+        prelude::*,
+        remote::BrpResult,
+        render::{Render, RenderApp, RenderSet},
+    };
+    use serde_json::{json, Value};
+
+    use crate::measurements;
+
+    /// Entities in the render world, counted there every frame. The render world can't be
+    /// reached from a remote method, so the count is shared through the resource in both
+    /// worlds.
+    #[derive(Resource, Clone, Default)]
+    struct RenderEntityCount(Arc<AtomicU32>);
+
+    pub fn build(app: &mut App) {
+        let count = RenderEntityCount::default();
+        app.insert_resource(count.clone());
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .insert_resource(count)
+                .add_systems(Render, count_render_entities.in_set(RenderSet::Render));
+        }
+    }
+
+    fn count_render_entities(entities: &Entities, count: Res<RenderEntityCount>) {
+        count.0.store(entities.len(), Ordering::Relaxed);
+    }
+
+    /// Bevy doesn't count draw calls, a game that does can report them as a
+    /// `render/draw_calls` diagnostic and they are sent along with the pass timings.
+    pub fn render_stats(
+        In(_): In<Option<Value>>,
+        visibility: Query<&ViewVisibility>,
+        render_entities: Res<RenderEntityCount>,
+        store: Option<Res<DiagnosticsStore>>,
+    ) -> BrpResult {
+        let visible = visibility.iter().filter(|v| v.get()).count();
+        let diagnostics = measurements(store.as_deref(), |path| path.starts_with("render/"));
+        Ok(json!({
+            "visible_entities": visible,
+            "render_entities": render_entities.0.load(Ordering::Relaxed),
+            "diagnostics": diagnostics,
+        }))
+    }
+}
@@ -0,0 +1,54 @@
+//! Names of the methods `InspectorPlugin` registers and of the discovery beacon. The
+//! inspector includes this file as it is, so both sides always agree on them. Keep it
+//! free of dependencies.
+
+/// Answers without doing any work, for measuring the round trip. Without the plugin the
+/// game answers it with an error right away, which is a round trip without work too.
+pub const INSPECTOR_PING_METHOD: &str = "inspector/ping";
+/// Pauses the game's virtual time.
+pub const INSPECTOR_PAUSE_METHOD: &str = "inspector/pause";
+/// Resumes the game's virtual time.
+pub const INSPECTOR_RESUME_METHOD: &str = "inspector/resume";
+/// Advances a paused game by exactly one frame.
+pub const INSPECTOR_STEP_METHOD: &str = "inspector/step";
+/// Number of frames the game has run so far, as an unsigned integer.
+pub const INSPECTOR_FRAME_METHOD: &str = "inspector/frame";
+/// Lists the custom panels the game wants shown, each as `{ "name", "schema", "method" }`.
+pub const INSPECTOR_PANELS_METHOD: &str = "inspector/panels";
+/// Sets the `Name` of an entity. Takes `{ "entity": Entity, "name": String }`.
+pub const INSPECTOR_RENAME_METHOD: &str = "inspector/rename";
+/// When each component of an entity was added and last changed, as
+/// `{ "tick", "components": { <type path>: { "added", "changed" } } }`.
+/// Takes `{ "entity": Entity }`.
+pub const INSPECTOR_CHANGE_TICKS_METHOD: &str = "inspector/change_ticks";
+/// Entities whose name, id or component types contain `text` and that have a component
+/// whose type contains `component`. Takes `{ "text", "component", "case_sensitive" }`,
+/// returns a list of entities.
+pub const INSPECTOR_SEARCH_METHOD: &str = "inspector/search";
+/// Which build of which game is running, its name, version, features, Bevy version,
+/// build profile, change tick and frame.
+pub const INSPECTOR_METADATA_METHOD: &str = "inspector/metadata";
+/// The current measurements of the game's diagnostics, e.g. FPS and frame time, as a list
+/// of `{ "path", "suffix", "value", "smoothed", "average" }`.
+pub const INSPECTOR_DIAGNOSTICS_METHOD: &str = "inspector/diagnostics";
+/// Like `bevy/query` but only returns entities inside the active camera's view, grown by
+/// a margin in world units. Takes `{ "query": <bevy/query params>, "margin": f32 }`.
+/// Only registered with the plugin's `render` feature.
+pub const INSPECTOR_QUERY_VISIBLE_METHOD: &str = "inspector/query_visible";
+/// Body encodings the game accepts besides JSON and the port each is served on, as a list
+/// of `{ "encoding", "port" }`. Empty unless the game adds the plugin's
+/// `MsgpackHttpPlugin`, which needs its `msgpack` feature.
+pub const INSPECTOR_ENCODINGS_METHOD: &str = "inspector/encodings";
+/// Content type of MessagePack bodies, carrying the same JSON-RPC structure as JSON ones.
+pub const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+/// Visible entities, render world entities and the `render/*` diagnostics, as
+/// `{ "visible_entities", "render_entities", "diagnostics" }`. Only registered with the
+/// plugin's `render` feature.
+pub const INSPECTOR_RENDER_STATS_METHOD: &str = "inspector/render_stats";
+
+/// Multicast group and port the `DiscoveryPlugin` announces the game on, as a JSON
+/// `{ "service", "name", "version", "port" }` every couple of seconds.
+pub const DISCOVERY_GROUP: std::net::Ipv4Addr = std::net::Ipv4Addr::new(239, 255, 66, 73);
+pub const DISCOVERY_PORT: u16 = 15703;
+/// `service` of the beacons, to tell them from other traffic on the port.
+pub const DISCOVERY_SERVICE: &str = "bevy_remote_inspector";
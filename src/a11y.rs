@@ -0,0 +1,28 @@
+//! Keyboard shortcuts and screen reader labels.
+
+use egui::{Response, WidgetInfo, WidgetType};
+
+/// Keys that can't be rebound, with what they do, for the help menu. The others are
+/// `Command`s.
+pub const SHORTCUTS: [(&str, &str); 4] = [
+    ("Up / Down", "Select the previous or next entity"),
+    ("Right / Left", "Expand or collapse the selected entity"),
+    ("Tab / Shift+Tab", "Move between buttons and fields"),
+    (
+        "Ctrl+drag",
+        "Snap edited positions to the grid and rotations to angle steps",
+    ),
+];
+
+/// Names an icon-only widget for screen readers, which would otherwise only hear the
+/// glyph.
+pub fn labeled(response: Response, typ: WidgetType, label: &str) -> Response {
+    response.widget_info(|| WidgetInfo::labeled(typ, true, label));
+    response
+}
+
+/// Arrow keys only drive the entity tree while no widget holds keyboard focus, so they
+/// keep moving focus and carets otherwise.
+pub fn tree_has_keyboard(ctx: &egui::Context) -> bool {
+    ctx.memory(|m| m.focused().is_none())
+}
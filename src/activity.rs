@@ -0,0 +1,49 @@
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow, utils::HashMap};
+
+/// Counts, per entity, in how many polls any of its component values changed.
+#[derive(Default)]
+pub struct ActivityTracker {
+    polls: u32,
+    changes: HashMap<Entity, u32>,
+}
+
+impl ActivityTracker {
+    pub fn record(
+        &mut self,
+        previous: &HashMap<Entity, BrpQueryRow>,
+        current: &HashMap<Entity, BrpQueryRow>,
+    ) {
+        // The first poll only establishes the baseline.
+        if previous.is_empty() {
+            return;
+        }
+        self.polls += 1;
+        for (entity, row) in current.iter() {
+            let changed = previous
+                .get(entity)
+                .is_none_or(|old| old.components != row.components);
+            if changed {
+                *self.changes.entry(*entity).or_default() += 1;
+            }
+        }
+        self.changes
+            .retain(|entity, _| current.contains_key(entity));
+    }
+
+    /// Share of recorded polls in which the entity changed, in `0.0..=1.0`.
+    pub fn heat(&self, entity: &Entity) -> f32 {
+        if self.polls == 0 {
+            return 0.0;
+        }
+        let changes = self.changes.get(entity).copied().unwrap_or_default();
+        changes as f32 / self.polls as f32
+    }
+
+    pub fn polls(&self) -> u32 {
+        self.polls
+    }
+
+    pub fn changes(&self, entity: &Entity) -> u32 {
+        self.changes.get(entity).copied().unwrap_or_default()
+    }
+}
@@ -0,0 +1,331 @@
+//! Alert rules on values from the game, reported to external tools when they fire.
+
+use std::collections::VecDeque;
+use std::net::UdpSocket;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use web_time::{Instant, SystemTime};
+
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow, utils::HashMap};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::dashboard::{display, TileInputs, TileSource};
+
+/// Fired alerts kept for the alerts window and the HUD.
+const LOG_LENGTH: usize = 50;
+/// Worlds captured by alerts kept in memory, each is a full copy.
+const SNAPSHOTS_KEPT: usize = 5;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct AlertRule {
+    pub name: String,
+    pub source: TileSource,
+    pub condition: Condition,
+    pub output: AlertOutput,
+    /// Capture the world as it was when the alert fired.
+    #[serde(default)]
+    pub snapshot: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub enum Condition {
+    Above(f64),
+    Below(f64),
+    /// Grows by at least this much from one poll to the next, like an entity count
+    /// spike.
+    RisesBy(f64),
+}
+
+/// Where a fired alert is reported besides the inspector itself.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub enum AlertOutput {
+    #[default]
+    None,
+    /// POSTs the alert as JSON.
+    Webhook { url: String },
+    /// Sends an OSC message over UDP with the alert name and value as arguments.
+    Osc { target: String, address: String },
+}
+
+impl Condition {
+    const KINDS: [&'static str; 3] = ["Above", "Below", "Rises by"];
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Condition::Above(_) => Self::KINDS[0],
+            Condition::Below(_) => Self::KINDS[1],
+            Condition::RisesBy(_) => Self::KINDS[2],
+        }
+    }
+
+    fn threshold(&mut self) -> &mut f64 {
+        match self {
+            Condition::Above(threshold)
+            | Condition::Below(threshold)
+            | Condition::RisesBy(threshold) => threshold,
+        }
+    }
+
+    fn with_kind(mut self, kind: &str) -> Self {
+        let threshold = *self.threshold();
+        match kind {
+            "Below" => Condition::Below(threshold),
+            "Rises by" => Condition::RisesBy(threshold),
+            _ => Condition::Above(threshold),
+        }
+    }
+
+    fn holds(&self, value: f64, previous: Option<f64>) -> bool {
+        match *self {
+            Condition::Above(threshold) => value > threshold,
+            Condition::Below(threshold) => value < threshold,
+            Condition::RisesBy(amount) => previous.is_some_and(|p| value - p >= amount),
+        }
+    }
+
+    fn describe(&self, value: f64) -> String {
+        let value = display(&value.into());
+        match self {
+            Condition::Above(threshold) => format!("{value} is above {threshold}"),
+            Condition::Below(threshold) => format!("{value} is below {threshold}"),
+            Condition::RisesBy(amount) => format!("rose by {amount} or more to {value}"),
+        }
+    }
+}
+
+impl Default for AlertRule {
+    fn default() -> Self {
+        Self {
+            name: "Alert".to_owned(),
+            source: TileSource::EntityCount {
+                components: Vec::new(),
+            },
+            condition: Condition::RisesBy(100.0),
+            output: AlertOutput::None,
+            snapshot: false,
+        }
+    }
+}
+
+impl AlertRule {
+    /// Fields for the name, the watched value, the condition and the output.
+    pub fn edit(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Name");
+            ui.text_edit_singleline(&mut self.name);
+        });
+        self.source.edit(ui);
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt(ui.id().with("condition"))
+                .selected_text(self.condition.kind())
+                .show_ui(ui, |ui| {
+                    for kind in Condition::KINDS {
+                        if ui
+                            .selectable_label(self.condition.kind() == kind, kind)
+                            .clicked()
+                        {
+                            self.condition = self.condition.with_kind(kind);
+                        }
+                    }
+                });
+            ui.add(egui::DragValue::new(self.condition.threshold()));
+        });
+        ui.horizontal(|ui| {
+            ui.label("Send to");
+            let kind = match self.output {
+                AlertOutput::None => "Nothing",
+                AlertOutput::Webhook { .. } => "Webhook",
+                AlertOutput::Osc { .. } => "OSC",
+            };
+            egui::ComboBox::from_id_salt(ui.id().with("output"))
+                .selected_text(kind)
+                .show_ui(ui, |ui| {
+                    if ui.selectable_label(kind == "Nothing", "Nothing").clicked() {
+                        self.output = AlertOutput::None;
+                    }
+                    if ui.selectable_label(kind == "Webhook", "Webhook").clicked() {
+                        self.output = AlertOutput::Webhook { url: String::new() };
+                    }
+                    if ui.selectable_label(kind == "OSC", "OSC").clicked() {
+                        self.output = AlertOutput::Osc {
+                            target: "127.0.0.1:9000".to_owned(),
+                            address: "/inspector/alert".to_owned(),
+                        };
+                    }
+                });
+        });
+        match &mut self.output {
+            AlertOutput::None => {}
+            AlertOutput::Webhook { url } => {
+                ui.add(egui::TextEdit::singleline(url).hint_text("https://..."));
+            }
+            AlertOutput::Osc { target, address } => {
+                ui.add(egui::TextEdit::singleline(target).hint_text("host:port"));
+                ui.add(egui::TextEdit::singleline(address).hint_text("OSC address"));
+            }
+        }
+        ui.checkbox(&mut self.snapshot, "Snapshot the world when it fires")
+            .on_hover_text("Keeps the entities as they were at that poll for offline analysis");
+    }
+}
+
+/// The world as it was when an alert fired.
+pub struct AlertSnapshot {
+    pub rule: String,
+    pub message: String,
+    pub taken: SystemTime,
+    pub rows: HashMap<Entity, BrpQueryRow>,
+    /// Where it was written automatically, or why that failed.
+    pub saved: Option<Result<PathBuf, String>>,
+}
+
+impl AlertSnapshot {
+    /// E.g. `alert-health_low-1714566600.json`.
+    pub fn file_name(&self) -> String {
+        let rule: String = self
+            .rule
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() {
+                    c.to_ascii_lowercase()
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let secs = self
+            .taken
+            .duration_since(web_time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        format!("alert-{rule}-{secs}.json")
+    }
+}
+
+pub struct FiredAlert {
+    pub rule: String,
+    pub message: String,
+    pub at: Instant,
+}
+
+/// Values of the previous poll and the alerts fired so far, not saved.
+#[derive(Default)]
+pub struct AlertState {
+    /// Last value of every rule by name, and whether its condition held then.
+    previous: HashMap<String, (f64, bool)>,
+    checked_poll: Option<u32>,
+    pub fired: VecDeque<FiredAlert>,
+    /// Why reporting the last alert of a rule failed, by rule name.
+    pub delivery_errors: Arc<Mutex<HashMap<String, String>>>,
+    /// Newest first.
+    pub snapshots: VecDeque<AlertSnapshot>,
+}
+
+impl AlertState {
+    /// Checks every rule once per poll. A rule fires when its condition starts to hold,
+    /// not again while it keeps holding. Rules asking for a snapshot get one of
+    /// `inputs.rows`, which is returned to be saved.
+    pub fn check(
+        &mut self,
+        rules: &[AlertRule],
+        poll: u32,
+        inputs: &TileInputs,
+    ) -> Option<&mut AlertSnapshot> {
+        if self.checked_poll == Some(poll) {
+            return None;
+        }
+        let mut snapshot = None;
+        self.checked_poll = Some(poll);
+        for rule in rules {
+            let Some(value) = rule.source.value(inputs).and_then(|v| v.as_f64()) else {
+                continue;
+            };
+            let previous = self.previous.get(&rule.name).copied();
+            let holds = rule
+                .condition
+                .holds(value, previous.map(|(value, _)| value));
+            self.previous.insert(rule.name.clone(), (value, holds));
+            if holds && !previous.is_some_and(|(_, held)| held) {
+                self.fire(rule, value);
+                if rule.snapshot {
+                    // One copy of the world serves every rule firing at the same poll.
+                    snapshot.get_or_insert_with(|| AlertSnapshot {
+                        rule: rule.name.clone(),
+                        message: self.fired[0].message.clone(),
+                        taken: SystemTime::now(),
+                        rows: inputs.rows.clone(),
+                        saved: None,
+                    });
+                }
+            }
+        }
+        let snapshot = snapshot?;
+        self.snapshots.push_front(snapshot);
+        self.snapshots.truncate(SNAPSHOTS_KEPT);
+        self.snapshots.front_mut()
+    }
+
+    fn fire(&mut self, rule: &AlertRule, value: f64) {
+        let message = rule.condition.describe(value);
+        self.deliver(rule, value, &message);
+        self.fired.push_front(FiredAlert {
+            rule: rule.name.clone(),
+            message,
+            at: Instant::now(),
+        });
+        self.fired.truncate(LOG_LENGTH);
+    }
+
+    fn deliver(&self, rule: &AlertRule, value: f64, message: &str) {
+        let errors = self.delivery_errors.clone();
+        errors.lock().unwrap().remove(&rule.name);
+        match &rule.output {
+            AlertOutput::None => {}
+            AlertOutput::Webhook { url } => {
+                let body = json!({ "alert": rule.name, "value": value, "message": message });
+                let request = ehttp::Request {
+                    method: "POST".to_owned(),
+                    url: url.clone(),
+                    body: body.to_string().into_bytes(),
+                    headers: ehttp::Headers::new(&[("Content-Type", "application/json")]),
+                };
+                let name = rule.name.clone();
+                ehttp::fetch(request, move |result| {
+                    let error = match result {
+                        Ok(response) if response.ok => return,
+                        Ok(response) => format!("{} {}", response.status, response.status_text),
+                        Err(error) => error,
+                    };
+                    errors.lock().unwrap().insert(name, error);
+                });
+            }
+            AlertOutput::Osc { target, address } => {
+                let packet = osc_message(address, &rule.name, value as f32);
+                let sent = UdpSocket::bind("0.0.0.0:0")
+                    .and_then(|socket| socket.send_to(&packet, target.as_str()));
+                if let Err(error) = sent {
+                    errors
+                        .lock()
+                        .unwrap()
+                        .insert(rule.name.clone(), error.to_string());
+                }
+            }
+        }
+    }
+}
+
+/// An OSC 1.0 message with a string and a float argument.
+fn osc_message(address: &str, name: &str, value: f32) -> Vec<u8> {
+    fn push_string(packet: &mut Vec<u8>, text: &str) {
+        packet.extend_from_slice(text.as_bytes());
+        // Null terminated, padded to four bytes.
+        packet.resize((packet.len() / 4 + 1) * 4, 0);
+    }
+    let mut packet = Vec::new();
+    push_string(&mut packet, address);
+    push_string(&mut packet, ",sf");
+    push_string(&mut packet, name);
+    packet.extend_from_slice(&value.to_be_bytes());
+    packet
+}
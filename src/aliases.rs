@@ -0,0 +1,57 @@
+use bevy::{remote::builtin_methods::BrpQueryRow, utils::HashMap};
+use serde::Deserialize;
+use std::{collections::BTreeMap, path::Path};
+
+use crate::annotations::{ComponentAnnotations, FieldAnnotation};
+
+/// Project specific display names and field annotations loaded from a TOML file, e.g.
+///
+/// ```toml
+/// [markers]
+/// "my_game::spawner::Spawner" = "Spawner"
+///
+/// [types]
+/// "my_game::ai::EnemyBrain" = "Enemy AI"
+///
+/// [fields]
+/// "my_game::Health.current" = "0..max"
+/// ```
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct Aliases {
+    /// Marker component type path to the label of entities carrying it. Ordered so an
+    /// entity with several markers always gets the same label.
+    pub markers: BTreeMap<String, String>,
+    /// Component type path to the name shown instead of it.
+    pub types: HashMap<String, String>,
+    /// Units and ranges of component fields, see [`FieldAnnotation`].
+    pub fields: BTreeMap<String, FieldAnnotation>,
+}
+
+impl Aliases {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(toml::from_str(&text)?)
+    }
+
+    /// Label of the first marker component the entity has.
+    pub fn entity_label(&self, row: &BrpQueryRow) -> Option<&str> {
+        self.markers
+            .iter()
+            .find(|(marker, _)| row.components.contains_key(*marker))
+            .map(|(_, label)| label.as_str())
+    }
+
+    pub fn annotations<'a>(&'a self, type_path: &'a str) -> ComponentAnnotations<'a> {
+        ComponentAnnotations {
+            fields: &self.fields,
+            type_path,
+        }
+    }
+
+    pub fn type_name<'a>(&'a self, type_path: &'a str) -> &'a str {
+        self.types
+            .get(type_path)
+            .map_or(type_path, |name| name.as_str())
+    }
+}
@@ -0,0 +1,176 @@
+//! Units and value ranges of component fields, declared in the project file next to the
+//! aliases.
+
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+/// Unit and range of one field. In the project file either a table or a string, which
+/// is a range when it contains `..` and a unit otherwise:
+///
+/// ```toml
+/// [fields]
+/// "my_game::Health.current" = "0..max"
+/// "speed" = "m/s"
+/// "my_game::Jump.height" = { range = "0..=10", unit = "m" }
+/// ```
+#[derive(Deserialize, Default, Clone, Debug)]
+#[serde(from = "AnnotationSpec")]
+pub struct FieldAnnotation {
+    pub range: Option<Range>,
+    pub unit: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AnnotationSpec {
+    Short(String),
+    Full {
+        range: Option<String>,
+        unit: Option<String>,
+    },
+}
+
+impl From<AnnotationSpec> for FieldAnnotation {
+    fn from(spec: AnnotationSpec) -> Self {
+        match spec {
+            AnnotationSpec::Short(text) => match Range::parse(&text) {
+                Some(range) => Self {
+                    range: Some(range),
+                    unit: None,
+                },
+                None => Self {
+                    range: None,
+                    unit: Some(text),
+                },
+            },
+            AnnotationSpec::Full { range, unit } => Self {
+                range: range.as_deref().and_then(Range::parse),
+                unit,
+            },
+        }
+    }
+}
+
+/// `min..max`, either side may be left out and may name a sibling field instead of a
+/// number. The end is inclusive either way since edited values are mostly floats.
+#[derive(Clone, Debug)]
+pub struct Range {
+    pub min: Option<Bound>,
+    pub max: Option<Bound>,
+}
+
+#[derive(Clone, Debug)]
+pub enum Bound {
+    Number(f64),
+    /// Value of another field of the same object.
+    Field(String),
+}
+
+impl Range {
+    fn parse(text: &str) -> Option<Self> {
+        let (min, max) = text.split_once("..")?;
+        let max = max.strip_prefix('=').unwrap_or(max);
+        Some(Self {
+            min: Bound::parse(min),
+            max: Bound::parse(max),
+        })
+    }
+
+    /// The bounds with field names looked up in `siblings`, unknown fields leave the
+    /// side open.
+    pub fn resolve(&self, siblings: Option<&Map<String, Value>>) -> (Option<f64>, Option<f64>) {
+        let resolve = |bound: &Option<Bound>| match bound.as_ref()? {
+            Bound::Number(number) => Some(*number),
+            Bound::Field(name) => siblings?.get(name)?.as_f64(),
+        };
+        (resolve(&self.min), resolve(&self.max))
+    }
+}
+
+impl Bound {
+    fn parse(text: &str) -> Option<Self> {
+        let text = text.trim();
+        if text.is_empty() {
+            return None;
+        }
+        Some(match text.parse() {
+            Ok(number) => Self::Number(number),
+            Err(_) => Self::Field(text.to_owned()),
+        })
+    }
+}
+
+/// Field annotations applying to one component type.
+#[derive(Clone, Copy)]
+pub struct ComponentAnnotations<'a> {
+    pub fields: &'a BTreeMap<String, FieldAnnotation>,
+    pub type_path: &'a str,
+}
+
+impl<'a> ComponentAnnotations<'a> {
+    /// Annotation of the field at JSON pointer `path`, declared for this type as
+    /// `type_path.field.nested` or for any type as just `field.nested`.
+    pub fn get(&self, path: &str) -> Option<&'a FieldAnnotation> {
+        if self.fields.is_empty() {
+            return None;
+        }
+        let dotted = path.trim_start_matches('/').replace('/', ".");
+        self.fields
+            .get(&format!("{}.{dotted}", self.type_path))
+            .or_else(|| self.fields.get(&dotted))
+    }
+
+    /// Fields of `value` outside their declared range, as messages to show.
+    pub fn warnings(&self, value: &Value) -> Vec<String> {
+        fn walk(
+            annotations: &ComponentAnnotations,
+            path: &str,
+            value: &Value,
+            siblings: Option<&Map<String, Value>>,
+            out: &mut Vec<String>,
+        ) {
+            match value {
+                Value::Object(fields) => {
+                    for (key, field) in fields {
+                        walk(
+                            annotations,
+                            &format!("{path}/{key}"),
+                            field,
+                            Some(fields),
+                            out,
+                        );
+                    }
+                }
+                Value::Array(items) => {
+                    for (index, item) in items.iter().enumerate() {
+                        walk(annotations, &format!("{path}/{index}"), item, None, out);
+                    }
+                }
+                Value::Number(number) => {
+                    let Some(range) = annotations.get(path).and_then(|a| a.range.as_ref()) else {
+                        return;
+                    };
+                    let number = number.as_f64().unwrap_or_default();
+                    let field = path.trim_start_matches('/');
+                    match range.resolve(siblings) {
+                        (Some(min), _) if number < min => {
+                            out.push(format!("{field} is {number}, below {min}"));
+                        }
+                        (_, Some(max)) if number > max => {
+                            out.push(format!("{field} is {number}, above {max}"));
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+        let mut out = Vec::new();
+        if !self.fields.is_empty() {
+            walk(self, "", value, None, &mut out);
+        }
+        out
+    }
+}
@@ -1,20 +1,148 @@
+//! The inspector app. State lives in [`TemplateApp`], views return a [`Msg`] for every
+//! change they want and [`TemplateApp::dispatch`] applies it. The views are split by
+//! area into the submodules, requests to the game live in `requests`.
+
 use bevy::{
     prelude::Entity,
-    remote::{
-        builtin_methods::{
-            BrpDestroyParams, BrpQuery, BrpQueryFilter, BrpQueryParams, BrpQueryRow,
-            BRP_DESTROY_METHOD, BRP_LIST_METHOD, BRP_QUERY_METHOD,
-        },
-        http::{DEFAULT_ADDR, DEFAULT_PORT},
+    remote::builtin_methods::{
+        BrpDestroyParams, BrpInsertParams, BrpMutateParams, BrpQuery, BrpQueryFilter,
+        BrpQueryParams, BrpQueryRow, BrpRemoveParams, BrpReparentParams, BrpSpawnParams,
+        BRP_DESTROY_METHOD, BRP_GET_AND_WATCH_METHOD, BRP_GET_METHOD, BRP_INSERT_METHOD,
+        BRP_LIST_METHOD, BRP_MUTATE_COMPONENT_METHOD, BRP_QUERY_METHOD, BRP_REMOVE_METHOD,
+        BRP_REPARENT_METHOD, BRP_SPAWN_METHOD,
     },
     utils::HashMap,
 };
 use eframe::egui::{self, ViewportCommand};
 use egui::{Color32, RichText};
 use serde::{Deserialize, Serialize};
-use std::sync::{Arc, Mutex};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet, HashSet, VecDeque};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+use web_time::{Duration, Instant};
+
+use crate::a11y::{self, labeled, tree_has_keyboard};
+use crate::activity::ActivityTracker;
+use crate::alerts::{AlertRule, AlertState};
+use crate::aliases::Aliases;
+use crate::appearance::Appearance;
+use crate::bookmarks::{edit_field, leaf_paths, with_field, FieldBookmark, FieldChange};
+use crate::capabilities::{Capabilities, RPC_DISCOVER_METHOD};
+use crate::category::categories;
+use crate::changes::{Change, ChangeLog, FeedChange};
+use crate::commands::{fuzzy_score, Command, CommandPalette};
+use crate::companion::{
+    ChangeTicks, Encoding, GameMetadata, INSPECTOR_CHANGE_TICKS_METHOD,
+    INSPECTOR_DIAGNOSTICS_METHOD, INSPECTOR_ENCODINGS_METHOD, INSPECTOR_FRAME_METHOD,
+    INSPECTOR_METADATA_METHOD, INSPECTOR_PANELS_METHOD, INSPECTOR_PAUSE_METHOD,
+    INSPECTOR_PING_METHOD, INSPECTOR_QUERY_VISIBLE_METHOD, INSPECTOR_RENAME_METHOD,
+    INSPECTOR_RENDER_STATS_METHOD, INSPECTOR_RESUME_METHOD, INSPECTOR_SEARCH_METHOD,
+    INSPECTOR_STEP_METHOD,
+};
+use crate::compat::{BevyVersion, TypePaths};
+use crate::completion::type_path_input;
+use crate::component_history::ComponentHistory;
+use crate::config::AppConfig;
+use crate::dashboard::{Dashboard, DashboardPack, DashboardState, TileInputs};
+use crate::despawned::Despawned;
+use crate::diagnostics::DiagnosticsBundle;
+use crate::dock;
+use crate::edit::{
+    field_mutations, merge_changes, modified_fields, ComponentEdit, ConflictResolution, EditState,
+    EditView, Snap,
+};
+use crate::error::InspectorError;
+use crate::files;
+use crate::geometry::WindowGeometry;
+use crate::graph::{write_graph, EntityGraph};
+use crate::groups::{group_of, EntityGroup};
+use crate::helper::{self, Endpoint};
+use crate::history::{QueryHistory, QueryRecord};
+use crate::hud::{show_hud, HudCorner};
+use crate::insights::{co_occurrence, Insight};
+use crate::latency::{LatencyTracker, PING_INTERVAL};
+use crate::launch::{LaunchStatus, Launcher};
+use crate::lazy::{LazyLoader, Oversized};
+use crate::lint::{lint, LintRule};
+use crate::local_scan::{LocalScan, ProbeStatus};
+use crate::migration::Migration;
+use crate::minimap::{Minimap, MinimapPoint};
+use crate::multi_get;
+use crate::onboarding::{draw_onboarding, ConnectionStatus, OnboardingAction};
+use crate::ordering::{children, hierarchy_order, EntitySort};
+use crate::palette::{set_palette, status_colors, StatusPalette};
+use crate::panels::{draw_value, CustomPanel, PanelDescriptor};
+use crate::paste::parse_entities;
+use crate::performance::PerformanceMonitor;
+use crate::pins::EntityPin;
+use crate::power;
+use crate::presets::ViewPreset;
+use crate::problems::{hierarchy_problems, parent_of, HierarchyFix, HierarchyProblem};
+use crate::project::{project_name, ProjectSettings};
+use crate::protection::protected_by;
+use crate::query_builder::CustomQuery;
+use crate::random::Rng;
+use crate::redaction::MASK;
+use crate::references::{asset_handles, find_asset_users, find_references, AssetKey, Reference};
+use crate::refresh::{RefreshPolicy, RefreshScheduler, RefreshTarget};
+use crate::renderers::{ComponentView, Renderers};
+use crate::resources::{
+    MutateResourceParams, ResourceWatch, BRP_GET_RESOURCE_METHOD, BRP_MUTATE_RESOURCE_METHOD,
+    VIRTUAL_TIME_RESOURCE,
+};
+use crate::responses::{
+    BrpResult, Components, Destroyed, Inserted, Mutated, QueryRows, RegistrySchemas, Removed,
+    Reparented, ResourceMutated, Spawned, TypeList,
+};
+use crate::rpc_log::{format_size, RpcLog, RpcLogAction};
+use crate::scene::{scene_entities, write_scene};
+use crate::scheduler::RequestScheduler;
+use crate::schema::{
+    docs_target, draw_docs, forget_text, set_docs_target, Schemas, BRP_REGISTRY_SCHEMA_METHOD,
+};
+use crate::search::{EntitySearch, RemoteSearch, SearchResult};
+use crate::spatial::{camera_position, filter_entities, world_position, SpatialFilter};
+use crate::spawn::{Anchors, SpawnDraft};
+use crate::staging::{capture_call, insert_call, restore_call, CommitReport, EditKey, StagedEdit};
+use crate::summary::{
+    entity_label, entity_name, entity_summary, render_label_template, short_name,
+};
+use crate::throttle;
+use crate::timeline::{Comparison, Timeline, TimelineState};
+use crate::toolbar::ToolbarItem;
+use crate::tour::{self, Tour, TourAction, TourAnchor};
+use crate::trace;
+use crate::transport::{self, CancelHandle};
+use crate::trash::{Trash, TrashedEntity, TrashedNode};
+use crate::tree::EntityTree;
+use crate::type_catalog::{catalog, CatalogAction, TypeCatalog};
+use crate::undo::{Mutation, UndoHistory};
+use crate::updates::UpdateChecker;
+use crate::value_format::{set_value_format, value_format};
+use crate::viewports::show_panel;
+use crate::watch::EntityWatch;
+
+mod chrome;
+mod command_palette;
+mod entity_tree;
+mod requests;
+mod sessions;
+mod tool_panels;
+mod top_bar;
+
+use chrome::*;
+use requests::*;
+use sessions::Session;
+
+type ComponentEdits = HashMap<(Entity, String), ComponentEdit>;
 
-use crate::helper;
+const VISIBILITY: &str = "bevy_render::view::visibility::Visibility";
+const RENDER_LAYERS: &str = "bevy_render::view::visibility::render_layers::RenderLayers";
 
 /// The response to a `bevy/query` request.
 pub type BrpQueryResponse = Vec<BrpQueryRow>;
@@ -29,12 +157,80 @@ impl ToHashMap for BrpQueryResponse {
     }
 }
 
+/// How long newly spawned entities stay highlighted.
+const SPAWN_FLASH_DURATION: Duration = Duration::from_millis(1500);
+
+/// How many of the most often added component types the Add component picker lists.
+const FREQUENT_COMPONENTS: usize = 5;
+
+/// Edits changing more fields than this are inserted whole instead of field by field.
+const MAX_FIELD_MUTATIONS: usize = 8;
+
+/// How often fetching is retried while the game can't be reached.
+const RETRY_INTERVAL: Duration = Duration::from_secs(2);
+/// Longest wait between retries, the interval doubles with each failed one until then.
+const MAX_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long a request may run before the watchdog warning shows up.
+const REQUEST_WARNING_AFTER: Duration = Duration::from_secs(3);
+/// How long a fired alert stays on the HUD.
+const HUD_ALERT_DURATION: Duration = Duration::from_secs(30);
+
+static DOWNLOAD_ID: AtomicUsize = AtomicUsize::new(1);
+
+#[derive(Default)]
 enum Download {
+    #[default]
     None,
-    InProgress,
+    InProgress {
+        /// Identifies the request so that the result of a cancelled one can be ignored.
+        id: usize,
+        started: Instant,
+        /// Elapsed time after which the watchdog warning is shown.
+        warn_after: Duration,
+        cancel: CancelHandle,
+    },
     Done,
 }
 
+impl Download {
+    fn start(store: &Mutex<Download>) -> usize {
+        let id = DOWNLOAD_ID.fetch_add(1, Ordering::SeqCst);
+        *store.lock().unwrap() = Download::InProgress {
+            id,
+            started: Instant::now(),
+            warn_after: REQUEST_WARNING_AFTER,
+            cancel: CancelHandle::default(),
+        };
+        id
+    }
+
+    /// Lets the watchdog abort the request, on backends that support it.
+    fn attach(store: &Mutex<Download>, id: usize, handle: CancelHandle) {
+        if let Download::InProgress {
+            id: current,
+            cancel,
+            ..
+        } = &mut *store.lock().unwrap()
+        {
+            if *current == id {
+                *cancel = handle;
+            }
+        }
+    }
+
+    /// Marks the request as finished. Returns `false` if it was cancelled or superseded
+    /// in the meantime, in which case its result should be dropped.
+    fn finish(store: &Mutex<Download>, id: usize) -> bool {
+        let mut download = store.lock().unwrap();
+        if !matches!(&*download, Download::InProgress { id: current, .. } if *current == id) {
+            return false;
+        }
+        *download = Download::Done;
+        true
+    }
+}
+
 /// We derive Deserialize/Serialize so we can persist app state on shutdown.
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(default)] // if we add new fields, give them default values when deserializing old state
@@ -43,182 +239,1810 @@ pub struct TemplateApp {
     query_list: Arc<Mutex<Option<BrpQueryParams>>>,
     #[serde(skip)] // This how you opt-out of serialization of a field
     download: Arc<Mutex<Download>>,
+    /// Types left out of the query because the remote failed to serialize them, with the
+    /// error. They are still queried for presence so affected rows can show a warning.
+    #[serde(skip)]
+    rejected_types: Arc<Mutex<HashMap<String, String>>>,
     #[serde(skip)]
     components: Arc<Mutex<HashMap<Entity, BrpQueryRow>>>,
-    skip_empty_entities: bool,
+    /// When each cached row was last received from the game.
+    #[serde(skip)]
+    fetched_at: Arc<Mutex<HashMap<Entity, Instant>>>,
+    /// Cached entities a poll showed to be gone from the game.
+    #[serde(skip)]
+    despawned: Arc<Mutex<Despawned>>,
+    /// When the world, resources, diagnostics and watches were last refreshed.
+    #[serde(skip)]
+    refresh: RefreshScheduler,
+    /// Game frame the cached rows were captured in, known with the companion plugin.
+    #[serde(skip)]
+    data_frame: Arc<Mutex<Option<u64>>>,
+    /// Latest frame number polled from the game for frame aligned live mode.
+    #[serde(skip)]
+    remote_frame: Arc<Mutex<Option<u64>>>,
+    #[serde(skip)]
+    frame_poll_in_flight: Arc<AtomicBool>,
+    #[serde(skip)]
+    change_ticks: Arc<Mutex<Option<SelectedTicks>>>,
+    #[serde(skip)]
+    change_ticks_in_flight: Arc<AtomicBool>,
+    /// Matches of the search as run by the game, when it has the companion plugin.
+    #[serde(skip)]
+    remote_search: Arc<Mutex<Option<RemoteMatches>>>,
+    #[serde(skip)]
+    remote_search_in_flight: Arc<AtomicBool>,
+    /// Entities that appeared since the previous fetch, with when they were first seen.
+    #[serde(skip)]
+    spawned: Arc<Mutex<HashMap<Entity, Instant>>>,
+    /// Newly spawned entity the tree should scroll to once, when following spawns.
+    #[serde(skip)]
+    spawn_to_follow: Arc<Mutex<Option<Entity>>>,
+    #[serde(skip)]
+    scroll_to_spawned: Option<Entity>,
+    config: AppConfig,
+    /// The connection wizard was completed once, it only comes back when connecting fails.
+    onboarding_complete: bool,
+    /// The guided tour was finished or skipped, it only comes back when asked for.
+    tour_complete: bool,
+    #[serde(skip)]
+    tour: Tour,
+    /// Connected to the built-in mock server started with `--demo`.
+    #[serde(skip)]
+    demo: bool,
+    #[serde(skip)]
+    activity: Arc<Mutex<ActivityTracker>>,
+    #[serde(skip)]
+    changes: Arc<Mutex<ChangeLog>>,
+    /// The game is paused by the inspector and stepped frame by frame.
+    #[serde(skip)]
+    frame_capture: bool,
+    /// Set when the world should be fetched again, e.g. after a frame step or a respawn.
+    #[serde(skip)]
+    refresh_requested: Arc<Mutex<bool>>,
+    #[serde(skip)]
+    error_info: Arc<Mutex<Option<InspectorError>>>,
+    /// The error and the response it came from, when there was one to show.
+    #[serde(skip)]
+    error_raw: Arc<Mutex<Option<(InspectorError, String)>>>,
+    /// Last automatic retry after the game couldn't be reached.
+    #[serde(skip)]
+    last_retry: Option<Instant>,
+    /// Retries since the game was last reached.
+    #[serde(skip)]
+    retry_attempts: u32,
+    #[serde(skip)]
+    edits: Arc<Mutex<ComponentEdits>>,
+    /// Entities destroyed from the inspector, kept so they can be respawned.
+    #[serde(skip)]
+    trash: Arc<Mutex<Trash>>,
+    #[serde(skip)]
+    show_trash: bool,
+    /// Entities ctrl-clicked in the tree for batch actions.
+    #[serde(skip)]
+    multi_selected: BTreeSet<Entity>,
+    /// Changes sent to the game, for Ctrl+Z and Ctrl+Shift+Z.
+    #[serde(skip)]
+    undo: Arc<Mutex<UndoHistory>>,
+    /// Value of the component being dragged from before the drag, recorded for undo
+    /// once the final value is inserted.
+    #[serde(skip)]
+    drag_before: Option<(Entity, String, Option<serde_json::Value>)>,
+    /// Repeated by [`Command::RepeatLast`].
+    #[serde(skip)]
+    last_action: Option<LastAction>,
+    /// Entities pasted as JSON waiting to be spawned, or why the paste isn't entities.
+    #[serde(skip)]
+    pasted: Option<Result<Vec<BrpSpawnParams>, String>>,
+    /// Components last copied from an entity, for pasting into another one.
+    #[serde(skip)]
+    copied_components: Option<HashMap<String, serde_json::Value>>,
+    #[serde(skip)]
+    scheduler: RequestScheduler,
+    #[serde(skip)]
+    new_profile_name: String,
+    /// Custom panels advertised by the companion plugin.
+    #[serde(skip)]
+    panels: Arc<Mutex<Vec<CustomPanel>>>,
+    /// Set once the connected remote was asked for its methods and panels.
+    #[serde(skip)]
+    discovery_requested: bool,
+    /// The user asked to connect, needed when the profile doesn't connect on its own.
+    #[serde(skip)]
+    connect_requested: bool,
+    /// The profile's startup actions still have to run once connected.
+    #[serde(skip)]
+    startup_pending: bool,
+    #[serde(skip)]
+    launcher: Launcher,
+    #[serde(skip)]
+    local_scan: LocalScan,
+    #[serde(skip)]
+    capabilities: Arc<Mutex<Capabilities>>,
+    /// The companion plugin's MessagePack endpoint once it answered there, requests are
+    /// JSON until then.
+    #[serde(skip)]
+    msgpack_url: Arc<Mutex<Option<String>>>,
+    /// Detected from the registered types, decides which type paths are well-known.
+    #[serde(skip)]
+    bevy_version: BevyVersion,
+    /// Loaded from `config.aliases_file`.
+    #[serde(skip)]
+    aliases: Aliases,
+    #[serde(skip)]
+    selected: Option<Entity>,
+    /// Change streams of the entities being focused on, see [`Self::sync_watches`].
+    #[serde(skip)]
+    watches: HashMap<Entity, EntityWatch>,
+    /// Set when the selection changed outside of the tree, e.g. in the minimap.
+    #[serde(skip)]
+    scroll_to_selected: bool,
+    detail_tab: DetailTab,
+    #[serde(skip)]
+    minimap: Minimap,
+    #[serde(skip)]
+    component_history: ComponentHistory,
+    /// Result of the last reference search with its window title, shown until closed.
+    #[serde(skip)]
+    references: Option<(String, Vec<Reference>)>,
+    #[serde(skip)]
+    history: Arc<Mutex<QueryHistory>>,
+    #[serde(skip)]
+    show_history: bool,
+    #[serde(skip)]
+    resource_watch: ResourceWatch,
+    /// Registry schemas used to generate forms for new components.
+    #[serde(skip)]
+    schemas: Arc<Mutex<Schemas>>,
+    #[serde(skip)]
+    wizard: Option<ComponentWizard>,
+    #[serde(skip)]
+    show_type_docs: bool,
+    #[serde(skip)]
+    show_change_feed: bool,
+    /// Entity or component type the change feed is narrowed to.
+    #[serde(skip)]
+    feed_filter: String,
+    /// Connection state of the tabs in the background, by profile name.
+    #[serde(skip)]
+    sessions: HashMap<String, Session>,
+    /// Narrows the entity tree down to matching entities.
+    #[serde(skip)]
+    search: EntitySearch,
+    #[serde(skip)]
+    show_insights: bool,
+    #[serde(skip)]
+    show_problems: bool,
+    #[serde(skip)]
+    spawn_draft: SpawnDraft,
+    #[serde(skip)]
+    show_staged: bool,
+    #[serde(skip)]
+    show_stats: bool,
+    #[serde(skip)]
+    show_dashboard: bool,
+    #[serde(skip)]
+    dashboard: DashboardState,
+    #[serde(skip)]
+    pending_confirmation: Option<PendingConfirmation>,
+    /// Differences between the states picked on the timeline, by their poll times.
+    #[serde(skip)]
+    comparison: Option<((Instant, Instant), Comparison)>,
+    #[serde(skip)]
+    show_alerts: bool,
+    #[serde(skip)]
+    show_query_builder: bool,
+    #[serde(skip)]
+    show_performance: bool,
+    #[serde(skip)]
+    performance: PerformanceMonitor,
+    #[serde(skip)]
+    show_render_stats: bool,
+    #[serde(skip)]
+    show_bookmarks: bool,
+    #[serde(skip)]
+    show_types: bool,
+    #[serde(skip)]
+    show_rpc_log: bool,
+    #[serde(skip)]
+    show_request_queue: bool,
+    #[serde(skip)]
+    rpc_log: RpcLog,
+    /// Saved type paths to move to new ones, shown as a dialog while set.
+    #[serde(skip)]
+    migration: Option<Migration>,
+    /// Whether the saved type paths were checked against this connection's types.
+    #[serde(skip)]
+    migration_checked: bool,
+    #[serde(skip)]
+    type_catalog: TypeCatalog,
+    /// Views of component types drawn instead of their JSON, forks register theirs here.
+    #[serde(skip)]
+    renderers: Renderers,
+    /// The query being put together in the query builder.
+    #[serde(skip)]
+    query_draft: CustomQuery,
+    /// Name typed for saving the current view as a preset.
+    #[serde(skip)]
+    preset_name: String,
+    #[serde(skip)]
+    alerts: AlertState,
+    /// Recent live poll results, for rewinding the view.
+    #[serde(skip)]
+    timeline: Arc<Mutex<Timeline>>,
+    /// What the last commit of staged edits applied, failed and rolled back.
+    #[serde(skip)]
+    commit_report: Arc<Mutex<Option<CommitReport>>>,
+    /// Entities drawn in the tree in the last frame, top to bottom, for arrow key
+    /// navigation.
+    #[serde(skip)]
+    visible_entities: Arc<Mutex<Vec<Entity>>>,
+    #[serde(skip)]
+    lazy: LazyLoader,
+    /// Moves keyboard focus into the details window on the next frame.
+    #[serde(skip)]
+    focus_details: bool,
+    /// Set while waiting for the screenshot that goes into a diagnostics bundle.
+    #[serde(skip)]
+    diagnostics_requested: bool,
+    /// Result of the last co-occurrence analysis of the cached world.
+    #[serde(skip)]
+    insights: Option<Vec<Insight>>,
+    /// Round trips of the ping sent every second.
+    #[serde(skip)]
+    latency: Arc<Mutex<LatencyTracker>>,
+    /// What the companion plugin says about the game, when it has it.
+    #[serde(skip)]
+    metadata: Arc<Mutex<Option<GameMetadata>>>,
+    #[serde(skip)]
+    command_palette: CommandPalette,
+    /// Density the style was last set up for, `None` before the first frame.
     #[serde(skip)]
-    error_info: Arc<Mutex<Option<String>>>,
+    applied_compact: Option<bool>,
+    /// Appearance egui was last set up for, `None` before the first frame.
+    #[serde(skip)]
+    applied_appearance: Option<Appearance>,
+    /// Why the system font couldn't be used, shown next to the font choice.
+    #[serde(skip)]
+    font_fallback: Option<String>,
+    #[serde(skip)]
+    updates: UpdateChecker,
+    /// Power saver state animations were last set up for, `None` before the first frame.
+    #[serde(skip)]
+    applied_power_saver: Option<bool>,
+    /// Set while the power saver holds polling back, the window being minimized.
+    #[serde(skip)]
+    polling_paused: bool,
+    window_geometry: WindowGeometry,
+    /// Set once the stored geometry was applied after launch.
+    #[serde(skip)]
+    geometry_restored: bool,
 }
 
-#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug, Default)]
-pub enum ActionToDo {
+/// Change requested by a view: to the game world, the connection, the toolbar's modes,
+/// the view presets or the dashboards. Views return or [send](TemplateApp::send) these
+/// instead of making such changes themselves, [`TemplateApp::dispatch`] is the one place
+/// they are carried out, so undo, scripting or tests can hook in there. What only
+/// concerns a view, e.g. which panels are open or text being typed, and the preferences
+/// of the Settings menu are still changed in place.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Default)]
+pub enum Msg {
     #[default]
     None,
-    Remove,
+    /// Destroy the entity.
+    Remove(Entity),
+    Insert(Entity, String, serde_json::Value),
+    /// Insert of a value still being dragged, the final one comes as [`Msg::Insert`].
+    LiveInsert(Entity, String, serde_json::Value),
+    /// Insert from the Add component picker, counted for its frequently added list.
+    AddComponent(Entity, String, serde_json::Value),
+    ToggleFavorite(String),
+    RemoveComponent(Entity, String),
+    TogglePin(String),
+    /// Pin the entity above the tree, or unpin it.
+    TogglePinEntity(Entity),
+    /// Bookmark the field at a JSON pointer of the component.
+    BookmarkField(Entity, String, String),
+    /// Show how the component changed over the kept polls.
+    ShowHistory(Entity, String),
+    ApplyEdit(Entity, String),
+    Select(Entity),
+    FindReferences(Entity),
+    Rename(Entity, String),
+    FindAssetUsers(AssetKey),
+    ResolveConflict(Entity, String, ConflictResolution),
+    /// Move the entity below another one, or to the root for `None`.
+    Reparent(Entity, Option<Entity>),
+    /// Save the entity and its descendants, or the whole world for `None`, to a scene
+    /// file.
+    ExportScene(Option<Entity>),
+    /// Save the hierarchy and references below the entity, or of the whole world for
+    /// `None`, as a graph.
+    ExportGraph(Option<Entity>),
+    /// Copy one component of the entity, or all of them for `None`, to the clipboard.
+    CopyComponents(Entity, Option<String>),
+    /// Insert the last copied components into the entity.
+    PasteComponents(Entity),
+    /// Add the entity to the selection for batch actions, or take it out.
+    ToggleMultiSelect(Entity),
+    DestroyMany(Vec<Entity>),
+    /// Remove the component from those of the entities that have it.
+    RemoveFromMany(Vec<Entity>, String),
+    /// Connect over HTTP to a game at the host and port, e.g. one found on this machine.
+    Attach(String, u16),
+    /// Talk to the game over HTTP or a WebSocket, plain or over TLS.
+    SetScheme {
+        websocket: bool,
+        secure: bool,
+    },
+    SetHost(String),
+    SetPort(u16),
+    SetFlag(Flag, bool),
+    /// Poll in step with the game's frames, every that many, or on a timer for 0.
+    SetLiveEveryFrames(u32),
+    SetLiveInterval(u64),
+    SetSort(EntitySort),
+    /// How far outside the camera's view entities are still fetched with
+    /// [`Flag::OnScreenOnly`], in world units.
+    SetVisibleMargin(f32),
+    /// Save the current view under the name, replacing a preset of the same name.
+    SavePreset(String),
+    ApplyPreset(usize),
+    DeletePreset(usize),
+    /// Replace the dashboards after they were edited, with the index of the one shown.
+    SetDashboards(Vec<Dashboard>, usize),
+    /// Pick a file of shared dashboards and add them.
+    ImportDashboards,
+}
+
+/// Modes of the toolbar that are turned on and off.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Flag {
+    Live,
+    /// Poll only the selected entity and its descendants while live.
+    LiveSubtree,
+    ReadOnly,
+    HideEmpty,
+    Activity,
+    FollowSpawns,
+    OnScreenOnly,
+    PowerSaver,
+}
+
+impl Flag {
+    fn of(self, config: &mut AppConfig) -> &mut bool {
+        match self {
+            Flag::Live => &mut config.live_mode,
+            Flag::LiveSubtree => &mut config.live_subtree,
+            Flag::ReadOnly => &mut config.read_only,
+            Flag::HideEmpty => &mut config.skip_empty_entities,
+            Flag::Activity => &mut config.show_activity,
+            Flag::FollowSpawns => &mut config.follow_spawns,
+            Flag::OnScreenOnly => &mut config.visible_only,
+            Flag::PowerSaver => &mut config.power_saver.enabled,
+        }
+    }
+}
+
+/// Change ticks of the selected entity's components.
+struct SelectedTicks {
+    entity: Entity,
+    /// When its row was fetched as they were asked for.
+    row_fetched: Option<Instant>,
+    /// `None` when the game couldn't tell.
+    ticks: Option<ChangeTicks>,
+}
+
+/// Entities the game matched a search against.
+struct RemoteMatches {
+    search: RemoteSearch,
+    /// World fetch they were asked for with, they are asked for again after the next one.
+    poll: u32,
+    /// `None` when the game couldn't search, the fetched rows are searched then.
+    entities: Option<HashSet<Entity>>,
+}
+
+/// The last change sent to the game, kept to send again with the same payload.
+enum LastAction {
+    Insert(String, serde_json::Value),
+    Remove(String),
+    Spawn(BrpSpawnParams),
+}
+
+/// A destroy, or a removal on a protected entity, waiting to be confirmed.
+struct PendingConfirmation {
+    msg: Msg,
+    /// Why the entity is protected, or what will be destroyed.
+    reason: String,
+    protected: bool,
+}
+
+/// A component being put together in the details window before it is inserted.
+struct ComponentWizard {
+    entity: Entity,
+    component: String,
+    value: serde_json::Value,
+    /// Typed into the search next to the component picker.
+    search: String,
+}
+
+/// Tabs of the details window of the selected entity.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum DetailTab {
+    #[default]
+    Components,
+    Hierarchy,
+    Raw,
+    History,
+}
+
+impl DetailTab {
+    const ALL: [DetailTab; 4] = [
+        DetailTab::Components,
+        DetailTab::Hierarchy,
+        DetailTab::Raw,
+        DetailTab::History,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            DetailTab::Components => "Components",
+            DetailTab::Hierarchy => "Hierarchy",
+            DetailTab::Raw => "Raw",
+            DetailTab::History => "History",
+        }
+    }
 }
 
 impl Default for TemplateApp {
     fn default() -> Self {
         Self {
             download: Arc::new(Mutex::new(Download::None)),
+            rejected_types: Arc::new(Mutex::new(HashMap::new())),
             query_list: Arc::new(Mutex::new(None)),
             components: Arc::new(Mutex::new(HashMap::new())),
-            skip_empty_entities: true,
+            fetched_at: Arc::new(Mutex::new(HashMap::new())),
+            despawned: Arc::new(Mutex::new(Despawned::default())),
+            refresh: RefreshScheduler::default(),
+            data_frame: Arc::new(Mutex::new(None)),
+            remote_frame: Arc::new(Mutex::new(None)),
+            frame_poll_in_flight: Arc::new(AtomicBool::new(false)),
+            change_ticks: Arc::new(Mutex::new(None)),
+            change_ticks_in_flight: Arc::new(AtomicBool::new(false)),
+            remote_search: Arc::new(Mutex::new(None)),
+            remote_search_in_flight: Arc::new(AtomicBool::new(false)),
+            spawned: Arc::new(Mutex::new(HashMap::new())),
+            spawn_to_follow: Arc::new(Mutex::new(None)),
+            scroll_to_spawned: None,
+            config: AppConfig::default(),
+            onboarding_complete: false,
+            tour_complete: false,
+            tour: Tour::default(),
+            demo: false,
+            activity: Arc::new(Mutex::new(ActivityTracker::default())),
+            changes: Arc::new(Mutex::new(ChangeLog::default())),
+            frame_capture: false,
+            refresh_requested: Arc::new(Mutex::new(false)),
             error_info: Arc::new(Mutex::new(None)),
+            error_raw: Arc::new(Mutex::new(None)),
+            last_retry: None,
+            retry_attempts: 0,
+            edits: Arc::new(Mutex::new(HashMap::new())),
+            trash: Arc::new(Mutex::new(Trash::default())),
+            show_trash: false,
+            multi_selected: BTreeSet::new(),
+            undo: Arc::new(Mutex::new(UndoHistory::default())),
+            drag_before: None,
+            last_action: None,
+            pasted: None,
+            copied_components: None,
+            scheduler: RequestScheduler::new(4, 20.0),
+            new_profile_name: String::new(),
+            panels: Arc::new(Mutex::new(Vec::new())),
+            discovery_requested: false,
+            connect_requested: false,
+            startup_pending: true,
+            launcher: Launcher::default(),
+            local_scan: LocalScan::default(),
+            capabilities: Arc::new(Mutex::new(Capabilities::default())),
+            msgpack_url: Arc::new(Mutex::new(None)),
+            bevy_version: BevyVersion::default(),
+            aliases: Aliases::default(),
+            selected: None,
+            watches: HashMap::new(),
+            scroll_to_selected: false,
+            detail_tab: DetailTab::default(),
+            minimap: Minimap::default(),
+            component_history: ComponentHistory::default(),
+            references: None,
+            history: Arc::new(Mutex::new(QueryHistory::default())),
+            show_history: false,
+            resource_watch: ResourceWatch::default(),
+            schemas: Arc::new(Mutex::new(Schemas::default())),
+            wizard: None,
+            show_type_docs: false,
+            show_change_feed: false,
+            feed_filter: String::new(),
+            sessions: HashMap::new(),
+            search: EntitySearch::default(),
+            show_insights: false,
+            show_problems: false,
+            spawn_draft: SpawnDraft::default(),
+            show_staged: false,
+            show_stats: false,
+            show_dashboard: false,
+            dashboard: DashboardState::default(),
+            pending_confirmation: None,
+            comparison: None,
+            show_alerts: false,
+            show_query_builder: false,
+            show_performance: false,
+            performance: PerformanceMonitor::default(),
+            show_render_stats: false,
+            show_bookmarks: false,
+            show_types: false,
+            show_rpc_log: false,
+            show_request_queue: false,
+            rpc_log: RpcLog::default(),
+            migration: None,
+            migration_checked: false,
+            type_catalog: TypeCatalog::default(),
+            renderers: Renderers::default(),
+            query_draft: CustomQuery::default(),
+            preset_name: String::new(),
+            alerts: AlertState::default(),
+            timeline: Arc::new(Mutex::new(Timeline::default())),
+            commit_report: Arc::new(Mutex::new(None)),
+            diagnostics_requested: false,
+            visible_entities: Arc::new(Mutex::new(Vec::new())),
+            lazy: LazyLoader::default(),
+            focus_details: false,
+            insights: None,
+            latency: Arc::default(),
+            metadata: Arc::default(),
+            command_palette: CommandPalette::default(),
+            applied_compact: None,
+            applied_appearance: None,
+            font_fallback: None,
+            updates: UpdateChecker::default(),
+            applied_power_saver: None,
+            polling_paused: false,
+            window_geometry: WindowGeometry::default(),
+            geometry_restored: false,
         }
     }
 }
 
 impl TemplateApp {
     /// Called once before the first frame.
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        setup_custom_fonts(&cc.egui_ctx);
-        // This is also where you can customize the look and feel of egui using
-        // `cc.egui_ctx.set_visuals` and `cc.egui_ctx.set_fonts`.
+    pub fn new(cc: &eframe::CreationContext<'_>, demo: Option<SocketAddr>) -> Self {
+        if let Some(addr) = demo {
+            return Self::demo(addr);
+        }
 
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
         if let Some(storage) = cc.storage {
-            return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+            let mut app: Self = eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+            // The project may have changed since, e.g. pulled from version control.
+            match app.config.project.clone() {
+                Some(dir) => app.load_project(dir),
+                None => app.reload_aliases(),
+            }
+            return app;
         }
 
         Default::default()
     }
 
+    /// Connected to the mock server at `addr`, see [`crate::demo`]. Demo sessions start
+    /// from defaults and are never saved, so they leave the user's own setup alone.
+    pub fn demo(addr: SocketAddr) -> Self {
+        let mut app = Self {
+            demo: true,
+            onboarding_complete: true,
+            ..Default::default()
+        };
+        app.config.host = addr.ip().to_string();
+        app.config.port = addr.port();
+        app.config.live_mode = true;
+        app.fetch_list();
+        app
+    }
+
+    fn reload_aliases(&mut self) {
+        let Some(path) = &self.config.aliases_file else {
+            self.aliases = Aliases::default();
+            return;
+        };
+        match Aliases::load(path) {
+            Ok(aliases) => self.aliases = aliases,
+            Err(e) => {
+                *self.error_info.lock().unwrap() = Some(
+                    InspectorError::Local(e.to_string())
+                        .context(format!("Failed to load {}", path.display())),
+                );
+            }
+        }
+    }
+
+    fn pick_aliases_file(&mut self) {
+        let Some(path) = files::FileDialog::new()
+            .add_filter("TOML", &["toml"])
+            .pick_file()
+        else {
+            return;
+        };
+        self.config.aliases_file = Some(path);
+        self.reload_aliases();
+    }
+
+    /// Copies of component values with the values hidden by the redaction rules masked.
+    fn redacted(
+        &self,
+        components: &HashMap<String, serde_json::Value>,
+    ) -> HashMap<String, serde_json::Value> {
+        components
+            .iter()
+            .map(|(key, value)| {
+                let value = self.config.redaction.redact(key, value).into_owned();
+                (key.clone(), value)
+            })
+            .collect()
+    }
+
+    /// Entity label, falling back to the project alias of a marker component for
+    /// entities without a `Name`.
+    fn entity_label(&self, entity: &Entity, row: &BrpQueryRow) -> String {
+        let paths = self.bevy_version.paths();
+        let row = &*self.config.redaction.redact_row(row);
+        if !self.config.label_template.trim().is_empty() {
+            return render_label_template(&self.config.label_template, entity, row, paths);
+        }
+        match self.aliases.entity_label(row) {
+            Some(alias) if !row.components.contains_key(paths.name) => {
+                format!("{entity}: {alias}")
+            }
+            _ => entity_label(entity, row, paths),
+        }
+    }
+
+    fn scheme(&self) -> &'static str {
+        match (self.config.websocket, self.config.secure) {
+            (false, false) => "http",
+            (false, true) => "https",
+            (true, false) => "ws",
+            (true, true) => "wss",
+        }
+    }
+
     fn get_url(&self) -> String {
-        let host_part = format!("{}:{}", DEFAULT_ADDR, DEFAULT_PORT);
-        let url = format!("http://{}/", host_part);
+        let host_part = format!("{}:{}", self.config.host, self.config.port);
+        let url = format!("{}://{}/", self.scheme(), host_part);
         url
     }
 
-    fn fetch_list(&self) {
-        let download_store = self.download.clone();
-        let error_info = self.error_info.clone();
-        let query_param = self.query_list.clone();
-        *download_store.lock().unwrap() = Download::InProgress;
+    /// Where this tab's requests go, signed with its connection's secret and in the
+    /// encoding agreed on with the game.
+    fn endpoint(&self) -> Endpoint {
+        let endpoint = Endpoint::new(
+            self.get_url(),
+            &self.config.signature_header,
+            &self.config.shared_secret,
+        );
+        match &*self.msgpack_url.lock().unwrap() {
+            Some(url) => endpoint.with_msgpack(url),
+            None => endpoint,
+        }
+    }
+
+    fn connection_status(&self) -> ConnectionStatus {
+        let is_downloading = matches!(&*self.download.lock().unwrap(), Download::InProgress { .. });
+        let error = self
+            .error_info
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|e| match e.hint() {
+                Some(hint) => format!("{e}\n\n{hint}"),
+                None => e.to_string(),
+            });
+        let types = self
+            .query_list
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|query| query.data.option.len());
+        match (is_downloading, types, error) {
+            (true, _, _) => ConnectionStatus::Testing,
+            (false, Some(types), _) => ConnectionStatus::Connected(types),
+            (false, None, Some(error)) => ConnectionStatus::Failed(error),
+            (false, None, None) => ConnectionStatus::Untested,
+        }
+    }
 
-        let request = helper::make_empty_request(BRP_LIST_METHOD, self.get_url());
-        ehttp::fetch(request, move |response| {
-            *download_store.lock().unwrap() = Download::Done;
-            let Ok(response) = response else {
-                *error_info.lock().unwrap() = Some(format!("{:#?}", &response));
-                // egui_ctx.request_repaint();
-                return;
-            };
-            if !response.ok {
-                *error_info.lock().unwrap() = Some(format!("{:#?}", &response));
-                return;
+    fn draw_onboarding(&mut self, ui: &mut egui::Ui) {
+        let status = self.connection_status();
+        match draw_onboarding(ui, &mut self.config, status) {
+            OnboardingAction::None => {}
+            OnboardingAction::TestConnection => {
+                *self.query_list.lock().unwrap() = None;
+                *self.error_info.lock().unwrap() = None;
+                self.discovery_requested = false;
+                self.fetch_list();
             }
-            let Ok(type_list) = helper::parse(&response) else {
-                *error_info.lock().unwrap() = Some(format!("{:#?}", &response));
-                return;
-            };
+            OnboardingAction::FindLocalGames => self.find_local_games(ui.ctx()),
+            OnboardingAction::Finish => {
+                self.onboarding_complete = true;
+                if !self.tour_complete {
+                    self.tour.start();
+                }
+            }
+        }
+    }
 
-            *query_param.lock().unwrap() = Some(BrpQueryParams {
-                data: BrpQuery {
-                    components: vec![],
-                    option: type_list,
-                    has: vec![],
-                },
-                filter: BrpQueryFilter::default(),
-            });
-            *error_info.lock().unwrap() = None;
-        });
+    fn export_config(&self) {
+        let Some(path) = files::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .set_file_name("inspector_settings.json")
+            .save_file()
+        else {
+            return;
+        };
+        if let Err(e) = self.config.export(&path) {
+            *self.error_info.lock().unwrap() =
+                Some(InspectorError::Local(e.to_string()).context("Failed to export settings"));
+        }
     }
 
-    fn draw_entity(
-        &self,
-        ui: &mut egui::Ui,
-        entity: &Entity,
-        components: &HashMap<Entity, BrpQueryRow>,
-    ) -> ActionToDo {
-        let mut action = ActionToDo::None;
-        let Some(item) = components.get(entity) else {
-            return action;
+    fn open_project(&mut self) {
+        let Some(dir) = files::FileDialog::new().pick_folder() else {
+            return;
         };
-        let is_empty = item.components.len() == 0;
-        if self.skip_empty_entities && is_empty {
-            return action;
-        }
-        let mut id = entity.to_string();
-        if let Some(name) = item.components.get("bevy_core::name::Name") {
-            let name = name
-                .as_object()
-                .map_or("NONE", |f| f.get("name").unwrap().as_str().unwrap());
-            id += ": ";
-            id += name;
+        self.load_project(dir);
+    }
+
+    /// Takes over the settings of the project in `dir`. A directory without project
+    /// settings becomes a project with the current ones.
+    fn load_project(&mut self, dir: PathBuf) {
+        match ProjectSettings::load(&dir) {
+            Ok(Some(settings)) => {
+                settings.apply(&mut self.config, &dir);
+                self.reload_aliases();
+                // The project's profiles may point at another game.
+                self.reset_connection();
+            }
+            Ok(None) => {
+                self.config.project = Some(dir);
+                self.save_project();
+            }
+            Err(e) => {
+                *self.error_info.lock().unwrap() = Some(
+                    InspectorError::Local(e.to_string())
+                        .context(format!("Failed to open project {}", dir.display())),
+                );
+            }
+        }
+    }
+
+    fn save_project(&self) {
+        let Some(dir) = &self.config.project else {
+            return;
         };
-        egui::CollapsingHeader::new(RichText::new(id).strong())
-            .default_open(false)
-            .show(ui, |ui| {
-                if ui.button("Remove entity").clicked() {
-                    action = ActionToDo::Remove;
-                }
-                if let Some(children) = item
-                    .components
-                    .get("bevy_hierarchy::components::children::Children")
-                {
-                    let Some(array) = children.as_array() else {
-                        return;
-                    };
-                    ui.heading("Children");
-                    ui.separator();
+        if let Err(e) = ProjectSettings::from_config(&self.config).save(dir) {
+            *self.error_info.lock().unwrap() =
+                Some(InspectorError::Local(e.to_string()).context("Failed to save project"));
+        }
+    }
 
-                    let array: Vec<u64> = array.into_iter().map(|v| v.as_u64()).flatten().collect();
-                    for el in array.iter() {
-                        self.draw_entity(ui, &Entity::from_bits(*el), components);
-                    }
-                }
+    fn export_scene(&self, root: Option<Entity>, content: &HashMap<Entity, BrpQueryRow>) {
+        let Some(path) = files::FileDialog::new()
+            .add_filter("Bevy scene", &["ron"])
+            .add_filter("JSON", &["json"])
+            .set_file_name("snapshot.scn.ron")
+            .save_file()
+        else {
+            return;
+        };
+        let entities = scene_entities(content, self.bevy_version.paths(), root);
+        if let Err(e) = self.write_redacted_scene(&path, entities) {
+            *self.error_info.lock().unwrap() =
+                Some(InspectorError::Local(e.to_string()).context("Failed to export scene"));
+        }
+    }
 
-                ui.heading("Components");
-                for (key, field) in item.components.iter() {
-                    if key.eq("bevy_hierarchy::components::parent::Parent") {
-                        continue;
-                    }
-                    if key.eq("bevy_hierarchy::components::children::Children") {
-                        continue;
-                    }
+    /// Writes `entities` as a scene with what the redaction rules hide masked.
+    fn write_redacted_scene(
+        &self,
+        path: &std::path::Path,
+        entities: BTreeMap<Entity, &BrpQueryRow>,
+    ) -> anyhow::Result<()> {
+        let redacted: Vec<(Entity, Cow<BrpQueryRow>)> = entities
+            .into_iter()
+            .map(|(entity, row)| (entity, self.config.redaction.redact_row(row)))
+            .collect();
+        let entities = redacted
+            .iter()
+            .map(|(entity, row)| (*entity, &**row))
+            .collect();
+        write_scene(path, &entities)
+    }
 
-                    let Ok(json) = serde_json::to_string_pretty(field) else {
-                        continue;
-                    };
-                    if json.eq("{}") {
-                        ui.label(RichText::new(key).strong());
-                        continue;
-                    }
-                    egui::CollapsingHeader::new(key)
-                        .default_open(false)
-                        .show(ui, |ui| {
-                            ui.label(json);
-                        });
-                }
-            });
-        ui.separator();
-        return action;
+    fn export_graph(&self, root: Option<Entity>, content: &HashMap<Entity, BrpQueryRow>) {
+        let Some(path) = files::FileDialog::new()
+            .add_filter("Graphviz", &["dot", "gv"])
+            .add_filter("GraphML", &["graphml"])
+            .set_file_name("entities.dot")
+            .save_file()
+        else {
+            return;
+        };
+        let paths = self.bevy_version.paths();
+        let entities = scene_entities(content, paths, root);
+        let graph = EntityGraph::new(&entities, paths, |entity, row| {
+            self.entity_label(entity, row)
+        });
+        if let Err(e) = write_graph(&path, &graph) {
+            *self.error_info.lock().unwrap() =
+                Some(InspectorError::Local(e.to_string()).context("Failed to export graph"));
+        }
     }
-}
 
-impl eframe::App for TemplateApp {
-    fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
-        egui::Rgba::TRANSPARENT.to_array() // Make sure we don't paint anything behind the rounded corners
+    fn export_type_registry(&self) {
+        let types: Vec<String> = self
+            .query_list
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|query| query.data.option.iter().cloned())
+            .collect();
+        let Some(path) = files::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .set_file_name("type_registry.json")
+            .save_file()
+        else {
+            return;
+        };
+        if let Err(e) = self.schemas.lock().unwrap().export(&path, &types) {
+            *self.error_info.lock().unwrap() = Some(
+                InspectorError::Local(e.to_string()).context("Failed to export the type registry"),
+            );
+        }
     }
-    /// Called by the frame work to save state before shutdown.
-    fn save(&mut self, storage: &mut dyn eframe::Storage) {
-        eframe::set_value(storage, eframe::APP_KEY, self);
+
+    fn export_trace(&self) {
+        let Some(path) = files::FileDialog::new()
+            .add_filter("HTTP Archive", &["har"])
+            .set_file_name("inspector_trace.har")
+            .save_file()
+        else {
+            return;
+        };
+        if let Err(e) = trace::export_har(&path) {
+            *self.error_info.lock().unwrap() =
+                Some(InspectorError::Local(e.to_string()).context("Failed to export trace"));
+        }
     }
 
-    /// Called each time the UI needs repainting, which may be many times per second.
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        custom_window_frame(ctx, "Bevy Inspector", |ui| {
-            // Put your widgets into a `SidePanel`, `TopBottomPanel`, `CentralPanel`, `Window` or `Area`.
-            // For inspiration and more examples, go to https://emilk.github.io/egui
+    fn export_rpc_log(&self, seqs: &[u64]) {
+        let Some(path) = files::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .set_file_name("rpc_log.json")
+            .save_file()
+        else {
+            return;
+        };
+        if let Err(e) = trace::export_json(&path, seqs) {
+            *self.error_info.lock().unwrap() =
+                Some(InspectorError::Local(e.to_string()).context("Failed to export the log"));
+        }
+    }
 
-            // egui::TopBottomPanel::top("top_panel")
-            //     .frame(egui::Frame::canvas(&ctx.style()))
-            //     .show(ctx, |ui| {
-            // ui.add_space(15.0);
-            // ui.horizontal(|ui| {
-            //     ui.add_space(8.0);
+    /// Asks for a screenshot of the inspector, the bundle is written once it arrives.
+    fn request_diagnostics(&mut self, ctx: &egui::Context) {
+        self.diagnostics_requested = true;
+        ctx.send_viewport_cmd(ViewportCommand::Screenshot);
+    }
+
+    /// Writes the diagnostics bundle after the screenshot for it was taken.
+    fn export_diagnostics(&mut self, ctx: &egui::Context) {
+        if !self.diagnostics_requested {
+            return;
+        }
+        let screenshot = ctx.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                egui::Event::Screenshot { image, .. } => Some(image.clone()),
+                _ => None,
+            })
+        });
+        let Some(screenshot) = screenshot else {
+            return;
+        };
+        self.diagnostics_requested = false;
+        let bundle = DiagnosticsBundle::collect(
+            &self.components.lock().unwrap(),
+            &self.history.lock().unwrap(),
+            &self.config,
+            Some(screenshot),
+        );
+        let Some(path) = files::FileDialog::new()
+            .add_filter("Zip archive", &["zip"])
+            .set_file_name("inspector_diagnostics.zip")
+            .save_file()
+        else {
+            return;
+        };
+        if let Err(e) = bundle.write(&path) {
+            *self.error_info.lock().unwrap() =
+                Some(InspectorError::Local(e.to_string()).context("Failed to export diagnostics"));
+        }
+    }
+
+    fn import_config(&mut self) {
+        let Some(path) = files::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .pick_file()
+        else {
+            return;
+        };
+        match AppConfig::import(&path) {
+            Ok(config) => self.config = config,
+            Err(e) => {
+                *self.error_info.lock().unwrap() =
+                    Some(InspectorError::Local(e.to_string()).context("Failed to import settings"));
+            }
+        }
+    }
+
+    /// Connects the active tab to another profile, or shows the profile's tab when it's
+    /// open already.
+    fn switch_profile(&mut self, name: &str) {
+        if name == self.config.profile {
+            return;
+        }
+        if self.config.open_tabs.iter().any(|tab| tab == name) {
+            self.activate_tab(name);
+            return;
+        }
+        let current = &self.config.profile;
+        if let Some(tab) = self.config.open_tabs.iter_mut().find(|tab| *tab == current) {
+            *tab = name.to_owned();
+        }
+        self.config.switch_profile(name);
+        self.reset_connection();
+    }
+
+    /// Drops everything cached from the previous connection.
+    fn reset_connection(&mut self) {
+        *self.download.lock().unwrap() = Download::None;
+        *self.query_list.lock().unwrap() = None;
+        *self.error_info.lock().unwrap() = None;
+        self.components.lock().unwrap().clear();
+        self.timeline.lock().unwrap().clear();
+        self.fetched_at.lock().unwrap().clear();
+        self.despawned.lock().unwrap().clear();
+        self.lazy.loaded.lock().unwrap().clear();
+        *self.lazy.narrowed.lock().unwrap() = None;
+        self.lazy.allow_oversized = false;
+        self.performance.clear();
+        *self.metadata.lock().unwrap() = None;
+        self.rejected_types.lock().unwrap().clear();
+        self.migration_checked = false;
+        self.edits.lock().unwrap().clear();
+        self.undo.lock().unwrap().clear();
+        *self.change_ticks.lock().unwrap() = None;
+        self.drag_before = None;
+        self.multi_selected.clear();
+        self.panels.lock().unwrap().clear();
+        self.discovery_requested = false;
+        self.connect_requested = false;
+        self.startup_pending = true;
+        throttle::reset();
+        *self.capabilities.lock().unwrap() = Capabilities::default();
+        *self.msgpack_url.lock().unwrap() = None;
+    }
+
+    /// Drops the current connection state so the next frame connects again.
+    fn reconnect(&mut self) {
+        *self.query_list.lock().unwrap() = None;
+        *self.error_info.lock().unwrap() = None;
+        throttle::reset();
+        self.discovery_requested = false;
+        self.connect_requested = true;
+    }
+
+    /// Time until the next automatic retry is due, growing with each failed one so a
+    /// game that's gone for a while isn't polled every other second.
+    fn retry_delay(&self) -> Duration {
+        let delay = RETRY_INTERVAL.saturating_mul(1 << self.retry_attempts.min(4));
+        // A server asking for a longer break gets it.
+        let throttled = self.last_retry.map_or(Duration::ZERO, |last| {
+            last.elapsed() + throttle::remaining().unwrap_or_default()
+        });
+        delay.min(MAX_RETRY_INTERVAL).max(throttled)
+    }
+
+    /// Fetches again what failed: the world once connected, the type list before that.
+    fn retry(&mut self, ctx: &egui::Context) {
+        self.last_retry = Some(Instant::now());
+        if self.query_list.lock().unwrap().is_some() {
+            self.fetch_components(ctx);
+        } else {
+            self.fetch_list();
+        }
+    }
+
+    /// Tries reaching the game again with backoff, e.g. while it restarts. Only unreachable
+    /// games are retried, other errors would just fail the same way.
+    fn retry_unreachable(&mut self, ctx: &egui::Context) {
+        let Some(retryable) = self
+            .error_info
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|e| e.is_retryable())
+        else {
+            self.retry_attempts = 0;
+            return;
+        };
+        let connecting = self.config.auto_connect || self.connect_requested;
+        if !retryable || (self.query_list.lock().unwrap().is_none() && !connecting) {
+            return;
+        }
+        let delay = self.retry_delay();
+        let idle = !matches!(&*self.download.lock().unwrap(), Download::InProgress { .. });
+        let due = self.last_retry.is_none_or(|last| last.elapsed() >= delay);
+        if idle && due {
+            self.retry_attempts += 1;
+            self.retry(ctx);
+        }
+        ctx.request_repaint_after(delay);
+    }
+
+    /// The current error: what went wrong in short, a hint at the cause, what the game
+    /// answered and a way to try again.
+    fn draw_error(&mut self, ui: &mut egui::Ui, error: &InspectorError) {
+        ui.heading(error.title());
+        ui.label(
+            RichText::new(error.to_string())
+                .color(status_colors(ui.ctx()).error)
+                .monospace()
+                .line_height(Some(25.0))
+                .size(20.0),
+        );
+        if let Some(hint) = error.hint() {
+            ui.label(RichText::new(hint).weak());
+        }
+        if let Some(data) = error.data() {
+            egui::CollapsingHeader::new("Error data")
+                .id_salt("error_data")
+                .show(ui, |ui| {
+                    ui.add(egui::Label::new(RichText::new(data).monospace()).selectable(true));
+                });
+        }
+        ui.add_space(8.0);
+        ui.horizontal(|ui| {
+            if ui.button("🔄 Retry now").clicked() {
+                self.retry(ui.ctx());
+            }
+            if error.is_retryable() {
+                let wait = self
+                    .last_retry
+                    .map(|last| self.retry_delay().saturating_sub(last.elapsed()))
+                    .unwrap_or_default();
+                ui.weak(format!(
+                    "Retrying in {}s (attempt {})",
+                    wait.as_secs() + 1,
+                    self.retry_attempts + 1
+                ));
+            }
+        });
+        let raw = self.error_raw.lock().unwrap();
+        let Some((_, raw)) = raw.as_ref().filter(|(raw_error, _)| raw_error == error) else {
+            return;
+        };
+        egui::CollapsingHeader::new("Raw response")
+            .id_salt("error_raw")
+            .show(ui, |ui| {
+                if ui.button("📋 Copy").clicked() {
+                    ui.ctx().copy_text(raw.clone());
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        ui.add(
+                            egui::Label::new(RichText::new(raw.as_str()).monospace())
+                                .selectable(true),
+                        );
+                    });
+            });
+    }
+
+    fn find_local_games(&mut self, ctx: &egui::Context) {
+        if cfg!(target_arch = "wasm32") {
+            *self.error_info.lock().unwrap() = Some(InspectorError::Local(
+                "Scanning for local games needs the native inspector".to_owned(),
+            ));
+            return;
+        }
+        self.local_scan.open = true;
+        let mut ports: Vec<u16> = self.config.profiles.iter().map(|p| p.port).collect();
+        ports.push(self.config.port);
+        self.local_scan.start(ctx, ports, self.config.discover_lan);
+    }
+
+    fn supports(&self, method: &str) -> bool {
+        self.capabilities.lock().unwrap().supports(method)
+    }
+
+    /// The refresh policy of `target`. Live mode and watching the selected entity keep
+    /// their own settings, which turn the world fetch and watches on and off.
+    fn refresh_policy(&self, target: RefreshTarget) -> RefreshPolicy {
+        let mut policy = self.config.refresh.get(target);
+        match target {
+            RefreshTarget::Tree => {
+                policy.enabled = self.config.live_mode;
+                policy.interval_ms = self.config.live_interval_ms;
+            }
+            RefreshTarget::Watches => policy.enabled = self.config.watch_selected,
+            _ => {}
+        }
+        self.config
+            .power_saver
+            .limit(&mut policy, self.polling_paused);
+        policy
+    }
+
+    /// Resources polled for the status bar, the open dashboard and the alerts.
+    fn polled_resources(&self) -> Vec<String> {
+        let mut resources = self.config.watched_resources.clone();
+        let dashboard = self.config.dashboards.get(self.config.active_dashboard);
+        for resource in dashboard.into_iter().flat_map(Dashboard::resources) {
+            if self.show_dashboard && !resources.contains(resource) {
+                resources.push(resource.clone());
+            }
+        }
+        for resource in self
+            .config
+            .alerts
+            .iter()
+            .filter_map(|a| a.source.resource())
+        {
+            if !resources.contains(resource) {
+                resources.push(resource.clone());
+            }
+        }
+        resources
+    }
+
+    /// Estimated requests per second of each refresh running now and bytes per second
+    /// brought in by the world fetch. A poll never overlaps the one before, so none runs
+    /// faster than the round trip.
+    fn refresh_cost(&self) -> (Vec<(&'static str, f64)>, f64) {
+        if self.polling_paused || self.query_list.lock().unwrap().is_none() {
+            return (Vec::new(), 0.0);
+        }
+        let round_trip = self.latency.lock().unwrap().median();
+        let rate = |policy: RefreshPolicy| {
+            let interval = policy.interval().max(round_trip.unwrap_or_default());
+            1.0 / interval.as_secs_f64().max(0.001)
+        };
+        let mut costs = Vec::new();
+        let tree = self.refresh_policy(RefreshTarget::Tree);
+        let tree_rate = tree.enabled.then(|| rate(tree));
+        if let Some(tree_rate) = tree_rate {
+            costs.push((RefreshTarget::Tree.label(), tree_rate));
+        }
+        let running = [
+            (
+                RefreshTarget::Resources,
+                !self.polled_resources().is_empty(),
+            ),
+            (RefreshTarget::Diagnostics, self.show_performance),
+            (RefreshTarget::Rendering, self.show_render_stats),
+        ];
+        for (target, running) in running {
+            let policy = self.refresh_policy(target);
+            if running && policy.enabled {
+                costs.push((target.label(), rate(policy)));
+            }
+        }
+        costs.push(("Latency ping", 1.0 / PING_INTERVAL.as_secs_f64()));
+        let world = self.timeline.lock().unwrap().latest_bytes().unwrap_or(0);
+        (costs, tree_rate.unwrap_or(0.0) * world as f64)
+    }
+
+    /// Toggles and intervals of every refresh target, in the settings menu.
+    fn draw_refresh_settings(&mut self, ui: &mut egui::Ui) {
+        egui::Grid::new("refresh_policies")
+            .num_columns(3)
+            .show(ui, |ui| {
+                for target in RefreshTarget::ALL {
+                    let policy = self.config.refresh.get_mut(target);
+                    let (enabled, interval_ms) = match target {
+                        RefreshTarget::Tree => (
+                            &mut self.config.live_mode,
+                            &mut self.config.live_interval_ms,
+                        ),
+                        RefreshTarget::Watches => {
+                            (&mut self.config.watch_selected, &mut policy.interval_ms)
+                        }
+                        _ => (&mut policy.enabled, &mut policy.interval_ms),
+                    };
+                    ui.checkbox(enabled, target.label());
+                    ui.add_enabled(
+                        *enabled,
+                        egui::DragValue::new(interval_ms)
+                            .range(0..=60_000)
+                            .speed(10.0)
+                            .suffix(" ms"),
+                    )
+                    .on_hover_text("Time between refreshes, 0 for as often as possible");
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn is_detached(&self, key: &str) -> bool {
+        self.config.detached_panels.iter().any(|k| k == key)
+    }
+
+    fn toggle_detached(&mut self, key: &str) {
+        let detached = &mut self.config.detached_panels;
+        match detached.iter().position(|k| k == key) {
+            Some(index) => {
+                detached.remove(index);
+            }
+            None => detached.push(key.to_owned()),
+        }
+    }
+
+    /// Offers to spawn JSON pasted while no text field has focus, or to insert it into the
+    /// selected entity, see [`parse_entities`].
+    fn handle_paste(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() || !self.onboarding_complete {
+            return;
+        }
+        let text = ctx.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                egui::Event::Paste(text) => Some(text.trim().to_owned()),
+                _ => None,
+            })
+        });
+        // Anything that doesn't even look like JSON wasn't meant for the inspector.
+        if let Some(text) = text.filter(|text| text.starts_with(['{', '['])) {
+            self.pasted = Some(parse_entities(&text, self.bevy_version.paths()));
+        }
+    }
+
+    /// Runs the commands whose shortcuts were pressed, and moves through the entity tree
+    /// with the arrow keys, see [`a11y::SHORTCUTS`].
+    fn handle_shortcuts(&mut self, ctx: &egui::Context) {
+        if ctx.wants_keyboard_input() {
+            return;
+        }
+        if let Some(command) = ctx.input_mut(|i| self.config.shortcuts.triggered(i)) {
+            self.run_command(ctx, command);
+        }
+        if tree_has_keyboard(ctx) {
+            let step = ctx.input(|i| {
+                i.key_pressed(egui::Key::ArrowDown) as isize
+                    - i.key_pressed(egui::Key::ArrowUp) as isize
+            });
+            if step != 0 {
+                let visible = self.visible_entities.lock().unwrap();
+                let next = match self
+                    .selected
+                    .and_then(|s| visible.iter().position(|e| *e == s))
+                {
+                    Some(index) => index.checked_add_signed(step).and_then(|i| visible.get(i)),
+                    None => visible.first(),
+                };
+                if let Some(next) = next.copied() {
+                    self.selected = Some(next);
+                    self.scroll_to_selected = true;
+                }
+            }
+        }
+    }
+
+    /// Carries out a message sent by a view, `content` being the rows it was drawn from.
+    fn dispatch(&mut self, ctx: &egui::Context, msg: Msg, content: &HashMap<Entity, BrpQueryRow>) {
+        let targets = match &msg {
+            Msg::Remove(entity) => vec![*entity],
+            Msg::RemoveComponent(entity, _) => vec![*entity],
+            Msg::DestroyMany(entities) | Msg::RemoveFromMany(entities, _) => entities.clone(),
+            _ => Vec::new(),
+        };
+        let protected = targets.iter().find_map(|entity| {
+            let row = content.get(entity)?;
+            Some((
+                *entity,
+                protected_by(row, &self.config.protected_components)?,
+            ))
+        });
+        if let Some((entity, component)) = protected {
+            self.pending_confirmation = Some(PendingConfirmation {
+                msg,
+                reason: format!("{entity} has {component}"),
+                protected: true,
+            });
+            return;
+        }
+        let destroys = matches!(msg, Msg::Remove(_) | Msg::DestroyMany(_));
+        if destroys && self.config.confirm_destroy && !self.config.read_only {
+            self.pending_confirmation = Some(PendingConfirmation {
+                reason: self.destroy_question(&targets, content),
+                msg,
+                protected: false,
+            });
+            return;
+        }
+        self.apply(ctx, msg, content);
+    }
+
+    /// Dispatches a message that doesn't come with the rows of a view, e.g. from the
+    /// toolbar, against the fetched ones.
+    pub(super) fn send(&mut self, ctx: &egui::Context, msg: Msg) {
+        let components = self.components.clone();
+        let content = components.lock().unwrap();
+        self.dispatch(ctx, msg, &content);
+    }
+
+    /// E.g. `Destroy Player and its 4 descendants?`.
+    fn destroy_question(
+        &self,
+        targets: &[Entity],
+        content: &HashMap<Entity, BrpQueryRow>,
+    ) -> String {
+        let paths = self.bevy_version.paths();
+        let mut seen: std::collections::HashSet<Entity> = targets.iter().copied().collect();
+        let mut stack: Vec<Entity> = targets.to_vec();
+        let mut descendants = 0;
+        while let Some(entity) = stack.pop() {
+            let Some(row) = content.get(&entity) else {
+                continue;
+            };
+            for child in children(row, paths) {
+                if seen.insert(child) {
+                    descendants += 1;
+                    stack.push(child);
+                }
+            }
+        }
+        let what = match targets {
+            [entity] => match content.get(entity) {
+                Some(row) => self.entity_label(entity, row),
+                None => entity.to_string(),
+            },
+            _ => format!("{} entities", targets.len()),
+        };
+        let whose = if targets.len() == 1 { "its" } else { "their" };
+        match descendants {
+            0 => format!("Destroy {what}?"),
+            1 => format!("Destroy {what} and {whose} descendant?"),
+            n => format!("Destroy {what} and {whose} {n} descendants?"),
+        }
+    }
+
+    /// Carries out `msg` without the protection checks of [`Self::dispatch`].
+    fn apply(&mut self, ctx: &egui::Context, msg: Msg, content: &HashMap<Entity, BrpQueryRow>) {
+        let paths = self.bevy_version.paths();
+        match msg {
+            Msg::None => {}
+            Msg::Select(entity) => {
+                self.selected = Some(entity);
+                self.multi_selected.clear();
+            }
+            Msg::ToggleMultiSelect(entity) => {
+                if self.multi_selected.is_empty() {
+                    self.multi_selected.extend(self.selected);
+                }
+                if !self.multi_selected.remove(&entity) {
+                    self.multi_selected.insert(entity);
+                }
+                self.selected = Some(entity);
+            }
+            Msg::DestroyMany(entities) => {
+                let selected: HashSet<Entity> = entities.iter().copied().collect();
+                for entity in entities {
+                    self.multi_selected.remove(&entity);
+                    // Destroyed and kept along with an ancestor that is destroyed too.
+                    let mut ancestor = content.get(&entity).and_then(|row| parent_of(row, paths));
+                    let mut below_selected = false;
+                    while let Some(parent) = ancestor.filter(|parent| *parent != entity) {
+                        if selected.contains(&parent) {
+                            below_selected = true;
+                            break;
+                        }
+                        ancestor = content.get(&parent).and_then(|row| parent_of(row, paths));
+                    }
+                    if !below_selected {
+                        self.apply(ctx, Msg::Remove(entity), content);
+                    }
+                }
+            }
+            Msg::RemoveFromMany(entities, component) => {
+                for entity in entities {
+                    let has = content
+                        .get(&entity)
+                        .is_some_and(|row| row.components.contains_key(&component));
+                    if has {
+                        let msg = Msg::RemoveComponent(entity, component.clone());
+                        self.apply(ctx, msg, content);
+                    }
+                }
+            }
+            Msg::FindReferences(entity) => {
+                self.references = Some((
+                    format!("References to {entity}"),
+                    find_references(entity, content),
+                ));
+            }
+            Msg::FindAssetUsers(key) => {
+                let users = find_asset_users(&key, content);
+                self.references = Some((format!("Users of {}", key.label()), users));
+            }
+            Msg::ApplyEdit(entity, component) => {
+                self.apply_edit(ctx, entity, component);
+            }
+            Msg::ResolveConflict(entity, component, resolution) => {
+                self.resolve_conflict(ctx, entity, component, resolution);
+            }
+            Msg::Insert(entity, component, value) => {
+                let before = match self.drag_before.take() {
+                    Some((dragged, key, before)) if dragged == entity && key == component => before,
+                    _ => current_value(content, entity, &component),
+                };
+                self.last_action = Some(LastAction::Insert(component.clone(), value.clone()));
+                self.insert_recorded(ctx, entity, component, before, value);
+            }
+            Msg::LiveInsert(entity, component, value) => {
+                let dragging = self
+                    .drag_before
+                    .as_ref()
+                    .is_some_and(|(dragged, key, _)| *dragged == entity && *key == component);
+                if !dragging {
+                    let before = current_value(content, entity, &component);
+                    self.drag_before = Some((entity, component.clone(), before));
+                }
+                self.send_value(ctx, entity, component, value);
+            }
+            Msg::AddComponent(entity, component, value) => {
+                *self
+                    .config
+                    .component_uses
+                    .entry(component.clone())
+                    .or_default() += 1;
+                let before = current_value(content, entity, &component);
+                self.last_action = Some(LastAction::Insert(component.clone(), value.clone()));
+                self.insert_recorded(ctx, entity, component, before, value);
+            }
+            Msg::RemoveComponent(entity, component) => {
+                if let Some(value) = current_value(content, entity, &component) {
+                    self.record(Mutation::Remove {
+                        entity,
+                        component: component.clone(),
+                        value,
+                    });
+                }
+                self.last_action = Some(LastAction::Remove(component.clone()));
+                self.remove_component(ctx, entity, component);
+            }
+            Msg::Reparent(entity, parent) => {
+                self.record(Mutation::Reparent {
+                    entity,
+                    before: content.get(&entity).and_then(|row| parent_of(row, paths)),
+                    after: parent,
+                });
+                self.reparent(ctx, entity, parent);
+            }
+            Msg::Rename(entity, name) => self.rename(ctx, entity, name),
+            Msg::ExportScene(root) => self.export_scene(root, content),
+            Msg::ExportGraph(root) => self.export_graph(root, content),
+            Msg::CopyComponents(entity, component) => {
+                let Some(row) = content.get(&entity) else {
+                    return;
+                };
+                let paths = self.bevy_version.paths();
+                let text = match component {
+                    Some(component) => {
+                        let Some(value) = row.components.get(&component) else {
+                            return;
+                        };
+                        let copied: HashMap<_, _> = [(component, value.clone())].into();
+                        let text = serde_json::to_string_pretty(&self.redacted(&copied));
+                        self.copied_components = Some(copied);
+                        text
+                    }
+                    None => {
+                        let params = spawn_params(row, paths);
+                        let text = serde_json::to_string_pretty(&BrpSpawnParams {
+                            components: self.redacted(&params.components),
+                        });
+                        self.copied_components = Some(params.components);
+                        text
+                    }
+                };
+                ctx.copy_text(text.unwrap_or_default());
+            }
+            Msg::PasteComponents(entity) => {
+                if let Some(copied) = self.copied_components.clone() {
+                    self.insert_components(ctx, entity, copied);
+                }
+            }
+            Msg::ToggleFavorite(component) => {
+                let favorites = &mut self.config.favorite_components;
+                match favorites.iter().position(|f| *f == component) {
+                    Some(index) => {
+                        favorites.remove(index);
+                    }
+                    None => favorites.push(component),
+                }
+            }
+            Msg::TogglePin(component) => {
+                let pinned = &mut self.config.pinned_components;
+                match pinned.iter().position(|p| *p == component) {
+                    Some(index) => {
+                        pinned.remove(index);
+                    }
+                    None => pinned.push(component),
+                }
+            }
+            Msg::TogglePinEntity(entity) => {
+                let paths = self.bevy_version.paths();
+                let pinned = &mut self.config.pinned_entities;
+                let existing = content.get(&entity).and_then(|row| {
+                    pinned
+                        .iter()
+                        .position(|pin| pin.matches(entity, row, paths))
+                });
+                match (existing, content.get(&entity)) {
+                    (Some(index), _) => {
+                        pinned.remove(index);
+                    }
+                    (None, Some(row)) => pinned.push(EntityPin::of(entity, row, paths)),
+                    (None, None) => {}
+                }
+            }
+            Msg::ShowHistory(entity, component) => {
+                self.component_history.show_for(entity, component);
+            }
+            Msg::BookmarkField(entity, component, path) => {
+                let Some(row) = content.get(&entity) else {
+                    return;
+                };
+                let bookmark = FieldBookmark {
+                    entity: EntityPin::of(entity, row, self.bevy_version.paths()),
+                    component,
+                    path,
+                };
+                if !self.config.bookmarks.contains(&bookmark) {
+                    self.config.bookmarks.push(bookmark);
+                }
+                self.show_bookmarks = true;
+            }
+            Msg::Remove(_) if self.config.read_only => {}
+            Msg::Remove(entity) => {
+                // Already on its way when clicked again before the game answered.
+                if !self.destroy(entity) {
+                    return;
+                }
+                if let Some(row) = content.get(&entity) {
+                    let label = self.entity_label(&entity, row);
+                    let params = spawn_params(row, paths);
+                    let descendants = descendant_nodes(entity, content, paths);
+                    self.record(Mutation::Destroy {
+                        entity,
+                        label: label.clone(),
+                        params: params.clone(),
+                        parent: parent_of(row, paths),
+                        descendants: descendants.clone(),
+                    });
+                    self.trash.lock().unwrap().push(TrashedEntity {
+                        entity,
+                        label,
+                        params,
+                        descendants,
+                    });
+                }
+            }
+            Msg::Attach(host, port) => {
+                self.config.host = host;
+                self.config.port = port;
+                self.config.websocket = false;
+                self.local_scan.open = false;
+                self.reconnect();
+            }
+            Msg::SetScheme { websocket, secure } => {
+                self.config.websocket = websocket;
+                self.config.secure = secure;
+            }
+            Msg::SetHost(host) => self.config.host = host,
+            Msg::SetPort(port) => self.config.port = port,
+            Msg::SetFlag(flag, on) => *flag.of(&mut self.config) = on,
+            Msg::SetLiveEveryFrames(frames) => self.config.live_every_frames = frames,
+            Msg::SetLiveInterval(ms) => self.config.live_interval_ms = ms,
+            Msg::SetSort(sort) => self.config.entity_sort = sort,
+            Msg::SetVisibleMargin(margin) => self.config.visible_margin = margin,
+            Msg::SavePreset(name) => {
+                let preset = ViewPreset::capture(name.clone(), &self.config, &self.search);
+                let presets = &mut self.config.view_presets;
+                match presets.iter_mut().find(|p| p.name == name) {
+                    Some(existing) => *existing = preset,
+                    None => presets.push(preset),
+                }
+                self.config.active_preset = name;
+            }
+            Msg::ApplyPreset(index) => {
+                let Some(preset) = self.config.view_presets.get(index).cloned() else {
+                    return;
+                };
+                if preset.apply(&mut self.config, &mut self.search) {
+                    *self.refresh_requested.lock().unwrap() = true;
+                }
+            }
+            Msg::DeletePreset(index) if index < self.config.view_presets.len() => {
+                let preset = self.config.view_presets.remove(index);
+                if preset.name == self.config.active_preset {
+                    self.config.active_preset.clear();
+                }
+            }
+            Msg::DeletePreset(_) => {}
+            Msg::SetDashboards(dashboards, active) => {
+                self.config.dashboards = dashboards;
+                self.config.active_dashboard = active;
+            }
+            Msg::ImportDashboards => self.import_dashboards(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
+
+impl eframe::App for TemplateApp {
+    fn clear_color(&self, _visuals: &egui::Visuals) -> [f32; 4] {
+        egui::Rgba::TRANSPARENT.to_array() // Make sure we don't paint anything behind the rounded corners
+    }
+    /// Called by the frame work to save state before shutdown.
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        if self.demo {
+            return;
+        }
+        eframe::set_value(storage, eframe::APP_KEY, self);
+        self.save_project();
+    }
+
+    /// Called each time the UI needs repainting, which may be many times per second.
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.ui(ctx);
+    }
+}
+
+impl TemplateApp {
+    /// Draws one frame, split from `update` so tests can drive the app without eframe.
+    pub fn ui(&mut self, ctx: &egui::Context) {
+        self.scheduler.set_limits(
+            self.config.batch_max_in_flight,
+            self.config.batch_requests_per_second,
+        );
+        if let Some(wait) = self.scheduler.pump() {
+            ctx.request_repaint_after(wait);
+        }
+        self.export_diagnostics(ctx);
+        set_palette(ctx, self.config.status_palette);
+        set_value_format(ctx, self.config.value_format);
+        dock::begin_frame(ctx, &self.config.docked_panels);
+        if self.geometry_restored {
+            self.window_geometry.capture(ctx);
+        } else {
+            self.geometry_restored = self.window_geometry.restore(ctx);
+        }
+        if self.applied_compact != Some(self.config.compact) {
+            self.applied_compact = Some(self.config.compact);
+            let compact = self.config.compact;
+            ctx.all_styles_mut(|style| {
+                style.spacing = if compact {
+                    compact_spacing()
+                } else {
+                    egui::style::Spacing::default()
+                };
+            });
+        }
+        if self.applied_appearance.as_ref() != Some(&self.config.appearance) {
+            self.config.appearance.apply(
+                ctx,
+                self.applied_appearance.as_ref(),
+                &mut self.font_fallback,
+            );
+            self.applied_appearance = Some(self.config.appearance.clone());
+        }
+        let power_saver = self.config.power_saver.enabled;
+        if self.applied_power_saver != Some(power_saver) {
+            self.applied_power_saver = Some(power_saver);
+            ctx.all_styles_mut(|style| {
+                style.animation_time = if power_saver {
+                    0.0
+                } else {
+                    egui::Style::default().animation_time
+                };
+            });
+        }
+        self.polling_paused = self.config.power_saver.pauses(ctx);
+        self.updates
+            .check_on_startup(ctx, self.config.check_for_updates);
+        if std::mem::take(&mut *self.refresh_requested.lock().unwrap()) {
+            self.fetch_components(ctx);
+        }
+        let follow = self.spawn_to_follow.lock().unwrap().take();
+        if self.config.follow_spawns {
+            self.scroll_to_spawned = follow;
+        }
+        if let Some(query) = &*self.query_list.lock().unwrap() {
+            self.bevy_version = BevyVersion::detect(&query.data.option);
+        }
+        let frame_aligned =
+            self.config.live_every_frames > 0 && self.supports(INSPECTOR_FRAME_METHOD);
+        if self.config.live_mode && frame_aligned {
+            let idle = !matches!(&*self.download.lock().unwrap(), Download::InProgress { .. })
+                && throttle::remaining().is_none()
+                && !self.polling_paused;
+            let data_frame = *self.data_frame.lock().unwrap();
+            let remote_frame = *self.remote_frame.lock().unwrap();
+            let due = match (data_frame, remote_frame) {
+                (Some(data), Some(remote)) => {
+                    remote >= data + u64::from(self.config.live_every_frames)
+                }
+                _ => true,
+            };
+            if idle && due && self.onboarding_complete {
+                self.live_fetch(ctx);
+            } else if idle {
+                // Repaints once the frame is known, which polls again until it's due.
+                self.poll_remote_frame(ctx);
+            }
+        } else if self.config.live_mode {
+            let mut policy = self.refresh_policy(RefreshTarget::Tree);
+            policy.interval_ms = throttle::slowed(policy.interval()).as_millis() as u64;
+            // A fetch still in flight is never overlapped, slow games just poll less often.
+            let idle = !matches!(&*self.download.lock().unwrap(), Download::InProgress { .. })
+                && throttle::remaining().is_none();
+            if idle
+                && self.onboarding_complete
+                && self.refresh.due(ctx, RefreshTarget::Tree, policy)
+            {
+                self.live_fetch(ctx);
+            }
+            if policy.enabled {
+                ctx.request_repaint_after(policy.interval());
+            }
+        }
+        let reachable =
+            self.query_list.lock().unwrap().is_some() && throttle::remaining().is_none();
+        if self.onboarding_complete && reachable && !self.polling_paused {
+            self.ping(ctx);
+            ctx.request_repaint_after(PING_INTERVAL);
+        }
+        if self.onboarding_complete && self.supports(BRP_GET_RESOURCE_METHOD) {
+            let url = self.endpoint();
+            let resources = self.polled_resources();
+            let policy = self.refresh_policy(RefreshTarget::Resources);
+            if !resources.is_empty() && self.refresh.due(ctx, RefreshTarget::Resources, policy) {
+                self.resource_watch.poll(ctx, &url, &resources);
+            }
+        }
+        self.retry_unreachable(ctx);
+        let ticker = self.entity_ticker();
+        custom_window_frame(ctx, "Bevy Inspector", ticker, |ui| {
+            // Put your widgets into a `SidePanel`, `TopBottomPanel`, `CentralPanel`, `Window` or `Area`.
+            // For inspiration and more examples, go to https://emilk.github.io/egui
+
+            // egui::TopBottomPanel::top("top_panel")
+            //     .frame(egui::Frame::canvas(&ctx.style()))
+            //     .show(ctx, |ui| {
+            // ui.add_space(15.0);
+            // ui.horizontal(|ui| {
+            //     ui.add_space(8.0);
             //     ui.label(
             //         RichText::new("Bevy inspector")
             //             .strong()
@@ -226,329 +2050,555 @@ impl eframe::App for TemplateApp {
             //             .color(egui::Color32::from_rgb(230, 102, 1)),
             //     );
             // });
+            self.draw_tabs(ui);
             ui.horizontal(|ui| {
                 let download_store = self.download.clone();
-                let is_downloading =
-                    matches!(&*download_store.lock().unwrap(), Download::InProgress);
+                let is_downloading = matches!(
+                    &*download_store.lock().unwrap(),
+                    Download::InProgress { .. }
+                );
                 let query_param = self.query_list.clone();
                 let has_query = query_param.lock().unwrap().is_some();
-                if !is_downloading && !has_query {
+                let has_error = self.error_info.lock().unwrap().is_some();
+                if self.launcher.take_ready() {
+                    self.reconnect();
+                }
+                let connect = self.config.auto_connect || self.connect_requested;
+                if !is_downloading && !has_query && !has_error && connect {
                     self.fetch_list();
                 }
+                if has_query && !self.discovery_requested {
+                    self.discover(ctx);
+                }
+                if has_query && std::mem::take(&mut self.startup_pending) {
+                    if self.config.auto_live {
+                        self.config.live_mode = true;
+                    }
+                    if self.config.auto_fetch {
+                        self.fetch_components(ctx);
+                    }
+                }
                 ui.add_space(8.0);
+                let mut switch_to = None;
+                egui::ComboBox::from_id_salt("profile")
+                    .selected_text(&self.config.profile)
+                    .show_ui(ui, |ui| {
+                        for name in self.config.profile_names() {
+                            if ui
+                                .selectable_label(name == self.config.profile, &name)
+                                .clicked()
+                            {
+                                switch_to = Some(name);
+                            }
+                        }
+                    })
+                    .response
+                    .on_hover_text("Connection profile");
+                if let Some(name) = switch_to {
+                    self.switch_profile(&name);
+                }
+                ui.add_space(8.0);
+                self.draw_connection(ui);
+                ui.add_space(8.0);
+                if !connect && ui.button("Connect").clicked() {
+                    self.connect_requested = true;
+                }
+                self.draw_launch_controls(ui);
                 ui.add_enabled_ui(!is_downloading && has_query, |ui| {
-                    if ui.button("Fetch").clicked() {
-                        let components = self.components.clone();
-                        let error_info = self.error_info.clone();
-                        let query_list = self.query_list.clone();
-                        *download_store.lock().unwrap() = Download::InProgress;
-                        let egui_ctx = ctx.clone();
-
-                        let request = helper::make_request(
-                            &*query_param.lock().unwrap(),
-                            BRP_QUERY_METHOD,
-                            self.get_url(),
+                    for item in self.config.toolbar.visible() {
+                        if self.draw_toolbar_item(ctx, ui, item) {
+                            ui.add_space(15.0);
+                        }
+                    }
+                    let settings = ui.menu_button("Settings", |ui| {
+                        if ui.button("Find local games...").clicked() {
+                            self.find_local_games(ctx);
+                            ui.close_menu();
+                        }
+                        ui.checkbox(&mut self.config.compact, "Compact layout")
+                            .on_hover_text("Tighter spacing and one line per entity");
+                        ui.checkbox(&mut self.config.details_docked, "Details beside the tree")
+                            .on_hover_text("Instead of a window of their own");
+                        ui.menu_button("Protected entities", |ui| {
+                            self.draw_protection_settings(ui);
+                        });
+                        ui.menu_button("Redaction", |ui| {
+                            self.draw_redaction_settings(ui);
+                        });
+                        ui.menu_button("Entity groups", |ui| {
+                            self.draw_group_settings(ui);
+                        });
+                        ui.checkbox(&mut self.config.lazy_loading, "Load components lazily")
+                            .on_hover_text(
+                                "Fetch only names and the hierarchy, and the components of \
+                                 entities as they come into view. For very large worlds",
+                            );
+                        ui.horizontal(|ui| {
+                            ui.label("Switch to it above");
+                            ui.add(
+                                egui::DragValue::new(&mut self.config.narrow_above_mb)
+                                    .range(1..=4096)
+                                    .suffix(" MB"),
+                            );
+                            ui.label("or");
+                            ui.add(
+                                egui::DragValue::new(&mut self.config.narrow_after_secs)
+                                    .range(0.5..=120.0)
+                                    .speed(0.1)
+                                    .suffix(" s"),
+                            );
+                        })
+                        .response
+                        .on_hover_text(
+                            "Full fetches answered with more data or slower than this only \
+                             ask for names and the hierarchy from then on",
                         );
-                        ehttp::fetch(request, move |response| {
-                            *download_store.lock().unwrap() = Download::Done;
-                            let Ok(response) = response else {
-                                *error_info.lock().unwrap() = Some(format!("{:#?}", &response));
-                                egui_ctx.request_repaint();
-                                return;
-                            };
-                            if !response.ok {
-                                *error_info.lock().unwrap() = Some(format!("{:#?}", &response));
-                                egui_ctx.request_repaint(); // Wake up UI thread
-                                return;
-                            }
-                            match helper::parse::<BrpQueryResponse>(&response) {
-                                Ok(r) => {
-                                    *components.lock().unwrap() = r.to_hash_map();
-                                    *error_info.lock().unwrap() = None;
+                        ui.checkbox(&mut self.config.watch_selected, "Watch selected entity")
+                            .on_hover_text(
+                                "Stream its changes with bevy/get+watch between fetches",
+                            );
+                        ui.horizontal(|ui| {
+                            ui.label("Snapping:");
+                            ui.add(
+                                egui::DragValue::new(&mut self.config.snap_grid)
+                                    .range(0.001..=1000.0)
+                                    .speed(0.05)
+                                    .suffix(" units"),
+                            );
+                            ui.add(
+                                egui::DragValue::new(&mut self.config.snap_angle)
+                                    .range(1.0..=180.0)
+                                    .suffix("°"),
+                            );
+                        })
+                        .response
+                        .on_hover_text(
+                            "Steps of edited translations and rotations while holding Ctrl",
+                        );
+                        ui.horizontal(|ui| {
+                            ui.label("Timeline length:");
+                            ui.add(
+                                egui::DragValue::new(&mut self.config.timeline_length)
+                                    .range(1..=10_000)
+                                    .suffix(" fetches"),
+                            )
+                            .on_hover_text("Fetch results kept for rewinding the view");
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Memory budget:");
+                            ui.add(
+                                egui::DragValue::new(&mut self.config.retained_memory_mb)
+                                    .range(1..=65_536)
+                                    .suffix(" MB"),
+                            )
+                            .on_hover_text(
+                                "Retained states beyond this are dropped, least recently \
+                                 viewed first",
+                            );
+                        });
+                        ui.menu_button("Customize toolbar", |ui| {
+                            self.config.toolbar.edit(ui);
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Entity label:");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.config.label_template)
+                                    .hint_text("{Entity}: {Name}")
+                                    .desired_width(220.0),
+                            )
+                            .on_hover_text(
+                                "Template for tree rows, e.g. {Name} hp:{my_game::Health.current}. \
+                                 Placeholders are a component type or short name with an \
+                                 optional field path, {Entity} is the id. Empty uses the name.",
+                            );
+                        });
+                        if ui.button("Take the tour").clicked() {
+                            ui.close_menu();
+                            self.tour.start();
+                        }
+                        ui.menu_button("Keyboard shortcuts", |ui| {
+                            self.config.shortcuts.edit(ui);
+                            ui.separator();
+                            egui::Grid::new("shortcuts").num_columns(2).show(ui, |ui| {
+                                for (keys, description) in a11y::SHORTCUTS {
+                                    ui.label(RichText::new(keys).monospace());
+                                    ui.label(description);
+                                    ui.end_row();
                                 }
-                                Err(err) => {
-                                    let mut v = query_list.lock().unwrap();
-                                    let mut s = (*v).clone().unwrap();
-                                    s.data.option = s
-                                        .data
-                                        .option
-                                        .iter()
-                                        .filter(|s| !err.contains(*s))
-                                        .map(|s| s.to_owned())
-                                        .collect();
-                                    *v = Some(s);
-                                    *error_info.lock().unwrap() = Some(err);
+                            });
+                        });
+                        ui.menu_button("Appearance", |ui| {
+                            self.config
+                                .appearance
+                                .edit(ui, self.font_fallback.as_deref());
+                            ui.separator();
+                            ui.horizontal(|ui| {
+                                ui.label("Status colors:");
+                                for palette in StatusPalette::ALL {
+                                    ui.radio_value(
+                                        &mut self.config.status_palette,
+                                        palette,
+                                        palette.label(),
+                                    );
                                 }
-                            }
-                            egui_ctx.request_repaint(); // Wake up UI thread
+                            });
                         });
-                    }
-                    ui.add_space(15.0);
-                    ui.checkbox(&mut self.skip_empty_entities, "Hide empty entities");
-                });
-            });
-            ui.separator();
-            ui.add_space(8.0);
-            // });
-
-            // egui::CentralPanel::default().show(ctx, |ui| {
-            egui::ScrollArea::vertical().show(ui, |ui| {
-                let content = self.components.lock().unwrap();
-                let is_empty = content.len() == 0;
-                let error = self.error_info.lock().unwrap();
-                if is_empty || error.is_some() {
-                    ui.vertical_centered(|ui| {
-                        ui.add_space(15.0);
-                        match &*error {
-                            Some(e) => {
-                                ui.label(
-                                    RichText::new(e)
-                                        .color(Color32::RED)
-                                        .monospace()
-                                        .line_height(Some(25.0))
-                                        .size(20.0),
-                                );
+                        ui.menu_button("Value format", |ui| {
+                            self.config.value_format.edit(ui);
+                        });
+                        ui.menu_button("Refresh rates", |ui| {
+                            self.draw_refresh_settings(ui);
+                        });
+                        ui.menu_button("Power saver", |ui| {
+                            self.config.power_saver.edit(ui);
+                        });
+                        ui.separator();
+                        if ui.button("Export settings...").clicked() {
+                            ui.close_menu();
+                            self.export_config();
+                        }
+                        if ui.button("Import settings...").clicked() {
+                            ui.close_menu();
+                            self.import_config();
+                        }
+                        if ui
+                            .button("Export scene...")
+                            .on_hover_text("Every fetched entity as a Bevy scene or JSON file")
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            let components = self.components.clone();
+                            let live = components.lock().unwrap();
+                            let rewound = self.timeline.lock().unwrap().viewed();
+                            self.export_scene(None, rewound.as_deref().unwrap_or(&live));
+                        }
+                        if ui
+                            .button("Export entity graph...")
+                            .on_hover_text(
+                                "The hierarchy and the references between entities as \
+                                 Graphviz or GraphML",
+                            )
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            let components = self.components.clone();
+                            let live = components.lock().unwrap();
+                            let rewound = self.timeline.lock().unwrap().viewed();
+                            self.export_graph(None, rewound.as_deref().unwrap_or(&live));
+                        }
+                        let has_types = self.query_list.lock().unwrap().is_some();
+                        if ui
+                            .add_enabled(has_types, egui::Button::new("Export type registry..."))
+                            .on_hover_text(
+                                "The game's component types and their schemas as JSON, for \
+                                 code generators and docs",
+                            )
+                            .on_disabled_hover_text("Connect to the game to fetch its types")
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            self.export_type_registry();
+                        }
+                        if ui
+                            .button(format!("Export protocol trace ({})...", trace::len()))
+                            .on_hover_text(
+                                "Every request and response as a HAR file, for replaying \
+                                 or attaching to bug reports",
+                            )
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            self.export_trace();
+                        }
+                        if ui
+                            .button("Export diagnostics...")
+                            .on_hover_text(
+                                "Snapshot, request log, settings without secrets and a \
+                                 screenshot in one archive for bug reports",
+                            )
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            self.request_diagnostics(ctx);
+                        }
+                        ui.separator();
+                        if ui
+                            .button("Open project...")
+                            .on_hover_text(
+                                "A directory, e.g. the game's repository, keeping profiles, \
+                                 queries, dashboards, alerts, groups and aliases",
+                            )
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            self.open_project();
+                        }
+                        if let Some(dir) = self.config.project.clone() {
+                            let name = project_name(&dir);
+                            if ui
+                                .button(format!("Save project {name}"))
+                                .on_hover_text(dir.display().to_string())
+                                .clicked()
+                            {
+                                ui.close_menu();
+                                self.save_project();
                             }
-                            None => {
-                                ui.heading("No components, try fetching first");
+                            if ui.button(format!("Reload project {name}")).clicked() {
+                                ui.close_menu();
+                                self.load_project(dir);
                             }
-                        };
-                        ui.add_space(15.0);
-
-                        let q = self.query_list.lock().unwrap();
-                        let Some(query) = &*q else {
-                            return;
-                        };
-                        ui.collapsing("Components list", |ui|{
-                            for e in query.data.option.iter() {
-                                ui.label(e);
+                            if ui.button("Close project").clicked() {
+                                ui.close_menu();
+                                self.config.project = None;
                             }
-
-                        });
-                    });
-                    return;
-                }
-                let entities: Vec<Entity> = content
-                    .iter()
-                    .map(|(e, row)| {
-                        if row
-                            .components
-                            .contains_key("bevy_hierarchy::components::parent::Parent")
+                        }
+                        if ui
+                            .button("Migrate saved type paths...")
+                            .on_hover_text(
+                                "Move pins, queries, dashboards and other saved settings \
+                                 to the type paths the game uses now",
+                            )
+                            .clicked()
                         {
-                            None
-                        } else {
-                            Some(e.clone())
+                            ui.close_menu();
+                            self.open_migration();
                         }
-                    })
-                    .flatten()
-                    .collect();
-                for e in entities.iter() {
-                    match self.draw_entity(ui, e, &content) {
-                        ActionToDo::None => {}
-                        ActionToDo::Remove => {
-                            let download_store = self.download.clone();
-                            let request = helper::make_request(
-                                &BrpDestroyParams { entity: *e },
-                                BRP_DESTROY_METHOD,
-                                self.get_url(),
+                        if ui.button("Load alias file...").clicked() {
+                            ui.close_menu();
+                            self.pick_aliases_file();
+                        }
+                        if let Some(path) = &self.config.aliases_file {
+                            let reload = ui
+                                .button("Reload aliases")
+                                .on_hover_text(path.display().to_string())
+                                .clicked();
+                            if reload {
+                                ui.close_menu();
+                                self.reload_aliases();
+                            }
+                        }
+                        ui.separator();
+                        ui.label("Profiles");
+                        ui.horizontal(|ui| {
+                            ui.text_edit_singleline(&mut self.new_profile_name);
+                            let name = self.new_profile_name.trim().to_owned();
+                            let exists = self.config.profile_names().contains(&name);
+                            if ui
+                                .add_enabled(!name.is_empty() && !exists, egui::Button::new("Add"))
+                                .clicked()
+                            {
+                                self.new_profile_name.clear();
+                                self.switch_profile(&name);
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Launch command");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.config.launch_command)
+                                    .hint_text("cargo run -p my_game"),
                             );
-                            ehttp::fetch(request, move |_response| {
-                                *download_store.lock().unwrap() = Download::Done;
+                        });
+                        ui.checkbox(&mut self.config.auto_connect, "Connect on launch");
+                        ui.checkbox(&mut self.config.auto_fetch, "Fetch on launch");
+                        ui.checkbox(&mut self.config.auto_live, "Live on launch");
+                        let others: Vec<String> = self
+                            .config
+                            .profile_names()
+                            .into_iter()
+                            .filter(|name| *name != self.config.profile)
+                            .collect();
+                        ui.add_enabled_ui(!others.is_empty(), |ui| {
+                            ui.menu_button("Delete profile", |ui| {
+                                for name in others {
+                                    if ui.button(&name).clicked() {
+                                        self.config.remove_profile(&name);
+                                        ui.close_menu();
+                                    }
+                                }
+                            });
+                        });
+                        ui.separator();
+                        ui.label("Request signing").on_hover_text(
+                            "For games that check an HMAC-SHA256 of each request body",
+                        );
+                        ui.horizontal(|ui| {
+                            ui.label("Header");
+                            ui.text_edit_singleline(&mut self.config.signature_header);
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("Secret");
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.config.shared_secret)
+                                    .password(true)
+                                    .hint_text("Off"),
+                            );
+                        });
+                        ui.separator();
+                        ui.add(
+                            egui::DragValue::new(&mut self.config.stale_after)
+                                .range(0.1..=60.0)
+                                .speed(0.1)
+                                .prefix("Stale after: ")
+                                .suffix(" s"),
+                        );
+                        ui.separator();
+                        ui.label(format!("Connected to {}", self.bevy_version.label()));
+                        self.updates.draw_settings(
+                            ui,
+                            &mut self.config.check_for_updates,
+                            &mut self.config.skipped_version,
+                        );
+                        let capabilities = self.capabilities.lock().unwrap();
+                        if capabilities.is_known() {
+                            ui.separator();
+                            ui.menu_button("Remote methods", |ui| {
+                                for method in capabilities.methods() {
+                                    ui.label(method);
+                                }
                             });
                         }
+                        drop(capabilities);
+                        ui.separator();
+                        ui.label("Batch operations");
+                        ui.add(
+                            egui::DragValue::new(&mut self.config.batch_max_in_flight)
+                                .range(1..=64)
+                                .prefix("In flight: "),
+                        );
+                        ui.add(
+                            egui::DragValue::new(&mut self.config.batch_requests_per_second)
+                                .range(0.1..=1000.0)
+                                .prefix("Requests/s: "),
+                        );
+                    });
+                    tour::mark(ctx, TourAnchor::Settings, settings.response.rect);
+                    let pending = self.scheduler.pending();
+                    if pending > 0 {
+                        ui.add_space(15.0);
+                        power::spinner(ui);
+                        if ui
+                            .link(format!("{pending} queued"))
+                            .on_hover_text("Show the queued requests")
+                            .clicked()
+                        {
+                            self.show_request_queue = !self.show_request_queue;
+                        }
                     }
-                }
+                    self.updates
+                        .draw_notice(ui, self.config.skipped_version.as_deref());
+                });
             });
+            let can_watch = self.supports(BRP_GET_RESOURCE_METHOD);
+            self.resource_watch
+                .draw(ui, &mut self.config.watched_resources, can_watch);
+            self.draw_watchdog(ui);
+            self.draw_narrowed(ui);
+            self.draw_timeline(ui);
+            if !self.config.compact {
+                ui.separator();
+                ui.add_space(8.0);
+            }
+            let search = {
+                let components = self.components.lock().unwrap();
+                let rewound = self.timeline.lock().unwrap().viewed();
+                let content = rewound.as_deref().unwrap_or(&components);
+                let changes = self.changes.lock().unwrap();
+                // The game only knows its current entities, not those of a past fetch.
+                let remote_search = self.search.remote().filter(|_| rewound.is_none());
+                let remote_matches = self.remote_search.lock().unwrap();
+                let remote = remote_matches
+                    .as_ref()
+                    .filter(|matches| Some(&matches.search) == remote_search.as_ref())
+                    .and_then(|matches| matches.entities.as_ref());
+                self.search.is_active().then(|| {
+                    self.search
+                        .run(content, self.bevy_version.paths(), &changes, remote)
+                })
+            };
+            if self.search.recently_spawned || self.search.recently_changed {
+                // Entities drop out of the filters as time passes, not only on fetches.
+                ctx.request_repaint_after(Duration::from_secs(1));
+            }
+            dock::reserve(ui);
+            if self.show_rpc_log {
+                egui::TopBottomPanel::bottom("rpc_log")
+                    .resizable(true)
+                    .default_height(220.0)
+                    .show_inside(ui, |ui| self.draw_rpc_log(ui));
+            }
+            if self.config.details_docked {
+                egui::SidePanel::right("details_pane")
+                    .resizable(true)
+                    .default_width(380.0)
+                    .show_inside(ui, |ui| self.draw_details_pane(ui));
+            }
+            let search_error = search.as_ref().and_then(|s| s.as_ref().err());
+            let destroyed = self.changes.lock().unwrap().recently_destroyed();
+            let query_list = self.query_list.lock().unwrap();
+            let types = query_list
+                .as_ref()
+                .map_or(&[][..], |query| query.data.option.as_slice());
+            let search_bar =
+                self.search
+                    .draw(ui, search_error.map(String::as_str), &destroyed, types);
+            drop(query_list);
+            tour::mark(ctx, TourAnchor::Search, search_bar.rect);
+            let search = search.and_then(Result::ok);
             // });
-        });
-    }
-}
-
-fn custom_window_frame(ctx: &egui::Context, title: &str, add_contents: impl FnOnce(&mut egui::Ui)) {
-    use egui::{CentralPanel, UiBuilder};
-
-    let panel_frame = egui::Frame {
-        fill: ctx.style().visuals.window_fill(),
-        rounding: 10.0.into(),
-        stroke: ctx.style().visuals.widgets.noninteractive.fg_stroke,
-        outer_margin: 0.5.into(), // so the stroke is within the bounds
-        ..Default::default()
-    };
-
-    CentralPanel::default().frame(panel_frame).show(ctx, |ui| {
-        let app_rect = ui.max_rect();
-
-        let title_bar_height = 32.0;
-        let title_bar_rect = {
-            let mut rect = app_rect;
-            rect.max.y = rect.min.y + title_bar_height;
-            rect
-        };
-        title_bar_ui(ui, title_bar_rect, title);
-
-        // Add the contents:
-        let content_rect = {
-            let mut rect = app_rect;
-            rect.min.y = title_bar_rect.max.y;
-            rect
-        }
-        .shrink(4.0);
-        let mut content_ui = ui.new_child(UiBuilder::new().max_rect(content_rect));
-        add_contents(&mut content_ui);
-    });
-}
 
-fn title_bar_ui(ui: &mut egui::Ui, title_bar_rect: eframe::epaint::Rect, title: &str) {
-    use egui::{vec2, Align2, FontId, Id, PointerButton, Sense, UiBuilder};
-
-    let painter = ui.painter();
-
-    let title_bar_response = ui.interact(
-        title_bar_rect,
-        Id::new("title_bar"),
-        Sense::click_and_drag(),
-    );
-
-    // Paint the title:
-    painter.text(
-        title_bar_rect.center(),
-        Align2::CENTER_CENTER,
-        title,
-        FontId::proportional(22.0),
-        egui::Color32::from_rgb(230, 102, 1),
-    );
-
-    // Paint the line under the title:
-    painter.line_segment(
-        [
-            title_bar_rect.left_bottom() + vec2(1.0, 0.0),
-            title_bar_rect.right_bottom() + vec2(-1.0, 0.0),
-        ],
-        ui.visuals().widgets.noninteractive.bg_stroke,
-    );
-
-    // Interact with the title bar (drag to move window):
-    if title_bar_response.double_clicked() {
-        let is_maximized = ui.input(|i| i.viewport().maximized.unwrap_or(false));
-        ui.ctx()
-            .send_viewport_cmd(ViewportCommand::Maximized(!is_maximized));
-    }
-
-    if title_bar_response.drag_started_by(PointerButton::Primary) {
-        ui.ctx().send_viewport_cmd(ViewportCommand::StartDrag);
-    }
-
-    ui.allocate_new_ui(
-        UiBuilder::new()
-            .max_rect(title_bar_rect)
-            .layout(egui::Layout::right_to_left(egui::Align::Center)),
-        |ui| {
-            ui.spacing_mut().item_spacing.x = 0.0;
-            ui.visuals_mut().button_frame = false;
-            ui.add_space(8.0);
-            close_maximize_minimize(ui);
-        },
-    );
-}
-
-/// Show some close/maximize/minimize buttons for the native window.
-fn close_maximize_minimize(ui: &mut egui::Ui) {
-    use egui::{Button, RichText};
-
-    let button_height = 12.0;
-
-    let close_response = ui
-        .add(Button::new(RichText::new("❌").size(button_height)))
-        .on_hover_text("Close the window");
-    if close_response.clicked() {
-        ui.ctx().send_viewport_cmd(egui::ViewportCommand::Close);
-    }
-
-    let is_maximized = ui.input(|i| i.viewport().maximized.unwrap_or(false));
-    if is_maximized {
-        let maximized_response = ui
-            .add(Button::new(RichText::new("🗗").size(button_height)))
-            .on_hover_text("Restore window");
-        if maximized_response.clicked() {
-            ui.ctx()
-                .send_viewport_cmd(ViewportCommand::Maximized(false));
+            // egui::CentralPanel::default().show(ctx, |ui| {
+            let tree_area = self.draw_tree(ctx, ui, search.as_ref());
+            tour::mark(ctx, TourAnchor::Tree, tree_area);
+            // });
+        });
+        self.scroll_to_selected = false;
+        self.scroll_to_spawned = None;
+        self.draw_trash(ctx);
+        self.draw_panels(ctx);
+        self.draw_minimap(ctx);
+        self.draw_component_history(ctx);
+        self.updates.show(ctx, &mut self.config.skipped_version);
+        self.draw_references(ctx);
+        self.draw_history(ctx);
+        self.draw_details(ctx);
+        self.draw_type_docs(ctx);
+        self.draw_change_feed(ctx);
+        self.draw_insights(ctx);
+        self.draw_problems(ctx);
+        self.draw_staged(ctx);
+        self.draw_confirmation(ctx);
+        self.check_migration();
+        self.draw_migration(ctx);
+        self.draw_comparison(ctx);
+        self.draw_stats(ctx);
+        self.draw_dashboard(ctx);
+        self.check_alerts();
+        self.draw_alerts(ctx);
+        self.draw_query_builder(ctx);
+        self.draw_performance(ctx);
+        self.draw_render_stats(ctx);
+        self.draw_bookmarks(ctx);
+        self.draw_types(ctx);
+        self.draw_request_queue(ctx);
+        self.draw_local_scan(ctx);
+        self.draw_pasted(ctx);
+        self.draw_spawn(ctx);
+        self.draw_hud(ctx);
+        self.draw_command_palette(ctx);
+        if self.tour.draw(ctx) == TourAction::Finish {
+            self.tour_complete = true;
         }
-    } else {
-        let maximized_response = ui
-            .add(Button::new(RichText::new("🗗").size(button_height)))
-            .on_hover_text("Maximize window");
-        if maximized_response.clicked() {
-            ui.ctx().send_viewport_cmd(ViewportCommand::Maximized(true));
+        self.handle_shortcuts(ctx);
+        self.handle_paste(ctx);
+        self.sync_watches(ctx);
+        self.sync_change_ticks(ctx);
+        self.sync_remote_search(ctx);
+        self.load_lazily(ctx);
+        for (key, side) in dock::take_changes(ctx) {
+            match side {
+                Some(side) => self.config.docked_panels.insert(key, side),
+                None => self.config.docked_panels.remove(&key),
+            };
         }
     }
-
-    let minimized_response = ui
-        .add(Button::new(RichText::new("🗕").size(button_height)))
-        .on_hover_text("Minimize the window");
-    if minimized_response.clicked() {
-        ui.ctx().send_viewport_cmd(ViewportCommand::Minimized(true));
-    }
-}
-fn setup_custom_fonts(ctx: &egui::Context) {
-    // Start with the default fonts (we will be adding to them rather than replacing them).
-    let mut fonts = egui::FontDefinitions::default();
-    if let Ok((regular, semibold)) = get_fonts() {
-        fonts
-            .font_data
-            .insert("regular".to_owned(), egui::FontData::from_owned(regular));
-        fonts
-            .font_data
-            .insert("semibold".to_owned(), egui::FontData::from_owned(semibold));
-
-        // Put my font first (highest priority) for proportional text:
-        fonts
-            .families
-            .entry(egui::FontFamily::Proportional)
-            .or_default()
-            .insert(0, "regular".to_owned());
-        fonts
-            .families
-            .entry(egui::FontFamily::Name("semibold".into()))
-            .or_default()
-            .insert(0, "semibold".to_owned());
-
-        // Put my font as last fallback for monospace:
-        fonts
-            .families
-            .entry(egui::FontFamily::Monospace)
-            .or_default()
-            .push("regular".to_owned());
-
-        // Tell egui to use these fonts:
-        ctx.set_fonts(fonts);
-    }
-
-    ctx.style_mut(|style| {
-        for font_id in style.text_styles.values_mut() {
-            font_id.size *= 1.4;
-        }
-    });
-}
-
-#[cfg(not(windows))]
-fn get_fonts() -> anyhow::Result<(Vec<u8>, Vec<u8>)> {
-    let font_path = std::path::Path::new("/System/Library/Fonts");
-
-    let regular = fs::read(font_path.join("SFNSRounded.ttf"))?;
-    let semibold = fs::read(font_path.join("SFCompact.ttf"))?;
-
-    Ok((regular, semibold))
-}
-
-#[cfg(windows)]
-fn get_fonts() -> anyhow::Result<(Vec<u8>, Vec<u8>)> {
-    use std::fs;
-
-    let app_data = std::env::var("APPDATA")?;
-    let font_path = std::path::Path::new(&app_data);
-
-    let regular = fs::read(font_path.join("../Local/Microsoft/Windows/Fonts/aptos.ttf"))?;
-    let semibold = fs::read(font_path.join("../Local/Microsoft/Windows/Fonts/aptos-semibold.ttf"))?;
-
-    Ok((regular, semibold))
 }
@@ -0,0 +1,338 @@
+//! Window decorations and fonts.
+
+use super::*;
+
+/// Width of the window edges that resize the borderless window, in points.
+const RESIZE_BORDER: f32 = 5.0;
+
+pub(super) fn compact_spacing() -> egui::style::Spacing {
+    egui::style::Spacing {
+        item_spacing: egui::vec2(4.0, 1.0),
+        button_padding: egui::vec2(2.0, 0.0),
+        indent: 10.0,
+        interact_size: egui::vec2(30.0, 14.0),
+        window_margin: egui::Margin::same(3.0),
+        menu_margin: egui::Margin::same(3.0),
+        ..Default::default()
+    }
+}
+
+/// Entity count shown in the title bar, e.g. `E: 1432 (+3/s)`.
+pub(super) struct Ticker {
+    text: String,
+    /// The count kept growing by at least one entity a second.
+    growing: bool,
+}
+
+impl TemplateApp {
+    /// `None` until entities were fetched.
+    pub(super) fn entity_ticker(&self) -> Option<Ticker> {
+        let count = self.components.lock().unwrap().len();
+        if count == 0 {
+            return None;
+        }
+        let rate = self.changes.lock().unwrap().net_spawn_rate();
+        let shown = if rate.abs() >= 1.0 {
+            format!("{rate:+.0}")
+        } else {
+            format!("{rate:+.1}")
+        };
+        Some(Ticker {
+            text: format!("E: {count} ({shown}/s)"),
+            growing: rate >= 1.0,
+        })
+    }
+}
+
+pub(super) fn custom_window_frame(
+    ctx: &egui::Context,
+    title: &str,
+    ticker: Option<Ticker>,
+    add_contents: impl FnOnce(&mut egui::Ui),
+) {
+    use egui::{CentralPanel, UiBuilder};
+
+    // In the browser the page is the window, there is nothing to drag, resize or close.
+    if cfg!(target_arch = "wasm32") {
+        CentralPanel::default().show(ctx, add_contents);
+        return;
+    }
+
+    // Sizes in points cover a different number of physical pixels on each monitor, the
+    // border is kept at whole pixels of the one the window is on now.
+    let pixel = 1.0 / ctx.pixels_per_point();
+    let maximized = ctx.input(|i| i.viewport().maximized.unwrap_or(false));
+    let mut stroke = ctx.style().visuals.widgets.noninteractive.fg_stroke;
+    stroke.width = (stroke.width / pixel).round().max(1.0) * pixel;
+    let panel_frame = egui::Frame {
+        fill: ctx.style().visuals.window_fill(),
+        rounding: if maximized { 0.0 } else { 10.0 }.into(),
+        stroke,
+        outer_margin: (stroke.width / 2.0).into(), // so the stroke is within the bounds
+        ..Default::default()
+    };
+
+    CentralPanel::default().frame(panel_frame).show(ctx, |ui| {
+        let app_rect = ui.painter().round_rect_to_pixels(ui.max_rect());
+
+        let title_bar_height = 32.0;
+        let title_bar_rect = {
+            let mut rect = app_rect;
+            rect.max.y = rect.min.y + title_bar_height;
+            ui.painter().round_rect_to_pixels(rect)
+        };
+        title_bar_ui(ui, title_bar_rect, title);
+        if let Some(ticker) = ticker {
+            ticker_ui(ui, title_bar_rect, ticker);
+        }
+
+        // Add the contents:
+        let content_rect = {
+            let mut rect = app_rect;
+            rect.min.y = title_bar_rect.max.y;
+            rect
+        }
+        .shrink(4.0);
+        let mut content_ui = ui.new_child(UiBuilder::new().max_rect(content_rect));
+        add_contents(&mut content_ui);
+
+        if !maximized {
+            resize_handles(ui, app_rect);
+        }
+    });
+}
+
+/// Edges and corners that resize the window, which has no decorations of its own to be
+/// resized by. Added after the contents so they are hit first.
+fn resize_handles(ui: &mut egui::Ui, app_rect: egui::Rect) {
+    use egui::{pos2, vec2, CursorIcon, Id, PointerButton, Rect, ResizeDirection, Sense};
+
+    let border = RESIZE_BORDER;
+    let corner = border * 2.0;
+    let (min, max) = (app_rect.min, app_rect.max);
+    let handles = [
+        (
+            Rect::from_min_max(min, min + vec2(corner, corner)),
+            ResizeDirection::NorthWest,
+            CursorIcon::ResizeNorthWest,
+        ),
+        (
+            Rect::from_min_max(pos2(max.x - corner, min.y), pos2(max.x, min.y + corner)),
+            ResizeDirection::NorthEast,
+            CursorIcon::ResizeNorthEast,
+        ),
+        (
+            Rect::from_min_max(pos2(min.x, max.y - corner), pos2(min.x + corner, max.y)),
+            ResizeDirection::SouthWest,
+            CursorIcon::ResizeSouthWest,
+        ),
+        (
+            Rect::from_min_max(max - vec2(corner, corner), max),
+            ResizeDirection::SouthEast,
+            CursorIcon::ResizeSouthEast,
+        ),
+        (
+            Rect::from_min_max(min, pos2(max.x, min.y + border)),
+            ResizeDirection::North,
+            CursorIcon::ResizeNorth,
+        ),
+        (
+            Rect::from_min_max(pos2(min.x, max.y - border), max),
+            ResizeDirection::South,
+            CursorIcon::ResizeSouth,
+        ),
+        (
+            Rect::from_min_max(min, pos2(min.x + border, max.y)),
+            ResizeDirection::West,
+            CursorIcon::ResizeWest,
+        ),
+        (
+            Rect::from_min_max(pos2(max.x - border, min.y), max),
+            ResizeDirection::East,
+            CursorIcon::ResizeEast,
+        ),
+    ];
+    paint_grip(ui, app_rect);
+    let mut hit = false;
+    for (index, (rect, direction, cursor)) in handles.into_iter().enumerate() {
+        let response = ui.interact(rect, Id::new(("window_resize", index)), Sense::drag());
+        // Corners overlap the edges, only the first handle under the pointer counts.
+        if hit || !(response.hovered() || response.dragged()) {
+            continue;
+        }
+        hit = true;
+        ui.ctx().set_cursor_icon(cursor);
+        if response.drag_started_by(PointerButton::Primary) {
+            ui.ctx()
+                .send_viewport_cmd(ViewportCommand::BeginResize(direction));
+        }
+    }
+}
+
+/// Diagonal lines in the bottom right corner, where windows are usually resized from.
+fn paint_grip(ui: &egui::Ui, app_rect: egui::Rect) {
+    let painter = ui.painter();
+    let stroke = ui.visuals().widgets.noninteractive.fg_stroke;
+    let corner = app_rect.max - egui::vec2(4.0, 4.0);
+    for offset in [4.0, 8.0, 12.0] {
+        painter.line_segment(
+            [
+                painter.round_pos_to_pixels(corner - egui::vec2(offset, 0.0)),
+                painter.round_pos_to_pixels(corner - egui::vec2(0.0, offset)),
+            ],
+            stroke,
+        );
+    }
+}
+
+pub(super) fn title_bar_ui(ui: &mut egui::Ui, title_bar_rect: eframe::epaint::Rect, title: &str) {
+    use egui::{pos2, Align2, FontId, Id, PointerButton, Sense, UiBuilder};
+
+    let painter = ui.painter();
+
+    let title_bar_response = ui.interact(
+        title_bar_rect,
+        Id::new("title_bar"),
+        Sense::click_and_drag(),
+    );
+
+    // Paint the title:
+    painter.text(
+        title_bar_rect.center(),
+        Align2::CENTER_CENTER,
+        title,
+        FontId::proportional(22.0),
+        egui::Color32::from_rgb(230, 102, 1),
+    );
+
+    // Paint the line under the title, centered on a physical pixel row so it stays sharp
+    // on every scale factor:
+    let line_y = painter.round_to_pixel_center(title_bar_rect.bottom());
+    painter.line_segment(
+        [
+            pos2(title_bar_rect.left() + 1.0, line_y),
+            pos2(title_bar_rect.right() - 1.0, line_y),
+        ],
+        ui.visuals().widgets.noninteractive.bg_stroke,
+    );
+
+    // Interact with the title bar (drag to move window):
+    if title_bar_response.double_clicked() {
+        let is_maximized = ui.input(|i| i.viewport().maximized.unwrap_or(false));
+        ui.ctx()
+            .send_viewport_cmd(ViewportCommand::Maximized(!is_maximized));
+    }
+
+    // The system moves the window, so dragging it to a screen edge snaps it there like
+    // any other window.
+    if title_bar_response.drag_started_by(PointerButton::Primary) {
+        ui.ctx().send_viewport_cmd(ViewportCommand::StartDrag);
+    }
+
+    // The window menu the system would show on a decorated title bar.
+    title_bar_response.context_menu(|ui| {
+        let is_maximized = ui.input(|i| i.viewport().maximized.unwrap_or(false));
+        let toggle = if is_maximized { "Restore" } else { "Maximize" };
+        if ui.button(toggle).clicked() {
+            ui.close_menu();
+            ui.ctx()
+                .send_viewport_cmd(ViewportCommand::Maximized(!is_maximized));
+        }
+        if ui.button("Minimize").clicked() {
+            ui.close_menu();
+            ui.ctx().send_viewport_cmd(ViewportCommand::Minimized(true));
+        }
+        ui.separator();
+        if ui.button("Close").clicked() {
+            ui.close_menu();
+            ui.ctx().send_viewport_cmd(ViewportCommand::Close);
+        }
+    });
+
+    ui.allocate_new_ui(
+        UiBuilder::new()
+            .max_rect(title_bar_rect)
+            .layout(egui::Layout::right_to_left(egui::Align::Center)),
+        |ui| {
+            ui.spacing_mut().item_spacing.x = 0.0;
+            ui.visuals_mut().button_frame = false;
+            ui.add_space(8.0);
+            close_maximize_minimize(ui);
+        },
+    );
+}
+
+/// The entity count at the left of the title bar, a steady health signal for entity
+/// leaks whatever panels are open.
+fn ticker_ui(ui: &mut egui::Ui, title_bar_rect: eframe::epaint::Rect, ticker: Ticker) {
+    use egui::{vec2, Align2, FontId, Id, Sense};
+
+    let color = if ticker.growing {
+        status_colors(ui.ctx()).warning
+    } else {
+        ui.visuals().weak_text_color()
+    };
+    let rect = ui.painter().text(
+        title_bar_rect.left_center() + vec2(12.0, 0.0),
+        Align2::LEFT_CENTER,
+        &ticker.text,
+        FontId::monospace(13.0),
+        color,
+    );
+    ui.interact(rect, Id::new("entity_ticker"), Sense::hover())
+        .on_hover_text(
+            "Entities in the world, and how many more there are per second over the last \
+             10 seconds",
+        );
+}
+
+/// Show some close/maximize/minimize buttons for the native window.
+pub(super) fn close_maximize_minimize(ui: &mut egui::Ui) {
+    use egui::{Button, RichText};
+
+    let button_height = 12.0;
+
+    let close_response = labeled(
+        ui.add(Button::new(RichText::new("❌").size(button_height))),
+        egui::WidgetType::Button,
+        "Close",
+    )
+    .on_hover_text("Close the window");
+    if close_response.clicked() {
+        ui.ctx().send_viewport_cmd(egui::ViewportCommand::Close);
+    }
+
+    let is_maximized = ui.input(|i| i.viewport().maximized.unwrap_or(false));
+    if is_maximized {
+        let maximized_response = labeled(
+            ui.add(Button::new(RichText::new("🗗").size(button_height))),
+            egui::WidgetType::Button,
+            "Restore",
+        )
+        .on_hover_text("Restore window");
+        if maximized_response.clicked() {
+            ui.ctx()
+                .send_viewport_cmd(ViewportCommand::Maximized(false));
+        }
+    } else {
+        let maximized_response = labeled(
+            ui.add(Button::new(RichText::new("🗗").size(button_height))),
+            egui::WidgetType::Button,
+            "Maximize",
+        )
+        .on_hover_text("Maximize window");
+        if maximized_response.clicked() {
+            ui.ctx().send_viewport_cmd(ViewportCommand::Maximized(true));
+        }
+    }
+
+    let minimized_response = labeled(
+        ui.add(Button::new(RichText::new("🗕").size(button_height))),
+        egui::WidgetType::Button,
+        "Minimize",
+    )
+    .on_hover_text("Minimize the window");
+    if minimized_response.clicked() {
+        ui.ctx().send_viewport_cmd(ViewportCommand::Minimized(true));
+    }
+}
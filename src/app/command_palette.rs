@@ -0,0 +1,195 @@
+//! Running [`Command`]s from shortcuts and the command palette, which also jumps to
+//! entities by name.
+
+use super::*;
+
+/// Matches shown in the palette at most.
+const MAX_MATCHES: usize = 12;
+
+enum Target {
+    Command(Command),
+    Entity(Entity),
+}
+
+impl TemplateApp {
+    pub(super) fn run_command(&mut self, ctx: &egui::Context, command: Command) {
+        match command {
+            Command::Fetch => {
+                let is_downloading =
+                    matches!(&*self.download.lock().unwrap(), Download::InProgress { .. });
+                if self.query_list.lock().unwrap().is_some() && !is_downloading {
+                    self.fetch_components(ctx);
+                }
+            }
+            Command::FocusSearch => self.search.focus_requested = true,
+            Command::Deselect => self.selected = None,
+            Command::OpenPalette => self.command_palette.toggle(),
+            Command::ToggleLive => self.send(ctx, Msg::SetFlag(Flag::Live, !self.config.live_mode)),
+            Command::ToggleReadOnly => {
+                self.send(ctx, Msg::SetFlag(Flag::ReadOnly, !self.config.read_only))
+            }
+            Command::TogglePowerSaver => {
+                let on = !self.config.power_saver.enabled;
+                self.send(ctx, Msg::SetFlag(Flag::PowerSaver, on))
+            }
+            Command::Reconnect => self.reconnect(),
+            Command::PruneDespawned => {
+                let mut components = self.components.lock().unwrap();
+                self.despawned.lock().unwrap().prune(&mut components);
+                self.selected = self.selected.filter(|e| components.contains_key(e));
+            }
+            Command::ExportScene => {
+                let components = self.components.clone();
+                let live = components.lock().unwrap();
+                let rewound = self.timeline.lock().unwrap().viewed();
+                self.export_scene(None, rewound.as_deref().unwrap_or(&live));
+            }
+            Command::FocusDetails => self.focus_details = self.selected.is_some(),
+            Command::Undo => self.undo(ctx),
+            Command::Redo => self.redo(ctx),
+            Command::RepeatLast => {
+                if let Some(LastAction::Spawn(params)) = &self.last_action {
+                    self.spawn(ctx, params.clone());
+                    return;
+                }
+                let (Some(selected), Some(action)) = (self.selected, &self.last_action) else {
+                    return;
+                };
+                let msg = match action {
+                    LastAction::Insert(component, value) => {
+                        Msg::Insert(selected, component.clone(), value.clone())
+                    }
+                    LastAction::Remove(component) => {
+                        Msg::RemoveComponent(selected, component.clone())
+                    }
+                    LastAction::Spawn(_) => return,
+                };
+                let components = self.components.clone();
+                let content = components.lock().unwrap();
+                self.dispatch(ctx, msg, &content);
+            }
+            Command::Destroy | Command::CopyJson | Command::FindReferences => {
+                let Some(selected) = self.selected else {
+                    return;
+                };
+                let components = self.components.clone();
+                let content = components.lock().unwrap();
+                let msg = match command {
+                    Command::Destroy if !self.supports(BRP_DESTROY_METHOD) => return,
+                    Command::Destroy if !self.multi_selected.is_empty() => {
+                        Msg::DestroyMany(self.multi_selected.iter().copied().collect())
+                    }
+                    Command::Destroy => Msg::Remove(selected),
+                    Command::FindReferences => Msg::FindReferences(selected),
+                    Command::CopyJson => Msg::CopyComponents(selected, None),
+                    _ => return,
+                };
+                self.dispatch(ctx, msg, &content);
+            }
+        }
+    }
+
+    /// Commands and entities matching what's typed, best first.
+    fn palette_matches(&self) -> Vec<(Target, String)> {
+        let query = &self.command_palette.query;
+        let mut matches: Vec<(i32, Target, String)> = Command::ALL
+            .into_iter()
+            .filter_map(|command| {
+                let score = fuzzy_score(query, command.label())?;
+                Some((score, Target::Command(command), command.label().to_owned()))
+            })
+            .collect();
+        if !query.trim().is_empty() {
+            let content = self.components.lock().unwrap();
+            matches.extend(content.iter().filter_map(|(entity, row)| {
+                let label = self.entity_label(entity, row);
+                let score = fuzzy_score(query, &label)?;
+                Some((score, Target::Entity(*entity), label))
+            }));
+        }
+        matches.sort_by_key(|(score, _, _)| std::cmp::Reverse(*score));
+        matches
+            .into_iter()
+            .take(MAX_MATCHES)
+            .map(|(_, target, label)| (target, label))
+            .collect()
+    }
+
+    pub(super) fn draw_command_palette(&mut self, ctx: &egui::Context) {
+        if !self.command_palette.open {
+            return;
+        }
+        let matches = self.palette_matches();
+        let mut chosen = None;
+        let mut close = false;
+        egui::Window::new("Command palette")
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 60.0])
+            .fixed_size([420.0, 0.0])
+            .show(ctx, |ui| {
+                let palette = &mut self.command_palette;
+                let field = ui.add(
+                    egui::TextEdit::singleline(&mut palette.query)
+                        .hint_text("Type a command or an entity")
+                        .desired_width(f32::INFINITY),
+                );
+                field.request_focus();
+                if field.changed() {
+                    palette.selected = 0;
+                }
+                let (up, down, enter, escape) = ui.input(|i| {
+                    (
+                        i.key_pressed(egui::Key::ArrowUp),
+                        i.key_pressed(egui::Key::ArrowDown),
+                        i.key_pressed(egui::Key::Enter),
+                        i.key_pressed(egui::Key::Escape),
+                    )
+                });
+                let last = matches.len().saturating_sub(1);
+                if down {
+                    palette.selected = (palette.selected + 1).min(last);
+                }
+                if up {
+                    palette.selected = palette.selected.saturating_sub(1);
+                }
+                palette.selected = palette.selected.min(last);
+                close = escape;
+                if matches.is_empty() {
+                    ui.weak("Nothing matches");
+                }
+                for (index, (target, label)) in matches.iter().enumerate() {
+                    let text = match target {
+                        Target::Command(command) => {
+                            match self.config.shortcuts.describe(ctx, *command) {
+                                Some(keys) => format!("{label}    {keys}"),
+                                None => label.clone(),
+                            }
+                        }
+                        Target::Entity(_) => format!("→ {label}"),
+                    };
+                    let highlighted = index == palette.selected;
+                    if ui.selectable_label(highlighted, text).clicked() || (highlighted && enter) {
+                        chosen = Some(index);
+                    }
+                }
+            });
+        if close {
+            self.command_palette.open = false;
+        }
+        let Some((target, _)) = chosen.and_then(|index| matches.into_iter().nth(index)) else {
+            return;
+        };
+        self.command_palette.open = false;
+        match target {
+            // Toggling the palette from itself would open it again right away.
+            Target::Command(Command::OpenPalette) => {}
+            Target::Command(command) => self.run_command(ctx, command),
+            Target::Entity(entity) => {
+                self.selected = Some(entity);
+                self.scroll_to_selected = true;
+            }
+        }
+    }
+}
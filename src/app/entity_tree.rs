@@ -0,0 +1,1119 @@
+//! The entity tree and the components of an entity.
+
+use egui::collapsing_header::{paint_default_icon, CollapsingState};
+
+use super::*;
+
+/// What the entity tree is drawn from in one frame.
+pub(super) struct TreeView<'a> {
+    pub rows: &'a HashMap<Entity, BrpQueryRow>,
+    pub tree: &'a EntityTree,
+    pub search: Option<&'a SearchResult>,
+}
+
+/// A line of the tree, which is laid out as a flat list so only the visible part of it
+/// is drawn.
+enum TreeLine {
+    /// Heading of the entity group at `index`, one past the groups for the ungrouped.
+    Group {
+        index: usize,
+        count: usize,
+    },
+    Entity {
+        entity: Entity,
+        depth: usize,
+    },
+}
+
+impl TreeLine {
+    fn entity(&self) -> Option<Entity> {
+        match self {
+            TreeLine::Group { .. } => None,
+            TreeLine::Entity { entity, .. } => Some(*entity),
+        }
+    }
+}
+
+impl TemplateApp {
+    /// The pinned entities, whether or not they match the search, so they are always a
+    /// click away. Pins of entities missing from the world stay listed until removed.
+    pub(super) fn draw_pinned(&mut self, ui: &mut egui::Ui, rows: &HashMap<Entity, BrpQueryRow>) {
+        if self.config.pinned_entities.is_empty() {
+            return;
+        }
+        let paths = self.bevy_version.paths();
+        let pins: Vec<(Option<Entity>, String)> = self
+            .config
+            .pinned_entities
+            .iter()
+            .map(|pin| match pin.resolve(rows, paths) {
+                Some(entity) => (Some(entity), self.entity_label(&entity, &rows[&entity])),
+                None => (None, format!("{} (not in the world)", pin.label())),
+            })
+            .collect();
+        let mut unpin = None;
+        let mut select = None;
+        egui::CollapsingHeader::new(RichText::new(format!("📌 Pinned ({})", pins.len())).strong())
+            .id_salt("pinned_entities")
+            .default_open(true)
+            .show(ui, |ui| {
+                for (index, (entity, label)) in pins.into_iter().enumerate() {
+                    ui.horizontal(|ui| {
+                        if ui.small_button("✖").on_hover_text("Unpin").clicked() {
+                            unpin = Some(index);
+                        }
+                        let Some(entity) = entity else {
+                            ui.weak(label);
+                            return;
+                        };
+                        if ui
+                            .selectable_label(self.selected == Some(entity), label)
+                            .clicked()
+                        {
+                            select = Some(entity);
+                        }
+                    });
+                }
+            });
+        if select.is_some() {
+            self.selected = select;
+            self.scroll_to_selected = true;
+        }
+        if let Some(index) = unpin {
+            self.config.pinned_entities.remove(index);
+        }
+        ui.separator();
+    }
+
+    /// Actions on all entities ctrl-clicked in the tree.
+    pub(super) fn draw_multi_selection(
+        &mut self,
+        ctx: &egui::Context,
+        ui: &mut egui::Ui,
+        rows: &HashMap<Entity, BrpQueryRow>,
+    ) {
+        self.multi_selected
+            .retain(|entity| rows.contains_key(entity));
+        if self.multi_selected.is_empty() {
+            return;
+        }
+        let selected: Vec<Entity> = self.multi_selected.iter().copied().collect();
+        let can_destroy = !self.config.read_only && self.supports(BRP_DESTROY_METHOD);
+        let can_remove = !self.config.read_only && self.supports(BRP_REMOVE_METHOD);
+        let mut action = Msg::None;
+        let mut clear = false;
+        ui.horizontal_wrapped(|ui| {
+            ui.strong(format!("{} selected", selected.len()));
+            if ui
+                .add_enabled(can_destroy, egui::Button::new("Destroy all"))
+                .clicked()
+            {
+                action = Msg::DestroyMany(selected.clone());
+            }
+            let mut shared: BTreeMap<&String, usize> = BTreeMap::new();
+            for entity in &selected {
+                for component in rows[entity].components.keys() {
+                    *shared.entry(component).or_default() += 1;
+                }
+            }
+            ui.add_enabled_ui(can_remove, |ui| {
+                ui.menu_button("Remove component", |ui| {
+                    egui::ScrollArea::vertical()
+                        .max_height(300.0)
+                        .show(ui, |ui| {
+                            for (component, count) in &shared {
+                                let text =
+                                    format!("{} ({count})", self.aliases.type_name(component));
+                                if ui.button(text).on_hover_text(*component).clicked() {
+                                    action =
+                                        Msg::RemoveFromMany(selected.clone(), (*component).clone());
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                });
+            });
+            clear = ui
+                .button("Clear")
+                .on_hover_text("Ctrl-click entities to select them")
+                .clicked();
+        });
+        if clear {
+            self.multi_selected.clear();
+        }
+        ui.separator();
+        self.dispatch(ctx, action, rows);
+    }
+
+    /// The entity tree below the search bar. Only the lines on screen are laid out, so a
+    /// world of thousands of entities draws as fast as a small one. Returns the area of
+    /// the tree.
+    pub(super) fn draw_tree(
+        &mut self,
+        ctx: &egui::Context,
+        ui: &mut egui::Ui,
+        search: Option<&SearchResult>,
+    ) -> egui::Rect {
+        let connection_failed =
+            self.query_list.lock().unwrap().is_none() && self.error_info.lock().unwrap().is_some();
+        if !self.onboarding_complete || connection_failed {
+            return egui::ScrollArea::vertical()
+                .show(ui, |ui| self.draw_onboarding(ui))
+                .inner_rect;
+        }
+        let components = self.components.clone();
+        let live = components.lock().unwrap();
+        let rewound = self.timeline.lock().unwrap().viewed();
+        let content = rewound.as_deref().unwrap_or(&live);
+        let is_empty = content.len() == 0;
+        let error = self.error_info.lock().unwrap().clone();
+        // Throttling only slows polling down, the last state stays useful meanwhile.
+        let blocking = error
+            .as_ref()
+            .is_some_and(|e| !matches!(e, InspectorError::Throttled { .. }));
+        if is_empty || blocking {
+            drop(live);
+            return egui::ScrollArea::vertical()
+                .show(ui, |ui| {
+                    ui.vertical_centered(|ui| {
+                        ui.add_space(15.0);
+                        match &error {
+                            Some(e) => self.draw_error(ui, e),
+                            None => {
+                                ui.heading("No components, try fetching first");
+                            }
+                        };
+                        ui.add_space(15.0);
+
+                        let q = self.query_list.lock().unwrap();
+                        let Some(query) = &*q else {
+                            return;
+                        };
+                        ui.collapsing("Components list", |ui| {
+                            for e in query.data.option.iter() {
+                                ui.label(e);
+                            }
+                        });
+                    });
+                })
+                .inner_rect;
+        }
+        drop(error);
+        let tree = EntityTree::build(content, self.bevy_version.paths());
+        let mut entities: Vec<Entity> = if self.config.spatial_filter.is_active() {
+            filter_entities(&self.config.spatial_filter, content, self.selected)
+        } else {
+            tree.roots.clone()
+        };
+        if let Some(search) = search {
+            entities.retain(|e| search.shows(e));
+        }
+        self.sort_entities(&mut entities, content);
+        if self.config.spatial_filter.is_active() && entities.is_empty() {
+            ui.label("No entities match the spatial filter.");
+        } else if search.is_some() && entities.is_empty() {
+            ui.label("No entities match the search.");
+        }
+        self.draw_pinned(ui, content);
+        self.draw_multi_selection(ctx, ui, content);
+        let gone = self.despawned.lock().unwrap().len();
+        let mut prune = false;
+        if gone > 0 {
+            ui.horizontal(|ui| {
+                ui.weak(format!("{gone} despawned, greyed out"));
+                prune = ui
+                    .small_button("Prune")
+                    .on_hover_text("Drop the last known components of despawned entities")
+                    .clicked();
+            });
+        }
+        let view = TreeView {
+            rows: content,
+            tree: &tree,
+            search,
+        };
+        let lines = self.tree_lines(ctx, &entities, &view);
+        *self.visible_entities.lock().unwrap() =
+            lines.iter().filter_map(TreeLine::entity).collect();
+        if egui::DragAndDrop::has_payload_of_type::<Entity>(ui.ctx()) {
+            let (_, dropped) =
+                ui.dnd_drop_zone::<Entity, ()>(egui::Frame::group(ui.style()), |ui| {
+                    ui.weak("Drop here to make it a root entity");
+                });
+            if let Some(dragged) = dropped.filter(|e| tree.parent(e).is_some()) {
+                self.dispatch(ctx, Msg::Reparent(*dragged, None), content);
+            }
+        }
+        let target = self
+            .selected
+            .filter(|_| self.scroll_to_selected)
+            .or(self.scroll_to_spawned)
+            .and_then(|target| lines.iter().position(|line| line.entity() == Some(target)));
+        let row_height = ui.spacing().interact_size.y;
+        let stride = row_height + ui.spacing().item_spacing.y;
+        let mut actions = Vec::new();
+        let area =
+            egui::ScrollArea::vertical().show_rows(ui, row_height, lines.len(), |ui, range| {
+                let left = ui.max_rect().left();
+                let width = ui.available_width();
+                let top = ui.max_rect().top() - range.start as f32 * stride;
+                let line_rect = |index: usize| {
+                    egui::Rect::from_min_size(
+                        egui::pos2(left, top + index as f32 * stride),
+                        egui::vec2(width, row_height),
+                    )
+                };
+                if let Some(index) = target {
+                    ui.scroll_to_rect(line_rect(index), Some(egui::Align::Center));
+                }
+                for index in range {
+                    let line = &lines[index];
+                    let builder = egui::UiBuilder::new()
+                        .max_rect(line_rect(index))
+                        .layout(egui::Layout::left_to_right(egui::Align::Center));
+                    let action = ui
+                        .allocate_new_ui(builder, |ui| self.draw_tree_line(ui, line, &view))
+                        .inner;
+                    actions.push(action);
+                }
+            });
+        for action in actions {
+            self.dispatch(ctx, action, content);
+        }
+        drop(live);
+        if prune {
+            self.run_command(ctx, Command::PruneDespawned);
+        }
+        area.inner_rect
+    }
+
+    /// The lines of the tree in display order: the entities passing the filters below
+    /// expanded parents, under the heading of their group when grouping.
+    fn tree_lines(&self, ctx: &egui::Context, roots: &[Entity], view: &TreeView) -> Vec<TreeLine> {
+        let mut lines = Vec::new();
+        let groups = &self.config.entity_groups;
+        if !self.config.group_entities || groups.is_empty() {
+            for root in roots {
+                self.push_lines(ctx, &mut lines, root, 0, view);
+            }
+            return lines;
+        }
+        // In the order of the groups, the rest last.
+        let mut grouped = vec![Vec::new(); groups.len() + 1];
+        for entity in roots {
+            let index = view.rows.get(entity).and_then(|row| group_of(row, groups));
+            grouped[index.unwrap_or(groups.len())].push(*entity);
+        }
+        for (index, entities) in grouped.into_iter().enumerate() {
+            if entities.is_empty() {
+                continue;
+            }
+            lines.push(TreeLine::Group {
+                index,
+                count: entities.len(),
+            });
+            if self.group_state(ctx, index).is_open() {
+                for entity in &entities {
+                    self.push_lines(ctx, &mut lines, entity, 0, view);
+                }
+            }
+        }
+        lines
+    }
+
+    fn push_lines(
+        &self,
+        ctx: &egui::Context,
+        lines: &mut Vec<TreeLine>,
+        entity: &Entity,
+        depth: usize,
+        view: &TreeView,
+    ) {
+        let Some(item) = view.rows.get(entity) else {
+            return;
+        };
+        if view.search.is_some_and(|search| !search.shows(entity)) {
+            return;
+        }
+        if self.config.skip_empty_entities && item.components.is_empty() {
+            return;
+        }
+        lines.push(TreeLine::Entity {
+            entity: *entity,
+            depth,
+        });
+        let mut children = view.tree.children(entity).to_vec();
+        if children.is_empty() {
+            return;
+        }
+        let mut state = self.node_state(ctx, entity);
+        // Keep the way to search matches open.
+        if view
+            .search
+            .is_some_and(|search| search.ancestors.contains(entity))
+        {
+            state.set_open(true);
+        }
+        if self.selected == Some(*entity) && tree_has_keyboard(ctx) {
+            if ctx.input(|i| i.key_pressed(egui::Key::ArrowRight)) {
+                state.set_open(true);
+            } else if ctx.input(|i| i.key_pressed(egui::Key::ArrowLeft)) {
+                state.set_open(false);
+            }
+        }
+        state.store(ctx);
+        if !state.is_open() {
+            return;
+        }
+        if self.config.entity_sort != EntitySort::Hierarchy {
+            self.sort_entities(&mut children, view.rows);
+        }
+        for child in &children {
+            self.push_lines(ctx, lines, child, depth + 1, view);
+        }
+    }
+
+    /// Whether `entity` is expanded in the tree. Scoped by profile so each connection
+    /// keeps its own expanded nodes.
+    fn node_state(&self, ctx: &egui::Context, entity: &Entity) -> CollapsingState {
+        let id = egui::Id::new(("entity_node", &self.config.profile, entity));
+        CollapsingState::load_with_default_open(ctx, id, false)
+    }
+
+    fn group_state(&self, ctx: &egui::Context, index: usize) -> CollapsingState {
+        let id = egui::Id::new(("entity_group", index, self.group_name(index)));
+        CollapsingState::load_with_default_open(ctx, id, true)
+    }
+
+    fn group_name(&self, index: usize) -> &str {
+        self.config
+            .entity_groups
+            .get(index)
+            .map_or("Ungrouped", |group| group.name.as_str())
+    }
+
+    fn draw_tree_line(&self, ui: &mut egui::Ui, line: &TreeLine, view: &TreeView) -> Msg {
+        match *line {
+            TreeLine::Group { index, count } => {
+                let mut state = self.group_state(ui.ctx(), index);
+                state.show_toggle_button(ui, paint_default_icon);
+                let title = RichText::new(format!("{} ({count})", self.group_name(index)));
+                if ui
+                    .add(egui::Label::new(title.strong()).sense(egui::Sense::click()))
+                    .clicked()
+                {
+                    state.toggle(ui);
+                }
+                state.store(ui.ctx());
+                Msg::None
+            }
+            TreeLine::Entity { entity, depth } => self.draw_entity(ui, &entity, depth, view),
+        }
+    }
+
+    /// One entity's line of the tree, indented by its `depth` below the roots.
+    fn draw_entity(
+        &self,
+        ui: &mut egui::Ui,
+        entity: &Entity,
+        depth: usize,
+        view: &TreeView,
+    ) -> Msg {
+        let mut action = Msg::None;
+        let Some(item) = view.rows.get(entity) else {
+            return action;
+        };
+        let age = self
+            .fetched_at
+            .lock()
+            .unwrap()
+            .get(entity)
+            .map(|fetched| fetched.elapsed());
+        let stale = self.config.live_mode
+            && age.is_some_and(|age| age.as_secs_f32() > self.config.stale_after);
+        let paths = self.bevy_version.paths();
+        let mut header_text = RichText::new(self.entity_label(entity, item)).strong();
+        if stale {
+            header_text = header_text.weak();
+        }
+        let gone = self
+            .despawned
+            .lock()
+            .unwrap()
+            .get(entity)
+            .map(|gone| gone.describe(*entity));
+        if gone.is_some() {
+            header_text = header_text.weak().strikethrough();
+        }
+        let is_selected = self.selected == Some(*entity);
+        if is_selected {
+            header_text = header_text.background_color(ui.visuals().selection.bg_fill);
+        } else if self.multi_selected.contains(entity) {
+            header_text =
+                header_text.background_color(ui.visuals().selection.bg_fill.gamma_multiply(0.6));
+        }
+        let spawned_ago = self
+            .spawned
+            .lock()
+            .unwrap()
+            .get(entity)
+            .map(|at| at.elapsed())
+            .filter(|ago| *ago < SPAWN_FLASH_DURATION);
+        if let Some(ago) = spawned_ago.filter(|_| self.config.follow_spawns) {
+            let fade = 1.0 - ago.as_secs_f32() / SPAWN_FLASH_DURATION.as_secs_f32();
+            header_text =
+                header_text.background_color(status_colors(ui.ctx()).ok.gamma_multiply(fade * 0.7));
+            self.config
+                .power_saver
+                .request_repaint_after(ui.ctx(), Duration::ZERO);
+        }
+        if self.config.show_activity {
+            let heat = self.activity.lock().unwrap().heat(entity);
+            header_text = header_text
+                .background_color(status_colors(ui.ctx()).change.gamma_multiply(heat * 0.6));
+        }
+        let mut header_clicked = false;
+        let multi_select = ui.input(|i| i.modifiers.command);
+        let mut state = self.node_state(ui.ctx(), entity);
+        let child_count = view.tree.children(entity).len();
+        let can_reparent = !self.config.read_only && self.supports(BRP_REPARENT_METHOD);
+        let can_rename = !self.config.read_only
+            && (self.supports(INSPECTOR_RENAME_METHOD) || self.supports(BRP_INSERT_METHOD));
+        let rename_id = egui::Id::new("rename_inline");
+        let renaming = ui
+            .data(|d| d.get_temp::<(Entity, String)>(rename_id))
+            .filter(|(renamed, _)| renamed == entity);
+        let header = ui.horizontal(|ui| {
+            ui.add_space(depth as f32 * ui.spacing().indent);
+            if child_count > 0 {
+                state.show_toggle_button(ui, paint_default_icon);
+            } else {
+                ui.add_space(ui.spacing().indent);
+            }
+            if can_reparent {
+                let handle =
+                    ui.dnd_drag_source(egui::Id::new(("drag_entity", entity)), *entity, |ui| {
+                        ui.weak("☰");
+                    });
+                labeled(handle.response, egui::WidgetType::Label, "Drag to reparent")
+                    .on_hover_text("Drag onto another entity to reparent");
+            }
+            let response = match renaming {
+                Some((_, mut name)) => {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut name)
+                            .hint_text("Name")
+                            .desired_width(160.0),
+                    );
+                    response.request_focus();
+                    if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                        ui.data_mut(|d| d.remove::<(Entity, String)>(rename_id));
+                    } else if response.lost_focus() {
+                        ui.data_mut(|d| d.remove::<(Entity, String)>(rename_id));
+                        if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                            action = Msg::Rename(*entity, name);
+                        }
+                    } else {
+                        ui.data_mut(|d| d.insert_temp(rename_id, (*entity, name)));
+                    }
+                    response
+                }
+                None => {
+                    let response =
+                        ui.add(egui::Label::new(header_text).sense(egui::Sense::click()));
+                    if can_rename && response.double_clicked() {
+                        let name = entity_name(item, paths).unwrap_or_default().to_owned();
+                        ui.data_mut(|d| d.insert_temp(rename_id, (*entity, name)));
+                    }
+                    if can_rename {
+                        response.on_hover_text("Double-click to rename")
+                    } else {
+                        response
+                    }
+                }
+            };
+            if child_count > 0 {
+                ui.weak(format!("({child_count})"))
+                    .on_hover_text(format!("{child_count} children"));
+            }
+            header_clicked = response.clicked();
+            let categories = categories(item.components.keys());
+            if !categories.is_empty() {
+                let icons: String = categories.iter().map(|c| c.icon()).collect();
+                let labels: Vec<&str> = categories.iter().map(|c| c.label()).collect();
+                labeled(ui.label(icons), egui::WidgetType::Label, &labels.join(", "))
+                    .on_hover_text(labels.join(", "));
+            }
+            let failed: Vec<String> = self
+                .rejected_types
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(t, _)| item.has.get(*t).and_then(|v| v.as_bool()) == Some(true))
+                .map(|(t, e)| format!("{}: {e}", short_name(t)))
+                .collect();
+            if !failed.is_empty() {
+                labeled(
+                    ui.label(RichText::new("⚠").color(status_colors(ui.ctx()).warning)),
+                    egui::WidgetType::Label,
+                    "Components failed to load",
+                )
+                .on_hover_text(format!(
+                    "Components the game failed to send:\n{}",
+                    failed.join("\n")
+                ));
+            }
+            if self.config.compact {
+                return;
+            }
+            if let Some(summary) = entity_summary(item) {
+                ui.label(RichText::new(summary).weak().italics());
+            }
+            self.draw_quick_toggles(ui, entity, item, &mut action);
+        });
+        if header_clicked && multi_select {
+            action = Msg::ToggleMultiSelect(*entity);
+        } else if header_clicked {
+            state.toggle(ui);
+            action = Msg::Select(*entity);
+        }
+        state.store(ui.ctx());
+        let header_response = header.response;
+        if self.is_lazy() && ui.is_rect_visible(header_response.rect) {
+            self.lazy.want(*entity);
+        }
+        if let Some(dragged) = header_response.dnd_hover_payload::<Entity>() {
+            let color = if view.tree.is_ancestor(&dragged, entity) {
+                status_colors(ui.ctx()).error
+            } else {
+                ui.visuals().selection.stroke.color
+            };
+            ui.painter()
+                .rect_stroke(header_response.rect, 2.0, egui::Stroke::new(1.5, color));
+        }
+        if let Some(dragged) = header_response.dnd_release_payload::<Entity>() {
+            let moved = view.tree.parent(&dragged) != Some(*entity);
+            if moved && !view.tree.is_ancestor(&dragged, entity) {
+                action = Msg::Reparent(*dragged, Some(*entity));
+            }
+        }
+        let header_response = match (age, gone) {
+            (_, Some(gone)) => header_response.on_hover_text(gone),
+            (Some(age), None) if stale => header_response
+                .on_hover_text(format!("Stale, last updated {:.1}s ago", age.as_secs_f32())),
+            _ => header_response,
+        };
+        header_response.context_menu(|ui| {
+            if ui.button("Copy entity JSON").clicked() {
+                action = Msg::CopyComponents(*entity, None);
+                ui.close_menu();
+            }
+            if !self.config.read_only {
+                let copied = self.copied_components.as_ref().map_or(0, |c| c.len());
+                if ui
+                    .add_enabled(
+                        copied > 0 && self.supports(BRP_INSERT_METHOD),
+                        egui::Button::new(format!("Paste components ({copied})")),
+                    )
+                    .on_hover_text("Insert the last copied components into this entity")
+                    .on_disabled_hover_text("Copy an entity or a component first")
+                    .clicked()
+                {
+                    action = Msg::PasteComponents(*entity);
+                    ui.close_menu();
+                }
+            }
+            let pinned = self
+                .config
+                .pinned_entities
+                .iter()
+                .any(|pin| pin.matches(*entity, item, paths));
+            if ui
+                .button(if pinned { "Unpin" } else { "Pin to top" })
+                .clicked()
+            {
+                action = Msg::TogglePinEntity(*entity);
+                ui.close_menu();
+            }
+            if ui.button("Copy hierarchy path").clicked() {
+                ui.ctx().copy_text(view.tree.path(entity, view.rows, paths));
+                ui.close_menu();
+            }
+            if ui.button("Find references").clicked() {
+                action = Msg::FindReferences(*entity);
+                ui.close_menu();
+            }
+            if !self.config.read_only {
+                ui.menu_button("Rename", |ui| {
+                    let id = ui.id().with("rename");
+                    let mut name: String = ui
+                        .data_mut(|d| d.get_temp(id))
+                        .unwrap_or_else(|| entity_name(item, paths).unwrap_or_default().to_owned());
+                    let response = ui.text_edit_singleline(&mut name);
+                    response.request_focus();
+                    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        ui.data_mut(|d| d.remove::<String>(id));
+                        action = Msg::Rename(*entity, name);
+                        ui.close_menu();
+                    } else {
+                        ui.data_mut(|d| d.insert_temp(id, name));
+                    }
+                });
+            }
+            if ui.button("Export as scene...").clicked() {
+                action = Msg::ExportScene(Some(*entity));
+                ui.close_menu();
+            }
+            if ui.button("Export as graph...").clicked() {
+                action = Msg::ExportGraph(Some(*entity));
+                ui.close_menu();
+            }
+            if !self.config.read_only
+                && self.supports(BRP_DESTROY_METHOD)
+                && ui
+                    .button("Remove entity")
+                    .on_hover_text("Destroy the entity in the game (Delete)")
+                    .clicked()
+            {
+                action = Msg::Remove(*entity);
+                ui.close_menu();
+            }
+        });
+        if self.config.show_activity {
+            let activity = self.activity.lock().unwrap();
+            header_response.on_hover_text(format!(
+                "Changed in {} of the last {} fetches",
+                activity.changes(entity),
+                activity.polls()
+            ));
+        }
+        action
+    }
+
+    pub(super) fn draw_components(
+        &self,
+        ui: &mut egui::Ui,
+        entity: &Entity,
+        item: &BrpQueryRow,
+    ) -> Msg {
+        let mut action = Msg::None;
+        let paths = self.bevy_version.paths();
+        let changed: HashMap<String, Vec<String>> = self
+            .changes
+            .lock()
+            .unwrap()
+            .last_poll(entity)
+            .map(|change| (change.component.clone(), change.fields.clone()))
+            .collect();
+        let change_color = status_colors(ui.ctx()).change;
+        let ticks = self
+            .change_ticks
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|selected| selected.entity == *entity)
+            .and_then(|selected| selected.ticks.clone());
+        for (key, field) in self.sorted_components(item) {
+            if key == paths.parent || key == paths.children {
+                continue;
+            }
+            let fields = changed.get(key);
+            if self.search.changed_only && fields.is_none() {
+                continue;
+            }
+
+            let pinned = self.config.pinned_components.contains(key);
+            let name = self.aliases.type_name(key);
+            let mut title = if pinned {
+                format!("📌 {name}")
+            } else {
+                name.to_owned()
+            };
+            let changed_ago = ticks.as_ref().and_then(|ticks| ticks.changed_ago(key));
+            if let Some(ago) = changed_ago {
+                title = format!("{title}  ⟳ {ago}");
+            }
+            let title = match fields {
+                Some(_) => RichText::new(format!("● {title}")).color(change_color),
+                None => RichText::new(title),
+            };
+            let response = if field.as_object().is_some_and(serde_json::Map::is_empty) {
+                ui.label(title.strong())
+            } else {
+                egui::CollapsingHeader::new(title)
+                    .id_salt(key)
+                    .default_open(false)
+                    .show(ui, |ui| {
+                        self.draw_component(ui, entity, key, field, &mut action);
+                    })
+                    .header_response
+            };
+            let response = match fields {
+                Some(fields) if !fields.is_empty() => response
+                    .on_hover_text(format!("Changed in the last fetch: {}", fields.join(", "))),
+                Some(_) => response.on_hover_text("Changed in the last fetch"),
+                None => response,
+            };
+            let response = match ticks.as_ref().and_then(|t| t.components.get(key)) {
+                Some(component) => response.on_hover_text(format!(
+                    "Added at tick {}, last changed at tick {}, {} ticks ago",
+                    component.added,
+                    component.changed,
+                    changed_ago.unwrap_or_default()
+                )),
+                None => response,
+            };
+            if response.hovered() {
+                set_docs_target(ui.ctx(), key);
+            }
+            let can_remove = !self.config.read_only && self.supports(BRP_REMOVE_METHOD);
+            response.context_menu(|ui| {
+                if ui
+                    .button(if pinned { "Unpin" } else { "Pin to top" })
+                    .clicked()
+                {
+                    action = Msg::TogglePin(key.clone());
+                    ui.close_menu();
+                }
+                if ui.button("Copy component JSON").clicked() {
+                    action = Msg::CopyComponents(*entity, Some(key.clone()));
+                    ui.close_menu();
+                }
+                if ui
+                    .button("History")
+                    .on_hover_text("How it changed over the last polls")
+                    .clicked()
+                {
+                    action = Msg::ShowHistory(*entity, key.clone());
+                    ui.close_menu();
+                }
+                let fields = leaf_paths(field);
+                ui.add_enabled_ui(!fields.is_empty(), |ui| {
+                    ui.menu_button("Bookmark field", |ui| {
+                        egui::ScrollArea::vertical()
+                            .max_height(300.0)
+                            .show(ui, |ui| {
+                                for path in fields {
+                                    let label = match path.as_str() {
+                                        "" => "(whole value)",
+                                        path => path,
+                                    };
+                                    if ui.button(label).clicked() {
+                                        action = Msg::BookmarkField(*entity, key.clone(), path);
+                                        ui.close_menu();
+                                    }
+                                }
+                            });
+                    });
+                });
+                if ui
+                    .add_enabled(can_remove, egui::Button::new("Remove component"))
+                    .clicked()
+                {
+                    action = Msg::RemoveComponent(*entity, key.clone());
+                    ui.close_menu();
+                }
+            });
+        }
+        action
+    }
+
+    /// Sorts entities drawn next to each other by the chosen order. By hierarchy, they
+    /// follow a depth-first walk, so siblings keep the order of their `Children`.
+    pub(super) fn sort_entities(
+        &self,
+        entities: &mut [Entity],
+        content: &HashMap<Entity, BrpQueryRow>,
+    ) {
+        match self.config.entity_sort {
+            EntitySort::Hierarchy => {
+                let order = hierarchy_order(content, self.bevy_version.paths());
+                entities.sort_by_key(|e| order.get(e).copied().unwrap_or(usize::MAX));
+            }
+            EntitySort::Name => entities.sort_by_cached_key(|e| {
+                let label = content
+                    .get(e)
+                    .map(|row| self.entity_label(e, row).to_lowercase());
+                (label, *e)
+            }),
+            EntitySort::Id => entities.sort(),
+        }
+    }
+
+    /// Components of the entity, pinned types first in pin order, then alphabetically
+    /// by short type name.
+    pub(super) fn sorted_components<'a>(
+        &self,
+        item: &'a BrpQueryRow,
+    ) -> Vec<(&'a String, &'a serde_json::Value)> {
+        let pinned = &self.config.pinned_components;
+        let mut components: Vec<_> = item.components.iter().collect();
+        components.sort_by_cached_key(|(key, _)| {
+            (
+                pinned.iter().position(|p| p == *key).unwrap_or(usize::MAX),
+                short_name(key).to_lowercase(),
+            )
+        });
+        components
+    }
+
+    /// Toggles for the most common debugging tweaks, shown right on the entity header row.
+    pub(super) fn draw_quick_toggles(
+        &self,
+        ui: &mut egui::Ui,
+        entity: &Entity,
+        item: &BrpQueryRow,
+        action: &mut Msg,
+    ) {
+        let can_insert = !self.config.read_only && self.supports(BRP_INSERT_METHOD);
+        ui.add_enabled_ui(can_insert, |ui| {
+            if let Some(visibility) = item.components.get(VISIBILITY).and_then(|v| v.as_str()) {
+                ui.add_space(8.0);
+                for option in ["Inherited", "Hidden", "Visible"] {
+                    if ui.selectable_label(visibility == option, option).clicked()
+                        && visibility != option
+                    {
+                        *action = Msg::Insert(
+                            *entity,
+                            VISIBILITY.to_owned(),
+                            serde_json::Value::String(option.to_owned()),
+                        );
+                    }
+                }
+            }
+            if let Some(layers) = item
+                .components
+                .get(RENDER_LAYERS)
+                .and_then(|v| v.as_array())
+            {
+                ui.add_space(8.0);
+                let mask = layers.first().and_then(|v| v.as_u64()).unwrap_or_default();
+                let enabled: Vec<String> = (0..64)
+                    .filter(|layer| mask & (1 << layer) != 0)
+                    .map(|layer| layer.to_string())
+                    .collect();
+                ui.menu_button(format!("Layers: {}", enabled.join(", ")), |ui| {
+                    for layer in 0..8 {
+                        let mut on = mask & (1 << layer) != 0;
+                        if ui.checkbox(&mut on, format!("Layer {layer}")).changed() {
+                            let mut layers = layers.clone();
+                            if layers.is_empty() {
+                                layers.push(0.into());
+                            }
+                            layers[0] = (mask ^ (1 << layer)).into();
+                            *action = Msg::Insert(
+                                *entity,
+                                RENDER_LAYERS.to_owned(),
+                                serde_json::Value::Array(layers),
+                            );
+                        }
+                    }
+                });
+            }
+        });
+    }
+
+    pub(super) fn draw_component(
+        &self,
+        ui: &mut egui::Ui,
+        entity: &Entity,
+        key: &str,
+        field: &serde_json::Value,
+        action: &mut Msg,
+    ) {
+        if self.config.redaction.hides(key) {
+            let redacted = self.config.redaction.redact(key, field);
+            value_format(ui.ctx()).show(ui, &redacted);
+            ui.weak("🔒 Redacted, see Settings > Redaction");
+            return;
+        }
+        let edit_key = (*entity, key.to_owned());
+        let mut edits = self.edits.lock().unwrap();
+        let Some(edit) = edits.get_mut(&edit_key) else {
+            let view = ComponentView {
+                entity: *entity,
+                type_path: key,
+                value: field,
+                editable: !self.config.read_only && self.supports(BRP_INSERT_METHOD),
+                snap: Snap {
+                    grid: self.config.snap_grid,
+                    angle: self.config.snap_angle,
+                },
+            };
+            match self.renderers.draw(ui, &view) {
+                Some(Msg::None) => {}
+                Some(msg) => *action = msg,
+                None => {
+                    value_format(ui.ctx()).show(ui, field);
+                    for (path, handle) in asset_handles(field) {
+                        if ui
+                            .small_button(format!("🔍 Entities using {}", handle.label()))
+                            .on_hover_text(format!("Handle at {key}{path}"))
+                            .clicked()
+                        {
+                            *action = Msg::FindAssetUsers(handle);
+                        }
+                    }
+                }
+            }
+            ui.horizontal(|ui| {
+                if !self.config.read_only
+                    && self.supports(BRP_INSERT_METHOD)
+                    && ui.button("Edit").clicked()
+                {
+                    edits.insert(edit_key, ComponentEdit::new(field));
+                }
+                if !self.config.read_only
+                    && self.supports(BRP_REMOVE_METHOD)
+                    && ui
+                        .button("🗑 Remove")
+                        .on_hover_text("Remove the component from the entity")
+                        .clicked()
+                {
+                    *action = Msg::RemoveComponent(*entity, key.to_owned());
+                }
+            });
+            return;
+        };
+        let mut cancel = false;
+        match &edit.state {
+            EditState::Editing => {
+                ui.horizontal(|ui| {
+                    ui.selectable_value(&mut edit.view, EditView::Fields, "Fields");
+                    if self.schemas.lock().unwrap().contains(key) {
+                        ui.selectable_value(&mut edit.view, EditView::Form, "Form")
+                            .on_hover_text("Edit through the type's schema, with enum dropdowns");
+                    }
+                    ui.selectable_value(&mut edit.view, EditView::Json, "JSON");
+                    if ui
+                        .button("🎲 Randomize")
+                        .on_hover_text("Fill the fields with random values")
+                        .clicked()
+                    {
+                        let current = edit.parsed().unwrap_or_else(|_| edit.original.clone());
+                        let schemas = self.schemas.lock().unwrap();
+                        edit.set(&schemas.randomize(key, &current, &mut Rng::new()));
+                    }
+                });
+                if edit.view == EditView::Json {
+                    ui.add(
+                        egui::TextEdit::multiline(&mut edit.text)
+                            .code_editor()
+                            .desired_width(f32::INFINITY),
+                    );
+                } else if edit.view == EditView::Form {
+                    let schemas = self.schemas.lock().unwrap();
+                    ui.push_id(("form", key), |ui| edit.draw_form(ui, &schemas, key));
+                } else {
+                    let annotations = self.aliases.annotations(key);
+                    let snap = Snap {
+                        grid: self.config.snap_grid,
+                        angle: self.config.snap_angle,
+                    };
+                    ui.push_id(("fields", key), |ui| {
+                        edit.draw_fields(ui, annotations, snap)
+                    });
+                }
+                let warnings = edit
+                    .parsed()
+                    .map(|mine| self.aliases.annotations(key).warnings(&mine))
+                    .unwrap_or_default();
+                for warning in warnings {
+                    ui.colored_label(ui.visuals().warn_fg_color, format!("⚠ {warning}"));
+                }
+                let modified = edit
+                    .parsed()
+                    .map(|mine| modified_fields(field, &mine))
+                    .unwrap_or_default();
+                let mut revert = None;
+                for path in modified {
+                    ui.horizontal(|ui| {
+                        let label = if path.is_empty() { "/" } else { &path };
+                        let response = labeled(
+                            ui.small_button("↺"),
+                            egui::WidgetType::Button,
+                            &format!("Revert {label}"),
+                        );
+                        if response
+                            .on_hover_text("Restore the value from the last fetch")
+                            .clicked()
+                        {
+                            revert = Some(path.clone());
+                        }
+                        ui.label(RichText::new(label).monospace());
+                    });
+                }
+                if let Some(path) = revert {
+                    edit.revert_field(field, &path);
+                }
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(!self.config.read_only, egui::Button::new("Apply"))
+                        .clicked()
+                    {
+                        *action = Msg::ApplyEdit(*entity, key.to_owned());
+                    }
+                    if ui
+                        .add_enabled(!self.config.read_only, egui::Button::new("Stage"))
+                        .on_hover_text("Apply later together with the other staged edits")
+                        .clicked()
+                    {
+                        match edit.parsed() {
+                            Ok(_) => {
+                                edit.state = EditState::Staged;
+                                edit.error = None;
+                            }
+                            Err(e) => edit.error = Some(e),
+                        }
+                    }
+                    if ui.button("Cancel").clicked() {
+                        cancel = true;
+                    }
+                });
+            }
+            EditState::Staged => {
+                ui.label(RichText::new(&edit.text).monospace());
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new("Staged").italics());
+                    if ui.button("Unstage").clicked() {
+                        edit.state = EditState::Editing;
+                    }
+                });
+            }
+            EditState::Checking | EditState::Applying => {
+                ui.horizontal(|ui| {
+                    power::spinner(ui);
+                    ui.label("Applying...");
+                });
+            }
+            EditState::Conflict(remote) => {
+                ui.label(
+                    RichText::new("The game changed this component since you started editing.")
+                        .color(status_colors(ui.ctx()).warning),
+                );
+                ui.collapsing("Current value", |ui| {
+                    ui.label(serde_json::to_string_pretty(remote).unwrap_or_default());
+                });
+                ui.horizontal(|ui| {
+                    for (label, resolution) in [
+                        ("Overwrite", ConflictResolution::Overwrite),
+                        ("Merge my changed fields", ConflictResolution::Merge),
+                        ("Discard", ConflictResolution::Discard),
+                    ] {
+                        let enabled =
+                            !self.config.read_only || resolution == ConflictResolution::Discard;
+                        if ui.add_enabled(enabled, egui::Button::new(label)).clicked() {
+                            *action = Msg::ResolveConflict(*entity, key.to_owned(), resolution);
+                        }
+                    }
+                });
+            }
+        }
+        if let Some(error) = &edit.error {
+            ui.label(RichText::new(error).color(status_colors(ui.ctx()).error));
+        }
+        if cancel {
+            edits.remove(&edit_key);
+        }
+    }
+}
@@ -0,0 +1,1906 @@
+//! Requests to the game and the callbacks storing their results.
+
+use super::*;
+
+impl TemplateApp {
+    /// Keeps a `+watch` subscription open for the selected entity and closes the ones of
+    /// entities no longer focused on. Subscriptions are renewed when components are added.
+    pub(super) fn sync_watches(&mut self, ctx: &egui::Context) {
+        let wanted: Vec<Entity> = self
+            .selected
+            .filter(|_| {
+                self.config.watch_selected
+                    && self.onboarding_complete
+                    && self.supports(BRP_GET_AND_WATCH_METHOD)
+            })
+            .into_iter()
+            .collect();
+        self.watches.retain(|entity, _| wanted.contains(entity));
+        for entity in wanted {
+            let components = match self.watches.get(&entity) {
+                Some(watch) => match watch.renewal() {
+                    Some(components) => {
+                        // Changes streamed to the subscription being replaced.
+                        watch.apply();
+                        components
+                    }
+                    None => continue,
+                },
+                None => {
+                    let rows = self.components.lock().unwrap();
+                    let Some(row) = rows.get(&entity) else {
+                        continue;
+                    };
+                    row.components.keys().cloned().collect()
+                }
+            };
+            let watch = EntityWatch::start(
+                &self.endpoint(),
+                entity,
+                components,
+                self.components.clone(),
+                self.fetched_at.clone(),
+                ctx.clone(),
+            );
+            self.watches.insert(entity, watch);
+        }
+        let policy = self.refresh_policy(RefreshTarget::Watches);
+        if !self.watches.is_empty() && self.refresh.due(ctx, RefreshTarget::Watches, policy) {
+            for watch in self.watches.values() {
+                watch.apply();
+            }
+        }
+    }
+
+    /// Asks the companion plugin when the components of the selected entity last changed,
+    /// again whenever its row was fetched anew.
+    pub(super) fn sync_change_ticks(&self, ctx: &egui::Context) {
+        let Some(entity) = self
+            .selected
+            .filter(|_| self.supports(INSPECTOR_CHANGE_TICKS_METHOD))
+        else {
+            return;
+        };
+        let row_fetched = self.fetched_at.lock().unwrap().get(&entity).copied();
+        let known = self
+            .change_ticks
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|known| known.entity == entity && known.row_fetched == row_fetched);
+        if known || self.change_ticks_in_flight.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let in_flight = self.change_ticks_in_flight.clone();
+        let change_ticks = self.change_ticks.clone();
+        let egui_ctx = ctx.clone();
+        let request = helper::make_request(
+            serde_json::json!({ "entity": entity }),
+            INSPECTOR_CHANGE_TICKS_METHOD,
+            &self.endpoint(),
+        );
+        transport::fetch(request, move |response| {
+            let ticks = helper::parse_response::<ChangeTicks>(response).ok();
+            *change_ticks.lock().unwrap() = Some(SelectedTicks {
+                entity,
+                row_fetched,
+                ticks,
+            });
+            in_flight.store(false, Ordering::SeqCst);
+            egui_ctx.request_repaint();
+        });
+    }
+
+    /// Has the game run the search whenever it changed or the world was fetched again, so
+    /// the text and component filters see every entity and component, fetched or not.
+    pub(super) fn sync_remote_search(&self, ctx: &egui::Context) {
+        let Some(search) = self
+            .search
+            .remote()
+            .filter(|_| self.supports(INSPECTOR_SEARCH_METHOD))
+        else {
+            return;
+        };
+        let poll = self.activity.lock().unwrap().polls();
+        let known = self
+            .remote_search
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|known| known.search == search && known.poll == poll);
+        if known || self.remote_search_in_flight.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let in_flight = self.remote_search_in_flight.clone();
+        let remote_search = self.remote_search.clone();
+        let egui_ctx = ctx.clone();
+        let request = helper::make_request(&search, INSPECTOR_SEARCH_METHOD, &self.endpoint());
+        transport::fetch(request, move |response| {
+            let entities = helper::parse_response::<Vec<Entity>>(response)
+                .ok()
+                .map(|entities| entities.into_iter().collect());
+            *remote_search.lock().unwrap() = Some(RemoteMatches {
+                search,
+                poll,
+                entities,
+            });
+            in_flight.store(false, Ordering::SeqCst);
+            egui_ctx.request_repaint();
+        });
+    }
+
+    pub(super) fn fetch_list(&self) {
+        let download_store = self.download.clone();
+        let error_info = self.error_info.clone();
+        let error_raw = self.error_raw.clone();
+        let query_param = self.query_list.clone();
+        let download_id = Download::start(&download_store);
+
+        let request = helper::make_empty_request(BRP_LIST_METHOD, &self.endpoint());
+        let download = self.download.clone();
+        let handle = transport::fetch(request, move |response| {
+            if !Download::finish(&download_store, download_id) {
+                return;
+            }
+            let raw = response.as_ref().ok().map(helper::raw_text);
+            let type_list = match helper::parse_result::<TypeList>(response) {
+                Ok(TypeList(type_list)) => type_list,
+                Err(e) => {
+                    report_failure(&error_info, &error_raw, e, raw);
+                    return;
+                }
+            };
+
+            *query_param.lock().unwrap() = Some(BrpQueryParams {
+                data: BrpQuery {
+                    components: vec![],
+                    option: type_list,
+                    has: vec![],
+                },
+                filter: BrpQueryFilter::default(),
+            });
+            *error_info.lock().unwrap() = None;
+        });
+        Download::attach(&download, download_id, handle);
+    }
+
+    pub(super) fn fetch_components(&self, ctx: &egui::Context) {
+        let download_store = self.download.clone();
+        let query_param = self.query_list.clone();
+        if query_param.lock().unwrap().is_none() {
+            return;
+        }
+        let components = self.components.clone();
+        let fetched_at = self.fetched_at.clone();
+        let despawned = self.despawned.clone();
+        let error_info = self.error_info.clone();
+        let query_list = self.query_list.clone();
+        let activity = self.activity.clone();
+        let changes = self.changes.clone();
+        let spawned = self.spawned.clone();
+        let spawn_to_follow = self.spawn_to_follow.clone();
+        let rejected_types = self.rejected_types.clone();
+        let refresh_requested = self.refresh_requested.clone();
+        let history = self.history.clone();
+        let timeline = self.timeline.clone();
+        let timeline_length = self.config.timeline_length;
+        let timeline_budget = self.config.retained_memory_mb * 1024 * 1024;
+        let download_id = Download::start(&download_store);
+        let egui_ctx = ctx.clone();
+
+        // Refresh the type list together with the query in a single batch exchange, so
+        // types registered after connecting show up in the next fetch.
+        let custom = self.config.query.as_ref().map(CustomQuery::params);
+        let is_custom = custom.is_some();
+        let lazy = self.is_lazy();
+        let query = match custom {
+            Some(params) => helper::create_request(Some(params), BRP_QUERY_METHOD),
+            None if lazy => {
+                let paths = self.bevy_version.paths();
+                let skeleton = BrpQueryParams {
+                    data: BrpQuery {
+                        components: vec![],
+                        option: [paths.name, paths.parent, paths.children]
+                            .map(str::to_owned)
+                            .to_vec(),
+                        has: vec![],
+                    },
+                    filter: BrpQueryFilter::default(),
+                };
+                helper::create_request(Some(skeleton), BRP_QUERY_METHOD)
+            }
+            None => helper::create_request(Some(&*query_param.lock().unwrap()), BRP_QUERY_METHOD),
+        };
+        let loaded = self.lazy.loaded.clone();
+        let narrowed = self.lazy.narrowed.clone();
+        // Only full fetches of the world can be narrowed down to the skeleton.
+        let limits = (!lazy && !is_custom && !self.lazy.allow_oversized).then(|| {
+            let megabytes = self.config.narrow_above_mb;
+            let seconds = self.config.narrow_after_secs;
+            (megabytes * 1024 * 1024, Duration::from_secs_f32(seconds))
+        });
+        let visible_only =
+            self.config.visible_only && self.supports(INSPECTOR_QUERY_VISIBLE_METHOD);
+        // Only entities missing from a fetch of the whole world are known to be gone.
+        let whole_world = !is_custom && !visible_only;
+        let query = if visible_only {
+            helper::create_request(
+                Some(serde_json::json!({
+                    "query": query.params,
+                    "margin": self.config.visible_margin,
+                })),
+                INSPECTOR_QUERY_VISIBLE_METHOD,
+            )
+        } else {
+            query
+        };
+        let mut calls = vec![
+            helper::create_request::<String>(None, BRP_LIST_METHOD),
+            query,
+        ];
+        // Answered in the same frame as the query, so the rows can be tagged with it.
+        if self.supports(INSPECTOR_FRAME_METHOD) {
+            calls.push(helper::create_request::<String>(
+                None,
+                INSPECTOR_FRAME_METHOD,
+            ));
+        }
+        let data_frame = self.data_frame.clone();
+        let request = helper::make_batch_request(&calls, &self.endpoint());
+        let started = Instant::now();
+        let download = self.download.clone();
+        let error_raw = self.error_raw.clone();
+        let handle = transport::fetch(request, move |response| {
+            if !Download::finish(&download_store, download_id) {
+                return;
+            }
+            let duration = started.elapsed();
+            // Bodies are only turned into text when something went wrong, they can be big.
+            let http_raw = response
+                .as_ref()
+                .ok()
+                .filter(|r| !r.ok)
+                .map(helper::raw_text);
+            let response = match helper::check_response(response) {
+                Ok(response) => response,
+                Err(e) => {
+                    report_failure(&error_info, &error_raw, e, http_raw);
+                    egui_ctx.request_repaint(); // Wake up UI thread
+                    return;
+                }
+            };
+            // Checked before parsing, an enormous payload would hold up the inspector
+            // for as long as parsing and diffing it takes.
+            let payload = response.bytes.len();
+            if let Some((max_payload, max_duration)) = limits {
+                if payload > max_payload || duration > max_duration {
+                    history.lock().unwrap().push(QueryRecord {
+                        method: calls[1].method.clone(),
+                        params: calls[1].params.clone(),
+                        duration,
+                        payload,
+                        result: Err("Too big, narrowed to names and the hierarchy".to_owned()),
+                    });
+                    *narrowed.lock().unwrap() = Some(Oversized { payload, duration });
+                    *refresh_requested.lock().unwrap() = true;
+                    egui_ctx.request_repaint();
+                    return;
+                }
+            }
+            let results = match helper::parse_batch(&response, &calls) {
+                Ok(results) => results,
+                Err(e) => {
+                    report_failure(
+                        &error_info,
+                        &error_raw,
+                        e,
+                        Some(helper::raw_text(&response)),
+                    );
+                    egui_ctx.request_repaint();
+                    return;
+                }
+            };
+            let mut results = results.into_iter();
+            let (Some(list), Some(query)) = (results.next(), results.next()) else {
+                unreachable!("one result per call");
+            };
+            let frame = results
+                .next()
+                .and_then(|frame| frame.ok())
+                .and_then(|frame| frame.as_u64());
+            if let Ok(TypeList(type_list)) = list.and_then(TypeList::decode) {
+                if let Some(query) = query_list.lock().unwrap().as_mut() {
+                    query.data.option = type_list;
+                    exclude_rejected(query, &rejected_types.lock().unwrap());
+                }
+            }
+            let query = query.and_then(QueryRows::decode).map(|rows| rows.0);
+            history.lock().unwrap().push(QueryRecord {
+                method: calls[1].method.clone(),
+                params: calls[1].params.clone(),
+                duration,
+                payload,
+                result: query
+                    .as_ref()
+                    .map(|rows| rows.len())
+                    .map_err(|e| e.to_string()),
+            });
+            match query {
+                Ok(r) => {
+                    let mut rows = r.to_hash_map();
+                    let mut components = components.lock().unwrap();
+                    let mut despawned = despawned.lock().unwrap();
+                    // Rows of entities known to be gone aren't compared with the poll.
+                    let gone_rows = despawned.take_rows(&mut components);
+                    if lazy {
+                        // Components loaded before stay until the entity is loaded again.
+                        for (entity, row) in rows.iter_mut() {
+                            let Some(old) = components.get(entity) else {
+                                continue;
+                            };
+                            for (key, value) in &old.components {
+                                row.components
+                                    .entry(key.clone())
+                                    .or_insert_with(|| value.clone());
+                            }
+                        }
+                        loaded.lock().unwrap().clear();
+                    }
+                    activity.lock().unwrap().record(&components, &rows);
+                    changes.lock().unwrap().record(&components, &rows);
+                    let now = Instant::now();
+                    // Everything is new on the first fetch, that is not worth highlighting.
+                    if !components.is_empty() {
+                        let mut spawned = spawned.lock().unwrap();
+                        spawned.retain(|_, at| at.elapsed() < SPAWN_FLASH_DURATION);
+                        for entity in rows.keys().filter(|e| !components.contains_key(*e)) {
+                            spawned.insert(*entity, now);
+                            *spawn_to_follow.lock().unwrap() = Some(*entity);
+                        }
+                    }
+                    *fetched_at.lock().unwrap() = rows.keys().map(|e| (*e, now)).collect();
+                    let state = TimelineState::new(now, frame, Arc::new(rows.clone()));
+                    timeline
+                        .lock()
+                        .unwrap()
+                        .push(state, timeline_length, timeline_budget);
+                    if whole_world {
+                        despawned.carry_over(&components, gone_rows, &mut rows, now);
+                    } else {
+                        despawned.clear();
+                    }
+                    *components = rows;
+                    *data_frame.lock().unwrap() = frame;
+                    *error_info.lock().unwrap() = None;
+                }
+                // The types of a custom query are the user's to fix.
+                Err(err) if is_custom => {
+                    let raw = Some(helper::raw_text(&response));
+                    report_failure(&error_info, &error_raw, err.context("Custom query"), raw);
+                }
+                Err(err) => {
+                    let mut v = query_list.lock().unwrap();
+                    let mut s = (*v).clone().unwrap();
+                    let mut rejected_types = rejected_types.lock().unwrap();
+                    let message = err.to_string();
+                    let failed: Vec<String> = s
+                        .data
+                        .option
+                        .iter()
+                        .filter(|s| message.contains(*s))
+                        .map(|s| s.to_owned())
+                        .collect();
+                    if failed.is_empty() {
+                        let raw = Some(helper::raw_text(&response));
+                        report_failure(&error_info, &error_raw, err, raw);
+                    } else {
+                        // Only the failing components are dropped, the rest is fetched
+                        // again right away instead of blanking the whole inspector.
+                        for type_path in failed {
+                            rejected_types.insert(type_path, message.clone());
+                        }
+                        *refresh_requested.lock().unwrap() = true;
+                    }
+                    exclude_rejected(&mut s, &rejected_types);
+                    *v = Some(s);
+                }
+            }
+            egui_ctx.request_repaint(); // Wake up UI thread
+        });
+        Download::attach(&download, download_id, handle);
+    }
+
+    /// A live poll, of the selected entity's subtree alone when polling is scoped to it.
+    pub(super) fn live_fetch(&self, ctx: &egui::Context) {
+        match self.selected.filter(|_| self.config.live_subtree) {
+            Some(root) if self.components.lock().unwrap().contains_key(&root) => {
+                self.fetch_subtree(ctx, root);
+            }
+            _ => self.fetch_components(ctx),
+        }
+    }
+
+    /// Refreshes `root` and its descendants with `bevy/get` calls, the rest of the world
+    /// keeps its last fetched state.
+    fn fetch_subtree(&self, ctx: &egui::Context, root: Entity) {
+        let Some(types) = self
+            .query_list
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|query| query.data.option.clone())
+        else {
+            return;
+        };
+        let paths = self.bevy_version.paths();
+        let entities = {
+            let rows = self.components.lock().unwrap();
+            let tree = EntityTree::build(&rows, paths);
+            let mut entities = Vec::new();
+            let mut seen = std::collections::HashSet::new();
+            let mut stack = vec![root];
+            while let Some(entity) = stack.pop() {
+                if !seen.insert(entity) {
+                    continue;
+                }
+                entities.push(entity);
+                stack.extend_from_slice(tree.children(&entity));
+                // Children spawned since the last fetch are only listed by their parent.
+                if let Some(row) = rows.get(&entity) {
+                    stack.extend(children(row, paths));
+                }
+            }
+            entities
+        };
+        let calls = entities
+            .iter()
+            .map(|entity| multi_get::get_call(*entity, &types))
+            .collect();
+        let download_store = self.download.clone();
+        let download_id = Download::start(&download_store);
+        let components = self.components.clone();
+        let fetched_at = self.fetched_at.clone();
+        let despawned = self.despawned.clone();
+        let activity = self.activity.clone();
+        let changes = self.changes.clone();
+        let error_info = self.error_info.clone();
+        let egui_ctx = ctx.clone();
+        let max_in_flight = self.config.batch_max_in_flight;
+        multi_get::get_many(self.endpoint(), calls, max_in_flight, move |joined| {
+            if !Download::finish(&download_store, download_id) {
+                return;
+            }
+            if let Some(e) = joined.total_failure() {
+                *error_info.lock().unwrap() = Some(e.clone().context("Polling the subtree"));
+                egui_ctx.request_repaint();
+                return;
+            }
+            let partial_failure = joined
+                .partial_failure()
+                .map(|e| e.context("Polling the subtree"));
+            let mut components = components.lock().unwrap();
+            let mut despawned = despawned.lock().unwrap();
+            let previous: HashMap<Entity, BrpQueryRow> = entities
+                .iter()
+                .filter(|entity| !despawned.contains(entity))
+                .filter_map(|entity| Some((*entity, components.get(entity)?.clone())))
+                .collect();
+            let mut current = HashMap::new();
+            for (entity, result) in entities.into_iter().zip(joined.results) {
+                // Nothing is known about entities whose batch failed, they keep their rows.
+                let Some(result) = result else {
+                    if let Some(row) = previous.get(&entity) {
+                        current.insert(entity, row.clone());
+                    }
+                    continue;
+                };
+                match result.and_then(Components::decode) {
+                    Ok(result) => {
+                        let row = BrpQueryRow {
+                            entity,
+                            components: result.components.into_iter().collect(),
+                            has: HashMap::new(),
+                        };
+                        current.insert(entity, row);
+                    }
+                    // Despawned since the last poll.
+                    Err(InspectorError::JsonRpc { .. }) => {}
+                    // Anything else says nothing about the entity, it keeps its old row.
+                    Err(_) => {
+                        if let Some(row) = previous.get(&entity) {
+                            current.insert(entity, row.clone());
+                        }
+                    }
+                }
+            }
+            activity.lock().unwrap().record(&previous, &current);
+            changes.lock().unwrap().record(&previous, &current);
+            let now = Instant::now();
+            let mut fetched_at = fetched_at.lock().unwrap();
+            // Their rows stay, greyed out until pruned.
+            for entity in previous.keys().filter(|e| !current.contains_key(*e)) {
+                despawned.mark(*entity, now);
+                fetched_at.remove(entity);
+            }
+            for (entity, row) in current {
+                fetched_at.insert(entity, now);
+                components.insert(entity, row);
+            }
+            despawned.link_reuses(&components);
+            *error_info.lock().unwrap() = partial_failure;
+            egui_ctx.request_repaint();
+        });
+    }
+
+    /// Whether fetches only ask for the skeleton of the world and components are
+    /// loaded as entities come into view.
+    pub(super) fn is_lazy(&self) -> bool {
+        let narrowed = self.lazy.narrowed.lock().unwrap().is_some();
+        (self.config.lazy_loading || narrowed) && self.config.query.is_none()
+    }
+
+    /// Fetches all components of the next chunk of entities shown without them, the
+    /// selected one first.
+    pub(super) fn load_lazily(&self, ctx: &egui::Context) {
+        let chunk = self.lazy.next_chunk(self.selected);
+        if !self.is_lazy() || chunk.is_empty() {
+            return;
+        }
+        let Some(types) = self
+            .query_list
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|query| query.data.option.clone())
+        else {
+            return;
+        };
+        let calls = chunk
+            .iter()
+            .map(|entity| multi_get::get_call(*entity, &types))
+            .collect();
+        let components = self.components.clone();
+        let loaded = self.lazy.loaded.clone();
+        let in_flight = self.lazy.in_flight.clone();
+        let error_info = self.error_info.clone();
+        let egui_ctx = ctx.clone();
+        let max_in_flight = self.config.batch_max_in_flight;
+        in_flight.store(true, Ordering::SeqCst);
+        multi_get::get_many(self.endpoint(), calls, max_in_flight, move |joined| {
+            in_flight.store(false, Ordering::SeqCst);
+            if let Some(e) = joined.total_failure() {
+                *error_info.lock().unwrap() = Some(e.clone().context("Loading components"));
+                egui_ctx.request_repaint();
+                return;
+            }
+            if let Some(e) = joined.partial_failure() {
+                *error_info.lock().unwrap() = Some(e.context("Loading components"));
+            }
+            let mut components = components.lock().unwrap();
+            let mut loaded = loaded.lock().unwrap();
+            for (entity, result) in chunk.into_iter().zip(joined.results) {
+                // Entities despawned since the skeleton fetch, or in a batch that failed,
+                // are left to the next chunk.
+                let Some(Ok(result)) = result.map(|result| result.and_then(Components::decode))
+                else {
+                    continue;
+                };
+                if let Some(row) = components.get_mut(&entity) {
+                    row.components = result.components.into_iter().collect();
+                }
+                loaded.insert(entity);
+            }
+            egui_ctx.request_repaint();
+        });
+    }
+
+    pub(super) fn insert_value(
+        &self,
+        ctx: &egui::Context,
+        entity: Entity,
+        component: String,
+        value: serde_json::Value,
+    ) {
+        if self.config.read_only {
+            return;
+        }
+        self.performance
+            .mark(format!("Set {} of {entity}", short_name(&component)));
+        self.send_value(ctx, entity, component, value);
+    }
+
+    /// Inserts `value` like [`Self::insert_value`] and records it for undo, `before`
+    /// being the value it replaces.
+    pub(super) fn insert_recorded(
+        &self,
+        ctx: &egui::Context,
+        entity: Entity,
+        component: String,
+        before: Option<serde_json::Value>,
+        value: serde_json::Value,
+    ) {
+        if self.config.read_only {
+            return;
+        }
+        self.record(Mutation::Insert {
+            entity,
+            component: component.clone(),
+            before,
+            after: value.clone(),
+        });
+        self.insert_value(ctx, entity, component, value);
+    }
+
+    /// Inserts without a performance marker, for values sent many times a second while
+    /// a field is dragged.
+    pub(super) fn send_value(
+        &self,
+        ctx: &egui::Context,
+        entity: Entity,
+        component: String,
+        value: serde_json::Value,
+    ) {
+        if self.config.read_only {
+            return;
+        }
+        let error_info = self.error_info.clone();
+        let egui_ctx = ctx.clone();
+        let on_done = move |result: Result<(), InspectorError>| {
+            if let Err(e) = result {
+                *error_info.lock().unwrap() = Some(e);
+            }
+            egui_ctx.request_repaint();
+        };
+        let mutations = self
+            .components
+            .lock()
+            .unwrap()
+            .get(&entity)
+            .and_then(|row| row.components.get(&component))
+            .filter(|_| self.supports(BRP_MUTATE_COMPONENT_METHOD))
+            .and_then(|fetched| field_mutations(fetched, &value))
+            .filter(|mutations| mutations.len() <= MAX_FIELD_MUTATIONS);
+        if let Some(mutations) = mutations {
+            send_mutations(
+                self.components.clone(),
+                self.endpoint(),
+                entity,
+                component,
+                value,
+                mutations,
+                on_done,
+            );
+            return;
+        }
+        send_insert(
+            self.components.clone(),
+            self.endpoint(),
+            entity,
+            component,
+            value,
+            on_done,
+        );
+    }
+
+    /// Inserts several components into `entity` in one `bevy/insert`, e.g. ones copied
+    /// from another entity.
+    pub(super) fn insert_components(
+        &self,
+        ctx: &egui::Context,
+        entity: Entity,
+        components: HashMap<String, serde_json::Value>,
+    ) {
+        if self.config.read_only || components.is_empty() {
+            return;
+        }
+        self.performance.mark(format!(
+            "Pasted {} components into {entity}",
+            components.len()
+        ));
+        let cached = self.components.lock().unwrap().get(&entity).cloned();
+        for (component, value) in &components {
+            self.record(Mutation::Insert {
+                entity,
+                component: component.clone(),
+                before: cached
+                    .as_ref()
+                    .and_then(|row| row.components.get(component).cloned()),
+                after: value.clone(),
+            });
+        }
+        let request = helper::make_request(
+            &BrpInsertParams {
+                entity,
+                components: components.clone(),
+            },
+            BRP_INSERT_METHOD,
+            &self.endpoint(),
+        );
+        let cache = self.components.clone();
+        let error_info = self.error_info.clone();
+        let egui_ctx = ctx.clone();
+        transport::fetch(request, move |response| {
+            match helper::parse_result::<Inserted>(response) {
+                Ok(Inserted) => {
+                    if let Some(row) = cache.lock().unwrap().get_mut(&entity) {
+                        row.components.extend(components);
+                    }
+                }
+                Err(e) => *error_info.lock().unwrap() = Some(e.context(BRP_INSERT_METHOD)),
+            }
+            egui_ctx.request_repaint();
+        });
+    }
+
+    /// Sets the `Name` of `entity`, with the companion plugin when the game has it and by
+    /// inserting the component otherwise.
+    pub(super) fn rename(&self, ctx: &egui::Context, entity: Entity, name: String) {
+        if self.config.read_only {
+            return;
+        }
+        let name_path = self.bevy_version.paths().name;
+        let before = self
+            .components
+            .lock()
+            .unwrap()
+            .get(&entity)
+            .and_then(|row| row.components.get(name_path).cloned());
+        if !self.supports(INSPECTOR_RENAME_METHOD) {
+            self.insert_recorded(ctx, entity, name_path.to_owned(), before, name.into());
+            return;
+        }
+        self.record(Mutation::Insert {
+            entity,
+            component: name_path.to_owned(),
+            before,
+            after: name.clone().into(),
+        });
+        let request = helper::make_request(
+            serde_json::json!({ "entity": entity, "name": name }),
+            INSPECTOR_RENAME_METHOD,
+            &self.endpoint(),
+        );
+        let components = self.components.clone();
+        let error_info = self.error_info.clone();
+        let egui_ctx = ctx.clone();
+        transport::fetch(request, move |response| {
+            match helper::parse_response::<serde_json::Value>(response) {
+                Ok(_) => {
+                    if let Some(row) = components.lock().unwrap().get_mut(&entity) {
+                        row.components.insert(name_path.to_owned(), name.into());
+                    }
+                }
+                Err(e) => *error_info.lock().unwrap() = Some(e.context("Failed to rename")),
+            }
+            egui_ctx.request_repaint();
+        });
+    }
+
+    /// Sends `bevy/remove` for a single component and drops it from the cached row once
+    /// the game removed it.
+    pub(super) fn remove_component(&self, ctx: &egui::Context, entity: Entity, component: String) {
+        if self.config.read_only {
+            return;
+        }
+        let request = helper::make_request(
+            BrpRemoveParams {
+                entity,
+                components: vec![component.clone()],
+            },
+            BRP_REMOVE_METHOD,
+            &self.endpoint(),
+        );
+        let components = self.components.clone();
+        let error_info = self.error_info.clone();
+        let egui_ctx = ctx.clone();
+        self.scheduler.enqueue_once(request, move |response| {
+            match helper::parse_result::<Removed>(response) {
+                Ok(Removed) => {
+                    if let Some(row) = components.lock().unwrap().get_mut(&entity) {
+                        row.components.remove(&component);
+                    }
+                }
+                Err(e) => {
+                    *error_info.lock().unwrap() =
+                        Some(e.context(format!("Failed to remove {}", short_name(&component))));
+                }
+            }
+            egui_ctx.request_repaint();
+        });
+    }
+
+    /// Repairs a broken hierarchy link.
+    pub(super) fn fix_hierarchy(&self, ctx: &egui::Context, fix: HierarchyFix) {
+        if self.config.read_only {
+            return;
+        }
+        match fix {
+            HierarchyFix::Reparent { entity, parent } => {
+                let before = self
+                    .components
+                    .lock()
+                    .unwrap()
+                    .get(&entity)
+                    .and_then(|row| parent_of(row, self.bevy_version.paths()));
+                self.record(Mutation::Reparent {
+                    entity,
+                    before,
+                    after: parent,
+                });
+                self.reparent(ctx, entity, parent);
+            }
+            HierarchyFix::RemoveChild { parent, child } => {
+                let paths = self.bevy_version.paths();
+                let Some(row) = self.components.lock().unwrap().get(&parent).cloned() else {
+                    return;
+                };
+                let remaining: Vec<serde_json::Value> = children(&row, paths)
+                    .into_iter()
+                    .filter(|c| *c != child)
+                    .map(|c| c.to_bits().into())
+                    .collect();
+                let before = row.components.get(paths.children).cloned();
+                self.insert_recorded(
+                    ctx,
+                    parent,
+                    paths.children.to_owned(),
+                    before,
+                    remaining.into(),
+                );
+            }
+        }
+    }
+
+    /// Moves `entity` below `parent`, or to the root for `None`. Fetches the world again
+    /// once done, since it changes components of several entities.
+    pub(super) fn reparent(&self, ctx: &egui::Context, entity: Entity, parent: Option<Entity>) {
+        if self.config.read_only {
+            return;
+        }
+        let error_info = self.error_info.clone();
+        let refresh_requested = self.refresh_requested.clone();
+        let egui_ctx = ctx.clone();
+        let request = helper::make_request(
+            &BrpReparentParams {
+                entities: vec![entity],
+                parent,
+            },
+            BRP_REPARENT_METHOD,
+            &self.endpoint(),
+        );
+        self.scheduler.enqueue(request, move |response| {
+            match helper::parse_result::<Reparented>(response) {
+                Ok(Reparented) => *refresh_requested.lock().unwrap() = true,
+                Err(e) => *error_info.lock().unwrap() = Some(e.context(BRP_REPARENT_METHOD)),
+            }
+            egui_ctx.request_repaint();
+        });
+    }
+
+    /// Sends `bevy/destroy` for `entity`. Returns `false` when nothing was sent because
+    /// an earlier destroy of it is still pending.
+    pub(super) fn destroy(&self, entity: Entity) -> bool {
+        if self.config.read_only {
+            return false;
+        }
+        let request = helper::make_request(
+            &BrpDestroyParams { entity },
+            BRP_DESTROY_METHOD,
+            &self.endpoint(),
+        );
+        let error_info = self.error_info.clone();
+        let refresh_requested = self.refresh_requested.clone();
+        let queued =
+            self.scheduler.enqueue_once(request, move |response| {
+                match helper::parse_result::<Destroyed>(response) {
+                    Ok(Destroyed) => *refresh_requested.lock().unwrap() = true,
+                    Err(e) => *error_info.lock().unwrap() = Some(e.context(BRP_DESTROY_METHOD)),
+                }
+            });
+        if queued {
+            self.performance.mark(format!("Destroyed {entity}"));
+        }
+        queued
+    }
+
+    /// Keeps a change about to be sent so it can be undone.
+    pub(super) fn record(&self, mutation: Mutation) {
+        if !self.config.read_only {
+            self.undo.lock().unwrap().record(mutation);
+        }
+    }
+
+    pub(super) fn undo(&self, ctx: &egui::Context) {
+        if self.config.read_only {
+            return;
+        }
+        let undo = self.undo.lock().unwrap().undo();
+        if let Some(mutation) = undo {
+            self.perform(ctx, mutation);
+        }
+    }
+
+    pub(super) fn redo(&self, ctx: &egui::Context) {
+        if self.config.read_only {
+            return;
+        }
+        let redo = self.undo.lock().unwrap().redo();
+        if let Some(mutation) = redo {
+            self.perform(ctx, mutation);
+        }
+    }
+
+    /// Sends `mutation` without recording it, for undo and redo.
+    fn perform(&self, ctx: &egui::Context, mutation: Mutation) {
+        match mutation {
+            Mutation::Insert {
+                entity,
+                component,
+                after,
+                ..
+            } => self.insert_value(ctx, entity, component, after),
+            Mutation::Remove {
+                entity, component, ..
+            } => self.remove_component(ctx, entity, component),
+            Mutation::Reparent { entity, after, .. } => self.reparent(ctx, entity, after),
+            Mutation::Destroy {
+                entity,
+                label,
+                params,
+                descendants,
+                ..
+            } => {
+                if self.destroy(entity) {
+                    self.trash.lock().unwrap().push(TrashedEntity {
+                        entity,
+                        label,
+                        params,
+                        descendants,
+                    });
+                }
+            }
+            Mutation::Respawn {
+                entity,
+                label,
+                params,
+                parent,
+                descendants,
+            } => self.respawn_as(ctx, entity, label, params, parent, descendants),
+        }
+    }
+
+    /// Spawns `params` again as `entity` was before it was destroyed. It gets a new id,
+    /// which the undo history is updated to, and is put back below its parent. Its
+    /// descendants follow, below their parents' new ids.
+    fn respawn_as(
+        &self,
+        ctx: &egui::Context,
+        entity: Entity,
+        label: String,
+        params: BrpSpawnParams,
+        parent: Option<Entity>,
+        descendants: Vec<TrashedNode>,
+    ) {
+        self.performance.mark(format!("Respawned {label}"));
+        let request = helper::make_request(&params, BRP_SPAWN_METHOD, &self.endpoint());
+        let undo = self.undo.clone();
+        let spawned = self.spawned.clone();
+        let error_info = self.error_info.clone();
+        let refresh_requested = self.refresh_requested.clone();
+        let url = self.endpoint();
+        let egui_ctx = ctx.clone();
+        self.scheduler.enqueue(request, move |response| {
+            match helper::parse_result::<Spawned>(response) {
+                Ok(Spawned { entity: respawned }) => {
+                    undo.lock().unwrap().remap(entity, respawned);
+                    spawned.lock().unwrap().insert(respawned, Instant::now());
+                    *refresh_requested.lock().unwrap() = true;
+                    if let Some(parent) = parent {
+                        let request = helper::make_request(
+                            &BrpReparentParams {
+                                entities: vec![respawned],
+                                parent: Some(parent),
+                            },
+                            BRP_REPARENT_METHOD,
+                            &url,
+                        );
+                        let error_info = error_info.clone();
+                        let refresh_requested = refresh_requested.clone();
+                        transport::fetch(request, move |response| {
+                            if let Err(e) = helper::parse_result::<Reparented>(response) {
+                                *error_info.lock().unwrap() = Some(e.context(BRP_REPARENT_METHOD));
+                            }
+                            *refresh_requested.lock().unwrap() = true;
+                        });
+                    }
+                    let ids = vec![(entity, respawned)];
+                    respawn_descendants(url, descendants.into(), ids, move |ids, error| {
+                        let mut undo = undo.lock().unwrap();
+                        let mut spawned = spawned.lock().unwrap();
+                        for (old, new) in ids.into_iter().skip(1) {
+                            undo.remap(old, new);
+                            spawned.insert(new, Instant::now());
+                        }
+                        if let Some(e) = error {
+                            *error_info.lock().unwrap() = Some(
+                                e.context(format!("Failed to respawn the descendants of {label}")),
+                            );
+                        }
+                        *refresh_requested.lock().unwrap() = true;
+                    });
+                }
+                Err(e) => {
+                    *error_info.lock().unwrap() =
+                        Some(e.context(format!("Failed to respawn {label}")));
+                }
+            }
+            egui_ctx.request_repaint();
+        });
+    }
+
+    /// Sends a companion plugin time control method, a successful pause or step
+    /// requests a fetch of the resulting frame.
+    pub(super) fn send_frame_command(&self, ctx: &egui::Context, method: &'static str) {
+        if self.config.read_only {
+            return;
+        }
+        let error_info = self.error_info.clone();
+        let refresh_requested = self.refresh_requested.clone();
+        let egui_ctx = ctx.clone();
+        let request = helper::make_empty_request(method, &self.endpoint());
+        transport::fetch(request, move |response| {
+            let result = helper::parse_response::<serde_json::Value>(response).map(|_| ());
+            match result {
+                Ok(()) if method != INSPECTOR_RESUME_METHOD => {
+                    *refresh_requested.lock().unwrap() = true;
+                }
+                Ok(()) => {}
+                Err(e) => *error_info.lock().unwrap() = Some(e.context(method)),
+            }
+            egui_ctx.request_repaint();
+        });
+    }
+
+    /// Pauses or resumes the game, with the companion plugin when it has it and by setting
+    /// `Time<Virtual>` otherwise.
+    pub(super) fn set_paused(&self, ctx: &egui::Context, paused: bool) {
+        if self.config.read_only {
+            return;
+        }
+        if self.supports(INSPECTOR_PAUSE_METHOD) {
+            let method = if paused {
+                INSPECTOR_PAUSE_METHOD
+            } else {
+                INSPECTOR_RESUME_METHOD
+            };
+            self.send_frame_command(ctx, method);
+            return;
+        }
+        let error_info = self.error_info.clone();
+        let refresh_requested = self.refresh_requested.clone();
+        let egui_ctx = ctx.clone();
+        let request = helper::make_request(
+            MutateResourceParams {
+                resource: VIRTUAL_TIME_RESOURCE.to_owned(),
+                path: ".context.paused".to_owned(),
+                value: paused.into(),
+            },
+            BRP_MUTATE_RESOURCE_METHOD,
+            &self.endpoint(),
+        );
+        transport::fetch(request, move |response| {
+            match helper::parse_result::<ResourceMutated>(response) {
+                Ok(_) if paused => *refresh_requested.lock().unwrap() = true,
+                Ok(_) => {}
+                Err(e) => {
+                    *error_info.lock().unwrap() = Some(e.context(BRP_MUTATE_RESOURCE_METHOD));
+                }
+            }
+            egui_ctx.request_repaint();
+        });
+    }
+
+    /// Re-creates a destroyed entity with `bevy/spawn`, it gets a new id but the same
+    /// components. Its descendants follow below it.
+    pub(super) fn respawn(&self, ctx: &egui::Context, entry: TrashedEntity) {
+        if self.config.read_only {
+            self.trash.lock().unwrap().push(entry);
+            return;
+        }
+        self.performance.mark(format!("Respawned {}", entry.label));
+        let request = helper::make_request(&entry.params, BRP_SPAWN_METHOD, &self.endpoint());
+        let trash = self.trash.clone();
+        let error_info = self.error_info.clone();
+        let refresh_requested = self.refresh_requested.clone();
+        let url = self.endpoint();
+        let egui_ctx = ctx.clone();
+        self.scheduler.enqueue(request, move |response| {
+            match helper::parse_result::<Spawned>(response) {
+                Ok(Spawned { entity: respawned }) => {
+                    *refresh_requested.lock().unwrap() = true;
+                    let ids = vec![(entry.entity, respawned)];
+                    let label = entry.label;
+                    let repaint_ctx = egui_ctx.clone();
+                    respawn_descendants(url, entry.descendants.into(), ids, move |_, error| {
+                        if let Some(e) = error {
+                            *error_info.lock().unwrap() = Some(
+                                e.context(format!("Failed to respawn the descendants of {label}")),
+                            );
+                        }
+                        *refresh_requested.lock().unwrap() = true;
+                        repaint_ctx.request_repaint();
+                    });
+                }
+                Err(e) => {
+                    *error_info.lock().unwrap() =
+                        Some(e.context(format!("Failed to respawn {}", entry.label)));
+                    trash.lock().unwrap().push(entry);
+                }
+            }
+            egui_ctx.request_repaint();
+        });
+    }
+
+    /// Spawns a new entity, e.g. one pasted from another game.
+    pub(super) fn spawn(&self, ctx: &egui::Context, params: BrpSpawnParams) {
+        if self.config.read_only {
+            return;
+        }
+        self.performance.mark("Spawned");
+        let request = helper::make_request(&params, BRP_SPAWN_METHOD, &self.endpoint());
+        let error_info = self.error_info.clone();
+        let refresh_requested = self.refresh_requested.clone();
+        let spawned = self.spawned.clone();
+        let egui_ctx = ctx.clone();
+        self.scheduler.enqueue(request, move |response| {
+            match helper::parse_result::<Spawned>(response) {
+                Ok(Spawned { entity }) => {
+                    spawned.lock().unwrap().insert(entity, Instant::now());
+                    *refresh_requested.lock().unwrap() = true;
+                }
+                Err(e) => *error_info.lock().unwrap() = Some(e.context("Failed to spawn")),
+            }
+            egui_ctx.request_repaint();
+        });
+    }
+
+    /// Asks a freshly connected remote which methods it supports and which custom
+    /// panels it offers.
+    pub(super) fn discover(&mut self, ctx: &egui::Context) {
+        self.discovery_requested = true;
+        let capabilities = self.capabilities.clone();
+        let egui_ctx = ctx.clone();
+        let request = helper::make_empty_request(RPC_DISCOVER_METHOD, &self.endpoint());
+        let url = self.endpoint();
+        transport::fetch(request, move |response| {
+            // Remotes without discovery keep the permissive default, apart from the
+            // companion plugin methods when the plugin doesn't answer.
+            let Ok(document) = helper::parse_response::<serde_json::Value>(response) else {
+                let request = helper::make_empty_request(INSPECTOR_PING_METHOD, &url);
+                transport::fetch(request, move |response| {
+                    let result = helper::parse_response::<serde_json::Value>(response);
+                    if let Err(InspectorError::JsonRpc { code: -32601, .. }) = result {
+                        capabilities.lock().unwrap().without_companion();
+                        egui_ctx.request_repaint();
+                    }
+                });
+                return;
+            };
+            if let Some(discovered) = Capabilities::from_discover(&document) {
+                *capabilities.lock().unwrap() = discovered;
+                egui_ctx.request_repaint();
+            }
+        });
+        self.negotiate_encoding();
+        self.fetch_panels(ctx);
+        self.fetch_metadata(ctx);
+        let schemas = self.schemas.clone();
+        let request = helper::make_empty_request(BRP_REGISTRY_SCHEMA_METHOD, &self.endpoint());
+        transport::fetch(request, move |response| {
+            // Without schemas new components are entered as plain JSON.
+            if let Ok(result) = helper::parse_result::<RegistrySchemas>(response) {
+                *schemas.lock().unwrap() = Schemas::from_response(result);
+            }
+        });
+    }
+
+    /// Switches to MessagePack bodies when the companion plugin serves them and they work,
+    /// requests stay JSON otherwise. Not over WebSockets, which keep their own connection,
+    /// nor with a shared secret, the MessagePack endpoint doesn't check signatures.
+    fn negotiate_encoding(&self) {
+        *self.msgpack_url.lock().unwrap() = None;
+        if !cfg!(feature = "msgpack")
+            || self.config.websocket
+            || !self.config.shared_secret.is_empty()
+        {
+            return;
+        }
+        let msgpack_url = self.msgpack_url.clone();
+        let endpoint = self.endpoint();
+        let scheme = self.scheme();
+        let host = self.config.host.clone();
+        let request = helper::make_empty_request(INSPECTOR_ENCODINGS_METHOD, &endpoint);
+        transport::fetch(request, move |response| {
+            let Ok(encodings) = helper::parse_response::<Vec<Encoding>>(response) else {
+                return;
+            };
+            let Some(port) = encodings
+                .iter()
+                .find(|e| e.encoding == "msgpack")
+                .map(|e| e.port)
+            else {
+                return;
+            };
+            let url = format!("{scheme}://{host}:{port}/");
+            let request =
+                helper::make_empty_request(INSPECTOR_PING_METHOD, &endpoint.with_msgpack(&url));
+            transport::fetch(request, move |response| {
+                if helper::parse_response::<serde_json::Value>(response).is_ok() {
+                    *msgpack_url.lock().unwrap() = Some(url);
+                }
+            });
+        });
+    }
+
+    /// Asks the companion plugin which game is running. Games without the plugin just
+    /// don't tell, so errors are not reported.
+    pub(super) fn fetch_metadata(&self, ctx: &egui::Context) {
+        let metadata = self.metadata.clone();
+        let egui_ctx = ctx.clone();
+        let request = helper::make_empty_request(INSPECTOR_METADATA_METHOD, &self.endpoint());
+        transport::fetch(request, move |response| {
+            *metadata.lock().unwrap() = helper::parse_response(response).ok();
+            egui_ctx.request_repaint();
+        });
+    }
+
+    /// Measures the round trip to the game with a request it answers without any work.
+    pub(super) fn ping(&self, ctx: &egui::Context) {
+        if !self.latency.lock().unwrap().start_ping() {
+            return;
+        }
+        let latency = self.latency.clone();
+        let started = Instant::now();
+        let egui_ctx = ctx.clone();
+        let request = helper::make_empty_request(INSPECTOR_PING_METHOD, &self.endpoint());
+        transport::fetch(request, move |response| {
+            // An error answer is a round trip too, only transport failures are lost.
+            latency
+                .lock()
+                .unwrap()
+                .record(response.is_ok().then(|| started.elapsed()));
+            egui_ctx.request_repaint();
+        });
+    }
+
+    /// Asks the game for its current frame, one request at a time.
+    pub(super) fn poll_remote_frame(&self, ctx: &egui::Context) {
+        if self.frame_poll_in_flight.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let in_flight = self.frame_poll_in_flight.clone();
+        let remote_frame = self.remote_frame.clone();
+        let egui_ctx = ctx.clone();
+        let request = helper::make_empty_request(INSPECTOR_FRAME_METHOD, &self.endpoint());
+        transport::fetch(request, move |response| {
+            in_flight.store(false, Ordering::SeqCst);
+            if let Ok(frame) = helper::parse_response::<u64>(response) {
+                *remote_frame.lock().unwrap() = Some(frame);
+            }
+            egui_ctx.request_repaint();
+        });
+    }
+
+    /// Asks the companion plugin for its custom panels. Games without the plugin
+    /// simply have none, so errors are not reported.
+    pub(super) fn fetch_panels(&self, ctx: &egui::Context) {
+        let panels = self.panels.clone();
+        let egui_ctx = ctx.clone();
+        let request = helper::make_empty_request(INSPECTOR_PANELS_METHOD, &self.endpoint());
+        transport::fetch(request, move |response| {
+            let Ok(descriptors) = helper::parse_response::<Vec<PanelDescriptor>>(response) else {
+                return;
+            };
+            *panels.lock().unwrap() = descriptors.into_iter().map(CustomPanel::new).collect();
+            egui_ctx.request_repaint();
+        });
+    }
+
+    pub(super) fn refresh_panel(&self, ctx: &egui::Context, index: usize) {
+        let mut panels = self.panels.lock().unwrap();
+        let Some(panel) = panels.get_mut(index) else {
+            return;
+        };
+        panel.loading = true;
+        let method = panel.descriptor.method.clone();
+        let panels = self.panels.clone();
+        let egui_ctx = ctx.clone();
+        let request = helper::make_empty_request(&method, &self.endpoint());
+        transport::fetch(request, move |response| {
+            let result = helper::parse_response::<serde_json::Value>(response);
+            let mut panels = panels.lock().unwrap();
+            if let Some(panel) = panels
+                .get_mut(index)
+                .filter(|p| p.descriptor.method == method)
+            {
+                panel.loading = false;
+                match result {
+                    Ok(data) => {
+                        panel.data = Some(data);
+                        panel.error = None;
+                    }
+                    Err(e) => panel.error = Some(e.to_string()),
+                }
+            }
+            egui_ctx.request_repaint();
+        });
+    }
+
+    /// Sends a query from the history again on its own, only to record how it performs now.
+    pub(super) fn rerun_query(&self, ctx: &egui::Context, index: usize) {
+        let Some((method, params)) = self
+            .history
+            .lock()
+            .unwrap()
+            .get(index)
+            .map(|record| (record.method.clone(), record.params.clone()))
+        else {
+            return;
+        };
+        let request = match &params {
+            Some(params) => helper::make_request(params, &method, &self.endpoint()),
+            None => helper::make_empty_request(&method, &self.endpoint()),
+        };
+        let history = self.history.clone();
+        let egui_ctx = ctx.clone();
+        let started = Instant::now();
+        transport::fetch(request, move |response| {
+            let duration = started.elapsed();
+            let payload = response.as_ref().map_or(0, |r| r.bytes.len());
+            let result = helper::parse_response::<serde_json::Value>(response)
+                .map(|value| value.as_array().map_or(1, |rows| rows.len()))
+                .map_err(|e| e.to_string());
+            history.lock().unwrap().push(QueryRecord {
+                method,
+                params,
+                duration,
+                payload,
+                result,
+            });
+            egui_ctx.request_repaint();
+        });
+    }
+
+    /// Applies all staged edits as a unit. The current values are captured first, and
+    /// if any edit fails the ones that went through are restored to them.
+    pub(super) fn commit_staged(&self, ctx: &egui::Context) {
+        if self.config.read_only {
+            return;
+        }
+        let mut edits = self.edits.lock().unwrap();
+        let mut staged = Vec::new();
+        for (key, edit) in edits.iter_mut() {
+            if !matches!(edit.state, EditState::Staged) {
+                continue;
+            }
+            match edit.parsed() {
+                Ok(value) => {
+                    edit.state = EditState::Applying;
+                    edit.error = None;
+                    staged.push((key.clone(), value));
+                }
+                Err(e) => edit.error = Some(e),
+            }
+        }
+        drop(edits);
+        if staged.is_empty() {
+            return;
+        }
+        *self.commit_report.lock().unwrap() = None;
+        let captures = staged.iter().map(|(key, _)| capture_call(key)).collect();
+        let edits = self.edits.clone();
+        let components = self.components.clone();
+        let undo = self.undo.clone();
+        let report = self.commit_report.clone();
+        let refresh_requested = self.refresh_requested.clone();
+        let url = self.endpoint();
+        let egui_ctx = ctx.clone();
+        let max_in_flight = self.config.batch_max_in_flight;
+        multi_get::get_many(url.clone(), captures, max_in_flight, move |joined| {
+            // Without every current value the edits couldn't be rolled back, and nothing
+            // was changed yet, so they just stay staged.
+            let results = match joined.batch_errors.first() {
+                None => joined.results,
+                Some(e) => {
+                    let mut edits = edits.lock().unwrap();
+                    for (key, _) in staged {
+                        if let Some(edit) = edits.get_mut(&key) {
+                            edit.state = EditState::Staged;
+                            edit.error = Some(format!("Could not capture the current value: {e}"));
+                        }
+                    }
+                    egui_ctx.request_repaint();
+                    return;
+                }
+            };
+            let staged = staged
+                .into_iter()
+                .zip(results)
+                .map(|((key, value), current)| StagedEdit {
+                    previous: current
+                        .and_then(Result::ok)
+                        .and_then(|current| Components::decode(current).ok())
+                        .and_then(|current| current.get(&key.1).cloned()),
+                    key,
+                    value,
+                })
+                .collect();
+            apply_staged(
+                edits,
+                components,
+                undo,
+                report,
+                refresh_requested,
+                url,
+                egui_ctx,
+                staged,
+            );
+        });
+    }
+
+    /// Re-fetches the edited component and only applies the edit if the game did not
+    /// change it in the meantime, otherwise the edit is put into conflict state.
+    pub(super) fn apply_edit(&self, ctx: &egui::Context, entity: Entity, component: String) {
+        if self.config.read_only {
+            return;
+        }
+        let key = (entity, component.clone());
+        let mut edits = self.edits.lock().unwrap();
+        let Some(edit) = edits.get_mut(&key) else {
+            return;
+        };
+        if let Err(e) = edit.parsed() {
+            edit.error = Some(e);
+            return;
+        }
+        let original = edit.original.clone();
+        edit.state = EditState::Checking;
+        edit.error = None;
+        drop(edits);
+        self.performance
+            .mark(format!("Edited {} of {entity}", short_name(&component)));
+
+        let edits = self.edits.clone();
+        let components = self.components.clone();
+        let undo = self.undo.clone();
+        let url = self.endpoint();
+        let egui_ctx = ctx.clone();
+        let request = helper::make_request(
+            serde_json::json!({ "entity": entity, "components": [component] }),
+            BRP_GET_METHOD,
+            &self.endpoint(),
+        );
+        transport::fetch(request, move |response| {
+            let remote = helper::parse_result::<Components>(response).and_then(|result| {
+                result
+                    .get(&component)
+                    .cloned()
+                    .ok_or_else(|| match result.errors.get(&component) {
+                        Some(error) => InspectorError::Decode(format!(
+                            "Could not read {component}: {}",
+                            error["message"].as_str().unwrap_or("unknown error")
+                        )),
+                        None => {
+                            InspectorError::Decode(format!("Entity has no {component} anymore"))
+                        }
+                    })
+            });
+            let mut edits_guard = edits.lock().unwrap();
+            let Some(edit) = edits_guard.get_mut(&key) else {
+                return;
+            };
+            match remote {
+                Ok(remote) if remote == original => {
+                    let Ok(value) = edit.parsed() else {
+                        return;
+                    };
+                    drop(edits_guard);
+                    insert_component(edits, components, &undo, url, egui_ctx.clone(), key, value);
+                }
+                Ok(remote) => edit.state = EditState::Conflict(remote),
+                Err(e) => {
+                    edit.state = EditState::Editing;
+                    edit.error = Some(e.to_string());
+                }
+            }
+            egui_ctx.request_repaint();
+        });
+    }
+
+    pub(super) fn resolve_conflict(
+        &self,
+        ctx: &egui::Context,
+        entity: Entity,
+        component: String,
+        resolution: ConflictResolution,
+    ) {
+        let key = (entity, component);
+        let mut edits = self.edits.lock().unwrap();
+        let Some(edit) = edits.get_mut(&key) else {
+            return;
+        };
+        let EditState::Conflict(remote) = &edit.state else {
+            return;
+        };
+        let mine = match edit.parsed() {
+            Ok(mine) => mine,
+            Err(e) => {
+                edit.error = Some(e);
+                return;
+            }
+        };
+        let value = match resolution {
+            _ if self.config.read_only && resolution != ConflictResolution::Discard => return,
+            ConflictResolution::Overwrite => mine,
+            ConflictResolution::Merge => merge_changes(&edit.original, &mine, remote),
+            ConflictResolution::Discard => {
+                edits.remove(&key);
+                return;
+            }
+        };
+        drop(edits);
+        insert_component(
+            self.edits.clone(),
+            self.components.clone(),
+            &self.undo,
+            self.endpoint(),
+            ctx.clone(),
+            key,
+            value,
+        );
+    }
+}
+
+/// Shows `error` in the main view, with what the game answered if it did.
+fn report_failure(
+    error_info: &Mutex<Option<InspectorError>>,
+    error_raw: &Mutex<Option<(InspectorError, String)>>,
+    error: InspectorError,
+    raw: Option<String>,
+) {
+    *error_raw.lock().unwrap() = raw.map(|raw| (error.clone(), raw));
+    *error_info.lock().unwrap() = Some(error);
+}
+
+/// Leaves types the remote failed to serialize out of the query, only asking whether
+/// entities have them.
+pub(super) fn exclude_rejected(query: &mut BrpQueryParams, rejected: &HashMap<String, String>) {
+    query.data.option.retain(|t| !rejected.contains_key(t));
+    query.data.has = rejected.keys().cloned().collect();
+}
+
+/// The cached value of `component` of `entity`.
+pub(super) fn current_value(
+    content: &HashMap<Entity, BrpQueryRow>,
+    entity: Entity,
+    component: &str,
+) -> Option<serde_json::Value> {
+    content.get(&entity)?.components.get(component).cloned()
+}
+
+/// Builds `bevy/spawn` parameters recreating the entity. Hierarchy components are left out
+/// since they reference entities of the source world.
+pub(super) fn spawn_params(row: &BrpQueryRow, paths: &TypePaths) -> BrpSpawnParams {
+    BrpSpawnParams {
+        components: row
+            .components
+            .iter()
+            .filter(|(key, _)| *key != paths.parent && *key != paths.children)
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect(),
+    }
+}
+
+/// Everything below `entity` as far as it's cached, parents first, which `bevy/destroy`
+/// takes along with it.
+pub(super) fn descendant_nodes(
+    entity: Entity,
+    content: &HashMap<Entity, BrpQueryRow>,
+    paths: &TypePaths,
+) -> Vec<TrashedNode> {
+    let mut nodes: Vec<TrashedNode> = Vec::new();
+    let mut next = 0;
+    let mut parent = Some(entity);
+    while let Some(current) = parent {
+        if let Some(row) = content.get(&current) {
+            for child in children(row, paths) {
+                // A broken hierarchy could loop back up.
+                if child == entity || nodes.iter().any(|node| node.entity == child) {
+                    continue;
+                }
+                if let Some(child_row) = content.get(&child) {
+                    nodes.push(TrashedNode {
+                        entity: child,
+                        parent: current,
+                        params: spawn_params(child_row, paths),
+                    });
+                }
+            }
+        }
+        parent = nodes.get(next).map(|node| node.entity);
+        next += 1;
+    }
+    nodes
+}
+
+/// Spawns `descendants` one after the other, each below the new id of its parent. `ids`
+/// pairs old ids with new ones and starts with the already respawned root, `on_done` gets
+/// all of them and the error that stopped the rest, if any.
+pub(super) fn respawn_descendants(
+    url: Endpoint,
+    mut descendants: VecDeque<TrashedNode>,
+    mut ids: Vec<(Entity, Entity)>,
+    on_done: impl 'static + Send + FnOnce(Vec<(Entity, Entity)>, Option<InspectorError>),
+) {
+    let Some(node) = descendants.pop_front() else {
+        on_done(ids, None);
+        return;
+    };
+    let request = helper::make_request(&node.params, BRP_SPAWN_METHOD, &url);
+    transport::fetch(request, move |response| {
+        let respawned = match helper::parse_result::<Spawned>(response) {
+            Ok(Spawned { entity }) => entity,
+            Err(e) => {
+                on_done(ids, Some(e.context(BRP_SPAWN_METHOD)));
+                return;
+            }
+        };
+        let parent = ids
+            .iter()
+            .find(|(old, _)| *old == node.parent)
+            .map_or(node.parent, |(_, new)| *new);
+        ids.push((node.entity, respawned));
+        let request = helper::make_request(
+            &BrpReparentParams {
+                entities: vec![respawned],
+                parent: Some(parent),
+            },
+            BRP_REPARENT_METHOD,
+            &url,
+        );
+        transport::fetch(request, move |response| {
+            match helper::parse_result::<Reparented>(response) {
+                Ok(Reparented) => respawn_descendants(url, descendants, ids, on_done),
+                Err(e) => on_done(ids, Some(e.context(BRP_REPARENT_METHOD))),
+            }
+        });
+    });
+}
+
+/// Sends `bevy/insert` for a single component and updates the cached value on success.
+pub(super) fn send_insert(
+    components: Arc<Mutex<HashMap<Entity, BrpQueryRow>>>,
+    url: Endpoint,
+    entity: Entity,
+    component: String,
+    value: serde_json::Value,
+    on_done: impl 'static + Send + FnOnce(Result<(), InspectorError>),
+) {
+    let request = helper::make_request(
+        &BrpInsertParams {
+            entity,
+            components: [(component.clone(), value.clone())].into_iter().collect(),
+        },
+        BRP_INSERT_METHOD,
+        &url,
+    );
+    transport::fetch(request, move |response| {
+        let result = helper::parse_result::<Inserted>(response).map(|Inserted| ());
+        if result.is_ok() {
+            if let Some(row) = components.lock().unwrap().get_mut(&entity) {
+                row.components.insert(component, value);
+            }
+        }
+        on_done(result);
+    });
+}
+
+/// Sets only the changed fields of a component with `bevy/mutate_component`, leaving
+/// fields the game changes meanwhile alone. `value` is the whole new value, cached once
+/// every field was set.
+fn send_mutations(
+    components: Arc<Mutex<HashMap<Entity, BrpQueryRow>>>,
+    url: Endpoint,
+    entity: Entity,
+    component: String,
+    value: serde_json::Value,
+    mutations: Vec<(String, serde_json::Value)>,
+    on_done: impl 'static + Send + FnOnce(Result<(), InspectorError>),
+) {
+    if mutations.is_empty() {
+        on_done(Ok(()));
+        return;
+    }
+    let calls: Vec<_> = mutations
+        .into_iter()
+        .map(|(path, value)| {
+            helper::create_request(
+                Some(BrpMutateParams {
+                    entity,
+                    component: component.clone(),
+                    path,
+                    value,
+                }),
+                BRP_MUTATE_COMPONENT_METHOD,
+            )
+        })
+        .collect();
+    let request = helper::make_batch_request(&calls, &url);
+    transport::fetch(request, move |response| {
+        let result = helper::check_response(response)
+            .and_then(|response| helper::parse_batch(&response, &calls))
+            .and_then(|results| {
+                results
+                    .into_iter()
+                    .find_map(|result| result.and_then(Mutated::decode).err())
+                    .map_or(Ok(()), Err)
+            })
+            .map_err(|e| e.context(BRP_MUTATE_COMPONENT_METHOD));
+        if result.is_ok() {
+            if let Some(row) = components.lock().unwrap().get_mut(&entity) {
+                row.components.insert(component, value);
+            }
+        }
+        on_done(result);
+    });
+}
+
+/// Inserts the staged values in one batch. When some fail, the others are restored to
+/// their captured values and the edits stay staged, see [`CommitReport`].
+#[allow(clippy::too_many_arguments)]
+pub(super) fn apply_staged(
+    edits: Arc<Mutex<ComponentEdits>>,
+    components: Arc<Mutex<HashMap<Entity, BrpQueryRow>>>,
+    undo: Arc<Mutex<UndoHistory>>,
+    report: Arc<Mutex<Option<CommitReport>>>,
+    refresh_requested: Arc<Mutex<bool>>,
+    url: Endpoint,
+    egui_ctx: egui::Context,
+    staged: Vec<StagedEdit>,
+) {
+    let calls: Vec<_> = staged
+        .iter()
+        .map(|edit| insert_call(&edit.key, &edit.value))
+        .collect();
+    let request = helper::make_batch_request(&calls, &url);
+    transport::fetch(request, move |response| {
+        let results: Vec<Result<(), String>> = match helper::check_response(response)
+            .and_then(|response| helper::parse_batch(&response, &calls))
+        {
+            Ok(results) => results
+                .into_iter()
+                .map(|result| {
+                    result
+                        .and_then(Inserted::decode)
+                        .map(|_| ())
+                        .map_err(|e| e.to_string())
+                })
+                .collect(),
+            Err(e) => vec![Err(e.to_string()); staged.len()],
+        };
+        let mut outcome = CommitReport::default();
+        let mut succeeded = Vec::new();
+        {
+            let mut edits = edits.lock().unwrap();
+            let all_applied = results.iter().all(|result| result.is_ok());
+            for (edit, result) in staged.iter().zip(results) {
+                match result {
+                    Ok(()) if all_applied => {
+                        edits.remove(&edit.key);
+                        undo.lock().unwrap().record(Mutation::Insert {
+                            entity: edit.key.0,
+                            component: edit.key.1.clone(),
+                            before: edit.previous.clone(),
+                            after: edit.value.clone(),
+                        });
+                        if let Some(row) = components.lock().unwrap().get_mut(&edit.key.0) {
+                            row.components
+                                .insert(edit.key.1.clone(), edit.value.clone());
+                        }
+                        outcome.applied += 1;
+                    }
+                    Ok(()) => {
+                        succeeded.push(edit);
+                        if let Some(pending) = edits.get_mut(&edit.key) {
+                            pending.state = EditState::Staged;
+                        }
+                    }
+                    Err(e) => {
+                        if let Some(pending) = edits.get_mut(&edit.key) {
+                            pending.state = EditState::Staged;
+                            pending.error = Some(e.clone());
+                        }
+                        outcome.failed.push((edit.key.clone(), e));
+                    }
+                }
+            }
+        }
+        if succeeded.is_empty() {
+            *report.lock().unwrap() = Some(outcome);
+            egui_ctx.request_repaint();
+            return;
+        }
+        let rollback: Vec<(EditKey, Option<serde_json::Value>)> = succeeded
+            .into_iter()
+            .map(|edit| (edit.key.clone(), edit.previous.clone()))
+            .collect();
+        let calls: Vec<_> = rollback
+            .iter()
+            .map(|(key, previous)| restore_call(key, previous.as_ref()))
+            .collect();
+        let request = helper::make_batch_request(&calls, &url);
+        transport::fetch(request, move |response| {
+            let results = helper::check_response(response)
+                .and_then(|response| helper::parse_batch(&response, &calls));
+            for (index, (key, previous)) in rollback.into_iter().enumerate() {
+                let result = match &results {
+                    Ok(results) => results[index]
+                        .clone()
+                        .and_then(|result| match previous {
+                            Some(_) => Inserted::decode(result).map(|_| ()),
+                            None => Removed::decode(result).map(|_| ()),
+                        })
+                        .map_err(|e| e.to_string()),
+                    Err(e) => Err(e.to_string()),
+                };
+                match result {
+                    Ok(()) => outcome.rolled_back.push(key),
+                    Err(e) => outcome.rollback_failed.push((key, e)),
+                }
+            }
+            // What the game holds now is uncertain, so take a fresh look at it.
+            *refresh_requested.lock().unwrap() = true;
+            *report.lock().unwrap() = Some(outcome);
+            egui_ctx.request_repaint();
+        });
+    });
+}
+
+/// Sends `bevy/insert` for an edited component, the edit is closed once it went through.
+/// It's recorded for undo against the cached value it replaces.
+pub(super) fn insert_component(
+    edits: Arc<Mutex<ComponentEdits>>,
+    components: Arc<Mutex<HashMap<Entity, BrpQueryRow>>>,
+    undo: &Mutex<UndoHistory>,
+    url: Endpoint,
+    egui_ctx: egui::Context,
+    key: (Entity, String),
+    value: serde_json::Value,
+) {
+    if let Some(edit) = edits.lock().unwrap().get_mut(&key) {
+        edit.state = EditState::Applying;
+    }
+    let (entity, component) = key.clone();
+    let before = current_value(&components.lock().unwrap(), entity, &component);
+    undo.lock().unwrap().record(Mutation::Insert {
+        entity,
+        component: component.clone(),
+        before,
+        after: value.clone(),
+    });
+    send_insert(components, url, entity, component, value, move |result| {
+        let mut edits = edits.lock().unwrap();
+        match result {
+            Ok(()) => {
+                edits.remove(&key);
+            }
+            Err(e) => {
+                if let Some(edit) = edits.get_mut(&key) {
+                    edit.state = EditState::Editing;
+                    edit.error = Some(e.to_string());
+                }
+            }
+        }
+        egui_ctx.request_repaint();
+    });
+}
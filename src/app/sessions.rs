@@ -0,0 +1,218 @@
+//! Connections to several games at once, each in a tab of its own.
+//!
+//! The app always works on the active tab's state in its own fields. Switching tabs
+//! swaps that state with the one parked for the other tab, requests still in flight keep
+//! writing into the state of the tab that sent them. Parked tabs don't poll, live mode
+//! picks up again once they are active.
+
+use super::*;
+
+/// Everything tied to one connection.
+#[derive(Default)]
+pub(super) struct Session {
+    download: Arc<Mutex<Download>>,
+    rejected_types: Arc<Mutex<HashMap<String, String>>>,
+    query_list: Arc<Mutex<Option<BrpQueryParams>>>,
+    components: Arc<Mutex<HashMap<Entity, BrpQueryRow>>>,
+    fetched_at: Arc<Mutex<HashMap<Entity, Instant>>>,
+    despawned: Arc<Mutex<Despawned>>,
+    refresh: RefreshScheduler,
+    data_frame: Arc<Mutex<Option<u64>>>,
+    remote_frame: Arc<Mutex<Option<u64>>>,
+    frame_poll_in_flight: Arc<AtomicBool>,
+    remote_search: Arc<Mutex<Option<RemoteMatches>>>,
+    remote_search_in_flight: Arc<AtomicBool>,
+    spawned: Arc<Mutex<HashMap<Entity, Instant>>>,
+    spawn_to_follow: Arc<Mutex<Option<Entity>>>,
+    activity: Arc<Mutex<ActivityTracker>>,
+    changes: Arc<Mutex<ChangeLog>>,
+    frame_capture: bool,
+    refresh_requested: Arc<Mutex<bool>>,
+    error_info: Arc<Mutex<Option<InspectorError>>>,
+    error_raw: Arc<Mutex<Option<(InspectorError, String)>>>,
+    last_retry: Option<Instant>,
+    retry_attempts: u32,
+    edits: Arc<Mutex<ComponentEdits>>,
+    trash: Arc<Mutex<Trash>>,
+    panels: Arc<Mutex<Vec<CustomPanel>>>,
+    connect_requested: bool,
+    startup_pending: bool,
+    capabilities: Arc<Mutex<Capabilities>>,
+    msgpack_url: Arc<Mutex<Option<String>>>,
+    bevy_version: BevyVersion,
+    selected: Option<Entity>,
+    watches: HashMap<Entity, EntityWatch>,
+    history: Arc<Mutex<QueryHistory>>,
+    resource_watch: ResourceWatch,
+    schemas: Arc<Mutex<Schemas>>,
+    timeline: Arc<Mutex<Timeline>>,
+    commit_report: Arc<Mutex<Option<CommitReport>>>,
+    insights: Option<Vec<Insight>>,
+    latency: Arc<Mutex<LatencyTracker>>,
+    lazy: LazyLoader,
+    performance: PerformanceMonitor,
+    metadata: Arc<Mutex<Option<GameMetadata>>>,
+}
+
+impl TemplateApp {
+    /// Exchanges the active connection state with `session`.
+    fn swap_session(&mut self, session: &mut Session) {
+        use std::mem::swap;
+        swap(&mut self.download, &mut session.download);
+        swap(&mut self.rejected_types, &mut session.rejected_types);
+        swap(&mut self.query_list, &mut session.query_list);
+        swap(&mut self.components, &mut session.components);
+        swap(&mut self.fetched_at, &mut session.fetched_at);
+        swap(&mut self.despawned, &mut session.despawned);
+        swap(&mut self.refresh, &mut session.refresh);
+        swap(&mut self.data_frame, &mut session.data_frame);
+        swap(&mut self.remote_frame, &mut session.remote_frame);
+        swap(
+            &mut self.frame_poll_in_flight,
+            &mut session.frame_poll_in_flight,
+        );
+        swap(&mut self.remote_search, &mut session.remote_search);
+        swap(
+            &mut self.remote_search_in_flight,
+            &mut session.remote_search_in_flight,
+        );
+        swap(&mut self.spawned, &mut session.spawned);
+        swap(&mut self.spawn_to_follow, &mut session.spawn_to_follow);
+        swap(&mut self.activity, &mut session.activity);
+        swap(&mut self.changes, &mut session.changes);
+        swap(&mut self.frame_capture, &mut session.frame_capture);
+        swap(&mut self.refresh_requested, &mut session.refresh_requested);
+        swap(&mut self.error_info, &mut session.error_info);
+        swap(&mut self.error_raw, &mut session.error_raw);
+        swap(&mut self.last_retry, &mut session.last_retry);
+        swap(&mut self.retry_attempts, &mut session.retry_attempts);
+        swap(&mut self.edits, &mut session.edits);
+        swap(&mut self.trash, &mut session.trash);
+        swap(&mut self.panels, &mut session.panels);
+        swap(&mut self.connect_requested, &mut session.connect_requested);
+        swap(&mut self.startup_pending, &mut session.startup_pending);
+        swap(&mut self.capabilities, &mut session.capabilities);
+        swap(&mut self.msgpack_url, &mut session.msgpack_url);
+        swap(&mut self.bevy_version, &mut session.bevy_version);
+        swap(&mut self.selected, &mut session.selected);
+        swap(&mut self.watches, &mut session.watches);
+        swap(&mut self.history, &mut session.history);
+        swap(&mut self.resource_watch, &mut session.resource_watch);
+        swap(&mut self.schemas, &mut session.schemas);
+        swap(&mut self.timeline, &mut session.timeline);
+        swap(&mut self.commit_report, &mut session.commit_report);
+        swap(&mut self.insights, &mut session.insights);
+        swap(&mut self.latency, &mut session.latency);
+        swap(&mut self.lazy, &mut session.lazy);
+        swap(&mut self.performance, &mut session.performance);
+        swap(&mut self.metadata, &mut session.metadata);
+        // Discovery isn't tracked per tab, the activated connection is asked again.
+        self.discovery_requested = false;
+    }
+
+    /// Makes the tab of profile `name` the active one, parking the current tab.
+    pub(super) fn activate_tab(&mut self, name: &str) {
+        if name == self.config.profile {
+            return;
+        }
+        let mut session = self.sessions.remove(name).unwrap_or_else(|| Session {
+            startup_pending: true,
+            ..Default::default()
+        });
+        self.swap_session(&mut session);
+        // Stop streaming changes of the parked tab's entities.
+        session.watches.clear();
+        self.sessions.insert(self.config.profile.clone(), session);
+        self.config.switch_profile(name);
+    }
+
+    fn open_tab(&mut self, name: &str) {
+        if !self.config.open_tabs.iter().any(|tab| tab == name) {
+            self.config.open_tabs.push(name.to_owned());
+        }
+        self.activate_tab(name);
+        self.connect_requested = true;
+    }
+
+    fn close_tab(&mut self, name: &str) {
+        let Some(index) = self.config.open_tabs.iter().position(|tab| tab == name) else {
+            return;
+        };
+        self.config.open_tabs.remove(index);
+        if name == self.config.profile {
+            let next = index.min(self.config.open_tabs.len().saturating_sub(1));
+            if let Some(next) = self.config.open_tabs.get(next).cloned() {
+                self.activate_tab(&next);
+            }
+        }
+        self.sessions.remove(name);
+    }
+
+    /// One tab per open connection, red while it has an error, plus a menu to open
+    /// another profile.
+    pub(super) fn draw_tabs(&mut self, ui: &mut egui::Ui) {
+        if !self.config.open_tabs.contains(&self.config.profile) {
+            self.config.open_tabs.insert(0, self.config.profile.clone());
+        }
+        let mut activate = None;
+        let mut close = None;
+        let mut open = None;
+        ui.horizontal(|ui| {
+            for name in &self.config.open_tabs {
+                let active = *name == self.config.profile;
+                let failed = if active {
+                    self.error_info.lock().unwrap().is_some()
+                } else {
+                    self.sessions
+                        .get(name)
+                        .is_some_and(|s| s.error_info.lock().unwrap().is_some())
+                };
+                let mut text = RichText::new(name);
+                if failed {
+                    text = text.color(status_colors(ui.ctx()).error);
+                }
+                if ui.selectable_label(active, text).clicked() {
+                    activate = Some(name.clone());
+                }
+                if self.config.open_tabs.len() > 1 {
+                    let response = labeled(
+                        ui.small_button("✖"),
+                        egui::WidgetType::Button,
+                        &format!("Close {name}"),
+                    );
+                    if response.on_hover_text("Close the tab").clicked() {
+                        close = Some(name.clone());
+                    }
+                }
+                ui.separator();
+            }
+            let closed: Vec<String> = self
+                .config
+                .profile_names()
+                .into_iter()
+                .filter(|name| !self.config.open_tabs.contains(name))
+                .collect();
+            ui.add_enabled_ui(!closed.is_empty(), |ui| {
+                ui.menu_button("➕", |ui| {
+                    for name in closed {
+                        if ui.button(&name).clicked() {
+                            open = Some(name);
+                            ui.close_menu();
+                        }
+                    }
+                })
+                .response
+                .on_hover_text("Connect to another profile in a new tab");
+            });
+        });
+        if let Some(name) = activate {
+            self.activate_tab(&name);
+        }
+        if let Some(name) = close {
+            self.close_tab(&name);
+        }
+        if let Some(name) = open {
+            self.open_tab(&name);
+        }
+    }
+}
@@ -0,0 +1,260 @@
+//! UI tests driving the app against the mock server from [`crate::demo`].
+//!
+//! Frames are run on a plain `egui::Context` and the widget structure is read back from
+//! the AccessKit tree egui builds every frame, clicks are sent as AccessKit actions.
+
+use std::time::{Duration, Instant};
+
+use egui::accesskit::{Action, ActionRequest, Node, NodeId};
+
+use super::*;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+const HEALTH: &str = "demo::Health";
+
+struct Harness {
+    ctx: egui::Context,
+    app: TemplateApp,
+    nodes: Vec<(NodeId, Node)>,
+    events: Vec<egui::Event>,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let addr = crate::demo::start().expect("Unable to start the demo server");
+        let ctx = egui::Context::default();
+        ctx.enable_accesskit();
+        Self {
+            ctx,
+            app: TemplateApp::demo(addr),
+            nodes: Vec::new(),
+            events: Vec::new(),
+        }
+    }
+
+    fn step(&mut self) {
+        let input = egui::RawInput {
+            screen_rect: Some(egui::Rect::from_min_size(
+                egui::Pos2::ZERO,
+                egui::vec2(1280.0, 800.0),
+            )),
+            events: std::mem::take(&mut self.events),
+            ..Default::default()
+        };
+        let app = &mut self.app;
+        let output = self.ctx.run(input, |ctx| app.ui(ctx));
+        if let Some(update) = output.platform_output.accesskit_update {
+            self.nodes = update.nodes;
+        }
+    }
+
+    /// Runs frames until `done` holds, giving the mock server time to answer.
+    fn run_until(&mut self, what: &str, done: impl Fn(&Self) -> bool) {
+        let started = Instant::now();
+        while started.elapsed() < TIMEOUT {
+            self.step();
+            if done(self) {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        panic!("Timed out waiting for {what}, widgets: {:#?}", self.names());
+    }
+
+    fn names(&self) -> Vec<&str> {
+        self.nodes
+            .iter()
+            .filter_map(|(_, node)| node.name())
+            .collect()
+    }
+
+    fn find(&self, name: &str) -> Option<NodeId> {
+        self.nodes
+            .iter()
+            .find(|(_, node)| node.name().is_some_and(|n| n.contains(name)))
+            .map(|(id, _)| *id)
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.find(name).is_some()
+    }
+
+    fn click(&mut self, name: &str) {
+        let target = self
+            .find(name)
+            .unwrap_or_else(|| panic!("No widget named {name}, widgets: {:#?}", self.names()));
+        self.events
+            .push(egui::Event::AccessKitActionRequest(ActionRequest {
+                action: Action::Default,
+                target,
+                data: None,
+            }));
+        self.step();
+    }
+
+    fn entity(&self, name: &str) -> Entity {
+        self.named(name)
+            .unwrap_or_else(|| panic!("No entity named {name}"))
+    }
+
+    /// The entity named `name`, leaving out the rows kept for a while after a despawn.
+    fn named(&self, name: &str) -> Option<Entity> {
+        let components = self.app.components.lock().unwrap();
+        let despawned = self.app.despawned.lock().unwrap();
+        let paths = self.app.bevy_version.paths();
+        components
+            .iter()
+            .filter(|(entity, _)| !despawned.contains(entity))
+            .find(|(_, row)| row.components.get(paths.name) == Some(&serde_json::json!(name)))
+            .map(|(entity, _)| *entity)
+    }
+
+    fn component(&self, entity: Entity, component: &str) -> Option<serde_json::Value> {
+        let components = self.app.components.lock().unwrap();
+        components.get(&entity)?.components.get(component).cloned()
+    }
+}
+
+#[test]
+fn fetch_lists_entities() {
+    let mut harness = Harness::new();
+    harness.run_until("the entity tree", |h| h.has(": Player"));
+    for name in ["Camera", "Sun", "Cube"] {
+        assert!(harness.has(&format!(": {name}")), "{name} missing");
+    }
+    // Children are listed under their parent once it's expanded.
+    assert!(!harness.has(": Sword"));
+}
+
+#[test]
+fn expanding_entity_shows_children_and_components() {
+    let mut harness = Harness::new();
+    harness.run_until("the entity tree", |h| h.has(": Player"));
+    harness.click(": Player");
+    harness.run_until("the children of Player", |h| {
+        h.has(": Sword") && h.has(": Shield")
+    });
+    let player = harness.entity("Player");
+    assert_eq!(harness.app.selected, Some(player));
+    harness.run_until("the components of Player", |h| h.has("Health"));
+}
+
+#[test]
+fn editing_component_inserts_it() {
+    let mut harness = Harness::new();
+    harness.run_until("the entity tree", |h| h.has(": Player"));
+    let player = harness.entity("Player");
+    harness.app.selected = Some(player);
+    harness.run_until("the components of Player", |h| h.has("Health"));
+    // Only the expanded component shows its Edit button.
+    harness.click("Health");
+    harness.run_until("the Edit button", |h| h.has("Edit"));
+    harness.click("Edit");
+    harness.run_until("the edit modes", |h| h.has("JSON"));
+    harness.click("JSON");
+    let key = (player, HEALTH.to_owned());
+    harness
+        .app
+        .edits
+        .lock()
+        .unwrap()
+        .get_mut(&key)
+        .unwrap()
+        .text = r#"{ "current": 55, "max": 100 }"#.to_owned();
+    harness.run_until("the Apply button", |h| h.has("Apply"));
+    harness.click("Apply");
+    harness.run_until("the inserted value", |h| {
+        h.component(player, HEALTH) == Some(serde_json::json!({ "current": 55, "max": 100 }))
+    });
+    assert!(harness.app.edits.lock().unwrap().get(&key).is_none());
+}
+
+#[test]
+fn toolbar_messages_change_the_config() {
+    let mut harness = Harness::new();
+    let ctx = harness.ctx.clone();
+    let app = &mut harness.app;
+    app.send(&ctx, Msg::SetFlag(Flag::ReadOnly, true));
+    assert!(app.config.read_only);
+    app.config.skip_empty_entities = true;
+    app.send(&ctx, Msg::SavePreset("Mine".to_owned()));
+    assert_eq!(app.config.active_preset, "Mine");
+    let index = app
+        .config
+        .view_presets
+        .iter()
+        .position(|preset| preset.name == "Mine")
+        .unwrap();
+    app.send(&ctx, Msg::SetFlag(Flag::HideEmpty, false));
+    app.send(&ctx, Msg::ApplyPreset(index));
+    assert!(app.config.skip_empty_entities);
+    app.send(&ctx, Msg::DeletePreset(index));
+    assert!(app.config.active_preset.is_empty());
+    assert!(!app.config.view_presets.iter().any(|p| p.name == "Mine"));
+}
+
+#[test]
+fn undoing_a_destroy_respawns_the_children() {
+    let mut harness = Harness::new();
+    harness.run_until("the entity tree", |h| h.has(": Player"));
+    let ctx = harness.ctx.clone();
+    harness.app.config.confirm_destroy = false;
+    let player = harness.entity("Player");
+    harness.run_until("the children of Player", |h| h.named("Sword").is_some());
+    harness.app.send(&ctx, Msg::Remove(player));
+    harness.run_until("Player to be destroyed", |h| {
+        h.named("Player").is_none() && h.named("Sword").is_none()
+    });
+    harness.app.undo(&ctx);
+    harness.run_until("Player and its children to be back", |h| {
+        let Some(player) = h.named("Player") else {
+            return false;
+        };
+        let children = h
+            .app
+            .components
+            .lock()
+            .unwrap()
+            .get(&player)
+            .map(|row| children(row, h.app.bevy_version.paths()));
+        children.is_some_and(|children| {
+            ["Sword", "Shield"]
+                .iter()
+                .all(|name| h.named(name).is_some_and(|child| children.contains(&child)))
+        })
+    });
+}
+
+#[test]
+fn undoing_an_apply_restores_the_value() {
+    let mut harness = Harness::new();
+    harness.run_until("the entity tree", |h| h.has(": Player"));
+    let ctx = harness.ctx.clone();
+    let player = harness.entity("Player");
+    harness.app.selected = Some(player);
+    harness.run_until("the components of Player", |h| h.has("Health"));
+    let original = harness.component(player, HEALTH).unwrap();
+    harness.click("Health");
+    harness.run_until("the Edit button", |h| h.has("Edit"));
+    harness.click("Edit");
+    harness.run_until("the edit modes", |h| h.has("JSON"));
+    harness.click("JSON");
+    let key = (player, HEALTH.to_owned());
+    harness
+        .app
+        .edits
+        .lock()
+        .unwrap()
+        .get_mut(&key)
+        .unwrap()
+        .text = r#"{ "current": 12, "max": 100 }"#.to_owned();
+    harness.run_until("the Apply button", |h| h.has("Apply"));
+    harness.click("Apply");
+    harness.run_until("the inserted value", |h| {
+        h.component(player, HEALTH) == Some(serde_json::json!({ "current": 12, "max": 100 }))
+    });
+    harness.app.undo(&ctx);
+    harness.run_until("the original value", |h| {
+        h.component(player, HEALTH).as_ref() == Some(&original)
+    });
+}
@@ -0,0 +1,2440 @@
+//! Windows opened from the toolbar and the details window of the selected entity.
+
+use super::*;
+
+impl TemplateApp {
+    pub(super) fn draw_trash(&mut self, ctx: &egui::Context) {
+        let mut respawn = None;
+        let mut respawn_all = false;
+        let can_spawn = !self.config.read_only && self.supports(BRP_SPAWN_METHOD);
+        let detached = self.is_detached("trash");
+        let toggled = show_panel(
+            ctx,
+            "trash",
+            "Trash",
+            &mut self.show_trash,
+            detached,
+            |ui| {
+                let mut trash = self.trash.lock().unwrap();
+                if trash.is_empty() {
+                    ui.label("Entities destroyed from the inspector show up here.");
+                    return;
+                }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (index, entry) in trash.entries().enumerate() {
+                        ui.horizontal(|ui| {
+                            ui.add_enabled_ui(can_spawn, |ui| {
+                                if ui.button("Respawn").clicked() {
+                                    respawn = Some(index);
+                                }
+                            });
+                            let hover = match entry.descendants.len() {
+                                0 => format!("{} components", entry.params.components.len()),
+                                descendants => format!(
+                                    "{} components, {descendants} descendants",
+                                    entry.params.components.len()
+                                ),
+                            };
+                            ui.label(&entry.label).on_hover_text(hover);
+                        });
+                    }
+                });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.add_enabled_ui(can_spawn, |ui| {
+                        respawn_all = ui.button("Respawn all").clicked();
+                    });
+                    if ui.button("Empty trash").clicked() {
+                        trash.clear();
+                    }
+                });
+            },
+        );
+        if toggled {
+            self.toggle_detached("trash");
+        }
+        let entries = match (respawn, respawn_all) {
+            (_, true) => self.trash.lock().unwrap().take_all(),
+            (Some(index), false) => self.trash.lock().unwrap().take(index).into_iter().collect(),
+            (None, false) => return,
+        };
+        for entry in entries {
+            self.respawn(ctx, entry);
+        }
+    }
+
+    pub(super) fn draw_spawn(&mut self, ctx: &egui::Context) {
+        if !self.spawn_draft.open {
+            return;
+        }
+        let can_spawn = !self.config.read_only && self.supports(BRP_SPAWN_METHOD);
+        let types: Vec<String> = self
+            .query_list
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|query| query.data.option.iter().cloned())
+            .collect();
+        let anchors = {
+            let rows = self.components.lock().unwrap();
+            Anchors {
+                camera: camera_position(&rows),
+                selected: self
+                    .selected
+                    .and_then(|entity| rows.get(&entity))
+                    .and_then(world_position),
+            }
+        };
+        let schemas = self.schemas.clone();
+        let mut open = true;
+        let mut spawn = None;
+        let detached = self.is_detached("spawn");
+        let toggled = show_panel(ctx, "spawn", "Spawn entity", &mut open, detached, |ui| {
+            let schemas = schemas.lock().unwrap();
+            spawn = self
+                .spawn_draft
+                .draw(ui, &types, &schemas, &self.aliases, &anchors, can_spawn);
+        });
+        if toggled {
+            self.toggle_detached("spawn");
+        }
+        self.spawn_draft.open &= open;
+        if let Some(params) = spawn {
+            self.last_action = Some(LastAction::Spawn(params.clone()));
+            self.spawn(ctx, params);
+        }
+    }
+
+    pub(super) fn draw_pasted(&mut self, ctx: &egui::Context) {
+        let Some(pasted) = &self.pasted else {
+            return;
+        };
+        let can_spawn = !self.config.read_only && self.supports(BRP_SPAWN_METHOD);
+        let can_insert = !self.config.read_only && self.supports(BRP_INSERT_METHOD);
+        let name_path = self.bevy_version.paths().name;
+        let target = self
+            .selected
+            .filter(|_| matches!(pasted, Ok(e) if e.len() == 1));
+        let mut open = true;
+        let mut spawn = false;
+        let mut insert = false;
+        let mut close = false;
+        let detached = self.is_detached("paste");
+        let toggled =
+            show_panel(
+                ctx,
+                "paste",
+                "Paste entities",
+                &mut open,
+                detached,
+                |ui| match pasted {
+                    Ok(entities) => {
+                        ui.label(format!(
+                            "Spawn {} entities from the clipboard?",
+                            entities.len()
+                        ));
+                        egui::ScrollArea::vertical()
+                            .max_height(200.0)
+                            .show(ui, |ui| {
+                                for params in entities {
+                                    let name =
+                                        params.components.get(name_path).and_then(|n| n.as_str());
+                                    let count = params.components.len();
+                                    ui.label(match name {
+                                        Some(name) => format!("{name} ({count} components)"),
+                                        None => format!("{count} components"),
+                                    });
+                                }
+                            });
+                        ui.horizontal(|ui| {
+                            spawn = ui
+                                .add_enabled(can_spawn, egui::Button::new("Spawn"))
+                                .clicked();
+                            if let Some(target) = target {
+                                insert = ui
+                                    .add_enabled(
+                                        can_insert,
+                                        egui::Button::new(format!("Insert into {target}")),
+                                    )
+                                    .on_hover_text("Add the components to the selected entity")
+                                    .clicked();
+                            }
+                            close = ui.button("Cancel").clicked();
+                        });
+                    }
+                    Err(error) => {
+                        ui.label("The pasted JSON is not an entity or a list of entities.");
+                        ui.label(RichText::new(error).color(status_colors(ui.ctx()).error));
+                        close = ui.button("Close").clicked();
+                    }
+                },
+            );
+        if toggled {
+            self.toggle_detached("paste");
+        }
+        if let Some(target) = target.filter(|_| insert) {
+            if let Some(Ok(mut entities)) = self.pasted.take() {
+                let params = entities.remove(0);
+                self.insert_components(ctx, target, params.components);
+            }
+        }
+        if spawn {
+            if let Some(Ok(entities)) = self.pasted.take() {
+                for params in entities {
+                    self.spawn(ctx, params);
+                }
+            }
+        }
+        if !open || close {
+            self.pasted = None;
+        }
+    }
+
+    pub(super) fn draw_local_scan(&mut self, ctx: &egui::Context) {
+        let mut attach = None;
+        let mut rescan = false;
+        let mut open = self.local_scan.open;
+        let detached = self.is_detached("local_games");
+        let toggled = show_panel(
+            ctx,
+            "local_games",
+            "Local games",
+            &mut open,
+            detached,
+            |ui| {
+                ui.horizontal(|ui| {
+                    rescan = ui
+                        .add_enabled(
+                            !self.local_scan.is_scanning(),
+                            egui::Button::new("Scan again"),
+                        )
+                        .clicked();
+                    if self.local_scan.is_scanning() {
+                        power::spinner(ui);
+                    }
+                    ui.checkbox(&mut self.config.discover_lan, "Network")
+                        .on_hover_text(
+                            "Also list games on the local network announced by the companion \
+                             plugin's DiscoveryPlugin",
+                        );
+                });
+                if let Some(error) = self.local_scan.lan_error() {
+                    ui.colored_label(
+                        status_colors(ui.ctx()).error,
+                        format!("Could not listen for games on the network: {error}"),
+                    );
+                }
+                let candidates = self.local_scan.candidates();
+                if candidates.is_empty() && !self.local_scan.is_scanning() {
+                    ui.label("No game with the remote protocol found.");
+                }
+                for candidate in candidates {
+                    if candidate.status == ProbeStatus::NotBevy {
+                        continue;
+                    }
+                    ui.horizontal(|ui| {
+                        let name = candidate
+                            .game
+                            .as_deref()
+                            .or(candidate.process.as_deref())
+                            .unwrap_or("Unknown process");
+                        ui.label(RichText::new(name).strong());
+                        if candidate.host.is_loopback() {
+                            ui.label(format!("port {}", candidate.port));
+                        } else {
+                            ui.label(format!("{}:{}", candidate.host, candidate.port));
+                        }
+                        match candidate.status {
+                            ProbeStatus::Probing => {
+                                power::spinner(ui);
+                            }
+                            ProbeStatus::Bevy(types) => {
+                                if ui
+                                    .button("Attach")
+                                    .on_hover_text(format!("{types} component types registered"))
+                                    .clicked()
+                                {
+                                    attach = Some((candidate.host, candidate.port));
+                                }
+                            }
+                            ProbeStatus::NotBevy => {}
+                        }
+                    });
+                }
+            },
+        );
+        self.local_scan.open = open;
+        if toggled {
+            self.toggle_detached("local_games");
+        }
+        if rescan {
+            self.find_local_games(ctx);
+        }
+        if let Some((host, port)) = attach {
+            self.send(ctx, Msg::Attach(host.to_string(), port));
+        }
+    }
+
+    pub(super) fn draw_history(&mut self, ctx: &egui::Context) {
+        let mut rerun = None;
+        let detached = self.is_detached("history");
+        let toggled = show_panel(
+            ctx,
+            "history",
+            "Query history",
+            &mut self.show_history,
+            detached,
+            |ui| {
+                let mut history = self.history.lock().unwrap();
+                if history.is_empty() {
+                    ui.label("Executed queries show up here.");
+                    return;
+                }
+                if ui.button("Clear").clicked() {
+                    history.clear();
+                }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("query_history")
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for heading in ["Method", "Rows", "Time", "Size", ""] {
+                                ui.label(RichText::new(heading).strong());
+                            }
+                            ui.end_row();
+                            for (index, record) in history.records().enumerate() {
+                                let params = record
+                                    .params
+                                    .as_ref()
+                                    .and_then(|p| serde_json::to_string_pretty(p).ok())
+                                    .unwrap_or_default();
+                                ui.label(&record.method).on_hover_text(params);
+                                match &record.result {
+                                    Ok(rows) => ui.label(rows.to_string()),
+                                    Err(e) => ui
+                                        .label(
+                                            RichText::new("failed")
+                                                .color(status_colors(ui.ctx()).error),
+                                        )
+                                        .on_hover_text(e),
+                                };
+                                ui.label(format!(
+                                    "{:.1} ms",
+                                    record.duration.as_secs_f64() * 1000.0
+                                ));
+                                ui.label(format!("{:.1} KB", record.payload as f64 / 1024.0));
+                                if ui.small_button("Re-run").clicked() {
+                                    rerun = Some(index);
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            },
+        );
+        if toggled {
+            self.toggle_detached("history");
+        }
+        if let Some(index) = rerun {
+            self.rerun_query(ctx, index);
+        }
+    }
+
+    pub(super) fn draw_references(&mut self, ctx: &egui::Context) {
+        let Some((title, references)) = &self.references else {
+            return;
+        };
+        let mut open = true;
+        let mut select = None;
+        let detached = self.is_detached("references");
+        let toggled = show_panel(ctx, "references", title, &mut open, detached, |ui| {
+            if references.is_empty() {
+                ui.label("Nothing in the cached components refers to it.");
+                return;
+            }
+            let components = self.components.lock().unwrap();
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for reference in references {
+                    let label = components
+                        .get(&reference.entity)
+                        .map_or(reference.entity.to_string(), |row| {
+                            self.entity_label(&reference.entity, row)
+                        });
+                    ui.horizontal(|ui| {
+                        if ui.link(label).clicked() {
+                            select = Some(reference.entity);
+                        }
+                        ui.label(format!(
+                            "{}{}",
+                            short_name(&reference.component),
+                            reference.path
+                        ))
+                        .on_hover_text(&reference.component);
+                    });
+                }
+            });
+        });
+        if toggled {
+            self.toggle_detached("references");
+        }
+        if let Some(entity) = select {
+            self.selected = Some(entity);
+            self.scroll_to_selected = true;
+        }
+        if !open {
+            self.references = None;
+        }
+    }
+
+    /// The details window, unless the details are docked beside the tree.
+    pub(super) fn draw_details(&mut self, ctx: &egui::Context) {
+        if self.config.details_docked {
+            return;
+        }
+        let Some(entity) = self.selected else {
+            return;
+        };
+        let components = self.components.clone();
+        let live = components.lock().unwrap();
+        let rewound = self.timeline.lock().unwrap().viewed();
+        let content = rewound.as_deref().unwrap_or(&live);
+        let Some(item) = content.get(&entity) else {
+            return;
+        };
+        let mut open = true;
+        let mut action = Msg::None;
+        let detached = self.is_detached("details");
+        let toggled = show_panel(
+            ctx,
+            "details",
+            "Entity details",
+            &mut open,
+            detached,
+            |ui| action = self.details_contents(ui, &entity, item, content),
+        );
+        if toggled {
+            self.toggle_detached("details");
+        }
+        if !open {
+            self.selected = None;
+        }
+        if matches!(action, Msg::Select(_)) {
+            self.scroll_to_selected = true;
+        }
+        self.dispatch(ctx, action, content);
+    }
+
+    /// The right pane of the two-pane layout, the details of the selected entity.
+    pub(super) fn draw_details_pane(&mut self, ui: &mut egui::Ui) {
+        let components = self.components.clone();
+        let live = components.lock().unwrap();
+        let rewound = self.timeline.lock().unwrap().viewed();
+        let content = rewound.as_deref().unwrap_or(&live);
+        let selected = self
+            .selected
+            .and_then(|entity| content.get(&entity).map(|item| (entity, item)));
+        let Some((entity, item)) = selected else {
+            ui.centered_and_justified(|ui| {
+                ui.weak("Select an entity to see its components.");
+            });
+            return;
+        };
+        let action = self.details_contents(ui, &entity, item, content);
+        if matches!(action, Msg::Select(_)) {
+            self.scroll_to_selected = true;
+        }
+        self.dispatch(ui.ctx(), action, content);
+    }
+
+    fn details_contents(
+        &mut self,
+        ui: &mut egui::Ui,
+        entity: &Entity,
+        item: &BrpQueryRow,
+        content: &HashMap<Entity, BrpQueryRow>,
+    ) -> Msg {
+        tour::mark(ui.ctx(), TourAnchor::Details, ui.max_rect());
+        let entity = *entity;
+        let mut tab = self.detail_tab;
+        let mut action = Msg::None;
+        let mut wizard = self.wizard.take().filter(|w| w.entity == entity);
+        ui.horizontal(|ui| {
+            ui.label(RichText::new(self.entity_label(&entity, item)).strong());
+            match self.watches.get(&entity).map(|watch| watch.error()) {
+                Some(None) => {
+                    ui.label(RichText::new("● watching").color(status_colors(ui.ctx()).ok))
+                        .on_hover_text("Changes stream in with bevy/get+watch");
+                }
+                Some(Some(error)) => {
+                    ui.label(RichText::new("● not watching").weak())
+                        .on_hover_text(error);
+                }
+                None => {}
+            }
+            if let Some(gone) = self.despawned.lock().unwrap().get(&entity) {
+                ui.label(RichText::new("† despawned").color(status_colors(ui.ctx()).warning))
+                    .on_hover_text(gone.describe(entity));
+            }
+            if self.is_lazy() && !self.lazy.is_loaded(&entity) {
+                power::spinner(ui);
+                ui.weak("loading components");
+            }
+        });
+        ui.horizontal(|ui| {
+            for option in DetailTab::ALL {
+                let response = ui.selectable_value(&mut tab, option, option.label());
+                if option == tab && std::mem::take(&mut self.focus_details) {
+                    response.request_focus();
+                }
+            }
+        });
+        ui.separator();
+        egui::ScrollArea::vertical().show(ui, |ui| match tab {
+            DetailTab::Components => {
+                action = self.draw_components(ui, &entity, item);
+                ui.separator();
+                if let Some(insert) = self.draw_wizard(ui, &entity, item, &mut wizard) {
+                    action = insert;
+                }
+            }
+            DetailTab::Hierarchy => action = self.draw_hierarchy(ui, item, content),
+            DetailTab::Raw => {
+                let json = raw_json(ui.ctx(), &self.config.redaction.redact_row(item));
+                if ui.button("Copy").clicked() {
+                    ui.ctx().copy_text(json.to_string());
+                }
+                ui.add(
+                    egui::TextEdit::multiline(&mut &*json)
+                        .code_editor()
+                        .desired_width(f32::INFINITY),
+                );
+            }
+            DetailTab::History => self.draw_changes(ui, &entity),
+        });
+        self.detail_tab = tab;
+        self.wizard = wizard;
+        action
+    }
+
+    /// Form for adding a component to the entity, generated from the type's registry
+    /// schema. Returns the insert action once the user confirms.
+    pub(super) fn draw_wizard(
+        &self,
+        ui: &mut egui::Ui,
+        entity: &Entity,
+        item: &BrpQueryRow,
+        wizard: &mut Option<ComponentWizard>,
+    ) -> Option<Msg> {
+        if self.config.read_only || !self.supports(BRP_INSERT_METHOD) {
+            return None;
+        }
+        let Some(current) = wizard else {
+            if ui.button("➕ Add component").clicked() {
+                *wizard = Some(ComponentWizard {
+                    entity: *entity,
+                    component: String::new(),
+                    value: serde_json::Value::Null,
+                    search: String::new(),
+                });
+            }
+            return None;
+        };
+        let schemas = self.schemas.lock().unwrap();
+        let types: Vec<String> = self
+            .query_list
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|query| query.data.option.iter())
+            .filter(|t| !item.components.contains_key(*t))
+            .cloned()
+            .collect();
+        let favorites: Vec<&String> = self
+            .config
+            .favorite_components
+            .iter()
+            .filter(|t| types.contains(t))
+            .collect();
+        let frequent: Vec<&String> = self
+            .config
+            .frequent_components(FREQUENT_COMPONENTS)
+            .into_iter()
+            .filter(|t| types.contains(t) && !favorites.contains(t))
+            .collect();
+        let mut msg = None;
+        let typed = type_path_input(
+            ui,
+            "wizard_search",
+            &mut current.search,
+            &types,
+            "Search components",
+            200.0,
+        );
+        if let Some(type_path) = typed.accepted {
+            current.search.clear();
+            current.value = schemas.default_value(&type_path);
+            current.component = type_path;
+        }
+        egui::ComboBox::from_label("Component")
+            .selected_text(short_name(&current.component))
+            .show_ui(ui, |ui| {
+                let sections = [
+                    ("Favorites", favorites),
+                    ("Frequently added", frequent),
+                    ("All", types.iter().collect()),
+                ];
+                for (title, section) in sections {
+                    if section.is_empty() {
+                        continue;
+                    }
+                    ui.label(RichText::new(title).weak().small());
+                    for type_path in section {
+                        let selected = current.component == *type_path;
+                        ui.horizontal(|ui| {
+                            let favorite = self.config.favorite_components.contains(type_path);
+                            let star = if favorite { "★" } else { "☆" };
+                            let response = labeled(
+                                ui.small_button(star),
+                                egui::WidgetType::Button,
+                                "Favorite",
+                            );
+                            if response.clicked() {
+                                msg = Some(Msg::ToggleFavorite(type_path.clone()));
+                            }
+                            if ui
+                                .selectable_label(selected, self.aliases.type_name(type_path))
+                                .on_hover_text(type_path)
+                                .clicked()
+                                && !selected
+                            {
+                                current.value = schemas.default_value(type_path);
+                                current.component = type_path.clone();
+                            }
+                        });
+                    }
+                }
+            });
+        if current.component.is_empty() {
+            return msg;
+        }
+        if schemas.is_empty() {
+            ui.label(
+                RichText::new("The game doesn't provide type schemas, enter the value as JSON.")
+                    .weak(),
+            );
+        }
+        let form = ui.make_persistent_id(("wizard", &current.component));
+        if ui
+            .button("🎲 Randomize")
+            .on_hover_text("Fill the fields with random values")
+            .clicked()
+        {
+            current.value = schemas.randomize(&current.component, &current.value, &mut Rng::new());
+            forget_text(ui.ctx(), form);
+        }
+        schemas.edit(ui, form, &current.component, &mut current.value);
+        let mut close = false;
+        ui.horizontal(|ui| {
+            if ui.button("Insert").clicked() {
+                msg = Some(Msg::AddComponent(
+                    *entity,
+                    current.component.clone(),
+                    current.value.clone(),
+                ));
+                close = true;
+            }
+            if ui.button("Cancel").clicked() {
+                close = true;
+            }
+        });
+        if close {
+            *wizard = None;
+        }
+        msg
+    }
+
+    pub(super) fn draw_type_docs(&mut self, ctx: &egui::Context) {
+        let detached = self.is_detached("type_docs");
+        let toggled = show_panel(
+            ctx,
+            "type_docs",
+            "Type docs",
+            &mut self.show_type_docs,
+            detached,
+            |ui| {
+                let Some(type_path) = docs_target(ctx) else {
+                    ui.label("Hover a component or field to see its type here.");
+                    return;
+                };
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    draw_docs(ui, &self.schemas.lock().unwrap(), &type_path);
+                });
+            },
+        );
+        if toggled {
+            self.toggle_detached("type_docs");
+        }
+    }
+
+    pub(super) fn draw_change_feed(&mut self, ctx: &egui::Context) {
+        let mut select = None;
+        let mut filter = std::mem::take(&mut self.feed_filter);
+        let mut open = self.show_change_feed;
+        let detached = self.is_detached("change_feed");
+        let toggled = show_panel(
+            ctx,
+            "change_feed",
+            "Change feed",
+            &mut open,
+            detached,
+            |ui| {
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut filter)
+                            .hint_text("Filter by entity or type"),
+                    );
+                    if ui.button("Clear").clicked() {
+                        self.changes.lock().unwrap().clear_feed();
+                    }
+                });
+                let filter = filter.to_lowercase();
+                let components = self.components.lock().unwrap();
+                let changes = self.changes.lock().unwrap();
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("change_feed").striped(true).show(ui, |ui| {
+                        for entry in changes.feed() {
+                            let label = components
+                                .get(&entry.entity)
+                                .map_or(entry.entity.to_string(), |row| {
+                                    self.entity_label(&entry.entity, row)
+                                });
+                            let matches = filter.is_empty()
+                                || label.to_lowercase().contains(&filter)
+                                || entry.entity.to_string().contains(&filter)
+                                || entry.component.to_lowercase().contains(&filter);
+                            if !matches {
+                                continue;
+                            }
+                            ui.label(format!("{:.1}s", entry.at.elapsed().as_secs_f32()));
+                            if ui.link(label).clicked() {
+                                select = Some(entry.entity);
+                            }
+                            ui.label(short_name(&entry.component))
+                                .on_hover_text(&entry.component);
+                            match &entry.change {
+                                FeedChange::Added => {
+                                    ui.label(
+                                        RichText::new("added").color(status_colors(ui.ctx()).ok),
+                                    );
+                                }
+                                FeedChange::Removed => {
+                                    ui.label(
+                                        RichText::new("removed")
+                                            .color(status_colors(ui.ctx()).error),
+                                    );
+                                }
+                                FeedChange::Field { path, .. }
+                                    if self
+                                        .config
+                                        .redaction
+                                        .hides_field(&entry.component, path) =>
+                                {
+                                    ui.label(format!("{path} {MASK}"));
+                                }
+                                FeedChange::Field { path, old, new } => {
+                                    ui.label(format!("{path} {old} → {new}"));
+                                }
+                                FeedChange::More(count) => {
+                                    ui.label(RichText::new(format!("{count} more fields")).weak());
+                                }
+                            }
+                            ui.end_row();
+                        }
+                    });
+                });
+            },
+        );
+        self.feed_filter = filter;
+        self.show_change_feed = open;
+        if toggled {
+            self.toggle_detached("change_feed");
+        }
+        if let Some(entity) = select {
+            self.selected = Some(entity);
+            self.scroll_to_selected = true;
+        }
+    }
+
+    /// Review list of the staged edits, committed together as one batch.
+    pub(super) fn draw_staged(&mut self, ctx: &egui::Context) {
+        let mut commit = false;
+        let mut select = None;
+        let mut open = self.show_staged;
+        let detached = self.is_detached("staged");
+        let read_only = self.config.read_only;
+        let toggled = show_panel(ctx, "staged", "Staged changes", &mut open, detached, |ui| {
+            let mut edits = self.edits.lock().unwrap();
+            let components = self.components.lock().unwrap();
+            let name = |(entity, component): &EditKey| {
+                let label = components
+                    .get(entity)
+                    .map_or(entity.to_string(), |row| self.entity_label(entity, row));
+                format!("{label} {}", self.aliases.type_name(component))
+            };
+            if let Some(report) = &*self.commit_report.lock().unwrap() {
+                let colors = status_colors(ui.ctx());
+                if report.failed.is_empty() {
+                    ui.colored_label(colors.ok, format!("Committed {} changes", report.applied));
+                } else {
+                    ui.colored_label(colors.error, "Commit failed, nothing was kept");
+                    for (key, error) in &report.failed {
+                        ui.label(format!("Failed: {}: {error}", name(key)));
+                    }
+                    for key in &report.rolled_back {
+                        ui.label(format!("Rolled back: {}", name(key)));
+                    }
+                    for (key, error) in &report.rollback_failed {
+                        ui.colored_label(
+                            colors.warning,
+                            format!("Could not roll back {}: {error}", name(key)),
+                        );
+                    }
+                }
+                ui.separator();
+            }
+            let mut staged: Vec<_> = edits
+                .iter()
+                .filter(|(_, edit)| matches!(edit.state, EditState::Staged | EditState::Applying))
+                .map(|(key, _)| key.clone())
+                .collect();
+            staged.sort();
+            if staged.is_empty() {
+                ui.label("Nothing staged. Use \"Stage\" on an edited component.");
+                return;
+            }
+            let mut unstage = Vec::new();
+            let mut discard = Vec::new();
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for key in &staged {
+                    let (entity, component) = key;
+                    let edit = &edits[key];
+                    let label = components
+                        .get(entity)
+                        .map_or(entity.to_string(), |row| self.entity_label(entity, row));
+                    ui.horizontal(|ui| {
+                        if ui.link(label).clicked() {
+                            select = Some(*entity);
+                        }
+                        ui.label(self.aliases.type_name(component));
+                    });
+                    let fetched = components
+                        .get(entity)
+                        .and_then(|row| row.components.get(component));
+                    if let (Some(fetched), Ok(mine)) = (fetched, edit.parsed()) {
+                        let fields = modified_fields(fetched, &mine);
+                        let fields = if fields.iter().any(|f| f.is_empty()) {
+                            "whole value".to_owned()
+                        } else {
+                            fields.join(", ")
+                        };
+                        ui.label(RichText::new(fields).monospace().weak());
+                    }
+                    if let Some(error) = &edit.error {
+                        ui.label(RichText::new(error).color(status_colors(ui.ctx()).error));
+                    }
+                    ui.horizontal(|ui| {
+                        if matches!(edit.state, EditState::Applying) {
+                            power::spinner(ui);
+                            return;
+                        }
+                        if ui.small_button("Unstage").clicked() {
+                            unstage.push(key.clone());
+                        }
+                        if ui.small_button("Discard").clicked() {
+                            discard.push(key.clone());
+                        }
+                    });
+                    ui.separator();
+                }
+            });
+            for key in unstage {
+                if let Some(edit) = edits.get_mut(&key) {
+                    edit.state = EditState::Editing;
+                }
+            }
+            for key in discard {
+                edits.remove(&key);
+            }
+            let pending = edits
+                .values()
+                .filter(|edit| matches!(edit.state, EditState::Staged))
+                .count();
+            ui.horizontal(|ui| {
+                commit = ui
+                    .add_enabled(
+                        !read_only && pending > 0,
+                        egui::Button::new(format!("Commit {pending} changes")),
+                    )
+                    .clicked();
+                if ui.button("Discard all").clicked() {
+                    edits.retain(|_, edit| !matches!(edit.state, EditState::Staged));
+                }
+            });
+        });
+        self.show_staged = open;
+        if toggled {
+            self.toggle_detached("staged");
+        }
+        if commit {
+            self.commit_staged(ctx);
+        }
+        if let Some(entity) = select {
+            self.selected = Some(entity);
+            self.scroll_to_selected = true;
+        }
+    }
+
+    pub(super) fn draw_problems(&mut self, ctx: &egui::Context) {
+        let mut select = None;
+        let mut fix = None;
+        let mut open = self.show_problems;
+        let detached = self.is_detached("problems");
+        let can_fix = !self.config.read_only;
+        let can_reparent = can_fix && self.supports(BRP_REPARENT_METHOD);
+        let can_insert = can_fix && self.supports(BRP_INSERT_METHOD);
+        let toggled = show_panel(ctx, "problems", "Problems", &mut open, detached, |ui| {
+            let components = self.components.lock().unwrap();
+            let paths = self.bevy_version.paths();
+            let problems = hierarchy_problems(&components, paths);
+            let findings = lint(&components, paths, &self.config.disabled_lints);
+            ui.horizontal(|ui| {
+                ui.menu_button("Lint rules", |ui| {
+                    for rule in LintRule::ALL {
+                        let mut enabled = !self.config.disabled_lints.contains(&rule);
+                        if ui.checkbox(&mut enabled, rule.label()).changed() {
+                            self.config.disabled_lints.retain(|r| *r != rule);
+                            if !enabled {
+                                self.config.disabled_lints.push(rule);
+                            }
+                        }
+                    }
+                });
+                ui.weak(format!(
+                    "{} hierarchy problems, {} lint findings",
+                    problems.len(),
+                    findings.len()
+                ));
+            });
+            if problems.is_empty() && findings.is_empty() {
+                ui.label("No problems in the cached entities.");
+                return;
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for finding in &findings {
+                    ui.horizontal_wrapped(|ui| {
+                        labeled(
+                            ui.colored_label(status_colors(ui.ctx()).warning, "⚠"),
+                            egui::WidgetType::Label,
+                            "Warning",
+                        );
+                        ui.label(&finding.message)
+                            .on_hover_text(finding.rule.label());
+                        if ui.small_button("Select").clicked() {
+                            select = Some(finding.entity);
+                        }
+                    });
+                    ui.separator();
+                }
+                for problem in problems {
+                    ui.horizontal_wrapped(|ui| {
+                        labeled(
+                            ui.colored_label(status_colors(ui.ctx()).warning, "⚠"),
+                            egui::WidgetType::Label,
+                            "Warning",
+                        );
+                        ui.label(problem.describe());
+                    });
+                    ui.horizontal(|ui| {
+                        let entity = match problem {
+                            HierarchyProblem::Orphaned { entity, .. }
+                            | HierarchyProblem::NotInChildren { entity, .. } => entity,
+                            HierarchyProblem::StaleChild { parent, .. } => parent,
+                        };
+                        if ui.small_button("Select").clicked() {
+                            select = Some(entity);
+                        }
+                        for (label, candidate) in problem.fixes() {
+                            let enabled = match candidate {
+                                HierarchyFix::Reparent { .. } => can_reparent,
+                                HierarchyFix::RemoveChild { .. } => can_insert,
+                            };
+                            if ui
+                                .add_enabled(enabled, egui::Button::new(label).small())
+                                .clicked()
+                            {
+                                fix = Some(candidate);
+                            }
+                        }
+                    });
+                    ui.separator();
+                }
+            });
+        });
+        self.show_problems = open;
+        if toggled {
+            self.toggle_detached("problems");
+        }
+        if let Some(fix) = fix {
+            self.fix_hierarchy(ctx, fix);
+        }
+        if let Some(entity) = select {
+            self.selected = Some(entity);
+            self.scroll_to_selected = true;
+        }
+    }
+
+    pub(super) fn draw_insights(&mut self, ctx: &egui::Context) {
+        let mut select = None;
+        let mut analyze = false;
+        let mut open = self.show_insights;
+        let detached = self.is_detached("insights");
+        let toggled = show_panel(ctx, "insights", "Insights", &mut open, detached, |ui| {
+            analyze = ui
+                .button("Analyze cached entities")
+                .on_hover_text("Find components that almost always come with another one")
+                .clicked();
+            let Some(insights) = &self.insights else {
+                return;
+            };
+            if insights.is_empty() {
+                ui.label("No component reliably comes with another one.");
+                return;
+            }
+            let components = self.components.lock().unwrap();
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (index, insight) in insights.iter().enumerate() {
+                    let text = format!(
+                        "{} → {}: {:.0}% of {}",
+                        self.aliases.type_name(&insight.component),
+                        self.aliases.type_name(&insight.companion),
+                        insight.share() * 100.0,
+                        insight.total
+                    );
+                    if insight.missing.is_empty() {
+                        ui.label(text);
+                        continue;
+                    }
+                    egui::CollapsingHeader::new(
+                        RichText::new(format!("{text}, {} without", insight.missing.len()))
+                            .color(status_colors(ui.ctx()).warning),
+                    )
+                    .id_salt(("insight", index))
+                    .show(ui, |ui| {
+                        for entity in &insight.missing {
+                            let label = components
+                                .get(entity)
+                                .map_or(entity.to_string(), |row| self.entity_label(entity, row));
+                            if ui.link(label).clicked() {
+                                select = Some(*entity);
+                            }
+                        }
+                    });
+                }
+            });
+        });
+        self.show_insights = open;
+        if toggled {
+            self.toggle_detached("insights");
+        }
+        if analyze {
+            let world = self.components.lock().unwrap();
+            self.insights = Some(co_occurrence(&world, 0.9));
+        }
+        if let Some(entity) = select {
+            self.selected = Some(entity);
+            self.scroll_to_selected = true;
+        }
+    }
+
+    /// Links to the parent and children of the entity.
+    pub(super) fn draw_hierarchy(
+        &self,
+        ui: &mut egui::Ui,
+        item: &BrpQueryRow,
+        content: &HashMap<Entity, BrpQueryRow>,
+    ) -> Msg {
+        let mut action = Msg::None;
+        let paths = self.bevy_version.paths();
+        let mut link = |ui: &mut egui::Ui, entity: Entity| {
+            let label = content
+                .get(&entity)
+                .map_or(entity.to_string(), |row| self.entity_label(&entity, row));
+            if ui.link(label).clicked() {
+                action = Msg::Select(entity);
+            }
+        };
+        ui.label(RichText::new("Parent").strong());
+        match item.components.get(paths.parent).and_then(|v| v.as_u64()) {
+            Some(parent) => link(ui, Entity::from_bits(parent)),
+            None => {
+                ui.label("None, this is a root entity");
+            }
+        }
+        ui.add_space(8.0);
+        let children: Vec<u64> = item
+            .components
+            .get(paths.children)
+            .and_then(|v| v.as_array())
+            .map(|array| array.iter().filter_map(|v| v.as_u64()).collect())
+            .unwrap_or_default();
+        ui.label(RichText::new(format!("Children ({})", children.len())).strong());
+        for child in children {
+            link(ui, Entity::from_bits(child));
+        }
+        action
+    }
+
+    /// Component changes of the entity seen between fetches.
+    pub(super) fn draw_changes(&self, ui: &mut egui::Ui, entity: &Entity) {
+        let changes = self.changes.lock().unwrap();
+        let mut changes = changes.entity(entity).peekable();
+        if changes.peek().is_none() {
+            ui.label("Changes between fetches show up here.");
+            return;
+        }
+        egui::Grid::new("entity_changes")
+            .striped(true)
+            .show(ui, |ui| {
+                for change in changes {
+                    ui.label(format!("{:.1}s ago", change.at.elapsed().as_secs_f32()));
+                    ui.label(short_name(&change.component))
+                        .on_hover_text(&change.component);
+                    let pretty = |value| {
+                        let value = self.config.redaction.redact(&change.component, value);
+                        serde_json::to_string_pretty(&value).unwrap_or_default()
+                    };
+                    match &change.change {
+                        Change::Added(value) => ui
+                            .label(RichText::new("added").color(status_colors(ui.ctx()).ok))
+                            .on_hover_text(pretty(value)),
+                        Change::Changed(value) => ui.label("changed").on_hover_text(pretty(value)),
+                        Change::Removed => {
+                            ui.label(RichText::new("removed").color(status_colors(ui.ctx()).error))
+                        }
+                    };
+                    ui.end_row();
+                }
+            });
+    }
+
+    pub(super) fn draw_component_history(&mut self, ctx: &egui::Context) {
+        let detached = self.is_detached("component_history");
+        let timeline = self.timeline.clone();
+        let timeline = timeline.lock().unwrap();
+        let redaction = &self.config.redaction;
+        if self
+            .component_history
+            .show(ctx, &timeline, redaction, detached)
+        {
+            self.toggle_detached("component_history");
+        }
+    }
+
+    pub(super) fn draw_minimap(&mut self, ctx: &egui::Context) {
+        if !self.minimap.open {
+            return;
+        }
+        let points: Vec<MinimapPoint> = self
+            .components
+            .lock()
+            .unwrap()
+            .iter()
+            .filter_map(|(entity, row)| {
+                Some(MinimapPoint {
+                    entity: *entity,
+                    position: world_position(row)?,
+                    color: categories(row.components.keys())
+                        .last()
+                        .map_or(Color32::GRAY, |c| c.color()),
+                    label: self.entity_label(entity, row),
+                })
+            })
+            .collect();
+        let detached = self.is_detached("minimap");
+        let (clicked, toggled) = self.minimap.show(ctx, &points, self.selected, detached);
+        if toggled {
+            self.toggle_detached("minimap");
+        }
+        if let Some(entity) = clicked {
+            self.selected = Some(entity);
+            self.scroll_to_selected = true;
+        }
+    }
+
+    pub(super) fn draw_panels(&mut self, ctx: &egui::Context) {
+        let mut refresh = Vec::new();
+        let mut toggled_keys = Vec::new();
+        for (index, panel) in self.panels.lock().unwrap().iter_mut().enumerate() {
+            let CustomPanel {
+                descriptor,
+                open,
+                loading,
+                data,
+                error,
+            } = panel;
+            let key = format!("custom_panel:{}", descriptor.method);
+            let detached = self.is_detached(&key);
+            let toggled = show_panel(ctx, &key, &descriptor.name, open, detached, |ui| {
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(!*loading, egui::Button::new("Refresh"))
+                        .clicked()
+                    {
+                        refresh.push(index);
+                    }
+                    if *loading {
+                        power::spinner(ui);
+                    }
+                });
+                if let Some(error) = error {
+                    ui.label(RichText::new(error.as_str()).color(status_colors(ui.ctx()).error));
+                }
+                ui.separator();
+                egui::ScrollArea::vertical().show(ui, |ui| match data {
+                    Some(data) => draw_value(ui, &descriptor.schema, data),
+                    None => {
+                        ui.label("No data yet");
+                    }
+                });
+            });
+            if toggled {
+                toggled_keys.push(key);
+            }
+            if panel.open && panel.data.is_none() && !panel.loading && panel.error.is_none() {
+                refresh.push(index);
+            }
+        }
+        for index in refresh {
+            self.refresh_panel(ctx, index);
+        }
+        for key in toggled_keys {
+            self.toggle_detached(&key);
+        }
+    }
+
+    pub(super) fn draw_dashboard(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_dashboard;
+        let detached = self.is_detached("dashboard");
+        let mut dashboards = self.config.dashboards.clone();
+        let mut active = self.config.active_dashboard;
+        let mut import = false;
+        let toggled = show_panel(ctx, "dashboard", "Dashboard", &mut open, detached, |ui| {
+            ui.horizontal(|ui| {
+                let name = dashboards.get(active).map_or("None", |d| d.name.as_str());
+                egui::ComboBox::from_id_salt("dashboard_pick")
+                    .selected_text(name)
+                    .show_ui(ui, |ui| {
+                        for (index, dashboard) in dashboards.iter().enumerate() {
+                            ui.selectable_value(&mut active, index, &dashboard.name);
+                        }
+                    });
+                if ui.button("➕ New").clicked() {
+                    let name = format!("Dashboard {}", dashboards.len() + 1);
+                    dashboards.push(Dashboard::new(&name));
+                    active = dashboards.len() - 1;
+                }
+                if let Some(dashboard) = dashboards.get_mut(active) {
+                    ui.add(egui::TextEdit::singleline(&mut dashboard.name).desired_width(120.0))
+                        .on_hover_text("Rename");
+                    if ui
+                        .button("🗑")
+                        .on_hover_text("Delete the dashboard")
+                        .clicked()
+                    {
+                        dashboards.remove(active);
+                        active = active.saturating_sub(1);
+                    }
+                }
+                ui.menu_button("Share", |ui| {
+                    if let Some(dashboard) = dashboards.get(active) {
+                        if ui
+                            .button("Export this dashboard...")
+                            .on_hover_text("Along with the watched resources")
+                            .clicked()
+                        {
+                            ui.close_menu();
+                            self.export_dashboards(std::slice::from_ref(dashboard));
+                        }
+                    }
+                    if ui.button("Export all dashboards...").clicked() {
+                        ui.close_menu();
+                        self.export_dashboards(&dashboards);
+                    }
+                    if ui
+                        .button("Import...")
+                        .on_hover_text("Dashboards with the same name are replaced")
+                        .clicked()
+                    {
+                        ui.close_menu();
+                        import = true;
+                    }
+                });
+            });
+            ui.separator();
+            let Some(dashboard) = dashboards.get_mut(active) else {
+                ui.weak("Create a dashboard to pin values from the game.");
+                return;
+            };
+            let rows = self.components.lock().unwrap();
+            let resource = |resource: &str| self.resource_watch.value(resource);
+            let inputs = TileInputs {
+                rows: &rows,
+                paths: self.bevy_version.paths(),
+                resource: &resource,
+            };
+            let poll = self.activity.lock().unwrap().polls();
+            self.dashboard.sample(dashboard, poll, &inputs);
+            self.dashboard.draw(ui, dashboard, &inputs);
+        });
+        if dashboards != self.config.dashboards || active != self.config.active_dashboard {
+            self.send(ctx, Msg::SetDashboards(dashboards, active));
+        }
+        if import {
+            self.send(ctx, Msg::ImportDashboards);
+        }
+        self.show_dashboard = open;
+        if toggled {
+            self.toggle_detached("dashboard");
+        }
+    }
+
+    /// Differences between the two states picked on the timeline.
+    pub(super) fn draw_comparison(&mut self, ctx: &egui::Context) {
+        let timeline = self.timeline.clone();
+        let timeline = timeline.lock().unwrap();
+        let Some(compared) = timeline.compared() else {
+            self.comparison = None;
+            return;
+        };
+        if self.comparison.as_ref().map(|(key, _)| *key) != Some(compared) {
+            self.comparison = timeline.comparison().map(|c| (compared, c));
+        }
+        let Some((_, comparison)) = &self.comparison else {
+            return;
+        };
+        let (old, new) = compared;
+        let from = match new.checked_duration_since(old) {
+            Some(earlier) => format!("{:.1} s earlier", earlier.as_secs_f32()),
+            None => format!("{:.1} s later", (old - new).as_secs_f32()),
+        };
+        let mut open = true;
+        let mut select = None;
+        egui::Window::new("Compare states")
+            .open(&mut open)
+            .default_size([360.0, 320.0])
+            .show(ctx, |ui| {
+                ui.label(format!("Changes from the state {from} to the viewed one"));
+                if comparison.is_empty() {
+                    ui.weak("Both states are the same.");
+                    return;
+                }
+                let colors = status_colors(ui.ctx());
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for entity in &comparison.added {
+                        if ui
+                            .link(RichText::new(format!("+ {entity}")).color(colors.ok))
+                            .clicked()
+                        {
+                            select = Some(*entity);
+                        }
+                    }
+                    for entity in &comparison.removed {
+                        ui.label(RichText::new(format!("- {entity}")).color(colors.error));
+                    }
+                    for (entity, component, fields) in &comparison.changed {
+                        let header = format!("{entity} {}", self.aliases.type_name(component));
+                        egui::CollapsingHeader::new(RichText::new(header).color(colors.change))
+                            .id_salt((entity, component))
+                            .show(ui, |ui| {
+                                if ui.small_button("Select").clicked() {
+                                    select = Some(*entity);
+                                }
+                                for (path, old, new) in fields {
+                                    ui.monospace(format!("{path}: {old} → {new}"));
+                                }
+                            });
+                    }
+                });
+            });
+        drop(timeline);
+        if let Some(entity) = select {
+            self.selected = Some(entity);
+            self.scroll_to_selected = true;
+        }
+        if !open {
+            self.timeline.lock().unwrap().compare_with = None;
+            self.comparison = None;
+        }
+    }
+
+    /// Asks before destroying an entity or removing a component of a protected one.
+    pub(super) fn draw_confirmation(&mut self, ctx: &egui::Context) {
+        let Some(pending) = &self.pending_confirmation else {
+            return;
+        };
+        let block = pending.protected && self.config.block_protected;
+        let action = match &pending.msg {
+            Msg::RemoveComponent(_, component) => {
+                format!("Remove {}", self.aliases.type_name(component))
+            }
+            Msg::RemoveFromMany(_, component) => {
+                format!("Remove {} from all", self.aliases.type_name(component))
+            }
+            Msg::DestroyMany(_) => "Destroy all".to_owned(),
+            _ => "Destroy".to_owned(),
+        };
+        let title = if pending.protected {
+            "Protected entity"
+        } else {
+            "Destroy entities"
+        };
+        let mut confirmed = false;
+        let mut closed = false;
+        egui::Window::new(title)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                if !pending.protected {
+                    ui.label(&pending.reason);
+                } else if block {
+                    ui.label(format!("{} is protected.", pending.reason));
+                    ui.label("Actions on protected entities are blocked in the settings.");
+                } else {
+                    ui.label(format!("{} is protected.", pending.reason));
+                    ui.label("The game may not survive losing it.");
+                }
+                ui.horizontal(|ui| {
+                    let confirm = if pending.protected {
+                        format!("{action} anyway")
+                    } else {
+                        action
+                    };
+                    if !block
+                        && ui
+                            .button(RichText::new(confirm).color(ui.visuals().error_fg_color))
+                            .clicked()
+                    {
+                        confirmed = true;
+                    }
+                    closed = ui.button(if block { "OK" } else { "Cancel" }).clicked();
+                });
+                if !pending.protected {
+                    let mut ask = self.config.confirm_destroy;
+                    if ui
+                        .checkbox(&mut ask, "Always ask before destroying")
+                        .changed()
+                    {
+                        self.config.confirm_destroy = ask;
+                    }
+                }
+            });
+        if !confirmed && !closed {
+            return;
+        }
+        let Some(pending) = self.pending_confirmation.take() else {
+            return;
+        };
+        if confirmed {
+            let components = self.components.clone();
+            let content = components.lock().unwrap();
+            self.apply(ctx, pending.msg, &content);
+        }
+    }
+
+    /// Looks for saved type paths the game doesn't know once its types and schemas are
+    /// in, offering the dialog when some of them likely were renamed.
+    pub(super) fn check_migration(&mut self) {
+        if self.migration_checked {
+            return;
+        }
+        let Some(known) = self.known_types() else {
+            return;
+        };
+        self.migration_checked = true;
+        self.migration = Migration::find(&self.config, &known).filter(Migration::has_suggestions);
+    }
+
+    /// Every saved type path the game doesn't know, ignored ones included.
+    pub(super) fn open_migration(&mut self) {
+        let Some(known) = self.known_types() else {
+            *self.error_info.lock().unwrap() = Some(InspectorError::Local(
+                "Connect to the game to compare the saved type paths with its types".to_owned(),
+            ));
+            return;
+        };
+        self.config.ignored_type_paths.clear();
+        self.migration = Some(Migration::find(&self.config, &known).unwrap_or_default());
+    }
+
+    /// Registered types of the connected game, `None` until its type list and schemas
+    /// arrived.
+    fn known_types(&self) -> Option<std::collections::HashSet<String>> {
+        if !self.capabilities.lock().unwrap().is_known() {
+            return None;
+        }
+        let types = self
+            .query_list
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|query| query.data.option.clone())?;
+        let schemas = self.schemas.lock().unwrap();
+        if self.supports(BRP_REGISTRY_SCHEMA_METHOD) && schemas.is_empty() {
+            return None;
+        }
+        let rejected = self.rejected_types.lock().unwrap();
+        Some(
+            types
+                .into_iter()
+                .chain(rejected.keys().cloned())
+                .chain(schemas.type_paths().cloned())
+                .collect(),
+        )
+    }
+
+    /// Old saved type paths next to the new ones they are moved to, each can be
+    /// unchecked or corrected.
+    pub(super) fn draw_migration(&mut self, ctx: &egui::Context) {
+        let Some(migration) = &mut self.migration else {
+            return;
+        };
+        let mut apply = false;
+        let mut ignore = false;
+        let mut later = false;
+        egui::Window::new("Renamed types")
+            .collapsible(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+            .show(ctx, |ui| {
+                if migration.renames.is_empty() {
+                    ui.label("The game knows every type path in the settings.");
+                } else {
+                    ui.label(
+                        "The settings refer to types the game doesn't have, they were likely \
+                         renamed or moved. Checked ones are replaced everywhere they are saved.",
+                    );
+                }
+                egui::ScrollArea::vertical()
+                    .max_height(400.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("type_migration")
+                            .num_columns(3)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for rename in &mut migration.renames {
+                                    ui.checkbox(&mut rename.apply, "");
+                                    ui.label(RichText::new(&rename.from).monospace());
+                                    ui.add(
+                                        egui::TextEdit::singleline(&mut rename.to)
+                                            .hint_text("New type path")
+                                            .desired_width(300.0),
+                                    );
+                                    ui.end_row();
+                                }
+                            });
+                    });
+                ui.horizontal(|ui| {
+                    apply = ui.button("Migrate").clicked();
+                    ignore = ui
+                        .button("Ignore")
+                        .on_hover_text("Don't offer these paths again")
+                        .clicked();
+                    later = ui.button("Later").clicked();
+                });
+            });
+        if apply {
+            self.config = migration.apply(&self.config);
+            *self.refresh_requested.lock().unwrap() = true;
+        }
+        if ignore {
+            let paths = migration.renames.iter().map(|rename| rename.from.clone());
+            self.config.ignored_type_paths.extend(paths);
+        }
+        if apply || ignore || later {
+            self.migration = None;
+        }
+    }
+
+    pub(super) fn draw_protection_settings(&mut self, ui: &mut egui::Ui) {
+        ui.label("Entities with these components ask before being destroyed or changed:");
+        let mut remove = None;
+        for (index, pattern) in self.config.protected_components.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui.small_button("✖").on_hover_text("Unprotect").clicked() {
+                    remove = Some(index);
+                }
+                ui.label(pattern);
+            });
+        }
+        if let Some(index) = remove {
+            self.config.protected_components.remove(index);
+        }
+        let id = ui.id().with("new_protected");
+        let mut text = ui
+            .data_mut(|d| d.get_temp::<String>(id))
+            .unwrap_or_default();
+        ui.horizontal(|ui| {
+            let response = ui.add(
+                egui::TextEdit::singleline(&mut text)
+                    .hint_text("Type path or short name")
+                    .desired_width(160.0),
+            );
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if (ui.button("Add").clicked() || submitted) && !text.trim().is_empty() {
+                self.config
+                    .protected_components
+                    .push(text.trim().to_owned());
+                text.clear();
+            }
+        });
+        ui.data_mut(|d| d.insert_temp(id, text));
+        ui.checkbox(&mut self.config.block_protected, "Block instead of asking");
+        ui.checkbox(
+            &mut self.config.confirm_destroy,
+            "Ask before destroying any entity",
+        );
+    }
+
+    pub(super) fn draw_redaction_settings(&mut self, ui: &mut egui::Ui) {
+        let redaction = &mut self.config.redaction;
+        ui.checkbox(&mut redaction.enabled, "Hide sensitive values")
+            .on_hover_text("In the views, copied JSON and exported scenes and graphs");
+        ui.label("Components, or fields of them like Account.auth.token, shown as ••• :");
+        let mut remove = None;
+        for (index, rule) in redaction.rules.iter().enumerate() {
+            ui.horizontal(|ui| {
+                if ui.small_button("✖").on_hover_text("Show again").clicked() {
+                    remove = Some(index);
+                }
+                ui.label(rule);
+            });
+        }
+        if let Some(index) = remove {
+            redaction.rules.remove(index);
+        }
+        let types: Vec<String> = self
+            .query_list
+            .lock()
+            .unwrap()
+            .iter()
+            .flat_map(|query| query.data.option.iter().cloned())
+            .collect();
+        let id = ui.id().with("new_redaction");
+        let mut text = ui
+            .data_mut(|d| d.get_temp::<String>(id))
+            .unwrap_or_default();
+        ui.horizontal(|ui| {
+            let input = type_path_input(
+                ui,
+                "new_redaction_input",
+                &mut text,
+                &types,
+                "Type path or short name, * for any",
+                220.0,
+            );
+            let submitted =
+                input.response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if (ui.button("Add").clicked() || submitted) && !text.trim().is_empty() {
+                redaction.rules.push(text.trim().to_owned());
+                text.clear();
+            }
+        });
+        ui.data_mut(|d| d.insert_temp(id, text));
+        ui.weak("Protocol traces are exported as they were received.");
+    }
+
+    pub(super) fn draw_group_settings(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.config.group_entities, "Group the tree");
+        ui.label("Top-level entities with any of a group's markers are listed under it:");
+        let mut remove = None;
+        for (index, group) in self.config.entity_groups.iter_mut().enumerate() {
+            ui.push_id(index, |ui| {
+                ui.horizontal(|ui| {
+                    if ui.small_button("✖").on_hover_text("Remove group").clicked() {
+                        remove = Some(index);
+                    }
+                    group.edit(ui);
+                });
+            });
+        }
+        if let Some(index) = remove {
+            self.config.entity_groups.remove(index);
+        }
+        if ui.button("➕ Add group").clicked() {
+            self.config.entity_groups.push(EntityGroup::default());
+        }
+    }
+
+    /// Checks the alert rules against the values of the latest poll.
+    pub(super) fn check_alerts(&mut self) {
+        if self.config.alerts.is_empty() {
+            return;
+        }
+        let rows = self.components.lock().unwrap();
+        let resource = |resource: &str| self.resource_watch.value(resource);
+        let inputs = TileInputs {
+            rows: &rows,
+            paths: self.bevy_version.paths(),
+            resource: &resource,
+        };
+        let poll = self.activity.lock().unwrap().polls();
+        let Some(snapshot) = self.alerts.check(&self.config.alerts, poll, &inputs) else {
+            return;
+        };
+        let Some(dir) = &self.config.alert_snapshot_dir else {
+            return;
+        };
+        let path = dir.join(snapshot.file_name());
+        let entities = scene_entities(&snapshot.rows, self.bevy_version.paths(), None);
+        snapshot.saved = Some(
+            write_scene(&path, &entities)
+                .map(|()| path)
+                .map_err(|e| e.to_string()),
+        );
+    }
+
+    pub(super) fn draw_query_builder(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_query_builder;
+        let detached = self.is_detached("query_builder");
+        let title = "Query builder";
+        let toggled = show_panel(ctx, "query_builder", title, &mut open, detached, |ui| {
+            if !self.config.saved_queries.is_empty() {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label("Saved:");
+                    let mut remove = None;
+                    for (index, query) in self.config.saved_queries.iter().enumerate() {
+                        let response = ui
+                            .button(&query.name)
+                            .on_hover_text("Right-click to delete");
+                        if response.clicked() {
+                            self.query_draft = query.clone();
+                        }
+                        response.context_menu(|ui| {
+                            if ui.button("🗑 Delete").clicked() {
+                                remove = Some(index);
+                                ui.close_menu();
+                            }
+                        });
+                    }
+                    if let Some(index) = remove {
+                        self.config.saved_queries.remove(index);
+                    }
+                });
+                ui.separator();
+            }
+            ui.horizontal(|ui| {
+                ui.label("Name");
+                ui.text_edit_singleline(&mut self.query_draft.name);
+            });
+            let types = self
+                .query_list
+                .lock()
+                .unwrap()
+                .as_ref()
+                .map(|query| query.data.option.clone())
+                .unwrap_or_default();
+            self.query_draft.edit(ui, &types);
+            ui.separator();
+            ui.horizontal(|ui| {
+                if ui.button("▶ Apply").clicked() {
+                    self.config.query = Some(self.query_draft.clone());
+                    *self.refresh_requested.lock().unwrap() = true;
+                }
+                let has_name = !self.query_draft.name.trim().is_empty();
+                if ui
+                    .add_enabled(has_name, egui::Button::new("💾 Save"))
+                    .on_disabled_hover_text("Name the query to save it")
+                    .clicked()
+                {
+                    let saved = &mut self.config.saved_queries;
+                    match saved.iter_mut().find(|q| q.name == self.query_draft.name) {
+                        Some(query) => *query = self.query_draft.clone(),
+                        None => saved.push(self.query_draft.clone()),
+                    }
+                }
+                if ui
+                    .add_enabled(
+                        self.config.query.is_some(),
+                        egui::Button::new("Fetch everything"),
+                    )
+                    .on_hover_text("Go back to fetching every registered type")
+                    .clicked()
+                {
+                    self.config.query = None;
+                    *self.refresh_requested.lock().unwrap() = true;
+                }
+            });
+        });
+        self.show_query_builder = open;
+        if toggled {
+            self.toggle_detached("query_builder");
+        }
+    }
+
+    pub(super) fn draw_alerts(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_alerts;
+        let detached = self.is_detached("alerts");
+        let toggled = show_panel(ctx, "alerts", "Alerts", &mut open, detached, |ui| {
+            let errors = self.alerts.delivery_errors.lock().unwrap().clone();
+            let mut remove = None;
+            for (index, rule) in self.config.alerts.iter_mut().enumerate() {
+                ui.push_id(index, |ui| {
+                    egui::CollapsingHeader::new(&rule.name)
+                        .id_salt("rule")
+                        .show(ui, |ui| {
+                            rule.edit(ui);
+                            if ui.button("🗑 Remove").clicked() {
+                                remove = Some(index);
+                            }
+                        });
+                    if let Some(error) = errors.get(&rule.name) {
+                        ui.colored_label(
+                            status_colors(ui.ctx()).error,
+                            format!("Sending failed: {error}"),
+                        );
+                    }
+                });
+            }
+            if let Some(index) = remove {
+                self.config.alerts.remove(index);
+            }
+            if ui.button("➕ Add alert").clicked() {
+                self.config.alerts.push(AlertRule::default());
+            }
+            ui.separator();
+            self.draw_alert_snapshots(ui);
+            ui.separator();
+            ui.horizontal(|ui| {
+                ui.strong("Fired");
+                if !self.alerts.fired.is_empty() && ui.small_button("Clear").clicked() {
+                    self.alerts.fired.clear();
+                }
+            });
+            if self.alerts.fired.is_empty() {
+                ui.weak("Nothing fired yet.");
+            }
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for alert in &self.alerts.fired {
+                    ui.label(format!(
+                        "{}s ago  {}: {}",
+                        alert.at.elapsed().as_secs(),
+                        alert.rule,
+                        alert.message
+                    ));
+                }
+            });
+        });
+        self.show_alerts = open;
+        if toggled {
+            self.toggle_detached("alerts");
+        }
+    }
+
+    /// Where snapshots of alerts go, and the ones taken so far.
+    fn draw_alert_snapshots(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.strong("Snapshots");
+            match &self.config.alert_snapshot_dir {
+                Some(dir) => {
+                    ui.weak(dir.display().to_string());
+                    if ui
+                        .small_button("✖")
+                        .on_hover_text("Keep them in memory only")
+                        .clicked()
+                    {
+                        self.config.alert_snapshot_dir = None;
+                    }
+                }
+                None => {
+                    ui.weak("kept in memory");
+                }
+            }
+            if ui
+                .small_button("Folder...")
+                .on_hover_text("Write every snapshot to a folder as it's taken")
+                .clicked()
+            {
+                if let Some(dir) = files::FileDialog::new().pick_folder() {
+                    self.config.alert_snapshot_dir = Some(dir);
+                }
+            }
+        });
+        let colors = status_colors(ui.ctx());
+        let mut save = None;
+        let mut forget = None;
+        for (index, snapshot) in self.alerts.snapshots.iter().enumerate() {
+            ui.horizontal(|ui| {
+                let age = snapshot.taken.elapsed().unwrap_or_default().as_secs();
+                ui.label(format!(
+                    "{age}s ago  {}: {} entities",
+                    snapshot.rule,
+                    snapshot.rows.len()
+                ))
+                .on_hover_text(&snapshot.message);
+                match &snapshot.saved {
+                    Some(Ok(path)) => {
+                        ui.weak("saved").on_hover_text(path.display().to_string());
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(colors.error, "⚠")
+                            .on_hover_text(format!("Writing it failed: {e}"));
+                    }
+                    None => {}
+                }
+                if ui.small_button("Save...").clicked() {
+                    save = Some(index);
+                }
+                if ui.small_button("🗑").on_hover_text("Forget it").clicked() {
+                    forget = Some(index);
+                }
+            });
+        }
+        if let Some(snapshot) = save.and_then(|index| self.alerts.snapshots.get(index)) {
+            let path = files::FileDialog::new()
+                .add_filter("JSON", &["json"])
+                .add_filter("Bevy scene", &["ron"])
+                .set_file_name(snapshot.file_name())
+                .save_file();
+            if let Some(path) = path {
+                let entities = scene_entities(&snapshot.rows, self.bevy_version.paths(), None);
+                if let Err(e) = self.write_redacted_scene(&path, entities) {
+                    *self.error_info.lock().unwrap() = Some(
+                        InspectorError::Local(e.to_string()).context("Failed to save the snapshot"),
+                    );
+                }
+            }
+        }
+        if let Some(index) = forget {
+            self.alerts.snapshots.remove(index);
+        }
+    }
+
+    fn export_dashboards(&self, dashboards: &[Dashboard]) {
+        let file_name = match dashboards {
+            [dashboard] => format!("{}.json", dashboard.name),
+            _ => "dashboards.json".to_owned(),
+        };
+        let Some(path) = files::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .set_file_name(file_name)
+            .save_file()
+        else {
+            return;
+        };
+        let pack = DashboardPack {
+            dashboards: dashboards.to_vec(),
+            watched_resources: self.config.watched_resources.clone(),
+        };
+        if let Err(e) = pack.write(&path) {
+            *self.error_info.lock().unwrap() =
+                Some(InspectorError::Local(e.to_string()).context("Failed to export dashboards"));
+        }
+    }
+
+    pub(super) fn import_dashboards(&mut self) {
+        let Some(path) = files::FileDialog::new()
+            .add_filter("JSON", &["json"])
+            .pick_file()
+        else {
+            return;
+        };
+        match DashboardPack::read(&path) {
+            Ok(pack) => pack.merge_into(
+                &mut self.config.dashboards,
+                &mut self.config.watched_resources,
+            ),
+            Err(e) => {
+                *self.error_info.lock().unwrap() = Some(
+                    InspectorError::Local(e.to_string()).context("Failed to import dashboards"),
+                );
+            }
+        }
+    }
+
+    /// The overlay for monitoring a fullscreen game: watched resources, then whatever
+    /// needs attention.
+    pub(super) fn draw_hud(&self, ctx: &egui::Context) {
+        if !self.config.hud {
+            return;
+        }
+        let problems =
+            hierarchy_problems(&self.components.lock().unwrap(), self.bevy_version.paths()).len();
+        show_hud(ctx, self.config.hud_corner, |ui| {
+            if self.config.watched_resources.is_empty() {
+                ui.weak("No watched resources");
+            } else {
+                self.resource_watch
+                    .draw_values(ui, &self.config.watched_resources);
+            }
+            ui.separator();
+            let colors = status_colors(ui.ctx());
+            let mut alerts = false;
+            let recent = self
+                .alerts
+                .fired
+                .iter()
+                .take_while(|alert| alert.at.elapsed() < HUD_ALERT_DURATION);
+            for alert in recent {
+                ui.colored_label(
+                    colors.warning,
+                    format!("🔔 {}: {}", alert.rule, alert.message),
+                );
+                alerts = true;
+            }
+            if let Some(error) = &*self.error_info.lock().unwrap() {
+                ui.colored_label(colors.error, format!("⚠ {}", error.title()));
+                alerts = true;
+            }
+            if let Download::InProgress { started, .. } = &*self.download.lock().unwrap() {
+                if started.elapsed() > REQUEST_WARNING_AFTER {
+                    ui.colored_label(colors.warning, "⚠ Fetch is taking long");
+                    alerts = true;
+                }
+            }
+            if problems > 0 {
+                ui.colored_label(colors.warning, format!("⚠ {problems} hierarchy problems"));
+                alerts = true;
+            }
+            if !alerts {
+                ui.colored_label(colors.ok, "✔ No alerts");
+            }
+        });
+    }
+
+    /// Polls the game's diagnostics while open, so they line up with what's done meanwhile.
+    pub(super) fn draw_performance(&mut self, ctx: &egui::Context) {
+        if !self.show_performance {
+            return;
+        }
+        let diagnostics = self.supports(INSPECTOR_DIAGNOSTICS_METHOD);
+        let policy = self.refresh_policy(RefreshTarget::Diagnostics);
+        if self.query_list.lock().unwrap().is_some()
+            && throttle::remaining().is_none()
+            && self.refresh.due(ctx, RefreshTarget::Diagnostics, policy)
+        {
+            let cached = self.components.lock().unwrap().len();
+            self.performance
+                .poll(ctx, &self.endpoint(), diagnostics, cached);
+        }
+        let mut open = self.show_performance;
+        let detached = self.is_detached("performance");
+        let toggled = show_panel(
+            ctx,
+            "performance",
+            "Performance",
+            &mut open,
+            detached,
+            |ui| {
+                ui.horizontal(|ui| {
+                    ui.weak("Lines mark spawns, destroys and edits done from the inspector.");
+                    if ui.button("Clear").clicked() {
+                        self.performance.clear();
+                    }
+                });
+                self.performance.draw(ui, diagnostics);
+            },
+        );
+        self.show_performance = open;
+        if toggled {
+            self.toggle_detached("performance");
+        }
+    }
+
+    /// Polls the companion plugin's render stats while open, plotted against the same
+    /// markers as the performance panel.
+    pub(super) fn draw_render_stats(&mut self, ctx: &egui::Context) {
+        if !self.show_render_stats {
+            return;
+        }
+        if !self.supports(INSPECTOR_RENDER_STATS_METHOD) {
+            self.show_render_stats = false;
+            return;
+        }
+        let policy = self.refresh_policy(RefreshTarget::Rendering);
+        if self.query_list.lock().unwrap().is_some()
+            && throttle::remaining().is_none()
+            && self.refresh.due(ctx, RefreshTarget::Rendering, policy)
+        {
+            self.performance.poll_render(ctx, &self.endpoint());
+        }
+        let mut open = self.show_render_stats;
+        let detached = self.is_detached("rendering");
+        let toggled = show_panel(ctx, "rendering", "Rendering", &mut open, detached, |ui| {
+            ui.horizontal(|ui| {
+                ui.weak("Lines mark spawns, destroys and edits done from the inspector.");
+                if ui.button("Clear").clicked() {
+                    self.performance.clear();
+                }
+            });
+            self.performance.draw_render(ui);
+        });
+        self.show_render_stats = open;
+        if toggled {
+            self.toggle_detached("rendering");
+        }
+    }
+
+    /// The RPC log console docked at the bottom, collapsible to a single line.
+    pub(super) fn draw_rpc_log(&mut self, ui: &mut egui::Ui) {
+        let title = format!("📜 RPC log ({})", trace::len());
+        let mut action = RpcLogAction::None;
+        let id = ui.make_persistent_id("rpc_log_console");
+        egui::collapsing_header::CollapsingState::load_with_default_open(ui.ctx(), id, true)
+            .show_header(ui, |ui| {
+                ui.strong(title);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.small_button("✖").on_hover_text("Close").clicked() {
+                        self.show_rpc_log = false;
+                    }
+                });
+            })
+            .body(|ui| action = self.rpc_log.draw(ui));
+        if let RpcLogAction::Export(seqs) = action {
+            self.export_rpc_log(&seqs);
+        }
+    }
+
+    /// The bookmarked fields with their current values, editable in place.
+    pub(super) fn draw_bookmarks(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_bookmarks;
+        let mut select = None;
+        let mut remove = None;
+        let mut insert = None;
+        let can_insert = !self.config.read_only && self.supports(BRP_INSERT_METHOD);
+        let detached = self.is_detached("bookmarks");
+        let toggled = show_panel(ctx, "bookmarks", "Bookmarks", &mut open, detached, |ui| {
+            if self.config.bookmarks.is_empty() {
+                ui.label("Bookmark fields from the context menu of a component.");
+                return;
+            }
+            let components = self.components.lock().unwrap();
+            let paths = self.bevy_version.paths();
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                egui::Grid::new("bookmarks")
+                    .num_columns(3)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (index, bookmark) in self.config.bookmarks.iter().enumerate() {
+                            let resolved = bookmark.resolve(&components, paths);
+                            let link = ui
+                                .add_enabled(resolved.is_some(), egui::Link::new(bookmark.label()))
+                                .on_hover_text(&bookmark.component);
+                            if link.clicked() {
+                                select = resolved.map(|(entity, _)| entity);
+                            }
+                            match resolved {
+                                Some((entity, value)) if can_insert => {
+                                    let id = ui.id().with(("bookmark", index));
+                                    let change = edit_field(ui, id, value);
+                                    let (live, field) = match change {
+                                        FieldChange::None => (false, None),
+                                        FieldChange::Dragging(field) => (true, Some(field)),
+                                        FieldChange::Done(field) => (false, Some(field)),
+                                    };
+                                    let component =
+                                        components[&entity].components.get(&bookmark.component);
+                                    if let Some(value) =
+                                        field.zip(component).and_then(|(field, component)| {
+                                            with_field(component, &bookmark.path, field)
+                                        })
+                                    {
+                                        insert =
+                                            Some((entity, bookmark.component.clone(), value, live));
+                                    }
+                                }
+                                Some((_, value)) => {
+                                    ui.label(value.to_string());
+                                }
+                                None => {
+                                    ui.weak("not found");
+                                }
+                            }
+                            if ui
+                                .small_button("✖")
+                                .on_hover_text("Remove bookmark")
+                                .clicked()
+                            {
+                                remove = Some(index);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+        });
+        self.show_bookmarks = open;
+        if toggled {
+            self.toggle_detached("bookmarks");
+        }
+        if let Some(index) = remove {
+            self.config.bookmarks.remove(index);
+        }
+        if let Some((entity, component, value, live)) = insert {
+            match live {
+                true => self.send_value(ctx, entity, component, value),
+                false => self.insert_value(ctx, entity, component, value),
+            }
+        }
+        if let Some(entity) = select {
+            self.selected = Some(entity);
+            self.scroll_to_selected = true;
+        }
+    }
+
+    /// The catalog of registered component types, from the type list of the last fetch.
+    pub(super) fn draw_types(&mut self, ctx: &egui::Context) {
+        if !self.show_types {
+            return;
+        }
+        let mut open = self.show_types;
+        let mut action = CatalogAction::None;
+        let detached = self.is_detached("types");
+        let toggled = show_panel(ctx, "types", "Types", &mut open, detached, |ui| {
+            let Some(types) = self
+                .query_list
+                .lock()
+                .unwrap()
+                .as_ref()
+                .map(|query| query.data.option.clone())
+            else {
+                ui.label("Connect to list the registered types.");
+                return;
+            };
+            let entries = catalog(
+                &types,
+                &self.rejected_types.lock().unwrap(),
+                &self.schemas.lock().unwrap(),
+                &self.components.lock().unwrap(),
+            );
+            action = self.type_catalog.draw(ui, &entries);
+        });
+        self.show_types = open;
+        if toggled {
+            self.toggle_detached("types");
+        }
+        match action {
+            CatalogAction::None => {}
+            CatalogAction::FilterEntities(type_path) => self.search.has_component = type_path,
+            CatalogAction::ShowDocs(type_path) => {
+                set_docs_target(ctx, &type_path);
+                self.show_type_docs = true;
+            }
+        }
+    }
+
+    /// Requests of the scheduler in flight and waiting, so long batch operations can be
+    /// cut short or pushed out at once.
+    pub(super) fn draw_request_queue(&mut self, ctx: &egui::Context) {
+        if !self.show_request_queue {
+            return;
+        }
+        let mut open = self.show_request_queue;
+        let detached = self.is_detached("request_queue");
+        let scheduler = self.scheduler.clone();
+        let requests = scheduler.requests();
+        if !requests.is_empty() {
+            self.config
+                .power_saver
+                .request_repaint_after(ctx, Duration::from_millis(250));
+        }
+        let toggled = show_panel(
+            ctx,
+            "request_queue",
+            "Request queue",
+            &mut open,
+            detached,
+            |ui| {
+                ui.weak(
+                    "Only spawn, respawn, destroy, remove and reparent requests are queued. \
+                     Edits, pausing, stepping and fetches are sent right away and aren't \
+                     listed.",
+                );
+                let waiting = requests.iter().filter(|r| r.sent.is_none()).count();
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "{} in flight, {waiting} waiting",
+                        requests.len() - waiting
+                    ));
+                    if ui
+                        .add_enabled(waiting > 0, egui::Button::new("Send all now"))
+                        .on_hover_text("Send the waiting requests regardless of the limits")
+                        .clicked()
+                    {
+                        scheduler.flush();
+                    }
+                    if ui
+                        .add_enabled(waiting > 0, egui::Button::new("Cancel all"))
+                        .clicked()
+                    {
+                        scheduler.cancel_all();
+                    }
+                });
+                if requests.is_empty() {
+                    ui.weak("Nothing queued.");
+                    return;
+                }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    egui::Grid::new("request_queue_grid")
+                        .num_columns(4)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for request in &requests {
+                                ui.strong(&request.method);
+                                ui.label(&request.target);
+                                let age = request.queued.elapsed().as_secs_f32();
+                                match request.sent {
+                                    Some(sent) => {
+                                        ui.weak(format!(
+                                            "sent {:.1}s ago",
+                                            sent.elapsed().as_secs_f32()
+                                        ))
+                                        .on_hover_text(format!("Queued {age:.1}s ago"));
+                                        power::spinner(ui);
+                                    }
+                                    None => {
+                                        ui.weak(format!("waiting {age:.1}s"));
+                                        if ui.small_button("✖").on_hover_text("Cancel").clicked()
+                                        {
+                                            scheduler.cancel(request.id);
+                                        }
+                                    }
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+            },
+        );
+        self.show_request_queue = open;
+        if toggled {
+            self.toggle_detached("request_queue");
+        }
+    }
+
+    pub(super) fn draw_stats(&mut self, ctx: &egui::Context) {
+        let mut open = self.show_stats;
+        let detached = self.is_detached("stats");
+        let toggled = show_panel(ctx, "stats", "Stats", &mut open, detached, |ui| {
+            // Before the timeline is locked, the estimate reads it too.
+            let (costs, bytes) = self.refresh_cost();
+            let timeline = self.timeline.lock().unwrap();
+            let memory = timeline.memory() as f64 / (1024.0 * 1024.0);
+            let budget = self.config.retained_memory_mb;
+            egui::Grid::new("stats").num_columns(2).show(ui, |ui| {
+                ui.label("Cached entities");
+                ui.label(self.components.lock().unwrap().len().to_string());
+                ui.end_row();
+                ui.label("Retained states");
+                ui.label(format!(
+                    "{} of {}",
+                    timeline.len(),
+                    self.config.timeline_length
+                ));
+                ui.end_row();
+                ui.label("Retained memory");
+                let text = format!("{memory:.1} MB of {budget} MB");
+                if memory > budget as f64 * 0.9 {
+                    ui.colored_label(status_colors(ui.ctx()).warning, text);
+                } else {
+                    ui.label(text);
+                }
+                ui.end_row();
+                ui.label("Queries in history");
+                ui.label(self.history.lock().unwrap().records().count().to_string());
+                ui.end_row();
+                ui.label("Refresh cost");
+                if self.polling_paused {
+                    ui.label("Paused while minimized");
+                } else {
+                    let requests: f64 = costs.iter().map(|(_, rate)| rate).sum();
+                    ui.label(format!(
+                        "~{requests:.1} requests/s, ~{}/s",
+                        format_size(bytes as usize)
+                    ))
+                    .on_hover_ui(|ui| {
+                        for (label, rate) in &costs {
+                            ui.label(format!("{label}: {rate:.1}/s"));
+                        }
+                        ui.weak("The world fetch is estimated from the size of the last one");
+                    });
+                }
+                ui.end_row();
+                if let Some(min) = self.config.power_saver.min_interval() {
+                    ui.label("Power saver");
+                    ui.label(format!(
+                        "At most {} frames/s, polls every {} ms or slower",
+                        self.config.power_saver.max_fps,
+                        min.as_millis()
+                    ));
+                    ui.end_row();
+                }
+            });
+            ui.separator();
+            let latency = self.latency.lock().unwrap();
+            let ms = |time: Option<Duration>| {
+                time.map_or("–".to_owned(), |t| {
+                    format!("{:.1} ms", t.as_secs_f64() * 1000.0)
+                })
+            };
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "Round trip {}, median {}",
+                    ms(latency.last()),
+                    ms(latency.median())
+                ));
+                let spikes = latency.spikes();
+                if spikes > 0 {
+                    ui.colored_label(
+                        status_colors(ui.ctx()).warning,
+                        format!("{spikes} spikes or lost"),
+                    );
+                }
+            })
+            .response
+            .on_hover_text(
+                "Measured with a request the game answers without work. Spikes here are the \
+                 network, spikes only in fetch times are the game.",
+            );
+            latency.draw(ui);
+        });
+        self.show_stats = open;
+        if toggled {
+            self.toggle_detached("stats");
+        }
+    }
+}
+
+/// `row` pretty-printed. Kept between frames and only printed again once the row changed,
+/// as printing a large entity takes longer than drawing it.
+fn raw_json(ctx: &egui::Context, row: &BrpQueryRow) -> Arc<str> {
+    #[derive(Clone, Default)]
+    struct Printed {
+        entity: Option<Entity>,
+        components: HashMap<String, serde_json::Value>,
+        has: HashMap<String, serde_json::Value>,
+        text: Arc<str>,
+    }
+    ctx.data_mut(|d| {
+        let printed = d.get_temp_mut_or_default::<Printed>(egui::Id::new("raw_json"));
+        let unchanged = printed.entity == Some(row.entity)
+            && printed.components == row.components
+            && printed.has == row.has;
+        if !unchanged {
+            *printed = Printed {
+                entity: Some(row.entity),
+                components: row.components.clone(),
+                has: row.has.clone(),
+                text: serde_json::to_string_pretty(row).unwrap_or_default().into(),
+            };
+        }
+        printed.text.clone()
+    })
+}
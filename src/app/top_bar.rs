@@ -0,0 +1,776 @@
+//! The toolbar and the controls living in it.
+
+use super::*;
+
+impl TemplateApp {
+    /// Address of the game with its connection state, editable from the toolbar.
+    pub(super) fn draw_connection(&mut self, ui: &mut egui::Ui) {
+        let status = self.connection_status();
+        let colors = status_colors(ui.ctx());
+        let dot = match status {
+            ConnectionStatus::Connected(_) => colors.ok,
+            ConnectionStatus::Failed(_) => colors.error,
+            ConnectionStatus::Testing => colors.warning,
+            ConnectionStatus::Untested => ui.visuals().weak_text_color(),
+        };
+        let metadata = self.metadata.lock().unwrap().clone();
+        let title = match &metadata {
+            Some(metadata) => format!(
+                "● {} @ {}:{}",
+                metadata.title(),
+                self.config.host,
+                self.config.port
+            ),
+            None => format!("● {}:{}", self.config.host, self.config.port),
+        };
+        let response = ui.menu_button(RichText::new(title).color(dot), |ui| {
+            if let Some(metadata) = &metadata {
+                metadata.draw(ui);
+                if ui.small_button("⟳ Refresh").clicked() {
+                    self.fetch_metadata(ui.ctx());
+                }
+                ui.separator();
+            }
+            egui::Grid::new("connection").num_columns(2).show(ui, |ui| {
+                ui.label("Scheme");
+                let schemes = [
+                    ("http", false, false),
+                    ("https", false, true),
+                    ("ws", true, false),
+                    ("wss", true, true),
+                ];
+                egui::ComboBox::from_id_salt("scheme")
+                    .selected_text(self.scheme())
+                    .show_ui(ui, |ui| {
+                        // WebSocket messages have no headers to carry the signature in.
+                        let signed = !self.config.shared_secret.is_empty();
+                        for (scheme, websocket, secure) in schemes {
+                            let selected =
+                                self.config.websocket == websocket && self.config.secure == secure;
+                            if ui
+                                .add_enabled(
+                                    !(websocket && signed),
+                                    egui::SelectableLabel::new(selected, scheme),
+                                )
+                                .on_disabled_hover_text("Signed requests need HTTP")
+                                .clicked()
+                            {
+                                self.send(ui.ctx(), Msg::SetScheme { websocket, secure });
+                            }
+                        }
+                    });
+                ui.end_row();
+                ui.label("Host");
+                let mut host = self.config.host.clone();
+                if ui.text_edit_singleline(&mut host).changed() {
+                    self.send(ui.ctx(), Msg::SetHost(host));
+                }
+                ui.end_row();
+                ui.label("Port");
+                let mut port = self.config.port;
+                if ui.add(egui::DragValue::new(&mut port)).changed() {
+                    self.send(ui.ctx(), Msg::SetPort(port));
+                }
+                ui.end_row();
+            });
+            ui.horizontal(|ui| {
+                if ui.button("Connect").clicked() {
+                    self.reconnect();
+                }
+                status.draw(ui);
+            });
+        });
+        tour::mark(ui.ctx(), TourAnchor::Connection, response.response.rect);
+        response.response.on_hover_text(self.get_url());
+        if throttle::is_active() {
+            let text = match throttle::remaining() {
+                Some(wait) => format!("🐢 Throttled, {}s", wait.as_secs() + 1),
+                None => "🐢 Throttled".to_owned(),
+            };
+            ui.colored_label(colors.warning, text).on_hover_text(
+                "The server asked for fewer requests, polling is slowed down for a while",
+            );
+        }
+    }
+
+    /// "Launch & attach" for profiles with a launch command, with the launch progress.
+    pub(super) fn draw_launch_controls(&mut self, ui: &mut egui::Ui) {
+        // Browsers can't start processes.
+        if cfg!(target_arch = "wasm32") || self.config.launch_command.is_empty() {
+            return;
+        }
+        match self.launcher.status() {
+            LaunchStatus::Starting => {
+                power::spinner(ui);
+                ui.label("Waiting for the game...");
+                if ui.button("Cancel").clicked() {
+                    self.launcher.stop();
+                }
+                return;
+            }
+            LaunchStatus::Failed(error) => {
+                ui.label(RichText::new("Launch failed").color(status_colors(ui.ctx()).error))
+                    .on_hover_text(error);
+            }
+            LaunchStatus::Idle | LaunchStatus::Ready | LaunchStatus::Attached => {}
+        }
+        if self.launcher.is_running() {
+            if ui.button("Stop game").clicked() {
+                self.launcher.stop();
+            }
+        } else if ui
+            .button("🚀 Launch & attach")
+            .on_hover_text(&self.config.launch_command)
+            .clicked()
+        {
+            self.launcher.launch(
+                ui.ctx(),
+                &self.config.launch_command,
+                &self.config.host,
+                self.config.port,
+            );
+        }
+        ui.add_space(8.0);
+    }
+
+    /// E.g. `Undo: Set Transform of 4v1 (Ctrl+Z)`.
+    fn undo_hint(&self, ctx: &egui::Context, command: Command, change: Option<String>) -> String {
+        let verb = match command {
+            Command::Redo => "Redo",
+            _ => "Undo",
+        };
+        let text = match change {
+            Some(change) => format!("{verb}: {change}"),
+            None => format!("Nothing to {}", verb.to_lowercase()),
+        };
+        match self.config.shortcuts.describe(ctx, command) {
+            Some(keys) => format!("{text} ({keys})"),
+            None => text,
+        }
+    }
+
+    /// Draws one item of the configurable toolbar, returns whether it showed anything.
+    pub(super) fn draw_toolbar_item(
+        &mut self,
+        ctx: &egui::Context,
+        ui: &mut egui::Ui,
+        item: ToolbarItem,
+    ) -> bool {
+        match item {
+            ToolbarItem::Fetch => {
+                let hint = match self.config.shortcuts.describe(ctx, Command::Fetch) {
+                    Some(keys) => format!("{} ({keys})", Command::Fetch.label()),
+                    None => Command::Fetch.label().to_owned(),
+                };
+                let fetch = ui.button("Fetch").on_hover_text(hint);
+                tour::mark(ctx, TourAnchor::Fetch, fetch.rect);
+                if fetch.clicked() {
+                    self.fetch_components(ctx);
+                }
+            }
+            ToolbarItem::FrameCapture => {
+                let read_only = self.config.read_only;
+                let can_pause = !read_only
+                    && (self.supports(INSPECTOR_PAUSE_METHOD)
+                        || self.supports(BRP_MUTATE_RESOURCE_METHOD));
+                let read_only_hint = "Turn off read-only mode to control the game's time";
+                let (step_hint, pause_hint) = if read_only {
+                    (read_only_hint, read_only_hint)
+                } else {
+                    (
+                        "Stepping requires the companion plugin",
+                        "Requires the companion plugin or bevy/mutate_resource (Bevy 0.16)",
+                    )
+                };
+                if self.frame_capture {
+                    if ui
+                        .add_enabled(
+                            !read_only && self.supports(INSPECTOR_STEP_METHOD),
+                            egui::Button::new("⏭ Step"),
+                        )
+                        .on_hover_text("Run exactly one frame and fetch its state")
+                        .on_disabled_hover_text(step_hint)
+                        .clicked()
+                    {
+                        self.send_frame_command(ctx, INSPECTOR_STEP_METHOD);
+                    }
+                    if ui
+                        .add_enabled(!read_only, egui::Button::new("▶ Resume"))
+                        .on_disabled_hover_text(read_only_hint)
+                        .clicked()
+                    {
+                        self.frame_capture = false;
+                        self.set_paused(ctx, false);
+                    }
+                } else if ui
+                    .add_enabled(can_pause, egui::Button::new("⏸ Pause"))
+                    .on_hover_text("Pause the game's virtual time and fetch its state")
+                    .on_disabled_hover_text(pause_hint)
+                    .clicked()
+                {
+                    self.frame_capture = true;
+                    self.set_paused(ctx, true);
+                }
+            }
+            ToolbarItem::Live => {
+                let mut on = self.config.live_mode;
+                let live = ui
+                    .checkbox(&mut on, "Live")
+                    .on_hover_text("Fetch continuously, rows not updated recently are grayed out");
+                tour::mark(ctx, TourAnchor::Live, live.rect);
+                if live.changed() {
+                    self.send(ctx, Msg::SetFlag(Flag::Live, on));
+                }
+                if self.config.live_mode && self.supports(INSPECTOR_FRAME_METHOD) {
+                    let mut frames = self.config.live_every_frames;
+                    let changed = ui
+                        .add(
+                            egui::DragValue::new(&mut frames)
+                                .range(0..=600)
+                                .custom_formatter(|n, _| match n as u32 {
+                                    0 => "on a timer".to_owned(),
+                                    n => format!("every {n} frames"),
+                                }),
+                        )
+                        .on_hover_text("Fetch in step with the game's frames, 0 uses a timer")
+                        .changed();
+                    if changed {
+                        self.send(ctx, Msg::SetLiveEveryFrames(frames));
+                    }
+                }
+                if self.config.live_mode {
+                    let mut subtree = self.config.live_subtree;
+                    let changed = ui
+                        .toggle_value(&mut subtree, "🎯 Subtree")
+                        .on_hover_text(
+                            "Poll only the selected entity and its descendants, the rest of \
+                             the world keeps its last state",
+                        )
+                        .changed();
+                    if changed {
+                        self.send(ctx, Msg::SetFlag(Flag::LiveSubtree, subtree));
+                    }
+                }
+                let frame_aligned =
+                    self.config.live_every_frames > 0 && self.supports(INSPECTOR_FRAME_METHOD);
+                if self.config.live_mode && !frame_aligned {
+                    let mut interval = self.config.live_interval_ms;
+                    let changed = ui
+                        .add(
+                            egui::Slider::new(&mut interval, 100..=5000)
+                                .logarithmic(true)
+                                .suffix(" ms"),
+                        )
+                        .on_hover_text("Time between fetches")
+                        .changed();
+                    if changed {
+                        self.send(ctx, Msg::SetLiveInterval(interval));
+                    }
+                }
+                if let Some(frame) = *self.data_frame.lock().unwrap() {
+                    ui.label(RichText::new(format!("Frame {frame}")).monospace())
+                        .on_hover_text("Game frame the shown data was captured in");
+                }
+            }
+            ToolbarItem::HideEmpty => {
+                let mut hide = self.config.skip_empty_entities;
+                if ui.checkbox(&mut hide, "Hide empty entities").changed() {
+                    self.send(ctx, Msg::SetFlag(Flag::HideEmpty, hide));
+                }
+            }
+            ToolbarItem::Sort => {
+                let mut chosen = self.config.entity_sort;
+                egui::ComboBox::from_id_salt("entity_sort")
+                    .selected_text(format!("Sort: {}", chosen.label()))
+                    .show_ui(ui, |ui| {
+                        for sort in EntitySort::ALL {
+                            ui.selectable_value(&mut chosen, sort, sort.label());
+                        }
+                    });
+                if chosen != self.config.entity_sort {
+                    self.send(ctx, Msg::SetSort(chosen));
+                }
+            }
+            ToolbarItem::Presets => {
+                let selected = match self.config.active_preset.as_str() {
+                    "" => "Preset".to_owned(),
+                    name => format!("Preset: {name}"),
+                };
+                let mut chosen = None;
+                let mut deleted = None;
+                let mut saved = None;
+                egui::ComboBox::from_id_salt("view_preset")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        for (index, preset) in self.config.view_presets.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                let active = preset.name == self.config.active_preset;
+                                if ui.selectable_label(active, &preset.name).clicked() {
+                                    chosen = Some(index);
+                                }
+                                if ui.small_button("🗑").on_hover_text("Delete").clicked() {
+                                    deleted = Some(index);
+                                }
+                            });
+                        }
+                        if !self.config.view_presets.is_empty() {
+                            ui.separator();
+                        }
+                        ui.horizontal(|ui| {
+                            ui.add(
+                                egui::TextEdit::singleline(&mut self.preset_name)
+                                    .hint_text("Name")
+                                    .desired_width(120.0),
+                            );
+                            let name = self.preset_name.trim().to_owned();
+                            if ui
+                                .add_enabled(!name.is_empty(), egui::Button::new("💾 Save"))
+                                .on_hover_text(
+                                    "Save the query, search filters, hidden empty entities \
+                                     and pinned entities under this name",
+                                )
+                                .clicked()
+                            {
+                                saved = Some(name);
+                                self.preset_name.clear();
+                            }
+                        });
+                    });
+                if let Some(name) = saved {
+                    self.send(ctx, Msg::SavePreset(name));
+                }
+                if let Some(index) = chosen {
+                    self.send(ctx, Msg::ApplyPreset(index));
+                }
+                if let Some(index) = deleted {
+                    self.send(ctx, Msg::DeletePreset(index));
+                }
+            }
+            ToolbarItem::Activity => {
+                let mut show = self.config.show_activity;
+                let changed = ui
+                    .checkbox(&mut show, "Activity heatmap")
+                    .on_hover_text("Tint entities by how often their components change")
+                    .changed();
+                if changed {
+                    self.send(ctx, Msg::SetFlag(Flag::Activity, show));
+                }
+            }
+            ToolbarItem::FollowSpawns => {
+                let mut follow = self.config.follow_spawns;
+                let changed = ui
+                    .checkbox(&mut follow, "Follow spawns")
+                    .on_hover_text("Scroll to and highlight entities spawned since the last fetch")
+                    .changed();
+                if changed {
+                    self.send(ctx, Msg::SetFlag(Flag::FollowSpawns, follow));
+                }
+            }
+            ToolbarItem::ReadOnly => {
+                let mut read_only = self.config.read_only;
+                let changed = ui
+                    .checkbox(&mut read_only, "Read-only")
+                    .on_hover_text(
+                        "Disable every action that changes the game world, kept with the \
+                         connection profile",
+                    )
+                    .changed();
+                if changed {
+                    self.send(ctx, Msg::SetFlag(Flag::ReadOnly, read_only));
+                }
+            }
+            ToolbarItem::Trash => {
+                let trash_len = self.trash.lock().unwrap().len();
+                ui.toggle_value(&mut self.show_trash, format!("🗑 Trash ({trash_len})"));
+            }
+            ToolbarItem::Minimap => {
+                ui.toggle_value(&mut self.minimap.open, "🗺 Minimap");
+            }
+            ToolbarItem::History => {
+                ui.toggle_value(&mut self.show_history, "History");
+            }
+            ToolbarItem::Insights => {
+                ui.toggle_value(&mut self.show_insights, "Insights")
+                    .on_hover_text("Components that usually appear together");
+            }
+            ToolbarItem::Staged => {
+                let staged = self
+                    .edits
+                    .lock()
+                    .unwrap()
+                    .values()
+                    .filter(|edit| matches!(edit.state, EditState::Staged))
+                    .count();
+                if staged == 0 && !self.show_staged {
+                    return false;
+                }
+                ui.toggle_value(&mut self.show_staged, format!("Staged ({staged})"))
+                    .on_hover_text("Review and commit staged edits");
+            }
+            ToolbarItem::Stats => {
+                ui.toggle_value(&mut self.show_stats, "Stats")
+                    .on_hover_text("Cache sizes and memory held for the timeline");
+            }
+            ToolbarItem::Spawn => {
+                ui.add_enabled_ui(
+                    !self.config.read_only && self.supports(BRP_SPAWN_METHOD),
+                    |ui| {
+                        ui.toggle_value(&mut self.spawn_draft.open, "➕ Spawn")
+                            .on_hover_text("Put together a new entity and spawn it in the game");
+                    },
+                );
+            }
+            ToolbarItem::Problems => {
+                ui.toggle_value(&mut self.show_problems, "Problems")
+                    .on_hover_text("Broken links between parents and children");
+            }
+            ToolbarItem::Changes => {
+                ui.toggle_value(&mut self.show_change_feed, "Changes")
+                    .on_hover_text("Live log of component changes between fetches");
+            }
+            ToolbarItem::TypeDocs => {
+                ui.toggle_value(&mut self.show_type_docs, "📖 Type docs")
+                    .on_hover_text("Describe the component or field under the pointer");
+            }
+            ToolbarItem::SpatialFilter => self.draw_spatial_filter(ui),
+            ToolbarItem::OnScreenOnly => {
+                let mut visible_only = self.config.visible_only;
+                let changed = ui
+                    .add_enabled(
+                        self.supports(INSPECTOR_QUERY_VISIBLE_METHOD),
+                        egui::Checkbox::new(&mut visible_only, "On screen only"),
+                    )
+                    .on_hover_text("Only fetch entities the active camera sees")
+                    .on_disabled_hover_text("The game doesn't have the companion plugin")
+                    .changed();
+                if changed {
+                    self.send(ctx, Msg::SetFlag(Flag::OnScreenOnly, visible_only));
+                }
+                if self.config.visible_only {
+                    let mut margin = self.config.visible_margin;
+                    let changed = ui
+                        .add(
+                            egui::DragValue::new(&mut margin)
+                                .range(0.0..=f32::MAX)
+                                .prefix("Margin: "),
+                        )
+                        .changed();
+                    if changed {
+                        self.send(ctx, Msg::SetVisibleMargin(margin));
+                    }
+                }
+            }
+            ToolbarItem::Panels => {
+                let mut panels = self.panels.lock().unwrap();
+                if panels.is_empty() {
+                    return false;
+                }
+                ui.menu_button("Panels", |ui| {
+                    for panel in panels.iter_mut() {
+                        ui.checkbox(&mut panel.open, &panel.descriptor.name);
+                    }
+                });
+            }
+            ToolbarItem::Dashboard => {
+                ui.toggle_value(&mut self.show_dashboard, "📊 Dashboard")
+                    .on_hover_text("Tiles with counts, fields and resources sampled every poll");
+            }
+            ToolbarItem::Alerts => {
+                let fired = self.alerts.fired.len();
+                let label = if fired > 0 {
+                    format!("🔔 Alerts ({fired})")
+                } else {
+                    "🔔 Alerts".to_owned()
+                };
+                ui.toggle_value(&mut self.show_alerts, label).on_hover_text(
+                    "Rules firing on thresholds and spikes, with webhook or OSC output",
+                );
+            }
+            ToolbarItem::Query => {
+                let label = match &self.config.query {
+                    Some(query) => format!("🔎 Query: {}", query.name),
+                    None => "🔎 Query".to_owned(),
+                };
+                if ui
+                    .toggle_value(&mut self.show_query_builder, label)
+                    .on_hover_text("Pick the components to fetch instead of every registered type")
+                    .clicked()
+                    && self.show_query_builder
+                {
+                    self.query_draft = self.config.query.clone().unwrap_or_default();
+                }
+            }
+            ToolbarItem::Performance => {
+                ui.toggle_value(&mut self.show_performance, "📈 Performance")
+                    .on_hover_text("FPS, frame time and entity count over time");
+            }
+            ToolbarItem::Rendering => {
+                if !self.supports(INSPECTOR_RENDER_STATS_METHOD) {
+                    return false;
+                }
+                ui.toggle_value(&mut self.show_render_stats, "🎨 Rendering")
+                    .on_hover_text(
+                        "Visible entities, render world entities and GPU time over time",
+                    );
+            }
+            ToolbarItem::Bookmarks => {
+                ui.toggle_value(&mut self.show_bookmarks, "🔖 Bookmarks")
+                    .on_hover_text("Fields bookmarked from the component context menu");
+            }
+            ToolbarItem::Types => {
+                ui.toggle_value(&mut self.show_types, "📚 Types")
+                    .on_hover_text("Every component type the game registered");
+            }
+            ToolbarItem::RpcLog => {
+                ui.toggle_value(&mut self.show_rpc_log, "📜 RPC log")
+                    .on_hover_text("Every request sent to the game and its response");
+            }
+            ToolbarItem::Undo => {
+                let (undo, redo) = {
+                    let history = self.undo.lock().unwrap();
+                    (
+                        history.next_undo().map(Mutation::label),
+                        history.next_redo().map(Mutation::label),
+                    )
+                };
+                let can_change = !self.config.read_only;
+                let can_undo = can_change && undo.is_some();
+                let undo_hint = self.undo_hint(ctx, Command::Undo, undo);
+                if ui
+                    .add_enabled(can_undo, egui::Button::new("↶"))
+                    .on_hover_text(&undo_hint)
+                    .on_disabled_hover_text(&undo_hint)
+                    .clicked()
+                {
+                    self.undo(ctx);
+                }
+                let can_redo = can_change && redo.is_some();
+                let redo_hint = self.undo_hint(ctx, Command::Redo, redo);
+                if ui
+                    .add_enabled(can_redo, egui::Button::new("↷"))
+                    .on_hover_text(&redo_hint)
+                    .on_disabled_hover_text(&redo_hint)
+                    .clicked()
+                {
+                    self.redo(ctx);
+                }
+            }
+            ToolbarItem::Hud => {
+                ui.toggle_value(&mut self.config.hud, "HUD")
+                    .on_hover_text(
+                        "Overlay with the watched resources and alerts on top of the game, \
+                         right-click to pick the corner",
+                    )
+                    .context_menu(|ui| {
+                        for corner in HudCorner::ALL {
+                            ui.radio_value(&mut self.config.hud_corner, corner, corner.label());
+                        }
+                    });
+            }
+        }
+        true
+    }
+
+    pub(super) fn draw_spatial_filter(&mut self, ui: &mut egui::Ui) {
+        let filter = &mut self.config.spatial_filter;
+        let title = if filter.is_active() {
+            "Spatial filter ●"
+        } else {
+            "Spatial filter"
+        };
+        ui.menu_button(title, |ui| {
+            let mut near = matches!(filter, SpatialFilter::NearSelected { .. });
+            let mut inside = matches!(filter, SpatialFilter::InsideBox { .. });
+            if ui.radio(!near && !inside, "Off").clicked() {
+                *filter = SpatialFilter::Off;
+            }
+            if ui
+                .radio_value(&mut near, true, "Near selected entity")
+                .clicked()
+            {
+                *filter = SpatialFilter::NearSelected { radius: 10.0 };
+            }
+            if ui.radio_value(&mut inside, true, "Inside box").clicked() {
+                *filter = SpatialFilter::InsideBox {
+                    min: [-10.0; 3],
+                    max: [10.0; 3],
+                };
+            }
+            match filter {
+                SpatialFilter::Off => {}
+                SpatialFilter::NearSelected { radius } => {
+                    ui.add(
+                        egui::DragValue::new(radius)
+                            .range(0.0..=f32::MAX)
+                            .prefix("Radius: "),
+                    );
+                    if self.selected.is_none() {
+                        ui.label(RichText::new("Select an entity first").weak());
+                    }
+                }
+                SpatialFilter::InsideBox { min, max } => {
+                    for (label, corner) in [("Min", min), ("Max", max)] {
+                        ui.horizontal(|ui| {
+                            ui.label(label);
+                            for (axis, value) in ["x: ", "y: ", "z: "].into_iter().zip(corner) {
+                                ui.add(egui::DragValue::new(value).speed(0.1).prefix(axis));
+                            }
+                        });
+                    }
+                }
+            }
+        });
+    }
+
+    /// Scrubber over the states kept while live polling, shown while live or rewound.
+    pub(super) fn draw_timeline(&mut self, ui: &mut egui::Ui) {
+        let mut timeline = self.timeline.lock().unwrap();
+        let position = timeline.position();
+        if timeline.len() < 2 {
+            return;
+        }
+        let newest = timeline.len() - 1;
+        let mut index = position.unwrap_or(newest);
+        let latest = timeline.get(newest).map(|state| state.at);
+        ui.horizontal(|ui| {
+            ui.add_space(8.0);
+            ui.label("Timeline");
+            let text = match (timeline.get(index), latest) {
+                (Some(state), Some(latest)) if index < newest => {
+                    let ago = latest.duration_since(state.at).as_secs_f32();
+                    match state.frame {
+                        Some(frame) => format!("-{ago:.1} s, frame {frame}"),
+                        None => format!("-{ago:.1} s"),
+                    }
+                }
+                _ => "live".to_owned(),
+            };
+            let slider = egui::Slider::new(&mut index, 0..=newest)
+                .show_value(false)
+                .text(text);
+            if ui
+                .add(slider)
+                .on_hover_text("Rewind the view to a recent fetch")
+                .changed()
+            {
+                timeline.view(index);
+            }
+            let mut comparing = timeline.compare_with.is_some();
+            if ui
+                .toggle_value(&mut comparing, "Compare")
+                .on_hover_text("List what differs between another fetch and the viewed one")
+                .changed()
+            {
+                let previous = index.saturating_sub(1);
+                timeline.compare_with = comparing
+                    .then(|| timeline.get(previous).map(|state| state.at))
+                    .flatten();
+            }
+            if let Some(at) = timeline.compare_with {
+                let mut other = timeline.index_of(at).unwrap_or_default();
+                let slider = egui::Slider::new(&mut other, 0..=newest)
+                    .show_value(false)
+                    .text("against");
+                if ui.add(slider).changed() {
+                    timeline.compare_with = timeline.get(other).map(|state| state.at);
+                }
+            }
+            if position.is_some() {
+                ui.label(
+                    RichText::new("Viewing past state").color(status_colors(ui.ctx()).warning),
+                );
+                if ui.button("Back to live").clicked() {
+                    timeline.go_live();
+                }
+            }
+        });
+    }
+
+    /// Tells that fetches were narrowed to the skeleton after an oversized response, with
+    /// the choice to keep it that way or go back to full fetches.
+    pub(super) fn draw_narrowed(&mut self, ui: &mut egui::Ui) {
+        let Some(oversized) = *self.lazy.narrowed.lock().unwrap() else {
+            return;
+        };
+        if self.config.lazy_loading {
+            return;
+        }
+        let mut keep = false;
+        let mut full = false;
+        ui.horizontal(|ui| {
+            ui.add_space(8.0);
+            ui.label(
+                RichText::new(format!(
+                    "The world came back as {:.1} MB in {:.1} s, fetching names and the \
+                     hierarchy only and components as entities come into view",
+                    oversized.payload as f64 / (1024.0 * 1024.0),
+                    oversized.duration.as_secs_f32()
+                ))
+                .color(status_colors(ui.ctx()).warning),
+            );
+            keep = ui
+                .button("Keep it")
+                .on_hover_text("Turn on Load components lazily")
+                .clicked();
+            full = ui
+                .button("Fetch everything")
+                .on_hover_text("Go back to full fetches for this connection")
+                .clicked();
+        });
+        if keep {
+            self.config.lazy_loading = true;
+            *self.lazy.narrowed.lock().unwrap() = None;
+        }
+        if full {
+            self.lazy.allow_oversized = true;
+            *self.lazy.narrowed.lock().unwrap() = None;
+            self.lazy.loaded.lock().unwrap().clear();
+            *self.refresh_requested.lock().unwrap() = true;
+        }
+    }
+
+    /// Warns about a request that takes suspiciously long and lets the user drop it,
+    /// so a lost callback never leaves the Fetch button disabled.
+    pub(super) fn draw_watchdog(&self, ui: &mut egui::Ui) {
+        let mut download = self.download.lock().unwrap();
+        let Download::InProgress {
+            started,
+            warn_after,
+            ..
+        } = &mut *download
+        else {
+            return;
+        };
+        let elapsed = started.elapsed();
+        if elapsed < *warn_after {
+            ui.ctx().request_repaint_after(*warn_after - elapsed);
+            return;
+        }
+        self.config
+            .power_saver
+            .request_repaint_after(ui.ctx(), Duration::from_millis(100));
+        let mut cancel = false;
+        ui.horizontal(|ui| {
+            ui.add_space(8.0);
+            ui.label(
+                RichText::new(format!(
+                    "Request still running after {:.1} s",
+                    elapsed.as_secs_f32()
+                ))
+                .color(status_colors(ui.ctx()).warning),
+            );
+            if ui.button("Keep waiting").clicked() {
+                *warn_after = elapsed + REQUEST_WARNING_AFTER;
+            }
+            if ui.button("Cancel").clicked() {
+                cancel = true;
+            }
+        });
+        if cancel {
+            if let Download::InProgress { cancel, .. } = &*download {
+                cancel.cancel();
+            }
+            *download = Download::None;
+        }
+    }
+}
@@ -0,0 +1,280 @@
+//! How the inspector looks: theme, text size, font and accent color. Kept in the settings
+//! and applied to egui whenever they change.
+
+use std::ops::RangeInclusive;
+
+use egui::{Color32, FontData, FontDefinitions, FontFamily};
+use serde::{Deserialize, Serialize};
+
+/// DejaVu Sans, so the inspector looks the same everywhere, see `assets/fonts/LICENSE`.
+const BUNDLED_REGULAR: &[u8] = include_bytes!("../assets/fonts/DejaVuSans.ttf");
+const BUNDLED_BOLD: &[u8] = include_bytes!("../assets/fonts/DejaVuSans-Bold.ttf");
+
+pub const SCALE_RANGE: RangeInclusive<f32> = 0.8..=2.5;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Theme {
+    /// Follows the system's dark or light preference.
+    #[default]
+    System,
+    Dark,
+    Light,
+}
+
+impl Theme {
+    pub const ALL: [Theme; 3] = [Theme::System, Theme::Dark, Theme::Light];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Theme::System => "Follow the system",
+            Theme::Dark => "Dark",
+            Theme::Light => "Light",
+        }
+    }
+
+    fn preference(self) -> egui::ThemePreference {
+        match self {
+            Theme::System => egui::ThemePreference::System,
+            Theme::Dark => egui::ThemePreference::Dark,
+            Theme::Light => egui::ThemePreference::Light,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum FontChoice {
+    #[default]
+    Bundled,
+    /// The platform's UI font, the bundled one where it isn't found.
+    System,
+    /// egui's own font.
+    Egui,
+}
+
+impl FontChoice {
+    pub const ALL: [FontChoice; 3] = [FontChoice::Bundled, FontChoice::System, FontChoice::Egui];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            FontChoice::Bundled => "DejaVu Sans",
+            FontChoice::System => "System font",
+            FontChoice::Egui => "egui default",
+        }
+    }
+
+    /// The fonts to set up, and why the system font couldn't be used if it was asked for.
+    fn definitions(self) -> (FontDefinitions, Option<String>) {
+        let mut fonts = FontDefinitions::default();
+        let (regular, bold, fallback) = match self {
+            FontChoice::Egui => return (fonts, None),
+            FontChoice::Bundled => (BUNDLED_REGULAR.to_vec(), BUNDLED_BOLD.to_vec(), None),
+            FontChoice::System => match system_fonts() {
+                Ok((regular, bold)) => (regular, bold, None),
+                Err(e) => (
+                    BUNDLED_REGULAR.to_vec(),
+                    BUNDLED_BOLD.to_vec(),
+                    Some(format!("System font not found, using DejaVu Sans: {e}")),
+                ),
+            },
+        };
+        fonts
+            .font_data
+            .insert("regular".to_owned(), FontData::from_owned(regular));
+        fonts
+            .font_data
+            .insert("semibold".to_owned(), FontData::from_owned(bold));
+        fonts
+            .families
+            .entry(FontFamily::Proportional)
+            .or_default()
+            .insert(0, "regular".to_owned());
+        fonts
+            .families
+            .entry(FontFamily::Name("semibold".into()))
+            .or_default()
+            .insert(0, "semibold".to_owned());
+        // Last fallback for monospace, for glyphs the monospace font lacks.
+        fonts
+            .families
+            .entry(FontFamily::Monospace)
+            .or_default()
+            .push("regular".to_owned());
+        (fonts, fallback)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(default)]
+pub struct Appearance {
+    pub theme: Theme,
+    /// Text size relative to egui's defaults.
+    pub scale: f32,
+    pub font: FontChoice,
+    /// Color of selections and links, the theme's own when `None`.
+    pub accent: Option<[u8; 3]>,
+}
+
+impl Default for Appearance {
+    fn default() -> Self {
+        Self {
+            theme: Theme::default(),
+            scale: 1.4,
+            font: FontChoice::default(),
+            accent: None,
+        }
+    }
+}
+
+impl Appearance {
+    /// Sets up egui for these settings, the fonts only if they differ from `previous`'s
+    /// since loading them takes a moment. When it does, `font_fallback` is set to why the
+    /// system font wasn't used, if it was asked for and wasn't.
+    pub fn apply(
+        &self,
+        ctx: &egui::Context,
+        previous: Option<&Appearance>,
+        font_fallback: &mut Option<String>,
+    ) {
+        if previous.map(|previous| previous.font) != Some(self.font) {
+            let (fonts, why) = self.font.definitions();
+            ctx.set_fonts(fonts);
+            *font_fallback = why;
+        }
+        ctx.set_theme(self.theme.preference());
+        let accent = self.accent.map(|[r, g, b]| Color32::from_rgb(r, g, b));
+        for theme in [egui::Theme::Dark, egui::Theme::Light] {
+            ctx.style_mut_of(theme, |style| {
+                style.text_styles = egui::style::default_text_styles();
+                for font_id in style.text_styles.values_mut() {
+                    font_id.size *= self.scale;
+                }
+                let defaults = theme.default_visuals();
+                style.visuals.selection = defaults.selection;
+                style.visuals.hyperlink_color = defaults.hyperlink_color;
+                if let Some(accent) = accent {
+                    style.visuals.selection.bg_fill = accent;
+                    style.visuals.selection.stroke.color = readable_on(accent);
+                    style.visuals.hyperlink_color = accent;
+                }
+            });
+        }
+    }
+
+    pub fn edit(&mut self, ui: &mut egui::Ui, font_fallback: Option<&str>) {
+        ui.horizontal(|ui| {
+            ui.label("Theme:");
+            for theme in Theme::ALL {
+                ui.radio_value(&mut self.theme, theme, theme.label());
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.label("Text size:");
+            ui.add(
+                egui::Slider::new(&mut self.scale, SCALE_RANGE)
+                    .step_by(0.05)
+                    .fixed_decimals(2)
+                    .suffix("×"),
+            );
+        });
+        ui.horizontal(|ui| {
+            ui.label("Font:");
+            for font in FontChoice::ALL {
+                ui.radio_value(&mut self.font, font, font.label());
+            }
+        });
+        if let Some(why) = font_fallback.filter(|_| self.font == FontChoice::System) {
+            ui.weak(why);
+        }
+        ui.horizontal(|ui| {
+            let mut custom = self.accent.is_some();
+            ui.checkbox(&mut custom, "Accent color")
+                .on_hover_text("Color of selections and links instead of the theme's");
+            match (custom, &mut self.accent) {
+                (true, Some(accent)) => {
+                    ui.color_edit_button_srgb(accent);
+                }
+                (true, accent) => *accent = Some([230, 102, 1]),
+                (false, accent) => *accent = None,
+            }
+        });
+        if ui.button("Reset appearance").clicked() {
+            *self = Self::default();
+        }
+    }
+}
+
+/// Black or white, whichever reads better on `background`.
+fn readable_on(background: Color32) -> Color32 {
+    let [r, g, b, _] = background.to_array();
+    let luminance = 0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32;
+    if luminance > 150.0 {
+        Color32::BLACK
+    } else {
+        Color32::WHITE
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+fn system_fonts() -> anyhow::Result<(Vec<u8>, Vec<u8>)> {
+    anyhow::bail!("System fonts can't be read from the browser")
+}
+
+#[cfg(target_os = "macos")]
+fn system_fonts() -> anyhow::Result<(Vec<u8>, Vec<u8>)> {
+    use std::fs;
+
+    let font_path = std::path::Path::new("/System/Library/Fonts");
+
+    let regular = fs::read(font_path.join("SFNSRounded.ttf"))?;
+    let semibold = fs::read(font_path.join("SFCompact.ttf"))?;
+
+    Ok((regular, semibold))
+}
+
+#[cfg(windows)]
+fn system_fonts() -> anyhow::Result<(Vec<u8>, Vec<u8>)> {
+    use std::fs;
+
+    let app_data = std::env::var("APPDATA")?;
+    let font_path = std::path::Path::new(&app_data);
+
+    let regular = fs::read(font_path.join("../Local/Microsoft/Windows/Fonts/aptos.ttf"))?;
+    let semibold = fs::read(font_path.join("../Local/Microsoft/Windows/Fonts/aptos-semibold.ttf"))?;
+
+    Ok((regular, semibold))
+}
+
+/// The first of the common desktop fonts that is installed.
+#[cfg(all(unix, not(target_os = "macos"), not(target_arch = "wasm32")))]
+fn system_fonts() -> anyhow::Result<(Vec<u8>, Vec<u8>)> {
+    use std::fs;
+
+    const CANDIDATES: [(&str, &str); 5] = [
+        (
+            "/usr/share/fonts/truetype/ubuntu/Ubuntu-R.ttf",
+            "/usr/share/fonts/truetype/ubuntu/Ubuntu-M.ttf",
+        ),
+        (
+            "/usr/share/fonts/cantarell/Cantarell-VF.otf",
+            "/usr/share/fonts/cantarell/Cantarell-VF.otf",
+        ),
+        (
+            "/usr/share/fonts/truetype/noto/NotoSans-Regular.ttf",
+            "/usr/share/fonts/truetype/noto/NotoSans-SemiBold.ttf",
+        ),
+        (
+            "/usr/share/fonts/noto/NotoSans-Regular.ttf",
+            "/usr/share/fonts/noto/NotoSans-SemiBold.ttf",
+        ),
+        (
+            "/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf",
+            "/usr/share/fonts/truetype/dejavu/DejaVuSans-Bold.ttf",
+        ),
+    ];
+    for (regular, semibold) in CANDIDATES {
+        if let (Ok(regular), Ok(semibold)) = (fs::read(regular), fs::read(semibold)) {
+            return Ok((regular, semibold));
+        }
+    }
+    anyhow::bail!("none of Ubuntu, Cantarell, Noto Sans or DejaVu Sans is installed")
+}
@@ -0,0 +1,136 @@
+//! Single component fields bookmarked into their own panel, to follow and edit values deep
+//! in a component without expanding it every session.
+
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow, utils::HashMap};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::compat::TypePaths;
+use crate::edit::escape;
+use crate::pins::EntityPin;
+use crate::summary::short_name;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub struct FieldBookmark {
+    pub entity: EntityPin,
+    pub component: String,
+    /// JSON pointer of the field in the component.
+    pub path: String,
+}
+
+impl FieldBookmark {
+    pub fn label(&self) -> String {
+        format!(
+            "{} › {}{}",
+            self.entity.label(),
+            short_name(&self.component),
+            self.path
+        )
+    }
+
+    /// The entity the bookmark stands for and the current value of the field.
+    pub fn resolve<'a>(
+        &self,
+        rows: &'a HashMap<Entity, BrpQueryRow>,
+        paths: &TypePaths,
+    ) -> Option<(Entity, &'a Value)> {
+        let entity = self.entity.resolve(rows, paths)?;
+        let value = rows
+            .get(&entity)?
+            .components
+            .get(&self.component)?
+            .pointer(&self.path)?;
+        Some((entity, value))
+    }
+}
+
+/// JSON pointers of the numbers, bools and strings in `value`, in the order they are shown.
+pub fn leaf_paths(value: &Value) -> Vec<String> {
+    fn walk(path: String, value: &Value, out: &mut Vec<String>) {
+        match value {
+            Value::Object(fields) => {
+                for (key, value) in fields {
+                    walk(format!("{path}/{}", escape(key)), value, out);
+                }
+            }
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    walk(format!("{path}/{index}"), item, out);
+                }
+            }
+            Value::Null => {}
+            _ => out.push(path),
+        }
+    }
+    let mut out = Vec::new();
+    walk(String::new(), value, &mut out);
+    out
+}
+
+/// `component` with the field at `path` set to `field`.
+pub fn with_field(component: &Value, path: &str, field: Value) -> Option<Value> {
+    let mut component = component.clone();
+    *component.pointer_mut(path)? = field;
+    Some(component)
+}
+
+/// What the editor of a bookmarked field wants sent.
+pub enum FieldChange {
+    None,
+    /// A value in the middle of a drag.
+    Dragging(Value),
+    Done(Value),
+}
+
+/// A widget for the scalar `value`. The value being dragged is kept in temp data under
+/// `id`, since the fetched one lags behind the inserts sent while dragging or typing.
+pub fn edit_field(ui: &mut egui::Ui, id: egui::Id, value: &Value) -> FieldChange {
+    let draft: Option<Value> = ui.data(|d| d.get_temp(id));
+    let mut edited = draft.unwrap_or_else(|| value.clone());
+    let response = match &mut edited {
+        Value::Bool(flag) => ui.checkbox(flag, ""),
+        Value::String(text) => ui.text_edit_singleline(text),
+        Value::Number(number) => match (number.as_i64(), number.as_f64()) {
+            (Some(mut int), _) => {
+                let response = ui.add(egui::DragValue::new(&mut int).update_while_editing(false));
+                *number = int.into();
+                response
+            }
+            (None, Some(mut float)) => {
+                let response = ui.add(
+                    egui::DragValue::new(&mut float)
+                        .speed(0.01)
+                        .update_while_editing(false),
+                );
+                // NaN and infinity have no JSON form, keep the old value then.
+                if let Some(float) = serde_json::Number::from_f64(float) {
+                    *number = float;
+                }
+                response
+            }
+            (None, None) => ui.label(number.to_string()),
+        },
+        other => ui.weak(other.to_string()),
+    };
+    let editing = response.dragged() || (edited.is_string() && response.has_focus());
+    if editing {
+        let dragged = response.dragged() && response.changed();
+        ui.data_mut(|d| d.insert_temp(id, edited.clone()));
+        return match dragged {
+            true => FieldChange::Dragging(edited),
+            false => FieldChange::None,
+        };
+    }
+    // Text is sent when the field loses focus rather than on every key.
+    if response.drag_stopped() || (response.lost_focus() && edited != *value) {
+        ui.data_mut(|d| d.remove::<Value>(id));
+        return FieldChange::Done(edited);
+    }
+    if response.lost_focus() {
+        ui.data_mut(|d| d.remove::<Value>(id));
+    }
+    match response.changed() {
+        true => FieldChange::Done(edited),
+        false => FieldChange::None,
+    }
+}
@@ -0,0 +1,62 @@
+use bevy::utils::HashSet;
+use serde_json::Value;
+
+use crate::companion::is_companion_method;
+
+/// OpenRPC discovery method, answered by remotes that can describe their methods.
+pub const RPC_DISCOVER_METHOD: &str = "rpc.discover";
+
+/// Methods the connected remote supports, used to disable features before the user
+/// clicks them instead of failing with an unknown method error.
+#[derive(Default)]
+pub struct Capabilities {
+    /// `None` when the remote can't describe itself, every method is assumed to work then.
+    methods: Option<HashSet<String>>,
+    /// Set when a remote without discovery turned out not to have the companion plugin.
+    companion_missing: bool,
+}
+
+impl Capabilities {
+    /// Reads the method names from an OpenRPC document returned by `rpc.discover`.
+    pub fn from_discover(document: &Value) -> Option<Self> {
+        let methods = document["methods"]
+            .as_array()?
+            .iter()
+            .filter_map(|method| method["name"].as_str())
+            .map(|name| name.to_owned())
+            .collect();
+        Some(Self {
+            methods: Some(methods),
+            companion_missing: false,
+        })
+    }
+
+    /// Disables the companion plugin methods of a remote that can't describe itself.
+    pub fn without_companion(&mut self) {
+        self.companion_missing = true;
+    }
+
+    pub fn supports(&self, method: &str) -> bool {
+        if self.companion_missing && is_companion_method(method) {
+            return false;
+        }
+        self.methods
+            .as_ref()
+            .is_none_or(|methods| methods.contains(method))
+    }
+
+    pub fn is_known(&self) -> bool {
+        self.methods.is_some()
+    }
+
+    pub fn methods(&self) -> Vec<&str> {
+        let mut methods: Vec<&str> = self
+            .methods
+            .iter()
+            .flatten()
+            .map(|method| method.as_str())
+            .collect();
+        methods.sort();
+        methods
+    }
+}
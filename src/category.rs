@@ -0,0 +1,77 @@
+/// Rough grouping of component types used to show compact icons on entity rows.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum ComponentCategory {
+    Transform,
+    Render,
+    Physics,
+    Audio,
+    Ui,
+    Custom,
+}
+
+impl ComponentCategory {
+    /// Category of a component type path, `None` for engine internals (hierarchy, names,
+    /// windows...) that carry no meaning when scanning the tree.
+    pub fn of(type_path: &str) -> Option<Self> {
+        let crate_name = type_path.split("::").next().unwrap_or(type_path);
+        match crate_name {
+            "bevy_transform" => Some(Self::Transform),
+            "bevy_render" | "bevy_pbr" | "bevy_sprite" | "bevy_core_pipeline" | "bevy_text"
+            | "bevy_gizmos" => Some(Self::Render),
+            "bevy_audio" | "bevy_kira_audio" => Some(Self::Audio),
+            "bevy_ui" => Some(Self::Ui),
+            name if name.starts_with("bevy_rapier")
+                || name.starts_with("avian")
+                || name.starts_with("bevy_xpbd") =>
+            {
+                Some(Self::Physics)
+            }
+            name if name.starts_with("bevy_") => None,
+            _ => Some(Self::Custom),
+        }
+    }
+
+    pub fn icon(self) -> &'static str {
+        match self {
+            Self::Transform => "✥",
+            Self::Render => "👁",
+            Self::Physics => "⚛",
+            Self::Audio => "🔊",
+            Self::Ui => "🖵",
+            Self::Custom => "⭐",
+        }
+    }
+
+    /// Color of the category in the minimap.
+    pub fn color(self) -> egui::Color32 {
+        match self {
+            Self::Transform => egui::Color32::GRAY,
+            Self::Render => egui::Color32::from_rgb(90, 170, 250),
+            Self::Physics => egui::Color32::from_rgb(240, 90, 80),
+            Self::Audio => egui::Color32::from_rgb(180, 120, 240),
+            Self::Ui => egui::Color32::from_rgb(120, 210, 120),
+            Self::Custom => egui::Color32::from_rgb(230, 102, 1),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Transform => "Transform",
+            Self::Render => "Rendering",
+            Self::Physics => "Physics",
+            Self::Audio => "Audio",
+            Self::Ui => "UI",
+            Self::Custom => "Custom",
+        }
+    }
+}
+
+/// Distinct categories of the given component type paths, in a stable order.
+pub fn categories<'a>(type_paths: impl Iterator<Item = &'a String>) -> Vec<ComponentCategory> {
+    let mut categories: Vec<ComponentCategory> = type_paths
+        .filter_map(|path| ComponentCategory::of(path))
+        .collect();
+    categories.sort();
+    categories.dedup();
+    categories
+}
@@ -0,0 +1,262 @@
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow, utils::HashMap};
+use serde_json::Value;
+use std::collections::{HashSet, VecDeque};
+use web_time::{Duration, Instant};
+
+/// How many changes are remembered per entity.
+const CHANGES_PER_ENTITY: usize = 50;
+/// How many entries the world wide change feed keeps.
+const FEED_CAPACITY: usize = 1000;
+/// Changed fields listed per component and poll, the rest is summarized.
+const FIELDS_PER_COMPONENT: usize = 20;
+/// How far back the "recently" spawned, changed and destroyed entities go.
+pub const RECENT_WINDOW: Duration = Duration::from_secs(10);
+
+pub enum Change {
+    Added(Value),
+    Changed(Value),
+    Removed,
+}
+
+/// A component of an entity that differed from the previous poll.
+pub struct ComponentChange {
+    pub at: Instant,
+    pub component: String,
+    pub change: Change,
+    /// Paths of the changed values inside a changed component, e.g. `.translation.x`.
+    pub fields: Vec<String>,
+}
+
+/// What changed about a component, as listed in the change feed.
+pub enum FeedChange {
+    Added,
+    Removed,
+    /// A value inside the component, `path` is empty when the component itself is a value.
+    Field {
+        path: String,
+        old: Value,
+        new: Value,
+    },
+    /// More fields changed than are listed.
+    More(usize),
+}
+
+pub struct FeedEntry {
+    pub at: Instant,
+    pub entity: Entity,
+    pub component: String,
+    pub change: FeedChange,
+}
+
+/// Component changes per entity, recorded by comparing consecutive polls, plus a feed
+/// of the changed fields across the whole world.
+#[derive(Default)]
+pub struct ChangeLog {
+    changes: HashMap<Entity, VecDeque<ComponentChange>>,
+    feed: VecDeque<FeedEntry>,
+    /// When the latest poll was compared.
+    last_recorded: Option<Instant>,
+    /// Entities that showed up within [`RECENT_WINDOW`], with when they did.
+    spawned: HashMap<Entity, Instant>,
+    /// Entities that went missing within [`RECENT_WINDOW`], newest first.
+    destroyed: VecDeque<(Instant, Entity)>,
+    /// When each entity that showed up within [`RECENT_WINDOW`] did, including the ones
+    /// gone again since.
+    births: VecDeque<Instant>,
+    first_recorded: Option<Instant>,
+}
+
+impl ChangeLog {
+    pub fn record(
+        &mut self,
+        previous: &HashMap<Entity, BrpQueryRow>,
+        current: &HashMap<Entity, BrpQueryRow>,
+    ) {
+        let now = Instant::now();
+        self.last_recorded = Some(now);
+        self.first_recorded.get_or_insert(now);
+        self.births.retain(|at| at.elapsed() < RECENT_WINDOW);
+        self.spawned
+            .retain(|entity, at| at.elapsed() < RECENT_WINDOW && current.contains_key(entity));
+        self.destroyed
+            .retain(|(at, _)| at.elapsed() < RECENT_WINDOW);
+        for entity in previous.keys().filter(|e| !current.contains_key(*e)) {
+            self.destroyed.push_front((now, *entity));
+        }
+        for (entity, row) in current.iter() {
+            // Entities seen for the first time have nothing to compare against. Everything
+            // is new on the first poll, that's not a spawn.
+            let Some(old) = previous.get(entity) else {
+                if !previous.is_empty() {
+                    self.spawned.insert(*entity, now);
+                    self.births.push_front(now);
+                }
+                continue;
+            };
+            let mut changes = Vec::new();
+            for (component, value) in row.components.iter() {
+                match old.components.get(component) {
+                    Some(old_value) if old_value == value => {}
+                    Some(old_value) => {
+                        let mut fields = Vec::new();
+                        diff_fields(String::new(), old_value, value, &mut fields);
+                        let more = fields.len().saturating_sub(FIELDS_PER_COMPONENT);
+                        fields.truncate(FIELDS_PER_COMPONENT);
+                        let paths = fields.iter().map(|(path, _, _)| path.clone()).collect();
+                        let mut feed: Vec<FeedChange> = fields
+                            .into_iter()
+                            .map(|(path, old, new)| FeedChange::Field { path, old, new })
+                            .collect();
+                        if more > 0 {
+                            feed.push(FeedChange::More(more));
+                        }
+                        for change in feed {
+                            self.push_feed(now, *entity, component, change);
+                        }
+                        changes.push((component, Change::Changed(value.clone()), paths));
+                    }
+                    None => {
+                        self.push_feed(now, *entity, component, FeedChange::Added);
+                        changes.push((component, Change::Added(value.clone()), Vec::new()));
+                    }
+                }
+            }
+            for component in old.components.keys() {
+                if !row.components.contains_key(component) {
+                    self.push_feed(now, *entity, component, FeedChange::Removed);
+                    changes.push((component, Change::Removed, Vec::new()));
+                }
+            }
+            if changes.is_empty() {
+                continue;
+            }
+            let log = self.changes.entry(*entity).or_default();
+            for (component, change, fields) in changes {
+                log.push_front(ComponentChange {
+                    at: now,
+                    component: component.clone(),
+                    change,
+                    fields,
+                });
+            }
+            log.truncate(CHANGES_PER_ENTITY);
+        }
+        self.changes
+            .retain(|entity, _| current.contains_key(entity));
+    }
+
+    fn push_feed(&mut self, at: Instant, entity: Entity, component: &str, change: FeedChange) {
+        self.feed.push_front(FeedEntry {
+            at,
+            entity,
+            component: component.to_owned(),
+            change,
+        });
+        self.feed.truncate(FEED_CAPACITY);
+    }
+
+    /// Changes across the world, newest first.
+    pub fn feed(&self) -> impl Iterator<Item = &FeedEntry> {
+        self.feed.iter()
+    }
+
+    pub fn clear_feed(&mut self) {
+        self.feed.clear();
+    }
+
+    /// Changes of the entity, newest first.
+    pub fn entity(&self, entity: &Entity) -> impl Iterator<Item = &ComponentChange> {
+        self.changes.get(entity).into_iter().flatten()
+    }
+
+    /// Changes of the entity between the last two polls.
+    pub fn last_poll(&self, entity: &Entity) -> impl Iterator<Item = &ComponentChange> {
+        self.entity(entity)
+            .take_while(|change| Some(change.at) == self.last_recorded)
+    }
+
+    /// Entities that showed up in the last [`RECENT_WINDOW`].
+    pub fn recently_spawned(&self) -> HashSet<Entity> {
+        self.spawned
+            .iter()
+            .filter(|(_, at)| at.elapsed() < RECENT_WINDOW)
+            .map(|(entity, _)| *entity)
+            .collect()
+    }
+
+    /// Entities with a component added, changed or removed in the last [`RECENT_WINDOW`].
+    pub fn recently_changed(&self) -> HashSet<Entity> {
+        self.changes
+            .iter()
+            .filter(|(_, log)| log.front().is_some_and(|c| c.at.elapsed() < RECENT_WINDOW))
+            .map(|(entity, _)| *entity)
+            .collect()
+    }
+
+    /// Entities that went missing in the last [`RECENT_WINDOW`], newest first.
+    pub fn recently_destroyed(&self) -> Vec<Entity> {
+        self.destroyed
+            .iter()
+            .filter(|(at, _)| at.elapsed() < RECENT_WINDOW)
+            .map(|(_, entity)| *entity)
+            .collect()
+    }
+
+    /// Entities spawned minus destroyed per second over the last [`RECENT_WINDOW`], or
+    /// since the first poll if that was more recent.
+    pub fn net_spawn_rate(&self) -> f32 {
+        let Some(first) = self.first_recorded else {
+            return 0.0;
+        };
+        let window = first
+            .elapsed()
+            .min(RECENT_WINDOW)
+            .max(Duration::from_secs(1));
+        let recent = |at: &Instant| at.elapsed() < RECENT_WINDOW;
+        let born = self.births.iter().filter(|at| recent(at)).count();
+        let died = self.destroyed.iter().filter(|(at, _)| recent(at)).count();
+        (born as f32 - died as f32) / window.as_secs_f32()
+    }
+
+    /// Entities that changed between the last two polls.
+    pub fn changed_entities(&self) -> HashSet<Entity> {
+        self.changes
+            .iter()
+            .filter(|(_, log)| log.front().map(|c| c.at) == self.last_recorded)
+            .map(|(entity, _)| *entity)
+            .collect()
+    }
+}
+
+/// Path, old and new value of a changed field.
+pub type FieldChange = (String, Value, Value);
+
+/// Collects the leaf values that differ between `old` and `new` with their paths,
+/// e.g. `.translation.x`.
+pub fn diff_fields(path: String, old: &Value, new: &Value, out: &mut Vec<FieldChange>) {
+    match (old, new) {
+        (Value::Object(old_map), Value::Object(new_map)) => {
+            for (key, new_value) in new_map {
+                let field = format!("{path}.{key}");
+                match old_map.get(key) {
+                    Some(old_value) => diff_fields(field, old_value, new_value, out),
+                    None => out.push((field, Value::Null, new_value.clone())),
+                }
+            }
+            for (key, old_value) in old_map {
+                if !new_map.contains_key(key) {
+                    out.push((format!("{path}.{key}"), old_value.clone(), Value::Null));
+                }
+            }
+        }
+        (Value::Array(old_items), Value::Array(new_items))
+            if old_items.len() == new_items.len() =>
+        {
+            for (index, (old_item, new_item)) in old_items.iter().zip(new_items).enumerate() {
+                diff_fields(format!("{path}[{index}]"), old_item, new_item, out);
+            }
+        }
+        _ if old != new => out.push((path, old.clone(), new.clone())),
+        _ => {}
+    }
+}
@@ -0,0 +1,408 @@
+//! Command line mode running single BRP calls without opening a window, for scripts and
+//! CI smoke tests, e.g.
+//! `bevy_remote_inspector query --with bevy_transform::components::transform::Transform --json`.
+
+use bevy::{
+    prelude::Entity,
+    remote::{
+        builtin_methods::{
+            BrpDestroyParams, BrpInsertParams, BrpQuery, BrpQueryFilter, BrpQueryParams,
+            BrpRemoveParams, BrpSpawnParams,
+        },
+        http::{DEFAULT_ADDR, DEFAULT_PORT},
+    },
+    utils::HashMap,
+};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::InspectorError;
+use crate::helper;
+use crate::responses::{
+    BrpResult, Components, Destroyed, Inserted, QueryRows, Removed, Spawned, TypeList,
+};
+use crate::summary::short_name;
+
+const USAGE: &str = "\
+Usage: bevy_remote_inspector [--headless] <command> [options]
+
+Commands:
+  list                                List the registered component types
+  query [filters]                     List matching entities
+  get <entity> <component>...         Print components of an entity
+  spawn <components>                  Spawn an entity, components as a JSON object
+  destroy <entity>                    Destroy an entity
+  insert <entity> <component> <value> Insert a component, the value as JSON
+  remove <entity> <component>...      Remove components from an entity
+
+Query filters, each can be repeated:
+  --component <type>  Fetched, entities without it don't match
+  --option <type>     Fetched when present
+  --has <type>        Reported as present or not
+  --with <type>       Only entities with it
+  --without <type>    Only entities without it
+
+Options:
+  --url <url>  Address of the game, http://127.0.0.1:15702/ by default
+  --demo       Talk to the built-in demo world instead of a game
+  --json       Print the raw result as JSON
+
+Without a command the inspector window opens.";
+
+const COMMANDS: [&str; 8] = [
+    "list", "query", "get", "spawn", "destroy", "insert", "remove", "help",
+];
+
+const VALUE_OPTIONS: [&str; 6] = [
+    "--url",
+    "--component",
+    "--option",
+    "--has",
+    "--with",
+    "--without",
+];
+
+/// Exit code of wrong arguments, told apart from failed requests.
+const USAGE_ERROR: i32 = 2;
+
+struct Args {
+    command: String,
+    positional: Vec<String>,
+    url: String,
+    json: bool,
+    query: BrpQueryParams,
+}
+
+/// Runs the command in `args`, without the program name. `None` when they don't name
+/// one and the window should open, the exit code otherwise.
+pub fn run(args: &[String]) -> Option<i32> {
+    let headless = args.iter().any(|arg| arg == "--headless");
+    let mut rest = args.iter();
+    let mut command = None;
+    while let Some(arg) = rest.next() {
+        if VALUE_OPTIONS.contains(&arg.as_str()) {
+            rest.next();
+        } else if !arg.starts_with("--") {
+            command = Some(arg);
+            break;
+        }
+    }
+    if !headless && !command.is_some_and(|c| COMMANDS.contains(&c.as_str())) {
+        return None;
+    }
+    attach_console();
+    let args = match parse(args) {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{message}\n\n{USAGE}");
+            return Some(USAGE_ERROR);
+        }
+    };
+    if args.command == "help" {
+        println!("{USAGE}");
+        return Some(0);
+    }
+    match execute(&args) {
+        Ok(output) => {
+            println!("{output}");
+            Some(0)
+        }
+        Err(CliError::Usage(message)) => {
+            eprintln!("{message}\n\n{USAGE}");
+            Some(USAGE_ERROR)
+        }
+        Err(CliError::Request(e)) => {
+            match e.hint() {
+                Some(hint) => eprintln!("{e}\n{hint}"),
+                None => eprintln!("{e}"),
+            }
+            Some(1)
+        }
+    }
+}
+
+/// Release builds on Windows are GUI programs without a console of their own, the output
+/// goes to the one of the shell that started them.
+#[cfg(windows)]
+fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    // Fails when there's no such console, e.g. when started from Explorer, or when the
+    // debug build already has one. Either way there's nothing else to attach to.
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+enum CliError {
+    Usage(String),
+    Request(InspectorError),
+}
+
+impl From<InspectorError> for CliError {
+    fn from(e: InspectorError) -> Self {
+        CliError::Request(e)
+    }
+}
+
+fn parse(args: &[String]) -> Result<Args, String> {
+    let mut parsed = Args {
+        command: String::new(),
+        positional: Vec::new(),
+        url: format!("http://{DEFAULT_ADDR}:{DEFAULT_PORT}/"),
+        json: false,
+        query: BrpQueryParams {
+            data: BrpQuery {
+                components: vec![],
+                option: vec![],
+                has: vec![],
+            },
+            filter: BrpQueryFilter::default(),
+        },
+    };
+    let mut demo = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("{arg} needs a value"))
+        };
+        match arg.as_str() {
+            "--headless" => {}
+            "--json" => parsed.json = true,
+            "--demo" => demo = true,
+            "--url" => parsed.url = value()?,
+            "--component" => parsed.query.data.components.push(value()?),
+            "--option" => parsed.query.data.option.push(value()?),
+            "--has" => parsed.query.data.has.push(value()?),
+            "--with" => parsed.query.filter.with.push(value()?),
+            "--without" => parsed.query.filter.without.push(value()?),
+            "--help" | "-h" => parsed.command = "help".to_owned(),
+            flag if flag.starts_with("--") => return Err(format!("Unknown option {flag}")),
+            _ if parsed.command.is_empty() => parsed.command = arg.clone(),
+            _ => parsed.positional.push(arg.clone()),
+        }
+    }
+    if parsed.command.is_empty() {
+        return Err("No command given".to_owned());
+    }
+    if !COMMANDS.contains(&parsed.command.as_str()) {
+        return Err(format!("Unknown command {}", parsed.command));
+    }
+    if demo {
+        let addr = crate::demo::start().map_err(|e| format!("Unable to start the demo: {e}"))?;
+        parsed.url = format!("http://{addr}/");
+    }
+    Ok(parsed)
+}
+
+fn execute(args: &Args) -> Result<String, CliError> {
+    let positional = |index: usize, name: &str| {
+        args.positional
+            .get(index)
+            .ok_or_else(|| CliError::Usage(format!("{} needs {name}", args.command)))
+    };
+    let entity = || parse_entity(positional(0, "an entity")?);
+    match args.command.as_str() {
+        "list" => {
+            let (TypeList(types), raw) = call::<TypeList>(args, None::<()>)?;
+            Ok(output(args, raw, || types.join("\n")))
+        }
+        "query" => {
+            let (QueryRows(rows), raw) = call::<QueryRows>(args, Some(&args.query))?;
+            Ok(output(args, raw, || {
+                let lines: Vec<String> = rows
+                    .iter()
+                    .map(|row| {
+                        let mut names: Vec<&str> =
+                            row.components.keys().map(|path| short_name(path)).collect();
+                        names.sort();
+                        format!("{}\t{}", entity_id(row.entity), names.join(", "))
+                    })
+                    .collect();
+                lines.join("\n")
+            }))
+        }
+        "get" => {
+            let entity = entity()?;
+            let components = &args.positional[1..];
+            if components.is_empty() {
+                return Err(CliError::Usage(
+                    "get needs at least one component".to_owned(),
+                ));
+            }
+            let params = serde_json::json!({ "entity": entity, "components": components });
+            let (result, raw) = call::<Components>(args, Some(params))?;
+            Ok(output(args, raw, || {
+                serde_json::to_string_pretty(&result.components).unwrap_or_default()
+            }))
+        }
+        "spawn" => {
+            let components: HashMap<String, Value> = parse_json(positional(0, "the components")?)?;
+            let (Spawned { entity }, raw) =
+                call::<Spawned>(args, Some(BrpSpawnParams { components }))?;
+            Ok(output(args, raw, || entity_id(entity)))
+        }
+        "destroy" => {
+            let entity = entity()?;
+            let (Destroyed, raw) = call::<Destroyed>(args, Some(BrpDestroyParams { entity }))?;
+            Ok(output(args, raw, || format!("Destroyed {entity}")))
+        }
+        "insert" => {
+            let entity = entity()?;
+            let component = positional(1, "a component")?.clone();
+            let value = parse_json(positional(2, "a value")?)?;
+            let params = BrpInsertParams {
+                entity,
+                components: [(component, value)].into_iter().collect(),
+            };
+            let (Inserted, raw) = call::<Inserted>(args, Some(params))?;
+            Ok(output(args, raw, || format!("Inserted into {entity}")))
+        }
+        "remove" => {
+            let entity = entity()?;
+            let components = args.positional[1..].to_vec();
+            if components.is_empty() {
+                return Err(CliError::Usage(
+                    "remove needs at least one component".to_owned(),
+                ));
+            }
+            let params = BrpRemoveParams { entity, components };
+            let (Removed, raw) = call::<Removed>(args, Some(params))?;
+            Ok(output(args, raw, || format!("Removed from {entity}")))
+        }
+        _ => unreachable!("commands are checked while parsing"),
+    }
+}
+
+/// Sends one call of `T`'s method and waits for it, returning the decoded result next to
+/// the raw one.
+fn call<T: BrpResult>(args: &Args, params: Option<impl Serialize>) -> Result<(T, Value), CliError> {
+    // The CLI has no secret to sign with, games checking signatures refuse it.
+    let endpoint = helper::Endpoint::unsigned(&args.url);
+    let request = match params {
+        Some(params) => helper::make_request(params, T::METHOD, &endpoint),
+        None => helper::make_empty_request(T::METHOD, &endpoint),
+    };
+    let raw: Value = helper::parse_response(ehttp::fetch_blocking(&request))
+        .map_err(|e| e.context(T::METHOD))?;
+    let result = T::decode(raw.clone()).map_err(|e| e.context(T::METHOD))?;
+    Ok((result, raw))
+}
+
+fn output(args: &Args, raw: Value, text: impl FnOnce() -> String) -> String {
+    if args.json {
+        serde_json::to_string_pretty(&raw).unwrap_or_default()
+    } else {
+        text()
+    }
+}
+
+/// Entities as the protocol writes them, so ids printed by one command can be passed to
+/// the next.
+fn entity_id(entity: Entity) -> String {
+    serde_json::to_string(&entity).unwrap_or_default()
+}
+
+fn parse_entity(text: &str) -> Result<Entity, CliError> {
+    serde_json::from_str(text).map_err(|_| {
+        CliError::Usage(format!(
+            "{text} is not an entity, use the ids printed by query"
+        ))
+    })
+}
+
+fn parse_json<T: serde::de::DeserializeOwned>(text: &str) -> Result<T, CliError> {
+    serde_json::from_str(text).map_err(|e| CliError::Usage(format!("Invalid JSON {text}: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Result<Args, String> {
+        parse(&args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn options_go_anywhere_around_the_command() {
+        let parsed = args(&[
+            "--url",
+            "http://game:1234/",
+            "get",
+            "--json",
+            "4294967298",
+            "demo::Health",
+        ])
+        .unwrap();
+        assert_eq!(parsed.command, "get");
+        assert_eq!(parsed.positional, ["4294967298", "demo::Health"]);
+        assert_eq!(parsed.url, "http://game:1234/");
+        assert!(parsed.json);
+    }
+
+    #[test]
+    fn query_filters_repeat() {
+        let parsed = args(&[
+            "query",
+            "--with",
+            "a::A",
+            "--with",
+            "b::B",
+            "--without",
+            "c::C",
+            "--option",
+            "d::D",
+            "--component",
+            "e::E",
+            "--has",
+            "f::F",
+        ])
+        .unwrap();
+        assert_eq!(parsed.query.filter.with, ["a::A", "b::B"]);
+        assert_eq!(parsed.query.filter.without, ["c::C"]);
+        assert_eq!(parsed.query.data.option, ["d::D"]);
+        assert_eq!(parsed.query.data.components, ["e::E"]);
+        assert_eq!(parsed.query.data.has, ["f::F"]);
+        assert_eq!(parsed.url, format!("http://{DEFAULT_ADDR}:{DEFAULT_PORT}/"));
+        assert!(!parsed.json);
+    }
+
+    #[test]
+    fn help_flag_is_a_command() {
+        assert_eq!(args(&["--help"]).unwrap().command, "help");
+        assert_eq!(args(&["query", "-h"]).unwrap().command, "help");
+    }
+
+    #[test]
+    fn wrong_arguments_are_reported() {
+        assert_eq!(args(&[]).err().unwrap(), "No command given");
+        assert_eq!(args(&["--headless"]).err().unwrap(), "No command given");
+        assert_eq!(
+            args(&["frobnicate"]).err().unwrap(),
+            "Unknown command frobnicate"
+        );
+        assert_eq!(
+            args(&["list", "--verbose"]).err().unwrap(),
+            "Unknown option --verbose"
+        );
+        assert_eq!(
+            args(&["query", "--with"]).err().unwrap(),
+            "--with needs a value"
+        );
+    }
+
+    #[test]
+    fn demo_points_at_the_demo_server() {
+        let parsed = args(&["list", "--demo", "--url", "http://game:1234/"]).unwrap();
+        assert_ne!(parsed.url, "http://game:1234/");
+        assert!(parsed.url.starts_with("http://127.0.0.1:"));
+    }
+}
@@ -0,0 +1,290 @@
+//! Actions that can be bound to keyboard shortcuts and run from the command palette.
+
+use std::collections::HashMap;
+
+use egui::{Event, InputState, Key, KeyboardShortcut, Modifiers};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Command {
+    Fetch,
+    FocusSearch,
+    Destroy,
+    CopyJson,
+    FindReferences,
+    FocusDetails,
+    Deselect,
+    OpenPalette,
+    ToggleLive,
+    ToggleReadOnly,
+    TogglePowerSaver,
+    Reconnect,
+    ExportScene,
+    Undo,
+    Redo,
+    RepeatLast,
+    PruneDespawned,
+}
+
+const fn shortcut(modifiers: Modifiers, key: Key) -> [KeyboardShortcut; 1] {
+    [KeyboardShortcut::new(modifiers, key)]
+}
+
+const FETCH: [KeyboardShortcut; 2] = [
+    KeyboardShortcut::new(Modifiers::NONE, Key::F5),
+    KeyboardShortcut::new(Modifiers::COMMAND, Key::R),
+];
+const FOCUS_SEARCH: [KeyboardShortcut; 1] = shortcut(Modifiers::COMMAND, Key::F);
+const DESTROY: [KeyboardShortcut; 1] = shortcut(Modifiers::NONE, Key::Delete);
+const COPY_JSON: [KeyboardShortcut; 1] = shortcut(Modifiers::COMMAND, Key::C);
+const FIND_REFERENCES: [KeyboardShortcut; 1] =
+    shortcut(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::F);
+const FOCUS_DETAILS: [KeyboardShortcut; 1] = shortcut(Modifiers::NONE, Key::F2);
+const DESELECT: [KeyboardShortcut; 1] = shortcut(Modifiers::NONE, Key::Escape);
+const COMMAND_PALETTE: [KeyboardShortcut; 1] = shortcut(Modifiers::COMMAND, Key::P);
+const UNDO: [KeyboardShortcut; 1] = shortcut(Modifiers::COMMAND, Key::Z);
+const REDO: [KeyboardShortcut; 1] = shortcut(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::Z);
+const REPEAT_LAST: [KeyboardShortcut; 1] = shortcut(Modifiers::COMMAND, Key::Y);
+
+impl Command {
+    pub const ALL: [Command; 17] = [
+        Command::Fetch,
+        Command::FocusSearch,
+        Command::Destroy,
+        Command::CopyJson,
+        Command::FindReferences,
+        Command::FocusDetails,
+        Command::Deselect,
+        Command::OpenPalette,
+        Command::ToggleLive,
+        Command::ToggleReadOnly,
+        Command::TogglePowerSaver,
+        Command::Reconnect,
+        Command::ExportScene,
+        Command::Undo,
+        Command::Redo,
+        Command::RepeatLast,
+        Command::PruneDespawned,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Command::Fetch => "Fetch entities",
+            Command::FocusSearch => "Focus the search",
+            Command::Destroy => "Destroy the selected entity",
+            Command::CopyJson => "Copy the selected entity as JSON",
+            Command::FindReferences => "Find references to the selected entity",
+            Command::FocusDetails => "Move keyboard focus to the entity details",
+            Command::Deselect => "Clear the selection",
+            Command::OpenPalette => "Open the command palette",
+            Command::ToggleLive => "Toggle live mode",
+            Command::ToggleReadOnly => "Toggle read-only mode",
+            Command::TogglePowerSaver => "Toggle power saver",
+            Command::Reconnect => "Reconnect",
+            Command::ExportScene => "Export the world as a scene",
+            Command::Undo => "Undo the last change sent to the game",
+            Command::Redo => "Redo the last undone change",
+            Command::RepeatLast => "Repeat the last change on the selected entity",
+            Command::PruneDespawned => "Prune despawned entities",
+        }
+    }
+
+    pub fn default_shortcuts(self) -> &'static [KeyboardShortcut] {
+        match self {
+            Command::Fetch => &FETCH,
+            Command::FocusSearch => &FOCUS_SEARCH,
+            Command::Destroy => &DESTROY,
+            Command::CopyJson => &COPY_JSON,
+            Command::FindReferences => &FIND_REFERENCES,
+            Command::FocusDetails => &FOCUS_DETAILS,
+            Command::Deselect => &DESELECT,
+            Command::OpenPalette => &COMMAND_PALETTE,
+            Command::Undo => &UNDO,
+            Command::Redo => &REDO,
+            Command::RepeatLast => &REPEAT_LAST,
+            Command::ToggleLive
+            | Command::ToggleReadOnly
+            | Command::TogglePowerSaver
+            | Command::Reconnect
+            | Command::ExportScene
+            | Command::PruneDespawned => &[],
+        }
+    }
+}
+
+/// The command palette's open state and what's typed into it.
+#[derive(Default)]
+pub struct CommandPalette {
+    pub open: bool,
+    pub query: String,
+    /// Index of the highlighted match.
+    pub selected: usize,
+}
+
+impl CommandPalette {
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.query.clear();
+        self.selected = 0;
+    }
+}
+
+/// Shortcuts changed from their defaults, per command.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct Shortcuts {
+    overrides: HashMap<Command, Vec<KeyboardShortcut>>,
+}
+
+impl Shortcuts {
+    pub fn get(&self, command: Command) -> &[KeyboardShortcut] {
+        match self.overrides.get(&command) {
+            Some(shortcuts) => shortcuts,
+            None => command.default_shortcuts(),
+        }
+    }
+
+    /// The first shortcut of `command` as text, e.g. `Ctrl+P`.
+    pub fn describe(&self, ctx: &egui::Context, command: Command) -> Option<String> {
+        self.get(command)
+            .first()
+            .map(|shortcut| ctx.format_shortcut(shortcut))
+    }
+
+    /// The command whose shortcut was pressed this frame, consuming the key press.
+    pub fn triggered(&self, input: &mut InputState) -> Option<Command> {
+        // Shortcuts with more modifiers go first, Ctrl+F would match Ctrl+Shift+F too.
+        let mut bindings: Vec<(Command, &KeyboardShortcut)> = Command::ALL
+            .iter()
+            .flat_map(|command| self.get(*command).iter().map(|s| (*command, s)))
+            .collect();
+        bindings.sort_by_key(|(_, shortcut)| std::cmp::Reverse(modifier_count(shortcut)));
+        bindings
+            .into_iter()
+            .find(|(_, shortcut)| consume(input, shortcut))
+            .map(|(command, _)| command)
+    }
+
+    /// A row per command with its shortcuts, removable with a click. Adding one records
+    /// the next key combination pressed.
+    pub fn edit(&mut self, ui: &mut egui::Ui) {
+        let recording_id = ui.id().with("recording_shortcut");
+        let recording: Option<Command> = ui.data(|d| d.get_temp(recording_id));
+        egui::Grid::new("shortcut_bindings")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                for command in Command::ALL {
+                    ui.label(command.label());
+                    ui.horizontal(|ui| {
+                        let mut shortcuts = self.get(command).to_vec();
+                        let mut changed = false;
+                        let mut remove = None;
+                        for (index, shortcut) in shortcuts.iter().enumerate() {
+                            let text = format!("{} ✖", ui.ctx().format_shortcut(shortcut));
+                            if ui.small_button(text).on_hover_text("Remove").clicked() {
+                                remove = Some(index);
+                            }
+                        }
+                        if let Some(index) = remove {
+                            shortcuts.remove(index);
+                            changed = true;
+                        }
+                        if recording == Some(command) {
+                            ui.label("Press keys, Escape cancels");
+                            if let Some(shortcut) = ui.input_mut(record) {
+                                ui.data_mut(|d| d.remove::<Command>(recording_id));
+                                if shortcut.logical_key != Key::Escape {
+                                    shortcuts.push(shortcut);
+                                    changed = true;
+                                }
+                            }
+                        } else if ui
+                            .small_button("➕")
+                            .on_hover_text("Add a shortcut")
+                            .clicked()
+                        {
+                            ui.data_mut(|d| d.insert_temp(recording_id, command));
+                        }
+                        if changed {
+                            self.overrides.insert(command, shortcuts);
+                        }
+                    });
+                    ui.end_row();
+                }
+            });
+        if ui.button("Reset to defaults").clicked() {
+            self.overrides.clear();
+        }
+    }
+}
+
+fn modifier_count(shortcut: &KeyboardShortcut) -> usize {
+    let m = shortcut.modifiers;
+    [m.alt, m.ctrl || m.command || m.mac_cmd, m.shift]
+        .into_iter()
+        .filter(|pressed| *pressed)
+        .count()
+}
+
+/// Copy, cut and paste combinations arrive as their own events instead of key presses.
+fn clipboard_event(shortcut: &KeyboardShortcut) -> Option<Event> {
+    if shortcut.modifiers != Modifiers::COMMAND {
+        return None;
+    }
+    match shortcut.logical_key {
+        Key::C => Some(Event::Copy),
+        Key::X => Some(Event::Cut),
+        _ => None,
+    }
+}
+
+fn consume(input: &mut InputState, shortcut: &KeyboardShortcut) -> bool {
+    if let Some(clipboard) = clipboard_event(shortcut) {
+        let before = input.events.len();
+        input.events.retain(|event| *event != clipboard);
+        if input.events.len() != before {
+            return true;
+        }
+    }
+    input.consume_shortcut(shortcut)
+}
+
+/// The first key combination pressed this frame.
+fn record(input: &mut InputState) -> Option<KeyboardShortcut> {
+    let index = input.events.iter().position(|event| {
+        matches!(
+            event,
+            Event::Key { pressed: true, .. } | Event::Copy | Event::Cut
+        )
+    })?;
+    match input.events.remove(index) {
+        Event::Key { key, modifiers, .. } => Some(KeyboardShortcut::new(modifiers, key)),
+        Event::Copy => Some(KeyboardShortcut::new(Modifiers::COMMAND, Key::C)),
+        Event::Cut => Some(KeyboardShortcut::new(Modifiers::COMMAND, Key::X)),
+        _ => None,
+    }
+}
+
+/// How well `needle` matches `haystack` as a subsequence, ignoring case. Consecutive
+/// characters and ones starting a word score higher, `None` when it doesn't match.
+pub fn fuzzy_score(needle: &str, haystack: &str) -> Option<i32> {
+    let haystack: Vec<char> = haystack.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+    for wanted in needle.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = (position..haystack.len()).find(|i| haystack[*i] == wanted)?;
+        score += 1;
+        if previous.is_some_and(|p| p + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !haystack[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        // Matches far apart are worth less.
+        score -= (found - position).min(10) as i32 / 2;
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
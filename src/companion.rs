@@ -0,0 +1,115 @@
+//! Methods registered by the optional companion plugin running inside the game, the
+//! `bevy_remote_inspector_plugin` crate of this repository. None of them are available on
+//! a plain `RemotePlugin` setup. The method names are the plugin's own, its `methods.rs`
+//! is included here.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+#[path = "../bevy_remote_inspector_plugin/src/methods.rs"]
+mod methods;
+
+pub use methods::*;
+
+/// Sent by games with the plugin's `DiscoveryPlugin` to [`DISCOVERY_GROUP`].
+#[derive(Deserialize, Clone, Debug)]
+pub struct Beacon {
+    pub service: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    /// Port the remote protocol listens on, at the sender's address.
+    pub port: u16,
+}
+
+/// One entry of [`INSPECTOR_ENCODINGS_METHOD`], served at `port` of the game's host.
+#[derive(Deserialize, Clone, Debug)]
+pub struct Encoding {
+    pub encoding: String,
+    pub port: u16,
+}
+
+/// Result of [`INSPECTOR_CHANGE_TICKS_METHOD`].
+#[derive(Deserialize, Clone, Debug, Default)]
+pub struct ChangeTicks {
+    /// Change tick of the world when asked.
+    pub tick: u32,
+    /// By component type path.
+    pub components: HashMap<String, ComponentTicks>,
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct ComponentTicks {
+    pub added: u32,
+    pub changed: u32,
+}
+
+impl ChangeTicks {
+    /// How many ticks ago `component` last changed, ticks wrap around.
+    pub fn changed_ago(&self, component: &str) -> Option<u32> {
+        let ticks = self.components.get(component)?;
+        Some(self.tick.wrapping_sub(ticks.changed))
+    }
+}
+
+/// Result of [`INSPECTOR_METADATA_METHOD`].
+#[derive(Deserialize, Clone, Debug, Default)]
+#[serde(default)]
+pub struct GameMetadata {
+    pub name: String,
+    pub version: String,
+    pub features: Vec<String>,
+    pub bevy_version: String,
+    /// `debug` or `release`.
+    pub profile: String,
+    /// Change tick of the world when asked.
+    pub tick: u32,
+    pub frame: Option<u32>,
+    pub plugin_features: Vec<String>,
+}
+
+impl GameMetadata {
+    /// Name and version of the game, e.g. `my_game 0.3.1 (debug)`.
+    pub fn title(&self) -> String {
+        let mut title = if self.name.is_empty() {
+            "Unnamed game".to_owned()
+        } else {
+            self.name.clone()
+        };
+        if !self.version.is_empty() {
+            title = format!("{title} {}", self.version);
+        }
+        if !self.profile.is_empty() {
+            title = format!("{title} ({})", self.profile);
+        }
+        title
+    }
+
+    pub fn draw(&self, ui: &mut egui::Ui) {
+        egui::Grid::new("game_metadata")
+            .num_columns(2)
+            .show(ui, |ui| {
+                let mut row = |label: &str, value: String| {
+                    ui.label(label);
+                    ui.label(value);
+                    ui.end_row();
+                };
+                row("Game", self.name.clone());
+                row("Version", self.version.clone());
+                row("Build", self.profile.clone());
+                row("Bevy", self.bevy_version.clone());
+                row("Features", self.features.join(", "));
+                row("Plugin features", self.plugin_features.join(", "));
+                row("Tick", self.tick.to_string());
+                if let Some(frame) = self.frame {
+                    row("Frame", frame.to_string());
+                }
+            });
+    }
+}
+
+pub fn is_companion_method(method: &str) -> bool {
+    method.starts_with("inspector/")
+}
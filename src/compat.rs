@@ -0,0 +1,58 @@
+//! Differences between the Bevy versions the inspector can talk to.
+//!
+//! `bevy_remote` first shipped with Bevy 0.15, so older games have no remote protocol
+//! to connect to at all.
+
+/// Type paths of the components the inspector gives special treatment.
+pub struct TypePaths {
+    pub name: &'static str,
+    pub parent: &'static str,
+    pub children: &'static str,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum BevyVersion {
+    #[default]
+    V0_15,
+    /// Hierarchy and `Name` moved into `bevy_ecs`, `Parent` became `ChildOf`.
+    V0_16,
+}
+
+const PATHS_0_15: TypePaths = TypePaths {
+    name: "bevy_core::name::Name",
+    parent: "bevy_hierarchy::components::parent::Parent",
+    children: "bevy_hierarchy::components::children::Children",
+};
+
+const PATHS_0_16: TypePaths = TypePaths {
+    name: "bevy_ecs::name::Name",
+    parent: "bevy_ecs::hierarchy::ChildOf",
+    children: "bevy_ecs::hierarchy::Children",
+};
+
+impl BevyVersion {
+    /// Guesses the version from the registered component types, falling back to 0.15
+    /// when none of the moved types is registered.
+    pub fn detect(types: &[String]) -> Self {
+        let has = |path: &str| types.iter().any(|t| t == path);
+        if has(PATHS_0_16.children) || has(PATHS_0_16.name) {
+            Self::V0_16
+        } else {
+            Self::V0_15
+        }
+    }
+
+    pub fn paths(self) -> &'static TypePaths {
+        match self {
+            Self::V0_15 => &PATHS_0_15,
+            Self::V0_16 => &PATHS_0_16,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::V0_15 => "Bevy 0.15",
+            Self::V0_16 => "Bevy 0.16",
+        }
+    }
+}
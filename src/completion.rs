@@ -0,0 +1,244 @@
+//! Text inputs for component type paths suggesting the registered types that fuzzily
+//! match what was typed. Up and down pick a suggestion, Enter or Tab takes it.
+
+use std::hash::Hash;
+
+use egui::{Key, Modifiers};
+
+use crate::commands::fuzzy_score;
+use crate::summary::short_name;
+
+/// Suggestions listed under an input.
+const SUGGESTIONS: usize = 10;
+
+#[derive(Clone, Copy, Default)]
+struct State {
+    highlighted: usize,
+    /// Where the list was last drawn, it stays while clicked even though the input lost
+    /// focus to the click.
+    list: Option<egui::Rect>,
+}
+
+pub struct Completion {
+    pub response: egui::Response,
+    /// The type taken from the suggestions this frame, already written to the text.
+    pub accepted: Option<String>,
+}
+
+/// A single-line input with the suggestions in a popup below it while it has focus.
+pub fn type_path_input(
+    ui: &mut egui::Ui,
+    id_salt: impl Hash,
+    text: &mut String,
+    types: &[String],
+    hint: &str,
+    width: f32,
+) -> Completion {
+    // Nothing to suggest from before something is typed.
+    let limit = if text.trim().is_empty() {
+        0
+    } else {
+        SUGGESTIONS
+    };
+    show(ui, id_salt, text, types, limit, |edit| {
+        edit.hint_text(hint).desired_width(width)
+    })
+    .popup()
+}
+
+/// An input with the matching types listed right under it, also before anything is typed.
+/// For menus, which close when a popup outside of them is clicked.
+pub fn type_path_picker(
+    ui: &mut egui::Ui,
+    id_salt: impl Hash,
+    text: &mut String,
+    types: &[String],
+    limit: usize,
+) -> Completion {
+    show(ui, id_salt, text, types, limit, |edit| {
+        edit.hint_text("Type path")
+    })
+    .inline()
+}
+
+/// The input drawn and the keys handled, with the list still to be drawn.
+struct Shown<'t, 'u> {
+    ui: &'u mut egui::Ui,
+    id: egui::Id,
+    state: State,
+    text: &'u mut String,
+    suggestions: Vec<&'t String>,
+    /// Whether the highlight moved this frame, to scroll it into view.
+    moved: bool,
+    response: egui::Response,
+    accepted: Option<String>,
+}
+
+fn show<'t, 'u>(
+    ui: &'u mut egui::Ui,
+    id_salt: impl Hash,
+    text: &'u mut String,
+    types: &'t [String],
+    limit: usize,
+    build: impl FnOnce(egui::TextEdit<'_>) -> egui::TextEdit<'_>,
+) -> Shown<'t, 'u> {
+    let id = ui.make_persistent_id(id_salt);
+    let mut state: State = ui.data_mut(|d| d.get_temp(id)).unwrap_or_default();
+    let suggestions = suggest(text, types, limit);
+    state.highlighted = state.highlighted.min(suggestions.len().saturating_sub(1));
+    let mut accepted = None;
+    let mut moved = false;
+    if ui.memory(|m| m.has_focus(id)) && !suggestions.is_empty() {
+        let count = suggestions.len();
+        ui.input_mut(|i| {
+            if i.consume_key(Modifiers::NONE, Key::ArrowDown) {
+                state.highlighted = (state.highlighted + 1) % count;
+                moved = true;
+            }
+            if i.consume_key(Modifiers::NONE, Key::ArrowUp) {
+                state.highlighted = (state.highlighted + count - 1) % count;
+                moved = true;
+            }
+            if i.consume_key(Modifiers::NONE, Key::Enter)
+                || i.consume_key(Modifiers::NONE, Key::Tab)
+            {
+                accepted = Some(suggestions[state.highlighted].clone());
+            }
+        });
+    }
+    // Tab takes the suggestion instead of moving on to the next widget.
+    let edit = egui::TextEdit::singleline(text)
+        .id(id)
+        .lock_focus(!suggestions.is_empty());
+    let response = ui.add(build(edit));
+    Shown {
+        ui,
+        id,
+        state,
+        text,
+        suggestions,
+        moved,
+        response,
+        accepted,
+    }
+}
+
+impl Shown<'_, '_> {
+    fn popup(mut self) -> Completion {
+        if self.listing() {
+            let area = egui::Area::new(self.id.with("suggestions"))
+                .order(egui::Order::Foreground)
+                .fixed_pos(self.response.rect.left_bottom())
+                .show(self.ui.ctx(), |ui| {
+                    egui::Frame::popup(ui.style()).show(ui, |ui| {
+                        ui.set_min_width(self.response.rect.width());
+                        list(ui, &self.suggestions, self.state.highlighted, false)
+                    })
+                });
+            self.state.list = Some(area.response.rect);
+            self.accepted = self.accepted.or(area.inner.inner);
+        } else {
+            self.state.list = None;
+        }
+        self.finish()
+    }
+
+    fn inline(mut self) -> Completion {
+        if self.listing() {
+            let shown = egui::ScrollArea::vertical()
+                .max_height(300.0)
+                .show(self.ui, |ui| {
+                    list(ui, &self.suggestions, self.state.highlighted, self.moved)
+                });
+            self.state.list = Some(shown.inner_rect);
+            self.accepted = self.accepted.or(shown.inner);
+        } else {
+            self.state.list = None;
+        }
+        self.finish()
+    }
+
+    /// Whether to list the suggestions, while typing or while one of them is clicked.
+    fn listing(&self) -> bool {
+        let clicking = self.state.list.is_some_and(|rect| {
+            self.ui.input(|i| {
+                (i.pointer.any_down() && i.pointer.interact_pos().is_some_and(|p| rect.contains(p)))
+                    || i.pointer.any_released()
+            })
+        });
+        self.accepted.is_none()
+            && !self.suggestions.is_empty()
+            && (self.response.has_focus() || clicking)
+    }
+
+    fn finish(mut self) -> Completion {
+        if let Some(type_path) = &self.accepted {
+            type_path.clone_into(self.text);
+            self.response.mark_changed();
+            self.response.request_focus();
+            if let Some(mut edit) = egui::TextEdit::load_state(self.ui.ctx(), self.id) {
+                let end = egui::text::CCursor::new(self.text.chars().count());
+                edit.cursor
+                    .set_char_range(Some(egui::text::CCursorRange::one(end)));
+                edit.store(self.ui.ctx(), self.id);
+            }
+            self.state.list = None;
+        }
+        let state = self.state;
+        self.ui.data_mut(|d| d.insert_temp(self.id, state));
+        Completion {
+            response: self.response,
+            accepted: self.accepted,
+        }
+    }
+}
+
+/// The suggestions as selectable rows, returning the clicked one.
+fn list(
+    ui: &mut egui::Ui,
+    suggestions: &[&String],
+    highlighted: usize,
+    scroll: bool,
+) -> Option<String> {
+    let mut clicked = None;
+    for (index, type_path) in suggestions.iter().enumerate() {
+        let row = ui
+            .selectable_label(index == highlighted, short_name(type_path))
+            .on_hover_text(type_path.as_str());
+        if scroll && index == highlighted {
+            row.scroll_to_me(None);
+        }
+        if row.clicked() {
+            clicked = Some((*type_path).clone());
+        }
+    }
+    clicked
+}
+
+/// The best matches of `text` among `types`, by short name or full path. None once the
+/// text is a type already, types in order when it's empty.
+fn suggest<'t>(text: &str, types: &'t [String], limit: usize) -> Vec<&'t String> {
+    let needle = text.trim();
+    if types.iter().any(|type_path| type_path == needle) {
+        return Vec::new();
+    }
+    let mut scored: Vec<(i32, &String)> = types
+        .iter()
+        .filter_map(|type_path| {
+            // A match of the short name beats the same letters spread over the module path.
+            let by_name = fuzzy_score(needle, short_name(type_path)).map(|score| score + 5);
+            let by_path = fuzzy_score(needle, type_path);
+            Some((by_name.max(by_path)?, type_path))
+        })
+        .collect();
+    if !needle.is_empty() {
+        scored.sort_by(|(a, a_path), (b, b_path)| {
+            b.cmp(a).then_with(|| a_path.len().cmp(&b_path.len()))
+        });
+    }
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, type_path)| type_path)
+        .collect()
+}
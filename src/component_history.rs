@@ -0,0 +1,290 @@
+//! How one component of an entity evolved over the kept polls, as a list of line diffs
+//! of its JSON, newest first.
+
+use bevy::prelude::Entity;
+use egui::{Color32, RichText};
+use serde_json::Value;
+use web_time::Instant;
+
+use crate::palette::status_colors;
+use crate::redaction::Redaction;
+use crate::summary::short_name;
+use crate::timeline::Timeline;
+use crate::viewports::show_panel;
+
+/// Above this many line pairs the lines are shown as all replaced instead of diffed.
+const DIFF_LIMIT: usize = 250_000;
+
+enum DiffLine {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+/// One poll where the component differed from the one before.
+struct Entry {
+    at: Instant,
+    frame: Option<u64>,
+    /// Whether the entity had the component, it may also have been gone.
+    present: bool,
+    lines: Vec<DiffLine>,
+}
+
+/// Entries worked out for the timeline as it was, until a new state comes in.
+struct Cached {
+    newest: Option<Instant>,
+    states: usize,
+    redaction: Redaction,
+    entries: Vec<Entry>,
+}
+
+pub struct ComponentHistory {
+    pub open: bool,
+    target: Option<(Entity, String)>,
+    /// Changes listed at most, newest first.
+    length: usize,
+    /// Unchanged lines around the changed ones shown, the rest is collapsed.
+    context: usize,
+    cache: Option<Cached>,
+}
+
+impl Default for ComponentHistory {
+    fn default() -> Self {
+        Self {
+            open: false,
+            target: None,
+            length: 20,
+            context: 3,
+            cache: None,
+        }
+    }
+}
+
+impl ComponentHistory {
+    pub fn show_for(&mut self, entity: Entity, component: String) {
+        self.open = true;
+        self.target = Some((entity, component));
+        self.cache = None;
+    }
+
+    /// Shows the history window, returns whether it asked to be detached or attached
+    /// again.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        timeline: &Timeline,
+        redaction: &Redaction,
+        detached: bool,
+    ) -> bool {
+        let Some((entity, component)) = self.target.clone() else {
+            return false;
+        };
+        let mut open = self.open;
+        let title = format!("History of {} on {entity}", short_name(&component));
+        let toggled = show_panel(
+            ctx,
+            "component_history",
+            &title,
+            &mut open,
+            detached,
+            |ui| {
+                let newest = timeline
+                    .len()
+                    .checked_sub(1)
+                    .and_then(|index| timeline.get(index))
+                    .map(|state| state.at);
+                let stale = self.cache.as_ref().is_none_or(|cache| {
+                    cache.newest != newest
+                        || cache.states != timeline.len()
+                        || cache.redaction != *redaction
+                });
+                if stale {
+                    self.cache = Some(Cached {
+                        newest,
+                        states: timeline.len(),
+                        redaction: redaction.clone(),
+                        entries: entries(timeline, &entity, &component, redaction),
+                    });
+                }
+                let entries = &self.cache.as_ref().expect("filled above").entries;
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "{} changes over the last {} polls",
+                        entries.len().saturating_sub(1),
+                        timeline.len()
+                    ))
+                    .on_hover_text("The number of polls kept is set by the timeline length");
+                    ui.add(
+                        egui::DragValue::new(&mut self.length)
+                            .range(1..=1000)
+                            .prefix("Show last "),
+                    );
+                    ui.add(
+                        egui::DragValue::new(&mut self.context)
+                            .range(0..=20)
+                            .prefix("Context lines: "),
+                    );
+                });
+                ui.separator();
+                if entries.is_empty() {
+                    ui.label(
+                        "Polls of the entity show up here, keep live mode on to collect them.",
+                    );
+                    return;
+                }
+                let colors = status_colors(ui.ctx());
+                egui::ScrollArea::vertical()
+                    .auto_shrink([false; 2])
+                    .show(ui, |ui| {
+                        for entry in entries.iter().rev().take(self.length) {
+                            let mut heading =
+                                format!("{:.1}s ago", entry.at.elapsed().as_secs_f32());
+                            if let Some(frame) = entry.frame {
+                                heading += &format!(", frame {frame}");
+                            }
+                            if !entry.present {
+                                heading += ", not on the entity";
+                            }
+                            ui.label(RichText::new(heading).strong());
+                            draw_lines(ui, &entry.lines, self.context, colors.ok, colors.error);
+                            ui.separator();
+                        }
+                    });
+            },
+        );
+        self.open = open;
+        toggled
+    }
+}
+
+/// The polls where the component differed from the one before, oldest first, each
+/// diffed against the previous one.
+fn entries(
+    timeline: &Timeline,
+    entity: &Entity,
+    component: &str,
+    redaction: &Redaction,
+) -> Vec<Entry> {
+    let mut entries = Vec::new();
+    let mut previous: Option<Option<&Value>> = None;
+    let mut previous_text = String::new();
+    for state in (0..timeline.len()).filter_map(|index| timeline.get(index)) {
+        let value = state
+            .world
+            .get(entity)
+            .and_then(|row| row.components.get(component));
+        if previous == Some(value) {
+            continue;
+        }
+        let text = value
+            .and_then(|value| {
+                serde_json::to_string_pretty(&redaction.redact(component, value)).ok()
+            })
+            .unwrap_or_default();
+        let lines = match previous {
+            // The first one has nothing to compare with.
+            None => text
+                .lines()
+                .map(|line| DiffLine::Same(line.to_owned()))
+                .collect(),
+            Some(_) => diff_lines(&previous_text, &text),
+        };
+        entries.push(Entry {
+            at: state.at,
+            frame: state.frame,
+            present: value.is_some(),
+            lines,
+        });
+        previous = Some(value);
+        previous_text = text;
+    }
+    entries
+}
+
+/// Lines of `old` and `new` marked by whether they were kept, added or removed, by the
+/// longest common subsequence.
+fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+    if old.len() * new.len() > DIFF_LIMIT {
+        return old
+            .iter()
+            .map(|line| DiffLine::Removed((*line).to_owned()))
+            .chain(new.iter().map(|line| DiffLine::Added((*line).to_owned())))
+            .collect();
+    }
+    // common[i][j] is the length of the longest common subsequence of old[i..] and new[j..].
+    let mut common = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            common[i][j] = if old[i] == new[j] {
+                common[i + 1][j + 1] + 1
+            } else {
+                common[i + 1][j].max(common[i][j + 1])
+            };
+        }
+    }
+    let mut lines = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            lines.push(DiffLine::Same(old[i].to_owned()));
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || common[i][j + 1] >= common[i + 1][j]) {
+            lines.push(DiffLine::Added(new[j].to_owned()));
+            j += 1;
+        } else {
+            lines.push(DiffLine::Removed(old[i].to_owned()));
+            i += 1;
+        }
+    }
+    lines
+}
+
+/// The lines in monospace, changed ones highlighted and runs of unchanged lines further
+/// than `context` from a change collapsed.
+fn draw_lines(
+    ui: &mut egui::Ui,
+    lines: &[DiffLine],
+    context: usize,
+    added: Color32,
+    removed: Color32,
+) {
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, DiffLine::Same(_)))
+        .map(|(index, _)| index)
+        .collect();
+    let near_change = |index: usize| {
+        changed.is_empty()
+            || changed
+                .iter()
+                .any(|changed| changed.abs_diff(index) <= context)
+    };
+    let mut hidden = 0;
+    for (index, line) in lines.iter().enumerate() {
+        if !near_change(index) {
+            hidden += 1;
+            continue;
+        }
+        if hidden > 0 {
+            ui.weak(format!("  ⋯ {hidden} unchanged lines"));
+            hidden = 0;
+        }
+        let text = match line {
+            DiffLine::Same(text) => RichText::new(format!("  {text}")),
+            DiffLine::Added(text) => RichText::new(format!("+ {text}"))
+                .color(added)
+                .background_color(added.gamma_multiply(0.15)),
+            DiffLine::Removed(text) => RichText::new(format!("- {text}"))
+                .color(removed)
+                .background_color(removed.gamma_multiply(0.15)),
+        };
+        ui.label(text.monospace());
+    }
+    if hidden > 0 {
+        ui.weak(format!("  ⋯ {hidden} unchanged lines"));
+    }
+}
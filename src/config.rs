@@ -0,0 +1,404 @@
+use bevy::remote::http::{DEFAULT_ADDR, DEFAULT_PORT};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::alerts::AlertRule;
+use crate::appearance::Appearance;
+use crate::bookmarks::FieldBookmark;
+use crate::commands::Shortcuts;
+use crate::dashboard::Dashboard;
+use crate::dock::DockSide;
+use crate::groups::EntityGroup;
+use crate::hud::HudCorner;
+use crate::lint::LintRule;
+use crate::ordering::EntitySort;
+use crate::palette::StatusPalette;
+use crate::pins::EntityPin;
+use crate::power::PowerSaver;
+use crate::presets::ViewPreset;
+use crate::protection::DEFAULT_PROTECTED;
+use crate::query_builder::CustomQuery;
+use crate::redaction::Redaction;
+use crate::refresh::RefreshPolicies;
+use crate::spatial::SpatialFilter;
+use crate::toolbar::ToolbarLayout;
+use crate::value_format::ValueFormat;
+
+pub const DEFAULT_SIGNATURE_HEADER: &str = "X-Signature";
+
+/// User settings that are persisted with the app state and can be shared with a team
+/// through an exported JSON file.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AppConfig {
+    /// Host of the game's BRP HTTP server.
+    pub host: String,
+    pub port: u16,
+    /// Talk to the game over a WebSocket instead of HTTP requests.
+    pub websocket: bool,
+    /// Use TLS, `https` or `wss`.
+    pub secure: bool,
+    /// Shared secret request bodies are signed with, empty when the game doesn't check.
+    /// Left out of exported settings.
+    pub shared_secret: String,
+    /// Header carrying the hex encoded HMAC-SHA256 signature.
+    pub signature_header: String,
+    pub skip_empty_entities: bool,
+    pub entity_sort: EntitySort,
+    /// Which toolbar actions are shown, in order.
+    pub toolbar: ToolbarLayout,
+    /// Key bindings changed from the defaults.
+    pub shortcuts: Shortcuts,
+    /// Template for entity rows, see `render_label_template`. Empty shows id and name.
+    pub label_template: String,
+    /// Hides every action that would mutate the remote world. Kept per connection
+    /// profile, so a tester's profile can stay read-only next to a designer's.
+    pub read_only: bool,
+    /// Tint entity rows by how often their components change between fetches.
+    pub show_activity: bool,
+    /// Scroll to and briefly highlight entities that appeared since the previous fetch.
+    pub follow_spawns: bool,
+    /// Component type paths listed first within an entity, in this order.
+    pub pinned_components: Vec<String>,
+    /// Entities listed above the tree, see [`EntityPin`].
+    pub pinned_entities: Vec<EntityPin>,
+    /// Fields shown in the Bookmarks panel.
+    pub bookmarks: Vec<FieldBookmark>,
+    /// How many times each component type was added with the Add component picker.
+    pub component_uses: BTreeMap<String, u32>,
+    /// Component types listed first in the Add component picker.
+    pub favorite_components: Vec<String>,
+    /// Saved type paths the game doesn't know that shouldn't be offered for migration.
+    pub ignored_type_paths: Vec<String>,
+    /// Entities with one of these components, by type path or short name, are only
+    /// destroyed or stripped of components after confirming.
+    pub protected_components: Vec<String>,
+    /// Refuse those actions on protected entities instead of asking.
+    pub block_protected: bool,
+    /// Ask before destroying any entity, destroying one may take its descendants along.
+    pub confirm_destroy: bool,
+    /// Limits for batch operations such as respawning many entities at once.
+    pub batch_max_in_flight: usize,
+    pub batch_requests_per_second: f32,
+    /// Keep fetching the world continuously instead of only on demand.
+    pub live_mode: bool,
+    /// Stream the changes of the selected entity with the BRP `+watch` methods.
+    pub watch_selected: bool,
+    /// Also list games announced on the local network when looking for games.
+    pub discover_lan: bool,
+    /// Grid step of edited translations while holding Ctrl.
+    pub snap_grid: f64,
+    /// Angle step of edited rotations while holding Ctrl, in degrees.
+    pub snap_angle: f64,
+    /// How often live mode fetches the world, in milliseconds.
+    pub live_interval_ms: u64,
+    /// How often resources, diagnostics and watches refresh, see [`RefreshPolicies`].
+    pub refresh: RefreshPolicies,
+    /// How many fetch results are kept for the timeline scrubber.
+    pub timeline_length: usize,
+    /// Memory the retained timeline states may use, in megabytes.
+    pub retained_memory_mb: usize,
+    /// In live mode, fetch every this many game frames instead of on a timer. Needs the
+    /// companion plugin, 0 keeps the timer.
+    pub live_every_frames: u32,
+    /// Seconds after which cached rows are shown as stale in live mode.
+    pub stale_after: f32,
+    /// Shell command starting the game, e.g. `cargo run -p my_game --features dev`.
+    pub launch_command: String,
+    /// Connect to the game as soon as the inspector starts.
+    pub auto_connect: bool,
+    /// Fetch the world once connected after starting.
+    pub auto_fetch: bool,
+    /// Turn on live mode once connected after starting.
+    pub auto_live: bool,
+    /// Name of the connection profile the fields above currently belong to.
+    pub profile: String,
+    /// Saved connection profiles, the active one is synced on switching.
+    pub profiles: Vec<ConnectionProfile>,
+    /// Profiles open in tabs, connected side by side.
+    pub open_tabs: Vec<String>,
+    /// TOML file with project specific entity and type names and field annotations.
+    pub aliases_file: Option<PathBuf>,
+    /// Directory of the open project, see `ProjectSettings`.
+    pub project: Option<PathBuf>,
+    pub spatial_filter: SpatialFilter,
+    /// Only fetch entities near the active camera's view, requires the companion plugin.
+    pub visible_only: bool,
+    /// World units the camera view is grown by for `visible_only` fetches.
+    pub visible_margin: f32,
+    /// Resource type paths polled and shown in the status bar.
+    pub watched_resources: Vec<String>,
+    /// Keys of the panels shown in their own native window.
+    pub detached_panels: Vec<String>,
+    /// Panels docked to a side of the main window, by key.
+    pub docked_panels: BTreeMap<String, DockSide>,
+    /// Scene lint rules turned off in the Problems panel.
+    pub disabled_lints: Vec<LintRule>,
+    /// Tight spacing and single-line rows, for running the inspector as a narrow strip
+    /// next to the game window.
+    pub compact: bool,
+    /// Show the details of the selected entity in a pane beside the tree instead of a
+    /// window of its own.
+    pub details_docked: bool,
+    /// Theme, text size, font and accent color.
+    pub appearance: Appearance,
+    /// Values masked in views and exports.
+    pub redaction: Redaction,
+    /// Frame cap, no animations and paused polling, for laptops running the game too.
+    pub power_saver: PowerSaver,
+    /// Look up the latest release on startup.
+    pub check_for_updates: bool,
+    /// Version whose update notice was dismissed for good.
+    pub skipped_version: Option<String>,
+    /// Colors for errors, warnings, successes and change highlights.
+    pub status_palette: StatusPalette,
+    /// Decimals, separators, vectors and color swatches of displayed values.
+    pub value_format: ValueFormat,
+    /// Show the click-through overlay with watched values and alerts.
+    pub hud: bool,
+    pub hud_corner: HudCorner,
+    pub dashboards: Vec<Dashboard>,
+    /// Rules checked on every poll, with where to report them when they fire.
+    pub alerts: Vec<AlertRule>,
+    /// Directory snapshots taken by alerts are written to, kept in memory only if unset.
+    pub alert_snapshot_dir: Option<PathBuf>,
+    /// Index of the dashboard shown in the Dashboard panel.
+    pub active_dashboard: usize,
+    /// Query used instead of fetching every registered type.
+    pub query: Option<CustomQuery>,
+    pub saved_queries: Vec<CustomQuery>,
+    /// Saved combinations of query, filters and pins, see [`ViewPreset`].
+    pub view_presets: Vec<ViewPreset>,
+    /// Name of the preset last switched to, empty for none.
+    pub active_preset: String,
+    /// Live polls only refresh the selected entity and its descendants.
+    pub live_subtree: bool,
+    /// Fetch only names and the hierarchy, components are loaded per entity as it's shown.
+    pub lazy_loading: bool,
+    /// Full fetches answered with more than this many megabytes, or taking longer than
+    /// `narrow_after_secs`, switch to lazy loading for the rest of the connection.
+    pub narrow_above_mb: usize,
+    pub narrow_after_secs: f32,
+    /// Split the top-level entities of the tree by the groups they belong to.
+    pub group_entities: bool,
+    pub entity_groups: Vec<EntityGroup>,
+}
+
+/// A saved connection together with the view state last used with it, so switching
+/// between games restores where the user was in each.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct ConnectionProfile {
+    pub name: String,
+    pub host: String,
+    pub port: u16,
+    pub websocket: bool,
+    pub secure: bool,
+    pub shared_secret: String,
+    pub signature_header: String,
+    pub skip_empty_entities: bool,
+    pub read_only: bool,
+    pub pinned_components: Vec<String>,
+    pub pinned_entities: Vec<EntityPin>,
+    pub bookmarks: Vec<FieldBookmark>,
+    pub launch_command: String,
+    pub auto_connect: bool,
+    pub auto_fetch: bool,
+    pub auto_live: bool,
+}
+
+impl Default for ConnectionProfile {
+    fn default() -> Self {
+        AppConfig::default().current_profile()
+    }
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            host: DEFAULT_ADDR.to_string(),
+            port: DEFAULT_PORT,
+            websocket: false,
+            secure: false,
+            shared_secret: String::new(),
+            signature_header: DEFAULT_SIGNATURE_HEADER.to_owned(),
+            skip_empty_entities: true,
+            entity_sort: EntitySort::default(),
+            toolbar: ToolbarLayout::default(),
+            shortcuts: Shortcuts::default(),
+            label_template: String::new(),
+            read_only: false,
+            show_activity: false,
+            follow_spawns: false,
+            pinned_components: Vec::new(),
+            pinned_entities: Vec::new(),
+            bookmarks: Vec::new(),
+            component_uses: BTreeMap::new(),
+            favorite_components: Vec::new(),
+            ignored_type_paths: Vec::new(),
+            protected_components: DEFAULT_PROTECTED.map(str::to_owned).to_vec(),
+            block_protected: false,
+            confirm_destroy: true,
+            batch_max_in_flight: 4,
+            batch_requests_per_second: 20.0,
+            live_mode: false,
+            watch_selected: true,
+            discover_lan: false,
+            snap_grid: 0.5,
+            snap_angle: 15.0,
+            live_interval_ms: 250,
+            refresh: RefreshPolicies::default(),
+            timeline_length: 100,
+            retained_memory_mb: 256,
+            live_every_frames: 0,
+            stale_after: 2.0,
+            launch_command: String::new(),
+            auto_connect: true,
+            auto_fetch: false,
+            auto_live: false,
+            profile: "Default".to_owned(),
+            profiles: Vec::new(),
+            open_tabs: Vec::new(),
+            aliases_file: None,
+            project: None,
+            spatial_filter: SpatialFilter::default(),
+            visible_only: false,
+            visible_margin: 5.0,
+            watched_resources: Vec::new(),
+            detached_panels: Vec::new(),
+            docked_panels: BTreeMap::new(),
+            disabled_lints: Vec::new(),
+            compact: false,
+            details_docked: true,
+            appearance: Appearance::default(),
+            redaction: Redaction::default(),
+            power_saver: PowerSaver::default(),
+            check_for_updates: false,
+            skipped_version: None,
+            status_palette: StatusPalette::default(),
+            value_format: ValueFormat::default(),
+            hud: false,
+            hud_corner: HudCorner::default(),
+            dashboards: Vec::new(),
+            active_dashboard: 0,
+            alerts: Vec::new(),
+            alert_snapshot_dir: None,
+            query: None,
+            saved_queries: Vec::new(),
+            view_presets: Vec::new(),
+            active_preset: String::new(),
+            live_subtree: false,
+            lazy_loading: false,
+            narrow_above_mb: 32,
+            narrow_after_secs: 5.0,
+            group_entities: true,
+            entity_groups: Vec::new(),
+        }
+    }
+}
+
+impl AppConfig {
+    /// Most often added component types, most used first.
+    pub fn frequent_components(&self, count: usize) -> Vec<&String> {
+        let mut used: Vec<_> = self.component_uses.iter().collect();
+        used.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        used.into_iter().take(count).map(|(path, _)| path).collect()
+    }
+
+    /// Copy of the settings that is safe to share, without any secrets.
+    pub fn scrubbed(&self) -> Self {
+        let mut shared = self.clone();
+        shared.shared_secret.clear();
+        for profile in shared.profiles.iter_mut() {
+            profile.shared_secret.clear();
+        }
+        shared
+    }
+
+    pub fn export(&self, path: &Path) -> anyhow::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(&self.scrubbed())?)?;
+        Ok(())
+    }
+
+    pub fn import(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    /// Names of all profiles, including the active one.
+    pub fn profile_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.profiles.iter().map(|p| p.name.clone()).collect();
+        if !names.contains(&self.profile) {
+            names.push(self.profile.clone());
+        }
+        names
+    }
+
+    /// The current connection and view state as a profile.
+    pub fn current_profile(&self) -> ConnectionProfile {
+        ConnectionProfile {
+            name: self.profile.clone(),
+            host: self.host.clone(),
+            port: self.port,
+            websocket: self.websocket,
+            secure: self.secure,
+            shared_secret: self.shared_secret.clone(),
+            signature_header: self.signature_header.clone(),
+            skip_empty_entities: self.skip_empty_entities,
+            read_only: self.read_only,
+            pinned_components: self.pinned_components.clone(),
+            pinned_entities: self.pinned_entities.clone(),
+            bookmarks: self.bookmarks.clone(),
+            launch_command: self.launch_command.clone(),
+            auto_connect: self.auto_connect,
+            auto_fetch: self.auto_fetch,
+            auto_live: self.auto_live,
+        }
+    }
+
+    /// Writes the current connection and view state into the active profile.
+    pub fn store_profile(&mut self) {
+        let profile = self.current_profile();
+        match self.profiles.iter_mut().find(|p| p.name == self.profile) {
+            Some(existing) => *existing = profile,
+            None => self.profiles.push(profile),
+        }
+    }
+
+    /// Stores the active profile and loads `name`. A profile that does not exist yet
+    /// starts as a copy of the current one.
+    pub fn switch_profile(&mut self, name: &str) {
+        self.store_profile();
+        self.load_profile(name);
+    }
+
+    /// Loads `name` without storing the active profile first. A profile that does not
+    /// exist yet is created from the current state.
+    pub fn load_profile(&mut self, name: &str) {
+        self.profile = name.to_owned();
+        let Some(profile) = self.profiles.iter().find(|p| p.name == name).cloned() else {
+            self.store_profile();
+            return;
+        };
+        self.host = profile.host;
+        self.port = profile.port;
+        self.websocket = profile.websocket;
+        self.secure = profile.secure;
+        self.shared_secret = profile.shared_secret;
+        self.signature_header = profile.signature_header;
+        self.skip_empty_entities = profile.skip_empty_entities;
+        self.read_only = profile.read_only;
+        self.pinned_components = profile.pinned_components;
+        self.pinned_entities = profile.pinned_entities;
+        self.bookmarks = profile.bookmarks;
+        self.launch_command = profile.launch_command;
+        self.auto_connect = profile.auto_connect;
+        self.auto_fetch = profile.auto_fetch;
+        self.auto_live = profile.auto_live;
+    }
+
+    pub fn remove_profile(&mut self, name: &str) {
+        self.profiles.retain(|p| p.name != name);
+    }
+}
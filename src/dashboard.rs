@@ -0,0 +1,366 @@
+//! Dashboards of tiles showing values picked from the game at a glance, sampled on
+//! every poll.
+
+use std::collections::VecDeque;
+use std::path::Path;
+
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow, utils::HashMap};
+use egui::{Color32, RichText, Stroke};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::compat::TypePaths;
+use crate::summary::{render_label_template, short_name};
+use crate::value_format::value_format;
+
+/// Samples kept per tile for its plot.
+const HISTORY_LENGTH: usize = 120;
+const TILE_SIZE: egui::Vec2 = egui::vec2(200.0, 110.0);
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Dashboard {
+    pub name: String,
+    pub tiles: Vec<Tile>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Tile {
+    pub title: String,
+    pub source: TileSource,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub enum TileSource {
+    /// Number of entities having all of the components, by type path or short name.
+    EntityCount { components: Vec<String> },
+    /// A field of the entity with this name or id, written like a label template
+    /// placeholder, e.g. `my_game::Health.current`.
+    Field { entity: String, field: String },
+    /// A resource value, polled like the watched resources.
+    Resource { resource: String },
+}
+
+impl TileSource {
+    const KINDS: [&'static str; 3] = ["Entity count", "Field", "Resource"];
+
+    fn kind(&self) -> &'static str {
+        match self {
+            TileSource::EntityCount { .. } => Self::KINDS[0],
+            TileSource::Field { .. } => Self::KINDS[1],
+            TileSource::Resource { .. } => Self::KINDS[2],
+        }
+    }
+
+    fn of_kind(kind: &str) -> Self {
+        match kind {
+            "Field" => TileSource::Field {
+                entity: String::new(),
+                field: String::new(),
+            },
+            "Resource" => TileSource::Resource {
+                resource: String::new(),
+            },
+            _ => TileSource::EntityCount {
+                components: Vec::new(),
+            },
+        }
+    }
+
+    /// A dropdown of the kind of source and fields for its settings.
+    pub fn edit(&mut self, ui: &mut egui::Ui) {
+        egui::ComboBox::from_id_salt(ui.id().with("kind"))
+            .selected_text(self.kind())
+            .show_ui(ui, |ui| {
+                for kind in Self::KINDS {
+                    if ui.selectable_label(self.kind() == kind, kind).clicked() {
+                        *self = Self::of_kind(kind);
+                    }
+                }
+            });
+        match self {
+            TileSource::EntityCount { components } => {
+                // Kept as typed, a trailing comma would be lost when joining again.
+                let id = ui.id().with("components");
+                let mut text = ui
+                    .data_mut(|d| d.get_temp::<String>(id))
+                    .unwrap_or_else(|| components.join(", "));
+                ui.add(
+                    egui::TextEdit::singleline(&mut text).hint_text("Components, comma separated"),
+                );
+                *components = text
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|c| !c.is_empty())
+                    .map(str::to_owned)
+                    .collect();
+                ui.data_mut(|d| d.insert_temp(id, text));
+            }
+            TileSource::Field { entity, field } => {
+                ui.add(egui::TextEdit::singleline(entity).hint_text("Entity name or id"));
+                ui.add(egui::TextEdit::singleline(field).hint_text("Component.field"));
+            }
+            TileSource::Resource { resource } => {
+                ui.add(egui::TextEdit::singleline(resource).hint_text("Resource type path"));
+            }
+        }
+    }
+
+    pub fn value(&self, inputs: &TileInputs) -> Option<Value> {
+        match self {
+            TileSource::EntityCount { components } => {
+                let has = |row: &BrpQueryRow, component: &String| {
+                    row.components
+                        .keys()
+                        .any(|key| key == component || short_name(key) == component)
+                };
+                let count = inputs
+                    .rows
+                    .values()
+                    .filter(|row| components.iter().all(|c| has(row, c)))
+                    .count();
+                Some(count.into())
+            }
+            TileSource::Field { entity, field } => {
+                let (entity, row) = find_entity(inputs.rows, inputs.paths, entity)?;
+                let text =
+                    render_label_template(&format!("{{{field}}}"), &entity, row, inputs.paths);
+                if text == "?" {
+                    return None;
+                }
+                Some(serde_json::from_str(&text).unwrap_or(Value::String(text)))
+            }
+            TileSource::Resource { resource } => (inputs.resource)(resource),
+        }
+    }
+
+    /// Resource the source reads, which has to be polled for it.
+    pub fn resource(&self) -> Option<&String> {
+        match self {
+            TileSource::Resource { resource } => Some(resource),
+            _ => None,
+        }
+    }
+}
+
+impl Dashboard {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            tiles: Vec::new(),
+        }
+    }
+
+    /// Resources the tiles show, they have to be polled along with the watched ones.
+    pub fn resources(&self) -> impl Iterator<Item = &String> {
+        self.tiles.iter().filter_map(|tile| tile.source.resource())
+    }
+}
+
+/// Dashboards and watched resources in a file of their own, for handing a team's
+/// standard views to everyone working on the game.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct DashboardPack {
+    pub dashboards: Vec<Dashboard>,
+    pub watched_resources: Vec<String>,
+}
+
+impl DashboardPack {
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    /// Adds the pack to the user's own dashboards and watches. Dashboards with a name
+    /// the user has already are replaced, so importing an updated pack again updates
+    /// them.
+    pub fn merge_into(self, dashboards: &mut Vec<Dashboard>, watched: &mut Vec<String>) {
+        for dashboard in self.dashboards {
+            match dashboards.iter_mut().find(|d| d.name == dashboard.name) {
+                Some(existing) => *existing = dashboard,
+                None => dashboards.push(dashboard),
+            }
+        }
+        for resource in self.watched_resources {
+            if !watched.contains(&resource) {
+                watched.push(resource);
+            }
+        }
+    }
+}
+
+/// Everything a tile can read its value from.
+pub struct TileInputs<'a> {
+    pub rows: &'a HashMap<Entity, BrpQueryRow>,
+    pub paths: &'a TypePaths,
+    pub resource: &'a dyn Fn(&str) -> Option<Value>,
+}
+
+/// Recent values of the tiles of the shown dashboard, not saved.
+#[derive(Default)]
+pub struct DashboardState {
+    history: HashMap<(String, usize), VecDeque<f64>>,
+    /// Poll the values were last sampled in.
+    sampled_poll: Option<u32>,
+    /// Tile being edited, by index.
+    editing: Option<usize>,
+}
+
+impl DashboardState {
+    /// Records the current value of every numeric tile once per poll.
+    pub fn sample(&mut self, dashboard: &Dashboard, poll: u32, inputs: &TileInputs) {
+        if self.sampled_poll == Some(poll) {
+            return;
+        }
+        self.sampled_poll = Some(poll);
+        for (index, tile) in dashboard.tiles.iter().enumerate() {
+            let Some(number) = tile.source.value(inputs).and_then(|v| v.as_f64()) else {
+                continue;
+            };
+            let history = self
+                .history
+                .entry((dashboard.name.clone(), index))
+                .or_default();
+            history.push_back(number);
+            if history.len() > HISTORY_LENGTH {
+                history.pop_front();
+            }
+        }
+    }
+
+    /// Draws the tiles in a wrapping grid, with editing through their context menus.
+    pub fn draw(&mut self, ui: &mut egui::Ui, dashboard: &mut Dashboard, inputs: &TileInputs) {
+        let mut remove = None;
+        ui.horizontal_wrapped(|ui| {
+            for (index, tile) in dashboard.tiles.iter_mut().enumerate() {
+                let key = (dashboard.name.clone(), index);
+                let response = egui::Frame::group(ui.style())
+                    .show(ui, |ui| {
+                        ui.set_min_size(TILE_SIZE);
+                        ui.set_max_width(TILE_SIZE.x);
+                        if self.editing == Some(index) {
+                            if edit_tile(ui, tile) {
+                                self.editing = None;
+                                self.history.remove(&key);
+                            }
+                            return;
+                        }
+                        ui.label(RichText::new(&tile.title).weak());
+                        let text = match tile.source.value(inputs) {
+                            Some(value) => value_format(ui.ctx()).text(&value),
+                            None => "–".to_owned(),
+                        };
+                        ui.label(RichText::new(text).heading().monospace());
+                        if let Some(history) = self.history.get(&key) {
+                            sparkline(ui, history);
+                        }
+                    })
+                    .response;
+                response.context_menu(|ui| {
+                    if ui.button("Edit").clicked() {
+                        self.editing = Some(index);
+                        ui.close_menu();
+                    }
+                    if ui.button("Remove").clicked() {
+                        remove = Some(index);
+                        ui.close_menu();
+                    }
+                });
+            }
+        });
+        if let Some(index) = remove {
+            dashboard.tiles.remove(index);
+            self.editing = None;
+            self.history.retain(|(name, _), _| *name != dashboard.name);
+        }
+        ui.menu_button("➕ Add tile", |ui| {
+            for kind in TileSource::KINDS {
+                if ui.button(kind).clicked() {
+                    dashboard.tiles.push(Tile {
+                        title: kind.to_owned(),
+                        source: TileSource::of_kind(kind),
+                    });
+                    self.editing = Some(dashboard.tiles.len() - 1);
+                    ui.close_menu();
+                }
+            }
+        });
+    }
+}
+
+/// Returns `true` once editing is done.
+fn edit_tile(ui: &mut egui::Ui, tile: &mut Tile) -> bool {
+    ui.horizontal(|ui| {
+        ui.label("Title");
+        ui.text_edit_singleline(&mut tile.title);
+    });
+    tile.source.edit(ui);
+    ui.button("Done").clicked()
+}
+
+/// The entity with the `Name`, or the id, `name`.
+fn find_entity<'a>(
+    rows: &'a HashMap<Entity, BrpQueryRow>,
+    paths: &TypePaths,
+    name: &str,
+) -> Option<(Entity, &'a BrpQueryRow)> {
+    rows.iter()
+        .find(|(entity, row)| {
+            let named = row.components.get(paths.name).and_then(|value| {
+                value
+                    .as_str()
+                    .or_else(|| value.get("name").and_then(Value::as_str))
+            });
+            named == Some(name) || entity.to_string() == name
+        })
+        .map(|(entity, row)| (*entity, row))
+}
+
+pub fn display(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Number(number) if !number.is_i64() && !number.is_u64() => {
+            format!("{:.2}", number.as_f64().unwrap_or_default())
+        }
+        // Newtypes like `Score(42)` show their value.
+        Value::Object(map) if map.len() == 1 => display(map.values().next().unwrap()),
+        other => other.to_string(),
+    }
+}
+
+/// Line plot of `values` scaled to their range.
+fn sparkline(ui: &mut egui::Ui, values: &VecDeque<f64>) {
+    if values.len() < 2 {
+        return;
+    }
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(TILE_SIZE.x, 36.0), egui::Sense::hover());
+    let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let span = (max - min).max(f64::EPSILON);
+    let step = rect.width() / (HISTORY_LENGTH - 1) as f32;
+    let start = rect.right() - step * (values.len() - 1) as f32;
+    let points: Vec<egui::Pos2> = values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            let y = ((value - min) / span) as f32;
+            egui::pos2(
+                start + step * index as f32,
+                rect.bottom() - y * rect.height(),
+            )
+        })
+        .collect();
+    let color = ui.visuals().selection.stroke.color;
+    ui.painter()
+        .add(egui::Shape::line(points, Stroke::new(1.5, color)));
+    ui.painter().rect_stroke(
+        rect,
+        0.0,
+        Stroke::new(0.5, Color32::from_gray(90).gamma_multiply(0.5)),
+    );
+}
@@ -0,0 +1,500 @@
+//! Mock BRP server serving a small canned world, started with `--demo` so the inspector
+//! can be shown, screenshot-tested and worked on without a running game.
+//!
+//! It speaks just enough HTTP/1.1 for JSON-RPC bodies and implements the builtin
+//! `bevy/*` methods against an in-memory world. Edits stick until the app is closed.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use web_time::Instant;
+
+use bevy::{
+    prelude::Entity,
+    remote::{
+        builtin_methods::{
+            BrpDestroyParams, BrpGetParams, BrpInsertParams, BrpListParams, BrpMutateParams,
+            BrpQueryParams, BrpRemoveParams, BrpReparentParams, BrpSpawnParams, BRP_DESTROY_METHOD,
+            BRP_GET_METHOD, BRP_INSERT_METHOD, BRP_LIST_METHOD, BRP_MUTATE_COMPONENT_METHOD,
+            BRP_QUERY_METHOD, BRP_REMOVE_METHOD, BRP_REPARENT_METHOD, BRP_SPAWN_METHOD,
+        },
+        error_codes, BrpError, BrpRequest,
+    },
+};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Map, Value};
+
+use crate::compat::BevyVersion;
+use crate::spatial::{GLOBAL_TRANSFORM, TRANSFORM};
+
+const VISIBILITY: &str = "bevy_render::view::visibility::Visibility";
+const CAMERA: &str = "bevy_render::camera::camera::Camera";
+const POINT_LIGHT: &str = "bevy_pbr::light::point_light::PointLight";
+const PLAYER: &str = "demo::Player";
+const HEALTH: &str = "demo::Health";
+const SPINNER: &str = "demo::Spinner";
+
+/// Starts the server on a free local port and returns its address.
+pub fn start() -> std::io::Result<SocketAddr> {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let addr = listener.local_addr()?;
+    let world = Arc::new(Mutex::new(MockWorld::canned()));
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let world = world.clone();
+            std::thread::spawn(move || serve(stream, &world));
+        }
+    });
+    Ok(addr)
+}
+
+/// Answers requests on one connection until the client closes it.
+fn serve(stream: TcpStream, world: &Mutex<MockWorld>) {
+    let Ok(mut writer) = stream.try_clone() else {
+        return;
+    };
+    let mut reader = BufReader::new(stream);
+    loop {
+        let mut content_length = 0;
+        let mut line = String::new();
+        // Request line, then headers up to the blank line.
+        if reader.read_line(&mut line).unwrap_or(0) == 0 {
+            return;
+        }
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                return;
+            }
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+            }
+        }
+        let mut body = vec![0; content_length];
+        if reader.read_exact(&mut body).is_err() {
+            return;
+        }
+        let response = world.lock().unwrap().handle_body(&body);
+        let response = serde_json::to_vec(&response).unwrap_or_default();
+        let head = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n",
+            response.len()
+        );
+        if writer.write_all(head.as_bytes()).is_err() || writer.write_all(&response).is_err() {
+            return;
+        }
+    }
+}
+
+struct MockWorld {
+    entities: Vec<(Entity, Map<String, Value>)>,
+    next_index: u32,
+    started: Instant,
+}
+
+impl MockWorld {
+    /// A camera, a light, a player with two children and a spinning cube.
+    fn canned() -> Self {
+        let mut world = Self {
+            entities: Vec::new(),
+            next_index: 0,
+            started: Instant::now(),
+        };
+        let transform = |x: f32, y: f32, z: f32| {
+            json!({
+                "translation": [x, y, z],
+                "rotation": [0.0, 0.0, 0.0, 1.0],
+                "scale": [1.0, 1.0, 1.0],
+            })
+        };
+        world.spawn(
+            "Camera",
+            [
+                (
+                    CAMERA,
+                    json!({ "order": 0, "is_active": true, "hdr": false }),
+                ),
+                (TRANSFORM, transform(0.0, 6.0, 12.0)),
+            ],
+        );
+        world.spawn(
+            "Sun",
+            [
+                (
+                    POINT_LIGHT,
+                    json!({
+                        "color": { "Srgba": { "red": 1.0, "green": 0.95, "blue": 0.8, "alpha": 1.0 } },
+                        "intensity": 1000000.0,
+                        "range": 20.0,
+                        "shadows_enabled": true,
+                    }),
+                ),
+                (TRANSFORM, transform(4.0, 8.0, 4.0)),
+            ],
+        );
+        let player = world.spawn(
+            "Player",
+            [
+                (PLAYER, json!({ "speed": 4.5, "jumping": false })),
+                (HEALTH, json!({ "current": 80, "max": 100 })),
+                (TRANSFORM, transform(0.0, 0.0, 0.0)),
+                (VISIBILITY, json!("Inherited")),
+            ],
+        );
+        for (name, x) in [("Sword", 0.5), ("Shield", -0.5)] {
+            let child = world.spawn(
+                name,
+                [
+                    (TRANSFORM, transform(x, 1.0, 0.0)),
+                    (VISIBILITY, json!("Inherited")),
+                ],
+            );
+            world.reparent(child, Some(player));
+        }
+        world.spawn(
+            "Cube",
+            [
+                (SPINNER, json!({ "speed": 1.0 })),
+                (TRANSFORM, transform(3.0, 0.5, -2.0)),
+                (VISIBILITY, json!("Visible")),
+            ],
+        );
+        world
+    }
+
+    fn spawn<'a>(
+        &mut self,
+        name: &str,
+        components: impl IntoIterator<Item = (&'a str, Value)>,
+    ) -> Entity {
+        let mut map: Map<String, Value> = components
+            .into_iter()
+            .map(|(path, value)| (path.to_owned(), value))
+            .collect();
+        map.insert(paths().name.to_owned(), json!(name));
+        self.spawn_raw(map)
+    }
+
+    fn spawn_raw(&mut self, mut components: Map<String, Value>) -> Entity {
+        let entity = Entity::from_raw(self.next_index);
+        self.next_index += 1;
+        if let Some(transform) = components.get(TRANSFORM).cloned() {
+            components.insert(GLOBAL_TRANSFORM.to_owned(), transform);
+        }
+        self.entities.push((entity, components));
+        entity
+    }
+
+    fn components(&mut self, entity: Entity) -> Result<&mut Map<String, Value>, BrpError> {
+        self.entities
+            .iter_mut()
+            .find(|(e, _)| *e == entity)
+            .map(|(_, components)| components)
+            .ok_or_else(|| {
+                error(
+                    error_codes::ENTITY_NOT_FOUND,
+                    format!("Entity {entity} not found"),
+                )
+            })
+    }
+
+    fn parent(&mut self, entity: Entity) -> Option<Entity> {
+        let parent = self.components(entity).ok()?.get(paths().parent)?.clone();
+        serde_json::from_value(parent).ok()
+    }
+
+    fn reparent(&mut self, entity: Entity, parent: Option<Entity>) {
+        let paths = paths();
+        if let Some(old) = self.parent(entity) {
+            if let Ok(components) = self.components(old) {
+                let children = children_without(components.get(paths.children), entity);
+                if children.is_empty() {
+                    components.remove(paths.children);
+                } else {
+                    components.insert(paths.children.to_owned(), json!(children));
+                }
+            }
+        }
+        let Ok(components) = self.components(entity) else {
+            return;
+        };
+        match parent {
+            Some(parent) => {
+                components.insert(paths.parent.to_owned(), json!(parent));
+                if let Ok(components) = self.components(parent) {
+                    let mut children = children_without(components.get(paths.children), entity);
+                    children.push(entity);
+                    components.insert(paths.children.to_owned(), json!(children));
+                }
+            }
+            None => {
+                components.remove(paths.parent);
+            }
+        }
+    }
+
+    fn destroy(&mut self, entity: Entity) {
+        self.reparent(entity, None);
+        let children = self
+            .components(entity)
+            .ok()
+            .and_then(|components| components.get(paths().children).cloned())
+            .and_then(|children| serde_json::from_value::<Vec<Entity>>(children).ok())
+            .unwrap_or_default();
+        for child in children {
+            self.destroy(child);
+        }
+        self.entities.retain(|(e, _)| *e != entity);
+    }
+
+    /// Rotates the spinning cube so live polling has something to show.
+    fn animate(&mut self) {
+        let seconds = self.started.elapsed().as_secs_f32();
+        for (_, components) in self.entities.iter_mut() {
+            let Some(speed) = components
+                .get(SPINNER)
+                .and_then(|spinner| spinner["speed"].as_f64())
+            else {
+                continue;
+            };
+            let half = seconds * speed as f32 / 2.0;
+            for path in [TRANSFORM, GLOBAL_TRANSFORM] {
+                if let Some(transform) = components.get_mut(path) {
+                    transform["rotation"] = json!([0.0, half.sin(), 0.0, half.cos()]);
+                }
+            }
+        }
+    }
+
+    fn types(&self) -> Vec<String> {
+        let mut types: Vec<String> = self
+            .entities
+            .iter()
+            .flat_map(|(_, components)| components.keys().cloned())
+            .collect();
+        types.extend([paths().parent.to_owned(), paths().children.to_owned()]);
+        types.sort();
+        types.dedup();
+        types
+    }
+
+    fn handle_body(&mut self, body: &[u8]) -> Value {
+        self.animate();
+        match serde_json::from_slice::<Value>(body) {
+            Ok(Value::Array(calls)) => calls
+                .into_iter()
+                .map(|call| self.handle_call(call))
+                .collect(),
+            Ok(call) => self.handle_call(call),
+            Err(e) => response(Value::Null, Err(error(error_codes::PARSE_ERROR, e))),
+        }
+    }
+
+    fn handle_call(&mut self, call: Value) -> Value {
+        match serde_json::from_value::<BrpRequest>(call) {
+            Ok(request) => {
+                let id = request.id.clone().unwrap_or_default();
+                response(id, self.handle(request))
+            }
+            Err(e) => response(Value::Null, Err(error(error_codes::INVALID_REQUEST, e))),
+        }
+    }
+
+    fn handle(&mut self, request: BrpRequest) -> Result<Value, BrpError> {
+        match request.method.as_str() {
+            BRP_LIST_METHOD => match request.params {
+                Some(params) => {
+                    let params: BrpListParams = parse(params)?;
+                    Ok(json!(self
+                        .components(params.entity)?
+                        .keys()
+                        .collect::<Vec<_>>()))
+                }
+                None => Ok(json!(self.types())),
+            },
+            BRP_QUERY_METHOD => {
+                let params: BrpQueryParams = parse(request.params.unwrap_or_default())?;
+                Ok(Value::Array(self.query(&params)))
+            }
+            BRP_GET_METHOD => {
+                let params: BrpGetParams = parse(request.params.unwrap_or_default())?;
+                let components = self.components(params.entity)?;
+                let mut found = Map::new();
+                let mut errors = Map::new();
+                for path in params.components {
+                    match components.get(&path) {
+                        Some(value) => {
+                            found.insert(path, value.clone());
+                        }
+                        None if params.strict => {
+                            return Err(error(
+                                error_codes::COMPONENT_NOT_PRESENT,
+                                format!("Component `{path}` not present in Entity"),
+                            ));
+                        }
+                        None => {
+                            let missing = error(error_codes::COMPONENT_NOT_PRESENT, &path);
+                            errors.insert(path, json!(missing));
+                        }
+                    }
+                }
+                Ok(json!({ "components": found, "errors": errors }))
+            }
+            BRP_INSERT_METHOD => {
+                let params: BrpInsertParams = parse(request.params.unwrap_or_default())?;
+                let components = self.components(params.entity)?;
+                for (path, value) in params.components {
+                    if path == TRANSFORM {
+                        components.insert(GLOBAL_TRANSFORM.to_owned(), value.clone());
+                    }
+                    components.insert(path, value);
+                }
+                Ok(Value::Null)
+            }
+            BRP_MUTATE_COMPONENT_METHOD => {
+                let params: BrpMutateParams = parse(request.params.unwrap_or_default())?;
+                let components = self.components(params.entity)?;
+                let Some(mut field) = components.get_mut(&params.component) else {
+                    return Err(error(
+                        error_codes::COMPONENT_NOT_PRESENT,
+                        format!("Component `{}` not present in Entity", params.component),
+                    ));
+                };
+                // Only the `.field.field` paths the inspector sends, not indices or variants.
+                for name in params.path.split('.').filter(|name| !name.is_empty()) {
+                    field = field.get_mut(name).ok_or_else(|| {
+                        error(
+                            error_codes::COMPONENT_ERROR,
+                            format!("{} has no field at {}", params.component, params.path),
+                        )
+                    })?;
+                }
+                *field = params.value;
+                if params.component == TRANSFORM {
+                    let transform = components[TRANSFORM].clone();
+                    components.insert(GLOBAL_TRANSFORM.to_owned(), transform);
+                }
+                Ok(Value::Null)
+            }
+            BRP_REMOVE_METHOD => {
+                let params: BrpRemoveParams = parse(request.params.unwrap_or_default())?;
+                let components = self.components(params.entity)?;
+                for path in params.components {
+                    components.remove(&path);
+                }
+                Ok(Value::Null)
+            }
+            BRP_SPAWN_METHOD => {
+                let params: BrpSpawnParams = parse(request.params.unwrap_or_default())?;
+                let entity = self.spawn_raw(params.components.into_iter().collect());
+                Ok(json!({ "entity": entity }))
+            }
+            BRP_DESTROY_METHOD => {
+                let params: BrpDestroyParams = parse(request.params.unwrap_or_default())?;
+                self.components(params.entity)?;
+                self.destroy(params.entity);
+                Ok(Value::Null)
+            }
+            BRP_REPARENT_METHOD => {
+                let params: BrpReparentParams = parse(request.params.unwrap_or_default())?;
+                if let Some(parent) = params.parent {
+                    if params.entities.contains(&parent) {
+                        return Err(error(
+                            error_codes::ENTITY_CANNOT_BE_OWN_PARENT,
+                            format!("Entity {parent} cannot be its own parent"),
+                        ));
+                    }
+                    self.components(parent)?;
+                }
+                for entity in params.entities {
+                    self.components(entity)?;
+                    self.reparent(entity, params.parent);
+                }
+                Ok(Value::Null)
+            }
+            method => Err(error(
+                error_codes::METHOD_NOT_FOUND,
+                format!("Method `{method}` not found"),
+            )),
+        }
+    }
+
+    fn query(&self, params: &BrpQueryParams) -> Vec<Value> {
+        let has = |components: &Map<String, Value>, path: &String| components.contains_key(path);
+        self.entities
+            .iter()
+            .filter(|(_, components)| {
+                params
+                    .data
+                    .components
+                    .iter()
+                    .all(|path| has(components, path))
+                    && params.filter.with.iter().all(|path| has(components, path))
+                    && !params
+                        .filter
+                        .without
+                        .iter()
+                        .any(|path| has(components, path))
+            })
+            .map(|(entity, components)| {
+                let selected: Map<String, Value> = params
+                    .data
+                    .components
+                    .iter()
+                    .chain(&params.data.option)
+                    .filter_map(|path| Some((path.clone(), components.get(path)?.clone())))
+                    .collect();
+                let mut row = json!({ "entity": entity, "components": selected });
+                if !params.data.has.is_empty() {
+                    let flags: Map<String, Value> = params
+                        .data
+                        .has
+                        .iter()
+                        .map(|path| (path.clone(), json!(has(components, path))))
+                        .collect();
+                    row["has"] = Value::Object(flags);
+                }
+                row
+            })
+            .collect()
+    }
+}
+
+/// The mock reports the Bevy 0.15 type paths.
+fn paths() -> &'static crate::compat::TypePaths {
+    BevyVersion::V0_15.paths()
+}
+
+fn children_without(children: Option<&Value>, entity: Entity) -> Vec<Entity> {
+    children
+        .and_then(|children| serde_json::from_value::<Vec<Entity>>(children.clone()).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|child| *child != entity)
+        .collect()
+}
+
+fn parse<T: DeserializeOwned>(params: Value) -> Result<T, BrpError> {
+    serde_json::from_value(params).map_err(|e| error(error_codes::INVALID_PARAMS, e))
+}
+
+fn error(code: i16, message: impl ToString) -> BrpError {
+    BrpError {
+        code,
+        message: message.to_string(),
+        data: None,
+    }
+}
+
+fn response(id: Value, result: Result<Value, BrpError>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error }),
+    }
+}
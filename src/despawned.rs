@@ -0,0 +1,121 @@
+//! Entities a poll showed to be gone from the game. Their last rows stay in the cache,
+//! greyed out, until pruned, so a despawn doesn't make the selection vanish without a
+//! trace and an index the game reused isn't taken for the entity that had it before.
+
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow, utils::HashMap};
+use web_time::Instant;
+
+pub struct Gone {
+    pub since: Instant,
+    /// The live entity with the same index and a newer generation, once the game reused
+    /// the index.
+    pub reused_by: Option<Entity>,
+}
+
+impl Gone {
+    /// Why `entity` is greyed out, with its index and generation spelled out since a
+    /// reused index is only told apart by the generation.
+    pub fn describe(&self, entity: Entity) -> String {
+        let mut text = format!(
+            "{entity} (index {}, generation {}) was despawned {:.0}s ago, these are its last \
+             known components",
+            entity.index(),
+            entity.generation(),
+            self.since.elapsed().as_secs_f32()
+        );
+        if let Some(reused_by) = self.reused_by {
+            text += &format!("\nIndex {} is now used by {reused_by}", entity.index());
+        }
+        text
+    }
+}
+
+#[derive(Default)]
+pub struct Despawned {
+    gone: HashMap<Entity, Gone>,
+}
+
+impl Despawned {
+    pub fn get(&self, entity: &Entity) -> Option<&Gone> {
+        self.gone.get(entity)
+    }
+
+    pub fn contains(&self, entity: &Entity) -> bool {
+        self.gone.contains_key(entity)
+    }
+
+    pub fn len(&self) -> usize {
+        self.gone.len()
+    }
+
+    /// Moves the rows of entities already known to be gone out of `rows`, leaving the
+    /// live ones to compare a poll with.
+    pub fn take_rows(
+        &self,
+        rows: &mut HashMap<Entity, BrpQueryRow>,
+    ) -> HashMap<Entity, BrpQueryRow> {
+        self.gone
+            .keys()
+            .filter_map(|entity| Some((*entity, rows.remove(entity)?)))
+            .collect()
+    }
+
+    /// Keeps the rows of `previous` and `gone_rows` missing from a poll of the whole world
+    /// in `current`, marking the newly missing ones as gone.
+    pub fn carry_over(
+        &mut self,
+        previous: &HashMap<Entity, BrpQueryRow>,
+        gone_rows: HashMap<Entity, BrpQueryRow>,
+        current: &mut HashMap<Entity, BrpQueryRow>,
+        now: Instant,
+    ) {
+        // Back in the poll, e.g. after a filter that hid it was lifted.
+        self.gone.retain(|entity, _| !current.contains_key(entity));
+        for (entity, row) in previous {
+            if !current.contains_key(entity) {
+                self.mark(*entity, now);
+                current.insert(*entity, row.clone());
+            }
+        }
+        for (entity, row) in gone_rows {
+            if self.gone.contains_key(&entity) {
+                current.insert(entity, row);
+            }
+        }
+        self.link_reuses(current);
+    }
+
+    pub fn mark(&mut self, entity: Entity, now: Instant) {
+        self.gone.entry(entity).or_insert(Gone {
+            since: now,
+            reused_by: None,
+        });
+    }
+
+    /// Points gone entities at the live ones in `rows` that reuse their index.
+    pub fn link_reuses(&mut self, rows: &HashMap<Entity, BrpQueryRow>) {
+        let live: HashMap<u32, Entity> = rows
+            .keys()
+            .filter(|entity| !self.gone.contains_key(*entity))
+            .map(|entity| (entity.index(), *entity))
+            .collect();
+        for (entity, gone) in self.gone.iter_mut() {
+            gone.reused_by = live.get(&entity.index()).copied();
+        }
+    }
+
+    /// Drops the rows of all gone entities from `rows`, returning how many there were.
+    pub fn prune(&mut self, rows: &mut HashMap<Entity, BrpQueryRow>) -> usize {
+        for entity in self.gone.keys() {
+            rows.remove(entity);
+        }
+        let pruned = self.gone.len();
+        self.gone.clear();
+        pruned
+    }
+
+    /// Forgets everything, for fetches that don't say whether entities still exist.
+    pub fn clear(&mut self) {
+        self.gone.clear();
+    }
+}
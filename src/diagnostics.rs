@@ -0,0 +1,86 @@
+//! Everything useful for a bug report, zipped into a single file.
+
+use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow, utils::HashMap};
+use egui::ColorImage;
+use serde_json::{json, Value};
+use zip::write::SimpleFileOptions;
+
+use crate::config::AppConfig;
+use crate::history::QueryHistory;
+
+/// Contents of a diagnostics archive, collected on the UI thread.
+pub struct DiagnosticsBundle {
+    pub snapshot: Value,
+    pub requests: Value,
+    pub settings: Value,
+    pub screenshot: Option<Arc<ColorImage>>,
+}
+
+impl DiagnosticsBundle {
+    /// The snapshot has what the redaction rules in `config` hide masked, like other
+    /// exports.
+    pub fn collect(
+        world: &HashMap<Entity, BrpQueryRow>,
+        history: &QueryHistory,
+        config: &AppConfig,
+        screenshot: Option<Arc<ColorImage>>,
+    ) -> Self {
+        let mut rows: Vec<_> = world
+            .values()
+            .map(|row| config.redaction.redact_row(row))
+            .collect();
+        rows.sort_by_key(|row| row.entity);
+        let requests = history
+            .records()
+            .map(|record| {
+                json!({
+                    "method": record.method,
+                    "params": record.params,
+                    "duration_ms": record.duration.as_secs_f64() * 1000.0,
+                    "payload": record.payload,
+                    "result": match &record.result {
+                        Ok(rows) => json!({ "rows": rows }),
+                        Err(e) => json!({ "error": e }),
+                    },
+                })
+            })
+            .collect();
+        Self {
+            snapshot: serde_json::to_value(rows).unwrap_or_default(),
+            requests,
+            settings: serde_json::to_value(config.scrubbed()).unwrap_or_default(),
+            screenshot,
+        }
+    }
+
+    /// Writes the archive with `snapshot.json`, `requests.json`, `settings.json` and,
+    /// when one was taken, `screenshot.png`.
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        let mut archive = zip::ZipWriter::new(std::fs::File::create(path)?);
+        let options = SimpleFileOptions::default();
+        for (name, value) in [
+            ("snapshot.json", &self.snapshot),
+            ("requests.json", &self.requests),
+            ("settings.json", &self.settings),
+        ] {
+            archive.start_file(name, options)?;
+            archive.write_all(serde_json::to_string_pretty(value)?.as_bytes())?;
+        }
+        if let Some(image) = &self.screenshot {
+            archive.start_file("screenshot.png", options)?;
+            let [width, height] = image.size;
+            let mut encoder = png::Encoder::new(&mut archive, width as u32, height as u32);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_depth(png::BitDepth::Eight);
+            let mut writer = encoder.write_header()?;
+            writer.write_image_data(image.as_raw())?;
+            writer.finish()?;
+        }
+        archive.finish()?;
+        Ok(())
+    }
+}
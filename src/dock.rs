@@ -0,0 +1,134 @@
+//! Panels docked to a side of the main window instead of floating above it. Space for
+//! them is reserved beside the tree while the main window is laid out, the panels are
+//! drawn into it later in the frame like floating ones. Where each panel is docked is
+//! kept in the settings, the sizes in egui's memory.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum DockSide {
+    Left,
+    Right,
+    Bottom,
+}
+
+impl DockSide {
+    pub const ALL: [DockSide; 3] = [DockSide::Left, DockSide::Right, DockSide::Bottom];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            DockSide::Left => "Dock left",
+            DockSide::Right => "Dock right",
+            DockSide::Bottom => "Dock at the bottom",
+        }
+    }
+}
+
+#[derive(Clone, Default)]
+struct DockState {
+    layout: BTreeMap<String, DockSide>,
+    /// Docked panels drawn in the previous frame, space is reserved for them.
+    shown: BTreeSet<String>,
+    shown_now: BTreeSet<String>,
+    reserved: HashMap<String, egui::Rect>,
+    /// Docking asked for in panel headers, `None` to float again.
+    changes: Vec<(String, Option<DockSide>)>,
+}
+
+fn state_id() -> egui::Id {
+    egui::Id::new("dock_state")
+}
+
+fn with_state<R>(ctx: &egui::Context, f: impl FnOnce(&mut DockState) -> R) -> R {
+    ctx.data_mut(|d| f(d.get_temp_mut_or_default::<DockState>(state_id())))
+}
+
+/// Makes `layout` the one panels are docked by this frame, called once per frame before
+/// anything is drawn.
+pub fn begin_frame(ctx: &egui::Context, layout: &BTreeMap<String, DockSide>) {
+    with_state(ctx, |state| {
+        state.layout = layout.clone();
+        state.shown = std::mem::take(&mut state.shown_now);
+        state.reserved.clear();
+    });
+}
+
+/// Reserves the space of the docked panels inside `ui`, before the rest of it is laid out.
+pub fn reserve(ui: &mut egui::Ui) {
+    let docked: Vec<(String, DockSide)> = with_state(ui.ctx(), |state| {
+        state
+            .layout
+            .iter()
+            .filter(|(key, _)| state.shown.contains(*key))
+            .map(|(key, side)| (key.clone(), *side))
+            .collect()
+    });
+    for (key, side) in docked {
+        let id = egui::Id::new(("dock", &key));
+        let reserve_rect = |ui: &mut egui::Ui| {
+            let rect = ui.max_rect();
+            with_state(ui.ctx(), |state| state.reserved.insert(key.clone(), rect));
+        };
+        match side {
+            DockSide::Left => {
+                egui::SidePanel::left(id)
+                    .resizable(true)
+                    .default_width(320.0)
+                    .show_inside(ui, reserve_rect);
+            }
+            DockSide::Right => {
+                egui::SidePanel::right(id)
+                    .resizable(true)
+                    .default_width(320.0)
+                    .show_inside(ui, reserve_rect);
+            }
+            DockSide::Bottom => {
+                egui::TopBottomPanel::bottom(id)
+                    .resizable(true)
+                    .default_height(220.0)
+                    .show_inside(ui, reserve_rect);
+            }
+        }
+    }
+}
+
+pub fn side(ctx: &egui::Context, key: &str) -> Option<DockSide> {
+    with_state(ctx, |state| state.layout.get(key).copied())
+}
+
+/// Asks to dock the panel `key` to `side`, or float it for `None`, applied with
+/// [`take_changes`].
+pub fn request(ctx: &egui::Context, key: &str, side: Option<DockSide>) {
+    with_state(ctx, |state| state.changes.push((key.to_owned(), side)));
+}
+
+/// Docking asked for this frame, to apply to the saved layout.
+pub fn take_changes(ctx: &egui::Context) -> Vec<(String, Option<DockSide>)> {
+    with_state(ctx, |state| std::mem::take(&mut state.changes))
+}
+
+/// Draws a docked panel into the space reserved for it. It shows up a frame after it was
+/// opened, once there is space for it.
+pub fn show_docked(ctx: &egui::Context, key: &str, add_contents: impl FnOnce(&mut egui::Ui)) {
+    let rect = with_state(ctx, |state| {
+        state.shown_now.insert(key.to_owned());
+        state.reserved.get(key).copied()
+    });
+    let Some(rect) = rect else {
+        ctx.request_repaint();
+        return;
+    };
+    egui::Area::new(egui::Id::new(("docked", key)))
+        .fixed_pos(rect.min)
+        .movable(false)
+        .show(ctx, |ui| {
+            ui.set_clip_rect(rect);
+            ui.set_width(rect.width());
+            ui.set_max_height(rect.height());
+            egui::ScrollArea::vertical()
+                .auto_shrink([false, false])
+                .show(ui, add_contents);
+        });
+}
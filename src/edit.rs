@@ -0,0 +1,464 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::annotations::{ComponentAnnotations, FieldAnnotation};
+use crate::schema::Schemas;
+use crate::widgets::{edit_color, is_color};
+
+/// In-progress edit of a single component value.
+pub struct ComponentEdit {
+    /// Value of the component at the moment editing started.
+    pub original: Value,
+    /// Edited value as JSON text.
+    pub text: String,
+    pub view: EditView,
+    pub state: EditState,
+    pub error: Option<String>,
+}
+
+#[derive(PartialEq, Eq, Clone, Copy)]
+pub enum EditView {
+    /// Widgets picked by the type of every JSON value.
+    Fields,
+    /// The form generated from the registry schema of the component.
+    Form,
+    Json,
+}
+
+pub enum EditState {
+    Editing,
+    /// Re-fetching the component to check whether the game changed it meanwhile.
+    Checking,
+    /// The game changed the component since editing started, holds the current remote value.
+    Conflict(Value),
+    /// Waiting to be applied together with the other staged edits.
+    Staged,
+    Applying,
+}
+
+/// Steps edited values snap to while Ctrl is held, like transform snapping in editors.
+#[derive(Clone, Copy)]
+pub struct Snap {
+    /// Grid step of translations.
+    pub grid: f64,
+    /// Step of rotations, in degrees.
+    pub angle: f64,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
+pub enum ConflictResolution {
+    Overwrite,
+    Merge,
+    Discard,
+}
+
+impl ComponentEdit {
+    pub fn new(original: &Value) -> Self {
+        Self {
+            original: original.clone(),
+            text: serde_json::to_string_pretty(original).unwrap_or_default(),
+            view: EditView::Fields,
+            state: EditState::Editing,
+            error: None,
+        }
+    }
+
+    pub fn parsed(&self) -> Result<Value, String> {
+        serde_json::from_str(&self.text).map_err(|e| e.to_string())
+    }
+
+    /// Replaces the edited value, e.g. with random values.
+    pub fn set(&mut self, value: &Value) {
+        self.text = serde_json::to_string_pretty(value).unwrap_or_default();
+    }
+
+    /// Typed widgets for every field of the edited value, written back to the text as
+    /// they change. Falls back to the JSON editor while the text doesn't parse. Fields
+    /// with a declared range get a slider, ones with a unit show it after the value.
+    /// Rotations are edited as Euler angles.
+    pub fn draw_fields(
+        &mut self,
+        ui: &mut egui::Ui,
+        annotations: ComponentAnnotations,
+        snap: Snap,
+    ) {
+        let Ok(mut value) = self.parsed() else {
+            self.view = EditView::Json;
+            return;
+        };
+        let field = Field {
+            annotations,
+            snap,
+            siblings: None,
+        };
+        if edit_value(ui, "", &mut value, &field) {
+            self.text = serde_json::to_string_pretty(&value).unwrap_or_default();
+        }
+    }
+
+    /// Edits the value through the form generated from the schema of `type_path`,
+    /// with dropdowns for enums. Falls back to the JSON editor while the text doesn't
+    /// parse.
+    pub fn draw_form(&mut self, ui: &mut egui::Ui, schemas: &Schemas, type_path: &str) {
+        let Ok(mut value) = self.parsed() else {
+            self.view = EditView::Json;
+            return;
+        };
+        let before = value.clone();
+        schemas.edit(ui, ui.id().with("form"), type_path, &mut value);
+        if value != before {
+            self.text = serde_json::to_string_pretty(&value).unwrap_or_default();
+        }
+    }
+
+    /// Restores the field at JSON pointer `path` to its value in `fetched`, leaving the
+    /// rest of the edited text as it is. Does nothing while the text doesn't parse.
+    pub fn revert_field(&mut self, fetched: &Value, path: &str) {
+        let Ok(mut mine) = self.parsed() else {
+            return;
+        };
+        let (parent, key) = path.rsplit_once('/').unwrap_or(("", path));
+        match (fetched.pointer(path), mine.pointer_mut(parent)) {
+            (Some(value), _) if path.is_empty() => mine = value.clone(),
+            (Some(value), Some(Value::Object(object))) => {
+                object.insert(unescape(key), value.clone());
+            }
+            (Some(value), Some(Value::Array(array))) => {
+                if let Some(slot) = key.parse().ok().and_then(|i: usize| array.get_mut(i)) {
+                    *slot = value.clone();
+                }
+            }
+            (None, Some(Value::Object(object))) => {
+                object.remove(&unescape(key));
+            }
+            _ => return,
+        }
+        self.text = serde_json::to_string_pretty(&mine).unwrap_or_default();
+    }
+}
+
+/// Where in the edited component a value is, to look up its annotation.
+struct Field<'a> {
+    annotations: ComponentAnnotations<'a>,
+    snap: Snap,
+    /// The object holding the value, ranges may refer to its other fields.
+    siblings: Option<Map<String, Value>>,
+}
+
+impl<'a> Field<'a> {
+    fn annotation(&self, path: &str) -> Option<&'a FieldAnnotation> {
+        self.annotations.get(path)
+    }
+
+    fn nested(&self, siblings: Option<Map<String, Value>>) -> Field<'a> {
+        Field {
+            annotations: self.annotations,
+            snap: self.snap,
+            siblings,
+        }
+    }
+}
+
+/// Drag values for numbers, checkboxes for bools and text fields for strings, nested
+/// objects and arrays as collapsible sections. Returns whether anything changed.
+fn edit_value(ui: &mut egui::Ui, id: &str, value: &mut Value, field: &Field) -> bool {
+    match value {
+        Value::Object(fields) => {
+            let nested = field.nested(Some(fields.clone()));
+            let mut changed = false;
+            for (key, value) in fields.iter_mut() {
+                let id = format!("{id}/{}", escape(key));
+                changed |= edit_field(ui, &id, key, value, &nested);
+            }
+            changed
+        }
+        Value::Array(items) if items.iter().all(|item| item.is_number()) => {
+            let nested = field.nested(None);
+            ui.horizontal_wrapped(|ui| {
+                let mut changed = false;
+                for (index, item) in items.iter_mut().enumerate() {
+                    changed |= edit_scalar(ui, &format!("{id}/{index}"), item, &nested);
+                }
+                changed
+            })
+            .inner
+        }
+        Value::Array(items) => {
+            let nested = field.nested(None);
+            let mut changed = false;
+            for (index, item) in items.iter_mut().enumerate() {
+                let id = format!("{id}/{index}");
+                changed |= edit_field(ui, &id, &index.to_string(), item, &nested);
+            }
+            changed
+        }
+        _ => edit_scalar(ui, id, value, field),
+    }
+}
+
+fn edit_field(ui: &mut egui::Ui, id: &str, label: &str, value: &mut Value, field: &Field) -> bool {
+    if is_color(value) {
+        return ui
+            .horizontal(|ui| {
+                ui.label(label);
+                edit_color(ui, value)
+            })
+            .inner;
+    }
+    let nested = match value {
+        Value::Object(_) => true,
+        Value::Array(items) => !items.iter().all(|item| item.is_number()),
+        _ => false,
+    };
+    if nested {
+        egui::CollapsingHeader::new(label)
+            .id_salt(id)
+            .default_open(true)
+            .show(ui, |ui| edit_value(ui, id, value, field))
+            .body_returned
+            .unwrap_or(false)
+    } else if label == "rotation" && as_quat(value).is_some() {
+        ui.horizontal(|ui| {
+            ui.label(label);
+            edit_rotation(ui, value, field.snap)
+        })
+        .inner
+    } else {
+        ui.horizontal(|ui| {
+            ui.label(label);
+            edit_value(ui, id, value, field)
+        })
+        .inner
+    }
+}
+
+/// Whether the snap modifier is held.
+pub fn snapping(ui: &egui::Ui) -> bool {
+    ui.input(|i| i.modifiers.command)
+}
+
+pub fn snap_to(value: f64, step: f64) -> f64 {
+    if step > 0.0 {
+        (value / step).round() * step
+    } else {
+        value
+    }
+}
+
+/// A quaternion as serialized by glam, `[x, y, z, w]`.
+pub fn as_quat(value: &Value) -> Option<[f64; 4]> {
+    match value.as_array()?.as_slice() {
+        [x, y, z, w] => Some([x.as_f64()?, y.as_f64()?, z.as_f64()?, w.as_f64()?]),
+        _ => None,
+    }
+}
+
+/// Yaw, pitch and roll in degrees, rotating around Y, then X, then Z like Bevy's
+/// `EulerRot::YXZ`.
+pub fn quat_to_euler([x, y, z, w]: [f64; 4]) -> [f64; 3] {
+    let yaw = (2.0 * (x * z + w * y)).atan2(1.0 - 2.0 * (x * x + y * y));
+    let pitch = (2.0 * (w * x - y * z)).clamp(-1.0, 1.0).asin();
+    let roll = (2.0 * (x * y + w * z)).atan2(1.0 - 2.0 * (x * x + z * z));
+    [yaw.to_degrees(), pitch.to_degrees(), roll.to_degrees()]
+}
+
+pub fn euler_to_quat([yaw, pitch, roll]: [f64; 3]) -> [f64; 4] {
+    let (sy, cy) = (yaw.to_radians() / 2.0).sin_cos();
+    let (sp, cp) = (pitch.to_radians() / 2.0).sin_cos();
+    let (sr, cr) = (roll.to_radians() / 2.0).sin_cos();
+    // Y(yaw) * X(pitch) * Z(roll)
+    [
+        cy * sp * cr + sy * cp * sr,
+        sy * cp * cr - cy * sp * sr,
+        cy * cp * sr - sy * sp * cr,
+        cy * cp * cr + sy * sp * sr,
+    ]
+}
+
+/// Edits a quaternion as yaw, pitch and roll, snapping to angle steps while Ctrl is held.
+fn edit_rotation(ui: &mut egui::Ui, value: &mut Value, snap: Snap) -> bool {
+    let Some(quat) = as_quat(value) else {
+        return false;
+    };
+    let mut angles = quat_to_euler(quat);
+    let mut changed = false;
+    for (angle, name) in angles.iter_mut().zip(["yaw", "pitch", "roll"]) {
+        let response = ui
+            .add(egui::DragValue::new(angle).speed(1.0).suffix("°"))
+            .on_hover_text(name);
+        if response.changed() {
+            if snapping(ui) {
+                *angle = snap_to(*angle, snap.angle);
+            }
+            changed = true;
+        }
+    }
+    if changed {
+        let quat = euler_to_quat(angles);
+        *value = Value::Array(
+            quat.iter()
+                .filter_map(|n| serde_json::Number::from_f64(*n).map(Value::Number))
+                .collect(),
+        );
+    }
+    changed
+}
+
+fn edit_scalar(ui: &mut egui::Ui, path: &str, value: &mut Value, field: &Field) -> bool {
+    let annotation = field.annotation(path);
+    let unit = annotation
+        .and_then(|a| a.unit.as_deref())
+        .map(|unit| format!(" {unit}"))
+        .unwrap_or_default();
+    let bounds = annotation
+        .and_then(|a| a.range.as_ref())
+        .map(|range| range.resolve(field.siblings.as_ref()));
+    match value {
+        Value::Bool(flag) => ui.checkbox(flag, "").changed(),
+        Value::String(text) => ui.text_edit_singleline(text).changed(),
+        Value::Number(number) => {
+            if let Some(mut int) = number.as_i64() {
+                let changed = match bounds {
+                    Some((Some(min), Some(max))) => ui
+                        .add(
+                            egui::Slider::new(&mut int, min as i64..=max as i64)
+                                .suffix(unit)
+                                .clamping(egui::SliderClamping::Edits),
+                        )
+                        .changed(),
+                    _ => ui
+                        .add(egui::DragValue::new(&mut int).suffix(unit))
+                        .changed(),
+                };
+                *value = int.into();
+                changed
+            } else if let Some(mut uint) = number.as_u64() {
+                let changed = ui
+                    .add(egui::DragValue::new(&mut uint).suffix(unit))
+                    .changed();
+                *value = uint.into();
+                changed
+            } else {
+                let mut float = number.as_f64().unwrap_or_default();
+                let changed = match bounds {
+                    Some((Some(min), Some(max))) => ui
+                        .add(
+                            egui::Slider::new(&mut float, min..=max)
+                                .suffix(unit)
+                                .clamping(egui::SliderClamping::Edits),
+                        )
+                        .changed(),
+                    _ => ui
+                        .add(egui::DragValue::new(&mut float).speed(0.01).suffix(unit))
+                        .changed(),
+                };
+                if changed && path.contains("/translation/") && snapping(ui) {
+                    float = snap_to(float, field.snap.grid);
+                }
+                // NaN and infinity have no JSON form, keep the old value then.
+                if let Some(number) = serde_json::Number::from_f64(float) {
+                    *value = Value::Number(number);
+                }
+                changed
+            }
+        }
+        Value::Null | Value::Array(_) | Value::Object(_) => {
+            ui.weak(value.to_string());
+            false
+        }
+    }
+}
+
+pub fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+fn unescape(key: &str) -> String {
+    key.replace("~1", "/").replace("~0", "~")
+}
+
+/// JSON pointers of the fields that differ between `fetched` and `mine`, descending into
+/// objects and into arrays of the same length.
+pub fn modified_fields(fetched: &Value, mine: &Value) -> Vec<String> {
+    fn walk(path: String, fetched: Option<&Value>, mine: Option<&Value>, out: &mut Vec<String>) {
+        match (fetched, mine) {
+            (Some(a), Some(b)) if a == b => {}
+            (Some(Value::Object(a)), Some(Value::Object(b))) => {
+                for key in a.keys().chain(b.keys().filter(|k| !a.contains_key(*k))) {
+                    let child = format!("{path}/{}", escape(key));
+                    walk(child, a.get(key), b.get(key), out);
+                }
+            }
+            (Some(Value::Array(a)), Some(Value::Array(b))) if a.len() == b.len() => {
+                for (index, (a, b)) in a.iter().zip(b).enumerate() {
+                    walk(format!("{path}/{index}"), Some(a), Some(b), out);
+                }
+            }
+            _ => out.push(path),
+        }
+    }
+    let mut out = Vec::new();
+    walk(String::new(), Some(fetched), Some(mine), &mut out);
+    out
+}
+
+/// Reflection paths like `.translation` and the values to set there to turn `fetched`
+/// into `mine`, for `bevy/mutate_component`. Arrays are set as a whole since vectors are
+/// structs to reflection, `None` when fields were added or removed, an enum changed
+/// variant or the whole value changed.
+pub fn field_mutations(fetched: &Value, mine: &Value) -> Option<Vec<(String, Value)>> {
+    fn walk(path: String, fetched: &Value, mine: &Value, out: &mut Vec<(String, Value)>) -> bool {
+        if fetched == mine {
+            return true;
+        }
+        match (fetched, mine) {
+            (Value::Object(a), Value::Object(b)) if !is_enum(a) => {
+                if a.len() != b.len() || a.keys().any(|key| !b.contains_key(key)) {
+                    return false;
+                }
+                b.iter()
+                    .all(|(key, value)| walk(format!("{path}.{key}"), &a[key], value, out))
+            }
+            _ if path.is_empty() => false,
+            _ => {
+                out.push((path, mine.clone()));
+                true
+            }
+        }
+    }
+    /// Externally tagged enum variants are the only keys starting in upper case.
+    fn is_enum(map: &Map<String, Value>) -> bool {
+        map.len() == 1 && map.keys().all(|key| key.starts_with(char::is_uppercase))
+    }
+    let mut out = Vec::new();
+    walk(String::new(), fetched, mine, &mut out).then_some(out)
+}
+
+/// Applies only the fields that differ between `original` and `mine` on top of `remote`,
+/// keeping every other value as the game currently has it.
+pub fn merge_changes(original: &Value, mine: &Value, remote: &Value) -> Value {
+    match (original, mine, remote) {
+        (Value::Object(original), Value::Object(mine), Value::Object(remote)) => {
+            let mut result = remote.clone();
+            for (key, value) in mine.iter() {
+                match (original.get(key), remote.get(key)) {
+                    (Some(before), _) if before == value => {}
+                    (Some(before), Some(current)) => {
+                        result.insert(key.clone(), merge_changes(before, value, current));
+                    }
+                    _ => {
+                        result.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+            for key in original.keys() {
+                if !mine.contains_key(key) {
+                    result.remove(key);
+                }
+            }
+            Value::Object(result)
+        }
+        _ if original == mine => remote.clone(),
+        _ => mine.clone(),
+    }
+}
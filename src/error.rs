@@ -0,0 +1,155 @@
+use std::fmt;
+
+/// Everything that can go wrong talking to the game, grouped so the UI can style and react
+/// to each kind differently.
+#[derive(Clone, Debug, PartialEq)]
+pub enum InspectorError {
+    /// No HTTP response at all, e.g. the game isn't running.
+    Transport(String),
+    Http {
+        status: u16,
+        status_text: String,
+    },
+    /// The remote answered with a JSON-RPC error.
+    JsonRpc {
+        code: i64,
+        message: String,
+        /// Whatever else the game sent along, e.g. the offending value.
+        data: Option<serde_json::Value>,
+    },
+    /// The response, or a value in it, didn't have the expected shape.
+    Decode(String),
+    Timeout,
+    /// The server or a proxy in front of it asked to slow down, see [`crate::throttle`].
+    Throttled {
+        status: u16,
+    },
+    /// Failures on the inspector's side, like reading a settings file.
+    Local(String),
+}
+
+impl InspectorError {
+    /// Whether trying again later can help, which is only the case when the game
+    /// couldn't be reached or asked for a break.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::Transport(_) | Self::Timeout | Self::Throttled { .. }
+        )
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Self::Transport(_) => "Connection failed",
+            Self::Http { .. } => "HTTP error",
+            Self::JsonRpc { .. } => "Remote error",
+            Self::Decode(_) => "Unexpected response",
+            Self::Timeout => "Request timed out",
+            Self::Throttled { .. } => "Throttled",
+            Self::Local(_) => "Error",
+        }
+    }
+
+    /// What probably went wrong and what to check, in plain words.
+    pub fn hint(&self) -> Option<&'static str> {
+        match self {
+            Self::Transport(e) if e.to_lowercase().contains("refused") => Some(
+                "Nothing is listening at that address. Is the game running, with \
+                 `RemotePlugin` and `RemoteHttpPlugin` added?",
+            ),
+            Self::Transport(_) => {
+                Some("The game could not be reached. Check the host and port of the connection.")
+            }
+            Self::Http { .. } => Some(
+                "Something answered at that address, but not like a Bevy game does. Check the \
+                 port of the connection.",
+            ),
+            Self::JsonRpc { code: -32601, .. } => Some(
+                "The game doesn't know this method, it may run another Bevy version or lack \
+                 the plugin providing it.",
+            ),
+            Self::JsonRpc { .. } => None,
+            Self::Decode(_) => Some(
+                "The game answered with something the inspector doesn't understand, it may \
+                 run another Bevy version.",
+            ),
+            Self::Timeout => Some(
+                "The game may be paused in a debugger or stuck on a long frame. Fetching \
+                 fewer components helps with very large worlds.",
+            ),
+            Self::Throttled { .. } => Some(
+                "Too many requests reach the game or a proxy in front of it. Polling slows \
+                 down until it recovers, a longer live interval avoids this.",
+            ),
+            Self::Local(_) => None,
+        }
+    }
+
+    /// The `data` of a JSON-RPC error, pretty printed.
+    pub fn data(&self) -> Option<String> {
+        match self {
+            Self::JsonRpc {
+                data: Some(data), ..
+            } => Some(serde_json::to_string_pretty(data).unwrap_or_else(|_| data.to_string())),
+            _ => None,
+        }
+    }
+
+    /// Prefixes the message with what was being done, keeping the kind of error.
+    pub fn context(self, context: impl fmt::Display) -> Self {
+        match self {
+            Self::Transport(e) => Self::Transport(format!("{context}: {e}")),
+            Self::Http {
+                status,
+                status_text,
+            } => Self::Http {
+                status,
+                status_text: format!("{context}: {status_text}"),
+            },
+            Self::JsonRpc {
+                code,
+                message,
+                data,
+            } => Self::JsonRpc {
+                code,
+                message: format!("{context}: {message}"),
+                data,
+            },
+            Self::Decode(e) => Self::Decode(format!("{context}: {e}")),
+            Self::Timeout => Self::Timeout,
+            Self::Throttled { status } => Self::Throttled { status },
+            Self::Local(e) => Self::Local(format!("{context}: {e}")),
+        }
+    }
+}
+
+impl fmt::Display for InspectorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transport(e) | Self::Decode(e) | Self::Local(e) => f.write_str(e),
+            Self::Http {
+                status,
+                status_text,
+            } => write!(f, "{status} {status_text}"),
+            Self::JsonRpc { code, message, .. } => write!(f, "{message} (code {code})"),
+            Self::Timeout => f.write_str("The game did not answer in time"),
+            Self::Throttled { status } => write!(f, "The server asked to slow down ({status})"),
+        }
+    }
+}
+
+impl From<serde_json::Error> for InspectorError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Decode(e.to_string())
+    }
+}
+
+impl From<jsonrpc_types::v2::Failure> for InspectorError {
+    fn from(failure: jsonrpc_types::v2::Failure) -> Self {
+        Self::JsonRpc {
+            code: failure.error.code.code(),
+            message: failure.error.message,
+            data: failure.error.data,
+        }
+    }
+}
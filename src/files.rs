@@ -0,0 +1,36 @@
+//! File dialogs. Browsers don't hand out paths to files, so on the web no file is ever
+//! picked and the features reading or writing files do nothing.
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use rfd::FileDialog;
+
+#[cfg(target_arch = "wasm32")]
+#[derive(Default)]
+pub struct FileDialog;
+
+#[cfg(target_arch = "wasm32")]
+impl FileDialog {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn add_filter(self, _name: impl Into<String>, _extensions: &[impl ToString]) -> Self {
+        self
+    }
+
+    pub fn set_file_name(self, _file_name: impl Into<String>) -> Self {
+        self
+    }
+
+    pub fn pick_file(self) -> Option<std::path::PathBuf> {
+        None
+    }
+
+    pub fn save_file(self) -> Option<std::path::PathBuf> {
+        None
+    }
+
+    pub fn pick_folder(self) -> Option<std::path::PathBuf> {
+        None
+    }
+}
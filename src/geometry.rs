@@ -0,0 +1,56 @@
+use egui::{Pos2, Vec2, ViewportCommand};
+use serde::{Deserialize, Serialize};
+
+/// Smallest window that still fits the toolbar, same as the minimum in `main`.
+const MIN_SIZE: Vec2 = Vec2::new(300.0, 220.0);
+
+/// Size, position and maximized state of the main window. The custom frame has no native
+/// decorations, so this is tracked by the app instead of the window manager.
+#[derive(Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(default)]
+pub struct WindowGeometry {
+    pub position: Option<[f32; 2]>,
+    pub size: Option<[f32; 2]>,
+    pub maximized: bool,
+}
+
+impl WindowGeometry {
+    /// Records the current geometry. Position and size are only taken from the normal
+    /// window state, so un-maximizing after a restart returns to them.
+    pub fn capture(&mut self, ctx: &egui::Context) {
+        ctx.input(|i| {
+            let viewport = i.viewport();
+            self.maximized = viewport.maximized.unwrap_or(false);
+            if self.maximized || viewport.minimized == Some(true) {
+                return;
+            }
+            if let Some(rect) = viewport.outer_rect {
+                self.position = Some(rect.min.into());
+            }
+            if let Some(rect) = viewport.inner_rect {
+                self.size = Some(rect.size().into());
+            }
+        });
+    }
+
+    /// Moves and resizes the window to the stored geometry, kept within the current
+    /// monitor. Returns `false` while the monitor size isn't known yet.
+    pub fn restore(&self, ctx: &egui::Context) -> bool {
+        let Some(monitor) = ctx.input(|i| i.viewport().monitor_size) else {
+            return false;
+        };
+        if let Some(size) = self.size {
+            let size = Vec2::from(size).max(MIN_SIZE).min(monitor);
+            ctx.send_viewport_cmd(ViewportCommand::InnerSize(size));
+            if let Some(position) = self.position {
+                let max = (monitor - size).max(Vec2::ZERO);
+                let position = Pos2::from(position).clamp(Pos2::ZERO, max.to_pos2());
+                ctx.send_viewport_cmd(ViewportCommand::OuterPosition(position));
+            }
+        }
+        if self.maximized {
+            ctx.send_viewport_cmd(ViewportCommand::Maximized(true));
+        }
+        true
+    }
+}
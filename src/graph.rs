@@ -0,0 +1,186 @@
+//! The hierarchy and the references between entities saved as a graph, for rendering
+//! in Graphviz or Gephi.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Write as _;
+use std::path::Path;
+
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow, utils::HashMap};
+
+use crate::compat::TypePaths;
+use crate::ordering::children;
+use crate::problems::parent_of;
+use crate::references::entities_in;
+use crate::summary::short_name;
+
+pub struct EntityGraph {
+    /// Entities with their labels, by id.
+    nodes: BTreeMap<Entity, String>,
+    /// Parent and child pairs.
+    hierarchy: BTreeSet<(Entity, Entity)>,
+    /// Component fields holding another of the entities.
+    references: Vec<GraphReference>,
+}
+
+struct GraphReference {
+    from: Entity,
+    to: Entity,
+    component: String,
+    /// Location of the value inside the component, e.g. `.target`.
+    path: String,
+}
+
+impl EntityGraph {
+    /// The graph of `entities`, leaving out links to entities outside of them.
+    pub fn new(
+        entities: &BTreeMap<Entity, &BrpQueryRow>,
+        paths: &TypePaths,
+        label: impl Fn(&Entity, &BrpQueryRow) -> String,
+    ) -> Self {
+        let known: HashMap<u64, Entity> = entities
+            .keys()
+            .map(|entity| (entity.to_bits(), *entity))
+            .collect();
+        let mut hierarchy = BTreeSet::new();
+        let mut references = Vec::new();
+        for (entity, row) in entities {
+            // Both sides of the relationship, a link missing on one still counts.
+            if let Some(parent) = parent_of(row, paths).filter(|p| entities.contains_key(p)) {
+                hierarchy.insert((parent, *entity));
+            }
+            for child in children(row, paths) {
+                if entities.contains_key(&child) {
+                    hierarchy.insert((*entity, child));
+                }
+            }
+            let mut components: Vec<_> = row
+                .components
+                .iter()
+                .filter(|(key, _)| *key != paths.parent && *key != paths.children)
+                .collect();
+            components.sort_by_key(|(key, _)| *key);
+            for (component, value) in components {
+                for (path, to) in entities_in(value, &known) {
+                    if to != *entity {
+                        references.push(GraphReference {
+                            from: *entity,
+                            to,
+                            component: component.clone(),
+                            path,
+                        });
+                    }
+                }
+            }
+        }
+        Self {
+            nodes: entities
+                .iter()
+                .map(|(entity, row)| (*entity, label(entity, row)))
+                .collect(),
+            hierarchy,
+            references,
+        }
+    }
+
+    /// Graphviz source, hierarchy links solid and references dashed and labeled with the
+    /// field.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph world {\n    node [shape=box];\n");
+        for (entity, label) in &self.nodes {
+            let label = format!("{label}\n{entity}");
+            let _ = writeln!(
+                dot,
+                "    {} [label=\"{}\"];",
+                entity.to_bits(),
+                dot_escape(&label)
+            );
+        }
+        for (parent, child) in &self.hierarchy {
+            let _ = writeln!(dot, "    {} -> {};", parent.to_bits(), child.to_bits());
+        }
+        for reference in &self.references {
+            let label = format!("{}{}", short_name(&reference.component), reference.path);
+            let _ = writeln!(
+                dot,
+                "    {} -> {} [style=dashed, label=\"{}\"];",
+                reference.from.to_bits(),
+                reference.to.to_bits(),
+                dot_escape(&label)
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// GraphML with the label of each node and the kind, component and field of each
+    /// edge as attributes.
+    pub fn to_graphml(&self) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n  \
+             <key id=\"label\" for=\"node\" attr.name=\"label\" attr.type=\"string\"/>\n  \
+             <key id=\"entity\" for=\"node\" attr.name=\"entity\" attr.type=\"string\"/>\n  \
+             <key id=\"kind\" for=\"edge\" attr.name=\"kind\" attr.type=\"string\"/>\n  \
+             <key id=\"component\" for=\"edge\" attr.name=\"component\" attr.type=\"string\"/>\n  \
+             <key id=\"field\" for=\"edge\" attr.name=\"field\" attr.type=\"string\"/>\n  \
+             <graph id=\"world\" edgedefault=\"directed\">\n",
+        );
+        for (entity, label) in &self.nodes {
+            let _ = writeln!(
+                xml,
+                "    <node id=\"n{}\"><data key=\"label\">{}</data><data key=\"entity\">{entity}</data></node>",
+                entity.to_bits(),
+                xml_escape(label)
+            );
+        }
+        for (parent, child) in &self.hierarchy {
+            let _ = writeln!(
+                xml,
+                "    <edge source=\"n{}\" target=\"n{}\"><data key=\"kind\">child</data></edge>",
+                parent.to_bits(),
+                child.to_bits()
+            );
+        }
+        for reference in &self.references {
+            let _ = writeln!(
+                xml,
+                "    <edge source=\"n{}\" target=\"n{}\"><data key=\"kind\">reference</data>\
+                 <data key=\"component\">{}</data><data key=\"field\">{}</data></edge>",
+                reference.from.to_bits(),
+                reference.to.to_bits(),
+                xml_escape(&reference.component),
+                xml_escape(&reference.path)
+            );
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+}
+
+/// Writes the graph as GraphML when the path ends in `.graphml`, as Graphviz source
+/// otherwise.
+pub fn write_graph(path: &Path, graph: &EntityGraph) -> anyhow::Result<()> {
+    let is_graphml = path
+        .extension()
+        .is_some_and(|extension| extension == "graphml");
+    let text = if is_graphml {
+        graph.to_graphml()
+    } else {
+        graph.to_dot()
+    };
+    std::fs::write(path, text)?;
+    Ok(())
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,58 @@
+//! Named groups of entities the tree can be split into, each defined by marker
+//! components like `Enemy` or `Node`.
+
+use bevy::remote::builtin_methods::BrpQueryRow;
+use serde::{Deserialize, Serialize};
+
+use crate::summary::short_name;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub struct EntityGroup {
+    pub name: String,
+    /// Type paths or short names, an entity with any of them belongs to the group.
+    pub markers: Vec<String>,
+}
+
+impl EntityGroup {
+    fn matches(&self, row: &BrpQueryRow) -> bool {
+        self.markers.iter().any(|marker| {
+            row.components
+                .keys()
+                .chain(row.has.keys())
+                .any(|key| key == marker || short_name(key) == marker.as_str())
+        })
+    }
+
+    /// Fields for the name and the markers, separated by commas.
+    pub fn edit(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.name)
+                    .hint_text("Name")
+                    .desired_width(100.0),
+            );
+            // Kept as typed, a trailing comma would be lost when joining again.
+            let id = ui.id().with("markers");
+            let mut text = ui
+                .data_mut(|d| d.get_temp::<String>(id))
+                .unwrap_or_else(|| self.markers.join(", "));
+            ui.add(
+                egui::TextEdit::singleline(&mut text)
+                    .hint_text("Markers, comma separated")
+                    .desired_width(160.0),
+            );
+            self.markers = text
+                .split(',')
+                .map(str::trim)
+                .filter(|marker| !marker.is_empty())
+                .map(str::to_owned)
+                .collect();
+            ui.data_mut(|d| d.insert_temp(id, text));
+        });
+    }
+}
+
+/// Index of the first group the entity belongs to.
+pub fn group_of(row: &BrpQueryRow, groups: &[EntityGroup]) -> Option<usize> {
+    groups.iter().position(|group| group.matches(row))
+}
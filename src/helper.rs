@@ -1,14 +1,134 @@
 use std::sync::atomic::AtomicUsize;
 
-use bevy::remote::BrpRequest;
+use bevy::{remote::BrpRequest, utils::HashMap};
 use ehttp::Response;
 use lazy_static::lazy_static;
 use serde::{de::DeserializeOwned, Serialize};
 
+use crate::companion::MSGPACK_CONTENT_TYPE;
+use crate::{error::InspectorError, responses::BrpResult, throttle};
+
 lazy_static! {
     static ref COUNTER: AtomicUsize = AtomicUsize::new(1);
 }
 
+const JSON_CONTENT_TYPE: &str = "application/json";
+
+/// Address of a game along with the key its request bodies are signed with. Taken from
+/// the connection when a request is made, and kept by whatever sends more requests for
+/// it later, e.g. the next batch, so those don't pick up the key of another tab.
+#[derive(Clone)]
+pub struct Endpoint {
+    pub url: String,
+    /// Header name and key used to sign request bodies.
+    signing: Option<(String, ring::hmac::Key)>,
+    /// Address of the companion plugin's MessagePack endpoint, see [`Self::with_msgpack`].
+    msgpack: Option<String>,
+}
+
+impl Endpoint {
+    /// Signs every request body with an HMAC-SHA256 of `secret`, sent hex encoded in
+    /// `header`, for games that gate their endpoint behind a shared secret. An empty
+    /// secret leaves them unsigned.
+    pub fn new(url: impl ToString, header: &str, secret: &str) -> Self {
+        let signing = (!secret.is_empty()).then(|| {
+            let key = ring::hmac::Key::new(ring::hmac::HMAC_SHA256, secret.as_bytes());
+            (header.to_owned(), key)
+        });
+        Self {
+            url: url.to_string(),
+            signing,
+            msgpack: None,
+        }
+    }
+
+    /// For games that don't check signatures, e.g. ones found on this machine.
+    pub fn unsigned(url: impl ToString) -> Self {
+        Self {
+            url: url.to_string(),
+            signing: None,
+            msgpack: None,
+        }
+    }
+
+    /// Sends bodies as MessagePack to `url`, where the companion plugin agreed to take
+    /// them. Requests stay JSON without the `msgpack` feature.
+    pub fn with_msgpack(mut self, url: impl ToString) -> Self {
+        if cfg!(feature = "msgpack") {
+            self.msgpack = Some(url.to_string());
+        }
+        self
+    }
+
+    /// The same game over plain JSON, for `+watch` streams which only the JSON endpoint
+    /// keeps open.
+    pub fn json(&self) -> Self {
+        Self {
+            msgpack: None,
+            ..self.clone()
+        }
+    }
+
+    fn target(&self) -> &str {
+        self.msgpack.as_deref().unwrap_or(&self.url)
+    }
+
+    fn encode(&self, payload: &impl Serialize) -> Vec<u8> {
+        #[cfg(feature = "msgpack")]
+        if self.msgpack.is_some() {
+            return rmp_serde::to_vec_named(payload).expect("Unable to encode the request");
+        }
+        serde_json::to_vec(payload).unwrap()
+    }
+
+    fn headers(&self, body: &[u8]) -> ehttp::Headers {
+        let content_type = if self.msgpack.is_some() {
+            MSGPACK_CONTENT_TYPE
+        } else {
+            JSON_CONTENT_TYPE
+        };
+        let mut headers =
+            ehttp::Headers::new(&[("Content-Type", content_type), ("Accept", content_type)]);
+        if let Some((header, key)) = &self.signing {
+            let tag = ring::hmac::sign(key, body);
+            let signature: String = tag.as_ref().iter().map(|b| format!("{b:02x}")).collect();
+            headers.insert(header, signature);
+        }
+        headers
+    }
+}
+
+/// Decodes a response body by its content type, anything but MessagePack is read as JSON
+/// so servers that ignore the negotiation keep working.
+fn decode<T: DeserializeOwned>(response: &Response) -> Result<T, InspectorError> {
+    #[cfg(feature = "msgpack")]
+    if response
+        .content_type()
+        .is_some_and(|t| t.starts_with(MSGPACK_CONTENT_TYPE))
+    {
+        let value: serde_json::Value = rmp_serde::from_slice(&response.bytes)
+            .map_err(|e| InspectorError::Decode(e.to_string()))?;
+        return Ok(serde_json::from_value(value)?);
+    }
+    let Some(json) = response.text() else {
+        return Err(InspectorError::Decode("Cannot parse text".into()));
+    };
+    Ok(serde_json::from_str(json)?)
+}
+
+/// Reads a request or response body for showing it, as JSON or, when it isn't text, as
+/// MessagePack.
+pub fn body_value(bytes: &[u8]) -> Option<serde_json::Value> {
+    if let Ok(value) = serde_json::from_slice(bytes) {
+        return Some(value);
+    }
+    #[cfg(feature = "msgpack")]
+    if std::str::from_utf8(bytes).is_err() {
+        return rmp_serde::from_slice(bytes).ok();
+    }
+    None
+}
+
 pub fn create_request<T: Serialize>(value: Option<T>, method: impl ToString) -> BrpRequest {
     let params = match value {
         None => None,
@@ -26,50 +146,268 @@ pub fn create_request<T: Serialize>(value: Option<T>, method: impl ToString) ->
     }
 }
 
+/// A POST of `payload` with its content type set, proxies and stricter HTTP stacks drop
+/// the body of a GET.
+fn post(payload: &impl Serialize, endpoint: &Endpoint) -> ehttp::Request {
+    let body = endpoint.encode(payload);
+    ehttp::Request {
+        method: "POST".to_string(),
+        url: endpoint.target().to_owned(),
+        headers: endpoint.headers(&body),
+        body,
+    }
+}
+
 pub fn make_request<T: Serialize>(
     value: T,
     method: impl ToString,
-    url: impl ToString,
+    endpoint: &Endpoint,
 ) -> ehttp::Request {
-    let request = create_request(Some(value), method);
-    ehttp::Request {
-        method: "GET".to_string(),
-        url: url.to_string(),
-        body: serde_json::to_string(&request).unwrap().into_bytes(),
-        headers: Default::default(),
-    }
+    post(&create_request(Some(value), method), endpoint)
 }
 
-pub fn make_empty_request(method: impl ToString, url: impl ToString) -> ehttp::Request {
-    let request = create_request::<String>(None, method);
-    ehttp::Request {
-        method: "GET".to_string(),
-        url: url.to_string(),
-        body: serde_json::to_string(&request).unwrap().into_bytes(),
-        headers: Default::default(),
-    }
+pub fn make_empty_request(method: impl ToString, endpoint: &Endpoint) -> ehttp::Request {
+    post(&create_request::<String>(None, method), endpoint)
+}
+
+/// Sends several calls in a single HTTP exchange as a JSON-RPC batch.
+pub fn make_batch_request(calls: &[BrpRequest], endpoint: &Endpoint) -> ehttp::Request {
+    post(&calls, endpoint)
 }
 
-pub fn parse<T>(response: &Response) -> Result<T, String>
+pub fn parse<T>(response: &Response) -> Result<T, InspectorError>
 where
     T: DeserializeOwned,
 {
-    let Some(json) = response.text() else {
-        return Err("Cannot parse text".into());
-    };
-    let result: jsonrpc_types::v2::Response = serde_json::from_str(json).unwrap();
-    let jsonrpc_types::v2::Response::Single(result) = result else {
-        return Err("NOT ONE".to_string());
+    let result: jsonrpc_types::v2::Response = decode(response)?;
+    Ok(serde_json::from_value(single_result(result)?)?)
+}
+
+fn single_result(result: jsonrpc_types::v2::Response) -> Result<serde_json::Value, InspectorError> {
+    let result = match result {
+        jsonrpc_types::v2::Response::Single(result) => result,
+        jsonrpc_types::v2::Response::Batch(mut results) if results.len() == 1 => results.remove(0),
+        jsonrpc_types::v2::Response::Batch(results) => {
+            return Err(InspectorError::Decode(format!(
+                "Expected a single response but got a batch of {}",
+                results.len()
+            )));
+        }
     };
 
     let result: jsonrpc_types::Success = match result {
         jsonrpc_types::Output::Success(result) => result,
         jsonrpc_types::Output::Failure(e) => {
-            return Err(e.to_string());
+            return Err(e.into());
+        }
+    };
+    Ok(result.result)
+}
+
+/// Splits the body of a `+watch` response into the JSON-RPC responses it carries as they
+/// stream in. Bevy sends them as server-sent events (`data: {...}` lines), plain JSON
+/// lines are accepted too.
+#[derive(Default)]
+pub struct WatchDecoder {
+    buffer: Vec<u8>,
+}
+
+impl WatchDecoder {
+    /// Results of the responses completed by `chunk`.
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<Result<serde_json::Value, InspectorError>> {
+        self.buffer.extend_from_slice(chunk);
+        let mut results = Vec::new();
+        while let Some(end) = self.buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            results.extend(parse_line(&line));
+        }
+        results
+    }
+
+    /// Result of a last response not followed by a line break.
+    pub fn finish(&mut self) -> Option<Result<serde_json::Value, InspectorError>> {
+        parse_line(&std::mem::take(&mut self.buffer))
+    }
+}
+
+fn parse_line(line: &[u8]) -> Option<Result<serde_json::Value, InspectorError>> {
+    let line = String::from_utf8_lossy(line);
+    let line = line.trim();
+    // Event names, ids and `:` comments carry nothing the inspector needs.
+    let json = line.strip_prefix("data:").unwrap_or(line).trim();
+    if json.is_empty() || !json.starts_with(['{', '[']) {
+        return None;
+    }
+    Some(
+        serde_json::from_str(json)
+            .map_err(InspectorError::from)
+            .and_then(single_result),
+    )
+}
+
+/// Turns transport and HTTP failures of an `ehttp::fetch` into errors.
+pub fn check_response(response: ehttp::Result<Response>) -> Result<Response, InspectorError> {
+    match response {
+        Ok(response) if response.ok => Ok(response),
+        Ok(response) if throttle::is_throttling(&response) => Err(InspectorError::Throttled {
+            status: response.status,
+        }),
+        Ok(response) => Err(InspectorError::Http {
+            status: response.status,
+            status_text: response.status_text,
+        }),
+        Err(e) if e.to_lowercase().contains("timed out") => Err(InspectorError::Timeout),
+        Err(e) => Err(InspectorError::Transport(e)),
+    }
+}
+
+/// Longest part of a response body kept to show next to an error.
+const RAW_TEXT_LIMIT: usize = 64 * 1024;
+
+/// The status line and body of a response as text, for showing what the game actually
+/// answered.
+pub fn raw_text(response: &Response) -> String {
+    let body = match response.text() {
+        Some(text) if text.len() > RAW_TEXT_LIMIT => {
+            let mut end = RAW_TEXT_LIMIT;
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            format!("{}\n… {} more bytes", &text[..end], text.len() - end)
+        }
+        Some(text) => text.to_owned(),
+        None => format!("<{} bytes of binary data>", response.bytes.len()),
+    };
+    format!("{} {}\n\n{body}", response.status, response.status_text)
+}
+
+/// Parses the result of an `ehttp::fetch`, turning transport and HTTP failures into errors too.
+pub fn parse_response<T>(response: ehttp::Result<Response>) -> Result<T, InspectorError>
+where
+    T: DeserializeOwned,
+{
+    parse(&check_response(response)?)
+}
+
+/// Parses the result of an `ehttp::fetch` to a builtin method into its typed form.
+pub fn parse_result<T: BrpResult>(response: ehttp::Result<Response>) -> Result<T, InspectorError> {
+    T::decode(parse_response(response)?)
+}
+
+/// Parses the response to a batch sent with [`make_batch_request`], returning the result of
+/// every call in the order of `calls`.
+pub fn parse_batch(
+    response: &Response,
+    calls: &[BrpRequest],
+) -> Result<Vec<Result<serde_json::Value, InspectorError>>, InspectorError> {
+    let outputs = match decode(response)? {
+        jsonrpc_types::v2::Response::Batch(outputs) => outputs,
+        // The server answers a batch it can't process with a single error.
+        jsonrpc_types::v2::Response::Single(jsonrpc_types::Output::Failure(e)) => {
+            return Err(e.into());
         }
+        jsonrpc_types::v2::Response::Single(output) => vec![output],
     };
-    match serde_json::from_value(result.result) {
-        Ok(v) => Ok(v),
-        Err(e) => Err(e.to_string()),
+    let mut by_id: HashMap<String, Result<serde_json::Value, InspectorError>> = outputs
+        .into_iter()
+        .map(|output| match output {
+            jsonrpc_types::Output::Success(s) => (id_key(&s.id), Ok(s.result)),
+            jsonrpc_types::Output::Failure(e) => (id_key(&e.id), Err(e.into())),
+        })
+        .collect();
+    Ok(calls
+        .iter()
+        .map(|call| {
+            by_id.remove(&id_key(&call.id)).unwrap_or_else(|| {
+                Err(InspectorError::Decode(format!(
+                    "No response to {}",
+                    call.method
+                )))
+            })
+        })
+        .collect())
+}
+
+/// Request ids compared by their JSON form, they are typed differently on both sides.
+fn id_key(id: &impl Serialize) -> String {
+    serde_json::to_string(id).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signature(request: &ehttp::Request) -> Option<&str> {
+        request.headers.get("X-Signature")
+    }
+
+    #[test]
+    fn endpoints_sign_with_their_own_key() {
+        let first = Endpoint::new("http://a/", "X-Signature", "first");
+        let second = Endpoint::new("http://b/", "X-Signature", "second");
+        let calls = [create_request::<String>(None, "bevy/list")];
+        let signed = make_batch_request(&calls, &first);
+        let other = make_batch_request(&calls, &second);
+        assert_eq!(signed.url, "http://a/");
+        let tag = ring::hmac::sign(
+            &ring::hmac::Key::new(ring::hmac::HMAC_SHA256, b"first"),
+            &signed.body,
+        );
+        let expected: String = tag.as_ref().iter().map(|b| format!("{b:02x}")).collect();
+        assert_eq!(signature(&signed), Some(expected.as_str()));
+        assert_ne!(signature(&signed), signature(&other));
+        // Requests built later from a kept endpoint still use its key.
+        let later = make_batch_request(&calls, &first.clone());
+        assert_eq!(signature(&later), Some(expected.as_str()));
+    }
+
+    #[test]
+    fn empty_secret_leaves_requests_unsigned() {
+        let request = make_empty_request("bevy/list", &Endpoint::new("http://a/", "X-Sig", ""));
+        assert_eq!(request.headers.get("X-Sig"), None);
+        let request = make_empty_request("bevy/list", &Endpoint::unsigned("http://a/"));
+        assert_eq!(signature(&request), None);
+        assert_eq!(request.headers.get("Content-Type"), Some(JSON_CONTENT_TYPE));
+    }
+
+    #[cfg(feature = "msgpack")]
+    #[test]
+    fn msgpack_endpoints_send_and_read_msgpack() {
+        let endpoint = Endpoint::unsigned("http://a:15702/").with_msgpack("http://a:15704/");
+        let request = make_request(serde_json::json!({ "entity": 7 }), "bevy/get", &endpoint);
+        assert_eq!(request.url, "http://a:15704/");
+        assert_eq!(
+            request.headers.get("Content-Type"),
+            Some(MSGPACK_CONTENT_TYPE)
+        );
+        assert_eq!(request.headers.get("Accept"), Some(MSGPACK_CONTENT_TYPE));
+        let sent: serde_json::Value = rmp_serde::from_slice(&request.body).unwrap();
+        assert_eq!(sent["method"], "bevy/get");
+        assert_eq!(sent["params"]["entity"], 7);
+        assert_eq!(body_value(&request.body), Some(sent));
+        // Streams and the fallback go to the JSON endpoint.
+        let json = make_empty_request("bevy/list", &endpoint.json());
+        assert_eq!(json.url, "http://a:15702/");
+        assert_eq!(json.headers.get("Content-Type"), Some(JSON_CONTENT_TYPE));
+
+        let answer = serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": [1.5, "a"] });
+        let response = |content_type: &str, bytes: Vec<u8>| Response {
+            url: String::new(),
+            ok: true,
+            status: 200,
+            status_text: "OK".to_owned(),
+            headers: ehttp::Headers::new(&[("content-type", content_type)]),
+            bytes,
+        };
+        let msgpack = response(
+            MSGPACK_CONTENT_TYPE,
+            rmp_serde::to_vec_named(&answer).unwrap(),
+        );
+        let json = response(JSON_CONTENT_TYPE, serde_json::to_vec(&answer).unwrap());
+        // Servers ignoring the negotiation answer in JSON, which is read all the same.
+        for response in [msgpack, json] {
+            let result: serde_json::Value = parse(&response).unwrap();
+            assert_eq!(result, serde_json::json!([1.5, "a"]));
+        }
     }
 }
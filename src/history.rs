@@ -0,0 +1,46 @@
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::time::Duration;
+
+/// How many executed queries are remembered.
+const HISTORY_CAPACITY: usize = 100;
+
+/// One executed query with what it cost.
+pub struct QueryRecord {
+    pub method: String,
+    pub params: Option<Value>,
+    pub duration: Duration,
+    /// Size of the response body in bytes.
+    pub payload: usize,
+    /// Number of returned rows, or the error the query failed with.
+    pub result: Result<usize, String>,
+}
+
+/// Executed queries, newest first.
+#[derive(Default)]
+pub struct QueryHistory {
+    records: VecDeque<QueryRecord>,
+}
+
+impl QueryHistory {
+    pub fn push(&mut self, record: QueryRecord) {
+        self.records.push_front(record);
+        self.records.truncate(HISTORY_CAPACITY);
+    }
+
+    pub fn records(&self) -> impl Iterator<Item = &QueryRecord> {
+        self.records.iter()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&QueryRecord> {
+        self.records.get(index)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+}
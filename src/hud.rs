@@ -0,0 +1,88 @@
+//! The game HUD, a frameless click-through overlay showing watched values and alerts on
+//! top of a fullscreen game.
+
+use egui::{Color32, Pos2, Vec2, ViewportBuilder, ViewportClass, ViewportId};
+use serde::{Deserialize, Serialize};
+
+const SIZE: Vec2 = Vec2::new(280.0, 180.0);
+/// Distance from the screen edges.
+const MARGIN: f32 = 16.0;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum HudCorner {
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl HudCorner {
+    pub const ALL: [HudCorner; 4] = [
+        HudCorner::TopLeft,
+        HudCorner::TopRight,
+        HudCorner::BottomLeft,
+        HudCorner::BottomRight,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            HudCorner::TopLeft => "Top left",
+            HudCorner::TopRight => "Top right",
+            HudCorner::BottomLeft => "Bottom left",
+            HudCorner::BottomRight => "Bottom right",
+        }
+    }
+
+    fn position(self, monitor: Vec2) -> Pos2 {
+        let left = MARGIN;
+        let right = monitor.x - SIZE.x - MARGIN;
+        let top = MARGIN;
+        let bottom = monitor.y - SIZE.y - MARGIN;
+        match self {
+            HudCorner::TopLeft => Pos2::new(left, top),
+            HudCorner::TopRight => Pos2::new(right, top),
+            HudCorner::BottomLeft => Pos2::new(left, bottom),
+            HudCorner::BottomRight => Pos2::new(right, bottom),
+        }
+    }
+}
+
+/// Shows the overlay in `corner` of the monitor the inspector is on. Backends without
+/// native windows get a plain window inside the main one instead.
+pub fn show_hud(ctx: &egui::Context, corner: HudCorner, add_contents: impl FnOnce(&mut egui::Ui)) {
+    let Some(monitor) = ctx.input(|i| i.viewport().monitor_size) else {
+        ctx.request_repaint();
+        return;
+    };
+    let builder = ViewportBuilder::default()
+        .with_title("Inspector HUD")
+        .with_decorations(false)
+        .with_transparent(true)
+        .with_always_on_top()
+        .with_mouse_passthrough(true)
+        .with_taskbar(false)
+        .with_resizable(false)
+        .with_position(corner.position(monitor))
+        .with_inner_size(SIZE);
+    let mut add_contents = Some(add_contents);
+    ctx.show_viewport_immediate(ViewportId::from_hash_of("hud"), builder, |ctx, class| {
+        let Some(add_contents) = add_contents.take() else {
+            return;
+        };
+        if class == ViewportClass::Embedded {
+            egui::Window::new("HUD")
+                .id(egui::Id::new("hud"))
+                .show(ctx, add_contents);
+            return;
+        }
+        let frame = egui::Frame::none()
+            .fill(Color32::from_black_alpha(170))
+            .rounding(8.0)
+            .inner_margin(10.0);
+        egui::CentralPanel::default().frame(frame).show(ctx, |ui| {
+            ui.visuals_mut().override_text_color = Some(Color32::WHITE);
+            add_contents(ui);
+        });
+    });
+}
@@ -0,0 +1,73 @@
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow, utils::HashMap};
+
+/// Components on fewer entities than this say too little about their companions.
+const MIN_ENTITIES: usize = 3;
+
+/// "Entities with `component` also have `companion`", with the entities where that
+/// doesn't hold.
+pub struct Insight {
+    pub component: String,
+    pub companion: String,
+    /// Entities having `component`.
+    pub total: usize,
+    /// Entities having `component` without `companion`, sorted.
+    pub missing: Vec<Entity>,
+}
+
+impl Insight {
+    pub fn share(&self) -> f32 {
+        (self.total - self.missing.len()) as f32 / self.total as f32
+    }
+}
+
+/// Finds component pairs that appear together on at least `min_share` of the entities
+/// having the first one. Rules with exceptions come first, as those point at entities
+/// probably missing a component.
+pub fn co_occurrence(world: &HashMap<Entity, BrpQueryRow>, min_share: f32) -> Vec<Insight> {
+    let mut counts: HashMap<&str, usize> = HashMap::default();
+    let mut pairs: HashMap<(&str, &str), usize> = HashMap::default();
+    for row in world.values() {
+        for component in row.components.keys() {
+            *counts.entry(component).or_default() += 1;
+            for companion in row.components.keys() {
+                if component != companion {
+                    *pairs.entry((component, companion)).or_default() += 1;
+                }
+            }
+        }
+    }
+    let mut insights: Vec<Insight> = pairs
+        .into_iter()
+        .filter_map(|((component, companion), together)| {
+            let total = counts[component];
+            let share = together as f32 / total as f32;
+            (total >= MIN_ENTITIES && share >= min_share).then_some((component, companion, total))
+        })
+        .map(|(component, companion, total)| {
+            let mut missing: Vec<Entity> = world
+                .iter()
+                .filter(|(_, row)| {
+                    row.components.contains_key(component)
+                        && !row.components.contains_key(companion)
+                })
+                .map(|(entity, _)| *entity)
+                .collect();
+            missing.sort();
+            Insight {
+                component: component.to_owned(),
+                companion: companion.to_owned(),
+                total,
+                missing,
+            }
+        })
+        .collect();
+    insights.sort_by(|a, b| {
+        a.missing
+            .is_empty()
+            .cmp(&b.missing.is_empty())
+            .then(b.share().total_cmp(&a.share()))
+            .then(b.total.cmp(&a.total))
+            .then(a.component.cmp(&b.component))
+    });
+    insights
+}
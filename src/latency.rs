@@ -0,0 +1,115 @@
+//! Round-trip times of a tiny request sent at a steady pace, telling a slow network
+//! apart from a hitching game.
+
+use std::collections::VecDeque;
+use web_time::{Duration, Instant};
+
+use egui::{Color32, Stroke};
+
+pub const PING_INTERVAL: Duration = Duration::from_secs(1);
+/// Samples kept, a few minutes at the ping interval.
+const CAPACITY: usize = 180;
+/// A round trip is a spike when it takes this many times the median, and at least
+/// `SPIKE_MIN` longer.
+const SPIKE_FACTOR: f64 = 3.0;
+const SPIKE_MIN: Duration = Duration::from_millis(20);
+
+#[derive(Default)]
+pub struct LatencyTracker {
+    /// Round-trip times, `None` for pings that got no response.
+    samples: VecDeque<Option<Duration>>,
+    last_ping: Option<Instant>,
+    in_flight: bool,
+}
+
+impl LatencyTracker {
+    /// Whether the next ping is due, marking it as sent then.
+    pub fn start_ping(&mut self) -> bool {
+        let due = self
+            .last_ping
+            .is_none_or(|last| last.elapsed() >= PING_INTERVAL);
+        if self.in_flight || !due {
+            return false;
+        }
+        self.in_flight = true;
+        self.last_ping = Some(Instant::now());
+        true
+    }
+
+    pub fn record(&mut self, round_trip: Option<Duration>) {
+        self.in_flight = false;
+        self.samples.push_back(round_trip);
+        if self.samples.len() > CAPACITY {
+            self.samples.pop_front();
+        }
+    }
+
+    pub fn last(&self) -> Option<Duration> {
+        self.samples.back().copied().flatten()
+    }
+
+    pub fn median(&self) -> Option<Duration> {
+        let mut times: Vec<Duration> = self.samples.iter().flatten().copied().collect();
+        times.sort();
+        times.get(times.len() / 2).copied()
+    }
+
+    fn is_spike(&self, round_trip: Duration, median: Duration) -> bool {
+        round_trip.as_secs_f64() > median.as_secs_f64() * SPIKE_FACTOR
+            && round_trip > median + SPIKE_MIN
+    }
+
+    /// Spikes and lost pings among the kept samples.
+    pub fn spikes(&self) -> usize {
+        let median = self.median().unwrap_or_default();
+        self.samples
+            .iter()
+            .filter(|sample| sample.is_none_or(|time| self.is_spike(time, median)))
+            .count()
+    }
+
+    /// Plot of the round trips, spikes marked red and lost pings as red ticks at the top.
+    pub fn draw(&self, ui: &mut egui::Ui) {
+        let (rect, _) =
+            ui.allocate_exact_size(egui::vec2(ui.available_width(), 60.0), egui::Sense::hover());
+        ui.painter().rect_stroke(
+            rect,
+            0.0,
+            Stroke::new(0.5, Color32::from_gray(90).gamma_multiply(0.5)),
+        );
+        let Some(median) = self.median() else {
+            return;
+        };
+        let max = self
+            .samples
+            .iter()
+            .flatten()
+            .max()
+            .copied()
+            .unwrap_or_default()
+            .max(Duration::from_millis(1));
+        let step = rect.width() / (CAPACITY - 1) as f32;
+        let start = rect.right() - step * (self.samples.len().saturating_sub(1)) as f32;
+        let color = ui.visuals().selection.stroke.color;
+        let spike = ui.visuals().error_fg_color;
+        let mut line = Vec::new();
+        for (index, sample) in self.samples.iter().enumerate() {
+            let x = start + step * index as f32;
+            let Some(time) = sample else {
+                ui.painter().line_segment(
+                    [egui::pos2(x, rect.top()), egui::pos2(x, rect.top() + 6.0)],
+                    Stroke::new(1.5, spike),
+                );
+                continue;
+            };
+            let y = rect.bottom() - (time.as_secs_f32() / max.as_secs_f32()) * rect.height();
+            let point = egui::pos2(x, y);
+            line.push(point);
+            if self.is_spike(*time, median) {
+                ui.painter().circle_filled(point, 2.5, spike);
+            }
+        }
+        ui.painter()
+            .add(egui::Shape::line(line, Stroke::new(1.5, color)));
+    }
+}
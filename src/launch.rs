@@ -0,0 +1,119 @@
+use std::net::{TcpStream, ToSocketAddrs};
+use std::process::{Child, Command};
+use std::sync::{Arc, Mutex};
+use web_time::{Duration, Instant};
+
+/// How long a launched game may take to open its remote port, first builds are slow.
+const LAUNCH_TIMEOUT: Duration = Duration::from_secs(600);
+const PORT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Clone, PartialEq)]
+pub enum LaunchStatus {
+    Idle,
+    /// Waiting for the game to open its port.
+    Starting,
+    /// The port is open, the inspector should connect.
+    Ready,
+    /// The game is running and was connected to.
+    Attached,
+    Failed(String),
+}
+
+/// Starts the game from a profile's command line and waits until it can be attached to.
+pub struct Launcher {
+    child: Arc<Mutex<Option<Child>>>,
+    status: Arc<Mutex<LaunchStatus>>,
+}
+
+impl Default for Launcher {
+    fn default() -> Self {
+        Self {
+            child: Arc::new(Mutex::new(None)),
+            status: Arc::new(Mutex::new(LaunchStatus::Idle)),
+        }
+    }
+}
+
+impl Launcher {
+    /// Runs `command` through the system shell and polls `host:port` in the background.
+    pub fn launch(&self, ctx: &egui::Context, command: &str, host: &str, port: u16) {
+        self.stop();
+        let child = if cfg!(windows) {
+            Command::new("cmd").args(["/C", command]).spawn()
+        } else {
+            Command::new("sh").args(["-c", command]).spawn()
+        };
+        let child = match child {
+            Ok(child) => child,
+            Err(e) => {
+                *self.status.lock().unwrap() = LaunchStatus::Failed(e.to_string());
+                return;
+            }
+        };
+        *self.child.lock().unwrap() = Some(child);
+        *self.status.lock().unwrap() = LaunchStatus::Starting;
+
+        let address = format!("{host}:{port}");
+        let child = self.child.clone();
+        let status = self.status.clone();
+        let egui_ctx = ctx.clone();
+        std::thread::spawn(move || {
+            let started = Instant::now();
+            let result = loop {
+                if *status.lock().unwrap() != LaunchStatus::Starting {
+                    return;
+                }
+                if let Some(child) = child.lock().unwrap().as_mut() {
+                    if let Ok(Some(exit)) = child.try_wait() {
+                        break LaunchStatus::Failed(format!("The game exited with {exit}"));
+                    }
+                }
+                let open = address
+                    .to_socket_addrs()
+                    .into_iter()
+                    .flatten()
+                    .any(|addr| TcpStream::connect_timeout(&addr, PORT_POLL_INTERVAL).is_ok());
+                if open {
+                    break LaunchStatus::Ready;
+                }
+                if started.elapsed() > LAUNCH_TIMEOUT {
+                    break LaunchStatus::Failed(format!("Nothing listened on {address} in time"));
+                }
+                std::thread::sleep(PORT_POLL_INTERVAL);
+            };
+            *status.lock().unwrap() = result;
+            egui_ctx.request_repaint();
+        });
+    }
+
+    pub fn status(&self) -> LaunchStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Returns `true` once after the launched game opened its port.
+    pub fn take_ready(&self) -> bool {
+        let mut status = self.status.lock().unwrap();
+        if *status != LaunchStatus::Ready {
+            return false;
+        }
+        *status = LaunchStatus::Attached;
+        true
+    }
+
+    /// Kills the launched game, if it is still running.
+    pub fn stop(&self) {
+        if let Some(mut child) = self.child.lock().unwrap().take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        *self.status.lock().unwrap() = LaunchStatus::Idle;
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.child
+            .lock()
+            .unwrap()
+            .as_mut()
+            .is_some_and(|child| matches!(child.try_wait(), Ok(None)))
+    }
+}
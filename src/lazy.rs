@@ -0,0 +1,65 @@
+//! Component data fetched entity by entity as it scrolls into view, for worlds too big to
+//! query whole. The regular fetch then only asks for names and the hierarchy.
+
+use std::collections::HashSet;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+use bevy::prelude::Entity;
+use web_time::Duration;
+
+/// Entities fetched in one batch, small enough for the game to answer within a frame.
+pub const CHUNK_SIZE: usize = 100;
+
+/// A full fetch over the configured limits, which switched fetches to the skeleton.
+#[derive(Clone, Copy, Debug)]
+pub struct Oversized {
+    /// Size of the response body in bytes.
+    pub payload: usize,
+    pub duration: Duration,
+}
+
+#[derive(Default)]
+pub struct LazyLoader {
+    /// Entities with all their components fetched since the last skeleton fetch.
+    pub loaded: Arc<Mutex<HashSet<Entity>>>,
+    /// Entities shown in the tree this frame, top to bottom.
+    wanted: Mutex<Vec<Entity>>,
+    pub in_flight: Arc<AtomicBool>,
+    /// Set when a full fetch was too big, fetches load lazily from then on.
+    pub narrowed: Arc<Mutex<Option<Oversized>>>,
+    /// The user chose full fetches despite their size, they aren't narrowed again.
+    pub allow_oversized: bool,
+}
+
+impl LazyLoader {
+    pub fn want(&self, entity: Entity) {
+        self.wanted.lock().unwrap().push(entity);
+    }
+
+    pub fn is_loaded(&self, entity: &Entity) -> bool {
+        self.loaded.lock().unwrap().contains(entity)
+    }
+
+    /// Up to a chunk of wanted entities missing their components, `first` ahead of the
+    /// others. Empty while the previous chunk is still loading.
+    pub fn next_chunk(&self, first: Option<Entity>) -> Vec<Entity> {
+        let wanted = std::mem::take(&mut *self.wanted.lock().unwrap());
+        if self.in_flight.load(Ordering::SeqCst) {
+            return Vec::new();
+        }
+        let loaded = self.loaded.lock().unwrap();
+        let mut chunk = Vec::new();
+        for entity in first.into_iter().chain(wanted) {
+            if !loaded.contains(&entity) && !chunk.contains(&entity) {
+                chunk.push(entity);
+            }
+            if chunk.len() == CHUNK_SIZE {
+                break;
+            }
+        }
+        chunk
+    }
+}
@@ -0,0 +1,142 @@
+//! Checks of the cached world for setups that are valid but almost always a mistake, like
+//! a mesh without a material, which Bevy silently doesn't render.
+
+use std::collections::BTreeMap;
+
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow, utils::HashMap};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::compat::TypePaths;
+use crate::problems::parent_of;
+use crate::renderers::is_bevy_type;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LintRule {
+    TransformWithoutGlobal,
+    UiNodeOutsideRoot,
+    MeshWithoutMaterial,
+    DuplicateCameras,
+}
+
+impl LintRule {
+    pub const ALL: [LintRule; 4] = [
+        LintRule::TransformWithoutGlobal,
+        LintRule::UiNodeOutsideRoot,
+        LintRule::MeshWithoutMaterial,
+        LintRule::DuplicateCameras,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            LintRule::TransformWithoutGlobal => "Transform without GlobalTransform",
+            LintRule::UiNodeOutsideRoot => "UI node below a non-UI entity",
+            LintRule::MeshWithoutMaterial => "Mesh without material",
+            LintRule::DuplicateCameras => "Cameras with the same target and order",
+        }
+    }
+}
+
+pub struct Finding {
+    pub rule: LintRule,
+    pub entity: Entity,
+    pub message: String,
+}
+
+/// Whether `row` has Bevy's component called `name`, fetched or only known to be there.
+fn has(row: &BrpQueryRow, name: &str) -> bool {
+    row.components
+        .keys()
+        .chain(
+            row.has
+                .iter()
+                .filter(|(_, present)| present.as_bool() == Some(true))
+                .map(|(path, _)| path),
+        )
+        .any(|path| is_bevy_type(path, name))
+}
+
+fn component<'a>(row: &'a BrpQueryRow, name: &str) -> Option<&'a Value> {
+    row.components
+        .iter()
+        .find(|(path, _)| is_bevy_type(path, name))
+        .map(|(_, value)| value)
+}
+
+/// Runs the rules not in `disabled` over `world`, findings sorted by entity.
+pub fn lint(
+    world: &HashMap<Entity, BrpQueryRow>,
+    paths: &TypePaths,
+    disabled: &[LintRule],
+) -> Vec<Finding> {
+    let enabled = |rule| !disabled.contains(&rule);
+    let mut findings = Vec::new();
+    let mut cameras: BTreeMap<String, Vec<Entity>> = BTreeMap::new();
+    for (entity, row) in world {
+        let entity = *entity;
+        if enabled(LintRule::TransformWithoutGlobal)
+            && has(row, "Transform")
+            && !has(row, "GlobalTransform")
+        {
+            findings.push(Finding {
+                rule: LintRule::TransformWithoutGlobal,
+                entity,
+                message: format!("{entity} has a Transform but no GlobalTransform, it won't move"),
+            });
+        }
+        if enabled(LintRule::UiNodeOutsideRoot) && has(row, "Node") {
+            let outside = parent_of(row, paths)
+                .filter(|parent| world.get(parent).is_some_and(|row| !has(row, "Node")));
+            if let Some(parent) = outside {
+                findings.push(Finding {
+                    rule: LintRule::UiNodeOutsideRoot,
+                    entity,
+                    message: format!(
+                        "{entity} is a UI node below {parent}, which isn't one, so it is \
+                         laid out as a root of its own"
+                    ),
+                });
+            }
+        }
+        if enabled(LintRule::MeshWithoutMaterial) {
+            for (mesh, material) in [("Mesh3d", "MeshMaterial3d"), ("Mesh2d", "MeshMaterial2d")] {
+                if has(row, mesh) && !has(row, material) {
+                    findings.push(Finding {
+                        rule: LintRule::MeshWithoutMaterial,
+                        entity,
+                        message: format!("{entity} has a {mesh} but no {material}, it isn't drawn"),
+                    });
+                }
+            }
+        }
+        if enabled(LintRule::DuplicateCameras) {
+            if let Some(camera) = component(row, "Camera") {
+                let active = camera.get("is_active").and_then(Value::as_bool) != Some(false);
+                if active {
+                    let key = format!(
+                        "{} {}",
+                        camera.get("target").unwrap_or(&Value::Null),
+                        camera.get("order").unwrap_or(&Value::Null)
+                    );
+                    cameras.entry(key).or_default().push(entity);
+                }
+            }
+        }
+    }
+    for mut same in cameras.into_values().filter(|same| same.len() > 1) {
+        same.sort();
+        let list: Vec<String> = same.iter().map(Entity::to_string).collect();
+        for entity in &same {
+            findings.push(Finding {
+                rule: LintRule::DuplicateCameras,
+                entity: *entity,
+                message: format!(
+                    "Cameras {} render to the same target with the same order",
+                    list.join(", ")
+                ),
+            });
+        }
+    }
+    findings.sort_by_key(|finding| finding.entity);
+    findings
+}
@@ -0,0 +1,235 @@
+//! Finds Bevy games with the remote protocol running on this machine, and optionally ones
+//! on the local network announced by the companion plugin.
+
+use bevy::remote::{builtin_methods::BRP_LIST_METHOD, http::DEFAULT_PORT};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, TcpStream, UdpSocket};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::companion::{Beacon, DISCOVERY_GROUP, DISCOVERY_PORT, DISCOVERY_SERVICE};
+use crate::helper;
+use crate::responses::TypeList;
+use crate::transport;
+
+/// Ports after the default one that are tried too, for several games running at once.
+const EXTRA_DEFAULT_PORTS: u16 = 9;
+const CONNECT_TIMEOUT: Duration = Duration::from_millis(100);
+/// How long to wait for beacons, a bit longer than the interval they are sent at.
+const LISTEN_TIME: Duration = Duration::from_secs(3);
+
+#[derive(Clone, PartialEq)]
+pub enum ProbeStatus {
+    Probing,
+    /// Answered `bevy/list`, with the number of registered component types.
+    Bevy(usize),
+    NotBevy,
+}
+
+#[derive(Clone)]
+pub struct Candidate {
+    pub host: IpAddr,
+    pub port: u16,
+    /// Name of the listening process, only known on Linux.
+    pub process: Option<String>,
+    /// Name and version of the game, for ones announced on the network.
+    pub game: Option<String>,
+    pub status: ProbeStatus,
+}
+
+#[derive(Default)]
+pub struct LocalScan {
+    pub open: bool,
+    candidates: Arc<Mutex<Vec<Candidate>>>,
+    scanning: Arc<Mutex<bool>>,
+    /// Why listening for games on the network failed, e.g. another inspector holding the
+    /// port.
+    lan_error: Arc<Mutex<Option<String>>>,
+}
+
+impl LocalScan {
+    /// Looks for open ports on localhost in the background and asks each of them
+    /// whether it speaks the remote protocol. With `lan`, listens for beacons of games
+    /// on the network afterwards.
+    pub fn start(&self, ctx: &egui::Context, known_ports: Vec<u16>, lan: bool) {
+        if std::mem::replace(&mut *self.scanning.lock().unwrap(), true) {
+            return;
+        }
+        self.candidates.lock().unwrap().clear();
+        *self.lan_error.lock().unwrap() = None;
+        let candidates = self.candidates.clone();
+        let lan_error = self.lan_error.clone();
+        let scanning = self.scanning.clone();
+        let egui_ctx = ctx.clone();
+        std::thread::spawn(move || {
+            let processes = listening_processes();
+            let mut ports: Vec<u16> = (DEFAULT_PORT..=DEFAULT_PORT + EXTRA_DEFAULT_PORTS)
+                .chain(known_ports)
+                .chain(processes.iter().map(|(port, _)| *port))
+                .collect();
+            ports.sort();
+            ports.dedup();
+            for port in ports {
+                let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+                if TcpStream::connect_timeout(&address, CONNECT_TIMEOUT).is_err() {
+                    continue;
+                }
+                let process = processes
+                    .iter()
+                    .find(|(p, _)| *p == port)
+                    .map(|(_, name)| name.clone());
+                candidates.lock().unwrap().push(Candidate {
+                    host: IpAddr::V4(Ipv4Addr::LOCALHOST),
+                    port,
+                    process,
+                    game: None,
+                    status: ProbeStatus::Probing,
+                });
+                probe(
+                    &egui_ctx,
+                    &candidates,
+                    IpAddr::V4(Ipv4Addr::LOCALHOST),
+                    port,
+                );
+            }
+            if lan {
+                if let Err(e) = listen_for_beacons(&egui_ctx, &candidates) {
+                    *lan_error.lock().unwrap() = Some(e.to_string());
+                }
+            }
+            *scanning.lock().unwrap() = false;
+            egui_ctx.request_repaint();
+        });
+    }
+
+    pub fn is_scanning(&self) -> bool {
+        *self.scanning.lock().unwrap()
+    }
+
+    pub fn candidates(&self) -> Vec<Candidate> {
+        self.candidates.lock().unwrap().clone()
+    }
+
+    pub fn lan_error(&self) -> Option<String> {
+        self.lan_error.lock().unwrap().clone()
+    }
+}
+
+/// Adds the games whose beacons arrive within [`LISTEN_TIME`], each probed like the local
+/// ones.
+fn listen_for_beacons(
+    ctx: &egui::Context,
+    candidates: &Arc<Mutex<Vec<Candidate>>>,
+) -> std::io::Result<()> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, DISCOVERY_PORT))?;
+    socket.join_multicast_v4(&DISCOVERY_GROUP, &Ipv4Addr::UNSPECIFIED)?;
+    socket.set_read_timeout(Some(Duration::from_millis(250)))?;
+    let started = Instant::now();
+    let mut buffer = [0; 1024];
+    while started.elapsed() < LISTEN_TIME {
+        let Ok((length, sender)) = socket.recv_from(&mut buffer) else {
+            continue;
+        };
+        let Ok(beacon) = serde_json::from_slice::<Beacon>(&buffer[..length]) else {
+            continue;
+        };
+        let host = sender.ip();
+        let mut list = candidates.lock().unwrap();
+        if beacon.service != DISCOVERY_SERVICE
+            || list.iter().any(|c| c.host == host && c.port == beacon.port)
+        {
+            continue;
+        }
+        let game = format!("{} {}", beacon.name, beacon.version);
+        list.push(Candidate {
+            host,
+            port: beacon.port,
+            process: None,
+            game: Some(game.trim().to_owned()).filter(|game| !game.is_empty()),
+            status: ProbeStatus::Probing,
+        });
+        drop(list);
+        probe(ctx, candidates, host, beacon.port);
+    }
+    Ok(())
+}
+
+fn probe(ctx: &egui::Context, candidates: &Arc<Mutex<Vec<Candidate>>>, host: IpAddr, port: u16) {
+    let url = format!("http://{}/", SocketAddr::new(host, port));
+    let request = helper::make_empty_request(BRP_LIST_METHOD, &helper::Endpoint::unsigned(url));
+    let candidates = candidates.clone();
+    let egui_ctx = ctx.clone();
+    transport::fetch(request, move |response| {
+        let status = match helper::parse_result::<TypeList>(response) {
+            Ok(TypeList(types)) => ProbeStatus::Bevy(types.len()),
+            Err(_) => ProbeStatus::NotBevy,
+        };
+        if let Some(candidate) = candidates
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .find(|c| c.host == host && c.port == port)
+        {
+            candidate.status = status;
+        }
+        egui_ctx.request_repaint();
+    });
+}
+
+/// Listening TCP ports with the name of the process owning them, as far as this user may
+/// see them.
+#[cfg(target_os = "linux")]
+fn listening_processes() -> Vec<(u16, String)> {
+    use bevy::utils::HashMap;
+
+    // Socket inode to port, for sockets in the LISTEN (0A) state.
+    let mut sockets: HashMap<String, u16> = HashMap::default();
+    for table in ["/proc/net/tcp", "/proc/net/tcp6"] {
+        let Ok(text) = std::fs::read_to_string(table) else {
+            continue;
+        };
+        for line in text.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 10 || fields[3] != "0A" {
+                continue;
+            }
+            let port = fields[1]
+                .rsplit(':')
+                .next()
+                .and_then(|port| u16::from_str_radix(port, 16).ok());
+            if let Some(port) = port {
+                sockets.insert(fields[9].to_owned(), port);
+            }
+        }
+    }
+    let mut result = Vec::new();
+    let Ok(processes) = std::fs::read_dir("/proc") else {
+        return result;
+    };
+    for process in processes.flatten() {
+        let Ok(fds) = std::fs::read_dir(process.path().join("fd")) else {
+            continue;
+        };
+        let name = std::fs::read_to_string(process.path().join("comm")).unwrap_or_default();
+        for fd in fds.flatten() {
+            let Ok(target) = std::fs::read_link(fd.path()) else {
+                continue;
+            };
+            let target = target.to_string_lossy();
+            let Some(inode) = target
+                .strip_prefix("socket:[")
+                .and_then(|t| t.strip_suffix(']'))
+            else {
+                continue;
+            };
+            if let Some(port) = sockets.get(inode) {
+                result.push((*port, name.trim().to_owned()));
+            }
+        }
+    }
+    result
+}
+
+#[cfg(not(target_os = "linux"))]
+fn listening_processes() -> Vec<(u16, String)> {
+    Vec::new()
+}
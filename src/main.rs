@@ -1,14 +1,112 @@
-#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
+#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release, the CLI attaches to the shell's
 
+mod a11y;
+mod activity;
+mod alerts;
+mod aliases;
+mod annotations;
 mod app;
+mod appearance;
+mod bookmarks;
+mod capabilities;
+mod category;
+mod changes;
+#[cfg(not(target_arch = "wasm32"))]
+mod cli;
+mod commands;
+mod companion;
+mod compat;
+mod completion;
+mod component_history;
+mod config;
+mod dashboard;
+mod demo;
+mod despawned;
+mod diagnostics;
+mod dock;
+mod edit;
+mod error;
+mod files;
+mod geometry;
+mod graph;
+mod groups;
 mod helper;
+mod history;
+mod hud;
+mod insights;
+mod latency;
+mod launch;
+mod lazy;
+mod lint;
+mod local_scan;
+mod migration;
+mod minimap;
+mod multi_get;
+mod onboarding;
+mod ordering;
+mod palette;
+mod panels;
+mod paste;
+mod performance;
+mod pins;
+mod power;
+mod presets;
+mod problems;
+mod project;
+mod protection;
+mod query_builder;
+mod random;
+mod redaction;
+mod references;
+mod refresh;
+mod renderers;
+mod resources;
+mod responses;
+mod rpc_log;
+mod scene;
+mod scheduler;
+mod schema;
+mod search;
+mod spatial;
+mod spawn;
+mod staging;
+mod summary;
+mod throttle;
+mod timeline;
+mod toolbar;
+mod tour;
+mod trace;
+mod transform;
+mod transport;
+mod trash;
+mod tree;
+mod type_catalog;
+mod undo;
+mod updates;
+mod value_format;
+mod viewports;
+mod watch;
+mod widgets;
 
+#[cfg(not(target_arch = "wasm32"))]
 fn main() -> eframe::Result {
     // env_logger::init(); // Log to stderr (if you run with `RUST_LOG=debug`).
 
+    // `query`, `spawn` and the other commands run a single request and exit, see `cli.rs`.
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = crate::cli::run(&args) {
+        std::process::exit(code);
+    }
+
+    // `--demo` serves a canned world from a built-in mock server instead of a game.
+    let demo = std::env::args()
+        .any(|arg| arg == "--demo")
+        .then(|| crate::demo::start().expect("Unable to start the demo server"));
+
     let native_options = eframe::NativeOptions {
         viewport: egui::ViewportBuilder::default()
             .with_decorations(false)
+            .with_resizable(true)
             .with_transparent(true)
             .with_inner_size([400.0, 300.0])
             .with_min_inner_size([300.0, 220.0]), // .with_icon(
@@ -16,11 +114,37 @@ fn main() -> eframe::Result {
         //     eframe::icon_data::from_png_bytes(&include_bytes!("../assets/icon-256.png")[..])
         //         .expect("Failed to load icon"),
         // )
+        // The app stores the geometry itself, see `geometry.rs`.
+        persist_window: false,
         ..Default::default()
     };
     eframe::run_native(
         "Bevy inspector",
         native_options,
-        Box::new(|cc| Ok(Box::new(crate::app::TemplateApp::new(cc)))),
+        Box::new(|cc| Ok(Box::new(crate::app::TemplateApp::new(cc, demo)))),
     )
 }
+
+/// In the browser the inspector draws into the `inspector_canvas` element of
+/// `index.html`, built with `trunk build --release`.
+#[cfg(target_arch = "wasm32")]
+fn main() {
+    use eframe::wasm_bindgen::JsCast as _;
+
+    wasm_bindgen_futures::spawn_local(async {
+        let canvas = web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.get_element_by_id("inspector_canvas"))
+            .expect("No inspector_canvas element in the page")
+            .dyn_into::<web_sys::HtmlCanvasElement>()
+            .expect("inspector_canvas is not a canvas");
+        eframe::WebRunner::new()
+            .start(
+                canvas,
+                eframe::WebOptions::default(),
+                Box::new(|cc| Ok(Box::new(crate::app::TemplateApp::new(cc, None)))),
+            )
+            .await
+            .expect("Unable to start the inspector");
+    });
+}
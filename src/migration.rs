@@ -0,0 +1,166 @@
+//! Moves saved type paths to new ones after the game renamed or moved types, e.g. with a
+//! Bevy upgrade. Works on the serialized settings, so every place a path is saved in is
+//! covered without listing them.
+
+use std::collections::{BTreeSet, HashSet};
+
+use serde_json::{Map, Value};
+
+use crate::commands::fuzzy_score;
+use crate::config::AppConfig;
+use crate::summary::short_name;
+
+/// Below this a fuzzy match of short names is more likely a different type.
+const MIN_SCORE: i32 = 8;
+
+pub struct Rename {
+    pub from: String,
+    /// Empty while no new path was picked.
+    pub to: String,
+    pub apply: bool,
+}
+
+/// Saved type paths the game doesn't know, each with the registered one it most likely
+/// became.
+#[derive(Default)]
+pub struct Migration {
+    pub renames: Vec<Rename>,
+}
+
+impl Migration {
+    /// `None` when every saved path is known or ignored.
+    pub fn find(config: &AppConfig, known: &HashSet<String>) -> Option<Self> {
+        let Ok(saved) = serde_json::to_value(config) else {
+            return None;
+        };
+        let mut paths = BTreeSet::new();
+        collect_type_paths(&saved, &mut paths);
+        let renames: Vec<Rename> = paths
+            .into_iter()
+            .filter(|path| !known.contains(path) && !config.ignored_type_paths.contains(path))
+            .map(|from| {
+                let to = suggest(&from, known).unwrap_or_default();
+                Rename {
+                    apply: !to.is_empty(),
+                    from,
+                    to,
+                }
+            })
+            .collect();
+        (!renames.is_empty()).then_some(Self { renames })
+    }
+
+    pub fn has_suggestions(&self) -> bool {
+        self.renames.iter().any(|rename| !rename.to.is_empty())
+    }
+
+    /// `config` with the checked renames applied everywhere a path is saved.
+    pub fn apply(&self, config: &AppConfig) -> AppConfig {
+        let renames: Vec<(&str, &str)> = self
+            .renames
+            .iter()
+            .filter(|rename| rename.apply && !rename.to.is_empty())
+            .map(|rename| (rename.from.as_str(), rename.to.as_str()))
+            .collect();
+        let Ok(mut saved) = serde_json::to_value(config) else {
+            return config.clone();
+        };
+        rewrite(&mut saved, &renames);
+        serde_json::from_value(saved).unwrap_or_else(|_| config.clone())
+    }
+}
+
+/// The registered type most likely meant by `old`: one with the same short name, closest
+/// in path if there are several, else the best fuzzy match of short names.
+fn suggest(old: &str, known: &HashSet<String>) -> Option<String> {
+    let old_short = short_name(old);
+    let segments: HashSet<&str> = old.split("::").collect();
+    let same_name = known
+        .iter()
+        .filter(|path| short_name(path) == old_short)
+        .max_by_key(|path| {
+            let shared = path.split("::").filter(|s| segments.contains(s)).count();
+            (shared, std::cmp::Reverse(path.len()))
+        });
+    if let Some(path) = same_name {
+        return Some(path.clone());
+    }
+    known
+        .iter()
+        .filter_map(|path| Some((fuzzy_score(old_short, short_name(path))?, path)))
+        .filter(|(score, _)| *score >= MIN_SCORE)
+        .max_by_key(|(score, path)| (*score, std::cmp::Reverse(path.len())))
+        .map(|(_, path)| path.clone())
+}
+
+/// A whole string that looks like a type path, e.g. `my_game::Health` or
+/// `bevy_asset::handle::Handle<bevy_image::image::Image>`.
+fn is_type_path(text: &str) -> bool {
+    let base = text.split('<').next().unwrap_or(text);
+    base.contains("::")
+        && !text.contains(char::is_whitespace)
+        && base
+            .split("::")
+            .all(|s| !s.is_empty() && s.chars().all(|c| c.is_alphanumeric() || c == '_'))
+}
+
+fn collect_type_paths(value: &Value, out: &mut BTreeSet<String>) {
+    match value {
+        Value::String(text) if is_type_path(text) => {
+            out.insert(text.clone());
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_type_paths(item, out)),
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                if is_type_path(key) {
+                    out.insert(key.clone());
+                }
+                collect_type_paths(value, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn rewrite(value: &mut Value, renames: &[(&str, &str)]) {
+    match value {
+        Value::String(text) => *text = rename_in(text, renames),
+        Value::Array(items) => items.iter_mut().for_each(|item| rewrite(item, renames)),
+        Value::Object(fields) => {
+            let renamed: Map<String, Value> = std::mem::take(fields)
+                .into_iter()
+                .map(|(key, mut value)| {
+                    rewrite(&mut value, renames);
+                    (rename_in(&key, renames), value)
+                })
+                .collect();
+            *fields = renamed;
+        }
+        _ => {}
+    }
+}
+
+/// Replaces whole paths only, so `my_game::Health` doesn't turn `my_game::HealthBar`
+/// into something else. Paths inside templates and generics are replaced too.
+fn rename_in(text: &str, renames: &[(&str, &str)]) -> String {
+    let is_path_char = |c: char| c.is_alphanumeric() || c == '_' || c == ':';
+    let mut text = text.to_owned();
+    for (from, to) in renames {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text.as_str();
+        while let Some(index) = rest.find(from) {
+            let before = rest[..index].chars().next_back();
+            let after = rest[index + from.len()..].chars().next();
+            result.push_str(&rest[..index]);
+            if before.is_some_and(is_path_char) || after.is_some_and(is_path_char) {
+                result.push_str(from);
+            } else {
+                result.push_str(to);
+            }
+            rest = &rest[index + from.len()..];
+        }
+        result.push_str(rest);
+        text = result;
+    }
+    text
+}
@@ -0,0 +1,127 @@
+use bevy::prelude::Entity;
+use egui::{Color32, Pos2, Rect, Sense, Stroke, Vec2};
+
+use crate::viewports::show_panel;
+
+const POINT_RADIUS: f32 = 3.0;
+const PICK_RADIUS: f32 = 6.0;
+
+pub struct MinimapPoint {
+    pub entity: Entity,
+    pub position: [f32; 3],
+    pub color: Color32,
+    pub label: String,
+}
+
+/// Top-down scatter plot of entity world positions, X to the right and Z downwards.
+pub struct Minimap {
+    pub open: bool,
+    /// World X/Z coordinates shown in the middle of the plot.
+    center: Vec2,
+    /// Screen points per world unit.
+    zoom: f32,
+    /// Set until the view was fitted to the entities once.
+    needs_fit: bool,
+}
+
+impl Default for Minimap {
+    fn default() -> Self {
+        Self {
+            open: false,
+            center: Vec2::ZERO,
+            zoom: 10.0,
+            needs_fit: true,
+        }
+    }
+}
+
+impl Minimap {
+    /// Shows the minimap window, returns the entity clicked in it and whether the
+    /// window asked to be detached or attached again.
+    pub fn show(
+        &mut self,
+        ctx: &egui::Context,
+        points: &[MinimapPoint],
+        selected: Option<Entity>,
+        detached: bool,
+    ) -> (Option<Entity>, bool) {
+        let mut clicked = None;
+        let mut open = self.open;
+        let toggled = show_panel(ctx, "minimap", "Minimap", &mut open, detached, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Fit").clicked() {
+                    self.needs_fit = true;
+                }
+                ui.label(format!("{} entities with a position", points.len()));
+            });
+            let (response, painter) = ui.allocate_painter(
+                ui.available_size().max(Vec2::splat(100.0)),
+                Sense::click_and_drag(),
+            );
+            let rect = response.rect;
+            if std::mem::take(&mut self.needs_fit) {
+                self.fit(points, rect);
+            }
+            if response.dragged() {
+                self.center -= response.drag_delta() / self.zoom;
+            }
+            if response.hovered() {
+                let scroll = ui.input(|i| i.smooth_scroll_delta.y);
+                self.zoom = (self.zoom * (scroll * 0.005).exp()).clamp(0.001, 10_000.0);
+            }
+
+            painter.rect_filled(rect, 0.0, ui.visuals().extreme_bg_color);
+            let to_screen = |position: &[f32; 3]| {
+                rect.center() + (Vec2::new(position[0], position[2]) - self.center) * self.zoom
+            };
+            let pointer = response.hover_pos();
+            let mut hovered: Option<(&MinimapPoint, f32)> = None;
+            for point in points {
+                let pos = to_screen(&point.position);
+                if !rect.contains(pos) {
+                    continue;
+                }
+                painter.circle_filled(pos, POINT_RADIUS, point.color);
+                if selected == Some(point.entity) {
+                    painter.circle_stroke(
+                        pos,
+                        POINT_RADIUS + 3.0,
+                        Stroke::new(1.5, ui.visuals().strong_text_color()),
+                    );
+                }
+                if let Some(distance) = pointer.map(|p| p.distance(pos)) {
+                    if distance < PICK_RADIUS && hovered.is_none_or(|(_, d)| distance < d) {
+                        hovered = Some((point, distance));
+                    }
+                }
+            }
+            if let Some((point, _)) = hovered {
+                if response.clicked() {
+                    clicked = Some(point.entity);
+                }
+                response.on_hover_text_at_pointer(&point.label);
+            }
+        });
+        self.open = open;
+        (clicked, toggled)
+    }
+
+    /// Centers the view on the points and zooms so all of them are visible.
+    fn fit(&mut self, points: &[MinimapPoint], rect: Rect) {
+        let Some(first) = points.first() else {
+            return;
+        };
+        let start = Pos2::new(first.position[0], first.position[2]);
+        let bounds = points
+            .iter()
+            .fold(Rect::from_min_max(start, start), |bounds, p| {
+                bounds.union(Rect::from_min_max(
+                    Pos2::new(p.position[0], p.position[2]),
+                    Pos2::new(p.position[0], p.position[2]),
+                ))
+            });
+        self.center = bounds.center().to_vec2();
+        let size = bounds.size().max(Vec2::splat(1.0));
+        self.zoom = ((rect.width() - 20.0) / size.x).min((rect.height() - 20.0) / size.y);
+    }
+}
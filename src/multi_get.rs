@@ -0,0 +1,147 @@
+//! Many `bevy/get` calls split into batch requests, a bounded number of them in flight at
+//! once, joined back into one result per call.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use bevy::{
+    prelude::Entity,
+    remote::{
+        builtin_methods::{BrpGetParams, BRP_GET_METHOD},
+        BrpRequest,
+    },
+};
+use serde_json::Value;
+
+use crate::error::InspectorError;
+use crate::helper::{self, Endpoint};
+use crate::transport;
+
+/// Calls per batch request, small enough for the game to answer within a frame.
+const BATCH_SIZE: usize = 50;
+
+/// `bevy/get` of `components` of `entity`, skipping the ones it doesn't have.
+pub fn get_call(entity: Entity, components: &[String]) -> BrpRequest {
+    helper::create_request(
+        Some(BrpGetParams {
+            entity,
+            components: components.to_vec(),
+            strict: false,
+        }),
+        BRP_GET_METHOD,
+    )
+}
+
+/// What came back for the calls of [`get_many`].
+pub struct Joined {
+    /// One per call, in the order given, `None` for calls of a batch that failed as a
+    /// whole.
+    pub results: Vec<Option<Result<Value, InspectorError>>>,
+    /// Errors of whole batches, e.g. timeouts.
+    pub batch_errors: Vec<InspectorError>,
+    batches: usize,
+}
+
+impl Joined {
+    /// The error when not a single batch got through.
+    pub fn total_failure(&self) -> Option<&InspectorError> {
+        self.batch_errors
+            .first()
+            .filter(|_| self.batch_errors.len() == self.batches)
+    }
+
+    /// The first batch error when only some of the batches failed, with how many did.
+    pub fn partial_failure(&self) -> Option<InspectorError> {
+        let error = self.batch_errors.first()?;
+        if self.batch_errors.len() == self.batches {
+            return None;
+        }
+        let failed = self.batch_errors.len();
+        Some(error.clone().context(format!(
+            "{failed} of {} batch requests failed",
+            self.batches
+        )))
+    }
+}
+
+struct Join {
+    queue: VecDeque<(usize, Vec<BrpRequest>)>,
+    results: Vec<Option<Result<Value, InspectorError>>>,
+    batch_errors: Vec<InspectorError>,
+    batches: usize,
+    remaining: usize,
+    on_done: Option<Box<dyn FnOnce(Joined) + Send>>,
+}
+
+/// Sends `calls` in batches with at most `max_in_flight` requests at a time and calls
+/// `on_done` once every batch answered or failed.
+pub fn get_many(
+    url: Endpoint,
+    calls: Vec<BrpRequest>,
+    max_in_flight: usize,
+    on_done: impl 'static + Send + FnOnce(Joined),
+) {
+    let queue: VecDeque<(usize, Vec<BrpRequest>)> = calls
+        .chunks(BATCH_SIZE)
+        .enumerate()
+        .map(|(index, chunk)| (index * BATCH_SIZE, chunk.to_vec()))
+        .collect();
+    let batches = queue.len();
+    if batches == 0 {
+        on_done(Joined {
+            results: Vec::new(),
+            batch_errors: Vec::new(),
+            batches,
+        });
+        return;
+    }
+    let join = Arc::new(Mutex::new(Join {
+        queue,
+        results: (0..calls.len()).map(|_| None).collect(),
+        batch_errors: Vec::new(),
+        batches,
+        remaining: batches,
+        on_done: Some(Box::new(on_done)),
+    }));
+    for _ in 0..max_in_flight.clamp(1, batches) {
+        send_next(&join, &url);
+    }
+}
+
+fn send_next(join: &Arc<Mutex<Join>>, url: &Endpoint) {
+    let Some((offset, calls)) = join.lock().unwrap().queue.pop_front() else {
+        return;
+    };
+    let request = helper::make_batch_request(&calls, url);
+    let join = join.clone();
+    let url = url.clone();
+    transport::fetch(request, move |response| {
+        let results = helper::check_response(response)
+            .and_then(|response| helper::parse_batch(&response, &calls));
+        let mut state = join.lock().unwrap();
+        match results {
+            Ok(results) => {
+                for (index, result) in results.into_iter().enumerate() {
+                    state.results[offset + index] = Some(result);
+                }
+            }
+            Err(e) => state.batch_errors.push(e),
+        }
+        state.remaining -= 1;
+        if state.remaining > 0 {
+            drop(state);
+            send_next(&join, &url);
+            return;
+        }
+        let joined = Joined {
+            results: std::mem::take(&mut state.results),
+            batch_errors: std::mem::take(&mut state.batch_errors),
+            batches: state.batches,
+        };
+        let on_done = state.on_done.take();
+        drop(state);
+        if let Some(on_done) = on_done {
+            on_done(joined);
+        }
+    });
+}
@@ -0,0 +1,124 @@
+use egui::RichText;
+
+use crate::config::AppConfig;
+use crate::palette::status_colors;
+use crate::power;
+
+const REMOTE_PLUGIN_SNIPPET: &str = "use bevy::prelude::*;
+use bevy::remote::{http::RemoteHttpPlugin, RemotePlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugins(RemotePlugin::default())
+        .add_plugins(RemoteHttpPlugin::default())
+        .run();
+}";
+
+const REPOSITORY_URL: &str = "https://github.com/Leinnan/bevy_remote_inspector";
+
+pub enum ConnectionStatus {
+    Untested,
+    Testing,
+    /// Connected, holds the number of registered component types.
+    Connected(usize),
+    Failed(String),
+}
+
+impl ConnectionStatus {
+    pub fn draw(&self, ui: &mut egui::Ui) {
+        match self {
+            ConnectionStatus::Untested => {}
+            ConnectionStatus::Testing => {
+                power::spinner(ui);
+            }
+            ConnectionStatus::Connected(types) => {
+                ui.label(
+                    RichText::new(format!("Connected, {types} component types registered"))
+                        .color(status_colors(ui.ctx()).ok),
+                );
+            }
+            ConnectionStatus::Failed(error) => {
+                ui.label(RichText::new("Connection failed").color(status_colors(ui.ctx()).error))
+                    .on_hover_text(error);
+            }
+        }
+    }
+}
+
+pub enum OnboardingAction {
+    None,
+    TestConnection,
+    FindLocalGames,
+    Finish,
+}
+
+/// Guided setup shown on first launch and whenever the connection to the game fails.
+pub fn draw_onboarding(
+    ui: &mut egui::Ui,
+    config: &mut AppConfig,
+    status: ConnectionStatus,
+) -> OnboardingAction {
+    let mut action = OnboardingAction::None;
+    ui.heading("Connect to a Bevy app");
+    ui.add_space(8.0);
+
+    ui.label(RichText::new("1. Enable the remote protocol in your game").strong());
+    ui.label("Enable the `bevy_remote` feature of Bevy and add the remote plugins:");
+    let mut snippet = REMOTE_PLUGIN_SNIPPET;
+    ui.add(
+        egui::TextEdit::multiline(&mut snippet)
+            .code_editor()
+            .desired_width(f32::INFINITY),
+    );
+    ui.add_space(8.0);
+
+    ui.label(RichText::new("2. Point the inspector at it").strong());
+    ui.horizontal(|ui| {
+        ui.label("Host");
+        ui.text_edit_singleline(&mut config.host);
+        ui.label("Port");
+        ui.add(egui::DragValue::new(&mut config.port));
+        ui.checkbox(&mut config.websocket, "WebSocket")
+            .on_hover_text("For games exposing the remote protocol over a WebSocket");
+    });
+    ui.horizontal(|ui| {
+        let testing = matches!(status, ConnectionStatus::Testing);
+        if ui
+            .add_enabled(!testing, egui::Button::new("Test connection"))
+            .clicked()
+        {
+            action = OnboardingAction::TestConnection;
+        }
+        if ui
+            .button("Find running games")
+            .on_hover_text("Look for games with the remote protocol on this machine")
+            .clicked()
+        {
+            action = OnboardingAction::FindLocalGames;
+        }
+        status.draw(ui);
+    });
+    ui.add_space(8.0);
+
+    ui.label(RichText::new("3. Optional: add the companion plugin").strong());
+    ui.horizontal_wrapped(|ui| {
+        ui.label(
+            "Pausing, frame stepping and other extras need `InspectorPlugin` from the \
+             `bevy_remote_inspector_plugin` crate, added after `RemotePlugin`, see",
+        );
+        ui.hyperlink_to("the project page", REPOSITORY_URL);
+    });
+    ui.add_space(15.0);
+
+    if ui
+        .add_enabled(
+            matches!(status, ConnectionStatus::Connected(_)),
+            egui::Button::new("Start inspecting"),
+        )
+        .clicked()
+    {
+        action = OnboardingAction::Finish;
+    }
+    action
+}
@@ -0,0 +1,74 @@
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+use crate::compat::TypePaths;
+
+/// Order of the entities in the tree.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum EntitySort {
+    /// Depth-first along the hierarchy, siblings in the order of their `Children`.
+    #[default]
+    Hierarchy,
+    Name,
+    Id,
+}
+
+impl EntitySort {
+    pub const ALL: [EntitySort; 3] = [EntitySort::Hierarchy, EntitySort::Name, EntitySort::Id];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            EntitySort::Hierarchy => "Hierarchy",
+            EntitySort::Name => "Name",
+            EntitySort::Id => "Id",
+        }
+    }
+}
+
+/// Children of an entity in the order of its `Children` component.
+pub fn children(row: &BrpQueryRow, paths: &TypePaths) -> Vec<Entity> {
+    row.components
+        .get(paths.children)
+        .and_then(|children| children.as_array())
+        .map(|array| {
+            array
+                .iter()
+                .filter_map(|v| v.as_u64())
+                .map(Entity::from_bits)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Stable depth-first position of every entity: roots by id, then each entity's children
+/// in their `Children` order. Entities the walk doesn't reach, e.g. with a parent that
+/// wasn't fetched, come last by id.
+pub fn hierarchy_order(
+    world: &HashMap<Entity, BrpQueryRow>,
+    paths: &TypePaths,
+) -> HashMap<Entity, usize> {
+    let mut sorted: Vec<Entity> = world.keys().copied().collect();
+    sorted.sort();
+    let mut order = HashMap::default();
+    let mut stack: Vec<Entity> = sorted
+        .iter()
+        .rev()
+        .filter(|e| !world[*e].components.contains_key(paths.parent))
+        .copied()
+        .collect();
+    while let Some(entity) = stack.pop() {
+        if order.contains_key(&entity) {
+            continue;
+        }
+        order.insert(entity, order.len());
+        if let Some(row) = world.get(&entity) {
+            stack.extend(children(row, paths).into_iter().rev());
+        }
+    }
+    for entity in sorted {
+        if !order.contains_key(&entity) {
+            order.insert(entity, order.len());
+        }
+    }
+    order
+}
@@ -0,0 +1,87 @@
+use egui::Color32;
+use serde::{Deserialize, Serialize};
+
+/// Set of status colors, selectable for people who can't tell red from green.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum StatusPalette {
+    #[default]
+    Default,
+    /// Blue and orange instead of green and red, for deuteranopia and protanopia.
+    RedGreenSafe,
+    /// Teal and red, avoiding blue/yellow pairs, for tritanopia.
+    BlueYellowSafe,
+    HighContrast,
+}
+
+/// Colors for success, warnings, errors and highlighted changes.
+#[derive(Clone, Copy)]
+pub struct StatusColors {
+    pub ok: Color32,
+    pub warning: Color32,
+    pub error: Color32,
+    pub change: Color32,
+}
+
+impl StatusPalette {
+    pub const ALL: [StatusPalette; 4] = [
+        StatusPalette::Default,
+        StatusPalette::RedGreenSafe,
+        StatusPalette::BlueYellowSafe,
+        StatusPalette::HighContrast,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StatusPalette::Default => "Default",
+            StatusPalette::RedGreenSafe => "Red-green safe",
+            StatusPalette::BlueYellowSafe => "Blue-yellow safe",
+            StatusPalette::HighContrast => "High contrast",
+        }
+    }
+
+    pub fn colors(self) -> StatusColors {
+        match self {
+            StatusPalette::Default => StatusColors {
+                ok: Color32::GREEN,
+                warning: Color32::ORANGE,
+                error: Color32::RED,
+                change: Color32::from_rgb(230, 102, 1),
+            },
+            // Okabe-Ito colors.
+            StatusPalette::RedGreenSafe => StatusColors {
+                ok: Color32::from_rgb(86, 180, 233),
+                warning: Color32::from_rgb(240, 228, 66),
+                error: Color32::from_rgb(213, 94, 0),
+                change: Color32::from_rgb(204, 121, 167),
+            },
+            StatusPalette::BlueYellowSafe => StatusColors {
+                ok: Color32::from_rgb(0, 170, 170),
+                warning: Color32::from_rgb(255, 130, 170),
+                error: Color32::from_rgb(220, 30, 40),
+                change: Color32::from_rgb(180, 100, 220),
+            },
+            StatusPalette::HighContrast => StatusColors {
+                ok: Color32::from_rgb(0, 200, 255),
+                warning: Color32::YELLOW,
+                error: Color32::from_rgb(255, 60, 60),
+                change: Color32::from_rgb(255, 0, 255),
+            },
+        }
+    }
+}
+
+fn palette_id() -> egui::Id {
+    egui::Id::new("status_palette")
+}
+
+/// Makes `palette` the one returned by [`status_colors`], called once per frame.
+pub fn set_palette(ctx: &egui::Context, palette: StatusPalette) {
+    ctx.data_mut(|d| d.insert_temp(palette_id(), palette));
+}
+
+/// Status colors of the palette chosen in the settings.
+pub fn status_colors(ctx: &egui::Context) -> StatusColors {
+    ctx.data(|d| d.get_temp::<StatusPalette>(palette_id()))
+        .unwrap_or_default()
+        .colors()
+}
@@ -0,0 +1,84 @@
+use egui::RichText;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A panel advertised by the companion plugin. The inspector calls `method` without
+/// params and renders whatever it returns, using `schema` (JSON schema) for titles
+/// and descriptions.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct PanelDescriptor {
+    pub name: String,
+    #[serde(default)]
+    pub schema: Value,
+    pub method: String,
+}
+
+pub struct CustomPanel {
+    pub descriptor: PanelDescriptor,
+    pub open: bool,
+    pub loading: bool,
+    pub data: Option<Value>,
+    pub error: Option<String>,
+}
+
+impl CustomPanel {
+    pub fn new(descriptor: PanelDescriptor) -> Self {
+        Self {
+            descriptor,
+            open: false,
+            loading: false,
+            data: None,
+            error: None,
+        }
+    }
+}
+
+/// Renders arbitrary panel data as a tree, labelling fields with the schema's `title`
+/// and showing its `description` on hover.
+pub fn draw_value(ui: &mut egui::Ui, schema: &Value, value: &Value) {
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                let field_schema = &schema["properties"][key];
+                draw_field(ui, key, field_schema, field);
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                draw_field(ui, &index.to_string(), &schema["items"], item);
+            }
+        }
+        _ => {
+            ui.label(scalar_text(value));
+        }
+    }
+}
+
+fn draw_field(ui: &mut egui::Ui, key: &str, schema: &Value, value: &Value) {
+    let title = schema["title"].as_str().unwrap_or(key);
+    let response = match value {
+        Value::Object(_) | Value::Array(_) => {
+            egui::CollapsingHeader::new(title)
+                .id_salt(key)
+                .show(ui, |ui| draw_value(ui, schema, value))
+                .header_response
+        }
+        _ => {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(title).strong());
+                ui.label(scalar_text(value));
+            })
+            .response
+        }
+    };
+    if let Some(description) = schema["description"].as_str() {
+        response.on_hover_text(description);
+    }
+}
+
+fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
@@ -0,0 +1,45 @@
+//! Entities pasted as JSON, e.g. copied with "Copy entity JSON" from an inspector
+//! connected to another game.
+
+use bevy::remote::builtin_methods::BrpSpawnParams;
+use serde_json::Value;
+
+use crate::compat::TypePaths;
+
+/// Reads spawn params from pasted text. Accepts what "Copy entity JSON" produces, a bare
+/// map of component type paths to values, or a list of either. Hierarchy components are
+/// dropped since the entities they point at belong to the other game.
+pub fn parse_entities(text: &str, paths: &TypePaths) -> Result<Vec<BrpSpawnParams>, String> {
+    let value: Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    let items = match value {
+        Value::Array(items) => items,
+        item => vec![item],
+    };
+    if items.is_empty() {
+        return Err("The list is empty".to_owned());
+    }
+    items
+        .into_iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let components = match item {
+                Value::Object(mut map) if map.len() == 1 && map.contains_key("components") => {
+                    map.remove("components").unwrap_or_default()
+                }
+                item => item,
+            };
+            let Value::Object(components) = components else {
+                return Err(format!("Entity {index} is not a component map"));
+            };
+            if let Some(key) = components.keys().find(|key| !key.contains("::")) {
+                return Err(format!("`{key}` of entity {index} is not a type path"));
+            }
+            Ok(BrpSpawnParams {
+                components: components
+                    .into_iter()
+                    .filter(|(key, _)| key != paths.parent && key != paths.children)
+                    .collect(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,357 @@
+//! FPS, frame time and entity count of the game over time, and what rendering costs, with
+//! the spawns, destroys and edits done from the inspector marked on the same time axis.
+
+use std::collections::VecDeque;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+use web_time::Instant;
+
+use egui_plot::{Legend, Line, Plot, PlotPoints, VLine};
+use serde::Deserialize;
+
+use crate::{
+    companion::{INSPECTOR_DIAGNOSTICS_METHOD, INSPECTOR_RENDER_STATS_METHOD},
+    helper::{self, Endpoint},
+    transport,
+};
+
+/// Samples kept, ten minutes at the default poll interval of a second.
+const CAPACITY: usize = 600;
+
+/// Paths of Bevy's `FrameTimeDiagnosticsPlugin` and `EntityCountDiagnosticsPlugin`.
+const FPS_PATH: &str = "fps";
+const FRAME_TIME_PATH: &str = "frame_time";
+const ENTITY_COUNT_PATH: &str = "entity_count";
+/// Not measured by Bevy itself, games counting their draw calls can report them here.
+const DRAW_CALLS_PATH: &str = "render/draw_calls";
+/// Suffix of the GPU time of each pass measured by Bevy's `RenderDiagnosticsPlugin`.
+const GPU_TIME_SUFFIX: &str = "/elapsed_gpu";
+
+/// One measurement as sent by the companion plugin.
+#[derive(Deserialize)]
+struct Measurement {
+    path: String,
+    value: Option<f64>,
+    smoothed: Option<f64>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct Sample {
+    /// Seconds since the monitor started.
+    at: f64,
+    fps: Option<f64>,
+    /// In milliseconds.
+    frame_time: Option<f64>,
+    entities: Option<f64>,
+}
+
+/// What the companion plugin's `inspector/render_stats` answers.
+#[derive(Deserialize)]
+struct RenderStats {
+    visible_entities: f64,
+    render_entities: f64,
+    diagnostics: Vec<Measurement>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct RenderSample {
+    at: f64,
+    visible: Option<f64>,
+    render_entities: Option<f64>,
+    draw_calls: Option<f64>,
+    /// Sum of the top level passes, in milliseconds.
+    gpu_time: Option<f64>,
+}
+
+/// Title, plot id and value of each plot.
+type Series<T> = (&'static str, &'static str, fn(&T) -> Option<f64>);
+
+const SERIES: [Series<Sample>; 3] = [
+    ("FPS", "fps", |s| s.fps),
+    ("Frame time (ms)", "frame_time", |s| s.frame_time),
+    ("Entities", "entities", |s| s.entities),
+];
+
+const RENDER_SERIES: [Series<RenderSample>; 4] = [
+    ("Visible entities", "visible", |s| s.visible),
+    ("Render world entities", "render_entities", |s| {
+        s.render_entities
+    }),
+    ("Draw calls", "draw_calls", |s| s.draw_calls),
+    ("GPU time (ms)", "gpu_time", |s| s.gpu_time),
+];
+
+/// Something done from the inspector, drawn as a line across the plots.
+struct Marker {
+    at: f64,
+    label: String,
+}
+
+pub struct PerformanceMonitor {
+    started: Instant,
+    samples: Arc<Mutex<VecDeque<Sample>>>,
+    render_samples: Arc<Mutex<VecDeque<RenderSample>>>,
+    /// GPU time of each pass in the last render sample, by path.
+    passes: Arc<Mutex<Vec<(String, f64)>>>,
+    markers: Mutex<Vec<Marker>>,
+    in_flight: Arc<AtomicBool>,
+    render_in_flight: Arc<AtomicBool>,
+    error: Arc<Mutex<Option<String>>>,
+}
+
+impl Default for PerformanceMonitor {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            samples: Arc::default(),
+            render_samples: Arc::default(),
+            passes: Arc::default(),
+            markers: Mutex::default(),
+            in_flight: Arc::default(),
+            render_in_flight: Arc::default(),
+            error: Arc::default(),
+        }
+    }
+}
+
+impl PerformanceMonitor {
+    fn now(&self) -> f64 {
+        self.started.elapsed().as_secs_f64()
+    }
+
+    pub fn mark(&self, label: impl Into<String>) {
+        let at = self.now();
+        self.markers.lock().unwrap().push(Marker {
+            at,
+            label: label.into(),
+        });
+    }
+
+    /// Samples the game's diagnostics unless the previous sample is still being taken.
+    /// Without the companion plugin only the entity count is known, taken from the cached
+    /// rows.
+    pub fn poll(&mut self, ctx: &egui::Context, url: &Endpoint, diagnostics: bool, cached: usize) {
+        if self.in_flight.load(Ordering::SeqCst) {
+            return;
+        }
+        let at = self.now();
+        if !diagnostics {
+            push(
+                &self.samples,
+                Sample {
+                    at,
+                    entities: Some(cached as f64),
+                    ..Default::default()
+                },
+            );
+            return;
+        }
+        self.in_flight.store(true, Ordering::SeqCst);
+        let request = helper::make_empty_request(INSPECTOR_DIAGNOSTICS_METHOD, url);
+        let samples = self.samples.clone();
+        let in_flight = self.in_flight.clone();
+        let error = self.error.clone();
+        let egui_ctx = ctx.clone();
+        transport::fetch(request, move |response| {
+            in_flight.store(false, Ordering::SeqCst);
+            match helper::parse_response::<Vec<Measurement>>(response) {
+                Ok(measurements) => {
+                    let find = |path: &str| {
+                        measurements
+                            .iter()
+                            .find(|m| m.path == path)
+                            .and_then(|m| m.smoothed.or(m.value))
+                    };
+                    push(
+                        &samples,
+                        Sample {
+                            at,
+                            fps: find(FPS_PATH),
+                            frame_time: find(FRAME_TIME_PATH),
+                            entities: find(ENTITY_COUNT_PATH).or(Some(cached as f64)),
+                        },
+                    );
+                    *error.lock().unwrap() = None;
+                }
+                Err(e) => *error.lock().unwrap() = Some(e.to_string()),
+            }
+            egui_ctx.request_repaint();
+        });
+    }
+
+    /// Samples the companion plugin's render stats unless the previous sample is still
+    /// being taken.
+    pub fn poll_render(&mut self, ctx: &egui::Context, url: &Endpoint) {
+        if self.render_in_flight.load(Ordering::SeqCst) {
+            return;
+        }
+        self.render_in_flight.store(true, Ordering::SeqCst);
+        let at = self.now();
+        let request = helper::make_empty_request(INSPECTOR_RENDER_STATS_METHOD, url);
+        let samples = self.render_samples.clone();
+        let passes = self.passes.clone();
+        let in_flight = self.render_in_flight.clone();
+        let error = self.error.clone();
+        let egui_ctx = ctx.clone();
+        transport::fetch(request, move |response| {
+            in_flight.store(false, Ordering::SeqCst);
+            match helper::parse_response::<RenderStats>(response) {
+                Ok(stats) => {
+                    let gpu: Vec<(String, f64)> = stats
+                        .diagnostics
+                        .iter()
+                        .filter(|m| m.path.ends_with(GPU_TIME_SUFFIX))
+                        .filter_map(|m| Some((m.path.clone(), m.smoothed.or(m.value)?)))
+                        .collect();
+                    // Nested passes are part of their parent's time already.
+                    let top_level = gpu
+                        .iter()
+                        .filter(|(path, _)| path.matches('/').count() == 2)
+                        .map(|(_, time)| time);
+                    push(
+                        &samples,
+                        RenderSample {
+                            at,
+                            visible: Some(stats.visible_entities),
+                            render_entities: Some(stats.render_entities),
+                            draw_calls: stats
+                                .diagnostics
+                                .iter()
+                                .find(|m| m.path == DRAW_CALLS_PATH)
+                                .and_then(|m| m.value),
+                            gpu_time: (!gpu.is_empty()).then(|| top_level.sum()),
+                        },
+                    );
+                    *passes.lock().unwrap() = gpu;
+                    *error.lock().unwrap() = None;
+                }
+                Err(e) => *error.lock().unwrap() = Some(e.to_string()),
+            }
+            egui_ctx.request_repaint();
+        });
+    }
+
+    pub fn clear(&self) {
+        self.samples.lock().unwrap().clear();
+        self.render_samples.lock().unwrap().clear();
+        self.passes.lock().unwrap().clear();
+        self.markers.lock().unwrap().clear();
+    }
+
+    /// A plot per measurement, stacked with a shared time axis.
+    pub fn draw(&self, ui: &mut egui::Ui, diagnostics: bool) {
+        self.draw_error(ui);
+        if !diagnostics {
+            ui.weak(
+                "FPS and frame time need the companion plugin and Bevy's \
+                 `FrameTimeDiagnosticsPlugin` in the game.",
+            );
+        }
+        let samples = self.samples.lock().unwrap();
+        let markers = self.markers.lock().unwrap();
+        draw_plots(ui, &samples, &SERIES, &markers);
+    }
+
+    /// The render stats plotted on the same time axis as [`Self::draw`], with the GPU time
+    /// of each pass in the last sample below.
+    pub fn draw_render(&self, ui: &mut egui::Ui) {
+        self.draw_error(ui);
+        let samples = self.render_samples.lock().unwrap();
+        let markers = self.markers.lock().unwrap();
+        let passes = self.passes.lock().unwrap();
+        if !samples.is_empty() && passes.is_empty() {
+            ui.weak("Pass timings need Bevy's `RenderDiagnosticsPlugin` in the game.");
+        }
+        if !passes.is_empty() {
+            egui::CollapsingHeader::new("GPU time per pass").show(ui, |ui| {
+                egui::Grid::new("render_passes")
+                    .num_columns(2)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (path, time) in passes.iter() {
+                            let pass = path
+                                .trim_start_matches("render/")
+                                .trim_end_matches(GPU_TIME_SUFFIX);
+                            ui.label(pass);
+                            ui.monospace(format!("{time:.3} ms"));
+                            ui.end_row();
+                        }
+                    });
+            });
+        }
+        draw_plots(ui, &samples, &RENDER_SERIES, &markers);
+    }
+
+    fn draw_error(&self, ui: &mut egui::Ui) {
+        if let Some(error) = &*self.error.lock().unwrap() {
+            ui.colored_label(ui.visuals().error_fg_color, error);
+        }
+    }
+}
+
+/// Stacked plots of the series that have values, sharing the time axis with every other
+/// plot of the monitor.
+fn draw_plots<T>(ui: &mut egui::Ui, samples: &VecDeque<T>, series: &[Series<T>], markers: &[Marker])
+where
+    T: HasTime,
+{
+    if samples.is_empty() {
+        ui.label("Waiting for the first sample...");
+        return;
+    }
+    let shown: Vec<_> = series
+        .iter()
+        .filter_map(|(title, id, value)| {
+            let points: PlotPoints = samples
+                .iter()
+                .filter_map(|s| value(s).map(|v| [s.at(), v]))
+                .collect();
+            (!points.points().is_empty()).then_some((*title, *id, points))
+        })
+        .collect();
+    let height = (ui.available_height() / shown.len().max(1) as f32 - 8.0).max(80.0);
+    let link = egui::Id::new("performance_time");
+    for (title, id, points) in shown {
+        ui.label(title);
+        Plot::new(id)
+            .height(height)
+            .link_axis(link, true, false)
+            .link_cursor(link, true, false)
+            .include_y(0.0)
+            .x_axis_formatter(|mark, _| format!("{:.0}s", mark.value))
+            .legend(Legend::default())
+            .show(ui, |plot| {
+                plot.line(Line::new(points).name(title));
+                for marker in markers {
+                    plot.vline(VLine::new(marker.at).name(&marker.label));
+                }
+            });
+    }
+}
+
+trait HasTime {
+    /// Seconds since the monitor started.
+    fn at(&self) -> f64;
+}
+
+impl HasTime for Sample {
+    fn at(&self) -> f64 {
+        self.at
+    }
+}
+
+impl HasTime for RenderSample {
+    fn at(&self) -> f64 {
+        self.at
+    }
+}
+
+fn push<T>(samples: &Mutex<VecDeque<T>>, sample: T) {
+    let mut samples = samples.lock().unwrap();
+    samples.push_back(sample);
+    if samples.len() > CAPACITY {
+        samples.pop_front();
+    }
+}
@@ -0,0 +1,54 @@
+//! Entities pinned above the entity tree. They are remembered by `Name` when they have one,
+//! since ids change whenever the game restarts, and by id otherwise.
+
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+use crate::compat::TypePaths;
+use crate::summary::entity_name;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Eq, Debug)]
+pub enum EntityPin {
+    Name(String),
+    Id(Entity),
+}
+
+impl EntityPin {
+    pub fn of(entity: Entity, row: &BrpQueryRow, paths: &TypePaths) -> Self {
+        match entity_name(row, paths) {
+            Some(name) => EntityPin::Name(name.to_owned()),
+            None => EntityPin::Id(entity),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            EntityPin::Name(name) => name.clone(),
+            EntityPin::Id(entity) => entity.to_string(),
+        }
+    }
+
+    pub fn matches(&self, entity: Entity, row: &BrpQueryRow, paths: &TypePaths) -> bool {
+        match self {
+            EntityPin::Name(name) => entity_name(row, paths) == Some(name.as_str()),
+            EntityPin::Id(id) => *id == entity,
+        }
+    }
+
+    /// The entity the pin stands for. A name shared by several entities picks the lowest
+    /// id, so the pin doesn't jump between them from one fetch to the next.
+    pub fn resolve(
+        &self,
+        rows: &HashMap<Entity, BrpQueryRow>,
+        paths: &TypePaths,
+    ) -> Option<Entity> {
+        match self {
+            EntityPin::Id(id) => rows.contains_key(id).then_some(*id),
+            EntityPin::Name(_) => rows
+                .iter()
+                .filter(|(entity, row)| self.matches(**entity, row, paths))
+                .map(|(entity, _)| *entity)
+                .min(),
+        }
+    }
+}
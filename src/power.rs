@@ -0,0 +1,91 @@
+//! Power saver for running the inspector on a laptop next to the game: fewer frames, no
+//! animations, slower polls and none at all while the window can't be seen.
+
+use serde::{Deserialize, Serialize};
+use web_time::Duration;
+
+use crate::refresh::RefreshPolicy;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct PowerSaver {
+    pub enabled: bool,
+    /// Frames per second the inspector redraws itself at most, also the fastest polls go.
+    /// Input is still answered right away.
+    pub max_fps: u32,
+    /// Stop polling the game while the window is minimized.
+    pub pause_when_hidden: bool,
+}
+
+impl Default for PowerSaver {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_fps: 10,
+            pause_when_hidden: true,
+        }
+    }
+}
+
+impl PowerSaver {
+    /// Shortest time between frames and between polls, `None` when off.
+    pub fn min_interval(&self) -> Option<Duration> {
+        self.enabled
+            .then(|| Duration::from_secs_f64(1.0 / f64::from(self.max_fps.max(1))))
+    }
+
+    /// Whether polling should stop, the window being minimized or shrunk to nothing. egui
+    /// doesn't tell when other windows cover it, then only the frame cap applies.
+    pub fn pauses(&self, ctx: &egui::Context) -> bool {
+        self.enabled
+            && self.pause_when_hidden
+            && ctx.input(|i| {
+                let viewport = i.viewport();
+                viewport.minimized == Some(true)
+                    || viewport.inner_rect.is_some_and(|rect| rect.area() <= 0.0)
+            })
+    }
+
+    /// Slows `policy` down to the frame cap, stops it while `paused`.
+    pub fn limit(&self, policy: &mut RefreshPolicy, paused: bool) {
+        if paused {
+            policy.enabled = false;
+        }
+        if let Some(min) = self.min_interval() {
+            policy.interval_ms = policy.interval_ms.max(min.as_millis() as u64);
+        }
+    }
+
+    /// Asks for another frame after `after`, or no sooner than the frame cap allows. For
+    /// views that change over time, which would otherwise redraw every frame.
+    pub fn request_repaint_after(&self, ctx: &egui::Context, after: Duration) {
+        let after = self.min_interval().map_or(after, |min| after.max(min));
+        ctx.request_repaint_after(after);
+    }
+
+    pub fn edit(&mut self, ui: &mut egui::Ui) {
+        ui.checkbox(&mut self.enabled, "Power saver").on_hover_text(
+            "Fewer frames and polls and no animations, for laptops running the game too",
+        );
+        ui.add_enabled_ui(self.enabled, |ui| {
+            ui.add(
+                egui::DragValue::new(&mut self.max_fps)
+                    .range(1..=60)
+                    .prefix("At most ")
+                    .suffix(" frames/s"),
+            )
+            .on_hover_text("Also the fastest any refresh runs. Input is handled right away");
+            ui.checkbox(&mut self.pause_when_hidden, "Pause polling while minimized");
+        });
+    }
+}
+
+/// A spinner, or a still hourglass while animations are off for the power saver, egui's
+/// spinner redraws every frame.
+pub fn spinner(ui: &mut egui::Ui) -> egui::Response {
+    if ui.style().animation_time > 0.0 {
+        ui.spinner()
+    } else {
+        ui.label("⏳")
+    }
+}
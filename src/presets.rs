@@ -0,0 +1,45 @@
+//! Named views of the world, e.g. a "UI debugging" preset querying only UI components,
+//! switched between from the toolbar.
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::AppConfig;
+use crate::pins::EntityPin;
+use crate::query_builder::CustomQuery;
+use crate::search::{EntitySearch, SearchFilters};
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ViewPreset {
+    pub name: String,
+    /// `None` fetches every registered type.
+    pub query: Option<CustomQuery>,
+    pub skip_empty_entities: bool,
+    pub search: SearchFilters,
+    pub pinned_entities: Vec<EntityPin>,
+}
+
+impl ViewPreset {
+    /// The current query, filters and pins under `name`.
+    pub fn capture(name: String, config: &AppConfig, search: &EntitySearch) -> Self {
+        Self {
+            name,
+            query: config.query.clone(),
+            skip_empty_entities: config.skip_empty_entities,
+            search: search.filters(),
+            pinned_entities: config.pinned_entities.clone(),
+        }
+    }
+
+    /// Switches to the preset, returns whether the world has to be fetched again for its
+    /// query.
+    pub fn apply(&self, config: &mut AppConfig, search: &mut EntitySearch) -> bool {
+        let refetch = config.query != self.query;
+        config.query = self.query.clone();
+        config.skip_empty_entities = self.skip_empty_entities;
+        config.pinned_entities = self.pinned_entities.clone();
+        config.active_preset = self.name.clone();
+        search.set_filters(self.search.clone());
+        refetch
+    }
+}
@@ -0,0 +1,125 @@
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow, utils::HashMap};
+
+use crate::compat::TypePaths;
+use crate::ordering::children;
+
+/// Inconsistency between `Parent` and `Children` components in the cached world.
+#[derive(Clone, Copy, PartialEq)]
+pub enum HierarchyProblem {
+    /// `entity` has a `Parent` that doesn't exist.
+    Orphaned { entity: Entity, parent: Entity },
+    /// `entity` has a `Parent` whose `Children` doesn't list it.
+    NotInChildren { entity: Entity, parent: Entity },
+    /// `parent` lists `child`, which is gone or has another parent.
+    StaleChild { parent: Entity, child: Entity },
+}
+
+/// One-click repair for a [`HierarchyProblem`].
+#[derive(Clone, Copy, PartialEq)]
+pub enum HierarchyFix {
+    /// `bevy/reparent` to `parent`, or to the root for `None`.
+    Reparent {
+        entity: Entity,
+        parent: Option<Entity>,
+    },
+    /// Rewrites the parent's `Children` without `child`.
+    RemoveChild { parent: Entity, child: Entity },
+}
+
+impl HierarchyProblem {
+    /// Fixes that resolve the problem, with their button labels.
+    pub fn fixes(&self) -> Vec<(&'static str, HierarchyFix)> {
+        match *self {
+            HierarchyProblem::Orphaned { entity, .. } => vec![(
+                "Reparent to root",
+                HierarchyFix::Reparent {
+                    entity,
+                    parent: None,
+                },
+            )],
+            HierarchyProblem::NotInChildren { entity, parent } => vec![
+                (
+                    "Attach to parent",
+                    HierarchyFix::Reparent {
+                        entity,
+                        parent: Some(parent),
+                    },
+                ),
+                (
+                    "Reparent to root",
+                    HierarchyFix::Reparent {
+                        entity,
+                        parent: None,
+                    },
+                ),
+            ],
+            HierarchyProblem::StaleChild { parent, child } => vec![(
+                "Remove stale entry",
+                HierarchyFix::RemoveChild { parent, child },
+            )],
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            HierarchyProblem::Orphaned { entity, parent } => {
+                format!("{entity} points to missing parent {parent}")
+            }
+            HierarchyProblem::NotInChildren { entity, parent } => {
+                format!("{entity} isn't listed in the children of {parent}")
+            }
+            HierarchyProblem::StaleChild { parent, child } => {
+                format!("{parent} lists {child}, which isn't its child")
+            }
+        }
+    }
+}
+
+pub fn parent_of(row: &BrpQueryRow, paths: &TypePaths) -> Option<Entity> {
+    row.components
+        .get(paths.parent)
+        .and_then(|parent| parent.as_u64())
+        .map(Entity::from_bits)
+}
+
+/// Finds broken links between parents and children, sorted by the entity they are about.
+pub fn hierarchy_problems(
+    world: &HashMap<Entity, BrpQueryRow>,
+    paths: &TypePaths,
+) -> Vec<HierarchyProblem> {
+    let mut problems = Vec::new();
+    for (entity, row) in world.iter() {
+        if let Some(parent) = parent_of(row, paths) {
+            match world.get(&parent) {
+                None => problems.push(HierarchyProblem::Orphaned {
+                    entity: *entity,
+                    parent,
+                }),
+                Some(parent_row) if !children(parent_row, paths).contains(entity) => {
+                    problems.push(HierarchyProblem::NotInChildren {
+                        entity: *entity,
+                        parent,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        for child in children(row, paths) {
+            let stale = world
+                .get(&child)
+                .is_none_or(|child_row| parent_of(child_row, paths) != Some(*entity));
+            if stale {
+                problems.push(HierarchyProblem::StaleChild {
+                    parent: *entity,
+                    child,
+                });
+            }
+        }
+    }
+    problems.sort_by_key(|problem| match problem {
+        HierarchyProblem::Orphaned { entity, .. }
+        | HierarchyProblem::NotInChildren { entity, .. } => *entity,
+        HierarchyProblem::StaleChild { parent, .. } => *parent,
+    });
+    problems
+}
@@ -0,0 +1,109 @@
+//! Project directories, so the settings that belong to a game live in its repository and
+//! are shared through version control. A project holds [`SETTINGS_FILE`] and optionally an
+//! [`ALIASES_FILE`] in the aliases format.
+
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::alerts::AlertRule;
+use crate::config::{AppConfig, ConnectionProfile};
+use crate::dashboard::Dashboard;
+use crate::groups::EntityGroup;
+use crate::query_builder::CustomQuery;
+
+pub const SETTINGS_FILE: &str = "inspector.json";
+pub const ALIASES_FILE: &str = "aliases.toml";
+
+/// The part of [`AppConfig`] specific to one game. Secrets are never written, they stay in
+/// the local settings.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ProjectSettings {
+    pub profiles: Vec<ConnectionProfile>,
+    pub label_template: String,
+    pub pinned_components: Vec<String>,
+    pub protected_components: Vec<String>,
+    pub saved_queries: Vec<CustomQuery>,
+    pub dashboards: Vec<Dashboard>,
+    pub alerts: Vec<AlertRule>,
+    pub entity_groups: Vec<EntityGroup>,
+}
+
+impl ProjectSettings {
+    pub fn from_config(config: &AppConfig) -> Self {
+        let mut config = config.scrubbed();
+        config.store_profile();
+        Self {
+            profiles: config.profiles,
+            label_template: config.label_template,
+            pinned_components: config.pinned_components,
+            protected_components: config.protected_components,
+            saved_queries: config.saved_queries,
+            dashboards: config.dashboards,
+            alerts: config.alerts,
+            entity_groups: config.entity_groups,
+        }
+    }
+
+    /// Replaces the project specific settings, keeping the secrets of local profiles with
+    /// the same name.
+    pub fn apply(self, config: &mut AppConfig, dir: &Path) {
+        config.store_profile();
+        let mut profiles = self.profiles;
+        for profile in profiles.iter_mut() {
+            if let Some(local) = config.profiles.iter().find(|p| p.name == profile.name) {
+                profile.shared_secret = local.shared_secret.clone();
+            }
+        }
+        if !profiles.is_empty() {
+            config.profiles = profiles;
+            let name = if config.profiles.iter().any(|p| p.name == config.profile) {
+                config.profile.clone()
+            } else {
+                config.profiles[0].name.clone()
+            };
+            config.load_profile(&name);
+        }
+        config.label_template = self.label_template;
+        config.pinned_components = self.pinned_components;
+        config.protected_components = self.protected_components;
+        config.saved_queries = self.saved_queries;
+        config.dashboards = self.dashboards;
+        config.active_dashboard = 0;
+        config.alerts = self.alerts;
+        config.entity_groups = self.entity_groups;
+        let aliases = dir.join(ALIASES_FILE);
+        config.aliases_file = aliases.exists().then_some(aliases);
+        config.project = Some(dir.to_owned());
+    }
+
+    /// The project's settings, `None` when the directory has no settings file yet.
+    pub fn load(dir: &Path) -> anyhow::Result<Option<Self>> {
+        let path = dir.join(SETTINGS_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let text = std::fs::read_to_string(path)?;
+        Ok(Some(serde_json::from_str(&text)?))
+    }
+
+    /// Writes the settings unless the file already has them, so saving on every autosave
+    /// doesn't touch the file in the working tree.
+    pub fn save(&self, dir: &Path) -> anyhow::Result<()> {
+        let path = dir.join(SETTINGS_FILE);
+        let text = serde_json::to_string_pretty(self)? + "\n";
+        if std::fs::read_to_string(&path).is_ok_and(|existing| existing == text) {
+            return Ok(());
+        }
+        std::fs::write(path, text)?;
+        Ok(())
+    }
+}
+
+/// Directory name of the project for menus.
+pub fn project_name(dir: &Path) -> String {
+    dir.file_name().map_or_else(
+        || dir.display().to_string(),
+        |name| name.to_string_lossy().into_owned(),
+    )
+}
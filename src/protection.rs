@@ -0,0 +1,23 @@
+//! Entities the game can't do without, guarded against being destroyed or stripped of
+//! components from the inspector.
+
+use bevy::remote::builtin_methods::BrpQueryRow;
+
+use crate::summary::short_name;
+
+/// Components marking protected entities by default. `Protected` is for projects to
+/// put on their own critical entities.
+pub const DEFAULT_PROTECTED: [&str; 4] = ["PrimaryWindow", "Window", "Camera", "Protected"];
+
+/// The first pattern, a type path or short name, that one of the entity's components
+/// matches.
+pub fn protected_by<'a>(row: &BrpQueryRow, patterns: &'a [String]) -> Option<&'a str> {
+    patterns
+        .iter()
+        .find(|pattern| {
+            row.components
+                .keys()
+                .any(|key| key == *pattern || short_name(key) == pattern.as_str())
+        })
+        .map(String::as_str)
+}
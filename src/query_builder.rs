@@ -0,0 +1,113 @@
+//! Queries narrower than the default one fetching every registered type, for worlds too
+//! big to pull in whole.
+
+use bevy::remote::builtin_methods::{BrpQuery, BrpQueryFilter, BrpQueryParams};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::completion::type_path_picker;
+use crate::summary::short_name;
+
+/// Types listed when picking one, typing narrows them down.
+const PICKER_LENGTH: usize = 30;
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct CustomQuery {
+    pub name: String,
+    /// Fetched, entities without them don't match.
+    pub components: Vec<String>,
+    /// Fetched when present.
+    pub option: Vec<String>,
+    /// Reported as present or not without fetching their values.
+    pub has: Vec<String>,
+    pub with: Vec<String>,
+    pub without: Vec<String>,
+    /// Fail on types that aren't registered components instead of skipping them.
+    pub strict: bool,
+}
+
+impl CustomQuery {
+    /// Parameters of the `bevy/query` request. `strict` is ignored by remotes that
+    /// predate it.
+    pub fn params(&self) -> Value {
+        let params = BrpQueryParams {
+            data: BrpQuery {
+                components: self.components.clone(),
+                option: self.option.clone(),
+                has: self.has.clone(),
+            },
+            filter: BrpQueryFilter {
+                with: self.with.clone(),
+                without: self.without.clone(),
+            },
+        };
+        let mut params = serde_json::to_value(params).unwrap_or_default();
+        params["strict"] = Value::Bool(self.strict);
+        params
+    }
+
+    /// A list of types per part of the query, each with a picker adding registered
+    /// types, and the strict mode toggle.
+    pub fn edit(&mut self, ui: &mut egui::Ui, types: &[String]) {
+        egui::Grid::new("query_parts")
+            .num_columns(2)
+            .striped(true)
+            .show(ui, |ui| {
+                let parts = [
+                    (
+                        "Components",
+                        "Fetched, required to match",
+                        &mut self.components,
+                    ),
+                    ("Optional", "Fetched when present", &mut self.option),
+                    ("Has", "Only whether it's present", &mut self.has),
+                    ("With", "Required to match, not fetched", &mut self.with),
+                    ("Without", "Excluded from the results", &mut self.without),
+                ];
+                for (label, hint, list) in parts {
+                    ui.label(label).on_hover_text(hint);
+                    ui.push_id(label, |ui| edit_types(ui, list, types));
+                    ui.end_row();
+                }
+            });
+        ui.checkbox(&mut self.strict, "Strict")
+            .on_hover_text("Fail when a type isn't a registered component instead of skipping it");
+    }
+}
+
+/// The types as removable chips, with a menu to add more.
+fn edit_types(ui: &mut egui::Ui, list: &mut Vec<String>, types: &[String]) {
+    ui.horizontal_wrapped(|ui| {
+        let mut remove = None;
+        for (index, type_path) in list.iter().enumerate() {
+            if ui
+                .small_button(format!("{} ✖", short_name(type_path)))
+                .on_hover_text(type_path)
+                .clicked()
+            {
+                remove = Some(index);
+            }
+        }
+        if let Some(index) = remove {
+            list.remove(index);
+        }
+        ui.menu_button("➕", |ui| {
+            let id = ui.id().with("filter");
+            let mut filter: String = ui.data_mut(|d| d.get_temp(id)).unwrap_or_default();
+            let available: Vec<String> = types
+                .iter()
+                .filter(|type_path| !list.contains(type_path))
+                .cloned()
+                .collect();
+            let picked = type_path_picker(ui, "picker", &mut filter, &available, PICKER_LENGTH);
+            picked.response.request_focus();
+            if let Some(type_path) = picked.accepted {
+                list.push(type_path);
+                filter.clear();
+                ui.close_menu();
+            }
+            ui.data_mut(|d| d.insert_temp(id, filter));
+        });
+    });
+}
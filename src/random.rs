@@ -0,0 +1,118 @@
+//! Random values for quickly producing varied test entities.
+
+use web_time::{SystemTime, UNIX_EPOCH};
+
+use serde_json::{Map, Value};
+
+/// Random numbers stay within `-BOUND..=BOUND`, unsigned ones within `0..=BOUND`.
+pub const BOUND: i64 = 100;
+
+/// Lists and maps get at most this many random items.
+pub const MAX_ITEMS: u64 = 3;
+
+const STRING_LEN: usize = 8;
+const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// Small xorshift generator, good enough for test data and reproducible from a seed.
+pub struct Rng(u64);
+
+impl Rng {
+    /// Seeded from the clock.
+    pub fn new() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        Self::seeded(nanos)
+    }
+
+    pub fn seeded(seed: u64) -> Self {
+        // Xorshift never leaves zero.
+        Self(seed.max(1))
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A number in `0..n`, `n` has to be positive.
+    pub fn below(&mut self, n: u64) -> u64 {
+        self.next_u64() % n
+    }
+
+    pub fn bool(&mut self) -> bool {
+        self.next_u64() & 1 == 1
+    }
+
+    pub fn int(&mut self, min: i64, max: i64) -> i64 {
+        min + self.below((max - min + 1) as u64) as i64
+    }
+
+    /// A float within `-BOUND..BOUND`, rounded to three decimals to stay readable.
+    pub fn float(&mut self) -> f64 {
+        let unit = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        let value = (unit * 2.0 - 1.0) * BOUND as f64;
+        (value * 1000.0).round() / 1000.0
+    }
+
+    pub fn string(&mut self, len: usize) -> String {
+        (0..len)
+            .map(|_| ALPHABET[self.below(ALPHABET.len() as u64) as usize] as char)
+            .collect()
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        if items.is_empty() {
+            return None;
+        }
+        items.get(self.below(items.len() as u64) as usize)
+    }
+}
+
+/// A random scalar of a schema `type`, `Null` for anything else.
+pub fn scalar(schema_type: &str, rng: &mut Rng) -> Value {
+    match schema_type {
+        "float" => rng.float().into(),
+        "uint" => rng.int(0, BOUND).into(),
+        "int" => rng.int(-BOUND, BOUND).into(),
+        "boolean" => rng.bool().into(),
+        "string" => rng.string(STRING_LEN).into(),
+        _ => Value::Null,
+    }
+}
+
+/// Random values for primitives the registry may not list schemas for.
+pub fn primitive(type_path: &str, rng: &mut Rng) -> Value {
+    match type_path {
+        "f32" | "f64" => scalar("float", rng),
+        "u8" | "u16" | "u32" | "u64" | "usize" => scalar("uint", rng),
+        "i8" | "i16" | "i32" | "i64" | "isize" => scalar("int", rng),
+        "bool" => scalar("boolean", rng),
+        "alloc::string::String" | "str" => scalar("string", rng),
+        "char" => rng.string(1).into(),
+        _ => Value::Null,
+    }
+}
+
+/// A value shaped like `value` with every scalar replaced, for types without a schema.
+/// Non-negative integers stay non-negative since they are likely unsigned.
+pub fn like(value: &Value, rng: &mut Rng) -> Value {
+    match value {
+        Value::Bool(_) => rng.bool().into(),
+        Value::Number(n) if n.is_u64() => scalar("uint", rng),
+        Value::Number(n) if n.is_i64() => scalar("int", rng),
+        Value::Number(_) => scalar("float", rng),
+        Value::String(_) => scalar("string", rng),
+        Value::Array(items) => Value::Array(items.iter().map(|item| like(item, rng)).collect()),
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, field)| (key.clone(), like(field, rng)))
+                .collect::<Map<_, _>>(),
+        ),
+        Value::Null => Value::Null,
+    }
+}
@@ -0,0 +1,196 @@
+//! Values hidden in the inspector's views and exports, so it can be shown on stream or
+//! its exports attached to public bug reports. A rule names a component by type path or
+//! short name, `*` matching anything, followed by the path of a field to hide, e.g.
+//! `Account.auth.token`. Without a field the whole component is hidden.
+
+use std::borrow::Cow;
+
+use bevy::remote::builtin_methods::BrpQueryRow;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::summary::short_name;
+
+/// Shown instead of hidden values.
+pub const MASK: &str = "•••";
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+#[serde(default)]
+pub struct Redaction {
+    pub enabled: bool,
+    pub rules: Vec<String>,
+}
+
+impl Redaction {
+    /// Paths of the fields the rules hide of `component`, empty for all of it.
+    fn fields<'a>(&'a self, component: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.rules
+            .iter()
+            .filter(|_| self.enabled)
+            .filter_map(move |rule| {
+                let (pattern, field) = rule.split_once('.').unwrap_or((rule, ""));
+                let pattern = pattern.trim();
+                (glob(pattern, component) || glob(pattern, short_name(component)))
+                    .then_some(field.trim())
+            })
+    }
+
+    pub fn hides(&self, component: &str) -> bool {
+        self.fields(component).next().is_some()
+    }
+
+    /// Whether the value at `path` inside `component`, like `.auth.token`, is hidden or
+    /// lies inside a hidden one.
+    pub fn hides_field(&self, component: &str, path: &str) -> bool {
+        self.fields(component).any(|field| {
+            let hidden: String = field
+                .split('.')
+                .map(|segment| format!(".{segment}"))
+                .collect();
+            field.is_empty()
+                || path
+                    .strip_prefix(&hidden)
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with(['.', '[']))
+        })
+    }
+
+    /// Masks what the rules hide of the value of `component`.
+    pub fn mask(&self, component: &str, value: &mut Value) {
+        for field in self.fields(component) {
+            mask(value, field);
+        }
+    }
+
+    pub fn redact<'v>(&self, component: &str, value: &'v Value) -> Cow<'v, Value> {
+        if !self.hides(component) {
+            return Cow::Borrowed(value);
+        }
+        let mut value = value.clone();
+        self.mask(component, &mut value);
+        Cow::Owned(value)
+    }
+
+    pub fn redact_row<'r>(&self, row: &'r BrpQueryRow) -> Cow<'r, BrpQueryRow> {
+        if !row.components.keys().any(|key| self.hides(key)) {
+            return Cow::Borrowed(row);
+        }
+        let mut row = row.clone();
+        for (key, value) in row.components.iter_mut() {
+            self.mask(key, value);
+        }
+        Cow::Owned(row)
+    }
+}
+
+/// Replaces the value at the dot separated `field` path, array items by index, with
+/// [`MASK`]. Does nothing when there is no such value.
+fn mask(value: &mut Value, field: &str) {
+    let mut target = value;
+    for segment in field.split('.').filter(|segment| !segment.is_empty()) {
+        let next = match target {
+            Value::Object(fields) => fields.get_mut(segment),
+            Value::Array(items) => segment
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| items.get_mut(index)),
+            _ => None,
+        };
+        let Some(next) = next else {
+            return;
+        };
+        target = next;
+    }
+    *target = Value::String(MASK.to_owned());
+}
+
+/// Whether `text` matches `pattern`, where `*` stands for any run of characters.
+fn glob(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let Some(mut rest) = text.strip_prefix(parts.next().unwrap_or_default()) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn rules(rules: &[&str]) -> Redaction {
+        Redaction {
+            enabled: true,
+            rules: rules.iter().map(|rule| (*rule).to_owned()).collect(),
+        }
+    }
+
+    #[test]
+    fn glob_matches_stars_anywhere() {
+        assert!(glob("Account", "Account"));
+        assert!(!glob("Account", "Accounts"));
+        assert!(glob("*", ""));
+        assert!(glob("Player*", "PlayerStats"));
+        assert!(glob("*Token", "AuthToken"));
+        assert!(!glob("*Token", "TokenStore"));
+        assert!(glob("game::*::Secret", "game::net::Secret"));
+        assert!(glob("a*b*c", "a_b_b_c"));
+        assert!(!glob("a*b*c", "a_c_b"));
+    }
+
+    #[test]
+    fn rules_match_type_path_or_short_name() {
+        let redaction = rules(&["Account", "net::*"]);
+        assert!(redaction.hides("game::Account"));
+        assert!(redaction.hides("net::Session"));
+        assert!(!redaction.hides("game::Health"));
+        let disabled = Redaction {
+            enabled: false,
+            ..redaction
+        };
+        assert!(!disabled.hides("game::Account"));
+    }
+
+    #[test]
+    fn hides_field_covers_nested_values_only() {
+        let redaction = rules(&["Account.auth.token"]);
+        assert!(redaction.hides_field("game::Account", ".auth.token"));
+        assert!(redaction.hides_field("game::Account", ".auth.token.expires"));
+        assert!(redaction.hides_field("game::Account", ".auth.token[0]"));
+        assert!(!redaction.hides_field("game::Account", ".auth"));
+        assert!(!redaction.hides_field("game::Account", ".auth.tokens"));
+        assert!(!redaction.hides_field("game::Health", ".auth.token"));
+        let whole = rules(&["Account"]);
+        assert!(whole.hides_field("game::Account", ".name"));
+    }
+
+    #[test]
+    fn mask_replaces_fields_and_array_items() {
+        let redaction = rules(&["Account.auth.token", "Account.keys.1", "Account.missing.x"]);
+        let mut value = json!({
+            "auth": { "token": "secret", "user": "me" },
+            "keys": ["a", "b"],
+        });
+        redaction.mask("game::Account", &mut value);
+        assert_eq!(
+            value,
+            json!({
+                "auth": { "token": MASK, "user": "me" },
+                "keys": ["a", MASK],
+            })
+        );
+        let mut whole = json!({ "token": "secret" });
+        rules(&["Account"]).mask("game::Account", &mut whole);
+        assert_eq!(whole, json!(MASK));
+    }
+}
@@ -0,0 +1,169 @@
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow, utils::HashMap};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// A component field holding an entity.
+pub struct Reference {
+    pub entity: Entity,
+    pub component: String,
+    /// Location of the value inside the component, e.g. `.target` or `[2]`.
+    pub path: String,
+}
+
+/// Finds every cached component value referencing `target`. Entities are serialized as
+/// their bits, older formats use an `{ index, generation }` struct.
+pub fn find_references(target: Entity, rows: &HashMap<Entity, BrpQueryRow>) -> Vec<Reference> {
+    let mut references = Vec::new();
+    for (entity, row) in rows {
+        if *entity == target {
+            continue;
+        }
+        for (component, value) in &row.components {
+            let mut paths = Vec::new();
+            scan(value, target, &mut String::new(), &mut paths);
+            references.extend(paths.into_iter().map(|path| Reference {
+                entity: *entity,
+                component: component.clone(),
+                path,
+            }));
+        }
+    }
+    references.sort_by(|a, b| (a.entity, &a.component).cmp(&(b.entity, &b.component)));
+    references
+}
+
+fn scan(value: &Value, target: Entity, path: &mut String, found: &mut Vec<String>) {
+    match value {
+        Value::Number(n) if n.as_u64() == Some(target.to_bits()) => found.push(path.clone()),
+        Value::Object(fields) if is_entity_struct(fields, target) => found.push(path.clone()),
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                let len = path.len();
+                path.push('.');
+                path.push_str(key);
+                scan(field, target, path, found);
+                path.truncate(len);
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                let len = path.len();
+                path.push_str(&format!("[{index}]"));
+                scan(item, target, path, found);
+                path.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_entity_struct(fields: &serde_json::Map<String, Value>, target: Entity) -> bool {
+    fields.len() == 2
+        && fields.get("index").and_then(Value::as_u64) == Some(target.index() as u64)
+        && fields.get("generation").and_then(Value::as_u64) == Some(target.generation() as u64)
+}
+
+/// Identity of an asset handle, serialized as a `Strong` or `Weak` enum variant.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub enum AssetKey {
+    Path(String),
+    Id(Value),
+}
+
+impl AssetKey {
+    pub fn of(value: &Value) -> Option<Self> {
+        let object = value.as_object().filter(|o| o.len() == 1)?;
+        let (variant, inner) = object.iter().next()?;
+        if variant != "Strong" && variant != "Weak" {
+            return None;
+        }
+        if let Some(path) = inner.get("path").and_then(Value::as_str) {
+            return Some(Self::Path(path.to_owned()));
+        }
+        Some(Self::Id(inner.get("id").unwrap_or(inner).clone()))
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Self::Path(path) => path.clone(),
+            Self::Id(id) => id.to_string(),
+        }
+    }
+}
+
+/// Entities of `known`, by their bits, that a component value refers to, with where.
+pub fn entities_in(value: &Value, known: &HashMap<u64, Entity>) -> Vec<(String, Entity)> {
+    let mut found = Vec::new();
+    visit(value, &mut String::new(), &mut |path, value| {
+        let bits = match value {
+            Value::Number(n) => n.as_u64()?,
+            Value::Object(fields) if fields.len() == 2 => {
+                let index = fields.get("index")?.as_u64()?;
+                let generation = fields.get("generation")?.as_u64()?;
+                (generation << 32) | index
+            }
+            _ => return None,
+        };
+        found.push((path.to_owned(), *known.get(&bits)?));
+        Some(())
+    });
+    found
+}
+
+/// Asset handles inside a component value, with their location.
+pub fn asset_handles(value: &Value) -> Vec<(String, AssetKey)> {
+    let mut handles = Vec::new();
+    visit(value, &mut String::new(), &mut |path, value| {
+        let key = AssetKey::of(value)?;
+        handles.push((path.to_owned(), key));
+        Some(())
+    });
+    handles
+}
+
+/// Finds every cached component holding a handle to the same asset.
+pub fn find_asset_users(key: &AssetKey, rows: &HashMap<Entity, BrpQueryRow>) -> Vec<Reference> {
+    let mut references = Vec::new();
+    for (entity, row) in rows {
+        for (component, value) in &row.components {
+            visit(value, &mut String::new(), &mut |path, value| {
+                (AssetKey::of(value)? == *key).then(|| {
+                    references.push(Reference {
+                        entity: *entity,
+                        component: component.clone(),
+                        path: path.to_owned(),
+                    })
+                })
+            });
+        }
+    }
+    references.sort_by(|a, b| (a.entity, &a.component).cmp(&(b.entity, &b.component)));
+    references
+}
+
+/// Calls `f` for every value, not descending into values for which it returns `Some`.
+fn visit(value: &Value, path: &mut String, f: &mut impl FnMut(&str, &Value) -> Option<()>) {
+    if f(path, value).is_some() {
+        return;
+    }
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                let len = path.len();
+                path.push('.');
+                path.push_str(key);
+                visit(field, path, f);
+                path.truncate(len);
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                let len = path.len();
+                path.push_str(&format!("[{index}]"));
+                visit(item, path, f);
+                path.truncate(len);
+            }
+        }
+        _ => {}
+    }
+}
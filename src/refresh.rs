@@ -0,0 +1,118 @@
+//! How often each part of the inspector asks the game for new data, so cheap views can
+//! update fast while the world query stays slow. The world fetch keeps its live mode
+//! settings, the others are kept here.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+use web_time::{Duration, Instant};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub enum RefreshTarget {
+    /// The world fetch of live mode.
+    Tree,
+    /// Watched resources, shown in the status bar, dashboards and alerts.
+    Resources,
+    /// Diagnostics of the Performance panel.
+    Diagnostics,
+    /// Render stats of the Rendering panel.
+    Rendering,
+    /// Streamed changes of the selected entity, applied in batches this often.
+    Watches,
+}
+
+impl RefreshTarget {
+    pub const ALL: [RefreshTarget; 5] = [
+        RefreshTarget::Tree,
+        RefreshTarget::Resources,
+        RefreshTarget::Diagnostics,
+        RefreshTarget::Rendering,
+        RefreshTarget::Watches,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RefreshTarget::Tree => "Entity tree",
+            RefreshTarget::Resources => "Watched resources",
+            RefreshTarget::Diagnostics => "Performance",
+            RefreshTarget::Rendering => "Rendering",
+            RefreshTarget::Watches => "Selected entity changes",
+        }
+    }
+
+    fn default_policy(self) -> RefreshPolicy {
+        let interval_ms = match self {
+            RefreshTarget::Tree => 250,
+            RefreshTarget::Resources => 100,
+            RefreshTarget::Diagnostics | RefreshTarget::Rendering => 1000,
+            RefreshTarget::Watches => 0,
+        };
+        RefreshPolicy {
+            enabled: true,
+            interval_ms,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+pub struct RefreshPolicy {
+    pub enabled: bool,
+    /// Time between requests, 0 for as often as the answers come in.
+    pub interval_ms: u64,
+}
+
+impl RefreshPolicy {
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms)
+    }
+}
+
+/// Policies changed from the defaults, by target.
+#[derive(Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct RefreshPolicies(BTreeMap<RefreshTarget, RefreshPolicy>);
+
+impl RefreshPolicies {
+    pub fn get(&self, target: RefreshTarget) -> RefreshPolicy {
+        self.0
+            .get(&target)
+            .copied()
+            .unwrap_or_else(|| target.default_policy())
+    }
+
+    pub fn get_mut(&mut self, target: RefreshTarget) -> &mut RefreshPolicy {
+        self.0
+            .entry(target)
+            .or_insert_with(|| target.default_policy())
+    }
+}
+
+/// When each target last refreshed, deciding for all of them when they are due next.
+#[derive(Default)]
+pub struct RefreshScheduler {
+    last: BTreeMap<RefreshTarget, Instant>,
+}
+
+impl RefreshScheduler {
+    /// Whether `target` should refresh now, counting it as refreshed if so. Schedules a
+    /// repaint for when it is due next.
+    pub fn due(
+        &mut self,
+        ctx: &egui::Context,
+        target: RefreshTarget,
+        policy: RefreshPolicy,
+    ) -> bool {
+        if !policy.enabled {
+            return false;
+        }
+        let interval = policy.interval();
+        ctx.request_repaint_after(interval);
+        let due = self
+            .last
+            .get(&target)
+            .is_none_or(|last| last.elapsed() >= interval);
+        if due {
+            self.last.insert(target, Instant::now());
+        }
+        due
+    }
+}
@@ -0,0 +1,216 @@
+//! Views of single component types shown instead of their JSON, e.g. a color picker for
+//! colors. Forks can add their own by registering a [`ComponentRenderer`] in
+//! `TemplateApp::new`.
+
+use bevy::prelude::Entity;
+use serde_json::Value;
+
+use crate::app::Msg;
+use crate::edit::Snap;
+use crate::references::AssetKey;
+use crate::spatial::TRANSFORM;
+use crate::summary::short_name;
+use crate::transform::{draw_transform, draw_transform_values, TransformChange};
+use crate::widgets::{edit_color, is_color};
+
+/// A component to draw, with what the renderer may do about it.
+pub struct ComponentView<'a> {
+    pub entity: Entity,
+    pub type_path: &'a str,
+    pub value: &'a Value,
+    /// Edits can be sent, the inspector isn't read-only and the game supports inserts.
+    pub editable: bool,
+    pub snap: Snap,
+}
+
+pub trait ComponentRenderer: Send + Sync {
+    /// Whether it draws components of this type.
+    fn matches(&self, type_path: &str) -> bool;
+
+    /// Draws the component and returns what to do after an edit. `None`, before drawing
+    /// anything, when the value isn't in a shape it knows, the JSON is shown then.
+    fn draw(&self, ui: &mut egui::Ui, view: &ComponentView) -> Option<Msg>;
+}
+
+/// Registered renderers, later ones come first.
+pub struct Renderers(Vec<Box<dyn ComponentRenderer>>);
+
+impl Default for Renderers {
+    fn default() -> Self {
+        let mut renderers = Self(Vec::new());
+        renderers.register(NameRenderer);
+        renderers.register(TransformRenderer);
+        renderers.register(VisibilityRenderer);
+        renderers.register(HandleRenderer);
+        renderers.register(ColorRenderer);
+        renderers
+    }
+}
+
+impl Renderers {
+    /// Adds a renderer, it takes over from built-in ones matching the same types.
+    pub fn register(&mut self, renderer: impl ComponentRenderer + 'static) {
+        self.0.push(Box::new(renderer));
+    }
+
+    /// Draws the component with the last registered renderer that matches and knows the
+    /// value. `None` when none did.
+    pub fn draw(&self, ui: &mut egui::Ui, view: &ComponentView) -> Option<Msg> {
+        self.0
+            .iter()
+            .rev()
+            .filter(|renderer| renderer.matches(view.type_path))
+            .find_map(|renderer| renderer.draw(ui, view))
+    }
+}
+
+/// Whether `type_path` is Bevy's type called `name`.
+pub fn is_bevy_type(type_path: &str, name: &str) -> bool {
+    type_path.starts_with("bevy_") && short_name(type_path) == name
+}
+
+/// The name in large text, renamed when edited.
+struct NameRenderer;
+
+impl ComponentRenderer for NameRenderer {
+    fn matches(&self, type_path: &str) -> bool {
+        is_bevy_type(type_path, "Name")
+    }
+
+    fn draw(&self, ui: &mut egui::Ui, view: &ComponentView) -> Option<Msg> {
+        // Depending on the version `Name` is serialized as a plain string or a struct.
+        let name = view
+            .value
+            .as_str()
+            .or_else(|| view.value.get("name").and_then(Value::as_str))?;
+        if !view.editable {
+            ui.heading(name);
+            return Some(Msg::None);
+        }
+        let id = ui.id().with(("name", view.entity));
+        let mut text: String = ui
+            .data(|d| d.get_temp(id))
+            .unwrap_or_else(|| name.to_owned());
+        let response = ui.add(egui::TextEdit::singleline(&mut text).font(egui::TextStyle::Heading));
+        if response.has_focus() {
+            ui.data_mut(|d| d.insert_temp(id, text));
+            return Some(Msg::None);
+        }
+        ui.data_mut(|d| d.remove::<String>(id));
+        Some(match response.lost_focus() && text != name {
+            true => Msg::Rename(view.entity, text),
+            false => Msg::None,
+        })
+    }
+}
+
+/// Drag fields for translation, Euler angles and scale.
+struct TransformRenderer;
+
+impl ComponentRenderer for TransformRenderer {
+    fn matches(&self, type_path: &str) -> bool {
+        type_path == TRANSFORM
+    }
+
+    fn draw(&self, ui: &mut egui::Ui, view: &ComponentView) -> Option<Msg> {
+        if !view.editable {
+            draw_transform_values(ui, view.value)?;
+            return Some(Msg::None);
+        }
+        let id = ui.id().with(("transform", view.entity));
+        let (entity, component) = (view.entity, view.type_path.to_owned());
+        Some(match draw_transform(ui, id, view.value, view.snap)? {
+            TransformChange::None => Msg::None,
+            TransformChange::Dragging(value) => Msg::LiveInsert(entity, component, value),
+            TransformChange::Done(value) => Msg::Insert(entity, component, value),
+        })
+    }
+}
+
+/// A switch between the three variants.
+struct VisibilityRenderer;
+
+impl ComponentRenderer for VisibilityRenderer {
+    fn matches(&self, type_path: &str) -> bool {
+        is_bevy_type(type_path, "Visibility")
+    }
+
+    fn draw(&self, ui: &mut egui::Ui, view: &ComponentView) -> Option<Msg> {
+        const VARIANTS: [&str; 3] = ["Inherited", "Visible", "Hidden"];
+        let current = view.value.as_str().filter(|v| VARIANTS.contains(v))?;
+        let mut selected = current;
+        ui.add_enabled_ui(view.editable, |ui| {
+            ui.horizontal(|ui| {
+                for variant in VARIANTS {
+                    ui.selectable_value(&mut selected, variant, variant);
+                }
+            });
+        });
+        Some(match selected != current {
+            true => Msg::Insert(
+                view.entity,
+                view.type_path.to_owned(),
+                Value::String(selected.to_owned()),
+            ),
+            false => Msg::None,
+        })
+    }
+}
+
+/// Components that are just a handle: the asset, and the entities sharing it.
+struct HandleRenderer;
+
+/// Components wrapping a single asset handle.
+const HANDLE_WRAPPERS: [&str; 7] = [
+    "Handle",
+    "Mesh2d",
+    "Mesh3d",
+    "MeshMaterial2d",
+    "MeshMaterial3d",
+    "SceneRoot",
+    "AudioPlayer",
+];
+
+impl ComponentRenderer for HandleRenderer {
+    fn matches(&self, type_path: &str) -> bool {
+        HANDLE_WRAPPERS.contains(&short_name(type_path))
+    }
+
+    fn draw(&self, ui: &mut egui::Ui, view: &ComponentView) -> Option<Msg> {
+        let key = AssetKey::of(view.value)?;
+        let generic = view.type_path.split_once('<').map(|(_, rest)| rest);
+        let asset = generic.map(|rest| short_name(rest.trim_end_matches('>')));
+        let msg = ui.horizontal(|ui| {
+            if let Some(asset) = asset {
+                ui.weak(asset);
+            }
+            ui.monospace(key.label());
+            match ui.small_button("🔍 Entities using it").clicked() {
+                true => Msg::FindAssetUsers(key.clone()),
+                false => Msg::None,
+            }
+        });
+        Some(msg.inner)
+    }
+}
+
+/// A color picker for components holding just a color, like `BackgroundColor`.
+struct ColorRenderer;
+
+impl ComponentRenderer for ColorRenderer {
+    fn matches(&self, type_path: &str) -> bool {
+        short_name(type_path).ends_with("Color")
+    }
+
+    fn draw(&self, ui: &mut egui::Ui, view: &ComponentView) -> Option<Msg> {
+        if !is_color(view.value) {
+            return None;
+        }
+        let mut value = view.value.clone();
+        let changed = ui.add_enabled_ui(view.editable, |ui| edit_color(ui, &mut value));
+        Some(match changed.inner {
+            true => Msg::Insert(view.entity, view.type_path.to_owned(), value),
+            false => Msg::None,
+        })
+    }
+}
@@ -0,0 +1,196 @@
+use bevy::utils::HashMap;
+use egui::RichText;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+
+use crate::error::InspectorError;
+use crate::helper::{self, Endpoint};
+use crate::palette::status_colors;
+use crate::power;
+use crate::responses::{BrpResult, ResourceValue};
+use crate::summary::short_name;
+use crate::transport;
+use crate::value_format::value_format;
+
+/// Reads a single resource, added to the remote protocol in Bevy 0.16.
+pub const BRP_GET_RESOURCE_METHOD: &str = "bevy/get_resource";
+/// Sets a field of a resource, added with [`BRP_GET_RESOURCE_METHOD`].
+pub const BRP_MUTATE_RESOURCE_METHOD: &str = "bevy/mutate_resource";
+/// The clock game logic runs on, pausing it pauses the game without the companion plugin.
+pub const VIRTUAL_TIME_RESOURCE: &str = "bevy_time::time::Time<bevy_time::virt::Virtual>";
+
+/// Parameters of [`BRP_GET_RESOURCE_METHOD`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct GetResourceParams {
+    /// Type path of the resource.
+    pub resource: String,
+}
+
+/// Parameters of [`BRP_MUTATE_RESOURCE_METHOD`].
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct MutateResourceParams {
+    pub resource: String,
+    /// Reflection path of the field, e.g. `.context.paused`.
+    pub path: String,
+    pub value: Value,
+}
+
+/// Longest value shown in the status bar before it is cut off.
+const MAX_VALUE_LENGTH: usize = 40;
+
+/// Latest values of the resources the user watches, polled independently of the world.
+#[derive(Default)]
+pub struct ResourceWatch {
+    values: Arc<Mutex<HashMap<String, Result<Value, InspectorError>>>>,
+    in_flight: Arc<AtomicBool>,
+    new_resource: String,
+}
+
+impl ResourceWatch {
+    /// Requests all `resources` in one batch unless the previous poll is still running.
+    /// How often is up to the caller, see `RefreshScheduler`.
+    pub fn poll(&mut self, ctx: &egui::Context, url: &Endpoint, resources: &[String]) {
+        if resources.is_empty() || self.in_flight.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let calls: Vec<_> = resources
+            .iter()
+            .map(|resource| {
+                helper::create_request(
+                    Some(GetResourceParams {
+                        resource: resource.clone(),
+                    }),
+                    BRP_GET_RESOURCE_METHOD,
+                )
+            })
+            .collect();
+        let request = helper::make_batch_request(&calls, url);
+        let resources = resources.to_vec();
+        let values = self.values.clone();
+        let in_flight = self.in_flight.clone();
+        let egui_ctx = ctx.clone();
+        transport::fetch(request, move |response| {
+            in_flight.store(false, Ordering::SeqCst);
+            let results = helper::check_response(response)
+                .and_then(|response| helper::parse_batch(&response, &calls));
+            let mut values = values.lock().unwrap();
+            values.clear();
+            for (index, resource) in resources.into_iter().enumerate() {
+                let value = match &results {
+                    Ok(results) => results[index]
+                        .clone()
+                        .and_then(|result| ResourceValue::decode(result).map(|value| value.0)),
+                    Err(e) => Err(e.clone()),
+                };
+                values.insert(resource, value);
+            }
+            egui_ctx.request_repaint();
+        });
+    }
+
+    /// Shows the watched resources in a row, with a menu to add more. Removing a
+    /// resource is done from its context menu.
+    pub fn draw(&mut self, ui: &mut egui::Ui, watched: &mut Vec<String>, enabled: bool) {
+        ui.horizontal(|ui| {
+            let values = self.values.lock().unwrap();
+            let mut remove = None;
+            for (index, resource) in watched.iter().enumerate() {
+                let name = RichText::new(format!("{}:", short_name(resource))).strong();
+                let response = match values.get(resource) {
+                    Some(Ok(value)) => {
+                        let text = compact(ui.ctx(), value);
+                        ui.label(name).on_hover_text(resource.as_str());
+                        ui.label(RichText::new(text).monospace())
+                            .on_hover_text(serde_json::to_string_pretty(value).unwrap_or_default())
+                    }
+                    Some(Err(e)) => {
+                        ui.label(name).on_hover_text(resource.as_str());
+                        ui.label(RichText::new("?").color(status_colors(ui.ctx()).error))
+                            .on_hover_text(e.to_string())
+                    }
+                    None => {
+                        ui.label(name).on_hover_text(resource.as_str());
+                        power::spinner(ui)
+                    }
+                };
+                response.context_menu(|ui| {
+                    if ui.button("Stop watching").clicked() {
+                        remove = Some(index);
+                        ui.close_menu();
+                    }
+                });
+                ui.separator();
+            }
+            drop(values);
+            if let Some(index) = remove {
+                watched.remove(index);
+            }
+            ui.add_enabled_ui(enabled, |ui| {
+                ui.menu_button("➕ Watch resource", |ui| {
+                    ui.label("Resource type path");
+                    ui.text_edit_singleline(&mut self.new_resource);
+                    let path = self.new_resource.trim();
+                    if ui
+                        .add_enabled(!path.is_empty(), egui::Button::new("Watch"))
+                        .clicked()
+                    {
+                        if !watched.iter().any(|w| w == path) {
+                            watched.push(path.to_owned());
+                        }
+                        self.new_resource.clear();
+                        ui.close_menu();
+                    }
+                });
+            })
+            .response
+            .on_disabled_hover_text("The game doesn't support reading resources");
+        });
+    }
+
+    /// Latest value of a polled resource.
+    pub fn value(&self, resource: &str) -> Option<Value> {
+        self.values
+            .lock()
+            .unwrap()
+            .get(resource)?
+            .as_ref()
+            .ok()
+            .cloned()
+    }
+
+    /// The watched values as a read-only list, for the HUD.
+    pub fn draw_values(&self, ui: &mut egui::Ui, watched: &[String]) {
+        let values = self.values.lock().unwrap();
+        egui::Grid::new("hud_resources")
+            .num_columns(2)
+            .show(ui, |ui| {
+                for resource in watched {
+                    ui.label(RichText::new(short_name(resource)).strong());
+                    match values.get(resource) {
+                        Some(Ok(value)) => {
+                            ui.label(RichText::new(compact(ui.ctx(), value)).monospace())
+                        }
+                        Some(Err(_)) => ui.colored_label(status_colors(ui.ctx()).error, "?"),
+                        None => ui.weak("…"),
+                    };
+                    ui.end_row();
+                }
+            });
+    }
+}
+
+/// Single line form of a value in the value format, cut at [`MAX_VALUE_LENGTH`].
+fn compact(ctx: &egui::Context, value: &Value) -> String {
+    let text = match value {
+        Value::Array(array) if array.len() == 1 => value_format(ctx).text(&array[0]),
+        _ => value_format(ctx).text(value),
+    };
+    match text.char_indices().nth(MAX_VALUE_LENGTH) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    }
+}
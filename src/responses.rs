@@ -0,0 +1,508 @@
+//! Typed results of the builtin BRP methods. Each method answers with its own shape:
+//! rows for queries, type paths for lists, `null` for the ones only changing the world.
+
+use bevy::{
+    prelude::Entity,
+    remote::builtin_methods::{
+        BrpQueryRow, BRP_DESTROY_METHOD, BRP_GET_AND_WATCH_METHOD, BRP_GET_METHOD,
+        BRP_INSERT_METHOD, BRP_LIST_AND_WATCH_METHOD, BRP_LIST_METHOD, BRP_MUTATE_COMPONENT_METHOD,
+        BRP_QUERY_METHOD, BRP_REMOVE_METHOD, BRP_REPARENT_METHOD, BRP_SPAWN_METHOD,
+    },
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{Map, Value};
+
+use crate::error::InspectorError;
+use crate::resources::{BRP_GET_RESOURCE_METHOD, BRP_MUTATE_RESOURCE_METHOD};
+use crate::schema::BRP_REGISTRY_SCHEMA_METHOD;
+
+/// The result of one builtin method, decoded from the `result` of its response.
+pub trait BrpResult: Sized {
+    const METHOD: &'static str;
+
+    fn decode(result: Value) -> Result<Self, InspectorError>;
+}
+
+fn from_value<T: DeserializeOwned>(method: &str, result: Value) -> Result<T, InspectorError> {
+    serde_json::from_value(result)
+        .map_err(|e| InspectorError::Decode(format!("Unexpected {method} result: {e}")))
+}
+
+/// `bevy/query`: a row per matching entity.
+pub struct QueryRows(pub Vec<BrpQueryRow>);
+
+impl BrpResult for QueryRows {
+    const METHOD: &'static str = BRP_QUERY_METHOD;
+
+    fn decode(result: Value) -> Result<Self, InspectorError> {
+        from_value(Self::METHOD, result).map(Self)
+    }
+}
+
+/// `bevy/list`: the type paths of the registered components, or of the components of one
+/// entity.
+pub struct TypeList(pub Vec<String>);
+
+impl BrpResult for TypeList {
+    const METHOD: &'static str = BRP_LIST_METHOD;
+
+    fn decode(result: Value) -> Result<Self, InspectorError> {
+        from_value(Self::METHOD, result).map(Self)
+    }
+}
+
+/// `bevy/get`: the values found and, for lenient requests, why the others weren't.
+#[derive(Default)]
+pub struct Components {
+    pub components: Map<String, Value>,
+    pub errors: Map<String, Value>,
+}
+
+impl Components {
+    pub fn get(&self, type_path: &str) -> Option<&Value> {
+        self.components.get(type_path)
+    }
+}
+
+impl BrpResult for Components {
+    const METHOD: &'static str = BRP_GET_METHOD;
+
+    /// Depending on the remote version the result is either the component map itself or
+    /// wraps it in a `components` field, next to the `errors`.
+    fn decode(result: Value) -> Result<Self, InspectorError> {
+        let Value::Object(mut map) = result else {
+            return Err(InspectorError::Decode(format!(
+                "Unexpected {} result: {result}",
+                Self::METHOD
+            )));
+        };
+        let is_lenient = map.get("components").is_some_and(Value::is_object)
+            && map.keys().all(|key| key == "components" || key == "errors");
+        if !is_lenient {
+            return Ok(Self {
+                components: map,
+                errors: Map::new(),
+            });
+        }
+        let take = |map: &mut Map<String, Value>, key| match map.remove(key) {
+            Some(Value::Object(fields)) => fields,
+            _ => Map::new(),
+        };
+        Ok(Self {
+            components: take(&mut map, "components"),
+            errors: take(&mut map, "errors"),
+        })
+    }
+}
+
+/// `bevy/spawn`: the new entity.
+pub struct Spawned {
+    pub entity: Entity,
+}
+
+impl BrpResult for Spawned {
+    const METHOD: &'static str = BRP_SPAWN_METHOD;
+
+    fn decode(mut result: Value) -> Result<Self, InspectorError> {
+        let entity = result
+            .get_mut("entity")
+            .map(Value::take)
+            .unwrap_or_default();
+        from_value(Self::METHOD, entity).map(|entity| Self { entity })
+    }
+}
+
+/// `bevy/get+watch`: one result per change of the watched components, with the values
+/// that changed and the components removed since the last one.
+#[derive(Default)]
+pub struct WatchedComponents {
+    pub changed: Map<String, Value>,
+    pub removed: Vec<String>,
+}
+
+impl BrpResult for WatchedComponents {
+    const METHOD: &'static str = BRP_GET_AND_WATCH_METHOD;
+
+    /// Lenient results wrap the changed values in `components` next to the `errors`,
+    /// strict ones have them next to `removed`.
+    fn decode(result: Value) -> Result<Self, InspectorError> {
+        let Value::Object(mut map) = result else {
+            return Err(InspectorError::Decode(format!(
+                "Unexpected {} result: {result}",
+                Self::METHOD
+            )));
+        };
+        let removed = from_value(Self::METHOD, map.remove("removed").unwrap_or(Value::Null))
+            .map(Option::unwrap_or_default)?;
+        map.remove("errors");
+        let changed = match map.remove("components") {
+            Some(Value::Object(changed)) => changed,
+            Some(other) => {
+                return Err(InspectorError::Decode(format!(
+                    "Unexpected {} result: {other}",
+                    Self::METHOD
+                )))
+            }
+            None => map,
+        };
+        Ok(Self { changed, removed })
+    }
+}
+
+/// `bevy/list+watch`: components added to and removed from the entity since the last
+/// result.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+pub struct ListChanges {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+impl BrpResult for ListChanges {
+    const METHOD: &'static str = BRP_LIST_AND_WATCH_METHOD;
+
+    fn decode(result: Value) -> Result<Self, InspectorError> {
+        from_value(Self::METHOD, result)
+    }
+}
+
+/// `bevy/get_resource`: the value of the resource.
+pub struct ResourceValue(pub Value);
+
+impl BrpResult for ResourceValue {
+    const METHOD: &'static str = BRP_GET_RESOURCE_METHOD;
+
+    /// Released versions wrap it in `value`, development builds answered with the bare
+    /// value for a while.
+    fn decode(result: Value) -> Result<Self, InspectorError> {
+        match result {
+            Value::Object(mut map) if map.len() == 1 && map.contains_key("value") => {
+                Ok(Self(map.remove("value").unwrap_or_default()))
+            }
+            other => Ok(Self(other)),
+        }
+    }
+}
+
+/// `bevy/registry/schema`: a JSON schema per registered type path.
+pub struct RegistrySchemas(pub Map<String, Value>);
+
+impl BrpResult for RegistrySchemas {
+    const METHOD: &'static str = BRP_REGISTRY_SCHEMA_METHOD;
+
+    fn decode(result: Value) -> Result<Self, InspectorError> {
+        from_value(Self::METHOD, result).map(Self)
+    }
+}
+
+/// Methods that only change the world and answer with `null`.
+macro_rules! unit_results {
+    ($($(#[$doc:meta])* $name:ident => $method:expr,)*) => {$(
+        $(#[$doc])*
+        pub struct $name;
+
+        impl BrpResult for $name {
+            const METHOD: &'static str = $method;
+
+            fn decode(result: Value) -> Result<Self, InspectorError> {
+                match result {
+                    Value::Null => Ok(Self),
+                    other => Err(InspectorError::Decode(format!(
+                        "Unexpected {} result: {other}",
+                        Self::METHOD
+                    ))),
+                }
+            }
+        }
+    )*};
+}
+
+unit_results! {
+    /// `bevy/insert`.
+    Inserted => BRP_INSERT_METHOD,
+    /// `bevy/remove`.
+    Removed => BRP_REMOVE_METHOD,
+    /// `bevy/destroy`.
+    Destroyed => BRP_DESTROY_METHOD,
+    /// `bevy/reparent`.
+    Reparented => BRP_REPARENT_METHOD,
+    /// `bevy/mutate_component`.
+    Mutated => BRP_MUTATE_COMPONENT_METHOD,
+    /// `bevy/mutate_resource`.
+    ResourceMutated => BRP_MUTATE_RESOURCE_METHOD,
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::remote::{
+        builtin_methods::{
+            BrpDestroyParams, BrpGetParams, BrpInsertParams, BrpListParams, BrpMutateParams,
+            BrpQuery, BrpQueryFilter, BrpQueryParams, BrpRemoveParams, BrpReparentParams,
+            BrpSpawnParams,
+        },
+        BrpRequest,
+    };
+    use serde_json::json;
+
+    use super::*;
+    use crate::helper::create_request;
+    use crate::multi_get::get_call;
+    use crate::resources::{GetResourceParams, MutateResourceParams};
+    use crate::staging::{insert_call, restore_call};
+
+    /// Decodes `result` the way it comes out of a JSON-RPC response body.
+    fn round_trip<T: BrpResult>(result: Value) -> Result<T, InspectorError> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "result": result }).to_string();
+        let response: Value = serde_json::from_str(&body).unwrap();
+        T::decode(response["result"].clone())
+    }
+
+    /// Reads the params of `request` back the way the game does from the request body,
+    /// after checking it calls the method `T` decodes the result of.
+    fn params<T: BrpResult, P: DeserializeOwned>(request: BrpRequest) -> P {
+        let body = serde_json::to_string(&request).unwrap();
+        let request: BrpRequest = serde_json::from_str(&body).unwrap();
+        assert_eq!(request.method, T::METHOD);
+        serde_json::from_value(request.params.unwrap_or_default()).unwrap()
+    }
+
+    #[test]
+    fn query_rows() {
+        let query = BrpQueryParams {
+            data: BrpQuery {
+                components: vec!["a::A".to_owned()],
+                option: vec!["b::B".to_owned()],
+                has: Vec::new(),
+            },
+            filter: BrpQueryFilter {
+                without: vec!["c::C".to_owned()],
+                with: Vec::new(),
+            },
+        };
+        let sent: BrpQueryParams =
+            params::<QueryRows, _>(create_request(Some(query), QueryRows::METHOD));
+        assert_eq!(sent.data.components, ["a::A"]);
+        assert_eq!(sent.data.option, ["b::B"]);
+        assert_eq!(sent.filter.without, ["c::C"]);
+
+        let entity = Entity::from_raw(7);
+        let rows = round_trip::<QueryRows>(json!([{
+            "entity": entity,
+            "components": { "bevy_core::name::Name": "Player" },
+            "has": { "bevy_render::view::visibility::Visibility": true },
+        }]))
+        .unwrap()
+        .0;
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].entity, entity);
+        assert_eq!(rows[0].components["bevy_core::name::Name"], "Player");
+        assert_eq!(rows[0].has.len(), 1);
+        assert!(round_trip::<QueryRows>(json!({ "entity": entity })).is_err());
+    }
+
+    #[test]
+    fn type_list() {
+        let request = create_request::<BrpListParams>(None, TypeList::METHOD);
+        assert!(request.params.is_none());
+        let entity = Entity::from_raw(3);
+        let sent: BrpListParams = params::<TypeList, _>(create_request(
+            Some(BrpListParams { entity }),
+            TypeList::METHOD,
+        ));
+        assert_eq!(sent.entity, entity);
+
+        let list = round_trip::<TypeList>(json!(["a::A", "b::B"])).unwrap().0;
+        assert_eq!(list, ["a::A", "b::B"]);
+        assert!(round_trip::<TypeList>(Value::Null).is_err());
+    }
+
+    #[test]
+    fn components_in_both_shapes() {
+        let entity = Entity::from_raw(5);
+        let sent: BrpGetParams = params::<Components, _>(get_call(entity, &["a::A".to_owned()]));
+        assert_eq!(sent.entity, entity);
+        assert_eq!(sent.components, ["a::A"]);
+        assert!(!sent.strict);
+
+        let strict = round_trip::<Components>(json!({ "a::A": 1, "b::B": { "x": 2 } })).unwrap();
+        assert_eq!(strict.get("a::A"), Some(&json!(1)));
+        assert_eq!(strict.get("b::B"), Some(&json!({ "x": 2 })));
+        assert!(strict.errors.is_empty());
+
+        let lenient = round_trip::<Components>(json!({
+            "components": { "a::A": 1 },
+            "errors": { "b::B": { "code": -23402, "message": "missing" } },
+        }))
+        .unwrap();
+        assert_eq!(lenient.get("a::A"), Some(&json!(1)));
+        assert_eq!(lenient.components.len(), 1);
+        assert!(lenient.errors.contains_key("b::B"));
+
+        // A component that happens to be called `components` is not a wrapper.
+        let named =
+            round_trip::<Components>(json!({ "components": { "x": 1 }, "a::A": 2 })).unwrap();
+        assert_eq!(named.components.len(), 2);
+
+        assert!(round_trip::<Components>(json!([1, 2])).is_err());
+    }
+
+    #[test]
+    fn spawned() {
+        let components = [("a::A".to_owned(), json!(1))].into_iter().collect();
+        let sent: BrpSpawnParams = params::<Spawned, _>(create_request(
+            Some(BrpSpawnParams { components }),
+            Spawned::METHOD,
+        ));
+        assert_eq!(sent.components["a::A"], json!(1));
+
+        let entity = Entity::from_raw(42);
+        let spawned = round_trip::<Spawned>(json!({ "entity": entity })).unwrap();
+        assert_eq!(spawned.entity, entity);
+        assert!(round_trip::<Spawned>(json!({})).is_err());
+    }
+
+    #[test]
+    fn unit_results() {
+        let entity = Entity::from_raw(9);
+        let key = (entity, "a::A".to_owned());
+        let inserted: BrpInsertParams = params::<Inserted, _>(insert_call(&key, &json!(2)));
+        assert_eq!(inserted.entity, entity);
+        assert_eq!(inserted.components["a::A"], json!(2));
+        let removed: BrpRemoveParams = params::<Removed, _>(restore_call(&key, None));
+        assert_eq!(removed.components, ["a::A"]);
+        let destroyed: BrpDestroyParams = params::<Destroyed, _>(create_request(
+            Some(BrpDestroyParams { entity }),
+            Destroyed::METHOD,
+        ));
+        assert_eq!(destroyed.entity, entity);
+        let reparented: BrpReparentParams = params::<Reparented, _>(create_request(
+            Some(BrpReparentParams {
+                entities: vec![entity],
+                parent: None,
+            }),
+            Reparented::METHOD,
+        ));
+        assert_eq!(reparented.entities, [entity]);
+        assert_eq!(reparented.parent, None);
+
+        assert!(round_trip::<Inserted>(Value::Null).is_ok());
+        assert!(round_trip::<Removed>(Value::Null).is_ok());
+        assert!(round_trip::<Destroyed>(Value::Null).is_ok());
+        assert!(round_trip::<Reparented>(Value::Null).is_ok());
+        let error = round_trip::<Inserted>(json!({ "entity": 1 }))
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains(BRP_INSERT_METHOD));
+    }
+
+    #[test]
+    fn mutated() {
+        let entity = Entity::from_raw(4);
+        let sent: BrpMutateParams = params::<Mutated, _>(create_request(
+            Some(BrpMutateParams {
+                entity,
+                component: "a::A".to_owned(),
+                path: ".x".to_owned(),
+                value: json!(1.5),
+            }),
+            Mutated::METHOD,
+        ));
+        assert_eq!(sent.entity, entity);
+        assert_eq!(sent.path, ".x");
+        assert_eq!(sent.value, json!(1.5));
+        assert!(round_trip::<Mutated>(Value::Null).is_ok());
+        assert!(round_trip::<Mutated>(json!(1)).is_err());
+    }
+
+    #[test]
+    fn resources() {
+        let sent: GetResourceParams = params::<ResourceValue, _>(create_request(
+            Some(GetResourceParams {
+                resource: "a::Score".to_owned(),
+            }),
+            ResourceValue::METHOD,
+        ));
+        assert_eq!(sent.resource, "a::Score");
+        let value = round_trip::<ResourceValue>(json!({ "value": { "points": 3 } })).unwrap();
+        assert_eq!(value.0, json!({ "points": 3 }));
+        // The bare value of development builds.
+        let bare = round_trip::<ResourceValue>(json!({ "points": 3 })).unwrap();
+        assert_eq!(bare.0, json!({ "points": 3 }));
+
+        let sent: MutateResourceParams = params::<ResourceMutated, _>(create_request(
+            Some(MutateResourceParams {
+                resource: "a::Score".to_owned(),
+                path: ".points".to_owned(),
+                value: json!(4),
+            }),
+            ResourceMutated::METHOD,
+        ));
+        assert_eq!(sent.path, ".points");
+        assert_eq!(sent.value, json!(4));
+        assert!(round_trip::<ResourceMutated>(Value::Null).is_ok());
+    }
+
+    #[test]
+    fn registry_schemas() {
+        let request = create_request::<Value>(None, RegistrySchemas::METHOD);
+        assert!(request.params.is_none());
+        let schemas = round_trip::<RegistrySchemas>(json!({
+            "a::A": { "type": "object", "properties": {} },
+        }))
+        .unwrap();
+        assert!(schemas.0.contains_key("a::A"));
+        assert!(round_trip::<RegistrySchemas>(json!([])).is_err());
+    }
+
+    #[test]
+    fn watched_components_in_both_shapes() {
+        let entity = Entity::from_raw(8);
+        let sent: BrpGetParams = params::<WatchedComponents, _>(create_request(
+            Some(BrpGetParams {
+                entity,
+                components: vec!["a::A".to_owned()],
+                strict: false,
+            }),
+            WatchedComponents::METHOD,
+        ));
+        assert_eq!(sent.components, ["a::A"]);
+
+        let strict = round_trip::<WatchedComponents>(json!({
+            "components": { "a::A": 1 },
+            "removed": ["b::B"],
+        }))
+        .unwrap();
+        assert_eq!(strict.changed.get("a::A"), Some(&json!(1)));
+        assert_eq!(strict.removed, ["b::B"]);
+
+        let lenient = round_trip::<WatchedComponents>(json!({
+            "components": { "a::A": 2 },
+            "removed": [],
+            "errors": { "c::C": { "code": -23402, "message": "missing" } },
+        }))
+        .unwrap();
+        assert_eq!(lenient.changed.len(), 1);
+        assert!(lenient.removed.is_empty());
+
+        let bare = round_trip::<WatchedComponents>(json!({ "a::A": 3 })).unwrap();
+        assert_eq!(bare.changed.get("a::A"), Some(&json!(3)));
+        assert!(round_trip::<WatchedComponents>(json!({ "components": 1 })).is_err());
+    }
+
+    #[test]
+    fn list_changes() {
+        let entity = Entity::from_raw(6);
+        let sent: BrpListParams = params::<ListChanges, _>(create_request(
+            Some(BrpListParams { entity }),
+            ListChanges::METHOD,
+        ));
+        assert_eq!(sent.entity, entity);
+        let changes =
+            round_trip::<ListChanges>(json!({ "added": ["a::A"], "removed": ["b::B"] })).unwrap();
+        assert_eq!(changes.added, ["a::A"]);
+        assert_eq!(changes.removed, ["b::B"]);
+        let added = round_trip::<ListChanges>(json!({ "added": ["a::A"] })).unwrap();
+        assert!(added.removed.is_empty());
+        assert!(round_trip::<ListChanges>(json!(["a::A"])).is_err());
+    }
+}
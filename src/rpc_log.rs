@@ -0,0 +1,165 @@
+//! Console listing every JSON-RPC exchange with the game from the protocol trace, for
+//! debugging interop problems between the inspector and a game.
+
+use egui::RichText;
+
+use crate::palette::status_colors;
+use crate::trace::{self, Summary};
+
+/// Longest body shown when an exchange is expanded, the export has all of it.
+const SHOWN_BODY: usize = 256 * 1024;
+
+pub enum RpcLogAction {
+    None,
+    /// Export the exchanges numbered so, the shown ones.
+    Export(Vec<u64>),
+}
+
+/// Full bodies of the expanded exchange, pretty printed once.
+struct Expanded {
+    seq: u64,
+    request: String,
+    response: String,
+}
+
+/// Filter and expanded row of the console, with the listed exchanges cached until the
+/// trace changes.
+#[derive(Default)]
+pub struct RpcLog {
+    /// Shows methods containing this.
+    pub filter: String,
+    pub errors_only: bool,
+    summaries: Vec<Summary>,
+    seen: u64,
+    expanded: Option<Expanded>,
+}
+
+impl RpcLog {
+    /// Newest exchanges first, a click on one shows its full request and response.
+    pub fn draw(&mut self, ui: &mut egui::Ui) -> RpcLogAction {
+        let mut action = RpcLogAction::None;
+        if trace::last_seq() != self.seen || trace::len() != self.summaries.len() {
+            self.seen = trace::last_seq();
+            self.summaries = trace::summaries();
+        }
+        let filter = self.filter.to_lowercase();
+        let shown: Vec<&Summary> = self
+            .summaries
+            .iter()
+            .rev()
+            .filter(|summary| !self.errors_only || summary.error.is_some())
+            .filter(|summary| filter.is_empty() || summary.method.to_lowercase().contains(&filter))
+            .collect();
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.filter)
+                    .hint_text("Filter by method")
+                    .desired_width(160.0),
+            );
+            ui.checkbox(&mut self.errors_only, "Errors only");
+            ui.weak(format!("{} of {}", shown.len(), self.summaries.len()));
+            if ui
+                .add_enabled(!shown.is_empty(), egui::Button::new("Export..."))
+                .on_hover_text("Save the shown exchanges as JSON")
+                .clicked()
+            {
+                action = RpcLogAction::Export(shown.iter().map(|s| s.seq).collect());
+            }
+            if ui.button("Clear").clicked() {
+                trace::clear();
+                self.expanded = None;
+            }
+        });
+        let colors = status_colors(ui.ctx());
+        let mut toggle = None;
+        egui::ScrollArea::vertical()
+            .auto_shrink([false, false])
+            .show(ui, |ui| {
+                for summary in shown {
+                    let is_expanded = self.expanded.as_ref().map(|e| e.seq) == Some(summary.seq);
+                    let row = ui.horizontal(|ui| {
+                        ui.label(if is_expanded { "⏷" } else { "⏵" });
+                        match &summary.error {
+                            Some(error) => ui
+                                .colored_label(
+                                    colors.error,
+                                    RichText::new(&summary.method).strong(),
+                                )
+                                .on_hover_text(error),
+                            None => ui.strong(&summary.method),
+                        };
+                        ui.weak(format!("id {}", summary.id));
+                        ui.weak(format!("{:.1} ms", summary.duration.as_secs_f64() * 1000.0));
+                        ui.weak(format_size(summary.size));
+                        ui.add(
+                            egui::Label::new(RichText::new(&summary.preview).monospace().weak())
+                                .truncate(),
+                        );
+                    });
+                    let row = ui.interact(
+                        row.response.rect,
+                        ui.id().with(("rpc_row", summary.seq)),
+                        egui::Sense::click(),
+                    );
+                    if row.clicked() {
+                        toggle = Some(summary.seq);
+                    }
+                    if !is_expanded {
+                        continue;
+                    }
+                    let Some(expanded) = &self.expanded else {
+                        continue;
+                    };
+                    if let Some(error) = &summary.error {
+                        ui.colored_label(colors.error, error);
+                    }
+                    ui.collapsing("Request", |ui| {
+                        ui.label(RichText::new(&expanded.request).monospace());
+                    });
+                    egui::CollapsingHeader::new("Response")
+                        .id_salt(("rpc_response", summary.seq))
+                        .default_open(true)
+                        .show(ui, |ui| {
+                            ui.label(RichText::new(&expanded.response).monospace());
+                        });
+                    ui.separator();
+                }
+            });
+        if let Some(seq) = toggle {
+            let collapse = self.expanded.as_ref().is_some_and(|e| e.seq == seq);
+            self.expanded = match trace::bodies(seq) {
+                Some((request, response)) if !collapse => Some(Expanded {
+                    seq,
+                    request: shorten(request),
+                    response: shorten(response),
+                }),
+                _ => None,
+            };
+        }
+        action
+    }
+}
+
+fn shorten(mut text: String) -> String {
+    if text.len() <= SHOWN_BODY {
+        return text;
+    }
+    let mut end = SHOWN_BODY;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    let rest = text.len() - end;
+    text.truncate(end);
+    text.push_str(&format!(
+        "\n… {rest} more bytes, export the log for all of it"
+    ));
+    text
+}
+
+pub fn format_size(bytes: usize) -> String {
+    match bytes {
+        0..1024 => format!("{bytes} B"),
+        1024..1_048_576 => format!("{:.1} KB", bytes as f64 / 1024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
@@ -0,0 +1,136 @@
+//! Snapshots of live entities saved as scene files, for bug reports and inspecting a
+//! game state offline.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow, utils::HashMap};
+use serde_json::{json, Map, Value};
+
+use crate::compat::TypePaths;
+use crate::tree::EntityTree;
+
+/// The entities to save, `root` and everything below it or the whole world, by id.
+pub fn scene_entities<'a>(
+    rows: &'a HashMap<Entity, BrpQueryRow>,
+    paths: &TypePaths,
+    root: Option<Entity>,
+) -> BTreeMap<Entity, &'a BrpQueryRow> {
+    let Some(root) = root else {
+        return rows.iter().map(|(entity, row)| (*entity, row)).collect();
+    };
+    let tree = EntityTree::build(rows, paths);
+    let mut entities = BTreeMap::new();
+    let mut stack = vec![root];
+    while let Some(entity) = stack.pop() {
+        let Some(row) = rows.get(&entity) else {
+            continue;
+        };
+        if entities.insert(entity, row).is_none() {
+            stack.extend_from_slice(tree.children(&entity));
+        }
+    }
+    entities
+}
+
+/// Writes the entities as a Bevy scene when the path ends in `.ron`, as JSON otherwise.
+pub fn write_scene(path: &Path, entities: &BTreeMap<Entity, &BrpQueryRow>) -> anyhow::Result<()> {
+    let is_ron = path.extension().is_some_and(|extension| extension == "ron");
+    let text = if is_ron {
+        to_ron(entities)
+    } else {
+        let entities: Map<String, Value> = entities
+            .iter()
+            .map(|(entity, row)| {
+                let components = serde_json::to_value(&row.components).unwrap_or_default();
+                (
+                    entity.to_bits().to_string(),
+                    json!({ "components": components }),
+                )
+            })
+            .collect();
+        serde_json::to_string_pretty(&json!({ "entities": entities }))?
+    };
+    std::fs::write(path, text)?;
+    Ok(())
+}
+
+/// The layout of `DynamicScene` files. Component values keep the shape they have in
+/// BRP responses, objects become structs and arrays sequences.
+fn to_ron(entities: &BTreeMap<Entity, &BrpQueryRow>) -> String {
+    let mut out = String::from("(\n  resources: {},\n  entities: {\n");
+    for (entity, row) in entities {
+        let _ = writeln!(out, "    {}: (\n      components: {{", entity.to_bits());
+        let mut components: Vec<(&String, &Value)> = row.components.iter().collect();
+        components.sort_by_key(|(key, _)| *key);
+        for (key, value) in components {
+            let _ = write!(out, "        \"{key}\": ");
+            write_ron(&mut out, value, 8);
+            out.push_str(",\n");
+        }
+        out.push_str("      },\n    ),\n");
+    }
+    out.push_str("  },\n)\n");
+    out
+}
+
+fn write_ron(out: &mut String, value: &Value, indent: usize) {
+    let pad = " ".repeat(indent + 2);
+    match value {
+        Value::Null => out.push_str("None"),
+        Value::Bool(flag) => out.push_str(&flag.to_string()),
+        Value::Number(number) => match number.as_f64() {
+            Some(float) if !number.is_i64() && !number.is_u64() => {
+                let _ = write!(out, "{float:?}");
+            }
+            _ => out.push_str(&number.to_string()),
+        },
+        Value::String(text) => out.push_str(&Value::String(text.clone()).to_string()),
+        Value::Array(items) if items.iter().all(|item| !item.is_object()) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push_str(", ");
+                }
+                write_ron(out, item, indent);
+            }
+            out.push(']');
+        }
+        Value::Array(items) => {
+            out.push_str("[\n");
+            for item in items {
+                out.push_str(&pad);
+                write_ron(out, item, indent + 2);
+                out.push_str(",\n");
+            }
+            let _ = write!(out, "{}]", " ".repeat(indent));
+        }
+        Value::Object(fields) => {
+            // Field names become struct fields, other keys map keys.
+            let is_struct = fields.keys().all(|key| is_identifier(key));
+            out.push_str(if is_struct { "(\n" } else { "{\n" });
+            for (key, field) in fields {
+                out.push_str(&pad);
+                if is_struct {
+                    out.push_str(key);
+                } else {
+                    out.push_str(&Value::String(key.clone()).to_string());
+                }
+                out.push_str(": ");
+                write_ron(out, field, indent + 2);
+                out.push_str(",\n");
+            }
+            out.push_str(&" ".repeat(indent));
+            out.push(if is_struct { ')' } else { '}' });
+        }
+    }
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
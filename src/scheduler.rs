@@ -0,0 +1,234 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use web_time::{Duration, Instant};
+
+use bevy::prelude::Entity;
+use serde_json::Value;
+
+type Callback = Box<dyn FnOnce(ehttp::Result<ehttp::Response>) + Send>;
+
+/// Error queued requests are completed with when cancelled.
+pub const CANCELLED: &str = "Cancelled";
+
+struct Job {
+    info: QueuedRequest,
+    request: ehttp::Request,
+    on_done: Callback,
+}
+
+/// A request of the scheduler, as listed in the request queue panel.
+#[derive(Clone)]
+pub struct QueuedRequest {
+    pub id: u64,
+    pub method: String,
+    /// The entity the request is about, or the URL it goes to.
+    pub target: String,
+    pub queued: Instant,
+    /// When it went out, `None` while it's waiting.
+    pub sent: Option<Instant>,
+    /// Method and params, the same for requests doing the same thing. `None` for bodies
+    /// that aren't JSON or MessagePack.
+    key: Option<String>,
+}
+
+impl QueuedRequest {
+    fn of(id: u64, request: &ehttp::Request) -> Self {
+        let parsed = crate::helper::body_value(&request.body);
+        let key = parsed
+            .as_ref()
+            .map(|call| format!("{} {}", call["method"], call["params"]));
+        let call = parsed.unwrap_or_default();
+        let method = match &call {
+            Value::Array(calls) => format!("batch of {}", calls.len()),
+            call => call["method"].as_str().unwrap_or("?").to_owned(),
+        };
+        let params = &call["params"];
+        let entity = params["entity"]
+            .as_u64()
+            .or_else(|| params["entities"][0].as_u64())
+            .map(Entity::from_bits);
+        Self {
+            id,
+            method,
+            target: entity.map_or_else(|| request.url.clone(), |entity| entity.to_string()),
+            queued: Instant::now(),
+            sent: None,
+            key,
+        }
+    }
+}
+
+struct SchedulerState {
+    queue: VecDeque<Job>,
+    in_flight: Vec<QueuedRequest>,
+    max_in_flight: usize,
+    requests_per_second: f32,
+    last_sent: Option<Instant>,
+    next_id: u64,
+}
+
+/// Executes batch operations with a bounded number of requests in flight and a
+/// requests-per-second cap, so large batches don't flood the game's update loop.
+#[derive(Clone)]
+pub struct RequestScheduler {
+    state: Arc<Mutex<SchedulerState>>,
+}
+
+impl RequestScheduler {
+    pub fn new(max_in_flight: usize, requests_per_second: f32) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SchedulerState {
+                queue: VecDeque::new(),
+                in_flight: Vec::new(),
+                max_in_flight,
+                requests_per_second,
+                last_sent: None,
+                next_id: 0,
+            })),
+        }
+    }
+
+    pub fn set_limits(&self, max_in_flight: usize, requests_per_second: f32) {
+        let mut state = self.state.lock().unwrap();
+        state.max_in_flight = max_in_flight.max(1);
+        state.requests_per_second = requests_per_second.max(0.1);
+    }
+
+    pub fn enqueue(
+        &self,
+        request: ehttp::Request,
+        on_done: impl 'static + Send + FnOnce(ehttp::Result<ehttp::Response>),
+    ) {
+        self.push(request, Box::new(on_done), false);
+    }
+
+    /// Like [`RequestScheduler::enqueue`], unless the same call is already waiting or in
+    /// flight, e.g. a destroy clicked again before the game answered. Returns whether it
+    /// was queued.
+    pub fn enqueue_once(
+        &self,
+        request: ehttp::Request,
+        on_done: impl 'static + Send + FnOnce(ehttp::Result<ehttp::Response>),
+    ) -> bool {
+        self.push(request, Box::new(on_done), true)
+    }
+
+    fn push(&self, request: ehttp::Request, on_done: Callback, once: bool) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state.next_id += 1;
+        let info = QueuedRequest::of(state.next_id, &request);
+        let duplicate = info.key.is_some()
+            && state
+                .in_flight
+                .iter()
+                .chain(state.queue.iter().map(|job| &job.info))
+                .any(|other| other.key == info.key);
+        if once && duplicate {
+            return false;
+        }
+        state.queue.push_back(Job {
+            info,
+            request,
+            on_done,
+        });
+        drop(state);
+        self.pump();
+        true
+    }
+
+    /// Requests waiting to be sent plus the ones in flight.
+    pub fn pending(&self) -> usize {
+        let state = self.state.lock().unwrap();
+        state.queue.len() + state.in_flight.len()
+    }
+
+    /// The requests in flight, then the waiting ones in the order they go out.
+    pub fn requests(&self) -> Vec<QueuedRequest> {
+        let state = self.state.lock().unwrap();
+        state
+            .in_flight
+            .iter()
+            .cloned()
+            .chain(state.queue.iter().map(|job| job.info.clone()))
+            .collect()
+    }
+
+    /// Drops a waiting request, its callback gets a [`CANCELLED`] error. Requests in
+    /// flight can't be taken back.
+    pub fn cancel(&self, id: u64) {
+        let mut state = self.state.lock().unwrap();
+        let Some(index) = state.queue.iter().position(|job| job.info.id == id) else {
+            return;
+        };
+        let job = state.queue.remove(index);
+        drop(state);
+        if let Some(job) = job {
+            (job.on_done)(Err(CANCELLED.to_owned()));
+        }
+    }
+
+    pub fn cancel_all(&self) {
+        let jobs: Vec<Job> = self.state.lock().unwrap().queue.drain(..).collect();
+        for job in jobs {
+            (job.on_done)(Err(CANCELLED.to_owned()));
+        }
+    }
+
+    /// Sends every waiting request right away, regardless of the limits.
+    pub fn flush(&self) {
+        loop {
+            let mut state = self.state.lock().unwrap();
+            let Some(job) = state.queue.pop_front() else {
+                return;
+            };
+            drop(state);
+            self.send(job);
+        }
+    }
+
+    /// Sends as many queued requests as the limits allow. Returns how long to wait before
+    /// the next queued request may go out, `None` when nothing is waiting.
+    pub fn pump(&self) -> Option<Duration> {
+        loop {
+            let mut state = self.state.lock().unwrap();
+            if state.queue.is_empty() {
+                return None;
+            }
+            if state.in_flight.len() >= state.max_in_flight {
+                return Some(Duration::from_millis(50));
+            }
+            let interval = Duration::from_secs_f32(1.0 / state.requests_per_second);
+            if let Some(wait) = state
+                .last_sent
+                .and_then(|last| interval.checked_sub(last.elapsed()))
+            {
+                return Some(wait);
+            }
+            let job = state.queue.pop_front()?;
+            drop(state);
+            self.send(job);
+        }
+    }
+
+    fn send(&self, mut job: Job) {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        state.last_sent = Some(now);
+        job.info.sent = Some(now);
+        state.in_flight.push(job.info.clone());
+        drop(state);
+
+        let scheduler = self.clone();
+        let id = job.info.id;
+        crate::transport::fetch(job.request, move |response| {
+            scheduler
+                .state
+                .lock()
+                .unwrap()
+                .in_flight
+                .retain(|info| info.id != id);
+            (job.on_done)(response);
+            scheduler.pump();
+        });
+    }
+}
@@ -0,0 +1,580 @@
+//! Forms generated from the JSON schemas of the remote type registry.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use bevy::utils::HashMap;
+use egui::RichText;
+use serde_json::{Map, Value};
+
+use crate::random::{self, Rng};
+use crate::responses::RegistrySchemas;
+use crate::summary::short_name;
+use crate::widgets::{edit_color, edit_vector, is_color, is_vector};
+
+/// Returns the JSON schema of every registered type, added in Bevy 0.16.
+pub const BRP_REGISTRY_SCHEMA_METHOD: &str = "bevy/registry/schema";
+
+/// Where the type under the pointer is remembered for the type docs window.
+const DOCS_TARGET_ID: &str = "type_docs_target";
+
+/// Types nest deeper than this only in recursive definitions, which are left empty.
+const MAX_DEPTH: usize = 8;
+
+/// Schemas of the remote registry by type path.
+#[derive(Default)]
+pub struct Schemas(HashMap<String, Value>);
+
+impl Schemas {
+    pub fn from_response(result: RegistrySchemas) -> Self {
+        Self(result.0.into_iter().collect())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn type_paths(&self) -> impl Iterator<Item = &String> {
+        self.0.keys()
+    }
+
+    /// Writes the registered components in `types` and the schemas as one JSON file,
+    /// sorted so exports of the same build compare equal.
+    pub fn export(&self, path: &Path, types: &[String]) -> anyhow::Result<()> {
+        let mut types = types.to_vec();
+        types.sort();
+        let schemas: BTreeMap<&String, &Value> = self.0.iter().collect();
+        let registry = serde_json::json!({ "types": types, "schemas": schemas });
+        std::fs::write(path, serde_json::to_string_pretty(&registry)?)?;
+        Ok(())
+    }
+
+    pub fn contains(&self, type_path: &str) -> bool {
+        self.0.contains_key(type_path)
+    }
+
+    /// Whether the type reflects `Serialize` and `Deserialize`, `None` without a schema.
+    pub fn is_serializable(&self, type_path: &str) -> Option<bool> {
+        let reflected = self.0.get(type_path)?["reflectTypes"].as_array()?;
+        let has = |name: &str| reflected.iter().any(|r| r.as_str() == Some(name));
+        Some(has("Serialize") && has("Deserialize"))
+    }
+
+    /// A value of the type with every field filled in, using the schema's `default` where
+    /// it has one.
+    pub fn default_value(&self, type_path: &str) -> Value {
+        self.default_at(type_path, 0)
+    }
+
+    fn default_at(&self, type_path: &str, depth: usize) -> Value {
+        let Some(schema) = self.0.get(type_path) else {
+            return primitive_default(type_path);
+        };
+        if depth > MAX_DEPTH {
+            return Value::Null;
+        }
+        if let Some(default) = schema.get("default") {
+            return default.clone();
+        }
+        match schema["kind"].as_str().unwrap_or_default() {
+            "Struct" => Value::Object(self.default_fields(schema, depth)),
+            "TupleStruct" | "Tuple" => {
+                let mut items = self.default_items(schema, depth);
+                // Newtypes are serialized as their only field.
+                if schema["kind"] == "TupleStruct" && items.len() == 1 {
+                    items.remove(0)
+                } else {
+                    Value::Array(items)
+                }
+            }
+            "Enum" => match schema["oneOf"].get(0) {
+                Some(variant) => self.default_variant(variant, depth),
+                None => Value::Null,
+            },
+            "List" | "Array" | "Set" => Value::Array(Vec::new()),
+            "Map" => Value::Object(Map::new()),
+            _ => scalar_default(schema["type"].as_str().unwrap_or_default()),
+        }
+    }
+
+    fn default_fields(&self, schema: &Value, depth: usize) -> Map<String, Value> {
+        let Some(properties) = schema["properties"].as_object() else {
+            return Map::new();
+        };
+        properties
+            .iter()
+            .map(|(name, property)| {
+                let value = match property.get("default") {
+                    Some(default) => default.clone(),
+                    None => self.default_at(reference(property), depth + 1),
+                };
+                (name.clone(), value)
+            })
+            .collect()
+    }
+
+    fn default_items(&self, schema: &Value, depth: usize) -> Vec<Value> {
+        schema["prefixItems"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|item| self.default_at(reference(item), depth + 1))
+            .collect()
+    }
+
+    fn default_variant(&self, variant: &Value, depth: usize) -> Value {
+        let name = variant_name(variant).to_owned();
+        match variant["kind"].as_str() {
+            Some("Struct") => {
+                let fields = Value::Object(self.default_fields(variant, depth));
+                Value::Object(Map::from_iter([(name, fields)]))
+            }
+            Some("Tuple") => {
+                let mut items = self.default_items(variant, depth);
+                let payload = if items.len() == 1 {
+                    items.remove(0)
+                } else {
+                    Value::Array(items)
+                };
+                Value::Object(Map::from_iter([(name, payload)]))
+            }
+            _ => Value::String(name),
+        }
+    }
+
+    /// A value of the type with every field filled in randomly, numbers within
+    /// [`random::BOUND`] and lists with at most [`random::MAX_ITEMS`] items.
+    pub fn random_value(&self, type_path: &str, rng: &mut Rng) -> Value {
+        self.random_at(type_path, rng, 0)
+    }
+
+    fn random_at(&self, type_path: &str, rng: &mut Rng, depth: usize) -> Value {
+        let Some(schema) = self.0.get(type_path) else {
+            return random::primitive(type_path, rng);
+        };
+        if depth > MAX_DEPTH {
+            return Value::Null;
+        }
+        match schema["kind"].as_str().unwrap_or_default() {
+            "Struct" => Value::Object(self.random_fields(schema, rng, depth)),
+            "TupleStruct" | "Tuple" => {
+                let mut items = self.random_items(schema, rng, depth);
+                if schema["kind"] == "TupleStruct" && items.len() == 1 {
+                    items.remove(0)
+                } else {
+                    Value::Array(items)
+                }
+            }
+            "Enum" => {
+                let variants = schema["oneOf"].as_array().cloned().unwrap_or_default();
+                match rng.pick(&variants) {
+                    Some(variant) => self.random_variant(variant, rng, depth),
+                    None => Value::Null,
+                }
+            }
+            "List" | "Set" => {
+                let count = rng.below(random::MAX_ITEMS + 1);
+                let item = reference(&schema["items"]);
+                let items = (0..count).map(|_| self.random_at(item, rng, depth + 1));
+                Value::Array(items.collect())
+            }
+            // Fixed size arrays keep the length of their default.
+            "Array" => match self.default_at(type_path, depth) {
+                Value::Array(items) => {
+                    let item = reference(&schema["items"]);
+                    let items = items.iter().map(|_| self.random_at(item, rng, depth + 1));
+                    Value::Array(items.collect())
+                }
+                other => random::like(&other, rng),
+            },
+            "Map" => {
+                let count = rng.below(random::MAX_ITEMS + 1);
+                let key = reference(&schema["keyType"]);
+                let value = reference(&schema["valueType"]);
+                let entries = (0..count).filter_map(|_| {
+                    let key = match self.random_at(key, rng, depth + 1) {
+                        Value::String(key) => key,
+                        Value::Number(key) => key.to_string(),
+                        _ => return None,
+                    };
+                    Some((key, self.random_at(value, rng, depth + 1)))
+                });
+                Value::Object(entries.collect())
+            }
+            _ => random::scalar(schema["type"].as_str().unwrap_or_default(), rng),
+        }
+    }
+
+    fn random_fields(&self, schema: &Value, rng: &mut Rng, depth: usize) -> Map<String, Value> {
+        let Some(properties) = schema["properties"].as_object() else {
+            return Map::new();
+        };
+        properties
+            .iter()
+            .map(|(name, property)| {
+                let value = self.random_at(reference(property), rng, depth + 1);
+                (name.clone(), value)
+            })
+            .collect()
+    }
+
+    fn random_items(&self, schema: &Value, rng: &mut Rng, depth: usize) -> Vec<Value> {
+        schema["prefixItems"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|item| self.random_at(reference(item), rng, depth + 1))
+            .collect()
+    }
+
+    fn random_variant(&self, variant: &Value, rng: &mut Rng, depth: usize) -> Value {
+        let name = variant_name(variant).to_owned();
+        match variant["kind"].as_str() {
+            Some("Struct") => {
+                let fields = Value::Object(self.random_fields(variant, rng, depth));
+                Value::Object(Map::from_iter([(name, fields)]))
+            }
+            Some("Tuple") => {
+                let mut items = self.random_items(variant, rng, depth);
+                let payload = if items.len() == 1 {
+                    items.remove(0)
+                } else {
+                    Value::Array(items)
+                };
+                Value::Object(Map::from_iter([(name, payload)]))
+            }
+            _ => Value::String(name),
+        }
+    }
+
+    /// A random value for `type_path`, shaped like `current` when the game doesn't
+    /// provide a schema for it.
+    pub fn randomize(&self, type_path: &str, current: &Value, rng: &mut Rng) -> Value {
+        if self.contains(type_path) {
+            self.random_value(type_path, rng)
+        } else {
+            random::like(current, rng)
+        }
+    }
+
+    /// Draws a form editing `value` as a `type_path`, falling back to a JSON text box for
+    /// types without a usable schema. Colors get a picker and vectors a drag value per
+    /// axis whatever their schema says.
+    pub fn edit(&self, ui: &mut egui::Ui, id: egui::Id, type_path: &str, value: &mut Value) {
+        self.edit_at(ui, id, type_path, value, 0);
+    }
+
+    fn edit_at(
+        &self,
+        ui: &mut egui::Ui,
+        id: egui::Id,
+        type_path: &str,
+        value: &mut Value,
+        depth: usize,
+    ) {
+        if is_color(value) {
+            edit_color(ui, value);
+            return;
+        }
+        if is_vector(value) {
+            edit_vector(ui, value);
+            return;
+        }
+        let schema = self.0.get(type_path);
+        let kind = schema.and_then(|s| s["kind"].as_str()).unwrap_or_default();
+        match (schema, kind) {
+            (Some(schema), "Struct") if value.is_object() && depth <= MAX_DEPTH => {
+                self.edit_fields(ui, id, schema, value, depth);
+            }
+            (Some(schema), "TupleStruct")
+                if schema["prefixItems"].as_array().map(Vec::len) == Some(1) =>
+            {
+                let inner = reference(&schema["prefixItems"][0]);
+                self.edit_at(ui, id, inner, value, depth + 1);
+            }
+            (Some(schema), "Enum") if depth <= MAX_DEPTH => {
+                self.edit_enum(ui, id, schema, value, depth);
+            }
+            (Some(schema), "Value") => edit_scalar(ui, id, schema["type"].as_str(), value),
+            (None, _) if value.is_number() || value.is_boolean() || value.is_string() => {
+                edit_scalar(ui, id, None, value);
+            }
+            _ => edit_json(ui, id, value),
+        }
+    }
+
+    fn edit_fields(
+        &self,
+        ui: &mut egui::Ui,
+        id: egui::Id,
+        schema: &Value,
+        value: &mut Value,
+        depth: usize,
+    ) {
+        let Some(properties) = schema["properties"].as_object() else {
+            return;
+        };
+        let Some(fields) = value.as_object_mut() else {
+            return;
+        };
+        egui::Grid::new(id).num_columns(2).show(ui, |ui| {
+            for (name, property) in properties {
+                let field = fields
+                    .entry(name.clone())
+                    .or_insert_with(|| self.default_at(reference(property), depth + 1));
+                let label = ui
+                    .label(RichText::new(name).strong())
+                    .on_hover_text(reference(property));
+                if label.hovered() {
+                    set_docs_target(ui.ctx(), reference(property));
+                }
+                ui.vertical(|ui| {
+                    self.edit_at(ui, id.with(name), reference(property), field, depth + 1);
+                });
+                ui.end_row();
+            }
+        });
+    }
+
+    fn edit_enum(
+        &self,
+        ui: &mut egui::Ui,
+        id: egui::Id,
+        schema: &Value,
+        value: &mut Value,
+        depth: usize,
+    ) {
+        let variants = schema["oneOf"].as_array().cloned().unwrap_or_default();
+        let current = match value {
+            Value::String(name) => name.clone(),
+            Value::Object(map) => map.keys().next().cloned().unwrap_or_default(),
+            _ => String::new(),
+        };
+        egui::ComboBox::from_id_salt(id)
+            .selected_text(&current)
+            .show_ui(ui, |ui| {
+                for variant in &variants {
+                    let name = variant_name(variant);
+                    if ui.selectable_label(name == current, name).clicked() && name != current {
+                        *value = self.default_variant(variant, depth);
+                    }
+                }
+            });
+        let Value::Object(map) = value else {
+            return;
+        };
+        let Some((name, payload)) = map.iter_mut().next() else {
+            return;
+        };
+        let Some(variant) = variants.iter().find(|v| variant_name(v) == name) else {
+            return;
+        };
+        let items = variant["prefixItems"].as_array().map_or(0, Vec::len);
+        if variant["kind"] == "Tuple" && items == 1 {
+            let inner = reference(&variant["prefixItems"][0]);
+            self.edit_at(ui, id.with(name), inner, payload, depth + 1);
+        } else if variant["kind"] == "Struct" {
+            self.edit_fields(ui, id.with(name), variant, payload, depth + 1);
+        } else {
+            edit_json(ui, id.with(name), payload);
+        }
+    }
+}
+
+/// Shows the schema of `type_path` as a reference: its docs, fields or variants and the
+/// types they have. Clicking a field type shows that type instead.
+pub fn draw_docs(ui: &mut egui::Ui, schemas: &Schemas, type_path: &str) {
+    ui.label(RichText::new(short_name(type_path)).heading());
+    ui.label(RichText::new(type_path).weak().monospace());
+    let Some(schema) = schemas.0.get(type_path) else {
+        ui.label("The game didn't provide a schema for this type.");
+        return;
+    };
+    if let Some(kind) = schema["kind"].as_str() {
+        ui.label(format!("Kind: {kind}"));
+    }
+    if let Some(description) = schema["description"].as_str() {
+        ui.label(description);
+    }
+    ui.separator();
+    let type_link = |ui: &mut egui::Ui, target: &str| {
+        if ui.link(short_name(target)).on_hover_text(target).clicked() {
+            set_docs_target(ui.ctx(), target);
+        }
+    };
+    if let Some(properties) = schema["properties"].as_object() {
+        egui::Grid::new("type_docs_fields")
+            .striped(true)
+            .show(ui, |ui| {
+                for (name, property) in properties {
+                    let response = ui.label(RichText::new(name).strong());
+                    if let Some(description) = property["description"].as_str() {
+                        response.on_hover_text(description);
+                    }
+                    type_link(ui, reference(property));
+                    ui.end_row();
+                }
+            });
+    }
+    if let Some(items) = schema["prefixItems"].as_array() {
+        for (index, item) in items.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(index.to_string()).strong());
+                type_link(ui, reference(item));
+            });
+        }
+    }
+    if let Some(variants) = schema["oneOf"].as_array() {
+        ui.label(RichText::new("Variants").strong());
+        for variant in variants {
+            let fields: Vec<&str> = variant["properties"]
+                .as_object()
+                .map(|p| p.keys().map(|k| k.as_str()).collect())
+                .unwrap_or_default();
+            let items: Vec<&str> = variant["prefixItems"]
+                .as_array()
+                .map(|p| p.iter().map(|i| short_name(reference(i))).collect())
+                .unwrap_or_default();
+            let shape = if !fields.is_empty() {
+                format!(" {{ {} }}", fields.join(", "))
+            } else if !items.is_empty() {
+                format!("({})", items.join(", "))
+            } else {
+                String::new()
+            };
+            ui.label(format!("{}{shape}", variant_name(variant)));
+        }
+    }
+    for key in ["items", "keyType", "valueType"] {
+        let target = reference(&schema[key]);
+        if !target.is_empty() {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(key).strong());
+                type_link(ui, target);
+            });
+        }
+    }
+}
+
+/// Makes `type_path` the type shown in the type docs window.
+pub fn set_docs_target(ctx: &egui::Context, type_path: &str) {
+    if type_path.is_empty() {
+        return;
+    }
+    ctx.data_mut(|d| d.insert_temp(egui::Id::new(DOCS_TARGET_ID), type_path.to_owned()));
+}
+
+pub fn docs_target(ctx: &egui::Context) -> Option<String> {
+    ctx.data(|d| d.get_temp(egui::Id::new(DOCS_TARGET_ID)))
+}
+
+/// The type path a property or item schema refers to through `{"type": {"$ref": ...}}`.
+fn reference(schema: &Value) -> &str {
+    schema["type"]["$ref"]
+        .as_str()
+        .or(schema["$ref"].as_str())
+        .unwrap_or_default()
+        .trim_start_matches("#/$defs/")
+}
+
+fn variant_name(variant: &Value) -> &str {
+    variant["shortPath"]
+        .as_str()
+        .or(variant.as_str())
+        .unwrap_or_default()
+}
+
+fn scalar_default(schema_type: &str) -> Value {
+    match schema_type {
+        "float" => 0.0.into(),
+        "uint" | "int" => 0.into(),
+        "boolean" => false.into(),
+        "string" => "".into(),
+        _ => Value::Null,
+    }
+}
+
+/// Defaults for primitives the registry may not list schemas for.
+fn primitive_default(type_path: &str) -> Value {
+    match type_path {
+        "f32" | "f64" => 0.0.into(),
+        "u8" | "u16" | "u32" | "u64" | "usize" | "i8" | "i16" | "i32" | "i64" | "isize" => 0.into(),
+        "bool" => false.into(),
+        "alloc::string::String" | "str" | "char" => "".into(),
+        _ => Value::Null,
+    }
+}
+
+fn edit_scalar(ui: &mut egui::Ui, id: egui::Id, schema_type: Option<&str>, value: &mut Value) {
+    let schema_type = schema_type.unwrap_or(match value {
+        Value::Number(n) if n.is_f64() => "float",
+        Value::Number(n) if n.is_u64() => "uint",
+        Value::Number(_) => "int",
+        Value::Bool(_) => "boolean",
+        Value::String(_) => "string",
+        _ => "",
+    });
+    match schema_type {
+        "float" => {
+            let mut number = value.as_f64().unwrap_or_default();
+            if ui
+                .add(egui::DragValue::new(&mut number).speed(0.1))
+                .changed()
+            {
+                *value = number.into();
+            }
+        }
+        "uint" => {
+            let mut number = value.as_u64().unwrap_or_default();
+            if ui.add(egui::DragValue::new(&mut number)).changed() {
+                *value = number.into();
+            }
+        }
+        "int" => {
+            let mut number = value.as_i64().unwrap_or_default();
+            if ui.add(egui::DragValue::new(&mut number)).changed() {
+                *value = number.into();
+            }
+        }
+        "boolean" => {
+            let mut flag = value.as_bool().unwrap_or_default();
+            if ui.checkbox(&mut flag, "").changed() {
+                *value = flag.into();
+            }
+        }
+        "string" => {
+            let mut text = value.as_str().unwrap_or_default().to_owned();
+            if ui.text_edit_singleline(&mut text).changed() {
+                *value = text.into();
+            }
+        }
+        _ => edit_json(ui, id, value),
+    }
+}
+
+/// Drops the JSON text kept for the form with `id`, so it shows its value again after the
+/// value was replaced from outside the form.
+pub fn forget_text(ctx: &egui::Context, id: egui::Id) {
+    ctx.data_mut(|d| d.remove::<String>(id.with("json")));
+}
+
+/// Raw JSON editor for anything the form can't represent. The text is kept in egui's
+/// memory so it survives frames in which it doesn't parse.
+fn edit_json(ui: &mut egui::Ui, id: egui::Id, value: &mut Value) {
+    let id = id.with("json");
+    let mut text = ui
+        .data_mut(|d| d.get_temp::<String>(id))
+        .unwrap_or_else(|| serde_json::to_string_pretty(value).unwrap_or_default());
+    let response = ui.add(
+        egui::TextEdit::multiline(&mut text)
+            .code_editor()
+            .desired_rows(1)
+            .desired_width(f32::INFINITY),
+    );
+    if response.changed() {
+        if let Ok(parsed) = serde_json::from_str(&text) {
+            *value = parsed;
+        }
+    }
+    ui.data_mut(|d| d.insert_temp(id, text));
+}
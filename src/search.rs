@@ -0,0 +1,267 @@
+//! The search bar above the entity tree, matching entity names, ids and component types.
+
+use std::collections::HashSet;
+
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow, utils::HashMap};
+use egui::RichText;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::changes::{ChangeLog, RECENT_WINDOW};
+use crate::compat::TypePaths;
+use crate::completion::type_path_input;
+use crate::problems::parent_of;
+use crate::summary::short_name;
+
+#[derive(Default)]
+pub struct EntitySearch {
+    pub text: String,
+    /// Match `text` as a regular expression instead of a substring.
+    pub regex: bool,
+    pub case_sensitive: bool,
+    /// Only entities with a component whose type path or short name contains this.
+    pub has_component: String,
+    /// Only entities that changed in the last fetch, showing only their changed
+    /// components.
+    pub changed_only: bool,
+    /// Only entities that showed up in the last [`RECENT_WINDOW`].
+    pub recently_spawned: bool,
+    /// Only entities with components changed in the last [`RECENT_WINDOW`].
+    pub recently_changed: bool,
+    /// Give the text field keyboard focus on the next draw.
+    pub focus_requested: bool,
+}
+
+/// Entities to draw while searching.
+pub struct SearchResult {
+    pub matches: HashSet<Entity>,
+    /// Ancestors of matches, drawn expanded so the matches below them are visible.
+    pub ancestors: HashSet<Entity>,
+}
+
+impl SearchResult {
+    pub fn shows(&self, entity: &Entity) -> bool {
+        self.matches.contains(entity) || self.ancestors.contains(entity)
+    }
+}
+
+/// The search fields of [`EntitySearch`], saved with view presets.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct SearchFilters {
+    pub text: String,
+    pub regex: bool,
+    pub case_sensitive: bool,
+    pub has_component: String,
+    pub changed_only: bool,
+    pub recently_spawned: bool,
+    pub recently_changed: bool,
+}
+
+/// The text and component filters as params of
+/// [`INSPECTOR_SEARCH_METHOD`](crate::companion::INSPECTOR_SEARCH_METHOD), matched by the
+/// game against all of its entities and components.
+#[derive(Serialize, Clone, PartialEq, Debug)]
+pub struct RemoteSearch {
+    pub text: String,
+    pub component: String,
+    pub case_sensitive: bool,
+}
+
+enum Pattern {
+    Substring(String),
+    Regex(Regex),
+}
+
+impl EntitySearch {
+    pub fn filters(&self) -> SearchFilters {
+        SearchFilters {
+            text: self.text.clone(),
+            regex: self.regex,
+            case_sensitive: self.case_sensitive,
+            has_component: self.has_component.clone(),
+            changed_only: self.changed_only,
+            recently_spawned: self.recently_spawned,
+            recently_changed: self.recently_changed,
+        }
+    }
+
+    pub fn set_filters(&mut self, filters: SearchFilters) {
+        self.text = filters.text;
+        self.regex = filters.regex;
+        self.case_sensitive = filters.case_sensitive;
+        self.has_component = filters.has_component;
+        self.changed_only = filters.changed_only;
+        self.recently_spawned = filters.recently_spawned;
+        self.recently_changed = filters.recently_changed;
+    }
+
+    pub fn is_active(&self) -> bool {
+        !self.text.is_empty()
+            || !self.has_component.is_empty()
+            || self.changed_only
+            || self.recently_spawned
+            || self.recently_changed
+    }
+
+    /// The search fields and filter chips. `destroyed` are the entities gone in the last
+    /// [`RECENT_WINDOW`], they can't be shown in the tree anymore so they're only counted.
+    pub fn draw(
+        &mut self,
+        ui: &mut egui::Ui,
+        error: Option<&str>,
+        destroyed: &[Entity],
+        types: &[String],
+    ) -> egui::Response {
+        ui.horizontal(|ui| {
+            ui.label("🔍");
+            let text = ui.add(
+                egui::TextEdit::singleline(&mut self.text)
+                    .hint_text("Name, id or component")
+                    .desired_width(200.0),
+            );
+            if std::mem::take(&mut self.focus_requested) {
+                text.request_focus();
+            }
+            ui.toggle_value(&mut self.regex, ".*")
+                .on_hover_text("Match as a regular expression");
+            ui.toggle_value(&mut self.case_sensitive, "Aa")
+                .on_hover_text("Case sensitive");
+            type_path_input(
+                ui,
+                "has_component",
+                &mut self.has_component,
+                types,
+                "Has component",
+                140.0,
+            );
+            ui.toggle_value(&mut self.changed_only, "Δ Changed")
+                .on_hover_text("Only what changed in the last fetch");
+            let window = RECENT_WINDOW.as_secs();
+            ui.toggle_value(&mut self.recently_spawned, format!("✨ {window}s"))
+                .on_hover_text(format!("Only entities spawned in the last {window}s"));
+            ui.toggle_value(&mut self.recently_changed, format!("Δ {window}s"))
+                .on_hover_text(format!("Only entities changed in the last {window}s"));
+            if !destroyed.is_empty() {
+                let ids: Vec<String> = destroyed.iter().map(Entity::to_string).collect();
+                ui.weak(format!("† {}", destroyed.len()))
+                    .on_hover_text(format!(
+                        "Destroyed in the last {window}s: {}",
+                        ids.join(", ")
+                    ));
+            }
+            if self.is_active() && ui.small_button("✖").on_hover_text("Clear").clicked() {
+                *self = Self::default();
+            }
+            if let Some(error) = error {
+                ui.label(RichText::new(error).color(ui.visuals().error_fg_color));
+            }
+        })
+        .response
+    }
+
+    /// The part of the search the game can run, `None` when there is no text or component
+    /// to look for or the text is a regular expression.
+    pub fn remote(&self) -> Option<RemoteSearch> {
+        let searching = !self.text.is_empty() || !self.has_component.is_empty();
+        (searching && !self.regex).then(|| RemoteSearch {
+            text: self.text.clone(),
+            component: self.has_component.clone(),
+            case_sensitive: self.case_sensitive,
+        })
+    }
+
+    /// The matching entities and their ancestors, or the error of an invalid regex.
+    /// `remote` are the entities the game matched the text and component against, used
+    /// instead of matching the fetched rows, which may lack the components.
+    pub fn run(
+        &self,
+        rows: &HashMap<Entity, BrpQueryRow>,
+        paths: &TypePaths,
+        changes: &ChangeLog,
+        remote: Option<&HashSet<Entity>>,
+    ) -> Result<SearchResult, String> {
+        let pattern = self.pattern()?;
+        let has_component = self.normalize(&self.has_component);
+        let changed = changes.changed_entities();
+        let spawned = self.recently_spawned.then(|| changes.recently_spawned());
+        let recently_changed = self.recently_changed.then(|| changes.recently_changed());
+        let matches: HashSet<Entity> = rows
+            .iter()
+            .filter(|(entity, row)| {
+                let found = match remote {
+                    Some(remote) => remote.contains(*entity),
+                    None => {
+                        let has = has_component.is_empty()
+                            || row.components.keys().any(|key| {
+                                self.normalize(key).contains(&has_component)
+                                    || self.normalize(short_name(key)).contains(&has_component)
+                            });
+                        has && self.matches_text(&pattern, entity, row, paths)
+                    }
+                };
+                found
+                    && (!self.changed_only || changed.contains(*entity))
+                    && spawned.as_ref().is_none_or(|s| s.contains(*entity))
+                    && recently_changed
+                        .as_ref()
+                        .is_none_or(|c| c.contains(*entity))
+            })
+            .map(|(entity, _)| *entity)
+            .collect();
+        let mut ancestors = HashSet::new();
+        for entity in &matches {
+            let mut current = rows.get(entity);
+            while let Some(parent) = current.and_then(|row| parent_of(row, paths)) {
+                if !ancestors.insert(parent) {
+                    break;
+                }
+                current = rows.get(&parent);
+            }
+        }
+        Ok(SearchResult { matches, ancestors })
+    }
+
+    fn pattern(&self) -> Result<Pattern, String> {
+        if !self.regex {
+            return Ok(Pattern::Substring(self.normalize(&self.text)));
+        }
+        RegexBuilder::new(&self.text)
+            .case_insensitive(!self.case_sensitive)
+            .build()
+            .map(Pattern::Regex)
+            .map_err(|e| e.to_string())
+    }
+
+    fn normalize(&self, text: &str) -> String {
+        if self.case_sensitive {
+            text.to_owned()
+        } else {
+            text.to_lowercase()
+        }
+    }
+
+    fn matches_text(
+        &self,
+        pattern: &Pattern,
+        entity: &Entity,
+        row: &BrpQueryRow,
+        paths: &TypePaths,
+    ) -> bool {
+        let name = row
+            .components
+            .get(paths.name)
+            .and_then(|name| name.as_str());
+        let ids = [entity.to_string(), entity.index().to_string()];
+        let mut candidates = name
+            .into_iter()
+            .chain(ids.iter().map(String::as_str))
+            .chain(row.components.keys().map(String::as_str))
+            .chain(row.components.keys().map(|key| short_name(key)));
+        match pattern {
+            Pattern::Substring(text) if text.is_empty() => true,
+            Pattern::Substring(text) => candidates.any(|c| self.normalize(c).contains(text)),
+            Pattern::Regex(regex) => candidates.any(|c| regex.is_match(c)),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow, utils::HashMap};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const GLOBAL_TRANSFORM: &str = "bevy_transform::components::global_transform::GlobalTransform";
+pub const TRANSFORM: &str = "bevy_transform::components::transform::Transform";
+
+/// World position of an entity. Uses `GlobalTransform` and falls back to the local
+/// `Transform`, which matches the world position for entities without a parent.
+pub fn world_position(row: &BrpQueryRow) -> Option<[f32; 3]> {
+    if let Some(global) = row.components.get(GLOBAL_TRANSFORM) {
+        return global_translation(global);
+    }
+    vec3(row.components.get(TRANSFORM)?.get("translation")?)
+}
+
+/// Position of the first active camera, by entity id.
+pub fn camera_position(rows: &HashMap<Entity, BrpQueryRow>) -> Option<[f32; 3]> {
+    let mut cameras: Vec<(&Entity, &BrpQueryRow)> = rows
+        .iter()
+        .filter(|(_, row)| {
+            row.components.iter().any(|(key, camera)| {
+                key.ends_with("::Camera") && camera.get("is_active") != Some(&Value::Bool(false))
+            })
+        })
+        .collect();
+    cameras.sort_by_key(|(entity, _)| **entity);
+    cameras.into_iter().find_map(|(_, row)| world_position(row))
+}
+
+/// `GlobalTransform` is serialized as the 12 floats of its affine matrix, column by column
+/// with the translation last, or as a struct when no serde impl is registered.
+fn global_translation(value: &Value) -> Option<[f32; 3]> {
+    match value {
+        Value::Array(floats) if floats.len() == 12 => vec3(&Value::Array(floats[9..].to_vec())),
+        Value::Object(fields) => vec3(fields.get("translation")?),
+        _ => None,
+    }
+}
+
+/// Reads both the `[x, y, z]` and the `{ "x": .., "y": .., "z": .. }` forms.
+pub fn vec3(value: &Value) -> Option<[f32; 3]> {
+    let component = |index: usize, name: &str| {
+        value
+            .get(index)
+            .or_else(|| value.get(name))
+            .and_then(Value::as_f64)
+            .map(|v| v as f32)
+    };
+    Some([component(0, "x")?, component(1, "y")?, component(2, "z")?])
+}
+
+/// Client side filter narrowing the tree down to a region of the world.
+#[derive(Serialize, Deserialize, Clone, PartialEq, Debug, Default)]
+pub enum SpatialFilter {
+    #[default]
+    Off,
+    /// Entities within `radius` world units of the selected entity.
+    NearSelected { radius: f32 },
+    /// Entities inside an axis aligned box.
+    InsideBox { min: [f32; 3], max: [f32; 3] },
+}
+
+impl SpatialFilter {
+    pub fn is_active(&self) -> bool {
+        *self != Self::Off
+    }
+
+    /// Whether an entity at `position` passes, `origin` is the selected entity's position.
+    pub fn matches(&self, position: [f32; 3], origin: Option<[f32; 3]>) -> bool {
+        match self {
+            Self::Off => true,
+            Self::NearSelected { radius } => origin.is_some_and(|origin| {
+                let distance_squared: f32 = (0..3).map(|i| (position[i] - origin[i]).powi(2)).sum();
+                distance_squared <= radius * radius
+            }),
+            Self::InsideBox { min, max } => {
+                (0..3).all(|i| min[i] <= position[i] && position[i] <= max[i])
+            }
+        }
+    }
+}
+
+/// Entities passing the filter, in a flat list since matches can sit anywhere in the
+/// hierarchy. Ordered by distance from the selection, or by id for a box.
+pub fn filter_entities(
+    filter: &SpatialFilter,
+    rows: &HashMap<Entity, BrpQueryRow>,
+    selected: Option<Entity>,
+) -> Vec<Entity> {
+    let origin = selected
+        .and_then(|entity| rows.get(&entity))
+        .and_then(world_position);
+    let mut matches: Vec<(Entity, f32)> = rows
+        .iter()
+        .filter_map(|(entity, row)| {
+            let position = world_position(row)?;
+            filter.matches(position, origin).then(|| {
+                let distance = origin.map_or(0.0, |origin| {
+                    (0..3).map(|i| (position[i] - origin[i]).powi(2)).sum()
+                });
+                (*entity, distance)
+            })
+        })
+        .collect();
+    matches.sort_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+    matches.into_iter().map(|(entity, _)| entity).collect()
+}
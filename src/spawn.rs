@@ -0,0 +1,243 @@
+//! The Spawn entity dialog, putting together the components of a new entity.
+
+use bevy::remote::builtin_methods::BrpSpawnParams;
+use egui::RichText;
+use serde_json::{json, Value};
+
+use crate::aliases::Aliases;
+use crate::completion::type_path_input;
+use crate::random::Rng;
+use crate::schema::{forget_text, Schemas};
+use crate::spatial::TRANSFORM;
+
+/// Components of the entity being put together, kept while the dialog is closed.
+#[derive(Default)]
+pub struct SpawnDraft {
+    pub open: bool,
+    components: Vec<(String, Value)>,
+    placement: Placement,
+    /// Typed into the search next to the component picker.
+    search: String,
+}
+
+/// Where the new entity is put, written into its `Transform` when spawning.
+#[derive(Clone, Copy, PartialEq, Default)]
+pub enum Placement {
+    /// Keep the `Transform` as entered, if there is one.
+    #[default]
+    AsEntered,
+    Origin,
+    Camera,
+    Selected,
+    At([f32; 3]),
+}
+
+impl Placement {
+    fn label(self) -> &'static str {
+        match self {
+            Placement::AsEntered => "As entered",
+            Placement::Origin => "At the origin",
+            Placement::Camera => "At the camera",
+            Placement::Selected => "At the selected entity",
+            Placement::At(_) => "At coordinates",
+        }
+    }
+}
+
+/// Positions the new entity can be placed at, when they are known.
+pub struct Anchors {
+    pub camera: Option<[f32; 3]>,
+    pub selected: Option<[f32; 3]>,
+}
+
+impl Anchors {
+    fn translation(&self, placement: Placement) -> Option<[f32; 3]> {
+        match placement {
+            Placement::AsEntered => None,
+            Placement::Origin => Some([0.0; 3]),
+            Placement::Camera => self.camera,
+            Placement::Selected => self.selected,
+            Placement::At(position) => Some(position),
+        }
+    }
+}
+
+impl SpawnDraft {
+    /// Returns the params to spawn with once the Spawn button is clicked, the draft is
+    /// cleared then.
+    pub fn draw(
+        &mut self,
+        ui: &mut egui::Ui,
+        types: &[String],
+        schemas: &Schemas,
+        aliases: &Aliases,
+        anchors: &Anchors,
+        can_spawn: bool,
+    ) -> Option<BrpSpawnParams> {
+        let mut add = None;
+        let available: Vec<String> = types
+            .iter()
+            .filter(|type_path| !self.components.iter().any(|(t, _)| t == *type_path))
+            .cloned()
+            .collect();
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("spawn_add_component")
+                .selected_text("➕ Add component")
+                .show_ui(ui, |ui| {
+                    for type_path in &available {
+                        if ui
+                            .selectable_label(false, aliases.type_name(type_path))
+                            .on_hover_text(type_path)
+                            .clicked()
+                        {
+                            add = Some(type_path.clone());
+                        }
+                    }
+                });
+            let typed = type_path_input(
+                ui,
+                "spawn_search",
+                &mut self.search,
+                &available,
+                "or type its name",
+                160.0,
+            );
+            if typed.accepted.is_some() {
+                add = typed.accepted;
+                self.search.clear();
+            }
+        });
+        if let Some(type_path) = add {
+            let value = schemas.default_value(&type_path);
+            self.components.push((type_path, value));
+        }
+        if self.components.is_empty() {
+            ui.label(RichText::new("Pick the components of the new entity.").weak());
+        } else if schemas.is_empty() {
+            ui.label(
+                RichText::new("The game doesn't provide type schemas, enter values as JSON.")
+                    .weak(),
+            );
+        }
+        let mut remove = None;
+        egui::ScrollArea::vertical()
+            .max_height(400.0)
+            .show(ui, |ui| {
+                for (index, (type_path, value)) in self.components.iter_mut().enumerate() {
+                    let id = ui.make_persistent_id(("spawn", &type_path));
+                    ui.horizontal(|ui| {
+                        if ui.small_button("🗑").on_hover_text("Remove").clicked() {
+                            remove = Some(index);
+                        }
+                        if ui
+                            .small_button("🎲")
+                            .on_hover_text("Fill with random values")
+                            .clicked()
+                        {
+                            *value = schemas.randomize(type_path, value, &mut Rng::new());
+                            forget_text(ui.ctx(), id);
+                        }
+                        ui.label(RichText::new(aliases.type_name(type_path)).strong())
+                            .on_hover_text(type_path.as_str());
+                    });
+                    schemas.edit(ui, id, type_path, value);
+                    ui.separator();
+                }
+            });
+        if let Some(index) = remove {
+            self.components.remove(index);
+        }
+        self.draw_placement(ui, anchors);
+        let mut spawn = None;
+        ui.horizontal(|ui| {
+            let enabled = can_spawn && !self.components.is_empty();
+            if ui
+                .add_enabled(enabled, egui::Button::new("Spawn"))
+                .clicked()
+            {
+                if let Some(translation) = anchors.translation(self.placement) {
+                    self.place(translation, schemas);
+                }
+                spawn = Some(BrpSpawnParams {
+                    components: self.components.drain(..).collect(),
+                });
+            }
+            if ui.button("Clear").clicked() {
+                self.components.clear();
+            }
+        });
+        spawn
+    }
+
+    fn draw_placement(&mut self, ui: &mut egui::Ui, anchors: &Anchors) {
+        ui.horizontal(|ui| {
+            ui.label("Place");
+            egui::ComboBox::from_id_salt("spawn_placement")
+                .selected_text(self.placement.label())
+                .show_ui(ui, |ui| {
+                    let at = match self.placement {
+                        Placement::At(position) => position,
+                        _ => anchors.selected.or(anchors.camera).unwrap_or_default(),
+                    };
+                    let options = [
+                        (Placement::AsEntered, true),
+                        (Placement::Origin, true),
+                        (Placement::Camera, anchors.camera.is_some()),
+                        (Placement::Selected, anchors.selected.is_some()),
+                        (Placement::At(at), true),
+                    ];
+                    for (placement, available) in options {
+                        let selected = std::mem::discriminant(&self.placement)
+                            == std::mem::discriminant(&placement);
+                        if ui
+                            .add_enabled(
+                                available,
+                                egui::SelectableLabel::new(selected, placement.label()),
+                            )
+                            .clicked()
+                        {
+                            self.placement = placement;
+                        }
+                    }
+                });
+            if let Placement::At(position) = &mut self.placement {
+                for (value, axis) in position.iter_mut().zip(["x: ", "y: ", "z: "]) {
+                    ui.add(egui::DragValue::new(value).speed(0.1).prefix(axis));
+                }
+            }
+        });
+        if anchors.translation(self.placement).is_none() && self.placement != Placement::AsEntered {
+            ui.label(RichText::new("That position isn't known, the Transform is kept.").weak());
+        }
+    }
+
+    /// Writes `translation` into the `Transform` of the new entity, adding one if it has
+    /// none.
+    fn place(&mut self, translation: [f32; 3], schemas: &Schemas) {
+        let index = match self.components.iter().position(|(t, _)| t == TRANSFORM) {
+            Some(index) => index,
+            None => {
+                let value = match schemas.default_value(TRANSFORM) {
+                    Value::Null => json!({
+                        "translation": [0.0, 0.0, 0.0],
+                        "rotation": [0.0, 0.0, 0.0, 1.0],
+                        "scale": [1.0, 1.0, 1.0],
+                    }),
+                    value => value,
+                };
+                self.components.push((TRANSFORM.to_owned(), value));
+                self.components.len() - 1
+            }
+        };
+        let Value::Object(transform) = &mut self.components[index].1 else {
+            return;
+        };
+        let [x, y, z] = translation;
+        // Keeps the form of the value the game sent, an array or an `x, y, z` struct.
+        let value = match transform.get("translation") {
+            Some(Value::Object(_)) => json!({ "x": x, "y": y, "z": z }),
+            _ => json!([x, y, z]),
+        };
+        transform.insert("translation".to_owned(), value);
+    }
+}
@@ -0,0 +1,67 @@
+//! Calls for committing staged edits as a unit, undone again when part of them fails.
+
+use bevy::{
+    prelude::Entity,
+    remote::{
+        builtin_methods::{
+            BrpInsertParams, BrpRemoveParams, BRP_GET_METHOD, BRP_INSERT_METHOD, BRP_REMOVE_METHOD,
+        },
+        BrpRequest,
+    },
+};
+use serde_json::Value;
+
+use crate::helper;
+
+/// Entity and component type path of an edit.
+pub type EditKey = (Entity, String);
+
+/// Staged edit being committed, with the value it replaces.
+pub struct StagedEdit {
+    pub key: EditKey,
+    pub value: Value,
+    /// Value before the commit, `None` when the entity didn't have the component.
+    pub previous: Option<Value>,
+}
+
+/// Outcome of the last commit of staged edits.
+#[derive(Default)]
+pub struct CommitReport {
+    pub applied: usize,
+    pub failed: Vec<(EditKey, String)>,
+    /// Edits that went through and were restored because others failed.
+    pub rolled_back: Vec<EditKey>,
+    pub rollback_failed: Vec<(EditKey, String)>,
+}
+
+/// Reads the current value, captured to restore it if the commit has to be undone.
+pub fn capture_call((entity, component): &EditKey) -> BrpRequest {
+    helper::create_request(
+        Some(serde_json::json!({ "entity": entity, "components": [component] })),
+        BRP_GET_METHOD,
+    )
+}
+
+pub fn insert_call((entity, component): &EditKey, value: &Value) -> BrpRequest {
+    helper::create_request(
+        Some(BrpInsertParams {
+            entity: *entity,
+            components: [(component.clone(), value.clone())].into_iter().collect(),
+        }),
+        BRP_INSERT_METHOD,
+    )
+}
+
+/// Puts back the captured value, or removes the component again if it didn't exist.
+pub fn restore_call(key: &EditKey, previous: Option<&Value>) -> BrpRequest {
+    match previous {
+        Some(value) => insert_call(key, value),
+        None => helper::create_request(
+            Some(BrpRemoveParams {
+                entity: key.0,
+                components: vec![key.1.clone()],
+            }),
+            BRP_REMOVE_METHOD,
+        ),
+    }
+}
@@ -0,0 +1,166 @@
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow};
+use serde_json::Value;
+
+use crate::compat::TypePaths;
+
+const MAX_SUMMARY_LEN: usize = 40;
+
+/// Short description of an entity derived from its characteristic components, so rows
+/// can be told apart without expanding them.
+pub fn entity_summary(row: &BrpQueryRow) -> Option<String> {
+    row.components
+        .iter()
+        .find_map(|(type_path, value)| component_summary(short_name(type_path), value))
+        .map(|summary| truncate(summary, MAX_SUMMARY_LEN))
+}
+
+/// Type name without its module path and generic arguments.
+pub fn short_name(type_path: &str) -> &str {
+    let without_generics = type_path.split('<').next().unwrap_or(type_path);
+    without_generics
+        .rsplit("::")
+        .next()
+        .unwrap_or(without_generics)
+}
+
+fn component_summary(short_name: &str, value: &Value) -> Option<String> {
+    match short_name {
+        "Text" | "Text2d" => text_content(value).map(|text| format!("\"{text}\"")),
+        "Sprite" => Some(match find_string(value, "path") {
+            Some(path) => format!("sprite {path}"),
+            None => "sprite".to_owned(),
+        }),
+        "PointLight" => Some("point light".to_owned()),
+        "SpotLight" => Some("spot light".to_owned()),
+        "DirectionalLight" => Some("directional light".to_owned()),
+        "Camera2d" => Some("2D camera".to_owned()),
+        "Camera3d" => Some("3D camera".to_owned()),
+        _ => None,
+    }
+}
+
+/// Text content of both the newer `Text(String)` form and the older section based one.
+fn text_content(value: &Value) -> Option<String> {
+    if let Some(text) = value.as_str() {
+        return Some(text.to_owned());
+    }
+    let sections = value.get("sections")?.as_array()?;
+    let text: String = sections
+        .iter()
+        .filter_map(|section| section.get("value").and_then(Value::as_str))
+        .collect();
+    Some(text)
+}
+
+fn find_string(value: &Value, key: &str) -> Option<String> {
+    match value {
+        Value::Object(map) => map.iter().find_map(|(k, v)| match v {
+            Value::String(s) if k == key => Some(s.clone()),
+            _ => find_string(v, key),
+        }),
+        Value::Array(array) => array.iter().find_map(|v| find_string(v, key)),
+        _ => None,
+    }
+}
+
+fn truncate(text: String, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text;
+    }
+    let mut truncated: String = text.chars().take(max).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Entity id followed by its `Name`, if it has one.
+pub fn entity_label(entity: &Entity, row: &BrpQueryRow, paths: &TypePaths) -> String {
+    let mut id = entity.to_string();
+    if row.components.contains_key(paths.name) {
+        id += ": ";
+        id += entity_name(row, paths).unwrap_or("NONE");
+    };
+    id
+}
+
+pub fn entity_name<'a>(row: &'a BrpQueryRow, paths: &TypePaths) -> Option<&'a str> {
+    let name = row.components.get(paths.name)?;
+    // Depending on the version `Name` is serialized as a plain string or a struct.
+    name.as_str()
+        .or_else(|| name.get("name").and_then(Value::as_str))
+}
+
+/// Fills a label template like `{Name} hp:{my_game::Health.current}` from the entity's
+/// components. Placeholders name a component by type path or short name, optionally
+/// followed by a `.`-separated field path, `{Entity}` is the entity id. Values that are
+/// missing show as `?`.
+pub fn render_label_template(
+    template: &str,
+    entity: &Entity,
+    row: &BrpQueryRow,
+    paths: &TypePaths,
+) -> String {
+    let mut label = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        label.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            rest = &rest[start..];
+            break;
+        };
+        let placeholder = rest[start + 1..start + end].trim();
+        label.push_str(&placeholder_value(placeholder, entity, row, paths));
+        rest = &rest[start + end + 1..];
+    }
+    label.push_str(rest);
+    label
+}
+
+fn placeholder_value(
+    placeholder: &str,
+    entity: &Entity,
+    row: &BrpQueryRow,
+    paths: &TypePaths,
+) -> String {
+    if placeholder == "Entity" {
+        return entity.to_string();
+    }
+    // Type paths contain `::` but no dots, so the first dot starts the field path.
+    let (component, fields) = placeholder.split_once('.').unwrap_or((placeholder, ""));
+    let found = row.components.get_key_value(component).or_else(|| {
+        row.components
+            .iter()
+            .find(|(type_path, _)| short_name(type_path) == component)
+    });
+    let Some((type_path, mut value)) = found else {
+        return "?".to_owned();
+    };
+    if type_path == paths.name && fields.is_empty() {
+        if let Some(name) = value
+            .as_str()
+            .or_else(|| value.get("name").and_then(Value::as_str))
+        {
+            return name.to_owned();
+        }
+    }
+    for field in fields.split('.').filter(|f| !f.is_empty()) {
+        let next = match field.parse::<usize>() {
+            Ok(index) => value.get(index),
+            Err(_) => value.get(field),
+        };
+        match next {
+            Some(next) => value = next,
+            None => return "?".to_owned(),
+        }
+    }
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Number(number) => match number.as_f64() {
+            Some(float) if !number.is_i64() && !number.is_u64() => format!("{:.2}", float)
+                .trim_end_matches('0')
+                .trim_end_matches('.')
+                .to_owned(),
+            _ => number.to_string(),
+        },
+        other => other.to_string(),
+    }
+}
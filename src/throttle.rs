@@ -0,0 +1,87 @@
+//! Back-pressure from the game or a proxy in front of it. `429 Too Many Requests` and
+//! `503 Service Unavailable` answers hold off polling for their `Retry-After`, and polling
+//! stays slower for a while afterwards instead of hammering the server again right away.
+
+use std::sync::Mutex;
+use web_time::{Duration, Instant};
+
+use ehttp::Response;
+use lazy_static::lazy_static;
+
+/// Wait after a throttling answer without `Retry-After`, doubled for each one in a row.
+const DEFAULT_WAIT: Duration = Duration::from_secs(1);
+const MAX_WAIT: Duration = Duration::from_secs(60);
+/// How long after the last throttling answer polling stays slowed down.
+const COOLDOWN: Duration = Duration::from_secs(30);
+/// Throttling answers in a row counted towards the slowdown, each doubles the intervals.
+const MAX_STRIKES: u32 = 4;
+
+#[derive(Default)]
+struct State {
+    /// Nothing should be polled before then.
+    until: Option<Instant>,
+    last: Option<Instant>,
+    strikes: u32,
+}
+
+lazy_static! {
+    static ref STATE: Mutex<State> = Mutex::new(State::default());
+}
+
+pub fn is_throttling(response: &Response) -> bool {
+    matches!(response.status, 429 | 503)
+}
+
+/// The `Retry-After` header in seconds. The HTTP date form isn't supported, the default
+/// backoff is used for it.
+pub fn retry_after(response: &Response) -> Option<Duration> {
+    let seconds = response.headers.get("retry-after")?.trim().parse().ok()?;
+    Some(Duration::from_secs(seconds).min(MAX_WAIT))
+}
+
+/// Notes a throttling answer, returning how long to wait before the next request.
+pub fn record(response: &Response) -> Duration {
+    let mut state = STATE.lock().unwrap();
+    let recent = state.last.is_some_and(|last| last.elapsed() < COOLDOWN);
+    state.strikes = if recent {
+        (state.strikes + 1).min(MAX_STRIKES)
+    } else {
+        1
+    };
+    let backoff = DEFAULT_WAIT.saturating_mul(1 << (state.strikes - 1));
+    let wait = retry_after(response).unwrap_or(backoff).min(MAX_WAIT);
+    let now = Instant::now();
+    state.last = Some(now);
+    state.until = Some(now + wait);
+    wait
+}
+
+/// Time left before the server wants to hear from the inspector again.
+pub fn remaining() -> Option<Duration> {
+    let until = STATE.lock().unwrap().until?;
+    let remaining = until.saturating_duration_since(Instant::now());
+    (!remaining.is_zero()).then_some(remaining)
+}
+
+/// Whether the server pushed back recently, polling is slowed down then.
+pub fn is_active() -> bool {
+    STATE
+        .lock()
+        .unwrap()
+        .last
+        .is_some_and(|last| last.elapsed() < COOLDOWN)
+}
+
+/// `interval` stretched by the recent throttling answers.
+pub fn slowed(interval: Duration) -> Duration {
+    let state = STATE.lock().unwrap();
+    match state.last {
+        Some(last) if last.elapsed() < COOLDOWN => interval.saturating_mul(1 << state.strikes),
+        _ => interval,
+    }
+}
+
+/// Forgets about past throttling, e.g. when connecting to another game.
+pub fn reset() {
+    *STATE.lock().unwrap() = State::default();
+}
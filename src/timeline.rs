@@ -0,0 +1,220 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use web_time::Instant;
+
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow, utils::HashMap};
+use serde_json::Value;
+
+use crate::changes::{diff_fields, FieldChange};
+
+/// World as returned by one live poll.
+pub struct TimelineState {
+    pub at: Instant,
+    /// Game frame of the state, when the companion plugin reports it.
+    pub frame: Option<u64>,
+    pub world: Arc<HashMap<Entity, BrpQueryRow>>,
+    /// Estimated memory held by `world`.
+    bytes: usize,
+    /// Last time the state was pushed or viewed, the least recently used state is evicted
+    /// first when over the memory budget.
+    last_used: Instant,
+}
+
+impl TimelineState {
+    pub fn new(at: Instant, frame: Option<u64>, world: Arc<HashMap<Entity, BrpQueryRow>>) -> Self {
+        let bytes = world.values().map(row_size).sum();
+        Self {
+            at,
+            frame,
+            world,
+            bytes,
+            last_used: Instant::now(),
+        }
+    }
+}
+
+/// Rough heap size of a row, good enough to keep the total within a budget.
+fn row_size(row: &BrpQueryRow) -> usize {
+    let components: usize = row
+        .components
+        .iter()
+        .map(|(key, value)| key.len() + value_size(value))
+        .sum();
+    std::mem::size_of::<BrpQueryRow>() + components
+}
+
+fn value_size(value: &Value) -> usize {
+    std::mem::size_of::<Value>()
+        + match value {
+            Value::String(text) => text.len(),
+            Value::Array(array) => array.iter().map(value_size).sum(),
+            Value::Object(map) => map.iter().map(|(k, v)| k.len() + value_size(v)).sum(),
+            _ => 0,
+        }
+}
+
+/// Differences between two kept states.
+#[derive(Default)]
+pub struct Comparison {
+    pub added: Vec<Entity>,
+    pub removed: Vec<Entity>,
+    /// Changed components with the paths, old and new values of their changed fields.
+    pub changed: Vec<(Entity, String, Vec<FieldChange>)>,
+}
+
+impl Comparison {
+    pub fn new(old: &HashMap<Entity, BrpQueryRow>, new: &HashMap<Entity, BrpQueryRow>) -> Self {
+        let mut comparison = Self::default();
+        for (entity, row) in new {
+            let Some(old_row) = old.get(entity) else {
+                comparison.added.push(*entity);
+                continue;
+            };
+            for (component, value) in &row.components {
+                let old_value = old_row.components.get(component).unwrap_or(&Value::Null);
+                let mut fields = Vec::new();
+                diff_fields(String::new(), old_value, value, &mut fields);
+                if !fields.is_empty() {
+                    comparison
+                        .changed
+                        .push((*entity, component.clone(), fields));
+                }
+            }
+            for (component, old_value) in &old_row.components {
+                if !row.components.contains_key(component) {
+                    let fields = vec![(String::new(), old_value.clone(), Value::Null)];
+                    comparison
+                        .changed
+                        .push((*entity, component.clone(), fields));
+                }
+            }
+        }
+        comparison.removed = old
+            .keys()
+            .filter(|e| !new.contains_key(*e))
+            .copied()
+            .collect();
+        comparison.added.sort();
+        comparison.removed.sort();
+        comparison
+            .changed
+            .sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+        comparison
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// The last fetched states, for rewinding the view and comparing states.
+#[derive(Default)]
+pub struct Timeline {
+    states: VecDeque<TimelineState>,
+    /// State the view is rewound to, identified by its poll time so it stays put while
+    /// new states arrive. `None` follows the live data.
+    viewing: Option<Instant>,
+    /// State the viewed one is compared against, while comparing.
+    pub compare_with: Option<Instant>,
+}
+
+impl Timeline {
+    /// Adds the newest state, then drops states beyond `capacity` oldest first and, while
+    /// over `budget` bytes, the least recently used ones. The newest state and the one
+    /// being viewed are kept.
+    pub fn push(&mut self, state: TimelineState, capacity: usize, budget: usize) {
+        self.states.push_back(state);
+        while self.states.len() > capacity.max(1) {
+            self.states.pop_front();
+        }
+        while self.memory() > budget && self.states.len() > 1 {
+            let kept = [self.viewing, self.compare_with];
+            let newest = self.states.len() - 1;
+            let evict = self
+                .states
+                .iter()
+                .enumerate()
+                .filter(|(index, state)| *index != newest && !kept.contains(&Some(state.at)))
+                .min_by_key(|(_, state)| state.last_used)
+                .map(|(index, _)| index);
+            match evict {
+                Some(index) => {
+                    self.states.remove(index);
+                }
+                None => break,
+            }
+        }
+    }
+
+    /// Estimated bytes of the newest state, about what a fetch of the world brings in.
+    pub fn latest_bytes(&self) -> Option<usize> {
+        self.states.back().map(|state| state.bytes)
+    }
+
+    /// Estimated bytes held by all kept states.
+    pub fn memory(&self) -> usize {
+        self.states.iter().map(|state| state.bytes).sum()
+    }
+
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&TimelineState> {
+        self.states.get(index)
+    }
+
+    /// Index of the state the view is rewound to, `None` when showing live data or once
+    /// that state was dropped.
+    pub fn position(&self) -> Option<usize> {
+        let viewing = self.viewing?;
+        self.states.iter().position(|state| state.at == viewing)
+    }
+
+    /// Rewinds the view to the state at `index`, the newest one returns to live data.
+    pub fn view(&mut self, index: usize) {
+        let newest = index + 1 >= self.states.len();
+        self.viewing = match self.states.get_mut(index) {
+            Some(state) if !newest => {
+                state.last_used = Instant::now();
+                Some(state.at)
+            }
+            _ => None,
+        };
+    }
+
+    pub fn go_live(&mut self) {
+        self.viewing = None;
+    }
+
+    pub fn index_of(&self, at: Instant) -> Option<usize> {
+        self.states.iter().position(|state| state.at == at)
+    }
+
+    /// Poll times of the compared and the viewed or newest state, while comparing.
+    pub fn compared(&self) -> Option<(Instant, Instant)> {
+        let old = self.get(self.index_of(self.compare_with?)?)?;
+        let new = self.get(self.position().unwrap_or(self.len().checked_sub(1)?))?;
+        Some((old.at, new.at))
+    }
+
+    /// Differences from the compared state to the viewed or newest one.
+    pub fn comparison(&self) -> Option<Comparison> {
+        let (old, new) = self.compared()?;
+        let old = self.get(self.index_of(old)?)?;
+        let new = self.get(self.index_of(new)?)?;
+        Some(Comparison::new(&old.world, &new.world))
+    }
+
+    /// World of the state the view is rewound to.
+    pub fn viewed(&self) -> Option<Arc<HashMap<Entity, BrpQueryRow>>> {
+        self.position()
+            .map(|index| self.states[index].world.clone())
+    }
+
+    pub fn clear(&mut self) {
+        self.states.clear();
+        self.viewing = None;
+        self.compare_with = None;
+    }
+}
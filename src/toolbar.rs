@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+
+/// Action or toggle that can be placed on the toolbar.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ToolbarItem {
+    Fetch,
+    FrameCapture,
+    Live,
+    HideEmpty,
+    Sort,
+    Presets,
+    Activity,
+    FollowSpawns,
+    ReadOnly,
+    Trash,
+    Spawn,
+    Minimap,
+    History,
+    Insights,
+    Staged,
+    Problems,
+    Stats,
+    Changes,
+    TypeDocs,
+    SpatialFilter,
+    OnScreenOnly,
+    Panels,
+    Hud,
+    Dashboard,
+    Alerts,
+    Query,
+    Performance,
+    Rendering,
+    Bookmarks,
+    Types,
+    Undo,
+    RpcLog,
+}
+
+impl ToolbarItem {
+    pub const ALL: [ToolbarItem; 32] = [
+        ToolbarItem::Fetch,
+        ToolbarItem::FrameCapture,
+        ToolbarItem::Live,
+        ToolbarItem::HideEmpty,
+        ToolbarItem::Sort,
+        ToolbarItem::Presets,
+        ToolbarItem::Activity,
+        ToolbarItem::FollowSpawns,
+        ToolbarItem::ReadOnly,
+        ToolbarItem::Trash,
+        ToolbarItem::Spawn,
+        ToolbarItem::Minimap,
+        ToolbarItem::History,
+        ToolbarItem::Insights,
+        ToolbarItem::Staged,
+        ToolbarItem::Problems,
+        ToolbarItem::Stats,
+        ToolbarItem::Changes,
+        ToolbarItem::TypeDocs,
+        ToolbarItem::SpatialFilter,
+        ToolbarItem::OnScreenOnly,
+        ToolbarItem::Panels,
+        ToolbarItem::Hud,
+        ToolbarItem::Dashboard,
+        ToolbarItem::Alerts,
+        ToolbarItem::Query,
+        ToolbarItem::Performance,
+        ToolbarItem::Rendering,
+        ToolbarItem::Bookmarks,
+        ToolbarItem::Types,
+        ToolbarItem::Undo,
+        ToolbarItem::RpcLog,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ToolbarItem::Fetch => "Fetch",
+            ToolbarItem::FrameCapture => "Pause and step",
+            ToolbarItem::Live => "Live",
+            ToolbarItem::HideEmpty => "Hide empty entities",
+            ToolbarItem::Sort => "Sort order",
+            ToolbarItem::Presets => "View presets",
+            ToolbarItem::Activity => "Activity heatmap",
+            ToolbarItem::FollowSpawns => "Follow spawns",
+            ToolbarItem::ReadOnly => "Read-only",
+            ToolbarItem::Trash => "Trash",
+            ToolbarItem::Spawn => "Spawn entity",
+            ToolbarItem::Minimap => "Minimap",
+            ToolbarItem::History => "History",
+            ToolbarItem::Insights => "Insights",
+            ToolbarItem::Staged => "Staged changes",
+            ToolbarItem::Problems => "Problems",
+            ToolbarItem::Stats => "Stats",
+            ToolbarItem::Changes => "Changes",
+            ToolbarItem::TypeDocs => "Type docs",
+            ToolbarItem::SpatialFilter => "Spatial filter",
+            ToolbarItem::OnScreenOnly => "On screen only",
+            ToolbarItem::Panels => "Custom panels",
+            ToolbarItem::Hud => "Game HUD",
+            ToolbarItem::Dashboard => "Dashboard",
+            ToolbarItem::Alerts => "Alerts",
+            ToolbarItem::Query => "Query builder",
+            ToolbarItem::Performance => "Performance",
+            ToolbarItem::Rendering => "Rendering",
+            ToolbarItem::Bookmarks => "Bookmarks",
+            ToolbarItem::Types => "Types",
+            ToolbarItem::Undo => "Undo and redo",
+            ToolbarItem::RpcLog => "RPC log",
+        }
+    }
+}
+
+/// Items shown on the toolbar in order, hidden ones keep their place for when they come
+/// back.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct ToolbarLayout {
+    pub items: Vec<(ToolbarItem, bool)>,
+}
+
+impl Default for ToolbarLayout {
+    fn default() -> Self {
+        Self {
+            items: ToolbarItem::ALL.iter().map(|item| (*item, true)).collect(),
+        }
+    }
+}
+
+impl ToolbarLayout {
+    pub fn visible(&self) -> Vec<ToolbarItem> {
+        // Items added in newer versions are missing from stored layouts.
+        let missing = ToolbarItem::ALL
+            .into_iter()
+            .filter(|item| !self.items.iter().any(|(i, _)| i == item));
+        self.items
+            .iter()
+            .filter(|(_, shown)| *shown)
+            .map(|(item, _)| *item)
+            .chain(missing)
+            .collect()
+    }
+
+    /// Checkboxes to show or hide items and drag handles to reorder them.
+    pub fn edit(&mut self, ui: &mut egui::Ui) {
+        for item in ToolbarItem::ALL {
+            if !self.items.iter().any(|(i, _)| *i == item) {
+                self.items.push((item, true));
+            }
+        }
+        let mut moved = None;
+        for (index, (item, shown)) in self.items.iter_mut().enumerate() {
+            let row = ui.horizontal(|ui| {
+                ui.dnd_drag_source(egui::Id::new(("toolbar_item", index)), index, |ui| {
+                    ui.label("☰");
+                })
+                .response
+                .on_hover_text("Drag to reorder");
+                ui.checkbox(shown, item.label());
+            });
+            if let Some(from) = row.response.dnd_release_payload::<usize>() {
+                moved = Some((*from, index));
+            }
+        }
+        if let Some((from, to)) = moved {
+            let item = self.items.remove(from);
+            self.items.insert(to, item);
+        }
+        if ui.button("Reset").clicked() {
+            *self = Self::default();
+        }
+    }
+}
@@ -0,0 +1,206 @@
+//! A guided tour for new users, highlighting the parts of the real UI one step at a time.
+//! Widgets mark where they are with [`mark`] while drawing, the tour points at whatever
+//! was marked for the current step in the previous frame. Steps are data in [`STEPS`],
+//! new features only need a step and a `mark` call.
+
+use egui::{Align2, Color32, Id, LayerId, Order, Rect, Stroke};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TourAnchor {
+    Connection,
+    Fetch,
+    Live,
+    Search,
+    Tree,
+    Details,
+    Settings,
+}
+
+pub struct TourStep {
+    pub anchor: TourAnchor,
+    pub title: &'static str,
+    pub text: &'static str,
+    /// Shown instead of the highlight while the anchor isn't on screen.
+    pub missing: &'static str,
+}
+
+pub const STEPS: [TourStep; 7] = [
+    TourStep {
+        anchor: TourAnchor::Connection,
+        title: "Connecting",
+        text: "The game the inspector talks to. Click to change the address, the dot turns \
+               green once the game answers.",
+        missing: "Finish the connection setup first.",
+    },
+    TourStep {
+        anchor: TourAnchor::Fetch,
+        title: "Fetching",
+        text: "Loads the entities of the game with all their components. F5 does the same.",
+        missing: "Fetching is available once connected.",
+    },
+    TourStep {
+        anchor: TourAnchor::Live,
+        title: "Live mode",
+        text: "Keeps fetching on its own, so the tree follows the game as it runs.",
+        missing: "Live mode is available once connected.",
+    },
+    TourStep {
+        anchor: TourAnchor::Search,
+        title: "Filtering",
+        text: "Narrows the tree down by name, id or component. The chips next to it keep \
+               only what changed or spawned lately.",
+        missing: "The search shows up once entities are fetched.",
+    },
+    TourStep {
+        anchor: TourAnchor::Tree,
+        title: "The entity tree",
+        text: "Every entity with its children. Click one to select it, right-click for \
+               copying, pinning, renaming and more.",
+        missing: "Fetch entities to fill the tree.",
+    },
+    TourStep {
+        anchor: TourAnchor::Details,
+        title: "Editing",
+        text: "The components of the selected entity. Change a value and apply it to \
+               write it into the running game.",
+        missing: "Select an entity in the tree to see its details.",
+    },
+    TourStep {
+        anchor: TourAnchor::Settings,
+        title: "Settings",
+        text: "Layout, shortcuts, profiles and projects. The tour can be taken again from \
+               here any time.",
+        missing: "The settings are in the top bar once connected.",
+    },
+];
+
+/// Index of the current step while the tour runs.
+#[derive(Default)]
+pub struct Tour {
+    pub step: Option<usize>,
+}
+
+/// What the user did with the tour's callout.
+#[derive(PartialEq)]
+pub enum TourAction {
+    None,
+    /// Skipped or went through every step.
+    Finish,
+}
+
+fn anchor_id(anchor: TourAnchor) -> Id {
+    Id::new(("tour_anchor", anchor))
+}
+
+/// Remembers where `anchor` was drawn this frame.
+pub fn mark(ctx: &egui::Context, anchor: TourAnchor, rect: Rect) {
+    let pass = ctx.cumulative_pass_nr();
+    ctx.data_mut(|d| d.insert_temp(anchor_id(anchor), (pass, rect)));
+}
+
+/// Where `anchor` was drawn in the last frame, `None` when it wasn't.
+fn marked(ctx: &egui::Context, anchor: TourAnchor) -> Option<Rect> {
+    let (pass, rect): (u64, Rect) = ctx.data(|d| d.get_temp(anchor_id(anchor)))?;
+    (pass + 1 >= ctx.cumulative_pass_nr()).then_some(rect)
+}
+
+impl Tour {
+    pub fn start(&mut self) {
+        self.step = Some(0);
+    }
+
+    /// Dims everything but the current step's anchor and shows its callout.
+    pub fn draw(&mut self, ctx: &egui::Context) -> TourAction {
+        let Some(index) = self.step else {
+            return TourAction::None;
+        };
+        let Some(step) = STEPS.get(index) else {
+            self.step = None;
+            return TourAction::Finish;
+        };
+        let screen = ctx.screen_rect();
+        let target = marked(ctx, step.anchor).map(|rect| rect.expand(4.0));
+        let painter = ctx.layer_painter(LayerId::new(Order::Foreground, Id::new("tour_dim")));
+        let dim = Color32::from_black_alpha(140);
+        match target {
+            Some(target) => {
+                // Four bands around the target, the target itself stays undimmed.
+                for band in [
+                    Rect::from_min_max(screen.min, egui::pos2(screen.max.x, target.min.y)),
+                    Rect::from_min_max(egui::pos2(screen.min.x, target.max.y), screen.max),
+                    Rect::from_min_max(
+                        egui::pos2(screen.min.x, target.min.y),
+                        egui::pos2(target.min.x, target.max.y),
+                    ),
+                    Rect::from_min_max(
+                        egui::pos2(target.max.x, target.min.y),
+                        egui::pos2(screen.max.x, target.max.y),
+                    ),
+                ] {
+                    painter.rect_filled(band, 0.0, dim);
+                }
+                painter.rect_stroke(
+                    target,
+                    4.0,
+                    Stroke::new(2.0, ctx.style().visuals.selection.stroke.color),
+                );
+            }
+            None => {
+                painter.rect_filled(screen, 0.0, dim);
+            }
+        }
+        // Below the target when there's room, above it otherwise.
+        let (pivot, position) = match target {
+            Some(target) if target.max.y + 160.0 < screen.max.y => (
+                Align2::LEFT_TOP,
+                target.left_bottom() + egui::vec2(0.0, 8.0),
+            ),
+            Some(target) => (
+                Align2::LEFT_BOTTOM,
+                target.left_top() - egui::vec2(0.0, 8.0),
+            ),
+            None => (Align2::CENTER_CENTER, screen.center()),
+        };
+        let mut action = TourAction::None;
+        egui::Area::new(Id::new("tour_callout"))
+            .order(Order::Tooltip)
+            .pivot(pivot)
+            .fixed_pos(position)
+            .constrain(true)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.set_max_width(320.0);
+                    ui.horizontal(|ui| {
+                        ui.strong(step.title);
+                        ui.weak(format!("{}/{}", index + 1, STEPS.len()));
+                    });
+                    ui.label(step.text);
+                    if target.is_none() {
+                        ui.weak(step.missing);
+                    }
+                    ui.horizontal(|ui| {
+                        if index > 0 && ui.button("Back").clicked() {
+                            self.step = Some(index - 1);
+                        }
+                        let last = index + 1 == STEPS.len();
+                        if ui.button(if last { "Done" } else { "Next" }).clicked() {
+                            self.step = Some(index + 1);
+                        }
+                        if !last && ui.button("Skip tour").clicked() {
+                            self.step = None;
+                            action = TourAction::Finish;
+                        }
+                    });
+                });
+            });
+        if ctx.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape)) {
+            self.step = None;
+            action = TourAction::Finish;
+        }
+        if self.step.is_some_and(|step| step >= STEPS.len()) {
+            self.step = None;
+            action = TourAction::Finish;
+        }
+        action
+    }
+}
@@ -0,0 +1,395 @@
+//! Log of every request sent to the game, exportable as a HAR file for replaying it or
+//! attaching it to a bug report, and listed in the RPC log console.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use web_time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use ehttp::{Request, Response};
+use lazy_static::lazy_static;
+use serde_json::{json, Value};
+
+use crate::helper;
+
+/// How many exchanges the trace keeps, older ones are dropped.
+const TRACE_CAPACITY: usize = 1000;
+/// Characters of the request body shown in the console before it's expanded.
+const PREVIEW_CHARS: usize = 160;
+/// Responses up to this size are read for JSON-RPC errors when recorded.
+const INSPECTED_RESPONSE: usize = 64 * 1024;
+
+/// Number of the last recorded exchange, 0 before the first.
+static LAST_SEQ: AtomicU64 = AtomicU64::new(0);
+
+lazy_static! {
+    static ref TRACE: Mutex<VecDeque<TraceEntry>> = Mutex::new(VecDeque::new());
+}
+
+/// One request with the response or transport error it got.
+struct TraceEntry {
+    started: SystemTime,
+    duration: Duration,
+    request: Request,
+    response: Result<Response, String>,
+    summary: Summary,
+}
+
+/// What the console lists about an exchange, worked out once when it's recorded.
+#[derive(Clone)]
+pub struct Summary {
+    pub seq: u64,
+    pub started: SystemTime,
+    pub duration: Duration,
+    /// The called method, or the methods of a batch with how many calls each.
+    pub method: String,
+    /// Id of the call, or how many calls a batch has.
+    pub id: String,
+    /// Transport or HTTP error, or the JSON-RPC errors in the response.
+    pub error: Option<String>,
+    /// Size of the response body in bytes.
+    pub size: usize,
+    /// Start of the request body.
+    pub preview: String,
+}
+
+impl Summary {
+    fn of(
+        seq: u64,
+        started: SystemTime,
+        duration: Duration,
+        request: &Request,
+        response: &ehttp::Result<Response>,
+    ) -> Self {
+        let call = helper::body_value(&request.body);
+        let (method, id) = match &call {
+            Some(Value::Array(calls)) => {
+                let mut methods: BTreeMap<&str, usize> = BTreeMap::new();
+                for call in calls {
+                    *methods
+                        .entry(call["method"].as_str().unwrap_or("?"))
+                        .or_default() += 1;
+                }
+                let methods: Vec<String> = methods
+                    .into_iter()
+                    .map(|(method, count)| format!("{method} ×{count}"))
+                    .collect();
+                (methods.join(", "), format!("{} calls", calls.len()))
+            }
+            Some(call) => (
+                call["method"].as_str().unwrap_or("?").to_owned(),
+                call.get("id").map(Value::to_string).unwrap_or_default(),
+            ),
+            None => ("?".to_owned(), String::new()),
+        };
+        let (error, size) = match response {
+            Ok(response) if !response.ok => (
+                Some(format!("{} {}", response.status, response.status_text)),
+                response.bytes.len(),
+            ),
+            Ok(response) => (rpc_errors(&response.bytes), response.bytes.len()),
+            Err(e) => (Some(e.clone()), 0),
+        };
+        let mut preview = match (&call, std::str::from_utf8(&request.body)) {
+            (Some(call), Err(_)) => call.to_string(),
+            _ => body_text(&request.body),
+        };
+        if let Some((end, _)) = preview.char_indices().nth(PREVIEW_CHARS) {
+            preview.truncate(end);
+            preview.push('…');
+        }
+        Self {
+            seq,
+            started,
+            duration,
+            method,
+            id,
+            error,
+            size,
+            preview,
+        }
+    }
+}
+
+/// The error messages of a JSON-RPC response, if it's small enough to look at.
+fn rpc_errors(body: &[u8]) -> Option<String> {
+    if body.len() > INSPECTED_RESPONSE {
+        return None;
+    }
+    let outputs = match helper::body_value(body)? {
+        Value::Array(outputs) => outputs,
+        output => vec![output],
+    };
+    let errors: Vec<String> = outputs
+        .iter()
+        .filter_map(|output| output.get("error"))
+        .map(|error| match error["message"].as_str() {
+            Some(message) => message.to_owned(),
+            None => error.to_string(),
+        })
+        .collect();
+    match errors.len() {
+        0 => None,
+        1 => errors.into_iter().next(),
+        n => Some(format!("{n} errors, first: {}", errors[0])),
+    }
+}
+
+/// A body as text, pretty printed if it's JSON or MessagePack.
+fn pretty_text(bytes: &[u8]) -> String {
+    match helper::body_value(bytes) {
+        Some(value) => serde_json::to_string_pretty(&value).unwrap_or_default(),
+        None => body_text(bytes),
+    }
+}
+
+fn body_text(bytes: &[u8]) -> String {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text.to_owned(),
+        Err(_) => format!("<{} bytes of binary data>", bytes.len()),
+    }
+}
+
+/// A request on its way, completed with [`PendingTrace::finish`].
+pub struct PendingTrace {
+    started: SystemTime,
+    timer: Instant,
+    request: Request,
+}
+
+impl PendingTrace {
+    pub fn start(request: &Request) -> Self {
+        Self {
+            started: SystemTime::now(),
+            timer: Instant::now(),
+            request: request.clone(),
+        }
+    }
+
+    pub fn finish(self, response: &ehttp::Result<Response>) {
+        let duration = self.timer.elapsed();
+        let seq = LAST_SEQ.fetch_add(1, Ordering::SeqCst) + 1;
+        let summary = Summary::of(seq, self.started, duration, &self.request, response);
+        let mut trace = TRACE.lock().unwrap();
+        trace.push_back(TraceEntry {
+            started: self.started,
+            duration,
+            request: self.request,
+            response: response.clone(),
+            summary,
+        });
+        while trace.len() > TRACE_CAPACITY {
+            trace.pop_front();
+        }
+    }
+}
+
+pub fn len() -> usize {
+    TRACE.lock().unwrap().len()
+}
+
+/// Number of the last recorded exchange, changes whenever one is added.
+pub fn last_seq() -> u64 {
+    LAST_SEQ.load(Ordering::SeqCst)
+}
+
+/// The recorded exchanges, oldest first.
+pub fn summaries() -> Vec<Summary> {
+    TRACE
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|entry| entry.summary.clone())
+        .collect()
+}
+
+/// Request and response body of an exchange, pretty printed. `None` once it was dropped.
+pub fn bodies(seq: u64) -> Option<(String, String)> {
+    let trace = TRACE.lock().unwrap();
+    let entry = trace.iter().find(|entry| entry.summary.seq == seq)?;
+    let response = match &entry.response {
+        Ok(response) => pretty_text(&response.bytes),
+        Err(e) => e.clone(),
+    };
+    Some((pretty_text(&entry.request.body), response))
+}
+
+pub fn clear() {
+    TRACE.lock().unwrap().clear();
+}
+
+/// Writes the exchanges numbered `seqs` as a JSON array with their decoded bodies.
+pub fn export_json(path: &Path, seqs: &[u64]) -> anyhow::Result<()> {
+    let trace = TRACE.lock().unwrap();
+    let entries: Vec<Value> = trace
+        .iter()
+        .filter(|entry| seqs.contains(&entry.summary.seq))
+        .map(|entry| {
+            let body = |bytes: &[u8]| {
+                helper::body_value(bytes).unwrap_or_else(|| Value::String(body_text(bytes)))
+            };
+            let summary = &entry.summary;
+            json!({
+                "started": iso8601(summary.started),
+                "duration_ms": summary.duration.as_secs_f64() * 1000.0,
+                "method": summary.method,
+                "id": summary.id,
+                "error": summary.error,
+                "request": body(&entry.request.body),
+                "response": match &entry.response {
+                    Ok(response) => body(&response.bytes),
+                    Err(e) => Value::String(e.clone()),
+                },
+            })
+        })
+        .collect();
+    std::fs::write(path, serde_json::to_string_pretty(&entries)?)?;
+    Ok(())
+}
+
+/// Writes the trace as HTTP Archive 1.2.
+pub fn export_har(path: &Path) -> anyhow::Result<()> {
+    let entries: Vec<Value> = TRACE.lock().unwrap().iter().map(har_entry).collect();
+    let har = json!({
+        "log": {
+            "version": "1.2",
+            "creator": {
+                "name": env!("CARGO_PKG_NAME"),
+                "version": env!("CARGO_PKG_VERSION"),
+            },
+            "entries": entries,
+        }
+    });
+    std::fs::write(path, serde_json::to_string_pretty(&har)?)?;
+    Ok(())
+}
+
+fn har_entry(entry: &TraceEntry) -> Value {
+    let time = entry.duration.as_secs_f64() * 1000.0;
+    let request = &entry.request;
+    let (response, error) = match &entry.response {
+        Ok(response) => (
+            json!({
+                "status": response.status,
+                "statusText": response.status_text,
+                "httpVersion": "HTTP/1.1",
+                "cookies": [],
+                "headers": har_headers(&response.headers),
+                "content": har_content(&response.bytes, response.content_type()),
+                "redirectURL": "",
+                "headersSize": -1,
+                "bodySize": response.bytes.len(),
+            }),
+            None,
+        ),
+        // HAR has no place for transport errors, status 0 is what browsers record.
+        Err(e) => (
+            json!({
+                "status": 0,
+                "statusText": "",
+                "httpVersion": "",
+                "cookies": [],
+                "headers": [],
+                "content": { "size": 0, "mimeType": "" },
+                "redirectURL": "",
+                "headersSize": -1,
+                "bodySize": -1,
+            }),
+            Some(e),
+        ),
+    };
+    let mime = request
+        .headers
+        .get("Content-Type")
+        .unwrap_or("application/json");
+    let mut post_data = har_content(&request.body, Some(mime));
+    post_data.as_object_mut().unwrap().remove("size");
+    let mut value = json!({
+        "startedDateTime": iso8601(entry.started),
+        "time": time,
+        "request": {
+            "method": request.method,
+            "url": request.url,
+            "httpVersion": "HTTP/1.1",
+            "cookies": [],
+            "headers": har_headers(&request.headers),
+            "queryString": [],
+            "postData": post_data,
+            "headersSize": -1,
+            "bodySize": request.body.len(),
+        },
+        "response": response,
+        "cache": {},
+        "timings": { "send": 0, "wait": time, "receive": 0 },
+    });
+    if let Some(error) = error {
+        value["_error"] = error.as_str().into();
+    }
+    value
+}
+
+fn har_headers(headers: &ehttp::Headers) -> Vec<Value> {
+    headers
+        .headers
+        .iter()
+        .map(|(name, value)| json!({ "name": name, "value": value }))
+        .collect()
+}
+
+/// Text bodies are kept as they are, binary ones are base64 encoded.
+fn har_content(bytes: &[u8], mime: Option<&str>) -> Value {
+    let mime = mime.unwrap_or("application/json");
+    match std::str::from_utf8(bytes) {
+        Ok(text) => json!({ "size": bytes.len(), "mimeType": mime, "text": text }),
+        Err(_) => json!({
+            "size": bytes.len(),
+            "mimeType": mime,
+            "text": base64(bytes),
+            "encoding": "base64",
+        }),
+    }
+}
+
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, b)| n | (*b as u32) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// UTC timestamp like `2024-05-01T12:30:00.000Z`.
+fn iso8601(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (days, rest) = ((secs / 86_400) as i64, secs % 86_400);
+    // Civil date from days since 1970-01-01, Howard Hinnant's algorithm.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+        rest / 3600,
+        rest / 60 % 60,
+        rest % 60,
+        since_epoch.subsec_millis()
+    )
+}
@@ -0,0 +1,218 @@
+//! Inline editor of `Transform`, with Euler angles instead of the quaternion and values
+//! sent to the game while they are dragged.
+
+use serde_json::{Number, Value};
+use web_time::{Duration, Instant};
+
+use crate::edit::{as_quat, euler_to_quat, quat_to_euler, snap_to, snapping, Snap};
+use crate::spatial::vec3;
+
+/// Time between inserts sent while dragging.
+const DRAG_SEND_INTERVAL: Duration = Duration::from_millis(50);
+const SCALE_STEP: f64 = 0.1;
+
+/// The transform being edited, angles in degrees. Kept while dragging since the angles
+/// read back from the quaternion may flip to an equivalent rotation.
+#[derive(Clone, Copy, PartialEq)]
+struct Parts {
+    translation: [f64; 3],
+    angles: [f64; 3],
+    scale: [f64; 3],
+}
+
+impl Parts {
+    fn read(value: &Value) -> Option<Self> {
+        let vec = |name: &str| value.get(name).and_then(vec3).map(|v| v.map(f64::from));
+        let rotation = value.get("rotation")?;
+        let quat = as_quat(rotation).or_else(|| {
+            let get = |name: &str| rotation.get(name).and_then(Value::as_f64);
+            Some([get("x")?, get("y")?, get("z")?, get("w")?])
+        })?;
+        Some(Self {
+            translation: vec("translation")?,
+            angles: quat_to_euler(quat),
+            scale: vec("scale")?,
+        })
+    }
+
+    /// `original` with the edited fields, each in the form it was sent in.
+    fn write(&self, original: &Value) -> Value {
+        let mut value = original.clone();
+        let quat = euler_to_quat(self.angles);
+        let fields: [(&str, &[f64], &[&str]); 3] = [
+            ("translation", &self.translation, &["x", "y", "z"]),
+            ("rotation", &quat, &["x", "y", "z", "w"]),
+            ("scale", &self.scale, &["x", "y", "z"]),
+        ];
+        for (name, numbers, names) in fields {
+            let Some(field) = value.get_mut(name) else {
+                continue;
+            };
+            let number = |n: f64| Number::from_f64(n).map_or(Value::Null, Value::Number);
+            *field = match field {
+                Value::Object(_) => names
+                    .iter()
+                    .zip(numbers)
+                    .map(|(name, n)| (name.to_string(), number(*n)))
+                    .collect(),
+                _ => numbers.iter().map(|n| number(*n)).collect(),
+            };
+        }
+        value
+    }
+}
+
+/// Translation, rotation as Euler angles and scale as text, for transforms that can't be
+/// edited. Returns `None` when `value` isn't a transform.
+pub fn draw_transform_values(ui: &mut egui::Ui, value: &Value) -> Option<()> {
+    let parts = Parts::read(value)?;
+    let format =
+        |values: [f64; 3], suffix: &str| values.map(|v| format!("{v:.3}{suffix}")).join("  ");
+    egui::Grid::new(ui.id().with("transform_values"))
+        .num_columns(2)
+        .show(ui, |ui| {
+            ui.label("Translation");
+            ui.monospace(format(parts.translation, ""));
+            ui.end_row();
+            ui.label("Rotation");
+            ui.monospace(format(parts.angles, "°"));
+            ui.end_row();
+            ui.label("Scale");
+            ui.monospace(format(parts.scale, ""));
+            ui.end_row();
+        });
+    Some(())
+}
+
+/// What the transform editor wants sent.
+pub enum TransformChange {
+    None,
+    /// A value in the middle of a drag.
+    Dragging(Value),
+    /// The value after a drag, a nudge or a typed number.
+    Done(Value),
+}
+
+/// One line of the editor: its drag speed, nudge step and axis names.
+struct Row {
+    label: &'static str,
+    speed: f64,
+    step: f64,
+    suffix: &'static str,
+    axes: [&'static str; 3],
+}
+
+impl Row {
+    fn new(
+        label: &'static str,
+        speed: f64,
+        step: f64,
+        suffix: &'static str,
+        axes: [&'static str; 3],
+    ) -> Self {
+        Self {
+            label,
+            speed,
+            step,
+            suffix,
+            axes,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct DragState {
+    parts: Parts,
+    last_sent: Instant,
+}
+
+/// Rows of drag values with nudge buttons for translation, rotation and scale. Returns
+/// `None` when `value` isn't a transform.
+pub fn draw_transform(
+    ui: &mut egui::Ui,
+    id: egui::Id,
+    value: &Value,
+    snap: Snap,
+) -> Option<TransformChange> {
+    let state: Option<DragState> = ui.data(|d| d.get_temp(id));
+    let mut parts = match &state {
+        Some(state) => state.parts,
+        None => Parts::read(value)?,
+    };
+    let mut dragging = false;
+    let mut done = false;
+    let rows = [
+        Row::new("Translation", 0.05, snap.grid, "", ["x", "y", "z"]),
+        Row::new("Rotation", 1.0, snap.angle, "°", ["yaw", "pitch", "roll"]),
+        Row::new("Scale", 0.01, SCALE_STEP, "", ["x", "y", "z"]),
+    ];
+    let values = [&mut parts.translation, &mut parts.angles, &mut parts.scale];
+    egui::Grid::new(id.with("grid"))
+        .num_columns(2)
+        .show(ui, |ui| {
+            for (row, values) in rows.into_iter().zip(values) {
+                let Row {
+                    label,
+                    speed,
+                    step,
+                    suffix,
+                    axes,
+                } = row;
+                ui.label(label);
+                ui.horizontal(|ui| {
+                    for (value, axis) in values.iter_mut().zip(axes) {
+                        if ui
+                            .small_button("−")
+                            .on_hover_text(format!("{axis} −{step}"))
+                            .clicked()
+                        {
+                            *value -= step;
+                            done = true;
+                        }
+                        let response = ui
+                            .add(egui::DragValue::new(value).speed(speed).suffix(suffix))
+                            .on_hover_text(axis);
+                        if response.changed() && snapping(ui) && step > 0.0 {
+                            *value = snap_to(*value, step);
+                        }
+                        if response.dragged() && response.changed() {
+                            dragging = true;
+                        } else if response.drag_stopped()
+                            || (response.changed() && !response.dragged())
+                        {
+                            done = true;
+                        }
+                        if ui
+                            .small_button("+")
+                            .on_hover_text(format!("{axis} +{step}"))
+                            .clicked()
+                        {
+                            *value += step;
+                            done = true;
+                        }
+                    }
+                });
+                ui.end_row();
+            }
+        });
+    ui.weak("Ctrl while dragging snaps to the grid and angle steps.");
+    if done {
+        ui.data_mut(|d| d.remove::<DragState>(id));
+        return Some(TransformChange::Done(parts.write(value)));
+    }
+    if !dragging {
+        return Some(TransformChange::None);
+    }
+    let due = state
+        .as_ref()
+        .is_none_or(|state| state.last_sent.elapsed() >= DRAG_SEND_INTERVAL);
+    let last_sent = match &state {
+        Some(state) if !due => state.last_sent,
+        _ => Instant::now(),
+    };
+    ui.data_mut(|d| d.insert_temp(id, DragState { parts, last_sent }));
+    Some(match due {
+        true => TransformChange::Dragging(parts.write(value)),
+        false => TransformChange::None,
+    })
+}
@@ -0,0 +1,489 @@
+//! Transport of the BRP client. Requests go through `ehttp` by default, the `reqwest`
+//! feature swaps in a tokio/reqwest client with connection pooling, HTTP/2 and requests
+//! that can actually be cancelled. `ws://` URLs are sent over a WebSocket instead when
+//! the `websocket` feature is enabled.
+//!
+//! Every transport carries the same JSON-RPC bodies and hands back an `ehttp::Response`,
+//! so encoding and decoding stay in [`crate::helper`].
+//!
+//! Responses that keep streaming in, like the ones of the BRP `+watch` methods, go
+//! through [`stream`] instead.
+//!
+//! WebSocket messages have no headers, signed requests are refused there.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use ehttp::{Request, Response};
+
+use crate::throttle;
+use crate::trace::PendingTrace;
+
+/// Cancels an in-flight request, its callback is then never called. The `ehttp` backend
+/// can't abort requests, there callers have to ignore late results themselves.
+#[derive(Clone, Default)]
+pub struct CancelHandle {
+    #[cfg(feature = "reqwest")]
+    token: Option<tokio_util::sync::CancellationToken>,
+}
+
+impl CancelHandle {
+    pub fn cancel(&self) {
+        #[cfg(feature = "reqwest")]
+        if let Some(token) = &self.token {
+            token.cancel();
+        }
+    }
+}
+
+pub fn fetch(
+    request: Request,
+    on_done: impl 'static + Send + FnOnce(ehttp::Result<Response>),
+) -> CancelHandle {
+    let trace = PendingTrace::start(&request);
+    let on_done = move |response: ehttp::Result<Response>| {
+        trace.finish(&response);
+        if let Ok(response) = &response {
+            if throttle::is_throttling(response) {
+                throttle::record(response);
+            }
+        }
+        on_done(response);
+    };
+    if is_websocket(&request.url) {
+        websocket::send(request, Box::new(on_done));
+        return CancelHandle::default();
+    }
+    http_fetch(request, on_done)
+}
+
+/// Stops a stream started with [`stream`], its callback is not called anymore.
+#[derive(Clone, Default)]
+pub struct StreamHandle {
+    stopped: Arc<AtomicBool>,
+    /// Closes the connection right away with the `reqwest` backend, `ehttp` only notices
+    /// `stopped` with the next chunk.
+    cancel: CancelHandle,
+}
+
+impl StreamHandle {
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        self.cancel.cancel();
+    }
+}
+
+/// Sends a request whose response body keeps coming in. `on_chunk` gets every piece of
+/// the body as it arrives and an empty one once the game closes the stream. Goes through
+/// the same backend as [`fetch`], over a WebSocket every message answering the request is
+/// a chunk.
+pub fn stream(
+    request: Request,
+    on_chunk: impl 'static + Send + Fn(ehttp::Result<Vec<u8>>),
+) -> StreamHandle {
+    let stopped = Arc::new(AtomicBool::new(false));
+    let on_chunk = {
+        let stopped = stopped.clone();
+        move |chunk| {
+            if !stopped.load(Ordering::SeqCst) {
+                on_chunk(chunk);
+            }
+        }
+    };
+    if is_websocket(&request.url) {
+        websocket::subscribe(request, stopped.clone(), Box::new(on_chunk));
+        return StreamHandle {
+            stopped,
+            cancel: CancelHandle::default(),
+        };
+    }
+    let cancel = http_stream(request, stopped.clone(), on_chunk);
+    StreamHandle { stopped, cancel }
+}
+
+#[cfg(not(feature = "reqwest"))]
+fn http_stream(
+    request: Request,
+    stopped: Arc<AtomicBool>,
+    on_chunk: impl 'static + Send + Fn(ehttp::Result<Vec<u8>>),
+) -> CancelHandle {
+    use std::ops::ControlFlow;
+
+    ehttp::streaming::fetch(request, move |part| {
+        if stopped.load(Ordering::SeqCst) {
+            return ControlFlow::Break(());
+        }
+        match part {
+            Ok(ehttp::streaming::Part::Response(response)) if response.ok => {
+                ControlFlow::Continue(())
+            }
+            Ok(ehttp::streaming::Part::Response(response)) => {
+                on_chunk(Err(format!("{} {}", response.status, response.status_text)));
+                ControlFlow::Break(())
+            }
+            Ok(ehttp::streaming::Part::Chunk(chunk)) => {
+                let closed = chunk.is_empty();
+                on_chunk(Ok(chunk));
+                if closed {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            }
+            Err(e) => {
+                on_chunk(Err(e));
+                ControlFlow::Break(())
+            }
+        }
+    });
+    CancelHandle::default()
+}
+
+#[cfg(feature = "reqwest")]
+fn http_stream(
+    request: Request,
+    _stopped: Arc<AtomicBool>,
+    on_chunk: impl 'static + Send + Fn(ehttp::Result<Vec<u8>>),
+) -> CancelHandle {
+    let token = tokio_util::sync::CancellationToken::new();
+    let cancelled = token.clone();
+    backend::RUNTIME.spawn(async move {
+        tokio::select! {
+            () = backend::stream(request, on_chunk) => {}
+            () = cancelled.cancelled() => {}
+        }
+    });
+    CancelHandle { token: Some(token) }
+}
+
+#[cfg(not(feature = "reqwest"))]
+fn http_fetch(
+    request: Request,
+    on_done: impl 'static + Send + FnOnce(ehttp::Result<Response>),
+) -> CancelHandle {
+    ehttp::fetch(request, on_done);
+    CancelHandle::default()
+}
+
+#[cfg(feature = "reqwest")]
+fn http_fetch(
+    request: Request,
+    on_done: impl 'static + Send + FnOnce(ehttp::Result<Response>),
+) -> CancelHandle {
+    let token = tokio_util::sync::CancellationToken::new();
+    let cancelled = token.clone();
+    backend::RUNTIME.spawn(async move {
+        tokio::select! {
+            result = backend::send(request) => on_done(result),
+            _ = cancelled.cancelled() => {}
+        }
+    });
+    CancelHandle { token: Some(token) }
+}
+
+#[cfg(feature = "reqwest")]
+mod backend {
+    use ehttp::{Headers, Request, Response};
+    use std::sync::LazyLock;
+
+    pub static RUNTIME: LazyLock<tokio::runtime::Runtime> = LazyLock::new(|| {
+        tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .enable_all()
+            .build()
+            .expect("Unable to start the HTTP runtime")
+    });
+
+    /// Shared so connections to the game are pooled and kept alive between polls.
+    static CLIENT: LazyLock<reqwest::Client> = LazyLock::new(reqwest::Client::new);
+
+    fn build(request: Request) -> ehttp::Result<reqwest::RequestBuilder> {
+        let method =
+            reqwest::Method::from_bytes(request.method.as_bytes()).map_err(|e| e.to_string())?;
+        let mut builder = CLIENT.request(method, &request.url).body(request.body);
+        for (key, value) in &request.headers.headers {
+            builder = builder.header(key, value);
+        }
+        Ok(builder)
+    }
+
+    pub async fn send(request: Request) -> ehttp::Result<Response> {
+        let mut response = build(request)?.send().await.map_err(|e| e.to_string())?;
+
+        let status = response.status();
+        let mut headers = Headers::default();
+        for (key, value) in response.headers() {
+            headers.insert(key, value.to_str().unwrap_or_default());
+        }
+        let url = response.url().to_string();
+        // Read the body chunk by chunk so a cancellation takes effect mid-transfer.
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(|e| e.to_string())? {
+            bytes.extend_from_slice(&chunk);
+        }
+        Ok(Response {
+            url,
+            ok: status.is_success(),
+            status: status.as_u16(),
+            status_text: status.canonical_reason().unwrap_or_default().to_owned(),
+            headers,
+            bytes,
+        })
+    }
+
+    /// Hands every chunk of the body to `on_chunk` as it arrives, see [`super::stream`].
+    pub async fn stream(request: Request, on_chunk: impl Fn(ehttp::Result<Vec<u8>>)) {
+        let response = match build(request) {
+            Ok(builder) => builder.send().await.map_err(|e| e.to_string()),
+            Err(e) => Err(e),
+        };
+        let mut response = match response {
+            Ok(response) if response.status().is_success() => response,
+            Ok(response) => {
+                let status = response.status();
+                let reason = status.canonical_reason().unwrap_or_default();
+                return on_chunk(Err(format!("{} {reason}", status.as_u16())));
+            }
+            Err(e) => return on_chunk(Err(e)),
+        };
+        loop {
+            match response.chunk().await {
+                Ok(Some(chunk)) => on_chunk(Ok(chunk.to_vec())),
+                // Closed by the game.
+                Ok(None) => return on_chunk(Ok(Vec::new())),
+                Err(e) => return on_chunk(Err(e.to_string())),
+            }
+        }
+    }
+}
+
+type Callback = Box<dyn FnOnce(ehttp::Result<Response>) + Send>;
+type ChunkCallback = Box<dyn Fn(ehttp::Result<Vec<u8>>) + Send>;
+
+fn is_websocket(url: &str) -> bool {
+    url.starts_with("ws://") || url.starts_with("wss://")
+}
+
+#[cfg(not(feature = "websocket"))]
+mod websocket {
+    use super::{Callback, ChunkCallback};
+
+    const UNSUPPORTED: &str =
+        "WebSocket connections need the inspector built with the `websocket` feature";
+
+    pub fn send(_request: ehttp::Request, on_done: Callback) {
+        on_done(Err(UNSUPPORTED.to_owned()));
+    }
+
+    pub fn subscribe(
+        _request: ehttp::Request,
+        _stopped: std::sync::Arc<std::sync::atomic::AtomicBool>,
+        on_chunk: ChunkCallback,
+    ) {
+        on_chunk(Err(UNSUPPORTED.to_owned()));
+    }
+}
+
+#[cfg(feature = "websocket")]
+mod websocket {
+    use super::{Callback, ChunkCallback};
+    use bevy::utils::HashMap;
+    use ehttp::{Headers, Request, Response};
+    use serde_json::Value;
+    use std::io::ErrorKind;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{mpsc, Arc, LazyLock, Mutex};
+    use std::time::{Duration, Instant};
+    use tungstenite::{stream::MaybeTlsStream, Message};
+
+    /// How long the connection thread waits for incoming messages before sending queued ones.
+    const POLL_INTERVAL: Duration = Duration::from_millis(5);
+    /// How long a request waits for its response before it fails, the game may never
+    /// answer it, e.g. when the method hangs.
+    const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+    enum Reply {
+        Once(Callback),
+        /// Every message with the request's id, like the responses of a `+watch` method,
+        /// until stopped.
+        Stream {
+            on_chunk: ChunkCallback,
+            stopped: Arc<AtomicBool>,
+        },
+    }
+
+    /// A request waiting for its response, matched by JSON-RPC ids.
+    struct Pending {
+        ids: Vec<String>,
+        sent: Instant,
+        reply: Reply,
+    }
+
+    impl Pending {
+        fn fail(self, error: &str) {
+            match self.reply {
+                Reply::Once(on_done) => on_done(Err(error.to_owned())),
+                Reply::Stream { on_chunk, .. } => on_chunk(Err(error.to_owned())),
+            }
+        }
+    }
+
+    type Outgoing = mpsc::Sender<(String, Pending)>;
+
+    /// One open connection per URL, each served by its own thread.
+    static CONNECTIONS: LazyLock<Mutex<HashMap<String, Outgoing>>> =
+        LazyLock::new(|| Mutex::new(HashMap::new()));
+
+    pub fn send(request: Request, on_done: Callback) {
+        queue(request, Reply::Once(on_done));
+    }
+
+    /// Sends `request` and hands every message answering it to `on_chunk`, each followed by
+    /// a line break like the lines of a streamed HTTP response.
+    pub fn subscribe(request: Request, stopped: Arc<AtomicBool>, on_chunk: ChunkCallback) {
+        queue(request, Reply::Stream { on_chunk, stopped });
+    }
+
+    fn queue(request: Request, reply: Reply) {
+        let pending = Pending {
+            ids: Vec::new(),
+            sent: Instant::now(),
+            reply,
+        };
+        if let Err(e) = check_headers(&request) {
+            return pending.fail(&e);
+        }
+        let body = match String::from_utf8(request.body) {
+            Ok(body) => body,
+            Err(e) => return pending.fail(&e.to_string()),
+        };
+        let ids = serde_json::from_str(&body).map_or(Vec::new(), |v| ids(&v));
+        let message = (body, Pending { ids, ..pending });
+        let mut connections = CONNECTIONS.lock().unwrap();
+        // A closed connection hands the message back, it then goes to a new one.
+        let message = match connections.get(&request.url) {
+            Some(outgoing) => match outgoing.send(message) {
+                Ok(()) => return,
+                Err(mpsc::SendError(message)) => message,
+            },
+            None => message,
+        };
+        let (outgoing, incoming) = mpsc::channel();
+        outgoing.send(message).expect("receiver is alive");
+        connections.insert(request.url.clone(), outgoing);
+        std::thread::spawn(move || run(request.url, incoming));
+    }
+
+    /// Messages have no headers of their own, requests needing more than the content
+    /// type, like signed ones, would arrive without them and be refused by the game.
+    fn check_headers(request: &Request) -> Result<(), String> {
+        let extra = request.headers.headers.iter().find(|(key, _)| {
+            !key.eq_ignore_ascii_case("Content-Type") && !key.eq_ignore_ascii_case("Accept")
+        });
+        match extra {
+            Some((key, _)) => Err(format!(
+                "The {key} header can't be sent over a WebSocket, connect over HTTP to sign requests"
+            )),
+            None => Ok(()),
+        }
+    }
+
+    fn run(url: String, outgoing: mpsc::Receiver<(String, Pending)>) {
+        let mut pending = Vec::new();
+        let mut socket = match tungstenite::connect(&url) {
+            Ok((socket, _)) => socket,
+            Err(e) => {
+                while let Ok((_, request)) = outgoing.try_recv() {
+                    pending.push(request);
+                }
+                return fail(pending, &e.to_string());
+            }
+        };
+        // Without a timeout reading blocks until the game sends something, and queued
+        // requests wait with it.
+        let stream = match socket.get_mut() {
+            MaybeTlsStream::Plain(stream) => Some(stream),
+            MaybeTlsStream::Rustls(stream) => Some(stream.get_mut()),
+            _ => None,
+        };
+        if let Some(stream) = stream {
+            let _ = stream.set_read_timeout(Some(POLL_INTERVAL));
+        }
+        loop {
+            while let Ok((body, request)) = outgoing.try_recv() {
+                pending.push(request);
+                if let Err(e) = socket.send(Message::Text(body)) {
+                    return fail(pending, &e.to_string());
+                }
+            }
+            expire(&mut pending);
+            let bytes = match socket.read() {
+                Ok(Message::Text(text)) => text.into_bytes(),
+                Ok(Message::Binary(bytes)) => bytes,
+                Ok(_) => continue,
+                Err(tungstenite::Error::Io(e))
+                    if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+                {
+                    continue
+                }
+                Err(e) => return fail(pending, &e.to_string()),
+            };
+            let Ok(value) = serde_json::from_slice::<Value>(&bytes) else {
+                continue;
+            };
+            let response_ids = ids(&value);
+            // Messages matching no request are notifications, those are ignored.
+            let Some(index) = pending
+                .iter()
+                .position(|p| p.ids.iter().any(|id| response_ids.contains(id)))
+            else {
+                continue;
+            };
+            if let Reply::Stream { on_chunk, .. } = &pending[index].reply {
+                let mut line = bytes;
+                line.push(b'\n');
+                on_chunk(Ok(line));
+                continue;
+            }
+            let Reply::Once(on_done) = pending.swap_remove(index).reply else {
+                unreachable!("streams are kept");
+            };
+            on_done(Ok(Response {
+                url: url.clone(),
+                ok: true,
+                status: 200,
+                status_text: "OK".to_owned(),
+                headers: Headers::default(),
+                bytes,
+            }));
+        }
+    }
+
+    /// Drops stopped streams and fails requests that waited too long.
+    fn expire(pending: &mut Vec<Pending>) {
+        pending.retain(|p| match &p.reply {
+            Reply::Stream { stopped, .. } => !stopped.load(Ordering::SeqCst),
+            Reply::Once(_) => true,
+        });
+        let (expired, waiting) = std::mem::take(pending)
+            .into_iter()
+            .partition(|p| matches!(p.reply, Reply::Once(_)) && p.sent.elapsed() > REQUEST_TIMEOUT);
+        *pending = waiting;
+        // Read as a timeout by `helper::check_response`.
+        fail(expired, "Request timed out");
+    }
+
+    fn fail(pending: Vec<Pending>, error: &str) {
+        for request in pending {
+            request.fail(error);
+        }
+    }
+
+    /// JSON-RPC ids of a single message or a batch.
+    fn ids(value: &Value) -> Vec<String> {
+        match value {
+            Value::Array(items) => items.iter().map(|item| item["id"].to_string()).collect(),
+            _ => vec![value["id"].to_string()],
+        }
+    }
+}
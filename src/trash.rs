@@ -0,0 +1,61 @@
+use bevy::{prelude::Entity, remote::builtin_methods::BrpSpawnParams};
+use std::collections::VecDeque;
+
+/// How many destroyed entities are kept around for respawning.
+const TRASH_CAPACITY: usize = 50;
+
+/// Last known state of an entity destroyed from the inspector.
+pub struct TrashedEntity {
+    /// Id it had, the parent of its children in [`Self::descendants`].
+    pub entity: Entity,
+    pub label: String,
+    pub params: BrpSpawnParams,
+    /// Destroyed along with it, see [`TrashedNode`].
+    pub descendants: Vec<TrashedNode>,
+}
+
+/// A descendant of a destroyed entity. They are listed parents first, so each one is
+/// respawned after its parent and put below the parent's new id.
+#[derive(Clone)]
+pub struct TrashedNode {
+    pub entity: Entity,
+    pub parent: Entity,
+    pub params: BrpSpawnParams,
+}
+
+/// Safety net for accidental deletes, newest entries first.
+#[derive(Default)]
+pub struct Trash {
+    entries: VecDeque<TrashedEntity>,
+}
+
+impl Trash {
+    pub fn push(&mut self, entry: TrashedEntity) {
+        self.entries.push_front(entry);
+        self.entries.truncate(TRASH_CAPACITY);
+    }
+
+    pub fn take(&mut self, index: usize) -> Option<TrashedEntity> {
+        self.entries.remove(index)
+    }
+
+    pub fn take_all(&mut self) -> Vec<TrashedEntity> {
+        self.entries.drain(..).collect()
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &TrashedEntity> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
@@ -0,0 +1,100 @@
+//! The entity hierarchy as a tree, built from both directions of the parent/child
+//! relationship so a link missing on one side still places the child.
+
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow, utils::HashMap};
+
+use crate::compat::TypePaths;
+use crate::ordering::children;
+use crate::problems::parent_of;
+use crate::summary::entity_name;
+
+#[derive(Default)]
+pub struct EntityTree {
+    /// Entities without a parent, or with one that wasn't fetched. Sorted by id.
+    pub roots: Vec<Entity>,
+    children: HashMap<Entity, Vec<Entity>>,
+    parents: HashMap<Entity, Entity>,
+}
+
+impl EntityTree {
+    pub fn build(world: &HashMap<Entity, BrpQueryRow>, paths: &TypePaths) -> Self {
+        let mut tree = Self::default();
+        let mut sorted: Vec<&Entity> = world.keys().collect();
+        sorted.sort();
+        // `Children` first to keep the sibling order the game uses.
+        for entity in &sorted {
+            for child in children(&world[*entity], paths) {
+                if world.contains_key(&child) && !tree.parents.contains_key(&child) {
+                    tree.parents.insert(child, **entity);
+                    tree.children.entry(**entity).or_default().push(child);
+                }
+            }
+        }
+        for entity in &sorted {
+            let Some(parent) = parent_of(&world[*entity], paths) else {
+                continue;
+            };
+            if world.contains_key(&parent) && !tree.parents.contains_key(*entity) {
+                tree.parents.insert(**entity, parent);
+                tree.children.entry(parent).or_default().push(**entity);
+            }
+        }
+        tree.roots = sorted
+            .into_iter()
+            .filter(|entity| !tree.parents.contains_key(*entity))
+            .copied()
+            .collect();
+        tree
+    }
+
+    pub fn children(&self, entity: &Entity) -> &[Entity] {
+        self.children.get(entity).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn parent(&self, entity: &Entity) -> Option<Entity> {
+        self.parents.get(entity).copied()
+    }
+
+    /// Names from the root down to `entity`, like `Root/Level_01/Enemies/Goblin#42`. The
+    /// entity's own index follows its name, unnamed entities are only their index.
+    pub fn path(
+        &self,
+        entity: &Entity,
+        rows: &HashMap<Entity, BrpQueryRow>,
+        paths: &TypePaths,
+    ) -> String {
+        let name = |entity: &Entity| rows.get(entity).and_then(|row| entity_name(row, paths));
+        let mut segments = vec![format!("{}#{}", name(entity).unwrap_or(""), entity.index())];
+        let mut current = self.parent(entity);
+        // Bounded in case the game's hierarchy has a cycle.
+        for _ in 0..self.parents.len() {
+            let Some(ancestor) = current else {
+                break;
+            };
+            segments.push(match name(&ancestor) {
+                Some(name) => name.to_owned(),
+                None => format!("#{}", ancestor.index()),
+            });
+            current = self.parent(&ancestor);
+        }
+        segments.reverse();
+        segments.join("/")
+    }
+
+    /// Whether `ancestor` is `entity` itself or above it, reparenting `ancestor` below
+    /// `entity` would make a cycle then.
+    pub fn is_ancestor(&self, ancestor: &Entity, entity: &Entity) -> bool {
+        let mut current = Some(*entity);
+        // Bounded in case the game's hierarchy has a cycle.
+        for _ in 0..=self.parents.len() {
+            let Some(entity) = current else {
+                return false;
+            };
+            if entity == *ancestor {
+                return true;
+            }
+            current = self.parent(&entity);
+        }
+        false
+    }
+}
@@ -0,0 +1,151 @@
+//! Every component type the game registered, searchable and grouped by crate, with how
+//! many of the cached entities have each.
+
+use std::collections::BTreeMap;
+
+use bevy::{prelude::Entity, remote::builtin_methods::BrpQueryRow, utils::HashMap};
+use egui::RichText;
+
+use crate::palette::status_colors;
+use crate::schema::Schemas;
+use crate::summary::short_name;
+
+pub struct TypeEntry {
+    pub path: String,
+    /// Whether the game can send it, `None` when neither the schema nor a failed query
+    /// told.
+    pub serializable: Option<bool>,
+    /// Cached entities having the component.
+    pub entities: usize,
+}
+
+/// The registered types, the ones queries rejected included, in path order.
+pub fn catalog(
+    types: &[String],
+    rejected: &HashMap<String, String>,
+    schemas: &Schemas,
+    rows: &HashMap<Entity, BrpQueryRow>,
+) -> Vec<TypeEntry> {
+    let mut counts: HashMap<&str, usize> = HashMap::default();
+    for row in rows.values() {
+        for key in row.components.keys() {
+            *counts.entry(key.as_str()).or_default() += 1;
+        }
+    }
+    let mut paths: Vec<&String> = types.iter().chain(rejected.keys()).collect();
+    paths.sort();
+    paths.dedup();
+    paths
+        .into_iter()
+        .map(|path| TypeEntry {
+            path: path.clone(),
+            serializable: match rejected.contains_key(path) {
+                true => Some(false),
+                false => schemas.is_serializable(path),
+            },
+            entities: counts.get(path.as_str()).copied().unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// The crate a type comes from, the first segment of its path before any generics.
+pub fn crate_name(type_path: &str) -> &str {
+    let path = type_path.split('<').next().unwrap_or(type_path);
+    path.split("::").next().unwrap_or(path)
+}
+
+/// What was clicked in the catalog.
+pub enum CatalogAction {
+    None,
+    /// Show only the entities having the type.
+    FilterEntities(String),
+    ShowDocs(String),
+}
+
+/// Search text and options of the type catalog.
+#[derive(Default)]
+pub struct TypeCatalog {
+    pub filter: String,
+    /// Hide types no cached entity has.
+    pub in_use_only: bool,
+}
+
+impl TypeCatalog {
+    /// A collapsible section per crate listing its types.
+    pub fn draw(&mut self, ui: &mut egui::Ui, entries: &[TypeEntry]) -> CatalogAction {
+        let mut action = CatalogAction::None;
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.filter)
+                    .hint_text("Search types")
+                    .desired_width(200.0),
+            );
+            ui.checkbox(&mut self.in_use_only, "In use only");
+        });
+        let filter = self.filter.to_lowercase();
+        let mut crates: BTreeMap<&str, Vec<&TypeEntry>> = BTreeMap::new();
+        for entry in entries {
+            if self.in_use_only && entry.entities == 0 {
+                continue;
+            }
+            if !filter.is_empty() && !entry.path.to_lowercase().contains(&filter) {
+                continue;
+            }
+            crates
+                .entry(crate_name(&entry.path))
+                .or_default()
+                .push(entry);
+        }
+        let shown: usize = crates.values().map(Vec::len).sum();
+        ui.weak(format!("{shown} of {} types", entries.len()));
+        let colors = status_colors(ui.ctx());
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (name, types) in crates {
+                let title = format!("{name} ({})", types.len());
+                egui::CollapsingHeader::new(title)
+                    .id_salt(("type_crate", name))
+                    .default_open(!filter.is_empty())
+                    .show(ui, |ui| {
+                        egui::Grid::new(("type_catalog", name))
+                            .num_columns(3)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for entry in types {
+                                    if ui
+                                        .link(short_name(&entry.path))
+                                        .on_hover_text(RichText::new(&entry.path).monospace())
+                                        .clicked()
+                                    {
+                                        action = CatalogAction::ShowDocs(entry.path.clone());
+                                    }
+                                    match entry.serializable {
+                                        Some(true) => ui.colored_label(colors.ok, "✔"),
+                                        Some(false) => ui.colored_label(colors.error, "✖"),
+                                        None => ui.weak("?"),
+                                    }
+                                    .on_hover_text(match entry
+                                        .serializable
+                                    {
+                                        Some(true) => "Serializable",
+                                        Some(false) => "Not serializable, can't be fetched",
+                                        None => "Unknown whether it's serializable",
+                                    });
+                                    let entities = format!("{} entities", entry.entities);
+                                    if entry.entities == 0 {
+                                        ui.weak(entities);
+                                    } else if ui
+                                        .link(entities)
+                                        .on_hover_text("Show only entities having it")
+                                        .clicked()
+                                    {
+                                        action = CatalogAction::FilterEntities(entry.path.clone());
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                    });
+            }
+        });
+        action
+    }
+}
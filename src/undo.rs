@@ -0,0 +1,231 @@
+//! Changes the inspector sent to the game, kept so they can be reversed by sending the
+//! opposite request and redone afterwards.
+
+use bevy::{prelude::Entity, remote::builtin_methods::BrpSpawnParams};
+use serde_json::Value;
+
+use crate::summary::short_name;
+use crate::trash::TrashedNode;
+
+/// How many changes can be undone.
+const UNDO_LIMIT: usize = 100;
+
+#[derive(Clone)]
+pub enum Mutation {
+    /// `before` is `None` when the entity didn't have the component.
+    Insert {
+        entity: Entity,
+        component: String,
+        before: Option<Value>,
+        after: Value,
+    },
+    Remove {
+        entity: Entity,
+        component: String,
+        value: Value,
+    },
+    Reparent {
+        entity: Entity,
+        before: Option<Entity>,
+        after: Option<Entity>,
+    },
+    /// `bevy/destroy` takes the descendants along, they are kept to be respawned too.
+    Destroy {
+        entity: Entity,
+        label: String,
+        params: BrpSpawnParams,
+        parent: Option<Entity>,
+        descendants: Vec<TrashedNode>,
+    },
+    /// Brings back what a [`Mutation::Destroy`] removed, with new ids for `entity` and
+    /// its descendants.
+    Respawn {
+        entity: Entity,
+        label: String,
+        params: BrpSpawnParams,
+        parent: Option<Entity>,
+        descendants: Vec<TrashedNode>,
+    },
+}
+
+impl Mutation {
+    /// The change that reverses this one.
+    pub fn inverse(&self) -> Mutation {
+        match self {
+            Mutation::Insert {
+                entity,
+                component,
+                before: Some(before),
+                after,
+            } => Mutation::Insert {
+                entity: *entity,
+                component: component.clone(),
+                before: Some(after.clone()),
+                after: before.clone(),
+            },
+            Mutation::Insert {
+                entity,
+                component,
+                before: None,
+                after,
+            } => Mutation::Remove {
+                entity: *entity,
+                component: component.clone(),
+                value: after.clone(),
+            },
+            Mutation::Remove {
+                entity,
+                component,
+                value,
+            } => Mutation::Insert {
+                entity: *entity,
+                component: component.clone(),
+                before: None,
+                after: value.clone(),
+            },
+            Mutation::Reparent {
+                entity,
+                before,
+                after,
+            } => Mutation::Reparent {
+                entity: *entity,
+                before: *after,
+                after: *before,
+            },
+            Mutation::Destroy {
+                entity,
+                label,
+                params,
+                parent,
+                descendants,
+            } => Mutation::Respawn {
+                entity: *entity,
+                label: label.clone(),
+                params: params.clone(),
+                parent: *parent,
+                descendants: descendants.clone(),
+            },
+            Mutation::Respawn {
+                entity,
+                label,
+                params,
+                parent,
+                descendants,
+            } => Mutation::Destroy {
+                entity: *entity,
+                label: label.clone(),
+                params: params.clone(),
+                parent: *parent,
+                descendants: descendants.clone(),
+            },
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            Mutation::Insert {
+                entity, component, ..
+            } => format!("Set {} of {entity}", short_name(component)),
+            Mutation::Remove {
+                entity, component, ..
+            } => format!("Remove {} from {entity}", short_name(component)),
+            Mutation::Reparent { entity, .. } => format!("Move {entity}"),
+            Mutation::Destroy { label, .. } => format!("Destroy {label}"),
+            Mutation::Respawn { label, .. } => format!("Respawn {label}"),
+        }
+    }
+
+    fn entities_mut(&mut self) -> Vec<&mut Entity> {
+        match self {
+            Mutation::Insert { entity, .. } | Mutation::Remove { entity, .. } => vec![entity],
+            Mutation::Reparent {
+                entity,
+                before,
+                after,
+            } => [Some(entity), before.as_mut(), after.as_mut()]
+                .into_iter()
+                .flatten()
+                .collect(),
+            Mutation::Destroy {
+                entity,
+                parent,
+                descendants,
+                ..
+            }
+            | Mutation::Respawn {
+                entity,
+                parent,
+                descendants,
+                ..
+            } => {
+                let mut entities: Vec<&mut Entity> = [Some(entity), parent.as_mut()]
+                    .into_iter()
+                    .flatten()
+                    .collect();
+                for node in descendants {
+                    entities.push(&mut node.entity);
+                    entities.push(&mut node.parent);
+                }
+                entities
+            }
+        }
+    }
+}
+
+/// Changes that can be undone, newest last, and the undone ones that can be redone.
+#[derive(Default)]
+pub struct UndoHistory {
+    done: Vec<Mutation>,
+    undone: Vec<Mutation>,
+}
+
+impl UndoHistory {
+    /// Records a change just sent, which drops the ones that could be redone.
+    pub fn record(&mut self, mutation: Mutation) {
+        self.done.push(mutation);
+        if self.done.len() > UNDO_LIMIT {
+            self.done.remove(0);
+        }
+        self.undone.clear();
+    }
+
+    /// The change to send to undo the last one.
+    pub fn undo(&mut self) -> Option<Mutation> {
+        let mutation = self.done.pop()?;
+        let inverse = mutation.inverse();
+        self.undone.push(mutation);
+        Some(inverse)
+    }
+
+    /// The last undone change, to send again.
+    pub fn redo(&mut self) -> Option<Mutation> {
+        let mutation = self.undone.pop()?;
+        let again = mutation.clone();
+        self.done.push(mutation);
+        Some(again)
+    }
+
+    pub fn next_undo(&self) -> Option<&Mutation> {
+        self.done.last()
+    }
+
+    pub fn next_redo(&self) -> Option<&Mutation> {
+        self.undone.last()
+    }
+
+    /// Points the changes about `from` to `to`, after it was respawned with a new id.
+    pub fn remap(&mut self, from: Entity, to: Entity) {
+        for mutation in self.done.iter_mut().chain(self.undone.iter_mut()) {
+            for entity in mutation.entities_mut() {
+                if *entity == from {
+                    *entity = to;
+                }
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.done.clear();
+        self.undone.clear();
+    }
+}
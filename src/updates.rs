@@ -0,0 +1,227 @@
+//! Looking up the latest release on GitHub, so users who installed the inspector once
+//! hear about new versions. Only done when turned on in the settings.
+
+use std::sync::{Arc, Mutex};
+
+use egui::RichText;
+use serde::Deserialize;
+
+use crate::palette::status_colors;
+use crate::power;
+
+const RELEASES_URL: &str =
+    "https://api.github.com/repos/Leinnan/bevy_remote_inspector/releases/latest";
+
+/// The version of this build.
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Release {
+    /// Version tag, e.g. `v0.2.0`.
+    #[serde(rename = "tag_name")]
+    pub tag: String,
+    /// Release notes in Markdown.
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Page of the release with its downloads.
+    #[serde(rename = "html_url")]
+    pub url: String,
+}
+
+impl Release {
+    pub fn version(&self) -> &str {
+        self.tag.trim_start_matches('v')
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub enum UpdateStatus {
+    #[default]
+    Unchecked,
+    Checking,
+    UpToDate,
+    Available(Release),
+    Failed(String),
+}
+
+#[derive(Default)]
+pub struct UpdateChecker {
+    status: Arc<Mutex<UpdateStatus>>,
+    /// Set once the check on startup was started.
+    checked_on_startup: bool,
+    show_changelog: bool,
+}
+
+impl UpdateChecker {
+    /// Checks once per run, when `enabled`.
+    pub fn check_on_startup(&mut self, ctx: &egui::Context, enabled: bool) {
+        if enabled && !std::mem::replace(&mut self.checked_on_startup, true) {
+            self.check(ctx);
+        }
+    }
+
+    pub fn check(&self, ctx: &egui::Context) {
+        *self.status.lock().unwrap() = UpdateStatus::Checking;
+        let request = ehttp::Request {
+            headers: ehttp::Headers::new(&[
+                ("Accept", "application/vnd.github+json"),
+                ("User-Agent", "bevy_remote_inspector"),
+            ]),
+            ..ehttp::Request::get(RELEASES_URL)
+        };
+        let status = self.status.clone();
+        let ctx = ctx.clone();
+        ehttp::fetch(request, move |result| {
+            let checked = match result {
+                Ok(response) if response.ok => {
+                    match serde_json::from_slice::<Release>(&response.bytes) {
+                        Ok(release) if is_newer(release.version(), CURRENT_VERSION) => {
+                            UpdateStatus::Available(release)
+                        }
+                        Ok(_) => UpdateStatus::UpToDate,
+                        Err(e) => UpdateStatus::Failed(format!("Unexpected response: {e}")),
+                    }
+                }
+                Ok(response) => {
+                    UpdateStatus::Failed(format!("{} {}", response.status, response.status_text))
+                }
+                Err(error) => UpdateStatus::Failed(error),
+            };
+            *status.lock().unwrap() = checked;
+            ctx.request_repaint();
+        });
+    }
+
+    /// The newer release found, unless it's the one `skipped`.
+    fn available(&self, skipped: Option<&str>) -> Option<Release> {
+        match &*self.status.lock().unwrap() {
+            UpdateStatus::Available(release) if Some(release.version()) != skipped => {
+                Some(release.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// A link to the changelog when there's a new version, for the top bar.
+    pub fn draw_notice(&mut self, ui: &mut egui::Ui, skipped: Option<&str>) {
+        let Some(release) = self.available(skipped) else {
+            return;
+        };
+        ui.add_space(15.0);
+        let notice = RichText::new(format!("⬆ {} available", release.version()))
+            .color(status_colors(ui.ctx()).ok);
+        if ui.link(notice).on_hover_text("Show what changed").clicked() {
+            self.show_changelog = !self.show_changelog;
+        }
+    }
+
+    /// The changelog of the new version with its download link. Skipping the version
+    /// writes it to `skipped`, so it's not brought up again.
+    pub fn show(&mut self, ctx: &egui::Context, skipped: &mut Option<String>) {
+        if !self.show_changelog {
+            return;
+        }
+        let Some(release) = self.available(skipped.as_deref()) else {
+            self.show_changelog = false;
+            return;
+        };
+        let mut open = true;
+        egui::Window::new(format!("Version {}", release.version()))
+            .open(&mut open)
+            .default_width(420.0)
+            .show(ctx, |ui| {
+                ui.label(format!("You are running version {CURRENT_VERSION}."));
+                ui.separator();
+                egui::ScrollArea::vertical()
+                    .max_height(320.0)
+                    .show(ui, |ui| match release.body.as_deref().map(str::trim) {
+                        Some(notes) if !notes.is_empty() => {
+                            ui.label(notes);
+                        }
+                        _ => {
+                            ui.weak("This release has no notes.");
+                        }
+                    });
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.hyperlink_to("Download", &release.url);
+                    if ui.button("Skip this version").clicked() {
+                        *skipped = Some(release.version().to_owned());
+                    }
+                    if ui.button("Later").clicked() {
+                        self.show_changelog = false;
+                    }
+                });
+            });
+        self.show_changelog &= open;
+    }
+
+    /// The opt-in checkbox, a button to check right away and the outcome.
+    pub fn draw_settings(
+        &mut self,
+        ui: &mut egui::Ui,
+        enabled: &mut bool,
+        skipped: &mut Option<String>,
+    ) {
+        ui.checkbox(enabled, "Check for updates on startup")
+            .on_hover_text("Looks up the latest release on GitHub");
+        let status = self.status.lock().unwrap().clone();
+        ui.horizontal(|ui| {
+            let checking = matches!(status, UpdateStatus::Checking);
+            if ui
+                .add_enabled(!checking, egui::Button::new("Check now"))
+                .clicked()
+            {
+                self.check(ui.ctx());
+            }
+            let colors = status_colors(ui.ctx());
+            match status {
+                UpdateStatus::Unchecked => {
+                    ui.weak(format!("Version {CURRENT_VERSION}"));
+                }
+                UpdateStatus::Checking => {
+                    power::spinner(ui);
+                }
+                UpdateStatus::UpToDate => {
+                    ui.label(format!("{CURRENT_VERSION} is the latest version"));
+                }
+                UpdateStatus::Available(release) => {
+                    if ui
+                        .link(
+                            RichText::new(format!("{} available", release.version()))
+                                .color(colors.ok),
+                        )
+                        .clicked()
+                    {
+                        // Asked for explicitly, so a skipped version is shown again.
+                        *skipped = None;
+                        self.show_changelog = true;
+                    }
+                }
+                UpdateStatus::Failed(error) => {
+                    ui.label(RichText::new(error).color(colors.error));
+                }
+            }
+        });
+    }
+}
+
+/// Whether `version` is later than `current`, comparing the dot separated numbers and
+/// ignoring pre-release and build suffixes.
+fn is_newer(version: &str, current: &str) -> bool {
+    fn numbers(version: &str) -> Vec<u64> {
+        let mut numbers: Vec<u64> = version
+            .split(['-', '+'])
+            .next()
+            .unwrap_or_default()
+            .split('.')
+            .map(|part| part.trim().parse().unwrap_or(0))
+            .collect();
+        // So that 0.2 and 0.2.0 are the same.
+        while numbers.last() == Some(&0) {
+            numbers.pop();
+        }
+        numbers
+    }
+    numbers(version) > numbers(current)
+}
@@ -0,0 +1,175 @@
+//! How values are shown where they are only read: decimals of floats, separators in
+//! large counts, vectors on one line and colors with a swatch. Set once per frame like the
+//! status palette, so every view formats alike.
+
+use egui::RichText;
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
+
+use crate::widgets::{as_color32, is_color, is_vector};
+
+/// Values longer than this on one line are shown expanded.
+const INLINE_LENGTH: usize = 60;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(default)]
+pub struct ValueFormat {
+    /// Decimal places of floats.
+    pub decimals: usize,
+    /// `1,234,567` instead of `1234567`.
+    pub thousands_separators: bool,
+    /// Vectors as `(1.0, 2.0, 3.0)` instead of one number per line.
+    pub inline_vectors: bool,
+    pub color_swatches: bool,
+}
+
+impl Default for ValueFormat {
+    fn default() -> Self {
+        Self {
+            decimals: 3,
+            thousands_separators: true,
+            inline_vectors: true,
+            color_swatches: true,
+        }
+    }
+}
+
+impl ValueFormat {
+    pub fn number(&self, number: &Number) -> String {
+        if let Some(int) = number.as_i64() {
+            return self.group(&int.to_string());
+        }
+        if let Some(uint) = number.as_u64() {
+            return self.group(&uint.to_string());
+        }
+        let float = number.as_f64().unwrap_or_default();
+        self.group(&format!("{float:.*}", self.decimals))
+    }
+
+    /// Puts separators between the thousands of the integer part of `digits`.
+    fn group(&self, digits: &str) -> String {
+        if !self.thousands_separators {
+            return digits.to_owned();
+        }
+        let (sign, unsigned) = match digits.strip_prefix('-') {
+            Some(rest) => ("-", rest),
+            None => ("", digits),
+        };
+        let (int, fraction) = match unsigned.find('.') {
+            Some(dot) => unsigned.split_at(dot),
+            None => (unsigned, ""),
+        };
+        let mut grouped = String::with_capacity(digits.len() + int.len() / 3);
+        for (index, digit) in int.chars().enumerate() {
+            if index > 0 && (int.len() - index) % 3 == 0 {
+                grouped.push(',');
+            }
+            grouped.push(digit);
+        }
+        format!("{sign}{grouped}{fraction}")
+    }
+
+    /// Single line form of a value, unwrapping newtypes like `Score(42)`.
+    pub fn text(&self, value: &Value) -> String {
+        match value {
+            Value::String(text) => text.clone(),
+            Value::Number(number) => self.number(number),
+            Value::Array(items) if is_vector(value) => format!("({})", self.join(items)),
+            Value::Object(map) if map.len() == 1 && !is_color(value) => {
+                self.text(map.values().next().unwrap())
+            }
+            Value::Object(map) => {
+                let fields: Vec<String> = map
+                    .iter()
+                    .map(|(key, value)| format!("{key}: {}", self.text(value)))
+                    .collect();
+                format!("{{ {} }}", fields.join(", "))
+            }
+            Value::Array(items) => format!("[{}]", self.join(items)),
+            Value::Bool(_) | Value::Null => value.to_string(),
+        }
+    }
+
+    fn join(&self, items: &[Value]) -> String {
+        let items: Vec<String> = items.iter().map(|item| self.text(item)).collect();
+        items.join(", ")
+    }
+
+    /// `value` as read-only fields, one per line, short ones inline.
+    pub fn show(&self, ui: &mut egui::Ui, value: &Value) {
+        if self.color_swatches {
+            if let Some(color) = as_color32(value) {
+                ui.horizontal(|ui| {
+                    egui::color_picker::show_color(ui, color, egui::vec2(24.0, 14.0));
+                    ui.label(RichText::new(self.text(value)).monospace());
+                });
+                return;
+            }
+        }
+        match value {
+            Value::Array(items) if is_vector(value) && !self.inline_vectors => {
+                for (item, axis) in items.iter().zip(["x", "y", "z", "w"]) {
+                    ui.label(RichText::new(format!("{axis}: {}", self.text(item))).monospace());
+                }
+            }
+            Value::Object(map) if !map.is_empty() => {
+                for (key, field) in map {
+                    self.show_field(ui, key, field);
+                }
+            }
+            Value::Array(items) if !items.is_empty() && !self.is_inline(value) => {
+                for (index, item) in items.iter().enumerate() {
+                    self.show_field(ui, &index.to_string(), item);
+                }
+            }
+            _ => {
+                ui.label(RichText::new(self.text(value)).monospace());
+            }
+        }
+    }
+
+    fn show_field(&self, ui: &mut egui::Ui, key: &str, value: &Value) {
+        if self.is_inline(value) {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(format!("{key}:")).strong());
+                self.show(ui, value);
+            });
+        } else {
+            ui.label(RichText::new(format!("{key}:")).strong());
+            ui.indent(key, |ui| self.show(ui, value));
+        }
+    }
+
+    fn is_inline(&self, value: &Value) -> bool {
+        match value {
+            Value::Array(_) if is_vector(value) => self.inline_vectors,
+            Value::Object(_) if self.color_swatches && is_color(value) => true,
+            Value::Array(_) | Value::Object(_) => self.text(value).len() <= INLINE_LENGTH,
+            _ => true,
+        }
+    }
+
+    /// The settings, in the settings menu.
+    pub fn edit(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label("Decimals:");
+            ui.add(egui::DragValue::new(&mut self.decimals).range(0..=9));
+        });
+        ui.checkbox(&mut self.thousands_separators, "Thousands separators");
+        ui.checkbox(&mut self.inline_vectors, "Vectors on one line");
+        ui.checkbox(&mut self.color_swatches, "Color swatches");
+    }
+}
+
+fn format_id() -> egui::Id {
+    egui::Id::new("value_format")
+}
+
+/// Makes `format` the one returned by [`value_format`], called once per frame.
+pub fn set_value_format(ctx: &egui::Context, format: ValueFormat) {
+    ctx.data_mut(|d| d.insert_temp(format_id(), format));
+}
+
+pub fn value_format(ctx: &egui::Context) -> ValueFormat {
+    ctx.data(|d| d.get_temp(format_id())).unwrap_or_default()
+}
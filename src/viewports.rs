@@ -0,0 +1,100 @@
+//! Panels that can be moved out of the main window into native windows of their own,
+//! e.g. to keep monitoring panels on a second screen, or docked to one of its sides.
+
+use egui::{ViewportBuilder, ViewportClass, ViewportId};
+
+use crate::dock::{self, DockSide};
+
+/// Shows a panel as a window inside the main window, docked to a side of it when the
+/// layout says so or, when `detached`, in its own native window. `key` identifies the
+/// panel across frames and titles. Returns `true` when the user asked to move the panel
+/// between the main window and its own.
+pub fn show_panel(
+    ctx: &egui::Context,
+    key: &str,
+    title: &str,
+    open: &mut bool,
+    detached: bool,
+    add_contents: impl FnOnce(&mut egui::Ui),
+) -> bool {
+    if !*open {
+        return false;
+    }
+    let mut toggled = false;
+    let docked = dock::side(ctx, key).filter(|_| !detached);
+    let mut close = false;
+    let mut add_contents = Some(add_contents);
+    let mut contents = |ui: &mut egui::Ui| {
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Min), |ui| {
+            if docked.is_some() && ui.small_button("✖").on_hover_text("Close").clicked() {
+                close = true;
+            }
+            let (icon, hint) = if detached {
+                ("⮪", "Move back into the main window")
+            } else {
+                ("⮫", "Move to its own window")
+            };
+            toggled = ui.small_button(icon).on_hover_text(hint).clicked();
+            if !detached {
+                ui.menu_button("⊞", |ui| {
+                    if ui.radio(docked.is_none(), "Float").clicked() {
+                        dock::request(ui.ctx(), key, None);
+                        ui.close_menu();
+                    }
+                    for side in DockSide::ALL {
+                        if ui.radio(docked == Some(side), side.label()).clicked() {
+                            dock::request(ui.ctx(), key, Some(side));
+                            ui.close_menu();
+                        }
+                    }
+                })
+                .response
+                .on_hover_text("Dock to a side of the main window");
+            }
+            if docked.is_some() {
+                ui.with_layout(egui::Layout::left_to_right(egui::Align::Min), |ui| {
+                    ui.strong(title);
+                });
+            }
+        });
+        if let Some(add_contents) = add_contents.take() {
+            add_contents(ui);
+        }
+    };
+    if docked.is_some() {
+        dock::show_docked(ctx, key, contents);
+        if close {
+            *open = false;
+        }
+        return toggled;
+    }
+    if !detached {
+        egui::Window::new(title)
+            .id(egui::Id::new(("panel", key)))
+            .open(open)
+            .default_size([320.0, 320.0])
+            .show(ctx, contents);
+        return toggled;
+    }
+    let builder = ViewportBuilder::default()
+        .with_title(title)
+        .with_inner_size([400.0, 400.0]);
+    ctx.show_viewport_immediate(
+        ViewportId::from_hash_of(("panel", key)),
+        builder,
+        |ctx, class| {
+            if class == ViewportClass::Embedded {
+                // The backend can't open more native windows, so it stays inside the main one.
+                egui::Window::new(title)
+                    .id(egui::Id::new(("panel", key)))
+                    .show(ctx, &mut contents);
+            } else {
+                egui::CentralPanel::default().show(ctx, &mut contents);
+            }
+            if ctx.input(|i| i.viewport().close_requested()) {
+                *open = false;
+            }
+        },
+    );
+    toggled
+}
@@ -0,0 +1,171 @@
+//! Streams component changes of single entities with the BRP `+watch` methods, so they
+//! update between full fetches.
+
+use std::sync::{Arc, Mutex};
+use web_time::Instant;
+
+use bevy::{
+    prelude::Entity,
+    remote::builtin_methods::{
+        BrpGetParams, BrpListParams, BrpQueryRow, BRP_GET_AND_WATCH_METHOD,
+        BRP_LIST_AND_WATCH_METHOD,
+    },
+    utils::HashMap,
+};
+
+use crate::helper::{self, Endpoint, WatchDecoder};
+use crate::responses::{BrpResult, ListChanges, WatchedComponents};
+use crate::transport::{self, StreamHandle};
+
+type Rows = Arc<Mutex<HashMap<Entity, BrpQueryRow>>>;
+
+/// `bevy/get+watch` and `bevy/list+watch` subscriptions of one entity. They are closed
+/// when this is dropped.
+pub struct EntityWatch {
+    entity: Entity,
+    components: Vec<String>,
+    rows: Rows,
+    fetched_at: Arc<Mutex<HashMap<Entity, Instant>>>,
+    /// `bevy/get+watch` results not applied to the rows yet, see [`Self::apply`].
+    pending: Arc<Mutex<Vec<WatchedComponents>>>,
+    get: StreamHandle,
+    list: StreamHandle,
+    /// Components added to the entity since subscribing, the subscription has to be
+    /// renewed to include them.
+    added: Arc<Mutex<Vec<String>>>,
+    error: Arc<Mutex<Option<String>>>,
+}
+
+impl EntityWatch {
+    pub fn start(
+        url: &Endpoint,
+        entity: Entity,
+        components: Vec<String>,
+        rows: Rows,
+        fetched_at: Arc<Mutex<HashMap<Entity, Instant>>>,
+        ctx: egui::Context,
+    ) -> Self {
+        let added = Arc::new(Mutex::new(Vec::new()));
+        let error = Arc::new(Mutex::new(None));
+        let pending = Arc::new(Mutex::new(Vec::new()));
+
+        let params = BrpGetParams {
+            entity,
+            components: components.clone(),
+            strict: false,
+        };
+        // Streams stay on the JSON endpoint, see `Endpoint::json`.
+        let url = &url.json();
+        let request = helper::make_request(params, BRP_GET_AND_WATCH_METHOD, url);
+        let get = subscribe(request, error.clone(), ctx.clone(), {
+            let pending = pending.clone();
+            move |result: WatchedComponents| pending.lock().unwrap().push(result)
+        });
+
+        let request =
+            helper::make_request(BrpListParams { entity }, BRP_LIST_AND_WATCH_METHOD, url);
+        let list = subscribe(request, error.clone(), ctx, {
+            let rows = rows.clone();
+            let added = added.clone();
+            move |result: ListChanges| {
+                if let Some(row) = rows.lock().unwrap().get_mut(&entity) {
+                    remove(row, &result.removed);
+                }
+                added.lock().unwrap().extend(result.added);
+            }
+        });
+
+        Self {
+            entity,
+            components,
+            rows,
+            fetched_at,
+            pending,
+            get,
+            list,
+            added,
+            error,
+        }
+    }
+
+    /// Components to subscribe to when renewing the subscription, `None` while the
+    /// current one still covers all of them.
+    pub fn renewal(&self) -> Option<Vec<String>> {
+        let added = self.added.lock().unwrap();
+        if added.is_empty() {
+            return None;
+        }
+        let mut components = self.components.clone();
+        for path in added.iter() {
+            if !components.contains(path) {
+                components.push(path.clone());
+            }
+        }
+        Some(components)
+    }
+
+    /// Applies the changes streamed since the last call to the cached row, called as
+    /// often as the refresh policy of watches allows.
+    pub fn apply(&self) {
+        let results = std::mem::take(&mut *self.pending.lock().unwrap());
+        if results.is_empty() {
+            return;
+        }
+        let mut rows = self.rows.lock().unwrap();
+        let Some(row) = rows.get_mut(&self.entity) else {
+            return;
+        };
+        for result in results {
+            row.components.extend(result.changed);
+            remove(row, &result.removed);
+        }
+        self.fetched_at
+            .lock()
+            .unwrap()
+            .insert(self.entity, Instant::now());
+    }
+
+    pub fn error(&self) -> Option<String> {
+        self.error.lock().unwrap().clone()
+    }
+}
+
+impl Drop for EntityWatch {
+    fn drop(&mut self) {
+        self.get.stop();
+        self.list.stop();
+    }
+}
+
+fn remove(row: &mut BrpQueryRow, removed: &[String]) {
+    for path in removed {
+        row.components.remove(path);
+    }
+}
+
+/// Streams `request` and calls `on_result` with every decoded result, the first error is
+/// kept to be shown.
+fn subscribe<T: BrpResult>(
+    request: ehttp::Request,
+    error: Arc<Mutex<Option<String>>>,
+    ctx: egui::Context,
+    on_result: impl 'static + Send + Fn(T),
+) -> StreamHandle {
+    let decoder = Mutex::new(WatchDecoder::default());
+    transport::stream(request, move |chunk| {
+        let results = match chunk {
+            Ok(chunk) if chunk.is_empty() => decoder.lock().unwrap().finish().into_iter().collect(),
+            Ok(chunk) => decoder.lock().unwrap().push(&chunk),
+            Err(e) => vec![Err(crate::error::InspectorError::Transport(e))],
+        };
+        for result in results {
+            match result.and_then(T::decode) {
+                Ok(result) => on_result(result),
+                Err(e) => {
+                    error.lock().unwrap().get_or_insert(e.to_string());
+                }
+            }
+        }
+        ctx.request_repaint();
+    })
+}
@@ -0,0 +1,128 @@
+//! Widgets for the value types games use most, recognized by the shape of their JSON.
+
+use egui::ecolor::{gamma_from_linear, linear_from_gamma};
+use serde_json::{Map, Value};
+
+const AXES: [&str; 4] = ["x", "y", "z", "w"];
+const CHANNELS: [&str; 4] = ["red", "green", "blue", "alpha"];
+
+/// A glam vector, serialized as an array of two to four numbers.
+pub fn is_vector(value: &Value) -> bool {
+    value
+        .as_array()
+        .is_some_and(|items| (2..=4).contains(&items.len()) && items.iter().all(Value::is_number))
+}
+
+/// One drag value per axis, labeled x, y, z and w. Returns whether anything changed.
+pub fn edit_vector(ui: &mut egui::Ui, value: &mut Value) -> bool {
+    let Some(items) = value.as_array_mut() else {
+        return false;
+    };
+    ui.horizontal(|ui| {
+        let mut changed = false;
+        for (item, axis) in items.iter_mut().zip(AXES) {
+            let mut number = item.as_f64().unwrap_or_default();
+            if ui
+                .add(
+                    egui::DragValue::new(&mut number)
+                        .speed(0.1)
+                        .prefix(format!("{axis}: ")),
+                )
+                .changed()
+            {
+                changed = true;
+                if let Some(number) = serde_json::Number::from_f64(number) {
+                    *item = Value::Number(number);
+                }
+            }
+        }
+        changed
+    })
+    .inner
+}
+
+/// Whether the channels of a color are stored gamma encoded.
+#[derive(Clone, Copy)]
+enum Encoding {
+    Srgb,
+    Linear,
+}
+
+/// The channels of a `Color` holding an `Srgba` or `LinearRgba`, or of one of those
+/// directly. Other color spaces are edited field by field.
+fn channels(value: &mut Value) -> Option<(Encoding, &mut Map<String, Value>)> {
+    let Value::Object(map) = value else {
+        return None;
+    };
+    let encoding = match map.keys().next().map(String::as_str) {
+        Some("Srgba") if map.len() == 1 => Some(Encoding::Srgb),
+        Some("LinearRgba") if map.len() == 1 => Some(Encoding::Linear),
+        _ => None,
+    };
+    let (encoding, map) = match encoding {
+        Some(encoding) => (encoding, map.values_mut().next()?.as_object_mut()?),
+        // Without the enum around it there is no telling, `Srgba` is the common one.
+        None => (Encoding::Srgb, map),
+    };
+    let is_rgba = map.len() == CHANNELS.len()
+        && CHANNELS
+            .iter()
+            .all(|channel| map.get(*channel).is_some_and(Value::is_number));
+    is_rgba.then_some((encoding, map))
+}
+
+pub fn is_color(value: &Value) -> bool {
+    channels(&mut value.clone()).is_some()
+}
+
+/// The color for showing it, `None` when `value` isn't one [`is_color`] recognizes.
+pub fn as_color32(value: &Value) -> Option<egui::Color32> {
+    let mut value = value.clone();
+    let (encoding, map) = channels(&mut value)?;
+    let [red, green, blue, alpha] =
+        CHANNELS.map(|channel| map[channel].as_f64().unwrap_or_default() as f32);
+    Some(match encoding {
+        Encoding::Srgb => egui::Rgba::from_srgba_unmultiplied(
+            (red * 255.0).round() as u8,
+            (green * 255.0).round() as u8,
+            (blue * 255.0).round() as u8,
+            (alpha * 255.0).round() as u8,
+        )
+        .into(),
+        Encoding::Linear => egui::Rgba::from_rgba_unmultiplied(red, green, blue, alpha).into(),
+    })
+}
+
+/// A color picker button next to the channel values. Returns whether anything changed.
+pub fn edit_color(ui: &mut egui::Ui, value: &mut Value) -> bool {
+    let Some((encoding, map)) = channels(value) else {
+        return false;
+    };
+    let mut rgba = CHANNELS.map(|channel| map[channel].as_f64().unwrap_or_default() as f32);
+    if let Encoding::Srgb = encoding {
+        for channel in &mut rgba[..3] {
+            *channel = linear_from_gamma(*channel);
+        }
+    }
+    ui.horizontal(|ui| {
+        if !ui.color_edit_button_rgba_unmultiplied(&mut rgba).changed() {
+            let [red, green, blue, alpha] = CHANNELS.map(|channel| map[channel].to_string());
+            ui.weak(format!("{red}, {green}, {blue}, {alpha}"));
+            return false;
+        }
+        if let Encoding::Srgb = encoding {
+            for channel in &mut rgba[..3] {
+                *channel = gamma_from_linear(*channel);
+            }
+        }
+        for (channel, number) in CHANNELS.iter().zip(rgba) {
+            // Round away the noise of the round trip through the picker.
+            let number = (f64::from(number) * 10_000.0).round() / 10_000.0;
+            if let Some(number) = serde_json::Number::from_f64(number) {
+                map.insert((*channel).to_owned(), Value::Number(number));
+            }
+        }
+        true
+    })
+    .inner
+}